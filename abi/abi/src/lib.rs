@@ -1,4 +1,5 @@
 pub use ethers_abi_enc::*;
+#[allow(unused_imports)]
 pub use ethers_abi_file::*;
 
 #[cfg(test)]
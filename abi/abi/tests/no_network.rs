@@ -0,0 +1,68 @@
+//! The ABI stack is used by offline tooling, and must build without any
+//! networking dependencies.
+
+use std::process::Command;
+
+const FORBIDDEN: &[&str] = &[
+    "tokio",
+    "reqwest",
+    "hyper",
+    "jsonrpsee",
+    "ethers-transports",
+    "ethers-provider",
+];
+
+fn normal_deps(extra_args: &[&str]) -> Vec<String> {
+    let cargo = std::env::var("CARGO").unwrap_or_else(|_| "cargo".to_owned());
+    let output = Command::new(cargo)
+        .args([
+            "tree",
+            "--package",
+            env!("CARGO_PKG_NAME"),
+            "--edges",
+            "normal",
+            "--target",
+            "all",
+            "--prefix",
+            "none",
+            "--format",
+            "{p}",
+        ])
+        .args(extra_args)
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .output()
+        .expect("failed to run cargo tree");
+    assert!(
+        output.status.success(),
+        "cargo tree failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    String::from_utf8(output.stdout)
+        .unwrap()
+        .lines()
+        .filter_map(|line| line.split_whitespace().next())
+        .map(ToOwned::to_owned)
+        .collect()
+}
+
+fn assert_no_network(extra_args: &[&str]) {
+    let deps = normal_deps(extra_args);
+    assert!(deps.iter().any(|dep| dep == "ethers-abi-enc"));
+
+    let found: Vec<_> = deps
+        .iter()
+        .filter(|dep| FORBIDDEN.contains(&dep.as_str()))
+        .collect();
+    assert!(
+        found.is_empty(),
+        "networking crates in the ABI dependency tree ({extra_args:?}): {found:?}"
+    );
+}
+
+#[test]
+fn it_builds_without_networking() {
+    assert_no_network(&[]);
+    assert_no_network(&["--no-default-features"]);
+    assert_no_network(&["--all-features"]);
+}
@@ -0,0 +1,261 @@
+//! Conversion between JSON values and ABI encodings.
+//!
+//! Values are represented as:
+//!
+//! - `address`, `bytesN`, `bytes`: `0x`-prefixed hex strings
+//! - `bool`: booleans
+//! - `intN`, `uintN`: decimal strings. Encoding also accepts numbers and
+//!   `0x`-prefixed hex strings
+//! - `string`: strings
+//! - arrays and tuples: arrays
+
+use ethers_abi_enc::{Token, Word};
+use ethers_primitives::U256;
+use serde_json::Value;
+
+use crate::{DynAbiError, DynDecoder, DynType, DynValue, Signature};
+
+/// Encode JSON values as the parameters of a signature. If the signature
+/// has a name, the calldata is prefixed with the function selector
+pub fn encode(signature: &Signature, values: &Value) -> Result<Vec<u8>, DynAbiError> {
+    let token = tokenize(&signature.tuple(), values)?;
+    let mut data = signature.selector().map(Vec::from).unwrap_or_default();
+    data.extend(ethers_abi_enc::encode(&token));
+    Ok(data)
+}
+
+/// Decode the parameters of a signature into a JSON array. If the
+/// signature has a name, the data must start with the function selector
+pub fn decode(signature: &Signature, data: &[u8]) -> Result<Value, DynAbiError> {
+    let data = match signature.selector() {
+        Some(selector) => data
+            .strip_prefix(&selector[..])
+            .ok_or(DynAbiError::SelectorMismatch)?,
+        None => data,
+    };
+    decode_value(&signature.tuple(), data).map(|value| to_json(&value))
+}
+
+fn invalid_value(ty: &DynType, value: &Value) -> DynAbiError {
+    DynAbiError::InvalidValue {
+        ty: ty.to_string(),
+        value: value.to_string(),
+    }
+}
+
+fn tokenize<'a>(ty: &DynType, value: &'a Value) -> Result<Token<'a>, DynAbiError> {
+    let invalid = || invalid_value(ty, value);
+    let hex_value = || {
+        value
+            .as_str()
+            .and_then(|s| hex::decode(s.strip_prefix("0x").unwrap_or(s)).ok())
+            .ok_or_else(invalid)
+    };
+    let seq = |inner: &DynType, len: Option<usize>| {
+        let values = value.as_array().ok_or_else(invalid)?;
+        if len.is_some_and(|len| len != values.len()) {
+            return Err(invalid());
+        }
+        values.iter().map(|v| tokenize(inner, v)).collect()
+    };
+
+    let mut word = Word::default();
+    match ty {
+        DynType::Address => {
+            let bytes = hex_value()?;
+            if bytes.len() != 20 {
+                return Err(invalid());
+            }
+            word[12..].copy_from_slice(&bytes);
+        }
+        DynType::Bool => word.0[31] = value.as_bool().ok_or_else(invalid)? as u8,
+        DynType::Uint(bits) => {
+            let (negative, n) = parse_int(value).ok_or_else(invalid)?;
+            if negative || n.bit_len() > *bits {
+                return Err(invalid());
+            }
+            word = Word::from(n.to_be_bytes::<32>());
+        }
+        DynType::Int(bits) => {
+            let (negative, n) = parse_int(value).ok_or_else(invalid)?;
+            let limit = U256::from(1u8) << (bits - 1);
+            if (negative && n > limit) || (!negative && n >= limit) {
+                return Err(invalid());
+            }
+            let n = if negative { n.wrapping_neg() } else { n };
+            word = Word::from(n.to_be_bytes::<32>());
+        }
+        DynType::FixedBytes(len) => {
+            let bytes = hex_value()?;
+            if bytes.len() != *len {
+                return Err(invalid());
+            }
+            word[..*len].copy_from_slice(&bytes);
+        }
+        DynType::Bytes => return Ok(Token::PackedSeq(hex_value()?.into())),
+        DynType::String => {
+            let s = value.as_str().ok_or_else(invalid)?;
+            return Ok(Token::PackedSeq(s.as_bytes().into()));
+        }
+        DynType::Array(inner) => return seq(inner, None).map(Token::DynSeq),
+        DynType::FixedArray(inner, len) => return seq(inner, Some(*len)).map(Token::FixedSeq),
+        DynType::Tuple(types) => {
+            let values = value.as_array().ok_or_else(invalid)?;
+            if values.len() != types.len() {
+                return Err(invalid());
+            }
+            return types
+                .iter()
+                .zip(values)
+                .map(|(ty, v)| tokenize(ty, v))
+                .collect::<Result<_, _>>()
+                .map(Token::FixedSeq);
+        }
+    }
+    Ok(Token::Word(word))
+}
+
+/// Parse an integer into its sign and magnitude
+fn parse_int(value: &Value) -> Option<(bool, U256)> {
+    match value {
+        Value::Number(n) => match (n.as_u64(), n.as_i64()) {
+            (Some(n), _) => Some((false, U256::from(n))),
+            (None, Some(n)) => Some((true, U256::from(n.unsigned_abs()))),
+            _ => None,
+        },
+        Value::String(s) => {
+            let (negative, s) = match s.strip_prefix('-') {
+                Some(s) => (true, s),
+                None => (false, s.as_str()),
+            };
+            let n = match s.strip_prefix("0x") {
+                Some(hex) => U256::from_str_radix(hex, 16),
+                None => U256::from_str_radix(s, 10),
+            };
+            n.ok().map(|n| (negative, n))
+        }
+        _ => None,
+    }
+}
+
+/// Decode a value whose encoding starts at the start of `data`. Words are
+/// not checked to be canonical encodings of their type
+pub(crate) fn decode_value(ty: &DynType, data: &[u8]) -> Result<DynValue, DynAbiError> {
+    DynDecoder::new(ty).decode(data)
+}
+
+/// The JSON representation of a value
+pub fn to_json(value: &DynValue) -> Value {
+    let hex = |bytes: &[u8]| Value::String(format!("0x{}", hex::encode(bytes)));
+    let seq = |values: &[DynValue]| Value::Array(values.iter().map(to_json).collect());
+
+    match value {
+        DynValue::Address(address) => hex(address.as_bytes()),
+        DynValue::Bool(b) => Value::Bool(*b),
+        DynValue::Uint(n, _) => Value::String(n.to_string()),
+        DynValue::Int(n, _) if n.bit(255) => Value::String(format!("-{}", n.wrapping_neg())),
+        DynValue::Int(n, _) => Value::String(n.to_string()),
+        DynValue::FixedBytes(word, len) => hex(&word[..*len]),
+        DynValue::Bytes(bytes) => hex(bytes),
+        DynValue::String(s) => Value::String(s.clone()),
+        DynValue::Array(values) | DynValue::FixedArray(values) | DynValue::Tuple(values) => {
+            seq(values)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::*;
+
+    fn round_trip(signature: &str, values: Value) -> Vec<u8> {
+        let signature = Signature::parse(signature).unwrap();
+        let data = encode(&signature, &values).unwrap();
+        assert_eq!(decode(&signature, &data).unwrap(), values);
+        data
+    }
+
+    #[test]
+    fn it_encodes_calls() {
+        let data = round_trip(
+            "transfer(address,uint256)",
+            json!(["0x1111111111111111111111111111111111111111", "1000"]),
+        );
+        assert_eq!(
+            hex::encode(data),
+            "a9059cbb\
+             0000000000000000000000001111111111111111111111111111111111111111\
+             00000000000000000000000000000000000000000000000000000000000003e8"
+        );
+
+        let signature = Signature::parse("transfer(address,uint256)").unwrap();
+        assert!(matches!(
+            decode(&signature, &[0u8; 68]),
+            Err(DynAbiError::SelectorMismatch)
+        ));
+    }
+
+    #[test]
+    fn it_round_trips_values() {
+        round_trip(
+            "(int8,int256,bool,bytes4,string,bytes)",
+            json!(["-128", "-1", true, "0x01020304", "hello", "0xdeadbeef"]),
+        );
+        round_trip(
+            "((uint256,string)[],uint8[2],(bool,bytes)[1])",
+            json!([[["1", "a"], ["2", "bc"]], ["3", "4"], [[false, "0x"]]]),
+        );
+        round_trip("()", json!([]));
+    }
+
+    #[test]
+    fn it_accepts_numbers() {
+        let signature = Signature::parse("(uint16,int16)").unwrap();
+        let data = encode(&signature, &json!([258, -2])).unwrap();
+        assert_eq!(decode(&signature, &data).unwrap(), json!(["258", "-2"]));
+        let data = encode(&signature, &json!(["0x102", "-0x2"])).unwrap();
+        assert_eq!(decode(&signature, &data).unwrap(), json!(["258", "-2"]));
+    }
+
+    #[test]
+    fn it_rejects_invalid_values() {
+        let cases = [
+            ("(uint8)", json!(["256"])),
+            ("(int8)", json!(["128"])),
+            ("(int8)", json!(["-129"])),
+            ("(uint8)", json!([-1])),
+            ("(address)", json!(["0x11"])),
+            ("(bytes2)", json!(["0x010203"])),
+            ("(uint8[2])", json!([["1"]])),
+            ("(bool,bool)", json!([true])),
+            ("(bool)", json!(true)),
+        ];
+        for (signature, values) in cases {
+            let signature = Signature::parse(signature).unwrap();
+            assert!(
+                matches!(
+                    encode(&signature, &values),
+                    Err(DynAbiError::InvalidValue { .. })
+                ),
+                "{signature} {values}"
+            );
+        }
+    }
+
+    #[test]
+    fn it_rejects_invalid_data() {
+        let signature = Signature::parse("(string)").unwrap();
+        let mut data = encode(&signature, &json!(["hello"])).unwrap();
+        data[63] = 0xff;
+        assert!(matches!(
+            decode(&signature, &data),
+            Err(DynAbiError::InvalidData)
+        ));
+        assert!(matches!(
+            decode(&signature, &data[..16]),
+            Err(DynAbiError::InvalidData)
+        ));
+    }
+}
@@ -0,0 +1,240 @@
+//! Decoding of a schema known only at runtime, compiled once for repeated
+//! use.
+//!
+//! Decoding a [`DynType`] asks each member whether it is dynamic and how
+//! large its head is, which walks the member's type tree, and repeats the
+//! walk for every element of an array. A [`DynDecoder`] computes both once
+//! per node of the tree. It is cheap to clone, as the compiled tree is
+//! shared.
+
+use std::sync::Arc;
+
+use ethers_abi_enc::Word;
+use ethers_primitives::{Address, U256};
+
+use crate::{DynAbiError, DynType, DynValue, Signature};
+
+/// A node of a compiled type
+#[derive(Debug)]
+struct Node {
+    kind: Kind,
+    dynamic: bool,
+    /// Size of the type in the head of an encoding, in bytes
+    head_size: usize,
+}
+
+#[derive(Debug)]
+enum Kind {
+    Address,
+    Bool,
+    Int(usize),
+    Uint(usize),
+    FixedBytes(usize),
+    Bytes,
+    String,
+    Array(Box<Node>),
+    FixedArray(Box<Node>, usize),
+    Tuple(Vec<Node>),
+}
+
+impl Node {
+    fn new(ty: &DynType) -> Self {
+        let kind = match ty {
+            DynType::Address => Kind::Address,
+            DynType::Bool => Kind::Bool,
+            DynType::Int(bits) => Kind::Int(*bits),
+            DynType::Uint(bits) => Kind::Uint(*bits),
+            DynType::FixedBytes(len) => Kind::FixedBytes(*len),
+            DynType::Bytes => Kind::Bytes,
+            DynType::String => Kind::String,
+            DynType::Array(inner) => Kind::Array(Box::new(Self::new(inner))),
+            DynType::FixedArray(inner, len) => Kind::FixedArray(Box::new(Self::new(inner)), *len),
+            DynType::Tuple(types) => Kind::Tuple(types.iter().map(Self::new).collect()),
+        };
+        let dynamic = match &kind {
+            Kind::Bytes | Kind::String | Kind::Array(_) => true,
+            Kind::FixedArray(inner, _) => inner.dynamic,
+            Kind::Tuple(nodes) => nodes.iter().any(|node| node.dynamic),
+            _ => false,
+        };
+        let head_size = match &kind {
+            _ if dynamic => 32,
+            Kind::FixedArray(inner, len) => inner.head_size * len,
+            Kind::Tuple(nodes) => nodes.iter().map(|node| node.head_size).sum(),
+            _ => 32,
+        };
+        Self {
+            kind,
+            dynamic,
+            head_size,
+        }
+    }
+
+    /// Decode a value whose encoding starts at the start of `data`
+    fn decode(&self, data: &[u8]) -> Result<DynValue, DynAbiError> {
+        let packed = || {
+            let len = usize_at(data, 0)?;
+            len.checked_add(32)
+                .and_then(|end| data.get(32..end))
+                .ok_or(DynAbiError::InvalidData)
+        };
+        let word = || word_at(data, 0).map(Word::from_slice);
+
+        Ok(match &self.kind {
+            Kind::Address => DynValue::Address(Address::from_slice(&word()?[12..])),
+            Kind::Bool => DynValue::Bool(word()?[31] != 0),
+            Kind::Uint(bits) => DynValue::Uint(U256::from_be_bytes(word()?.0), *bits),
+            Kind::Int(bits) => DynValue::Int(U256::from_be_bytes(word()?.0), *bits),
+            Kind::FixedBytes(len) => DynValue::FixedBytes(word()?, *len),
+            Kind::Bytes => DynValue::Bytes(packed()?.to_vec()),
+            Kind::String => DynValue::String(
+                String::from_utf8(packed()?.to_vec()).map_err(|_| DynAbiError::InvalidData)?,
+            ),
+            Kind::Array(inner) => {
+                let len = usize_at(data, 0)?;
+                // each element takes at least one word
+                if len > data.len() / 32 {
+                    return Err(DynAbiError::InvalidData);
+                }
+                DynValue::Array(decode_seq(std::iter::repeat_n(&**inner, len), &data[32..])?)
+            }
+            Kind::FixedArray(inner, len) => {
+                DynValue::FixedArray(decode_seq(std::iter::repeat_n(&**inner, *len), data)?)
+            }
+            Kind::Tuple(nodes) => DynValue::Tuple(decode_seq(nodes, data)?),
+        })
+    }
+}
+
+fn word_at(data: &[u8], offset: usize) -> Result<&[u8], DynAbiError> {
+    offset
+        .checked_add(32)
+        .and_then(|end| data.get(offset..end))
+        .ok_or(DynAbiError::InvalidData)
+}
+
+fn usize_at(data: &[u8], offset: usize) -> Result<usize, DynAbiError> {
+    let n = U256::from_be_slice(word_at(data, offset)?);
+    usize::try_from(n).map_err(|_| DynAbiError::InvalidData)
+}
+
+/// Decode a sequence of values laid out in head/tail order
+fn decode_seq<'a>(
+    nodes: impl IntoIterator<Item = &'a Node>,
+    data: &[u8],
+) -> Result<Vec<DynValue>, DynAbiError> {
+    let mut head = 0;
+    let mut values = vec![];
+    for node in nodes {
+        let start = if node.dynamic {
+            usize_at(data, head)?
+        } else {
+            head
+        };
+        values.push(node.decode(data.get(start..).ok_or(DynAbiError::InvalidData)?)?);
+        head += node.head_size;
+    }
+    Ok(values)
+}
+
+/// A [`DynType`] compiled for decoding many values of the same schema,
+/// e.g. the logs of one event.
+///
+/// Words are not checked to be canonical encodings of their type, as with
+/// [`DynValue::decode`].
+///
+/// ```
+/// use ethers_abi_dyn::{DynDecoder, DynValue, Signature};
+/// use ethers_primitives::U256;
+///
+/// let signature = Signature::parse("(uint256,bool)").unwrap();
+/// let decoder = DynDecoder::params(&signature);
+///
+/// let mut data = U256::from(7u8).to_be_bytes::<32>().to_vec();
+/// data.extend(U256::from(1u8).to_be_bytes::<32>());
+/// assert_eq!(
+///     decoder.decode(&data).unwrap(),
+///     DynValue::Tuple(vec![DynValue::Uint(U256::from(7u8), 256), DynValue::Bool(true)])
+/// );
+/// ```
+#[derive(Clone, Debug)]
+pub struct DynDecoder {
+    root: Arc<Node>,
+}
+
+impl DynDecoder {
+    /// Compile a type
+    pub fn new(ty: &DynType) -> Self {
+        Self {
+            root: Arc::new(Node::new(ty)),
+        }
+    }
+
+    /// Compile the parameters of a signature, as a tuple
+    pub fn params(signature: &Signature) -> Self {
+        Self::new(&signature.tuple())
+    }
+
+    /// True if the type is dynamic according to ABI rules
+    pub fn is_dynamic(&self) -> bool {
+        self.root.dynamic
+    }
+
+    /// Size of the type in the head of an encoding, in bytes
+    pub fn head_size(&self) -> usize {
+        self.root.head_size
+    }
+
+    /// Decode a value whose encoding starts at the start of `data`
+    pub fn decode(&self, data: &[u8]) -> Result<DynValue, DynAbiError> {
+        self.root.decode(data)
+    }
+}
+
+impl From<&DynType> for DynDecoder {
+    fn from(ty: &DynType) -> Self {
+        Self::new(ty)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::*;
+    use crate::codec;
+
+    #[test]
+    fn it_precomputes_head_sizes() {
+        let cases = [
+            ("uint256", false, 32),
+            ("(address,bool)[3]", false, 192),
+            ("(uint8,(bytes32,bool)[2])", false, 160),
+            ("(uint8,string)[3]", true, 32),
+            ("bytes", true, 32),
+        ];
+        for (ty, dynamic, head_size) in cases {
+            let decoder = DynDecoder::new(&DynType::parse(ty).unwrap());
+            assert_eq!(decoder.is_dynamic(), dynamic, "{ty}");
+            assert_eq!(decoder.head_size(), head_size, "{ty}");
+        }
+    }
+
+    #[test]
+    fn it_decodes_repeatedly() {
+        let signature = Signature::parse("((uint256,string)[],uint8[2],bytes)").unwrap();
+        let decoder = DynDecoder::params(&signature);
+        let shared = decoder.clone();
+
+        for i in 0..4 {
+            let values = json!([[[i.to_string(), "a"]], ["3", "4"], "0xdead"]);
+            let data = codec::encode(&signature, &values).unwrap();
+            assert_eq!(codec::to_json(&decoder.decode(&data).unwrap()), values);
+            assert_eq!(
+                shared.decode(&data).unwrap(),
+                decoder.decode(&data).unwrap()
+            );
+        }
+        assert!(decoder.decode(&[0; 16]).is_err());
+    }
+}
@@ -0,0 +1,54 @@
+#![warn(
+    missing_debug_implementations,
+    missing_docs,
+    unreachable_pub,
+    unused_crate_dependencies
+)]
+#![deny(unused_must_use, rust_2018_idioms)]
+
+//! ABI encoding and decoding of types known only at runtime.
+//!
+//! Types are given as signature strings, e.g. `transfer(address,uint256)`
+//! or `(bool,string)`, and values as [`DynValue`]s or JSON. See [`codec`]
+//! for the JSON representation of each type. Arguments can be redacted from
+//! calldata before sharing it, see [`redact`]. Calldata, logs and revert
+//! data of many contracts can be decoded with an [`AbiRegistry`].
+
+pub mod codec;
+
+mod decoder;
+pub use decoder::DynDecoder;
+
+pub mod redact;
+pub use redact::{Redactor, ValueKind};
+
+pub mod registry;
+pub use registry::{AbiRegistry, Decoded};
+
+mod ty;
+pub use ty::{DynType, Signature};
+
+mod value;
+pub use value::DynValue;
+
+/// Runtime ABI errors
+#[derive(Debug, thiserror::Error)]
+pub enum DynAbiError {
+    /// A type or signature could not be parsed
+    #[error("invalid type: {0}")]
+    InvalidType(String),
+    /// A JSON value does not match its type
+    #[error("invalid value for {ty}: {value}")]
+    InvalidValue {
+        /// The type
+        ty: String,
+        /// The value
+        value: String,
+    },
+    /// The data is not a valid encoding of the types
+    #[error("invalid ABI data")]
+    InvalidData,
+    /// The data does not start with the signature's selector
+    #[error("selector mismatch")]
+    SelectorMismatch,
+}
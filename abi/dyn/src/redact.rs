@@ -0,0 +1,207 @@
+//! Redaction of calldata arguments, for sharing debugging artifacts without
+//! leaking sensitive values.
+//!
+//! Redacted values are replaced by values of the same type and length:
+//! zero for addresses, integers and fixed bytes, `false` for booleans,
+//! zero bytes for `bytes`, and `x`s for strings. Arrays keep their length.
+//! The redacted calldata is therefore a valid encoding of the same call,
+//! with the same layout and size as the original.
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::{codec, DynAbiError, DynType, Signature};
+
+/// A class of values to redact wherever they occur
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ValueKind {
+    /// `address`
+    Address,
+    /// `bool`
+    Bool,
+    /// `intN` and `uintN`
+    Integer,
+    /// `bytesN`
+    FixedBytes,
+    /// `bytes`
+    Bytes,
+    /// `string`
+    String,
+}
+
+impl ValueKind {
+    fn matches(&self, ty: &DynType) -> bool {
+        matches!(
+            (self, ty),
+            (Self::Address, DynType::Address)
+                | (Self::Bool, DynType::Bool)
+                | (Self::Integer, DynType::Int(_) | DynType::Uint(_))
+                | (Self::FixedBytes, DynType::FixedBytes(_))
+                | (Self::Bytes, DynType::Bytes)
+                | (Self::String, DynType::String)
+        )
+    }
+}
+
+/// Selects the arguments to redact.
+///
+/// Parameters are selected by path: `[1]` is the second argument, `[0, 2]`
+/// is the third field of the first argument, which is a tuple. Arrays are
+/// transparent to paths, so `[0, 2]` also selects the third field of every
+/// element of a first argument of type `(..)[]`.
+///
+/// Deserializes from JSON such as
+/// `{"params": [[1], [0, 2]], "kinds": ["address"]}`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct Redactor {
+    params: Vec<Vec<usize>>,
+    kinds: Vec<ValueKind>,
+}
+
+impl Redactor {
+    /// Instantiate a redactor that redacts nothing
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Redact the value at a parameter path
+    #[must_use = "Builder method outputs must be used"]
+    pub fn param(mut self, path: impl Into<Vec<usize>>) -> Self {
+        self.params.push(path.into());
+        self
+    }
+
+    /// Redact every value of a kind, wherever it occurs
+    #[must_use = "Builder method outputs must be used"]
+    pub fn kind(mut self, kind: ValueKind) -> Self {
+        self.kinds.push(kind);
+        self
+    }
+
+    /// Redact decoded values, as returned by [`codec::decode`]
+    pub fn redact_values(&self, signature: &Signature, values: &mut Value) {
+        self.redact_at(&signature.tuple(), values, &mut vec![]);
+    }
+
+    /// Redact calldata, or bare parameters if the signature has no name,
+    /// and re-encode it
+    pub fn redact(&self, signature: &Signature, data: &[u8]) -> Result<Vec<u8>, DynAbiError> {
+        let mut values = codec::decode(signature, data)?;
+        self.redact_values(signature, &mut values);
+        codec::encode(signature, &values)
+    }
+
+    fn redact_at(&self, ty: &DynType, value: &mut Value, path: &mut Vec<usize>) {
+        if self.params.contains(path) || self.kinds.iter().any(|kind| kind.matches(ty)) {
+            blank(ty, value);
+            return;
+        }
+        match ty {
+            DynType::Array(inner) | DynType::FixedArray(inner, _) => {
+                if let Value::Array(values) = value {
+                    values
+                        .iter_mut()
+                        .for_each(|v| self.redact_at(inner, v, path));
+                }
+            }
+            DynType::Tuple(types) => {
+                if let Value::Array(values) = value {
+                    for (i, (ty, v)) in types.iter().zip(values).enumerate() {
+                        path.push(i);
+                        self.redact_at(ty, v, path);
+                        path.pop();
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Replace a value by a value of the same type and encoded length
+fn blank(ty: &DynType, value: &mut Value) {
+    let hex_len = || {
+        value
+            .as_str()
+            .map_or(0, |s| s.trim_start_matches("0x").len() / 2)
+    };
+    *value = match ty {
+        DynType::Address => Value::String(format!("0x{}", "00".repeat(20))),
+        DynType::Bool => Value::Bool(false),
+        DynType::Int(_) | DynType::Uint(_) => Value::String("0".to_owned()),
+        DynType::FixedBytes(len) => Value::String(format!("0x{}", "00".repeat(*len))),
+        DynType::Bytes => Value::String(format!("0x{}", "00".repeat(hex_len()))),
+        DynType::String => Value::String("x".repeat(value.as_str().map_or(0, str::len))),
+        DynType::Array(inner) | DynType::FixedArray(inner, _) => {
+            if let Value::Array(values) = value {
+                values.iter_mut().for_each(|v| blank(inner, v));
+            }
+            return;
+        }
+        DynType::Tuple(types) => {
+            if let Value::Array(values) = value {
+                types.iter().zip(values).for_each(|(ty, v)| blank(ty, v));
+            }
+            return;
+        }
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn it_redacts_params() {
+        let signature = Signature::parse("transfer(address,uint256)").unwrap();
+        let data = codec::encode(
+            &signature,
+            &json!(["0x1111111111111111111111111111111111111111", "1000"]),
+        )
+        .unwrap();
+
+        let redacted = Redactor::new()
+            .param([1])
+            .redact(&signature, &data)
+            .unwrap();
+        assert_eq!(redacted.len(), data.len());
+        assert_eq!(
+            codec::decode(&signature, &redacted).unwrap(),
+            json!(["0x1111111111111111111111111111111111111111", "0"])
+        );
+    }
+
+    #[test]
+    fn it_redacts_kinds_and_preserves_layout() {
+        let signature = Signature::parse("f((address,string)[],bytes,bool)").unwrap();
+        let values = json!([
+            [
+                ["0x1111111111111111111111111111111111111111", "alice"],
+                ["0x2222222222222222222222222222222222222222", "bob"]
+            ],
+            "0xdeadbeef",
+            true
+        ]);
+        let data = codec::encode(&signature, &values).unwrap();
+
+        let redactor: Redactor =
+            serde_json::from_str(r#"{"params": [[0, 1]], "kinds": ["address", "bytes"]}"#).unwrap();
+        let redacted = redactor.redact(&signature, &data).unwrap();
+        assert_eq!(redacted.len(), data.len());
+        assert_eq!(
+            codec::decode(&signature, &redacted).unwrap(),
+            json!([
+                [
+                    ["0x0000000000000000000000000000000000000000", "xxxxx"],
+                    ["0x0000000000000000000000000000000000000000", "xxx"]
+                ],
+                "0x00000000",
+                true
+            ])
+        );
+    }
+}
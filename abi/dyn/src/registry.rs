@@ -0,0 +1,474 @@
+//! An index of the functions, events and errors of many ABIs, for decoding
+//! calldata, logs and revert data whose contract is not known in advance.
+//!
+//! Functions and errors are keyed by selector, and events by their first
+//! topic. Items sharing a key are tried in registration order.
+
+use std::collections::BTreeMap;
+
+use ethers_abi_enc::event_topic;
+use ethers_abi_file::{EventParam, JsonAbi, Param};
+use ethers_primitives::{Address, B256};
+
+use crate::{codec::decode_value, DynAbiError, DynDecoder, DynType, DynValue, Signature};
+
+/// A registered function, event or error
+#[derive(Clone, Debug)]
+struct Item {
+    signature: Signature,
+    names: Vec<String>,
+    /// Which parameters are topics. Empty except for events
+    indexed: Vec<bool>,
+    /// The decoder of the parameters that are not topics
+    decoder: DynDecoder,
+}
+
+impl Item {
+    fn new(signature: Signature, names: Vec<String>, indexed: Vec<bool>) -> Self {
+        // functions and errors have no topics
+        let body = signature
+            .params
+            .iter()
+            .zip(indexed.iter().chain(std::iter::repeat(&false)))
+            .filter(|(_, indexed)| !**indexed)
+            .map(|(ty, _)| ty.clone())
+            .collect();
+        Self {
+            decoder: DynDecoder::new(&DynType::Tuple(body)),
+            signature,
+            names,
+            indexed,
+        }
+    }
+
+    fn decoded(&self, values: Vec<DynValue>) -> Decoded {
+        Decoded {
+            signature: self.signature.clone(),
+            names: self.names.clone(),
+            values,
+        }
+    }
+}
+
+/// Calldata, a log or revert data, decoded with a registered item
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Decoded {
+    /// The signature of the function, event or error
+    pub signature: Signature,
+    /// The parameter names. Empty for unnamed parameters
+    pub names: Vec<String>,
+    /// The parameter values, in declaration order. Indexed event
+    /// parameters of dynamic or composite types are the hash of the value,
+    /// as `bytes32`
+    pub values: Vec<DynValue>,
+}
+
+impl Decoded {
+    /// The function, event or error name
+    pub fn name(&self) -> &str {
+        self.signature.name.as_deref().unwrap_or_default()
+    }
+}
+
+/// The canonical type of a JSON ABI parameter, with tuples expanded
+fn canonical_type(ty: &str, components: &[Param]) -> String {
+    match ty.strip_prefix("tuple") {
+        Some(suffix) => {
+            let inner: Vec<_> = components
+                .iter()
+                .map(|param| canonical_type(&param.ty, &param.components))
+                .collect();
+            format!("({}){suffix}", inner.join(","))
+        }
+        None => ty.to_owned(),
+    }
+}
+
+fn param_type(ty: &str, components: &[Param]) -> Result<DynType, DynAbiError> {
+    DynType::parse(&canonical_type(ty, components))
+}
+
+fn function_item(name: &str, params: &[Param]) -> Result<Item, DynAbiError> {
+    let signature = Signature {
+        name: Some(name.to_owned()),
+        params: params
+            .iter()
+            .map(|param| param_type(&param.ty, &param.components))
+            .collect::<Result<_, _>>()?,
+    };
+    let names = params.iter().map(|param| param.name.clone()).collect();
+    Ok(Item::new(signature, names, vec![]))
+}
+
+fn event_item(name: &str, params: &[EventParam]) -> Result<Item, DynAbiError> {
+    let signature = Signature {
+        name: Some(name.to_owned()),
+        params: params
+            .iter()
+            .map(|param| param_type(&param.ty, &param.components))
+            .collect::<Result<_, _>>()?,
+    };
+    let names = params.iter().map(|param| param.name.clone()).collect();
+    let indexed = params.iter().map(|param| param.indexed).collect();
+    Ok(Item::new(signature, names, indexed))
+}
+
+fn insert<K: Ord>(map: &mut BTreeMap<K, Vec<Item>>, key: K, item: Item) {
+    let items = map.entry(key).or_default();
+    let known = items
+        .iter()
+        .any(|known| known.signature == item.signature && known.indexed == item.indexed);
+    if !known {
+        items.push(item);
+    }
+}
+
+/// True if an indexed parameter of the type is stored as its value rather
+/// than its hash
+fn is_value_type(ty: &DynType) -> bool {
+    !ty.is_dynamic() && !matches!(ty, DynType::FixedArray(..) | DynType::Tuple(_))
+}
+
+/// Functions, events and errors from many ABIs, indexed for decoding.
+///
+/// Registering an item twice is a no-op. Items that collide on their
+/// selector or topic are kept, and the first that decodes the data is used.
+///
+/// ```
+/// use ethers_abi_dyn::{AbiRegistry, DynValue};
+/// use ethers_primitives::U256;
+///
+/// let mut registry = AbiRegistry::new();
+/// let selector = registry.register_function("approve(address,uint256)").unwrap();
+///
+/// let mut data = selector.to_vec();
+/// data.extend([0u8; 32]);
+/// data.extend(U256::from(7u8).to_be_bytes::<32>());
+/// let call = registry.decode_calldata(&data).unwrap();
+/// assert_eq!(call.name(), "approve");
+/// assert_eq!(call.values[1], DynValue::Uint(U256::from(7u8), 256));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct AbiRegistry {
+    functions: BTreeMap<[u8; 4], Vec<Item>>,
+    events: BTreeMap<B256, Vec<Item>>,
+    errors: BTreeMap<[u8; 4], Vec<Item>>,
+    labels: BTreeMap<Address, String>,
+}
+
+impl AbiRegistry {
+    /// Instantiate an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register the functions, events and errors of an ABI. Anonymous
+    /// events are skipped, as they have no topic to be found by. Fails if a
+    /// parameter type is invalid, in which case nothing is registered
+    pub fn register_abi(&mut self, abi: &JsonAbi) -> Result<(), DynAbiError> {
+        let functions = abi
+            .functions()
+            .map(|function| function_item(&function.name, &function.inputs))
+            .collect::<Result<Vec<_>, _>>()?;
+        let events = abi
+            .events()
+            .filter(|event| !event.anonymous)
+            .map(|event| event_item(&event.name, &event.inputs))
+            .collect::<Result<Vec<_>, _>>()?;
+        let errors = abi
+            .errors()
+            .map(|error| function_item(&error.name, &error.inputs))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        for item in functions {
+            let selector = item.signature.selector().expect("named");
+            insert(&mut self.functions, selector, item);
+        }
+        for item in events {
+            let topic = event_topic(&item.signature.to_string());
+            insert(&mut self.events, topic, item);
+        }
+        for item in errors {
+            let selector = item.signature.selector().expect("named");
+            insert(&mut self.errors, selector, item);
+        }
+        Ok(())
+    }
+
+    /// Register the ABI of a contract, and label its address with `name`
+    pub fn register_contract(
+        &mut self,
+        address: Address,
+        name: impl Into<String>,
+        abi: &JsonAbi,
+    ) -> Result<(), DynAbiError> {
+        self.register_abi(abi)?;
+        self.set_label(address, name);
+        Ok(())
+    }
+
+    /// Register a function by signature, e.g. `transfer(address,uint256)`.
+    /// Its parameters are unnamed. Returns the selector
+    pub fn register_function(&mut self, signature: &str) -> Result<[u8; 4], DynAbiError> {
+        self.register_signature(signature, false)
+    }
+
+    /// Register a custom error by signature, e.g.
+    /// `InsufficientBalance(uint256,uint256)`. Its parameters are unnamed.
+    /// Returns the selector
+    pub fn register_error(&mut self, signature: &str) -> Result<[u8; 4], DynAbiError> {
+        self.register_signature(signature, true)
+    }
+
+    fn register_signature(&mut self, signature: &str, error: bool) -> Result<[u8; 4], DynAbiError> {
+        let signature = Signature::parse(signature)?;
+        let selector = signature
+            .selector()
+            .ok_or_else(|| DynAbiError::InvalidType(signature.to_string()))?;
+        let names = vec![String::new(); signature.params.len()];
+        let item = Item::new(signature, names, vec![]);
+        let map = if error {
+            &mut self.errors
+        } else {
+            &mut self.functions
+        };
+        insert(map, selector, item);
+        Ok(selector)
+    }
+
+    /// Label an address, e.g. with its contract name
+    pub fn set_label(&mut self, address: Address, name: impl Into<String>) {
+        self.labels.insert(address, name.into());
+    }
+
+    /// The label of an address, if any
+    pub fn label(&self, address: Address) -> Option<&str> {
+        self.labels.get(&address).map(String::as_str)
+    }
+
+    /// The first registered function with a selector
+    pub fn function(&self, selector: [u8; 4]) -> Option<&Signature> {
+        self.functions
+            .get(&selector)
+            .and_then(|items| items.first())
+            .map(|item| &item.signature)
+    }
+
+    /// The first registered custom error with a selector
+    pub fn error(&self, selector: [u8; 4]) -> Option<&Signature> {
+        self.errors
+            .get(&selector)
+            .and_then(|items| items.first())
+            .map(|item| &item.signature)
+    }
+
+    /// The number of registered functions, events and errors
+    pub fn len(&self) -> usize {
+        self.functions
+            .values()
+            .chain(self.events.values())
+            .chain(self.errors.values())
+            .map(Vec::len)
+            .sum()
+    }
+
+    /// True if nothing is registered
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Decode calldata with the function its selector matches. `None` if
+    /// the selector is unknown, or the arguments do not decode
+    pub fn decode_calldata(&self, data: &[u8]) -> Option<Decoded> {
+        Self::decode_selected(&self.functions, data)
+    }
+
+    /// Decode revert data with the custom error its selector matches.
+    /// `None` if the selector is unknown, or the arguments do not decode
+    pub fn decode_error(&self, data: &[u8]) -> Option<Decoded> {
+        Self::decode_selected(&self.errors, data)
+    }
+
+    fn decode_selected(map: &BTreeMap<[u8; 4], Vec<Item>>, data: &[u8]) -> Option<Decoded> {
+        let (selector, args) = data.split_first_chunk::<4>()?;
+        map.get(selector)?
+            .iter()
+            .find_map(|item| match item.decoder.decode(args).ok()? {
+                DynValue::Tuple(values) => Some(item.decoded(values)),
+                _ => None,
+            })
+    }
+
+    /// Decode a log with the event its first topic matches. `None` if the
+    /// topic is unknown, or the log does not decode
+    pub fn decode_log(&self, topics: &[B256], data: &[u8]) -> Option<Decoded> {
+        let (topic0, topics) = topics.split_first()?;
+        self.events
+            .get(topic0)?
+            .iter()
+            .find_map(|item| Self::decode_event(item, topics, data))
+    }
+
+    fn decode_event(item: &Item, topics: &[B256], data: &[u8]) -> Option<Decoded> {
+        let params = item.signature.params.iter().zip(&item.indexed);
+        if params.clone().filter(|(_, indexed)| **indexed).count() != topics.len() {
+            return None;
+        }
+        let DynValue::Tuple(body) = item.decoder.decode(data).ok()? else {
+            return None;
+        };
+
+        let mut topics = topics.iter();
+        let mut body = body.into_iter();
+        let values = params
+            .map(|(ty, indexed)| match indexed {
+                true => {
+                    let topic = topics.next().expect("counted");
+                    match is_value_type(ty) {
+                        true => decode_value(ty, topic.as_bytes()).ok(),
+                        false => Some(DynValue::FixedBytes(*topic, 32)),
+                    }
+                }
+                false => body.next(),
+            })
+            .collect::<Option<_>>()?;
+        Some(item.decoded(values))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ethers_abi_enc::{encode_topic, sol_type, SolType};
+    use ethers_primitives::U256;
+
+    use super::*;
+
+    const ABI: &str = r#"[
+        {"type": "function", "name": "transfer", "inputs": [
+            {"name": "to", "type": "address"},
+            {"name": "amount", "type": "uint256"}
+        ], "outputs": [{"name": "", "type": "bool"}]},
+        {"type": "function", "name": "swap", "inputs": [
+            {"name": "route", "type": "tuple[]", "components": [
+                {"name": "pool", "type": "address"},
+                {"name": "fee", "type": "uint24"}
+            ]}
+        ]},
+        {"type": "event", "name": "Transfer", "inputs": [
+            {"name": "from", "type": "address", "indexed": true},
+            {"name": "to", "type": "address", "indexed": true},
+            {"name": "value", "type": "uint256", "indexed": false}
+        ]},
+        {"type": "event", "name": "Memo", "inputs": [
+            {"name": "memo", "type": "string", "indexed": true},
+            {"name": "id", "type": "uint8", "indexed": false}
+        ]},
+        {"type": "error", "name": "InsufficientBalance", "inputs": [
+            {"name": "available", "type": "uint256"}
+        ]}
+    ]"#;
+
+    fn registry() -> AbiRegistry {
+        let abi: JsonAbi = serde_json::from_str(ABI).unwrap();
+        let mut registry = AbiRegistry::new();
+        registry
+            .register_contract(Address::repeat_byte(0x11), "Token", &abi)
+            .unwrap();
+        registry
+    }
+
+    #[test]
+    fn it_decodes_calldata() {
+        let registry = registry();
+        assert_eq!(registry.len(), 5);
+        assert_eq!(registry.label(Address::repeat_byte(0x11)), Some("Token"));
+
+        let to = Address::repeat_byte(0x22);
+        let mut data = ethers_abi_enc::selector("transfer(address,uint256)").to_vec();
+        data.extend(<(sol_type::Address, sol_type::Uint<256>)>::encode_params((
+            to,
+            U256::from(5u8),
+        )));
+        let call = registry.decode_calldata(&data).unwrap();
+        assert_eq!(call.name(), "transfer");
+        assert_eq!(call.names, ["to", "amount"]);
+        assert_eq!(
+            call.values,
+            [DynValue::Address(to), DynValue::Uint(U256::from(5u8), 256)]
+        );
+        assert!(registry.decode_calldata(&data[..40]).is_none());
+        assert!(registry.decode_calldata(&[0; 4]).is_none());
+
+        let swap = registry
+            .function(ethers_abi_enc::selector("swap((address,uint24)[])"))
+            .unwrap();
+        assert_eq!(swap.to_string(), "swap((address,uint24)[])");
+
+        let mut data = ethers_abi_enc::selector("InsufficientBalance(uint256)").to_vec();
+        data.extend(<(sol_type::Uint<256>,)>::encode_params((U256::from(1u8),)));
+        let error = registry.decode_error(&data).unwrap();
+        assert_eq!(error.names, ["available"]);
+    }
+
+    #[test]
+    fn it_decodes_logs() {
+        let registry = registry();
+        let from = Address::repeat_byte(0x22);
+        let to = Address::repeat_byte(0x33);
+
+        let topics = [
+            event_topic("Transfer(address,address,uint256)"),
+            encode_topic(&sol_type::Address::tokenize(from)),
+            encode_topic(&sol_type::Address::tokenize(to)),
+        ];
+        let data = <(sol_type::Uint<256>,)>::encode_params((U256::from(9u8),));
+        let log = registry.decode_log(&topics, &data).unwrap();
+        assert_eq!(log.name(), "Transfer");
+        assert_eq!(
+            log.values,
+            [
+                DynValue::Address(from),
+                DynValue::Address(to),
+                DynValue::Uint(U256::from(9u8), 256)
+            ]
+        );
+        // the ERC-721 event shares the topic, but indexes the last parameter
+        assert!(registry.decode_log(&topics[..2], &data).is_none());
+
+        let hash = B256([0xaa; 32]);
+        let topics = [event_topic("Memo(string,uint8)"), hash];
+        let data = <(sol_type::Uint<8>,)>::encode_params((1,));
+        let log = registry.decode_log(&topics, &data).unwrap();
+        assert_eq!(
+            log.values,
+            [
+                DynValue::FixedBytes(hash, 32),
+                DynValue::Uint(U256::from(1u8), 8)
+            ]
+        );
+    }
+
+    #[test]
+    fn it_keeps_colliding_items() {
+        let mut registry = registry();
+        // registering the same item again is a no-op
+        registry
+            .register_function("transfer(address,uint256)")
+            .unwrap();
+        assert_eq!(registry.len(), 5);
+        assert!(registry.register_function("(uint256)").is_err());
+
+        // `collate_propagate_storage(bytes16)` shares the selector of
+        // `burn(uint256)`
+        let selector = registry.register_function("burn(uint256)").unwrap();
+        assert_eq!(
+            registry
+                .register_function("collate_propagate_storage(bytes16)")
+                .unwrap(),
+            selector
+        );
+        let mut data = selector.to_vec();
+        data.extend([1; 32]);
+        assert_eq!(registry.decode_calldata(&data).unwrap().name(), "burn");
+    }
+}
@@ -0,0 +1,248 @@
+use std::fmt;
+
+use crate::DynAbiError;
+
+/// A Solidity type parsed at runtime
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DynType {
+    /// `address`
+    Address,
+    /// `bool`
+    Bool,
+    /// `intN`
+    Int(usize),
+    /// `uintN`
+    Uint(usize),
+    /// `bytesN`
+    FixedBytes(usize),
+    /// `bytes`
+    Bytes,
+    /// `string`
+    String,
+    /// `T[]`
+    Array(Box<DynType>),
+    /// `T[N]`
+    FixedArray(Box<DynType>, usize),
+    /// `(T1,T2,...)`
+    Tuple(Vec<DynType>),
+}
+
+impl DynType {
+    /// Parse a type name, e.g. `(address,uint256[])[2]`. `uint` and `int`
+    /// are aliases of `uint256` and `int256`, and tuples may be prefixed
+    /// with `tuple`
+    pub fn parse(s: &str) -> Result<Self, DynAbiError> {
+        let s = s.trim();
+        let invalid = || DynAbiError::InvalidType(s.to_owned());
+
+        if let Some(rest) = s.strip_suffix(']') {
+            let open = rest.rfind('[').ok_or_else(invalid)?;
+            let inner = Box::new(Self::parse(&rest[..open])?);
+            let len = &rest[open + 1..];
+            return if len.is_empty() {
+                Ok(Self::Array(inner))
+            } else {
+                let len = len.parse().map_err(|_| invalid())?;
+                Ok(Self::FixedArray(inner, len))
+            };
+        }
+
+        let unprefixed = s.strip_prefix("tuple").unwrap_or(s);
+        if let Some(inner) = unprefixed
+            .strip_prefix('(')
+            .and_then(|rest| rest.strip_suffix(')'))
+        {
+            return split_params(inner)
+                .ok_or_else(invalid)?
+                .into_iter()
+                .map(Self::parse)
+                .collect::<Result<_, _>>()
+                .map(Self::Tuple);
+        }
+
+        let sized = |prefix: &str, default: usize| -> Option<usize> {
+            let size = s.strip_prefix(prefix)?;
+            if size.is_empty() {
+                return Some(default);
+            }
+            size.parse().ok()
+        };
+        match s {
+            "address" => Ok(Self::Address),
+            "bool" => Ok(Self::Bool),
+            "bytes" => Ok(Self::Bytes),
+            "string" => Ok(Self::String),
+            _ => {
+                if let Some(bits) = sized("uint", 256) {
+                    check_bits(bits).map(Self::Uint).ok_or_else(invalid)
+                } else if let Some(bits) = sized("int", 256) {
+                    check_bits(bits).map(Self::Int).ok_or_else(invalid)
+                } else if let Some(len) = s.strip_prefix("bytes").and_then(|n| n.parse().ok()) {
+                    (1..=32)
+                        .contains(&len)
+                        .then_some(Self::FixedBytes(len))
+                        .ok_or_else(invalid)
+                } else {
+                    Err(invalid())
+                }
+            }
+        }
+    }
+
+    /// True if the type is dynamic according to ABI rules
+    pub fn is_dynamic(&self) -> bool {
+        match self {
+            Self::Bytes | Self::String | Self::Array(_) => true,
+            Self::FixedArray(inner, _) => inner.is_dynamic(),
+            Self::Tuple(inner) => inner.iter().any(Self::is_dynamic),
+            _ => false,
+        }
+    }
+}
+
+fn check_bits(bits: usize) -> Option<usize> {
+    (bits > 0 && bits <= 256 && bits.is_multiple_of(8)).then_some(bits)
+}
+
+/// Split a comma-separated parameter list at the top level
+fn split_params(s: &str) -> Option<Vec<&str>> {
+    if s.trim().is_empty() {
+        return Some(vec![]);
+    }
+    let mut depth = 0usize;
+    let mut start = 0;
+    let mut params = vec![];
+    for (i, c) in s.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth = depth.checked_sub(1)?,
+            ',' if depth == 0 => {
+                params.push(&s[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    params.push(&s[start..]);
+    (depth == 0).then_some(params)
+}
+
+impl fmt::Display for DynType {
+    /// The canonical type name, as used in function selectors
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Address => f.write_str("address"),
+            Self::Bool => f.write_str("bool"),
+            Self::Int(bits) => write!(f, "int{bits}"),
+            Self::Uint(bits) => write!(f, "uint{bits}"),
+            Self::FixedBytes(len) => write!(f, "bytes{len}"),
+            Self::Bytes => f.write_str("bytes"),
+            Self::String => f.write_str("string"),
+            Self::Array(inner) => write!(f, "{inner}[]"),
+            Self::FixedArray(inner, len) => write!(f, "{inner}[{len}]"),
+            Self::Tuple(inner) => {
+                f.write_str("(")?;
+                for (i, ty) in inner.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(",")?;
+                    }
+                    write!(f, "{ty}")?;
+                }
+                f.write_str(")")
+            }
+        }
+    }
+}
+
+/// A function signature, e.g. `transfer(address,uint256)`, or a bare
+/// parameter list, e.g. `(uint256,bool)`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Signature {
+    /// The function name, if any
+    pub name: Option<String>,
+    /// The parameter types
+    pub params: Vec<DynType>,
+}
+
+impl Signature {
+    /// Parse a signature. Whitespace and type aliases are accepted
+    pub fn parse(s: &str) -> Result<Self, DynAbiError> {
+        let s = s.trim();
+        let open = s
+            .find('(')
+            .ok_or_else(|| DynAbiError::InvalidType(s.to_owned()))?;
+        let name = s[..open].trim();
+        let DynType::Tuple(params) = DynType::parse(&s[open..])? else {
+            return Err(DynAbiError::InvalidType(s.to_owned()));
+        };
+        Ok(Self {
+            name: (!name.is_empty()).then(|| name.to_owned()),
+            params,
+        })
+    }
+
+    /// The parameter types, as a tuple
+    pub(crate) fn tuple(&self) -> DynType {
+        DynType::Tuple(self.params.clone())
+    }
+
+    /// The function selector, if the signature has a name
+    pub fn selector(&self) -> Option<[u8; 4]> {
+        self.name
+            .as_ref()
+            .map(|_| ethers_abi_enc::selector(&self.to_string()))
+    }
+}
+
+impl fmt::Display for Signature {
+    /// The canonical signature
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(name) = &self.name {
+            f.write_str(name)?;
+        }
+        write!(f, "{}", self.tuple())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_parses_types() {
+        let cases = [
+            ("uint", "uint256"),
+            ("int8", "int8"),
+            ("bytes32", "bytes32"),
+            ("tuple(address, bool)[2]", "(address,bool)[2]"),
+            (
+                "(uint256[],(string,bytes))[]",
+                "(uint256[],(string,bytes))[]",
+            ),
+            ("()", "()"),
+        ];
+        for (s, canonical) in cases {
+            assert_eq!(DynType::parse(s).unwrap().to_string(), canonical, "{s}");
+        }
+
+        for s in [
+            "uint7", "int264", "bytes0", "bytes33", "(uint256", "foo", "uint[x]",
+        ] {
+            assert!(DynType::parse(s).is_err(), "{s}");
+        }
+    }
+
+    #[test]
+    fn it_parses_signatures() {
+        let sig = Signature::parse("transfer(address to, uint amount)");
+        assert!(sig.is_err());
+
+        let sig = Signature::parse("transfer(address, uint)").unwrap();
+        assert_eq!(sig.to_string(), "transfer(address,uint256)");
+        assert_eq!(sig.selector(), Some([0xa9, 0x05, 0x9c, 0xbb]));
+
+        let sig = Signature::parse("(bool,string)").unwrap();
+        assert_eq!(sig.name, None);
+        assert_eq!(sig.selector(), None);
+    }
+}
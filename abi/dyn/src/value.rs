@@ -0,0 +1,222 @@
+//! Runtime values, for bridging typed bindings and runtime ABI tooling.
+//!
+//! Values produced by [`SolType::tokenize`] can be converted to a
+//! [`DynValue`] with [`DynValue::from_token`], and back with
+//! [`DynValue::into_token`], without re-encoding.
+
+use ethers_abi_enc::{SolType, Token, Word};
+use ethers_primitives::{Address, U256};
+
+use crate::{DynAbiError, DynType};
+
+/// A value of a [`DynType`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DynValue {
+    /// `address`
+    Address(Address),
+    /// `bool`
+    Bool(bool),
+    /// `intN`, as a two's complement word, and its size in bits
+    Int(U256, usize),
+    /// `uintN`, and its size in bits
+    Uint(U256, usize),
+    /// `bytesN`, left-aligned in a word, and its size in bytes
+    FixedBytes(Word, usize),
+    /// `bytes`
+    Bytes(Vec<u8>),
+    /// `string`
+    String(String),
+    /// `T[]`
+    Array(Vec<DynValue>),
+    /// `T[N]`
+    FixedArray(Vec<DynValue>),
+    /// `(T1,T2,...)`
+    Tuple(Vec<DynValue>),
+}
+
+impl DynType {
+    /// The runtime type of a Solidity type. Fails for `function`, which
+    /// has no runtime type
+    pub fn of<T: SolType>() -> Result<Self, DynAbiError> {
+        Self::parse(&T::sol_type_name())
+    }
+}
+
+/// True if the word is a `bits`-bit two's complement integer, sign
+/// extended to 256 bits
+fn is_sign_extended(n: U256, bits: usize) -> bool {
+    if bits == 256 {
+        return true;
+    }
+    let high = n >> (bits - 1);
+    high == U256::ZERO || high == U256::MAX >> (bits - 1)
+}
+
+impl DynValue {
+    /// Decode a value of type `ty`. Words are not checked to be canonical
+    /// encodings of their type
+    pub fn decode(ty: &DynType, data: &[u8]) -> Result<Self, DynAbiError> {
+        crate::codec::decode_value(ty, data)
+    }
+
+    /// Convert a token to a value of type `ty`. Fails if the token does not
+    /// match the type, or a word is not a canonical encoding of its type
+    pub fn from_token(token: Token<'_>, ty: &DynType) -> Result<Self, DynAbiError> {
+        let invalid = |value: &dyn core::fmt::Debug| DynAbiError::InvalidValue {
+            ty: ty.to_string(),
+            value: format!("{value:?}"),
+        };
+        let seq = |tokens: Vec<Token<'_>>, inner: &DynType| {
+            tokens
+                .into_iter()
+                .map(|token| Self::from_token(token, inner))
+                .collect::<Result<Vec<_>, _>>()
+        };
+
+        Ok(match (ty, token) {
+            (DynType::Address, Token::Word(word)) => {
+                if word[..12] != [0; 12] {
+                    return Err(invalid(&word));
+                }
+                Self::Address(Address::from_slice(&word[12..]))
+            }
+            (DynType::Bool, Token::Word(word)) => match U256::from_be_bytes(word.0) {
+                n if n == U256::ZERO => Self::Bool(false),
+                n if n == U256::from(1u8) => Self::Bool(true),
+                _ => return Err(invalid(&word)),
+            },
+            (DynType::Uint(bits), Token::Word(word)) => {
+                let n = U256::from_be_bytes(word.0);
+                if n.bit_len() > *bits {
+                    return Err(invalid(&word));
+                }
+                Self::Uint(n, *bits)
+            }
+            (DynType::Int(bits), Token::Word(word)) => {
+                let n = U256::from_be_bytes(word.0);
+                if !is_sign_extended(n, *bits) {
+                    return Err(invalid(&word));
+                }
+                Self::Int(n, *bits)
+            }
+            (DynType::FixedBytes(len), Token::Word(word)) => {
+                if word[*len..].iter().any(|b| *b != 0) {
+                    return Err(invalid(&word));
+                }
+                Self::FixedBytes(word, *len)
+            }
+            (DynType::Bytes, Token::PackedSeq(bytes)) => Self::Bytes(bytes.into_owned()),
+            (DynType::String, Token::PackedSeq(bytes)) => {
+                match String::from_utf8(bytes.into_owned()) {
+                    Ok(s) => Self::String(s),
+                    Err(e) => return Err(invalid(&Token::PackedSeq(e.into_bytes().into()))),
+                }
+            }
+            (DynType::Array(inner), Token::DynSeq(tokens)) => Self::Array(seq(tokens, inner)?),
+            (DynType::FixedArray(inner, len), Token::FixedSeq(tokens)) if tokens.len() == *len => {
+                Self::FixedArray(seq(tokens, inner)?)
+            }
+            (DynType::Tuple(types), Token::FixedSeq(tokens)) if tokens.len() == types.len() => {
+                Self::Tuple(
+                    tokens
+                        .into_iter()
+                        .zip(types)
+                        .map(|(token, ty)| Self::from_token(token, ty))
+                        .collect::<Result<_, _>>()?,
+                )
+            }
+            (_, token) => return Err(invalid(&token)),
+        })
+    }
+
+    /// Convert the value to a token, e.g. to detokenize it with a
+    /// [`SolType`], or to encode it
+    pub fn into_token(self) -> Token<'static> {
+        let seq = |values: Vec<Self>| values.into_iter().map(Self::into_token).collect();
+        match self {
+            Self::Address(address) => {
+                let mut word = Word::default();
+                word[12..].copy_from_slice(address.as_bytes());
+                Token::Word(word)
+            }
+            Self::Bool(b) => Token::Word(Word::from(U256::from(b as u8).to_be_bytes::<32>())),
+            Self::Int(n, _) | Self::Uint(n, _) => Token::Word(Word::from(n.to_be_bytes::<32>())),
+            Self::FixedBytes(word, _) => Token::Word(word),
+            Self::Bytes(bytes) => Token::PackedSeq(bytes.into()),
+            Self::String(s) => Token::PackedSeq(s.into_bytes().into()),
+            Self::Array(values) => Token::DynSeq(seq(values)),
+            Self::FixedArray(values) | Self::Tuple(values) => Token::FixedSeq(seq(values)),
+        }
+    }
+}
+
+impl From<DynValue> for Token<'static> {
+    fn from(value: DynValue) -> Self {
+        value.into_token()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ethers_abi_enc::sol_type;
+
+    use super::*;
+
+    #[test]
+    fn it_bridges_typed_values() {
+        type MyTy = (
+            sol_type::Address,
+            sol_type::Int<8>,
+            sol_type::Array<sol_type::String>,
+            sol_type::FixedArray<sol_type::FixedBytes<4>, 2>,
+        );
+        let ty = DynType::of::<MyTy>().unwrap();
+        assert_eq!(ty.to_string(), "(address,int8,string[],bytes4[2])");
+
+        let rust = (
+            Address::repeat_byte(0x11),
+            -2i8,
+            vec!["a".to_string(), "bc".to_string()],
+            [[1, 2, 3, 4], [5, 6, 7, 8]],
+        );
+        let value = DynValue::from_token(MyTy::tokenize(rust.clone()), &ty).unwrap();
+        let DynValue::Tuple(values) = &value else {
+            panic!("expected a tuple");
+        };
+        assert_eq!(values[0], DynValue::Address(Address::repeat_byte(0x11)));
+        assert_eq!(values[1], DynValue::Int(U256::MAX - U256::from(1u8), 8));
+        assert_eq!(
+            values[2],
+            DynValue::Array(vec![
+                DynValue::String("a".into()),
+                DynValue::String("bc".into())
+            ])
+        );
+
+        assert_eq!(MyTy::detokenize(&value.into_token()).unwrap(), rust);
+        assert!(DynType::of::<sol_type::Function>().is_err());
+    }
+
+    #[test]
+    fn it_rejects_mismatched_tokens() {
+        let word = |n: U256| Token::Word(Word::from(n.to_be_bytes::<32>()));
+        let cases = [
+            ("uint8", word(U256::from(256u16))),
+            ("int8", word(U256::from(128u8))),
+            ("bool", word(U256::from(2u8))),
+            ("address", word(U256::MAX)),
+            ("bytes1", word(U256::from(1u8))),
+            ("string", Token::PackedSeq(vec![0xff].into())),
+            ("uint8[2]", Token::FixedSeq(vec![word(U256::ZERO)])),
+            ("(bool)", Token::DynSeq(vec![])),
+        ];
+        for (ty, token) in cases {
+            let ty = DynType::parse(ty).unwrap();
+            assert!(DynValue::from_token(token, &ty).is_err(), "{ty}");
+        }
+
+        let ty = DynType::parse("int8").unwrap();
+        assert!(DynValue::from_token(word(U256::MAX - U256::from(127u8)), &ty).is_ok());
+        assert!(DynValue::from_token(word(U256::MAX - U256::from(128u8)), &ty).is_err());
+    }
+}
@@ -0,0 +1,69 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use ethers_abi_enc::{decode_batch, decode_params, encode, encode_batch, sol_type, SolType};
+use ethers_primitives::{Address, U256};
+
+type Row = (
+    sol_type::Address,
+    sol_type::Uint<256>,
+    sol_type::Bytes,
+    sol_type::Array<sol_type::Uint<256>>,
+);
+
+fn rows(n: usize) -> Vec<<Row as SolType>::RustType> {
+    (0..n)
+        .map(|i| {
+            (
+                Address::repeat_byte(i as u8),
+                U256::from(i),
+                vec![0xab; 100],
+                vec![U256::from(i); 8],
+            )
+        })
+        .collect()
+}
+
+fn encode_rows(c: &mut Criterion) {
+    let mut group = c.benchmark_group("encode_batch");
+    for n in [1_000, 10_000, 100_000] {
+        let batch: Vec<_> = rows(n)
+            .into_iter()
+            .map(|row| Row::tokenize(row).as_fixed_seq().unwrap().to_vec())
+            .collect();
+        group.throughput(Throughput::Elements(n as u64));
+        group.bench_with_input(BenchmarkId::new("sequential", n), &batch, |b, batch| {
+            b.iter(|| {
+                batch
+                    .iter()
+                    .map(|row| encode(&ethers_abi_enc::Token::FixedSeq(row.clone())))
+                    .collect::<Vec<_>>()
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("parallel", n), &batch, |b, batch| {
+            b.iter(|| encode_batch(black_box(batch)))
+        });
+    }
+    group.finish();
+}
+
+fn decode_rows(c: &mut Criterion) {
+    let mut group = c.benchmark_group("decode_batch");
+    for n in [1_000, 10_000, 100_000] {
+        let blobs: Vec<_> = rows(n).into_iter().map(Row::encode_params).collect();
+        group.throughput(Throughput::Elements(n as u64));
+        group.bench_with_input(BenchmarkId::new("sequential", n), &blobs, |b, blobs| {
+            b.iter(|| {
+                blobs
+                    .iter()
+                    .map(|blob| decode_params::<Row>(blob))
+                    .collect::<Vec<_>>()
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("parallel", n), &blobs, |b, blobs| {
+            b.iter(|| decode_batch::<Row, _>(black_box(blobs)))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, encode_rows, decode_rows);
+criterion_main!(benches);
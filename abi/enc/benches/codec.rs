@@ -0,0 +1,70 @@
+use criterion::{
+    black_box, criterion_group, criterion_main, measurement::WallTime, BenchmarkGroup, Criterion,
+    Throughput,
+};
+use ethers_abi_enc::{sol_type, SolType};
+use ethers_primitives::{Address, U256};
+
+/// A tuple nested four levels deep, mixing static and dynamic members
+type Deep = (
+    sol_type::Uint<256>,
+    (
+        sol_type::Address,
+        (
+            sol_type::Bytes,
+            (sol_type::Uint<256>, sol_type::Array<sol_type::Address>),
+        ),
+    ),
+);
+type LongArray = sol_type::Array<sol_type::Uint<256>>;
+type BigBytes = sol_type::Bytes;
+
+fn deep() -> <Deep as SolType>::RustType {
+    (
+        U256::from(1u64),
+        (
+            Address::repeat_byte(0x11),
+            (
+                vec![0xab; 64],
+                (U256::from(2u64), vec![Address::repeat_byte(0x22); 4]),
+            ),
+        ),
+    )
+}
+
+fn long_array() -> <LongArray as SolType>::RustType {
+    (0..10_000u64).map(U256::from).collect()
+}
+
+fn big_bytes() -> <BigBytes as SolType>::RustType {
+    vec![0xcd; 1 << 20]
+}
+
+fn bench<T>(group: &mut BenchmarkGroup<'_, WallTime>, name: &str, rust: T::RustType)
+where
+    T: SolType,
+    T::RustType: Clone,
+{
+    let encoded = T::encode_params(rust.clone());
+    group.throughput(Throughput::Bytes(encoded.len() as u64));
+    group.bench_function(format!("{name}/encode"), |b| {
+        b.iter(|| T::encode_params(black_box(rust.clone())))
+    });
+    group.bench_function(format!("{name}/decode"), |b| {
+        b.iter(|| T::decode_params(black_box(&encoded)).unwrap())
+    });
+    group.bench_function(format!("{name}/decode_validate"), |b| {
+        b.iter(|| T::decode_params_validate(black_box(&encoded)).unwrap())
+    });
+}
+
+fn codec(c: &mut Criterion) {
+    let mut group = c.benchmark_group("codec");
+    bench::<Deep>(&mut group, "deep_tuple", deep());
+    bench::<LongArray>(&mut group, "long_array", long_array());
+    bench::<BigBytes>(&mut group, "big_bytes", big_bytes());
+    group.finish();
+}
+
+criterion_group!(benches, codec);
+criterion_main!(benches);
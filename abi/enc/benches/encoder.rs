@@ -0,0 +1,52 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use ethers_abi_enc::{sol_type, Encoder, SolType};
+use ethers_primitives::{Address, U256};
+
+type Order = (
+    sol_type::Address,
+    sol_type::Uint<256>,
+    sol_type::Uint<256>,
+    sol_type::FixedArray<sol_type::Address, 2>,
+    sol_type::Bytes,
+    sol_type::Array<sol_type::Uint<256>>,
+);
+
+fn order() -> <Order as SolType>::RustType {
+    (
+        Address::repeat_byte(0x11),
+        U256::from(1_000_000u64),
+        U256::from(42u64),
+        [Address::repeat_byte(0x22), Address::repeat_byte(0x33)],
+        vec![0xab; 100],
+        vec![U256::from(7u64); 4],
+    )
+}
+
+fn encode_params(c: &mut Criterion) {
+    let mut group = c.benchmark_group("encode_params");
+    group.bench_function("SolType::encode_params", |b| {
+        b.iter(|| Order::encode_params(black_box(order())))
+    });
+    let encoder = Encoder::<Order>::new();
+    group.bench_function("Encoder::encode_params", |b| {
+        b.iter(|| encoder.encode_params(black_box(order())))
+    });
+    group.finish();
+}
+
+fn encode_small(c: &mut Criterion) {
+    type Transfer = (sol_type::Address, sol_type::Uint<256>);
+    let transfer = (Address::repeat_byte(0x11), U256::from(1_000_000u64));
+
+    let mut group = c.benchmark_group("encode_small");
+    group.bench_function("SolType::encode_params_ref", |b| {
+        b.iter(|| Transfer::encode_params_ref(black_box(&transfer)))
+    });
+    group.bench_function("SolType::encode_params_to_array", |b| {
+        b.iter(|| Transfer::encode_params_to_array::<256>(black_box(&transfer)))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, encode_params, encode_small);
+criterion_main!(benches);
@@ -0,0 +1,22 @@
+#![no_main]
+
+use ethers_abi_enc::{decode_params, decode_params_validate, encode, sol_type, SolType};
+use libfuzzer_sys::fuzz_target;
+
+type Params = (
+    sol_type::Address,
+    sol_type::Bytes,
+    sol_type::Array<(sol_type::Bool, sol_type::String)>,
+    sol_type::Int<64>,
+    sol_type::FixedArray<sol_type::Array<sol_type::Uint<256>>, 2>,
+);
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(token) = decode_params::<Params>(data) {
+        let _ = Params::detokenize(&token);
+        assert_eq!(decode_params::<Params>(&encode(&token)).unwrap(), token);
+    }
+    if let Ok(token) = decode_params_validate::<Params>(data) {
+        assert_eq!(encode(&token), data);
+    }
+});
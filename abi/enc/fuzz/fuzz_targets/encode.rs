@@ -0,0 +1,9 @@
+#![no_main]
+
+use ethers_abi_enc::{encode, encode_raw, Token};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|token: Token<'static>| {
+    assert_eq!(token.encoded_size(), encode(&token).len());
+    assert_eq!(token.encoded_raw_size(), encode_raw(&token).len());
+});
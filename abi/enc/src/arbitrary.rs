@@ -0,0 +1,142 @@
+//! `Arbitrary` implementations for fuzzing and property tests
+
+use alloc::borrow::Cow;
+
+use arbitrary::Unstructured;
+use ethers_primitives::B256;
+use proptest::{
+    collection::vec,
+    prelude::{any, prop_oneof, BoxedStrategy, Strategy},
+};
+
+use crate::Token;
+
+/// The maximum nesting of generated sequences
+const MAX_DEPTH: u32 = 4;
+
+fn arbitrary_token(u: &mut Unstructured<'_>, depth: u32) -> arbitrary::Result<Token<'static>> {
+    // only leaves at the maximum depth
+    let max_variant = if depth == 0 { 1 } else { 3 };
+    Ok(match u.int_in_range(0..=max_variant)? {
+        0 => Token::Word(B256(u.arbitrary()?)),
+        1 => Token::PackedSeq(Cow::Owned(u.arbitrary()?)),
+        variant => {
+            let len = u.arbitrary_len::<[u8; 32]>()?;
+            let tokens = (0..len)
+                .map(|_| arbitrary_token(u, depth - 1))
+                .collect::<arbitrary::Result<_>>()?;
+            match variant {
+                2 => Token::FixedSeq(tokens),
+                _ => Token::DynSeq(tokens),
+            }
+        }
+    })
+}
+
+impl<'a> arbitrary::Arbitrary<'a> for Token<'static> {
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        arbitrary_token(u, MAX_DEPTH)
+    }
+}
+
+impl proptest::arbitrary::Arbitrary for Token<'static> {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        let leaf = prop_oneof![
+            any::<[u8; 32]>().prop_map(|word| Token::Word(B256(word))),
+            vec(any::<u8>(), 0..100).prop_map(|buf| Token::PackedSeq(buf.into())),
+        ];
+        leaf.prop_recursive(MAX_DEPTH, 64, 8, |inner| {
+            prop_oneof![
+                vec(inner.clone(), 0..8).prop_map(Token::FixedSeq),
+                vec(inner, 0..8).prop_map(Token::DynSeq),
+            ]
+        })
+        .boxed()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ethers_primitives::{Address, U256};
+    use proptest::{prelude::*, string::string_regex};
+
+    use super::*;
+    use crate::{decode_params, decode_params_validate, encode, encode_raw, sol_type, SolType};
+
+    type Params = (
+        sol_type::Address,
+        sol_type::Bytes,
+        sol_type::Array<(sol_type::Bool, sol_type::String)>,
+        sol_type::Int<64>,
+        sol_type::FixedArray<sol_type::Uint<256>, 2>,
+    );
+
+    fn params() -> impl Strategy<Value = <Params as SolType>::RustType> {
+        let uint = any::<[u8; 32]>().prop_map(U256::from_be_bytes);
+        (
+            any::<[u8; 20]>().prop_map(Address::new),
+            vec(any::<u8>(), 0..80),
+            vec((any::<bool>(), string_regex(".{0,40}").unwrap()), 0..4),
+            any::<i64>(),
+            [uint.clone(), uint],
+        )
+    }
+
+    proptest! {
+        #[test]
+        fn encoded_size_matches_encoding(token in any::<Token<'static>>()) {
+            prop_assert_eq!(token.encoded_size(), encode(&token).len());
+            prop_assert_eq!(token.encoded_raw_size(), encode_raw(&token).len());
+        }
+
+        #[test]
+        fn params_round_trip(value in params()) {
+            let encoded = Params::encode_params(value.clone());
+            prop_assert_eq!(Params::encoded_params_size(&value), encoded.len());
+
+            let token = decode_params_validate::<Params>(&encoded).unwrap();
+            prop_assert_eq!(Params::detokenize(&token).unwrap(), value);
+        }
+
+        #[test]
+        fn decoding_does_not_panic(
+            value in params(),
+            index in any::<prop::sample::Index>(),
+            byte in any::<u8>(),
+        ) {
+            // corrupt one byte of a valid encoding
+            let mut data = Params::encode_params(value).to_vec();
+            let index = index.index(data.len());
+            data[index] = byte;
+
+            if let Ok(token) = decode_params::<Params>(&data) {
+                let _ = Params::detokenize(&token);
+                // the re-encoding has canonical offsets, and decodes the same
+                let encoded = encode(&token);
+                prop_assert_eq!(decode_params::<Params>(&encoded).unwrap(), token);
+            }
+        }
+
+        #[test]
+        fn validated_decoding_is_exact(data in vec(any::<u8>(), 0..512)) {
+            if let Ok(token) = decode_params_validate::<Params>(&data) {
+                prop_assert_eq!(encode(&token), data);
+            }
+        }
+    }
+
+    #[test]
+    fn arbitrary_tokens_are_bounded() {
+        let data: Vec<u8> = (0..4096).map(|i| (i * 7 + 3) as u8).collect();
+        let mut u = Unstructured::new(&data);
+        while let Ok(token) = <Token<'static> as arbitrary::Arbitrary>::arbitrary(&mut u) {
+            assert_eq!(token.encoded_size(), encode(&token).len());
+            if u.is_empty() {
+                break;
+            }
+        }
+    }
+}
@@ -0,0 +1,86 @@
+//! Parallel encoding and decoding of many independent values.
+//!
+//! Indexers encode and decode the same schema for many rows. Each row is
+//! independent, so the rows are spread over the [rayon] thread pool. The
+//! output is in the order of the input.
+
+use rayon::prelude::*;
+
+use crate::{
+    decoder::decode_params_impl, encoder::encode_impl, Bytes, DecoderConfig, SolType, Token,
+};
+
+/// Encode rows of params in parallel. Each row is encoded as the params of
+/// a call, as [`crate::encode`] encodes a tuple of the row's tokens
+pub fn encode_batch(rows: &[Vec<Token<'_>>]) -> Vec<Bytes> {
+    rows.par_iter()
+        .map(|row| {
+            let mut out = Vec::new();
+            encode_impl(&mut out, row);
+            out.into()
+        })
+        .collect()
+}
+
+/// Decode blobs of params of `T` in parallel, as [`crate::decode_params`]
+/// decodes each. A blob that fails to decode does not affect the others
+pub fn decode_batch<T, D>(blobs: &[D]) -> Vec<crate::Result<Token<'static>>>
+where
+    T: SolType,
+    D: AsRef<[u8]> + Sync,
+{
+    blobs
+        .par_iter()
+        .map(|blob| {
+            decode_params_impl::<T>(blob.as_ref(), false, DecoderConfig::default())
+                .map(Token::into_owned)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use ethers_primitives::{Address, U256};
+
+    use super::*;
+    use crate::{decode_params, encode, sol_type, Error};
+
+    type Row = (sol_type::Address, sol_type::Uint<256>, sol_type::Bytes);
+
+    fn rows() -> Vec<<Row as SolType>::RustType> {
+        (0..64u8)
+            .map(|i| (Address::repeat_byte(i), U256::from(i), vec![i; i as usize]))
+            .collect()
+    }
+
+    #[test]
+    fn it_encodes_like_encode() {
+        let tokens: Vec<_> = rows().into_iter().map(Row::tokenize).collect();
+        let batch: Vec<_> = tokens
+            .iter()
+            .map(|token| token.as_fixed_seq().unwrap().to_vec())
+            .collect();
+
+        let encoded = encode_batch(&batch);
+        assert_eq!(encoded.len(), tokens.len());
+        for (token, encoded) in tokens.iter().zip(&encoded) {
+            assert_eq!(*encoded, encode(token));
+        }
+    }
+
+    #[test]
+    fn it_decodes_like_decode_params() {
+        let mut blobs: Vec<_> = rows().into_iter().map(Row::encode_params).collect();
+        blobs.push(vec![0xff; 31].into());
+
+        let decoded = decode_batch::<Row, _>(&blobs);
+        assert_eq!(decoded.len(), blobs.len());
+        for (blob, decoded) in blobs.iter().zip(&decoded[..blobs.len() - 1]) {
+            assert_eq!(
+                *decoded.as_ref().unwrap(),
+                decode_params::<Row>(blob).unwrap()
+            );
+        }
+        assert!(matches!(decoded.last(), Some(Err(Error::Overrun))));
+    }
+}
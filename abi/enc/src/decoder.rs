@@ -9,14 +9,15 @@
 //
 //! ABI decoder.
 
-use core::ops::Range;
+use alloc::rc::Rc;
+use core::{cell::Cell, ops::Range};
 
 #[cfg(not(feature = "std"))]
 use crate::no_std_prelude::*;
-use crate::{encode, Error, SolType, Token, Word};
+use crate::{encode, Error, NamedValues, SolType, Token, Word};
 
 fn round_up_nearest_multiple(value: usize, padding: usize) -> usize {
-    (value + padding - 1) / padding * padding
+    value.div_ceil(padding) * padding
 }
 
 pub(crate) fn check_fixed_bytes(word: Word, len: usize) -> Result<(), Error> {
@@ -28,7 +29,6 @@ pub(crate) fn check_fixed_bytes(word: Word, len: usize) -> Result<(), Error> {
         1..=31 => check_zeroes(&word[len..]),
         32 => Ok(()),
         33.. => Err(Error::InvalidData),
-        _ => unreachable!(),
     }
 }
 
@@ -48,7 +48,90 @@ pub(crate) fn check_bool(slice: Word) -> Result<(), Error> {
     Ok(())
 }
 
-#[derive(Clone, Copy)]
+/// Resource limits for decoding, to bound the work done on untrusted data.
+///
+/// Offsets may point many values at the same data, so a short blob can
+/// describe a huge value. The limits are checked while decoding, and
+/// exceeding one fails with [`Error::LimitExceeded`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DecoderConfig {
+    /// Maximum nesting of dynamic values, i.e. of offsets followed
+    pub max_depth: usize,
+    /// Maximum total number of elements of dynamic arrays
+    pub max_elements: usize,
+    /// Maximum total number of bytes read, counting data read more than
+    /// once each time
+    pub max_bytes: usize,
+}
+
+impl Default for DecoderConfig {
+    fn default() -> Self {
+        Self {
+            max_depth: 64,
+            max_elements: 1 << 20,
+            max_bytes: 1 << 26,
+        }
+    }
+}
+
+impl DecoderConfig {
+    /// No limits
+    pub const fn unlimited() -> Self {
+        Self {
+            max_depth: usize::MAX,
+            max_elements: usize::MAX,
+            max_bytes: usize::MAX,
+        }
+    }
+
+    /// Set the maximum nesting of dynamic values
+    #[must_use = "Builder method outputs must be used"]
+    pub const fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Set the maximum total number of elements of dynamic arrays
+    #[must_use = "Builder method outputs must be used"]
+    pub const fn with_max_elements(mut self, max_elements: usize) -> Self {
+        self.max_elements = max_elements;
+        self
+    }
+
+    /// Set the maximum total number of bytes read
+    #[must_use = "Builder method outputs must be used"]
+    pub const fn with_max_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_bytes = max_bytes;
+        self
+    }
+}
+
+/// Resources used by a decoder and its children
+#[derive(Debug)]
+struct Usage {
+    config: DecoderConfig,
+    elements: Cell<usize>,
+    bytes: Cell<usize>,
+}
+
+impl Usage {
+    fn claim(used: &Cell<usize>, n: usize, max: usize, limit: &'static str) -> Result<(), Error> {
+        let total = used.get().saturating_add(n);
+        if total > max {
+            return Err(Error::LimitExceeded(limit));
+        }
+        used.set(total);
+        Ok(())
+    }
+}
+
+/// A cursor over an ABI blob, from which [`SolType::read_token`] reads
+/// tokens.
+///
+/// Offsets are relative to the start of the decoder's buffer. Dynamic
+/// values are read through an offset word, by a child decoder whose buffer
+/// starts at the value
+#[derive(Clone)]
 pub struct Decoder<'a> {
     // the underlying buffer
     buf: &'a [u8],
@@ -58,6 +141,10 @@ pub struct Decoder<'a> {
     is_params: bool,
     // true if we validate type correctness and blob re-encoding
     validate: bool,
+    // the number of offsets followed to reach this decoder
+    depth: usize,
+    // limits and usage, shared with children
+    usage: Rc<Usage>,
 }
 
 impl std::fmt::Debug for Decoder<'_> {
@@ -67,17 +154,39 @@ impl std::fmt::Debug for Decoder<'_> {
             .field("offset", &self.offset)
             .field("is_params", &self.is_params)
             .field("validate", &self.validate)
+            .field("depth", &self.depth)
+            .field("usage", &self.usage)
             .finish()
     }
 }
 
 impl<'a> Decoder<'a> {
+    /// Instantiate a decoder at the start of `buf`. `is_params` if `buf`
+    /// is the params of a call, whose top-level tuple has no offset word.
+    /// `validate` to reject non-zero padding. Uses the default
+    /// [`DecoderConfig`]
     pub fn new(buf: &'a [u8], is_params: bool, validate: bool) -> Self {
+        Self::with_config(buf, is_params, validate, DecoderConfig::default())
+    }
+
+    /// Instantiate a decoder with resource limits. See [`Decoder::new`]
+    pub fn with_config(
+        buf: &'a [u8],
+        is_params: bool,
+        validate: bool,
+        config: DecoderConfig,
+    ) -> Self {
         Self {
             buf,
             offset: 0,
             is_params,
             validate,
+            depth: 0,
+            usage: Rc::new(Usage {
+                config,
+                elements: Cell::new(0),
+                bytes: Cell::new(0),
+            }),
         }
     }
 
@@ -90,11 +199,32 @@ impl<'a> Decoder<'a> {
             offset: 0,
             is_params: false,
             validate: self.validate,
+            depth: self.depth,
+            usage: self.usage.clone(),
         })
     }
 
-    pub fn raw_child(&self) -> Decoder<'a> {
-        self.child(self.offset).unwrap()
+    /// Count `len` elements of a dynamic array against the element limit
+    pub fn claim_elements(&self, len: usize) -> Result<(), Error> {
+        let usage = &*self.usage;
+        Usage::claim(
+            &usage.elements,
+            len,
+            usage.config.max_elements,
+            "max_elements",
+        )
+    }
+
+    /// Count `len` bytes read against the byte limit
+    pub fn claim_bytes(&self, len: usize) -> Result<(), Error> {
+        let usage = &*self.usage;
+        Usage::claim(&usage.bytes, len, usage.config.max_bytes, "max_bytes")
+    }
+
+    /// A decoder whose buffer starts at the current offset. Fails if the
+    /// offset is past the end of the buffer
+    pub fn raw_child(&self) -> Result<Decoder<'a>, Error> {
+        self.child(self.offset)
     }
 
     fn increase_offset(&mut self, len: usize) {
@@ -102,54 +232,74 @@ impl<'a> Decoder<'a> {
         self.is_params = false;
     }
 
+    /// The bytes in `range`, without advancing
     pub fn peek(&self, range: Range<usize>) -> Result<&'a [u8], Error> {
         (self.buf.len() >= range.end)
             .then(|| &self.buf[range])
             .ok_or(Error::Overrun)
     }
 
+    /// `len` bytes at `offset`, without advancing
     pub fn peek_len_at(&self, offset: usize, len: usize) -> Result<&'a [u8], Error> {
         self.peek(offset..offset + len)
     }
 
+    /// `len` bytes at the current offset, without advancing
     pub fn peek_len(&self, len: usize) -> Result<&'a [u8], Error> {
         self.peek_len_at(self.offset, len)
     }
 
+    /// The word at `offset`, without advancing
     pub fn peek_word_at(&self, offset: usize) -> Result<Word, Error> {
         Ok(Word::from_slice(
             self.peek_len_at(offset, Word::len_bytes())?,
         ))
     }
 
+    /// The word at the current offset, without advancing
     pub fn peek_word(&self) -> Result<Word, Error> {
         self.peek_word_at(self.offset)
     }
 
+    /// The word at `offset` as a `usize`, without advancing. Fails if it
+    /// does not fit in 32 bits
     pub fn peek_usize_at(&self, offset: usize) -> Result<usize, Error> {
         as_usize(self.peek_word_at(offset)?)
     }
 
+    /// The word at the current offset as a `usize`, without advancing
     pub fn peek_usize(&self) -> Result<usize, Error> {
         as_usize(self.peek_word()?)
     }
 
+    /// Read a word, advancing past it
     pub fn take_word(&mut self) -> Result<Word, Error> {
+        self.claim_bytes(Word::len_bytes())?;
         let contents = self.peek_word()?;
         self.increase_offset(Word::len_bytes());
         Ok(contents)
     }
 
+    /// Read an offset word, returning a child decoder at the offset
     pub fn take_indirection(&mut self) -> Result<Decoder<'a>, Error> {
+        if self.depth >= self.usage.config.max_depth {
+            return Err(Error::LimitExceeded("max_depth"));
+        }
         let ptr = self.take_usize()?;
-        self.child(ptr)
+        let mut child = self.child(ptr)?;
+        child.depth += 1;
+        Ok(child)
     }
 
+    /// Read a word as a `usize`, advancing past it
     pub fn take_usize(&mut self) -> Result<usize, Error> {
         as_usize(self.take_word()?)
     }
 
+    /// Read `len` bytes, advancing past them. Padding is checked, and not
+    /// skipped, if the decoder validates
     pub fn take_slice(&mut self, len: usize) -> Result<&[u8], Error> {
+        self.claim_bytes(len)?;
         if self.validate {
             let padded_len = round_up_nearest_multiple(len, 32);
             if self.offset + padded_len > self.buf.len() {
@@ -162,65 +312,104 @@ impl<'a> Decoder<'a> {
         Ok(res)
     }
 
+    /// True if the decoder rejects non-zero padding
     pub fn validate(&self) -> bool {
         self.validate
     }
 
+    /// True if the decoder is at the start of call params
     pub fn is_params(&self) -> bool {
         self.is_params
     }
 
+    /// Advance to the offset of a child decoder made with
+    /// [`Decoder::raw_child`]
     pub fn take_offset(&mut self, child: Decoder<'a>) {
         self.set_offset(child.offset + (self.buf.len() - child.buf.len()))
     }
 
+    /// Move to `offset`
     pub fn set_offset(&mut self, offset: usize) {
         self.offset = offset;
     }
 
+    /// The current offset
     pub fn offset(&self) -> usize {
         self.offset
     }
 }
 
-#[doc(hidden)]
-#[derive(Debug)]
-pub struct DecodeResult {
-    pub token: Token,
+/// A token decoded by [`decode_at`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodeResult<'a> {
+    /// The decoded token. Packed sequences borrow from the data
+    pub token: Token<'a>,
+    /// The offset after the token's head, where the next value of the
+    /// enclosing sequence starts
     pub new_offset: usize,
 }
 
-pub(crate) fn decode_params_impl<T>(data: &[u8], validate: bool) -> crate::Result<Token>
+/// Decode a `T` whose head is at `offset` in an ABI blob, e.g. the `i`th
+/// member of a tuple of single-word heads at `32 * i`, without decoding
+/// the rest of the blob.
+///
+/// Offsets of dynamic values are relative to the start of `data`, as in
+/// the encoding of a tuple or call params. Use [`DecodeResult::new_offset`]
+/// to read the following value
+pub fn decode_at<T>(data: &[u8], offset: usize) -> crate::Result<DecodeResult<'_>>
 where
     T: SolType,
 {
-    let mut decoder = Decoder::new(data, true, validate);
+    let mut decoder = Decoder::new(data, false, false);
+    decoder.set_offset(offset);
+    let token = T::read_token(&mut decoder)?;
+    Ok(DecodeResult {
+        token,
+        new_offset: decoder.offset(),
+    })
+}
 
-    if data.is_empty() {
+pub(crate) fn decode_params_impl<T>(
+    data: &[u8],
+    validate: bool,
+    config: DecoderConfig,
+) -> crate::Result<Token<'_>>
+where
+    T: SolType,
+{
+    let mut decoder = Decoder::with_config(data, true, validate, config);
+
+    // only the empty tuple, returned by functions that return nothing,
+    // decodes from empty data
+    if data.is_empty() && T::head_words() != 0 {
         return Err(Error::InvalidData);
     }
 
     let token = T::read_token(&mut decoder)?;
 
-    if validate && encode(&token) != data {
+    if validate && encode(&token)[..] != *data {
         return Err(Error::ExtraData);
     }
 
     Ok(token)
 }
 
-pub(crate) fn decode_impl<T>(data: &[u8], validate: bool) -> crate::Result<Token>
+pub(crate) fn decode_impl<T>(
+    data: &[u8],
+    validate: bool,
+    config: DecoderConfig,
+) -> crate::Result<Token<'_>>
 where
     T: SolType,
 {
-    let mut decoder = Decoder::new(data, false, validate);
+    let mut decoder = Decoder::with_config(data, false, validate, config);
 
     if data.is_empty() {
         return Err(Error::InvalidData);
     }
     let token = T::read_token(&mut decoder)?;
 
-    if validate && encode(&token) != data {
+    if validate && encode(&token)[..] != *data {
         return Err(Error::ExtraData);
     }
 
@@ -229,35 +418,94 @@ where
 
 /// Decodes ABI compliant vector of bytes into vector of tokens described by types param.
 /// Checks, that decoded data is exact as input provided
-pub fn decode_validate<T>(data: &[u8]) -> crate::Result<Token>
+pub fn decode_validate<T>(data: &[u8]) -> crate::Result<Token<'static>>
 where
     T: SolType,
 {
-    decode_impl::<T>(data, true)
+    decode_impl::<T>(data, true, DecoderConfig::default()).map(Token::into_owned)
 }
 
 /// Decode top-level function args and validate
-pub fn decode_params_validate<T>(data: &[u8]) -> crate::Result<Token>
+pub fn decode_params_validate<T>(data: &[u8]) -> crate::Result<Token<'static>>
 where
     T: SolType,
 {
-    decode_params_impl::<T>(data, true)
+    decode_params_impl::<T>(data, true, DecoderConfig::default()).map(Token::into_owned)
 }
 
 /// Decodes ABI compliant vector of bytes into vector of tokens described by types param.
-pub fn decode<T>(data: &[u8]) -> Result<Token, Error>
+pub fn decode<T>(data: &[u8]) -> Result<Token<'static>, Error>
 where
     T: SolType,
 {
-    decode_impl::<T>(data, false)
+    decode_impl::<T>(data, false, DecoderConfig::default()).map(Token::into_owned)
 }
 
 /// Decode top-level function args
-pub fn decode_params<T>(data: &[u8]) -> crate::Result<Token>
+pub fn decode_params<T>(data: &[u8]) -> crate::Result<Token<'static>>
+where
+    T: SolType,
+{
+    decode_params_impl::<T>(data, false, DecoderConfig::default()).map(Token::into_owned)
+}
+
+/// Decode with resource limits. Validates if `validate`, as
+/// [`decode_validate`] does
+pub fn decode_with_config<T>(
+    data: &[u8],
+    validate: bool,
+    config: DecoderConfig,
+) -> crate::Result<Token<'static>>
+where
+    T: SolType,
+{
+    decode_impl::<T>(data, validate, config).map(Token::into_owned)
+}
+
+/// Decode top-level function args with resource limits. Validates if
+/// `validate`, as [`decode_params_validate`] does
+pub fn decode_params_with_config<T>(
+    data: &[u8],
+    validate: bool,
+    config: DecoderConfig,
+) -> crate::Result<Token<'static>>
+where
+    T: SolType,
+{
+    decode_params_impl::<T>(data, validate, config).map(Token::into_owned)
+}
+
+/// Decode without copying `bytes` and `string` contents. Packed sequences
+/// in the token borrow from `data`
+pub fn decode_borrowed<T>(data: &[u8]) -> crate::Result<Token<'_>>
+where
+    T: SolType,
+{
+    decode_impl::<T>(data, false, DecoderConfig::default())
+}
+
+/// Decode top-level function args without copying `bytes` and `string`
+/// contents. Packed sequences in the token borrow from `data`
+pub fn decode_params_borrowed<T>(data: &[u8]) -> crate::Result<Token<'_>>
 where
     T: SolType,
 {
-    decode_params_impl::<T>(data, false)
+    decode_params_impl::<T>(data, false, DecoderConfig::default())
+}
+
+/// Decode top-level function args, pairing them with their ABI parameter
+/// names. `T` must be the tuple of the parameter types
+pub fn decode_params_named<T>(data: &[u8], names: &[&str]) -> crate::Result<NamedValues>
+where
+    T: SolType,
+{
+    match decode_params::<T>(data)? {
+        Token::FixedSeq(tokens) => NamedValues::new(
+            names.iter().map(|name| (*name).to_owned()).collect(),
+            tokens,
+        ),
+        _ => Err(Error::InvalidData),
+    }
 }
 
 pub(crate) fn check_zeroes(data: &[u8]) -> Result<(), Error> {
@@ -270,12 +518,17 @@ pub(crate) fn check_zeroes(data: &[u8]) -> Result<(), Error> {
 
 #[cfg(test)]
 mod tests {
-    use ethers_primitives::{B160, B256};
-    use hex_literal::hex;
+    use alloc::borrow::Cow;
+
+    use ethers_primitives::hex;
+    use ethers_primitives::{Address, B256, U256};
 
     #[cfg(not(feature = "std"))]
     use crate::no_std_prelude::*;
-    use crate::{decode, decode_params, decode_validate, sol_type, util::pad_u32, SolType, Token};
+    use crate::{
+        decode, decode_at, decode_params, decode_validate, decode_with_config, sol_type,
+        util::pad_u32, DecoderConfig, Error, SolType, Token,
+    };
 
     #[test]
     fn decode_static_tuple_of_addresses_and_uints() {
@@ -286,8 +539,8 @@ mod tests {
 			1111111111111111111111111111111111111111111111111111111111111111
 		"
         );
-        let address1 = sol_type::Address::tokenize(B160([0x11u8; 20]));
-        let address2 = sol_type::Address::tokenize(B160([0x22u8; 20]));
+        let address1 = sol_type::Address::tokenize(Address::repeat_byte(0x11));
+        let address2 = sol_type::Address::tokenize(Address::repeat_byte(0x22));
         let uint = Token::Word([0x11u8; 32].into());
         let expected = Token::FixedSeq(vec![address1, address2, uint]);
         let decoded =
@@ -308,8 +561,8 @@ mod tests {
 			6761766f66796f726b0000000000000000000000000000000000000000000000
 		"
         );
-        let string1 = Token::PackedSeq(b"gavofyork".to_vec());
-        let string2 = Token::PackedSeq(b"gavofyork".to_vec());
+        let string1 = Token::PackedSeq(b"gavofyork".to_vec().into());
+        let string2 = Token::PackedSeq(b"gavofyork".to_vec().into());
         let expected = Token::FixedSeq(vec![string1, string2]);
 
         let decoded = decode::<(sol_type::String, sol_type::String)>(&encoded).unwrap();
@@ -344,12 +597,12 @@ mod tests {
 			66756e7465737473000000000000000000000000000000000000000000000000
 		"
         );
-        let string1 = Token::PackedSeq(b"test".to_vec());
-        let string2 = Token::PackedSeq(b"cyborg".to_vec());
-        let string3 = Token::PackedSeq(b"night".to_vec());
-        let string4 = Token::PackedSeq(b"day".to_vec());
-        let string5 = Token::PackedSeq(b"weee".to_vec());
-        let string6 = Token::PackedSeq(b"funtests".to_vec());
+        let string1 = Token::PackedSeq(b"test".to_vec().into());
+        let string2 = Token::PackedSeq(b"cyborg".to_vec().into());
+        let string3 = Token::PackedSeq(b"night".to_vec().into());
+        let string4 = Token::PackedSeq(b"day".to_vec().into());
+        let string5 = Token::PackedSeq(b"weee".to_vec().into());
+        let string6 = Token::PackedSeq(b"funtests".to_vec().into());
         let bool = sol_type::Bool::tokenize(true);
         let deep_tuple = Token::FixedSeq(vec![string5, string6]);
         let inner_tuple = Token::FixedSeq(vec![string3, string4, deep_tuple]);
@@ -384,9 +637,9 @@ mod tests {
 		"
         );
         let uint = Token::Word([0x11u8; 32].into());
-        let string = Token::PackedSeq(b"gavofyork".to_vec());
-        let address1 = sol_type::Address::tokenize(B160([0x11u8; 20]));
-        let address2 = sol_type::Address::tokenize(B160([0x22u8; 20]));
+        let string = Token::PackedSeq(b"gavofyork".to_vec().into());
+        let address1 = sol_type::Address::tokenize(Address::repeat_byte(0x11));
+        let address2 = sol_type::Address::tokenize(Address::repeat_byte(0x22));
         let expected = Token::FixedSeq(vec![uint, string, address1, address2]);
 
         type MyTy = (
@@ -418,13 +671,13 @@ mod tests {
 			6379626f72670000000000000000000000000000000000000000000000000000
 		"
         );
-        let address1 = sol_type::Address::tokenize(B160([0x22u8; 20]));
+        let address1 = sol_type::Address::tokenize(Address::repeat_byte(0x22));
         let bool1 = sol_type::Bool::tokenize(true);
-        let string1 = Token::PackedSeq(b"spaceship".to_vec());
-        let string2 = Token::PackedSeq(b"cyborg".to_vec());
+        let string1 = Token::PackedSeq(b"spaceship".to_vec().into());
+        let string2 = Token::PackedSeq(b"cyborg".to_vec().into());
         let tuple = Token::FixedSeq(vec![bool1, string1, string2]);
-        let address2 = sol_type::Address::tokenize(B160([0x33u8; 20]));
-        let address3 = sol_type::Address::tokenize(B160([0x44u8; 20]));
+        let address2 = sol_type::Address::tokenize(Address::repeat_byte(0x33));
+        let address3 = sol_type::Address::tokenize(Address::repeat_byte(0x44));
         let bool2 = sol_type::Bool::tokenize(false);
         let expected = Token::FixedSeq(vec![address1, tuple, address2, address3, bool2]);
 
@@ -452,13 +705,13 @@ mod tests {
 			0000000000000000000000004444444444444444444444444444444444444444
 		"
         );
-        let address1 = sol_type::Address::tokenize(B160([0x11u8; 20]));
-        let address2 = sol_type::Address::tokenize(B160([0x22u8; 20]));
+        let address1 = sol_type::Address::tokenize(Address::repeat_byte(0x11));
+        let address2 = sol_type::Address::tokenize(Address::repeat_byte(0x22));
         let bool1 = sol_type::Bool::tokenize(true);
         let bool2 = sol_type::Bool::tokenize(false);
         let tuple = Token::FixedSeq(vec![address2, bool1, bool2]);
-        let address3 = sol_type::Address::tokenize(B160([0x33u8; 20]));
-        let address4 = sol_type::Address::tokenize(B160([0x44u8; 20]));
+        let address3 = sol_type::Address::tokenize(Address::repeat_byte(0x33));
+        let address4 = sol_type::Address::tokenize(Address::repeat_byte(0x44));
 
         let expected = Token::FixedSeq(vec![address1, tuple, address3, address4]);
 
@@ -508,8 +761,8 @@ mod tests {
 			Token::FixedSeq(
                 vec![
                     Token::Word(pad_u32(0)),
-                    Token::PackedSeq(b"12203967b532a0c14c980b5aeffb17048bdfaef2c293a9509f08eb3c6b0f5f8f0942e7b9cc76ca51cca26ce546920448e308fda6870b5e2ae12a2409d942de428113P720p30fps16x9".to_vec()),
-                    Token::PackedSeq(b"93c717e7c0a6517a".to_vec()),
+                    Token::PackedSeq(b"12203967b532a0c14c980b5aeffb17048bdfaef2c293a9509f08eb3c6b0f5f8f0942e7b9cc76ca51cca26ce546920448e308fda6870b5e2ae12a2409d942de428113P720p30fps16x9".to_vec().into()),
+                    Token::PackedSeq(b"93c717e7c0a6517a".to_vec().into()),
                     Token::Word(pad_u32(1)),
                     Token::Word(pad_u32(5538829))
                 ]
@@ -540,10 +793,12 @@ mod tests {
         assert_eq!(
             decode_params::<MyTy>(&encoded).unwrap(),
             Token::FixedSeq(vec![
-                sol_type::Address::tokenize(B160(hex!("8497afefdc5ac170a664a231f6efb25526ef813f"))),
+                sol_type::Address::tokenize(Address::new(hex!(
+                    "8497afefdc5ac170a664a231f6efb25526ef813f"
+                ))),
                 Token::Word(B256::repeat_byte(0x01)),
                 Token::Word(B256::repeat_byte(0x02)),
-                Token::PackedSeq("0x0000001F".into()),
+                Token::PackedSeq("0x0000001F".as_bytes().into()),
             ])
         )
     }
@@ -560,7 +815,7 @@ mod tests {
 
         assert_eq!(
             decode::<sol_type::String>(&encoded).unwrap(),
-            Token::PackedSeq([0xe4, 0xb8, 0x8d, 0xe5].to_vec())
+            Token::PackedSeq([0xe4, 0xb8, 0x8d, 0xe5].to_vec().into())
         );
     }
 
@@ -607,4 +862,200 @@ mod tests {
         assert!(decode_validate::<(sol_type::Address, sol_type::FixedBytes<20>)>(&input).is_err());
         assert!(decode_validate::<(sol_type::Address, sol_type::Address)>(&input).is_ok());
     }
+
+    #[test]
+    fn decode_dynamic_array_of_dynamic_tuples() {
+        let encoded = hex!(
+            "
+			0000000000000000000000000000000000000000000000000000000000000020
+			0000000000000000000000000000000000000000000000000000000000000002
+			0000000000000000000000000000000000000000000000000000000000000040
+			00000000000000000000000000000000000000000000000000000000000000c0
+			0000000000000000000000000000000000000000000000000000000000000001
+			0000000000000000000000000000000000000000000000000000000000000040
+			0000000000000000000000000000000000000000000000000000000000000002
+			0102000000000000000000000000000000000000000000000000000000000000
+			0000000000000000000000000000000000000000000000000000000000000000
+			0000000000000000000000000000000000000000000000000000000000000040
+			0000000000000000000000000000000000000000000000000000000000000000
+		"
+        );
+        type Results = (sol_type::Array<(sol_type::Bool, sol_type::Bytes)>,);
+
+        let decoded = Results::detokenize(&decode_params::<Results>(&encoded).unwrap()).unwrap();
+        assert_eq!(decoded, (vec![(true, vec![1, 2]), (false, vec![])],));
+        assert_eq!(Results::encode_params(decoded), encoded);
+    }
+
+    #[test]
+    fn decode_borrows_packed_seqs() {
+        type MyTy = (sol_type::Uint<8>, sol_type::Array<sol_type::Bytes>);
+        let value = (7, vec![vec![0xab; 40], vec![0xcd; 3]]);
+        let encoded = MyTy::encode_params(value.clone());
+
+        let token = crate::decode_params_borrowed::<MyTy>(&encoded).unwrap();
+        let Token::DynSeq(blobs) = &token.as_fixed_seq().unwrap()[1] else {
+            panic!("expected a dynamic sequence");
+        };
+        for blob in blobs {
+            let Token::PackedSeq(Cow::Borrowed(blob)) = blob else {
+                panic!("expected borrowed bytes, got {blob:?}");
+            };
+            assert!(encoded.as_ptr_range().contains(&blob.as_ptr()));
+        }
+        assert_eq!(MyTy::detokenize(&token).unwrap(), value);
+
+        let owned = decode_params::<MyTy>(&encoded).unwrap();
+        assert_eq!(owned, token);
+        assert_eq!(token.into_owned(), owned);
+
+        let token = MyTy::tokenize_ref(&value);
+        assert_eq!(crate::encode(&token), encoded);
+    }
+
+    #[test]
+    fn decode_at_reads_one_member() {
+        type MyTy = (
+            sol_type::Uint<256>,
+            sol_type::String,
+            (sol_type::Bool, sol_type::Address),
+            sol_type::Bool,
+        );
+        let encoded = MyTy::encode_params((
+            U256::from(5),
+            "lazy".to_string(),
+            (true, Address::repeat_byte(0x11)),
+            false,
+        ));
+
+        let string = decode_at::<sol_type::String>(&encoded, 32).unwrap();
+        assert_eq!(string.token, Token::PackedSeq(b"lazy".as_slice().into()));
+        assert_eq!(string.new_offset, 64);
+
+        let tuple = decode_at::<(sol_type::Bool, sol_type::Address)>(&encoded, 64).unwrap();
+        assert_eq!(
+            <(sol_type::Bool, sol_type::Address)>::detokenize(&tuple.token).unwrap(),
+            (true, Address::repeat_byte(0x11))
+        );
+        let last = decode_at::<sol_type::Bool>(&encoded, tuple.new_offset).unwrap();
+        assert_eq!(last.token, sol_type::Bool::tokenize(false));
+
+        assert!(decode_at::<sol_type::Bool>(&encoded, encoded.len()).is_err());
+        assert!(matches!(
+            decode_at::<(sol_type::Bool, sol_type::Address)>(&encoded, encoded.len() + 1),
+            Err(Error::Overrun)
+        ));
+    }
+
+    #[test]
+    fn decode_at_advances_past_fixed_arrays() {
+        type Pair = sol_type::FixedArray<sol_type::Uint<256>, 2>;
+        let encoded = <(Pair, sol_type::Uint<256>)>::encode_params((
+            [U256::from(1), U256::from(2)],
+            U256::from(3),
+        ));
+
+        let pair = decode_at::<Pair>(&encoded, 0).unwrap();
+        assert_eq!(pair.new_offset, 64);
+        let last = decode_at::<sol_type::Uint<256>>(&encoded, pair.new_offset).unwrap();
+        assert_eq!(last.token, sol_type::Uint::<256>::tokenize(U256::from(3)));
+
+        assert_eq!(
+            <(Pair, sol_type::Uint<256>)>::decode_params(&encoded).unwrap(),
+            ([U256::from(1), U256::from(2)], U256::from(3))
+        );
+    }
+
+    #[test]
+    fn decode_with_config_bounds_shared_offsets() {
+        // a uint256[][] of 4 arrays, all pointing at the same 4 elements
+        let mut encoded = pad_u32(0x20).as_bytes().to_vec();
+        encoded.extend_from_slice(pad_u32(4).as_bytes());
+        for _ in 0..4 {
+            encoded.extend_from_slice(pad_u32(4 * 32).as_bytes());
+        }
+        encoded.extend_from_slice(pad_u32(4).as_bytes());
+        for i in 0..4 {
+            encoded.extend_from_slice(pad_u32(i).as_bytes());
+        }
+        type Nested = sol_type::Array<sol_type::Array<sol_type::Uint<256>>>;
+
+        assert!(decode::<Nested>(&encoded).is_ok());
+        let config = DecoderConfig::default().with_max_elements(19);
+        assert!(matches!(
+            decode_with_config::<Nested>(&encoded, false, config),
+            Err(Error::LimitExceeded("max_elements"))
+        ));
+        let config = DecoderConfig::default().with_max_bytes(15 * 32);
+        assert!(matches!(
+            decode_with_config::<Nested>(&encoded, false, config),
+            Err(Error::LimitExceeded("max_bytes"))
+        ));
+        let config = DecoderConfig::default().with_max_depth(1);
+        assert!(matches!(
+            decode_with_config::<Nested>(&encoded, false, config),
+            Err(Error::LimitExceeded("max_depth"))
+        ));
+    }
+
+    #[test]
+    fn sol_type_decode_trailing_data() {
+        type Ret = (sol_type::Uint<256>, sol_type::String);
+        let value = (U256::from(7), "ok".to_owned());
+        let mut encoded = Ret::encode_params(value.clone()).to_vec();
+
+        assert_eq!(Ret::decode_params(&encoded).unwrap(), value);
+        assert_eq!(Ret::decode_params_validate(&encoded).unwrap(), value);
+
+        encoded.extend_from_slice(pad_u32(1).as_bytes());
+        assert_eq!(Ret::decode_params(&encoded).unwrap(), value);
+        assert!(matches!(
+            Ret::decode_params_validate(&encoded),
+            Err(Error::ExtraData)
+        ));
+
+        let mut encoded = sol_type::Address::encode(Address::repeat_byte(0x11)).to_vec();
+        assert!(sol_type::Address::decode_validate(&encoded).is_ok());
+        encoded.push(0);
+        assert!(sol_type::Address::decode(&encoded).is_ok());
+        assert!(sol_type::Address::decode_validate(&encoded).is_err());
+    }
+
+    #[test]
+    fn sol_type_unit() {
+        assert!(<()>::encode_params(()).is_empty());
+        assert_eq!(
+            <()>::encode_with_selector([1, 2, 3, 4], ()),
+            [1, 2, 3, 4][..]
+        );
+        <()>::decode_params(&[]).unwrap();
+        <()>::decode_params_validate(&[]).unwrap();
+        assert!(<()>::decode_params_validate(pad_u32(1).as_bytes()).is_err());
+        assert!(<(sol_type::Bool,)>::decode_params(&[]).is_err());
+    }
+
+    #[test]
+    fn sol_type_optional() {
+        type Ret = sol_type::Optional<sol_type::String>;
+        for value in [Some("found".to_owned()), None] {
+            let encoded = Ret::encode_params(value.clone());
+            assert_eq!(
+                encoded,
+                <(sol_type::Bool, sol_type::String)>::encode_params((
+                    value.is_some(),
+                    value.clone().unwrap_or_default()
+                ))
+            );
+            assert_eq!(Ret::encode_params_ref(&value), encoded);
+            assert_eq!(Ret::decode_params_validate(&encoded).unwrap(), value);
+        }
+
+        // a value with a false flag is ignored
+        let encoded =
+            <(sol_type::Bool, sol_type::Uint<256>)>::encode_params((false, U256::from(1)));
+        assert_eq!(
+            sol_type::Optional::<sol_type::Uint<256>>::decode_params(&encoded).unwrap(),
+            None
+        );
+    }
 }
@@ -10,49 +10,78 @@
 
 #[cfg(not(feature = "std"))]
 use crate::no_std_prelude::*;
-use crate::{util::pad_u32, Bytes, Token, Word};
+use core::marker::PhantomData;
+
+use smallvec::SmallVec;
+
+use crate::{util::pad_u32, Bytes, SolType, Token, Word};
 
 fn pad_bytes_len(bytes: &[u8]) -> u32 {
     // "+ 1" because len is also appended
-    ((bytes.len() + 31) / 32) as u32 + 1
+    bytes.len().div_ceil(32) as u32 + 1
 }
 
-fn pad_bytes_append(data: &mut Vec<Word>, bytes: &[u8]) {
-    data.push(pad_u32(bytes.len() as u32));
-    fixed_bytes_append(data, bytes);
+/// An output buffer the encoder appends to
+trait Sink {
+    fn put(&mut self, bytes: &[u8]);
+    fn put_zeroes(&mut self, len: usize);
 }
 
-fn fixed_bytes_append(result: &mut Vec<Word>, bytes: &[u8]) {
-    let len = (bytes.len() + 31) / 32;
-    for i in 0..len {
-        let mut padded = Word::default();
-
-        let to_copy = match i == len - 1 {
-            false => 32,
-            true => match bytes.len() % 32 {
-                0 => 32,
-                x => x,
-            },
-        };
+impl Sink for Vec<u8> {
+    fn put(&mut self, bytes: &[u8]) {
+        self.extend_from_slice(bytes);
+    }
+
+    fn put_zeroes(&mut self, len: usize) {
+        self.resize(self.len() + len, 0);
+    }
+}
+
+/// A fixed-size buffer, filled from the start. The encoding's length is
+/// checked before writing, so writes never overflow it
+struct ArrayBuf<'a> {
+    buf: &'a mut [u8],
+    len: usize,
+}
+
+impl Sink for ArrayBuf<'_> {
+    fn put(&mut self, bytes: &[u8]) {
+        self.buf[self.len..self.len + bytes.len()].copy_from_slice(bytes);
+        self.len += bytes.len();
+    }
 
-        let offset = 32 * i;
-        padded[..to_copy].copy_from_slice(&bytes[offset..offset + to_copy]);
-        result.push(padded);
+    fn put_zeroes(&mut self, len: usize) {
+        // the buffer starts zeroed
+        self.len += len;
     }
 }
 
-fn encode_head_tail(mediates: &[Mediate]) -> Vec<Word> {
-    let (heads_len, tails_len) = mediates.iter().fold((0, 0), |(head_acc, tail_acc), m| {
-        (head_acc + m.head_len(), tail_acc + m.tail_len())
-    });
+fn push_word(data: &mut impl Sink, word: Word) {
+    data.put(word.as_ref());
+}
 
-    let mut result = Vec::with_capacity((heads_len + tails_len) as usize);
-    encode_head_tail_append(&mut result, mediates);
+fn pad_bytes_append(data: &mut impl Sink, bytes: &[u8]) {
+    push_word(data, pad_u32(bytes.len() as u32));
+    fixed_bytes_append(data, bytes);
+}
+
+fn fixed_bytes_append(result: &mut impl Sink, bytes: &[u8]) {
+    result.put(bytes);
+    // right-pad to a whole number of words
+    let padded = bytes.len().div_ceil(32) * 32;
+    result.put_zeroes(padded - bytes.len());
+}
 
-    result
+fn encoded_len(mediates: &[Mediate]) -> u32 {
+    mediates.iter().map(|m| m.head_len() + m.tail_len()).sum()
 }
 
-fn encode_head_tail_append(acc: &mut Vec<Word>, mediates: &[Mediate]) {
+fn encode_head_tail(out: &mut Vec<u8>, mediates: &[Mediate]) {
+    out.reserve(encoded_len(mediates) as usize);
+    encode_head_tail_append(out, mediates);
+}
+
+fn encode_head_tail_append(acc: &mut impl Sink, mediates: &[Mediate]) {
     let heads_len = mediates
         .iter()
         .fold(0, |head_acc, m| head_acc + m.head_len());
@@ -66,9 +95,9 @@ fn encode_head_tail_append(acc: &mut Vec<Word>, mediates: &[Mediate]) {
     mediates.iter().for_each(|m| m.tail_append(acc));
 }
 
-fn encode_token_append(data: &mut Vec<Word>, token: &Token) {
+fn encode_token_append(data: &mut impl Sink, token: &Token) {
     match token {
-        Token::Word(word) => data.push(*word),
+        Token::Word(word) => push_word(data, *word),
         Token::PackedSeq(bytes) => pad_bytes_append(data, bytes),
         _ => panic!("Unhandled nested token: {:?}", token),
     };
@@ -80,21 +109,21 @@ enum Mediate<'a> {
     // Head-only
 
     // Raw: head words + token
-    Raw(u32, &'a Token),
+    Raw(u32, &'a Token<'a>),
     // RawArray: tokens
     RawArray(Vec<Mediate<'a>>),
 
     // head + tail
 
     // Prefixed: tail words, token
-    Prefixed(u32, &'a Token),
+    Prefixed(u32, &'a Token<'a>),
     //
     PrefixedArray(Vec<Mediate<'a>>),
     PrefixedArrayWithLength(Vec<Mediate<'a>>),
 }
 
-impl Mediate<'_> {
-    fn from_token(token: &Token) -> Mediate<'_> {
+impl<'a> Mediate<'a> {
+    fn from_token(token: &'a Token<'a>) -> Mediate<'a> {
         match token {
             Token::Word(_) => Mediate::Raw(1, token),
             Token::FixedSeq(tokens) => {
@@ -140,7 +169,7 @@ impl Mediate<'_> {
         }
     }
 
-    fn head_append(&self, acc: &mut Vec<Word>, suffix_offset: u32) {
+    fn head_append(&self, acc: &mut impl Sink, suffix_offset: u32) {
         match *self {
             Mediate::Raw(_, raw) => encode_token_append(acc, raw),
             Mediate::RawArray(ref raw) => {
@@ -148,63 +177,288 @@ impl Mediate<'_> {
             }
             Mediate::Prefixed(_, _)
             | Mediate::PrefixedArray(_)
-            | Mediate::PrefixedArrayWithLength(_) => acc.push(pad_u32(suffix_offset)),
+            | Mediate::PrefixedArrayWithLength(_) => push_word(acc, pad_u32(suffix_offset)),
         }
     }
 
-    fn tail_append(&self, acc: &mut Vec<Word>) {
+    fn tail_append(&self, acc: &mut impl Sink) {
         match *self {
             Mediate::Raw(_, _) | Mediate::RawArray(_) => {}
             Mediate::Prefixed(_, raw) => encode_token_append(acc, raw),
             Mediate::PrefixedArray(ref mediates) => encode_head_tail_append(acc, mediates),
             Mediate::PrefixedArrayWithLength(ref mediates) => {
                 // + 32 added to offset represents len of the array prepended to tail
-                acc.push(pad_u32(mediates.len() as u32));
+                push_word(acc, pad_u32(mediates.len() as u32));
                 encode_head_tail_append(acc, mediates);
             }
         };
     }
 }
 
-/// Encodes vector of tokens into ABI compliant vector of bytes.
-fn encode_impl<'a>(tokens: impl IntoIterator<Item = &'a Token>) -> Bytes {
-    let mediates = &tokens
-        .into_iter()
-        .map(Mediate::from_token)
-        .collect::<Vec<_>>();
-
-    encode_head_tail(mediates)
-        .into_iter()
-        .flat_map(Into::<[u8; 32]>::into)
-        .collect()
+/// The top-level mediates of an encoding. Calls rarely have more than a
+/// few params, so they are kept inline
+type Mediates<'a> = SmallVec<[Mediate<'a>; 8]>;
+
+/// Encodes tokens into ABI compliant bytes, appending to `out`.
+pub(crate) fn encode_impl<'a>(out: &mut Vec<u8>, tokens: impl IntoIterator<Item = &'a Token<'a>>) {
+    let mediates: Mediates = tokens.into_iter().map(Mediate::from_token).collect();
+
+    encode_head_tail(out, &mediates)
 }
 
-/// Encode a token to a bytearray.
+/// Encode a token to a bytearray. A [`Token::FixedSeq`], i.e. a tuple or
+/// a fixed-size array, is encoded as the params of a call, as
+/// [`encode_params`] encodes its members. Any other token is encoded as a
+/// single param, as [`encode_single`] does.
+///
+/// The two differ for dynamic tuples, whose encoding as a single value
+/// starts with an offset. Prefer the explicit functions when the token may
+/// be a tuple.
 pub fn encode(token: &Token) -> Bytes {
+    let mut out = Vec::new();
+    encode_into(token, &mut out);
+    out.into()
+}
+
+/// Encode a token, appending to `out`. Equivalent to [`encode`], without
+/// allocating a new buffer
+pub fn encode_into(token: &Token, out: &mut Vec<u8>) {
     match token {
-        Token::FixedSeq(v) => encode_impl(v),
-        _ => encode_impl([token]),
+        Token::FixedSeq(params) => encode_impl(out, params),
+        _ => encode_raw_into(token, out),
     }
 }
 
+/// Encode a single value, as Solidity's `abi.encode(value)` does. A tuple
+/// is encoded as one param, so a dynamic tuple starts with the offset of
+/// its encoding.
+///
+/// ```
+/// use ethers_abi_enc::{encode_params, encode_single, Token};
+///
+/// let bytes = Token::PackedSeq(vec![0xab].into());
+/// let tuple = Token::FixedSeq(vec![bytes.clone()]);
+/// assert_eq!(encode_single(&tuple)[..32], encode_single(&bytes)[..32]);
+/// assert_eq!(encode_single(&tuple)[32..], encode_single(&bytes)[..]);
+/// assert_eq!(encode_params(&[bytes.clone()]), encode_single(&bytes));
+/// ```
+pub fn encode_single(token: &Token) -> Bytes {
+    encode_params(core::slice::from_ref(token))
+}
+
+/// Encode values as the params of a call, as Solidity's
+/// `abi.encode(a, b, ...)` and function calls do. Each token is one param.
+/// Equivalent to [`encode_single`] of a tuple of the tokens, without the
+/// tuple's offset
+pub fn encode_params(tokens: &[Token]) -> Bytes {
+    let mut out = Vec::new();
+    encode_impl(&mut out, tokens);
+    out.into()
+}
+
+/// Encode a token into a stack buffer, as [`encode`] does, without
+/// allocating the output. Returns the buffer and the length of the
+/// encoding, which occupies its start.
+///
+/// Params that are words, `bytes` or `string`s are encoded without
+/// touching the heap. Size `N` for the largest expected call, e.g. 256
+/// bytes for the 8 words of most token calls.
+///
+/// # Panics
+///
+/// If the encoding is longer than `N` bytes. See [`Token::encoded_size`]
+///
+/// ```
+/// use ethers_abi_enc::{encode, encode_to_array, sol_type, SolType};
+/// use ethers_primitives::U256;
+///
+/// type Transfer = (sol_type::Address, sol_type::Uint<256>);
+///
+/// let token = Transfer::tokenize((Default::default(), U256::from(1)));
+/// let (buf, len) = encode_to_array::<256>(&token);
+/// assert_eq!(buf[..len], encode(&token)[..]);
+/// ```
+pub fn encode_to_array<const N: usize>(token: &Token) -> ([u8; N], usize) {
+    let mediates: Mediates = match token {
+        Token::FixedSeq(v) => v.iter().map(Mediate::from_token).collect(),
+        _ => core::iter::once(Mediate::from_token(token)).collect(),
+    };
+    let len = encoded_len(&mediates) as usize;
+    assert!(len <= N, "encoding of {len} bytes does not fit in {N}");
+
+    let mut buf = [0; N];
+    let mut sink = ArrayBuf {
+        buf: &mut buf,
+        len: 0,
+    };
+    encode_head_tail_append(&mut sink, &mediates);
+    debug_assert_eq!(sink.len, len);
+    (buf, len)
+}
+
 /// Encode a token into a bytearray suitable for use INTERNAL to an abi blob.
-/// Typically.
+/// Equivalent to [`encode_single`]
 pub fn encode_raw(token: &Token) -> Bytes {
-    encode_impl([token])
+    encode_single(token)
+}
+
+/// Encode a token, appending to `out`. Equivalent to [`encode_raw`],
+/// without allocating a new buffer
+pub fn encode_raw_into(token: &Token, out: &mut Vec<u8>) {
+    encode_impl(out, [token])
+}
+
+/// Encode an indexed event parameter as a topic. Value types are their
+/// word. Strings and bytes are the hash of their contents, and arrays and
+/// tuples the hash of their elements' encodings, each padded to a whole
+/// number of words
+pub fn encode_topic(token: &Token) -> Word {
+    match token {
+        Token::Word(word) => *word,
+        Token::PackedSeq(bytes) => ethers_primitives::keccak256(bytes),
+        _ => {
+            let mut preimage = Vec::new();
+            topic_preimage_append(&mut preimage, token);
+            ethers_primitives::keccak256(preimage)
+        }
+    }
+}
+
+fn topic_preimage_append(out: &mut Vec<u8>, token: &Token) {
+    match token {
+        Token::Word(word) => push_word(out, *word),
+        Token::PackedSeq(bytes) => fixed_bytes_append(out, bytes),
+        Token::FixedSeq(tokens) | Token::DynSeq(tokens) => tokens
+            .iter()
+            .for_each(|token| topic_preimage_append(out, token)),
+    }
+}
+
+/// The position of a top-level parameter in the head
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Slot {
+    /// Offset of the parameter's head, in words
+    offset: usize,
+    /// Length of the parameter's head, in words
+    words: usize,
+    dynamic: bool,
+}
+
+/// Encodes values of `T` as params, reusing a head layout computed once.
+///
+/// [`SolType::encode_params`] measures every parameter's head and tail on
+/// each call to find the tail offsets. The head layout depends only on the
+/// type, so the encoder precomputes each parameter's head offset as a
+/// prefix sum. Per value, it writes static heads in place and appends
+/// dynamic tails, taking their offsets from the length of the output so
+/// far. Prefer it when encoding the same schema many times.
+///
+/// ```
+/// use ethers_abi_enc::{sol_type, Encoder, SolType};
+/// use ethers_primitives::U256;
+///
+/// type Transfer = (sol_type::Address, sol_type::Uint<256>, sol_type::Bytes);
+///
+/// let encoder = Encoder::<Transfer>::new();
+/// let value = (Default::default(), U256::from(1), vec![0xde, 0xad]);
+/// assert_eq!(encoder.encode_params(value.clone()), Transfer::encode_params(value));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Encoder<T> {
+    slots: Vec<Slot>,
+    head_words: usize,
+    _pd: PhantomData<fn(T)>,
+}
+
+impl<T: SolType> Default for Encoder<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: SolType> Encoder<T> {
+    /// Compute the head layout of `T`
+    pub fn new() -> Self {
+        let mut offset = 0;
+        let slots = T::param_layout()
+            .into_iter()
+            .map(|(words, dynamic)| {
+                let slot = Slot {
+                    offset,
+                    words,
+                    dynamic,
+                };
+                offset += words;
+                slot
+            })
+            .collect();
+
+        Self {
+            slots,
+            head_words: offset,
+            _pd: PhantomData,
+        }
+    }
+
+    /// The length of the head, in bytes. This is the full length of the
+    /// encoding if `T` is static
+    pub fn head_len(&self) -> usize {
+        self.head_words * 32
+    }
+
+    /// Encode a value as params. Equivalent to [`SolType::encode_params`]
+    pub fn encode_params(&self, rust: T::RustType) -> Bytes {
+        let mut out = Vec::new();
+        self.encode_params_to(rust, &mut out);
+        out.into()
+    }
+
+    /// Encode a value as params, appending to `out`
+    pub fn encode_params_to(&self, rust: T::RustType, out: &mut Vec<u8>) {
+        let token = T::tokenize(rust);
+        let params = match &token {
+            Token::FixedSeq(tokens) => tokens.as_slice(),
+            _ => core::slice::from_ref(&token),
+        };
+        debug_assert_eq!(params.len(), self.slots.len());
+
+        let mediates: Mediates = params.iter().map(Mediate::from_token).collect();
+        let tails_len: u32 = mediates.iter().map(Mediate::tail_len).sum();
+
+        // offsets are relative to the start of this encoding, not of `out`
+        let base = out.len();
+        out.reserve(self.head_len() + tails_len as usize);
+        out.resize(base + self.head_len(), 0);
+        for (slot, mediate) in self.slots.iter().zip(&mediates) {
+            let head = base + slot.offset * 32;
+            if slot.dynamic {
+                let offset = pad_u32((out.len() - base) as u32);
+                out[head..head + 32].copy_from_slice(offset.as_ref());
+                mediate.tail_append(out);
+            } else {
+                // append the static head, then move it into its slot
+                let end = out.len();
+                mediate.head_append(out, 0);
+                debug_assert_eq!(out.len() - end, slot.words * 32);
+                out.copy_within(end.., head);
+                out.truncate(end);
+            }
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use ethers_primitives::{B160, U256};
-    use hex_literal::hex;
+    use ethers_primitives::hex;
+    use ethers_primitives::{Address, U256};
 
     #[cfg(not(feature = "std"))]
     use crate::no_std_prelude::*;
-    use crate::{sol_type, util::pad_u32, SolType};
+    use crate::{sol_type, util::pad_u32, Encoder, SolType, Token};
 
     #[test]
     fn encode_address() {
-        let address = B160([0x11u8; 20]);
+        let address = Address::repeat_byte(0x11);
         let expected = hex!("0000000000000000000000001111111111111111111111111111111111111111");
         let encoded = sol_type::Address::encode(address);
         assert_eq!(encoded, expected);
@@ -214,7 +468,7 @@ mod tests {
     #[test]
     fn encode_dynamic_array_of_addresses() {
         type MyTy = sol_type::Array<sol_type::Address>;
-        let rust = vec![B160([0x11u8; 20]), B160([0x22u8; 20])];
+        let rust = vec![Address::repeat_byte(0x11), Address::repeat_byte(0x22)];
         let encoded = MyTy::encode(rust);
         let expected = hex!(
             "
@@ -232,7 +486,7 @@ mod tests {
     fn encode_fixed_array_of_addresses() {
         type MyTy = sol_type::FixedArray<sol_type::Address, 2>;
 
-        let addresses = [B160([0x11u8; 20]), B160([0x22u8; 20])];
+        let addresses = [Address::repeat_byte(0x11), Address::repeat_byte(0x22)];
 
         let encoded = MyTy::encode(addresses);
         let encoded_params = MyTy::encode_params(addresses);
@@ -250,7 +504,7 @@ mod tests {
     #[test]
     fn encode_two_addresses() {
         type MyTy = (sol_type::Address, sol_type::Address);
-        let addresses = (B160([0x11u8; 20]), B160([0x22u8; 20]));
+        let addresses = (Address::repeat_byte(0x11), Address::repeat_byte(0x22));
 
         let encoded = MyTy::encode(addresses);
         let encoded_params = MyTy::encode_params(addresses);
@@ -270,8 +524,8 @@ mod tests {
         type MyTy = sol_type::FixedArray<sol_type::Array<sol_type::Address>, 2>;
 
         let fixed = [
-            vec![B160([0x11u8; 20]), B160([0x22u8; 20])],
-            vec![B160([0x33u8; 20]), B160([0x44u8; 20])],
+            vec![Address::repeat_byte(0x11), Address::repeat_byte(0x22)],
+            vec![Address::repeat_byte(0x33), Address::repeat_byte(0x44)],
         ];
 
         let encoded = MyTy::encode(fixed.clone());
@@ -304,8 +558,8 @@ mod tests {
         type MyTy = sol_type::Array<TwoAddrs>;
 
         let dynamic = vec![
-            [B160([0x11u8; 20]), B160([0x22u8; 20])],
-            [B160([0x33u8; 20]), B160([0x44u8; 20])],
+            [Address::repeat_byte(0x11), Address::repeat_byte(0x22)],
+            [Address::repeat_byte(0x33), Address::repeat_byte(0x44)],
         ];
 
         let encoded = MyTy::encode(dynamic.clone());
@@ -330,7 +584,10 @@ mod tests {
     fn encode_dynamic_array_of_dynamic_arrays() {
         type MyTy = sol_type::Array<sol_type::Array<sol_type::Address>>;
 
-        let dynamic = vec![vec![B160([0x11u8; 20])], vec![B160([0x22u8; 20])]];
+        let dynamic = vec![
+            vec![Address::repeat_byte(0x11)],
+            vec![Address::repeat_byte(0x22)],
+        ];
 
         let encoded = MyTy::encode(dynamic.clone());
         let encoded_params = MyTy::encode_params(dynamic);
@@ -352,13 +609,90 @@ mod tests {
         assert_eq!(encoded_params, expected);
     }
 
+    #[test]
+    fn encode_generic_sizes() {
+        fn fixed_bytes<const N: usize>(rust: [u8; N]) -> crate::Bytes {
+            sol_type::FixedBytes::<N>::encode(rust)
+        }
+        fn uint<const BITS: usize>(
+            rust: <sol_type::BitCount<BITS> as sol_type::SupportedUint>::Uint,
+        ) -> (String, crate::Bytes)
+        where
+            sol_type::BitCount<BITS>: sol_type::SupportedUint,
+        {
+            (
+                sol_type::Uint::<BITS>::sol_type_name(),
+                sol_type::Uint::<BITS>::encode(rust),
+            )
+        }
+
+        assert_eq!(
+            fixed_bytes([0x12, 0x34]),
+            hex!("1234000000000000000000000000000000000000000000000000000000000000")
+        );
+        assert_eq!(sol_type::FixedBytes::<3>::sol_type_name(), "bytes3");
+
+        let expected = hex!("0000000000000000000000000000000000000000000000000000000000000007");
+        assert_eq!(uint::<16>(7), ("uint16".to_owned(), expected.into()));
+        assert_eq!(
+            uint::<200>(U256::from(7u8)),
+            ("uint200".to_owned(), expected.into())
+        );
+        assert_eq!(
+            sol_type::Int::<24>::encode(-2),
+            hex!("fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffe")
+        );
+        assert_eq!(
+            sol_type::Int::<24>::decode(&sol_type::Int::<24>::encode(-2)).unwrap(),
+            -2
+        );
+    }
+
+    #[test]
+    fn encode_borrowed_dynamic_arrays() {
+        type MyTy = sol_type::Array<sol_type::Array<sol_type::String>>;
+
+        let rust = vec![
+            vec!["a".to_owned(), "bc".to_owned()],
+            vec![],
+            vec!["d".repeat(40)],
+        ];
+        let expected = MyTy::encode(rust.clone());
+        assert_eq!(MyTy::encode_ref(&rust), expected);
+        assert_eq!(MyTy::encode_slice(&rust), expected);
+        assert_eq!(
+            MyTy::encode_params_ref(&rust),
+            MyTy::encode_params(rust.clone())
+        );
+        assert_eq!(
+            MyTy::tokenize_iter(rust.iter().take(2)),
+            MyTy::tokenize(rust[..2].to_vec())
+        );
+        assert_eq!(MyTy::decode(&expected).unwrap(), rust);
+
+        type Nested = (
+            sol_type::Array<sol_type::Array<sol_type::Bytes>>,
+            sol_type::Uint<256>,
+        );
+        let rust = (
+            vec![vec![vec![1u8; 33]], vec![vec![], vec![2]]],
+            U256::from(3u8),
+        );
+        let encoded = Nested::encode_params_ref(&rust);
+        assert_eq!(encoded, Nested::encode_params(rust.clone()));
+        assert_eq!(
+            Nested::detokenize(&crate::decode_params::<Nested>(&encoded).unwrap()).unwrap(),
+            rust
+        );
+    }
+
     #[test]
     fn encode_dynamic_array_of_dynamic_arrays2() {
         type MyTy = sol_type::Array<sol_type::Array<sol_type::Address>>;
 
         let dynamic = vec![
-            vec![B160([0x11u8; 20]), B160([0x22u8; 20])],
-            vec![B160([0x33u8; 20]), B160([0x44u8; 20])],
+            vec![Address::repeat_byte(0x11), Address::repeat_byte(0x22)],
+            vec![Address::repeat_byte(0x33), Address::repeat_byte(0x44)],
         ];
         let encoded = MyTy::encode(dynamic.clone());
         let encoded_params = MyTy::encode_params(dynamic);
@@ -387,8 +721,8 @@ mod tests {
         type MyTy = sol_type::FixedArray<sol_type::FixedArray<sol_type::Address, 2>, 2>;
 
         let fixed = [
-            [B160([0x11u8; 20]), B160([0x22u8; 20])],
-            [B160([0x33u8; 20]), B160([0x44u8; 20])],
+            [Address::repeat_byte(0x11), Address::repeat_byte(0x22)],
+            [Address::repeat_byte(0x33), Address::repeat_byte(0x44)],
         ];
 
         let encoded = MyTy::encode(fixed);
@@ -418,9 +752,13 @@ mod tests {
                 (
                     U256::from(93523141),
                     U256::from(352332135),
-                    B160([0x44u8; 20]),
+                    Address::repeat_byte(0x44),
+                ),
+                (
+                    U256::from(12411),
+                    U256::from(451),
+                    Address::repeat_byte(0x22),
                 ),
-                (U256::from(12411), U256::from(451), B160([0x22u8; 20])),
             ],
             "gavofyork".to_string(),
         );
@@ -816,7 +1154,7 @@ mod tests {
     #[test]
     fn encode_static_tuple_of_addresses() {
         type MyTy = (sol_type::Address, sol_type::Address);
-        let data = (B160([0x11u8; 20]), B160([0x22u8; 20]));
+        let data = (Address::repeat_byte(0x11), Address::repeat_byte(0x22));
 
         let encoded = MyTy::encode(data);
         let encoded_params = MyTy::encode_params(data);
@@ -906,8 +1244,8 @@ mod tests {
         let data = (
             U256::from_be_bytes::<32>([0x11u8; 32]),
             "gavofyork".to_owned(),
-            B160([0x11u8; 20]),
-            B160([0x22u8; 20]),
+            Address::repeat_byte(0x11),
+            Address::repeat_byte(0x22),
         );
 
         let encoded = MyTy::encode(data.clone());
@@ -1003,10 +1341,10 @@ mod tests {
             sol_type::Bool,
         );
         let data = (
-            B160([0x22u8; 20]),
+            Address::repeat_byte(0x22),
             (true, "spaceship".to_owned(), "cyborg".to_owned()),
-            B160([0x33u8; 20]),
-            B160([0x44u8; 20]),
+            Address::repeat_byte(0x33),
+            Address::repeat_byte(0x44),
             false,
         );
 
@@ -1048,10 +1386,10 @@ mod tests {
         );
 
         let data = (
-            B160([0x11u8; 20]),
-            (B160([0x22u8; 20]), true, false),
-            B160([0x33u8; 20]),
-            B160([0x44u8; 20]),
+            Address::repeat_byte(0x11),
+            (Address::repeat_byte(0x22), true, false),
+            Address::repeat_byte(0x33),
+            Address::repeat_byte(0x44),
         );
 
         let encoded = MyTy::encode(data);
@@ -1104,4 +1442,170 @@ mod tests {
         assert_ne!(encoded_params, expected);
         assert_eq!(encoded_params.len() + 32, encoded.len());
     }
+
+    #[test]
+    fn encoder_matches_encode_params() {
+        type MyTy = (
+            sol_type::Uint<256>,
+            sol_type::Array<sol_type::String>,
+            sol_type::FixedArray<sol_type::Address, 2>,
+            (sol_type::Bool, sol_type::Bytes),
+            sol_type::FixedBytes<32>,
+        );
+        let encoder = Encoder::<MyTy>::new();
+        assert_eq!(encoder.head_len(), 6 * 32);
+
+        for i in 0..3u64 {
+            let value = (
+                U256::from(i),
+                vec!["gavofyork".to_string(); i as usize],
+                [Address::repeat_byte(0x11), Address::repeat_byte(i as u8)],
+                (i % 2 == 0, vec![0xab; 33 * i as usize]),
+                [i as u8; 32],
+            );
+            assert_eq!(
+                encoder.encode_params(value.clone()),
+                MyTy::encode_params(value)
+            );
+        }
+
+        let encoder = Encoder::<sol_type::FixedArray<sol_type::Address, 2>>::new();
+        let addresses = [Address::repeat_byte(0x11), Address::repeat_byte(0x22)];
+        assert_eq!(encoder.head_len(), 64);
+        assert_eq!(
+            encoder.encode_params(addresses),
+            sol_type::FixedArray::<sol_type::Address, 2>::encode_params(addresses)
+        );
+
+        let encoder = Encoder::<sol_type::String>::new();
+        assert_eq!(
+            encoder.encode_params("hello".to_string()),
+            sol_type::String::encode_params("hello".to_string())
+        );
+    }
+
+    #[test]
+    fn encode_appends_to_buffer() {
+        type MyTy = (sol_type::Uint<256>, sol_type::Bytes, sol_type::String);
+        let value = (U256::from(1), vec![0xab; 40], "hello".to_string());
+        let params = MyTy::encode_params(value.clone());
+        let selector = [0xde, 0xad, 0xbe, 0xef];
+
+        let mut expected = selector.to_vec();
+        expected.extend(&params);
+        assert_eq!(
+            MyTy::encode_with_selector(selector, value.clone()),
+            expected
+        );
+
+        // offsets are relative to the start of the params, not the buffer
+        let mut out = selector.to_vec();
+        Encoder::<MyTy>::new().encode_params_to(value.clone(), &mut out);
+        assert_eq!(out, expected);
+
+        let token = MyTy::tokenize(value);
+        let mut out = selector.to_vec();
+        crate::encode_into(&token, &mut out);
+        assert_eq!(out, expected);
+
+        let mut out = selector.to_vec();
+        crate::encode_raw_into(&token, &mut out);
+        assert_eq!(out[4..], crate::encode_raw(&token));
+    }
+
+    #[test]
+    fn encode_topics() {
+        let address = Address::repeat_byte(0x11);
+        assert_eq!(
+            sol_type::Address::encode_topic(address)[..],
+            sol_type::Address::encode(address)[..]
+        );
+        assert_eq!(
+            sol_type::String::encode_topic("hello".to_string()),
+            ethers_primitives::keccak256("hello")
+        );
+
+        let preimage = hex!(
+            "
+            0000000000000000000000000000000000000000000000000000000000000001
+            ab00000000000000000000000000000000000000000000000000000000000000
+            "
+        );
+        assert_eq!(
+            <(sol_type::Uint<8>, sol_type::Bytes)>::encode_topic((1, vec![0xab])),
+            ethers_primitives::keccak256(preimage)
+        );
+    }
+
+    #[test]
+    fn encode_collections_and_tuples() {
+        // the shape of a struct with dynamic collections
+        type Order = (
+            sol_type::Address,
+            sol_type::Array<sol_type::Uint<256>>,
+            (sol_type::Bool, sol_type::Bytes),
+        );
+        let order = (
+            Address::repeat_byte(0x11),
+            vec![U256::from(1), U256::from(2)],
+            (true, vec![0xab, 0xcd]),
+        );
+        let encoded = Order::encode_params(order.clone());
+        assert_eq!(Order::encode_params_ref(&order), encoded);
+        assert_eq!(Order::decode_params_validate(&encoded).unwrap(), order);
+
+        // slices encode without collecting into a `Vec`
+        let amounts = [U256::from(1), U256::from(2)];
+        assert_eq!(
+            sol_type::Array::<sol_type::Uint<256>>::encode_slice(&amounts),
+            sol_type::Array::<sol_type::Uint<256>>::encode(amounts.to_vec())
+        );
+    }
+
+    #[test]
+    fn encode_to_array() {
+        type Call = (sol_type::Address, sol_type::Uint<256>, sol_type::String);
+        let call = (
+            Address::repeat_byte(0x11),
+            U256::from(1),
+            "hello".to_owned(),
+        );
+
+        let (buf, len) = Call::encode_params_to_array::<256>(&call);
+        assert_eq!(buf[..len], Call::encode_params(call.clone())[..]);
+        assert!(buf[len..].iter().all(|b| *b == 0));
+
+        let (buf, len) = sol_type::Address::encode_params_to_array::<32>(&call.0);
+        assert_eq!(buf[..len], sol_type::Address::encode_params(call.0)[..]);
+    }
+
+    #[test]
+    #[should_panic(expected = "does not fit")]
+    fn encode_to_array_overflow() {
+        type Call = (sol_type::Address, sol_type::Uint<256>, sol_type::String);
+        let call = (
+            Address::repeat_byte(0x11),
+            U256::from(1),
+            "hello".to_owned(),
+        );
+        Call::encode_params_to_array::<128>(&call);
+    }
+
+    #[test]
+    fn encode_single_and_params() {
+        let bytes = Token::PackedSeq(vec![0xab, 0xcd].into());
+        let word = Token::Word(pad_u32(7));
+        let tuple = Token::FixedSeq(vec![word.clone(), bytes.clone()]);
+
+        // a dynamic tuple as a single value starts with its offset
+        let single = crate::encode_single(&tuple);
+        let params = crate::encode_params(&[word.clone(), bytes]);
+        assert_eq!(single[..32], pad_u32(32)[..]);
+        assert_eq!(single[32..], params[..]);
+        assert_eq!(crate::encode(&tuple), params);
+
+        // a static value has no offset
+        assert_eq!(crate::encode_single(&word), crate::encode_params(&[word]));
+        assert!(crate::encode_params(&[]).is_empty());
+    }
 }
@@ -33,6 +33,10 @@ pub enum Error {
     #[cfg_attr(feature = "std", error("Extra data in deser buffer"))]
     /// Extra data in deser buffer
     ExtraData,
+    #[cfg_attr(feature = "std", error("Decoding limit exceeded: {0}"))]
+    /// A [`DecoderConfig`](crate::DecoderConfig) limit was exceeded. Carries
+    /// the name of the limit
+    LimitExceeded(&'static str),
     /// Serialization error.
     #[cfg(feature = "full-serde")]
     #[error("Serialization error: {0}")]
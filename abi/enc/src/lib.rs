@@ -21,15 +21,33 @@ mod no_std_prelude {
     pub use std::borrow::Cow;
 }
 
-use ethers_primitives::{B160, B256};
+use ethers_primitives::B256;
 #[cfg(not(feature = "std"))]
 use no_std_prelude::*;
 
 mod decoder;
-pub use decoder::{decode, decode_params, decode_params_validate, decode_validate};
+pub use decoder::{
+    decode, decode_at, decode_borrowed, decode_params, decode_params_borrowed, decode_params_named,
+    decode_params_validate, decode_params_with_config, decode_validate, decode_with_config,
+    DecodeResult, Decoder, DecoderConfig,
+};
 
 mod encoder;
-pub use encoder::{encode, encode_raw};
+pub use encoder::{
+    encode, encode_into, encode_params, encode_raw, encode_raw_into, encode_single,
+    encode_to_array, encode_topic, Encoder,
+};
+
+#[cfg(feature = "parallel")]
+mod batch;
+#[cfg(feature = "parallel")]
+pub use batch::{decode_batch, encode_batch};
+
+mod revert;
+pub use revert::{panic_reason, Revert, RevertDecoder, ERROR_SELECTOR, PANIC_SELECTOR};
+
+mod named;
+pub use named::NamedValues;
 
 mod token;
 pub use token::Token;
@@ -37,20 +55,24 @@ pub use token::Token;
 mod errors;
 pub use errors::{Error, Result};
 
+#[cfg(any(test, feature = "arbitrary"))]
+mod arbitrary;
+
 /// Solidity Types
 pub mod sol_type;
 pub use sol_type::SolType;
 
 pub mod util;
+pub use util::{event_topic, selector};
 
 /// EVM Word
 pub type Word = B256;
 /// EVM Address
-pub type Address = B160;
+pub use ethers_primitives::Address;
 /// FixedBytes type
 pub type FixedBytes = Vec<u8>;
 /// Dynamic Byte array
-pub type Bytes = Vec<u8>;
+pub use ethers_primitives::Bytes;
 /// Signed int
 pub type Int = B256;
 /// Unsigned Int
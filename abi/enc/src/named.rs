@@ -0,0 +1,176 @@
+//! Decoded values with their ABI parameter names.
+
+use core::ops::Index;
+
+#[cfg(not(feature = "std"))]
+use crate::no_std_prelude::*;
+use crate::{Error, Token};
+
+/// Values decoded against ABI parameters, with the parameters' names.
+///
+/// Values keep their ABI order, and can be accessed by position or by name.
+/// Parameters with an empty name are only accessible by position.
+#[derive(Clone, Debug, PartialEq)]
+pub struct NamedValues<T = Token<'static>> {
+    names: Vec<String>,
+    values: Vec<T>,
+}
+
+impl<T> NamedValues<T> {
+    /// Pair values with their parameter names. Fails if the counts differ
+    pub fn new(names: Vec<String>, values: Vec<T>) -> crate::Result<Self> {
+        if names.len() != values.len() {
+            return Err(Error::Other(
+                format!(
+                    "expected {} named values, got {}",
+                    names.len(),
+                    values.len()
+                )
+                .into(),
+            ));
+        }
+        Ok(Self { names, values })
+    }
+
+    /// Number of values
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// True if there are no values
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// The value at a position
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.values.get(index)
+    }
+
+    /// The value of the first parameter with a name
+    pub fn get_by_name(&self, name: &str) -> Option<&T> {
+        self.position(name).map(|index| &self.values[index])
+    }
+
+    /// The position of the first parameter with a name
+    pub fn position(&self, name: &str) -> Option<usize> {
+        if name.is_empty() {
+            return None;
+        }
+        self.names.iter().position(|n| n == name)
+    }
+
+    /// The name of the parameter at a position, if it has one
+    pub fn name(&self, index: usize) -> Option<&str> {
+        self.names
+            .get(index)
+            .map(String::as_str)
+            .filter(|name| !name.is_empty())
+    }
+
+    /// Iterate over names and values, in ABI order
+    pub fn iter(&self) -> impl Iterator<Item = (Option<&str>, &T)> {
+        (0..self.len()).map(|index| (self.name(index), &self.values[index]))
+    }
+
+    /// The parameter names, in ABI order. Unnamed parameters are empty
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    /// The values, in ABI order
+    pub fn values(&self) -> &[T] {
+        &self.values
+    }
+
+    /// Discard the names
+    pub fn into_values(self) -> Vec<T> {
+        self.values
+    }
+}
+
+impl<T> Index<usize> for NamedValues<T> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.values[index]
+    }
+}
+
+impl<T> Index<&str> for NamedValues<T> {
+    type Output = T;
+
+    fn index(&self, name: &str) -> &Self::Output {
+        self.get_by_name(name)
+            .unwrap_or_else(|| panic!("no value named {name:?}"))
+    }
+}
+
+impl<T> IntoIterator for NamedValues<T> {
+    type Item = (String, T);
+    type IntoIter = core::iter::Zip<
+        <Vec<String> as IntoIterator>::IntoIter,
+        <Vec<T> as IntoIterator>::IntoIter,
+    >;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.names.into_iter().zip(self.values)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ethers_primitives::hex;
+
+    use crate::{decode_params_named, sol_type, SolType, Token, Word};
+
+    use super::NamedValues;
+
+    #[test]
+    fn it_accesses_by_name_and_position() {
+        let values = NamedValues::new(
+            vec!["to".to_owned(), "".to_owned(), "amount".to_owned()],
+            vec![1, 2, 3],
+        )
+        .unwrap();
+
+        assert_eq!(values.len(), 3);
+        assert_eq!(values[0], 1);
+        assert_eq!(values["amount"], 3);
+        assert_eq!(values.get_by_name("to"), Some(&1));
+        assert_eq!(values.get_by_name(""), None);
+        assert_eq!(values.get_by_name("from"), None);
+        assert_eq!(values.name(1), None);
+        assert_eq!(values.position("amount"), Some(2));
+        assert_eq!(
+            values.iter().collect::<Vec<_>>(),
+            vec![(Some("to"), &1), (None, &2), (Some("amount"), &3)]
+        );
+
+        assert!(NamedValues::new(vec!["a".to_owned()], vec![1, 2]).is_err());
+    }
+
+    #[test]
+    fn it_decodes_named_params() {
+        type Transfer = (sol_type::Address, sol_type::Uint<256>);
+
+        let data = hex!(
+            "
+            0000000000000000000000001111111111111111111111111111111111111111
+            0000000000000000000000000000000000000000000000000000000000000064
+            "
+        );
+        let decoded = decode_params_named::<Transfer>(&data, &["to", "amount"]).unwrap();
+        assert_eq!(decoded.names(), ["to", "amount"]);
+
+        let mut amount = Word::default();
+        amount.0[31] = 0x64;
+        assert_eq!(decoded["amount"], Token::Word(amount));
+        assert_eq!(
+            sol_type::Address::detokenize(&decoded["to"]).unwrap(),
+            [0x11; 20].into()
+        );
+
+        assert!(decode_params_named::<Transfer>(&data, &["to"]).is_err());
+    }
+}
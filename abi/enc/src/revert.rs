@@ -0,0 +1,261 @@
+//! Decoding of revert data into standard and custom errors
+
+use alloc::collections::BTreeMap;
+use core::fmt;
+
+use ethers_primitives::U256;
+
+#[cfg(not(feature = "std"))]
+use crate::no_std_prelude::*;
+use crate::{decode_params, sol_type, util::selector, SolType, Token};
+
+/// The selector of `Error(string)`
+pub const ERROR_SELECTOR: [u8; 4] = selector("Error(string)");
+/// The selector of `Panic(uint256)`
+pub const PANIC_SELECTOR: [u8; 4] = selector("Panic(uint256)");
+
+/// Decoded revert data
+#[derive(Debug, Clone, PartialEq)]
+pub enum Revert {
+    /// `Error(string)`, from `require` and `revert` with a reason
+    Error(String),
+    /// `Panic(uint256)`, from failed assertions and runtime errors. See
+    /// [`panic_reason`]
+    Panic(U256),
+    /// A registered custom error
+    Custom {
+        /// The error name
+        name: String,
+        /// The error signature, e.g. `InsufficientBalance(uint256,uint256)`
+        signature: String,
+        /// The arguments, to be detokenized with the tuple of the parameter
+        /// types
+        args: Token<'static>,
+    },
+    /// No data, from `revert()`, or a failed call without a reason
+    Empty,
+    /// Data that is not a known error
+    Unknown(Vec<u8>),
+}
+
+/// The meaning of a Solidity panic code
+pub fn panic_reason(code: U256) -> Option<&'static str> {
+    let code: u8 = code.try_into().ok()?;
+    Some(match code {
+        0x00 => "generic panic",
+        0x01 => "assertion failed",
+        0x11 => "arithmetic overflow or underflow",
+        0x12 => "division or modulo by zero",
+        0x21 => "invalid enum value",
+        0x22 => "invalid storage byte array encoding",
+        0x31 => "pop on empty array",
+        0x32 => "array index out of bounds",
+        0x41 => "out of memory",
+        0x51 => "call to uninitialized function",
+        _ => return None,
+    })
+}
+
+impl fmt::Display for Revert {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Error(reason) => write!(f, "reverted: {reason}"),
+            Self::Panic(code) => match panic_reason(*code) {
+                Some(reason) => write!(f, "panicked: {reason}"),
+                None => write!(f, "panicked with code {code:#x}"),
+            },
+            Self::Custom { signature, .. } => write!(f, "reverted with {signature}"),
+            Self::Empty => write!(f, "reverted without data"),
+            Self::Unknown(data) => write!(f, "reverted with 0x{}", hex::encode(data)),
+        }
+    }
+}
+
+type DecodeArgs = fn(&[u8]) -> crate::Result<Token<'static>>;
+
+#[derive(Debug, Clone)]
+struct CustomError {
+    name: String,
+    signature: String,
+    decode: DecodeArgs,
+}
+
+fn decode_args<T: SolType>(args: &[u8]) -> crate::Result<T::RustType> {
+    T::detokenize(&decode_params::<T>(args)?)
+}
+
+fn decode_no_args(data: &[u8]) -> crate::Result<Token<'static>> {
+    match data {
+        [] => Ok(Token::FixedSeq(vec![])),
+        _ => Err(crate::Error::ExtraData),
+    }
+}
+
+/// Decodes revert data into a [`Revert`].
+///
+/// `Error(string)` and `Panic(uint256)` are always recognized. Custom
+/// errors are recognized once registered.
+///
+/// ```
+/// use ethers_abi_enc::{sol_type, Revert, RevertDecoder, SolType};
+/// use ethers_primitives::U256;
+///
+/// type Balances = (sol_type::Uint<256>, sol_type::Uint<256>);
+/// let decoder = RevertDecoder::new().with_error::<Balances>("InsufficientBalance");
+///
+/// let mut data = ethers_abi_enc::selector("InsufficientBalance(uint256,uint256)").to_vec();
+/// data.extend(Balances::encode_params((U256::from(1u8), U256::from(2u8))));
+/// let Revert::Custom { args, .. } = decoder.decode(&data) else {
+///     panic!("expected a custom error");
+/// };
+/// assert_eq!(Balances::detokenize(&args).unwrap().1, U256::from(2u8));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct RevertDecoder {
+    errors: BTreeMap<[u8; 4], CustomError>,
+}
+
+impl RevertDecoder {
+    /// Instantiate a decoder with no custom errors
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a custom error. `T` must be the tuple of the parameter
+    /// types. Returns the error selector
+    pub fn register<T: SolType>(&mut self, name: &str) -> [u8; 4] {
+        let params = T::sol_type_name().replace("tuple(", "(");
+        self.insert(name, format!("{name}{params}"), decode_params::<T>)
+    }
+
+    /// Register a custom error without parameters. Returns the error
+    /// selector
+    pub fn register_empty(&mut self, name: &str) -> [u8; 4] {
+        self.insert(name, format!("{name}()"), decode_no_args)
+    }
+
+    /// Register a custom error. `T` must be the tuple of the parameter
+    /// types
+    #[must_use = "Builder method outputs must be used"]
+    pub fn with_error<T: SolType>(mut self, name: &str) -> Self {
+        self.register::<T>(name);
+        self
+    }
+
+    /// Register a custom error without parameters
+    #[must_use = "Builder method outputs must be used"]
+    pub fn with_empty_error(mut self, name: &str) -> Self {
+        self.register_empty(name);
+        self
+    }
+
+    fn insert(&mut self, name: &str, signature: String, decode: DecodeArgs) -> [u8; 4] {
+        let selector = selector(&signature);
+        let error = CustomError {
+            name: name.to_owned(),
+            signature,
+            decode,
+        };
+        self.errors.insert(selector, error);
+        selector
+    }
+
+    /// Decode revert data. Data with a known selector whose arguments do
+    /// not decode is reported as [`Revert::Unknown`]
+    pub fn decode(&self, data: &[u8]) -> Revert {
+        if data.is_empty() {
+            return Revert::Empty;
+        }
+        let unknown = || Revert::Unknown(data.to_vec());
+        let Some((head, args)) = data.split_first_chunk::<4>() else {
+            return unknown();
+        };
+
+        match *head {
+            ERROR_SELECTOR => decode_args::<(sol_type::String,)>(args)
+                .map_or_else(|_| unknown(), |(reason,)| Revert::Error(reason)),
+            PANIC_SELECTOR => decode_args::<(sol_type::Uint<256>,)>(args)
+                .map_or_else(|_| unknown(), |(code,)| Revert::Panic(code)),
+            selector => match self.errors.get(&selector) {
+                Some(error) => match (error.decode)(args) {
+                    Ok(args) => Revert::Custom {
+                        name: error.name.clone(),
+                        signature: error.signature.clone(),
+                        args,
+                    },
+                    Err(_) => unknown(),
+                },
+                None => unknown(),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ethers_primitives::hex;
+
+    use super::*;
+    use crate::util;
+
+    #[test]
+    fn it_decodes_standard_errors() {
+        let decoder = RevertDecoder::new();
+
+        // require(false, "Not enough Ether provided.")
+        let data = hex!(
+            "08c379a0"
+            "0000000000000000000000000000000000000000000000000000000000000020"
+            "000000000000000000000000000000000000000000000000000000000000001a"
+            "4e6f7420656e6f7567682045746865722070726f76696465642e000000000000"
+        );
+        let revert = decoder.decode(&data);
+        assert_eq!(revert, Revert::Error("Not enough Ether provided.".into()));
+        assert_eq!(revert.to_string(), "reverted: Not enough Ether provided.");
+
+        let mut data = PANIC_SELECTOR.to_vec();
+        data.extend(<(sol_type::Uint<256>,)>::encode_params((U256::from(
+            0x11u8,
+        ),)));
+        let revert = decoder.decode(&data);
+        assert_eq!(revert, Revert::Panic(U256::from(0x11u8)));
+        assert_eq!(
+            revert.to_string(),
+            "panicked: arithmetic overflow or underflow"
+        );
+
+        assert_eq!(decoder.decode(&[]), Revert::Empty);
+        assert_eq!(
+            decoder.decode(&ERROR_SELECTOR),
+            Revert::Unknown(ERROR_SELECTOR.to_vec())
+        );
+        assert_eq!(decoder.decode(&[1, 2]), Revert::Unknown(vec![1, 2]));
+    }
+
+    #[test]
+    fn it_decodes_registered_errors() {
+        type Args = (sol_type::Address, sol_type::Uint<256>);
+        let mut decoder = RevertDecoder::new();
+        let selector = decoder.register::<Args>("Unauthorized");
+        assert_eq!(selector, util::selector("Unauthorized(address,uint256)"));
+        let empty = decoder.register_empty("Paused");
+
+        let mut data = selector.to_vec();
+        data.extend(Args::encode_params((Default::default(), U256::from(7u8))));
+        match decoder.decode(&data) {
+            Revert::Custom {
+                name,
+                signature,
+                args,
+            } => {
+                assert_eq!(name, "Unauthorized");
+                assert_eq!(signature, "Unauthorized(address,uint256)");
+                assert_eq!(Args::detokenize(&args).unwrap().1, U256::from(7u8));
+            }
+            other => panic!("expected a custom error, got {other:?}"),
+        }
+
+        assert!(matches!(decoder.decode(&empty), Revert::Custom { name, .. } if name == "Paused"));
+        assert!(matches!(decoder.decode(&selector), Revert::Unknown(_)));
+    }
+}
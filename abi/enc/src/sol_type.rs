@@ -1,6 +1,7 @@
+use alloc::borrow::Cow;
 use core::marker::PhantomData;
 
-use ethers_primitives::{B160, B256, U256};
+use ethers_primitives::{Address as RustAddress, B256, U256};
 
 use std::string::String as RustString;
 
@@ -14,29 +15,99 @@ pub trait SolType {
     fn sol_type_name() -> RustString;
     /// True if the type is dynamic according to ABI rules
     fn is_dynamic() -> bool;
+    /// The number of words the type occupies in the head of an enclosing
+    /// sequence. Dynamic types occupy a single offset word
+    fn head_words() -> usize {
+        1
+    }
+    #[doc(hidden)]
+    /// The `(head_words, is_dynamic)` of each top-level parameter when the
+    /// type is encoded as params
+    fn param_layout() -> Vec<(usize, bool)> {
+        vec![(Self::head_words(), Self::is_dynamic())]
+    }
     /// Check a token to see if it can be detokenized with this type
     fn type_check(token: &Token) -> bool;
     /// Detokenize
     fn detokenize(token: &Token) -> crate::Result<Self::RustType>;
     /// Tokenize
-    fn tokenize(rust: Self::RustType) -> Token;
-
-    #[doc(hidden)]
-    /// Read a token from the
-    fn read_token(decoder: &mut Decoder<'_>) -> crate::Result<Token>;
+    fn tokenize(rust: Self::RustType) -> Token<'static>;
+    /// Tokenize a borrowed Rust type. Packed sequences borrow from it
+    fn tokenize_ref(rust: &Self::RustType) -> Token<'_>;
+
+    /// Read a token from a decoder, advancing it past the token's head.
+    /// Packed sequences borrow from the decoder's buffer. See
+    /// [`decode_at`]
+    fn read_token<'a>(decoder: &mut Decoder<'a>) -> crate::Result<Token<'a>>;
+
+    /// Encode a Rust type to an ABI blob, as a single value. See
+    /// [`crate::encode_single`]
+    fn encode(rust: Self::RustType) -> crate::Bytes {
+        let token = Self::tokenize(rust);
+        crate::encode_single(&token)
+    }
 
-    /// Encode a Rust type to an ABI blob
-    fn encode(rust: Self::RustType) -> Vec<u8> {
+    /// Encode a Rust type as params. Tuples and fixed-size arrays are
+    /// encoded as one param per member, as [`crate::encode_params`] does,
+    /// and other types as a single param
+    fn encode_params(rust: Self::RustType) -> crate::Bytes {
         let token = Self::tokenize(rust);
-        crate::encode_raw(&token)
+        crate::encode(&token)
     }
 
-    /// Encode a Rust type
-    fn encode_params(rust: Self::RustType) -> Vec<u8> {
+    /// Encode a Rust type, appending to `out`
+    fn encode_params_to(rust: Self::RustType, out: &mut Vec<u8>) {
         let token = Self::tokenize(rust);
+        crate::encode_into(&token, out)
+    }
+
+    /// Encode a borrowed Rust type into a stack buffer. Returns the buffer
+    /// and the length of the encoding. See [`crate::encode_to_array`]
+    ///
+    /// # Panics
+    ///
+    /// If the encoding is longer than `N` bytes
+    fn encode_params_to_array<const N: usize>(rust: &Self::RustType) -> ([u8; N], usize) {
+        crate::encode_to_array(&Self::tokenize_ref(rust))
+    }
+
+    /// Encode a Rust type as the arguments of a call to `selector`
+    fn encode_with_selector(selector: [u8; 4], rust: Self::RustType) -> crate::Bytes {
+        let mut out = selector.to_vec();
+        Self::encode_params_to(rust, &mut out);
+        out.into()
+    }
+
+    /// The length of [`SolType::encode`]'s output, computed without
+    /// encoding
+    fn encoded_size(rust: &Self::RustType) -> usize {
+        Self::tokenize_ref(rust).encoded_raw_size()
+    }
+
+    /// The length of [`SolType::encode_params`]'s output, computed without
+    /// encoding
+    fn encoded_params_size(rust: &Self::RustType) -> usize {
+        Self::tokenize_ref(rust).encoded_size()
+    }
+
+    /// Encode a borrowed Rust type to an ABI blob
+    fn encode_ref(rust: &Self::RustType) -> crate::Bytes {
+        let token = Self::tokenize_ref(rust);
+        crate::encode_single(&token)
+    }
+
+    /// Encode a borrowed Rust type
+    fn encode_params_ref(rust: &Self::RustType) -> crate::Bytes {
+        let token = Self::tokenize_ref(rust);
         crate::encode(&token)
     }
 
+    /// Encode a Rust type as the topic of an indexed event parameter. See
+    /// [`crate::encode_topic`]
+    fn encode_topic(rust: Self::RustType) -> Word {
+        crate::encode_topic(&Self::tokenize(rust))
+    }
+
     /// Encode a Rust type to an ABI blob, then hex encode the blob
     fn hex_encode(rust: Self::RustType) -> RustString {
         format!("0x{}", hex::encode(Self::encode(rust)))
@@ -47,6 +118,43 @@ pub trait SolType {
         Self::detokenize(&Self::read_token(&mut Decoder::new(data, false, false))?)
     }
 
+    /// Decode a Rust type from an ABI blob, rejecting trailing bytes and
+    /// non-canonical encodings. See [`decode_validate`]
+    fn decode_validate(data: &[u8]) -> crate::Result<Self::RustType>
+    where
+        Self: Sized,
+    {
+        Self::detokenize(&decode_impl::<Self>(data, true, DecoderConfig::default())?)
+    }
+
+    /// Decode a Rust type from top-level params, such as the return data
+    /// of a call or the data of a log. Trailing bytes are ignored
+    fn decode_params(data: &[u8]) -> crate::Result<Self::RustType>
+    where
+        Self: Sized,
+    {
+        Self::detokenize(&decode_params_impl::<Self>(
+            data,
+            false,
+            DecoderConfig::default(),
+        )?)
+    }
+
+    /// Decode a Rust type from top-level params, rejecting trailing bytes
+    /// and non-canonical encodings. Use for function return data, which
+    /// should be exactly the encoding of the return type. See
+    /// [`decode_params_validate`]
+    fn decode_params_validate(data: &[u8]) -> crate::Result<Self::RustType>
+    where
+        Self: Sized,
+    {
+        Self::detokenize(&decode_params_impl::<Self>(
+            data,
+            true,
+            DecoderConfig::default(),
+        )?)
+    }
+
     /// Decode a Rust type from a hex-encoded ABI blob
     fn hex_decode(data: &str) -> crate::Result<Self::RustType> {
         let payload = data.strip_prefix("0x").unwrap_or(data);
@@ -60,7 +168,7 @@ pub trait SolType {
 pub struct Address;
 
 impl SolType for Address {
-    type RustType = B160;
+    type RustType = RustAddress;
 
     fn is_dynamic() -> bool {
         false
@@ -78,17 +186,21 @@ impl SolType for Address {
         token
             .as_word_array()
             .map(|arr| &arr[12..])
-            .map(B160::from_slice)
+            .map(RustAddress::from_slice)
             .ok_or(InvalidData)
     }
 
-    fn tokenize(rust: Self::RustType) -> Token {
+    fn tokenize(rust: Self::RustType) -> Token<'static> {
         let mut word = Word::default();
-        word[12..].copy_from_slice(&rust[..]);
+        word[12..].copy_from_slice(rust.as_bytes());
         Token::Word(word)
     }
 
-    fn read_token(decoder: &mut Decoder<'_>) -> crate::Result<Token> {
+    fn tokenize_ref(rust: &Self::RustType) -> Token<'_> {
+        Self::tokenize(*rust)
+    }
+
+    fn read_token<'a>(decoder: &mut Decoder<'a>) -> crate::Result<Token<'a>> {
         let slice = decoder.take_word()?;
         let token = Token::Word(slice);
         if decoder.validate() && !Self::type_check(&token) {
@@ -123,190 +235,196 @@ impl SolType for Bytes {
             .ok_or(InvalidData)
     }
 
-    fn tokenize(rust: Self::RustType) -> Token {
-        Token::PackedSeq(rust)
+    fn tokenize(rust: Self::RustType) -> Token<'static> {
+        Token::PackedSeq(Cow::Owned(rust))
     }
 
-    fn read_token(decoder: &mut Decoder<'_>) -> crate::Result<Token> {
+    fn tokenize_ref(rust: &Self::RustType) -> Token<'_> {
+        Token::PackedSeq(Cow::Borrowed(rust))
+    }
+
+    fn read_token<'a>(decoder: &mut Decoder<'a>) -> crate::Result<Token<'a>> {
         let mut child = decoder.take_indirection()?;
         let len = child.take_usize()?;
+        child.claim_bytes(len)?;
         let bytes = child.peek_len(len)?;
-        Ok(Token::PackedSeq(bytes.to_vec()))
+        Ok(Token::PackedSeq(Cow::Borrowed(bytes)))
     }
 }
 
-macro_rules! impl_int_sol_type {
-    ($ity:ty, $bits:literal) => {
-        impl SolType for Int<$bits> {
-            type RustType = $ity;
-
-            fn is_dynamic() -> bool {
-                false
-            }
-
-            fn sol_type_name() -> RustString {
-                format!("int{}", $bits)
-            }
-
-            fn type_check(token: &Token) -> bool {
-                matches!(token, Token::Word(_))
-            }
-
-            fn detokenize(token: &Token) -> crate::Result<Self::RustType> {
-                let bytes = (<$ity>::BITS / 8) as usize;
-                token
-                    .as_word_array()
-                    .map(|arr| &arr[32 - bytes..])
-                    .map(|sli| <$ity>::from_be_bytes(sli.try_into().unwrap()))
-                    .ok_or(InvalidData)
-            }
+/// A Rust integer type holding a Solidity integer, left-padded to a word
+pub trait WordInt: Copy {
+    /// Pad the integer to a word, sign-extending it if negative
+    fn to_word(self) -> Word;
+    /// Read the integer from the low-order bytes of a word
+    fn from_word(word: &Word) -> Self;
+}
 
-            fn tokenize(rust: Self::RustType) -> Token {
-                let bytes = (<$ity>::BITS / 8) as usize;
-                let mut word = if rust < 0 {
+macro_rules! impl_word_int {
+    ($($ty:ty),+) => {$(
+        impl WordInt for $ty {
+            #[allow(unused_comparisons)]
+            fn to_word(self) -> Word {
+                let bytes = (<$ty>::BITS / 8) as usize;
+                let mut word = if self < 0 {
                     // account for negative
                     Word::repeat_byte(0xff)
                 } else {
                     Word::default()
                 };
-                let slice = rust.to_be_bytes();
-                word[32 - bytes..].copy_from_slice(&slice);
-                Token::Word(word)
+                word[32 - bytes..].copy_from_slice(&self.to_be_bytes());
+                word
             }
 
-            fn read_token(decoder: &mut Decoder<'_>) -> crate::Result<Token> {
-                let slice = decoder.take_word()?;
-                let token = Token::Word(slice);
-                if decoder.validate() && !Self::type_check(&token) {
-                    return Err(InvalidData);
-                }
-                Ok(token)
+            fn from_word(word: &Word) -> Self {
+                let bytes = (<$ty>::BITS / 8) as usize;
+                <$ty>::from_be_bytes(word[32 - bytes..].try_into().unwrap())
             }
         }
-    };
+    )+};
 }
 
-/// Int - `intX`
-pub struct Int<const BITS: usize>;
-impl_int_sol_type!(i8, 8);
-impl_int_sol_type!(i16, 16);
-impl_int_sol_type!(i32, 24);
-impl_int_sol_type!(i32, 32);
-impl_int_sol_type!(i64, 40);
-impl_int_sol_type!(i64, 48);
-impl_int_sol_type!(i64, 56);
-impl_int_sol_type!(i64, 64);
-// TODO: larger
-
-macro_rules! impl_uint_sol_type {
-    ($uty:ty, $bits:literal) => {
-        impl SolType for Uint<$bits> {
-            type RustType = $uty;
+impl_word_int!(i8, i16, i32, i64, u8, u16, u32, u64);
 
-            fn is_dynamic() -> bool {
-                false
-            }
+impl WordInt for U256 {
+    fn to_word(self) -> Word {
+        B256(self.to_be_bytes::<32>())
+    }
 
-            fn sol_type_name() -> RustString {
-                format!("uint{}", $bits)
-            }
+    fn from_word(word: &Word) -> Self {
+        U256::from_be_bytes::<32>(word.0)
+    }
+}
 
-            fn type_check(token: &Token) -> bool {
-                matches!(token, Token::Word(_))
-            }
+/// A Solidity integer size. Bound on `BitCount<N>: SupportedInt` or
+/// `BitCount<N>: SupportedUint` to use [`Int<N>`] or [`Uint<N>`]
+/// generically
+pub struct BitCount<const BITS: usize>;
 
-            fn detokenize(token: &Token) -> crate::Result<Self::RustType> {
-                let bytes = (<$uty>::BITS / 8) as usize;
-                token
-                    .as_word_array()
-                    .map(|arr| &arr[32 - bytes..])
-                    .map(|sli| <$uty>::from_be_bytes(sli.try_into().unwrap()))
-                    .ok_or(InvalidData)
-            }
+/// A size with a Rust type for `intN`
+pub trait SupportedInt {
+    /// The Rust type
+    type Int: WordInt;
+}
 
-            fn tokenize(rust: Self::RustType) -> Token {
-                let bytes = (<$uty>::BITS / 8) as usize;
-                let mut word = Word::default();
-                let slice = rust.to_be_bytes();
-                word[32 - bytes..].copy_from_slice(&slice);
-                Token::Word(word)
-            }
+/// A size with a Rust type for `uintN`
+pub trait SupportedUint {
+    /// The Rust type
+    type Uint: WordInt;
+}
 
-            fn read_token(
-                decoder: &mut Decoder<'_>,
-            ) -> crate::Result<Token> {
-                let slice = decoder.take_word()?;
-                let token = Token::Word(slice);
-                if decoder.validate() && !Self::type_check(&token) {
-                    return Err(InvalidData);
-                }
-                Ok(token)
-            }
+macro_rules! impl_supported {
+    ($trait:ident::$assoc:ident, $($ty:ty => $($bits:literal),+;)+) => {$($(
+        impl $trait for BitCount<$bits> {
+            type $assoc = $ty;
         }
-    };
+    )+)+};
+}
+
+impl_supported!(
+    SupportedInt::Int,
+    i8 => 8;
+    i16 => 16;
+    i32 => 24, 32;
+    i64 => 40, 48, 56, 64;
+);
+// TODO: larger
 
-    ($bits:literal) => {
-        impl SolType for Uint<$bits> {
-            type RustType = U256;
+impl_supported!(
+    SupportedUint::Uint,
+    u8 => 8;
+    u16 => 16;
+    u32 => 24, 32;
+    u64 => 40, 48, 56, 64;
+    U256 => 72, 80, 88, 96, 104, 112, 120, 128, 136, 144, 152, 160, 168, 176, 184, 192, 200, 208,
+        216, 224, 232, 240, 248, 256;
+);
 
-            fn is_dynamic() -> bool {
-                false
-            }
+/// Int - `intX`
+pub struct Int<const BITS: usize>;
 
-            fn sol_type_name() -> RustString {
-                format!("uint{}", $bits)
-            }
+impl<const BITS: usize> SolType for Int<BITS>
+where
+    BitCount<BITS>: SupportedInt,
+{
+    type RustType = <BitCount<BITS> as SupportedInt>::Int;
 
-            fn type_check(token: &Token) -> bool {
-                matches!(token, Token::Word(_))
-            }
+    fn is_dynamic() -> bool {
+        false
+    }
 
-            fn detokenize(token: &Token) -> crate::Result<Self::RustType> {
-                token
-                    .as_word_array()
-                    .map(|word| U256::from_be_bytes::<32>(*word))
-                    .ok_or(InvalidData)
-            }
+    fn sol_type_name() -> RustString {
+        format!("int{BITS}")
+    }
 
-            fn tokenize(rust: Self::RustType) -> Token {
-                Token::Word(B256(rust.to_be_bytes::<32>()))
-            }
+    fn type_check(token: &Token) -> bool {
+        matches!(token, Token::Word(_))
+    }
 
-            fn read_token(
-                decoder: &mut Decoder<'_>,
-            ) -> crate::Result<Token> {
-                let slice = decoder.take_word()?;
-                let token = Token::Word(slice);
-                if decoder.validate() && !Self::type_check(&token) {
-                    return Err(InvalidData);
-                }
-                Ok(token)
-            }
-        }
-    };
+    fn detokenize(token: &Token) -> crate::Result<Self::RustType> {
+        token.as_word().map(WordInt::from_word).ok_or(InvalidData)
+    }
+
+    fn tokenize(rust: Self::RustType) -> Token<'static> {
+        Token::Word(rust.to_word())
+    }
+
+    fn tokenize_ref(rust: &Self::RustType) -> Token<'_> {
+        Self::tokenize(*rust)
+    }
 
-    ($($bits:literal,)+) => {
-        $(
-            impl_uint_sol_type!($bits);
-        )+
+    fn read_token<'a>(decoder: &mut Decoder<'a>) -> crate::Result<Token<'a>> {
+        let slice = decoder.take_word()?;
+        let token = Token::Word(slice);
+        if decoder.validate() && !Self::type_check(&token) {
+            return Err(InvalidData);
+        }
+        Ok(token)
     }
 }
 
 /// Uint - `uintX`
 pub struct Uint<const BITS: usize>;
 
-impl_uint_sol_type!(u8, 8);
-impl_uint_sol_type!(u16, 16);
-impl_uint_sol_type!(u32, 24);
-impl_uint_sol_type!(u32, 32);
-impl_uint_sol_type!(u64, 40);
-impl_uint_sol_type!(u64, 48);
-impl_uint_sol_type!(u64, 56);
-impl_uint_sol_type!(u64, 64);
-impl_uint_sol_type!(
-    72, 80, 88, 96, 104, 112, 120, 128, 136, 144, 152, 160, 168, 176, 184, 192, 200, 208, 216, 224,
-    232, 240, 248, 256,
-);
+impl<const BITS: usize> SolType for Uint<BITS>
+where
+    BitCount<BITS>: SupportedUint,
+{
+    type RustType = <BitCount<BITS> as SupportedUint>::Uint;
+
+    fn is_dynamic() -> bool {
+        false
+    }
+
+    fn sol_type_name() -> RustString {
+        format!("uint{BITS}")
+    }
+
+    fn type_check(token: &Token) -> bool {
+        matches!(token, Token::Word(_))
+    }
+
+    fn detokenize(token: &Token) -> crate::Result<Self::RustType> {
+        token.as_word().map(WordInt::from_word).ok_or(InvalidData)
+    }
+
+    fn tokenize(rust: Self::RustType) -> Token<'static> {
+        Token::Word(rust.to_word())
+    }
+
+    fn tokenize_ref(rust: &Self::RustType) -> Token<'_> {
+        Self::tokenize(*rust)
+    }
+
+    fn read_token<'a>(decoder: &mut Decoder<'a>) -> crate::Result<Token<'a>> {
+        let slice = decoder.take_word()?;
+        let token = Token::Word(slice);
+        if decoder.validate() && !Self::type_check(&token) {
+            return Err(InvalidData);
+        }
+        Ok(token)
+    }
+}
 
 /// Bool - `bool`
 pub struct Bool;
@@ -330,18 +448,22 @@ impl SolType for Bool {
 
     fn detokenize(token: &Token) -> crate::Result<Self::RustType> {
         match token {
-            Token::Word(word) => Ok(word[31] < 2),
+            Token::Word(word) => Ok(!word.is_zero()),
             _ => Err(InvalidData),
         }
     }
 
-    fn tokenize(rust: Self::RustType) -> Token {
+    fn tokenize(rust: Self::RustType) -> Token<'static> {
         let mut word = Word::default();
         word[31..32].copy_from_slice(&[rust as u8]);
         Token::Word(word)
     }
 
-    fn read_token(decoder: &mut Decoder<'_>) -> crate::Result<Token> {
+    fn tokenize_ref(rust: &Self::RustType) -> Token<'_> {
+        Self::tokenize(*rust)
+    }
+
+    fn read_token<'a>(decoder: &mut Decoder<'a>) -> crate::Result<Token<'a>> {
         let slice = decoder.take_word()?;
         let token = Token::Word(slice);
         if decoder.validate() && !Self::type_check(&token) {
@@ -354,6 +476,26 @@ impl SolType for Bool {
 /// Array - `T[]`
 pub struct Array<T: SolType>(PhantomData<T>);
 
+impl<T> Array<T>
+where
+    T: SolType,
+{
+    /// Tokenize borrowed elements, e.g. from a slice or an iterator over
+    /// a larger collection
+    pub fn tokenize_iter<'a, I>(iter: I) -> Token<'a>
+    where
+        I: IntoIterator<Item = &'a T::RustType>,
+        T::RustType: 'a,
+    {
+        Token::DynSeq(iter.into_iter().map(T::tokenize_ref).collect())
+    }
+
+    /// Encode a slice to an ABI blob, without collecting it into a `Vec`
+    pub fn encode_slice(rust: &[T::RustType]) -> crate::Bytes {
+        crate::encode_single(&Self::tokenize_iter(rust))
+    }
+}
+
 impl<T> SolType for Array<T>
 where
     T: SolType,
@@ -380,13 +522,21 @@ where
         }
     }
 
-    fn tokenize(rust: Self::RustType) -> Token {
+    fn tokenize(rust: Self::RustType) -> Token<'static> {
         Token::DynSeq(rust.into_iter().map(|r| T::tokenize(r)).collect())
     }
 
-    fn read_token(decoder: &mut Decoder<'_>) -> crate::Result<Token> {
+    fn tokenize_ref(rust: &Self::RustType) -> Token<'_> {
+        Self::tokenize_iter(rust)
+    }
+
+    fn read_token<'a>(decoder: &mut Decoder<'a>) -> crate::Result<Token<'a>> {
         let mut child = decoder.take_indirection()?;
         let len = child.take_usize()?;
+        child.claim_elements(len)?;
+        // offsets of dynamic elements are relative to the start of the
+        // elements, after the length
+        let mut child = child.raw_child()?;
 
         let mut tokens = vec![];
 
@@ -424,72 +574,73 @@ impl SolType for String {
         RustString::from_utf8(Bytes::detokenize(token)?).map_err(|_| InvalidData)
     }
 
-    fn tokenize(rust: Self::RustType) -> Token {
-        Token::PackedSeq(rust.into_bytes())
+    fn tokenize(rust: Self::RustType) -> Token<'static> {
+        Token::PackedSeq(Cow::Owned(rust.into_bytes()))
+    }
+
+    fn tokenize_ref(rust: &Self::RustType) -> Token<'_> {
+        Token::PackedSeq(Cow::Borrowed(rust.as_bytes()))
     }
 
-    fn read_token(decoder: &mut Decoder<'_>) -> crate::Result<Token> {
+    fn read_token<'a>(decoder: &mut Decoder<'a>) -> crate::Result<Token<'a>> {
         let mut child = decoder.take_indirection()?;
         let len = child.take_usize()?;
+        child.claim_bytes(len)?;
         let bytes = child.peek_len(len)?;
-        Ok(Token::PackedSeq(bytes.to_vec()))
+        Ok(Token::PackedSeq(Cow::Borrowed(bytes)))
     }
 }
 
-macro_rules! impl_fixed_bytes_sol_type {
-    ($bytes:literal) => {
-        impl SolType for FixedBytes<$bytes> {
+/// FixedBytes - `bytesX`
+pub struct FixedBytes<const N: usize>;
 
-            type RustType = [u8; $bytes];
+impl<const N: usize> FixedBytes<N> {
+    const VALID_SIZE: () = assert!(N > 0 && N <= 32, "bytesN must have 1 to 32 bytes");
+}
 
-            fn is_dynamic() -> bool {
-                false
-            }
+impl<const N: usize> SolType for FixedBytes<N> {
+    type RustType = [u8; N];
 
-            fn sol_type_name() -> RustString {
-                format!("bytes{}", $bytes)
-            }
+    fn is_dynamic() -> bool {
+        false
+    }
 
-            fn type_check(token: &Token) -> bool {
-                matches!(token, Token::Word(_))
-            }
+    fn sol_type_name() -> RustString {
+        format!("bytes{N}")
+    }
 
-            fn detokenize(token: &Token) -> crate::Result<Self::RustType> {
-                let word = token
-                    .as_word_array()
-                    .ok_or(InvalidData)?;
-                let mut res = Self::RustType::default();
-                res[..$bytes].copy_from_slice(&word[..$bytes]);
-                Ok(res)
-            }
+    fn type_check(token: &Token) -> bool {
+        matches!(token, Token::Word(_))
+    }
 
-            fn tokenize(rust: Self::RustType) -> Token {
-                let mut word = Word::default();
-                word[..$bytes].copy_from_slice(&rust[..]);
-                Token::Word(word)
-            }
+    fn detokenize(token: &Token) -> crate::Result<Self::RustType> {
+        let () = Self::VALID_SIZE;
+        let word = token.as_word_array().ok_or(InvalidData)?;
+        let mut res = [0u8; N];
+        res.copy_from_slice(&word[..N]);
+        Ok(res)
+    }
 
-            fn read_token(decoder: &mut Decoder<'_>) -> crate::Result<Token> {
-                let word = decoder.take_word()?;
-                if decoder.validate() {
-                    check_fixed_bytes(word, $bytes)?;
-                }
-                Ok(Token::Word(word))
-            }
-        }
-    };
+    fn tokenize(rust: Self::RustType) -> Token<'static> {
+        let () = Self::VALID_SIZE;
+        let mut word = Word::default();
+        word[..N].copy_from_slice(&rust[..]);
+        Token::Word(word)
+    }
 
-    ($($bytes:literal,)+) => {
-        $(impl_fixed_bytes_sol_type!($bytes);)+
-    };
-}
+    fn tokenize_ref(rust: &Self::RustType) -> Token<'_> {
+        Self::tokenize(*rust)
+    }
 
-/// FixedBytes - `bytesX`
-pub struct FixedBytes<const N: usize>;
-impl_fixed_bytes_sol_type!(
-    1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26,
-    27, 28, 29, 30, 31, 32,
-);
+    fn read_token<'a>(decoder: &mut Decoder<'a>) -> crate::Result<Token<'a>> {
+        let () = Self::VALID_SIZE;
+        let word = decoder.take_word()?;
+        if decoder.validate() {
+            check_fixed_bytes(word, N)?;
+        }
+        Ok(Token::Word(word))
+    }
+}
 
 /// FixedArray - `T[M]`
 pub struct FixedArray<T, const N: usize>(PhantomData<T>);
@@ -504,6 +655,18 @@ where
         T::is_dynamic()
     }
 
+    fn head_words() -> usize {
+        if Self::is_dynamic() {
+            1
+        } else {
+            N * T::head_words()
+        }
+    }
+
+    fn param_layout() -> Vec<(usize, bool)> {
+        vec![(T::head_words(), T::is_dynamic()); N]
+    }
+
     fn sol_type_name() -> RustString {
         format!("{}[{}]", T::sol_type_name(), N)
     }
@@ -528,17 +691,21 @@ where
             .map_err(|_| InvalidData)
     }
 
-    fn tokenize(rust: Self::RustType) -> Token {
+    fn tokenize(rust: Self::RustType) -> Token<'static> {
         Token::FixedSeq(rust.into_iter().map(|r| T::tokenize(r)).collect())
     }
 
-    fn read_token(decoder: &mut Decoder<'_>) -> crate::Result<Token> {
+    fn tokenize_ref(rust: &Self::RustType) -> Token<'_> {
+        Token::FixedSeq(rust.iter().map(T::tokenize_ref).collect())
+    }
+
+    fn read_token<'a>(decoder: &mut Decoder<'a>) -> crate::Result<Token<'a>> {
         let is_dynamic = Self::is_dynamic();
 
         let mut child = if is_dynamic {
             decoder.take_indirection()?
         } else {
-            decoder.raw_child()
+            decoder.raw_child()?
         };
 
         let mut tokens = Vec::with_capacity(N);
@@ -547,6 +714,11 @@ where
             let token = T::read_token(&mut child)?;
             tokens.push(token);
         }
+        // a static array is inline, so the next value follows its last
+        // element
+        if !is_dynamic {
+            decoder.take_offset(child);
+        }
 
         Ok(Token::FixedSeq(tokens))
     }
@@ -571,6 +743,17 @@ macro_rules! impl_tuple_sol_type {
                 false
             }
 
+            fn head_words() -> usize {
+                if Self::is_dynamic() {
+                    return 1;
+                }
+                0 $( + $ty::head_words() )+
+            }
+
+            fn param_layout() -> Vec<(usize, bool)> {
+                vec![$( ($ty::head_words(), $ty::is_dynamic()), )+]
+            }
+
             fn sol_type_name() -> RustString {
                 let mut types = Vec::with_capacity($num);
                 $(
@@ -610,7 +793,7 @@ macro_rules! impl_tuple_sol_type {
                 ))
             }
 
-            fn tokenize(rust: Self::RustType) -> Token {
+            fn tokenize(rust: Self::RustType) -> Token<'static> {
                 let tokens = vec![
                     $(
                         $ty::tokenize(rust.$no),
@@ -619,7 +802,16 @@ macro_rules! impl_tuple_sol_type {
                 Token::FixedSeq(tokens)
             }
 
-            fn read_token(decoder: &mut Decoder<'_>) -> crate::Result<Token> {
+            fn tokenize_ref(rust: &Self::RustType) -> Token<'_> {
+                let tokens = vec![
+                    $(
+                        $ty::tokenize_ref(&rust.$no),
+                    )+
+                ];
+                Token::FixedSeq(tokens)
+            }
+
+            fn read_token<'a>(decoder: &mut Decoder<'a>) -> crate::Result<Token<'a>> {
 
                 let is_dynamic = Self::is_dynamic() && !decoder.is_params();
                 // The first element in a dynamic Tuple is an offset to the Tuple's data
@@ -627,7 +819,7 @@ macro_rules! impl_tuple_sol_type {
                 let mut child = if is_dynamic {
                     decoder.take_indirection()?
                 } else {
-                    decoder.raw_child()
+                    decoder.raw_child()?
                 };
 
                 let mut tokens = Vec::with_capacity($num);
@@ -669,11 +861,113 @@ impl_tuple_sol_type!(19, A:0, B:1, C:2, D:3, E:4, F:5, G:6, H:7, I:8, J:9, K:10,
 impl_tuple_sol_type!(20, A:0, B:1, C:2, D:3, E:4, F:5, G:6, H:7, I:8, J:9, K:10, L:11, M:12, N:13, O:14, P:15, Q:16, R:17, S:18, T:19,);
 impl_tuple_sol_type!(21, A:0, B:1, C:2, D:3, E:4, F:5, G:6, H:7, I:8, J:9, K:10, L:11, M:12, N:13, O:14, P:15, Q:16, R:17, S:18, T:19, U:20,);
 
+/// The empty tuple, for functions that take no arguments or return
+/// nothing. Encodes to, and decodes from, empty data
+impl SolType for () {
+    type RustType = ();
+
+    fn is_dynamic() -> bool {
+        false
+    }
+
+    fn head_words() -> usize {
+        0
+    }
+
+    fn param_layout() -> Vec<(usize, bool)> {
+        vec![]
+    }
+
+    fn sol_type_name() -> RustString {
+        "tuple()".to_string()
+    }
+
+    fn type_check(token: &Token) -> bool {
+        matches!(token, Token::FixedSeq(tokens) if tokens.is_empty())
+    }
+
+    fn detokenize(token: &Token) -> crate::Result<Self::RustType> {
+        Self::type_check(token).then_some(()).ok_or(InvalidData)
+    }
+
+    fn tokenize(_rust: Self::RustType) -> Token<'static> {
+        Token::FixedSeq(vec![])
+    }
+
+    fn tokenize_ref(_rust: &Self::RustType) -> Token<'_> {
+        Token::FixedSeq(vec![])
+    }
+
+    fn read_token<'a>(_decoder: &mut Decoder<'a>) -> crate::Result<Token<'a>> {
+        Ok(Token::FixedSeq(vec![]))
+    }
+}
+
+/// An optional value, encoded as the tuple `(bool, T)`. `None` encodes the
+/// flag `false` and the default value of `T`, and any value with a `false`
+/// flag decodes to `None`.
+///
+/// Solidity has no optional type. This is the convention of functions like
+/// `tryGet(key) returns (bool found, T value)`
+pub struct Optional<T>(PhantomData<T>);
+
+impl<T> SolType for Optional<T>
+where
+    T: SolType,
+    T::RustType: Default,
+{
+    type RustType = Option<T::RustType>;
+
+    fn is_dynamic() -> bool {
+        <(Bool, T)>::is_dynamic()
+    }
+
+    fn head_words() -> usize {
+        <(Bool, T)>::head_words()
+    }
+
+    fn param_layout() -> Vec<(usize, bool)> {
+        <(Bool, T)>::param_layout()
+    }
+
+    fn sol_type_name() -> RustString {
+        <(Bool, T)>::sol_type_name()
+    }
+
+    fn type_check(token: &Token) -> bool {
+        <(Bool, T)>::type_check(token)
+    }
+
+    fn detokenize(token: &Token) -> crate::Result<Self::RustType> {
+        let (some, value) = <(Bool, T)>::detokenize(token)?;
+        Ok(some.then_some(value))
+    }
+
+    fn tokenize(rust: Self::RustType) -> Token<'static> {
+        <(Bool, T)>::tokenize(match rust {
+            Some(value) => (true, value),
+            None => (false, Default::default()),
+        })
+    }
+
+    fn tokenize_ref(rust: &Self::RustType) -> Token<'_> {
+        let value = match rust {
+            Some(value) => T::tokenize_ref(value),
+            None => T::tokenize(Default::default()),
+        };
+        Token::FixedSeq(vec![Bool::tokenize(rust.is_some()), value])
+    }
+
+    fn read_token<'a>(decoder: &mut Decoder<'a>) -> crate::Result<Token<'a>> {
+        <(Bool, T)>::read_token(decoder)
+    }
+}
+
 /// Function - `function`
 pub struct Function;
 
 impl SolType for Function {
-    type RustType = (B160, [u8; 4]);
+    type RustType = (RustAddress, [u8; 4]);
 
     fn sol_type_name() -> RustString {
         "function".to_string()
@@ -700,17 +994,21 @@ impl SolType for Function {
         let mut selector = [0u8; 4];
         address.copy_from_slice(&t[..20]);
         selector.copy_from_slice(&t[20..24]);
-        Ok((B160(address), selector))
+        Ok((RustAddress::new(address), selector))
     }
 
-    fn tokenize(rust: Self::RustType) -> Token {
+    fn tokenize(rust: Self::RustType) -> Token<'static> {
         let mut word = Word::default();
-        word[..20].copy_from_slice(&rust.0[..]);
+        word[..20].copy_from_slice(rust.0.as_bytes());
         word[20..24].copy_from_slice(&rust.1[..]);
         Token::Word(word)
     }
 
-    fn read_token(decoder: &mut Decoder<'_>) -> crate::Result<Token> {
+    fn tokenize_ref(rust: &Self::RustType) -> Token<'_> {
+        Self::tokenize(*rust)
+    }
+
+    fn read_token<'a>(decoder: &mut Decoder<'a>) -> crate::Result<Token<'a>> {
         let word = decoder.take_word()?;
         if decoder.validate() {
             check_fixed_bytes(word, 24)?;
@@ -8,6 +8,7 @@
 
 //! Ethereum ABI params.
 
+use alloc::borrow::Cow;
 use core::fmt;
 
 #[cfg(feature = "serde")]
@@ -18,19 +19,22 @@ use crate::no_std_prelude::*;
 use crate::Word;
 
 /// Ethereum ABI params.
+///
+/// Packed sequences may borrow from the buffer they were decoded from. See
+/// [`crate::decode_borrowed`]
 #[derive(PartialEq, Clone)]
-pub enum Token {
+pub enum Token<'a> {
     /// Single Word
     Word(Word),
     /// Tuple or `T[M]`
-    FixedSeq(Vec<Token>),
+    FixedSeq(Vec<Token<'a>>),
     /// T[]
-    DynSeq(Vec<Token>),
+    DynSeq(Vec<Token<'a>>),
     /// String or Bytes
-    PackedSeq(Vec<u8>),
+    PackedSeq(Cow<'a, [u8]>),
 }
 
-impl fmt::Debug for Token {
+impl fmt::Debug for Token<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Self::Word(arg0) => f.debug_tuple("Word").field(arg0).finish(),
@@ -44,7 +48,7 @@ impl fmt::Debug for Token {
     }
 }
 
-impl fmt::Display for Token {
+impl fmt::Display for Token<'_> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             Token::Word(contents) => write!(f, "Word {contents}"),
@@ -55,7 +59,21 @@ impl fmt::Display for Token {
     }
 }
 
-impl Token {
+impl<'a> Token<'a> {
+    /// Copy any borrowed data, detaching the token from its buffer
+    pub fn into_owned(self) -> Token<'static> {
+        match self {
+            Token::Word(word) => Token::Word(word),
+            Token::FixedSeq(tokens) => {
+                Token::FixedSeq(tokens.into_iter().map(Token::into_owned).collect())
+            }
+            Token::DynSeq(tokens) => {
+                Token::DynSeq(tokens.into_iter().map(Token::into_owned).collect())
+            }
+            Token::PackedSeq(buf) => Token::PackedSeq(Cow::Owned(buf.into_owned())),
+        }
+    }
+
     /// Return a reference to the underlying word for a value type
     pub fn as_word(&self) -> Option<&Word> {
         match self {
@@ -79,7 +97,7 @@ impl Token {
     }
 
     /// Return a reference to the underlying vector for a dynamic sequence
-    pub fn as_dyn_seq(&self) -> Option<&[Token]> {
+    pub fn as_dyn_seq(&self) -> Option<&[Token<'a>]> {
         match self {
             Token::DynSeq(buf) => Some(buf.as_ref()),
             _ => None,
@@ -87,7 +105,7 @@ impl Token {
     }
 
     /// Return a reference to the underlying vector for a dynamic sequence
-    pub fn as_fixed_seq(&self) -> Option<&[Token]> {
+    pub fn as_fixed_seq(&self) -> Option<&[Token<'a>]> {
         match self {
             Token::FixedSeq(buf) => Some(buf.as_ref()),
             _ => None,
@@ -102,6 +120,46 @@ impl Token {
             _ => false,
         }
     }
+
+    /// The length of the token's encoding by [`crate::encode`], computed
+    /// without encoding it
+    pub fn encoded_size(&self) -> usize {
+        match self {
+            Token::FixedSeq(tokens) => tokens.iter().map(Token::encoded_raw_size).sum(),
+            _ => self.encoded_raw_size(),
+        }
+    }
+
+    /// The length of the token's encoding by [`crate::encode_raw`], computed
+    /// without encoding it
+    pub fn encoded_raw_size(&self) -> usize {
+        self.head_size() + self.tail_size()
+    }
+
+    /// The size of the token in the head of an enclosing sequence
+    fn head_size(&self) -> usize {
+        match self {
+            Token::FixedSeq(tokens) if !self.is_dynamic() => {
+                tokens.iter().map(Token::head_size).sum()
+            }
+            // a word, or the offset of a dynamic token
+            _ => 32,
+        }
+    }
+
+    /// The size of the token in the tail of an enclosing sequence
+    fn tail_size(&self) -> usize {
+        match self {
+            Token::Word(_) => 0,
+            Token::FixedSeq(tokens) if self.is_dynamic() => {
+                tokens.iter().map(Token::encoded_raw_size).sum()
+            }
+            Token::FixedSeq(_) => 0,
+            // prefixed by their length
+            Token::DynSeq(tokens) => 32 + tokens.iter().map(Token::encoded_raw_size).sum::<usize>(),
+            Token::PackedSeq(buf) => 32 + buf.len().div_ceil(32) * 32,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -216,16 +274,58 @@ mod tests {
     #[test]
     fn test_is_dynamic() {
         assert!(!Token::Word(B256::default()).is_dynamic());
-        assert!(Token::PackedSeq(vec![0, 0, 0, 0]).is_dynamic());
+        assert!(Token::PackedSeq(vec![0, 0, 0, 0].into()).is_dynamic());
         assert!(!Token::Word(B256::default()).is_dynamic());
         assert!(!Token::Word(B256::default()).is_dynamic());
         assert!(!Token::Word(B256::default()).is_dynamic());
-        assert!(Token::PackedSeq("".into()).is_dynamic());
+        assert!(Token::PackedSeq("".as_bytes().into()).is_dynamic());
         assert!(Token::DynSeq(vec![Token::Word(B256::default())]).is_dynamic());
         assert!(!Token::FixedSeq(vec![Token::Word(B256::default())]).is_dynamic());
-        assert!(Token::FixedSeq(vec![Token::PackedSeq("".into())]).is_dynamic());
+        assert!(Token::FixedSeq(vec![Token::PackedSeq("".as_bytes().into())]).is_dynamic());
         assert!(
             Token::FixedSeq(vec![Token::DynSeq(vec![Token::Word(B256::default())])]).is_dynamic()
         );
     }
+
+    #[test]
+    fn test_encoded_size() {
+        macro_rules! assert_sizes {
+            ($sol:ty, $value:expr) => {{
+                let value = $value;
+                assert_eq!(
+                    <$sol>::encoded_size(&value),
+                    <$sol>::encode(value.clone()).len()
+                );
+                assert_eq!(
+                    <$sol>::encoded_params_size(&value),
+                    <$sol>::encode_params(value).len()
+                );
+            }};
+        }
+
+        assert_sizes!(sol_type::Uint<256>, Default::default());
+        assert_sizes!(sol_type::Bytes, vec![]);
+        assert_sizes!(sol_type::Bytes, vec![1; 33]);
+        assert_sizes!(sol_type::Array<sol_type::String>, vec!["a".repeat(64); 3]);
+        assert_sizes!(
+            sol_type::FixedArray<sol_type::Bool, 3>,
+            [true, false, true]
+        );
+        assert_sizes!(
+            (sol_type::Address, (sol_type::Bool, sol_type::FixedBytes<4>)),
+            Default::default()
+        );
+        assert_sizes!(
+            (
+                sol_type::Uint<8>,
+                sol_type::FixedArray<sol_type::String, 2>,
+                sol_type::Array<(sol_type::Bool, sol_type::Bytes)>,
+            ),
+            (
+                1,
+                ["x".to_owned(), "y".repeat(40)],
+                vec![(true, vec![]), (false, vec![2; 65])]
+            )
+        );
+    }
 }
@@ -9,7 +9,8 @@
 
 //! Utils used by different modules.
 
-use crate::Word;
+use crate::{Hash, Word};
+use ethers_primitives::B256;
 
 /// Converts a u32 to a right aligned array of 32 bytes.
 pub fn pad_u32(value: u32) -> Word {
@@ -18,10 +19,178 @@ pub fn pad_u32(value: u32) -> Word {
     padded
 }
 
+const KECCAK_ROUND_CONSTANTS: [u64; 24] = [
+    0x0000000000000001,
+    0x0000000000008082,
+    0x800000000000808a,
+    0x8000000080008000,
+    0x000000000000808b,
+    0x0000000080000001,
+    0x8000000080008081,
+    0x8000000000008009,
+    0x000000000000008a,
+    0x0000000000000088,
+    0x0000000080008009,
+    0x000000008000000a,
+    0x000000008000808b,
+    0x800000000000008b,
+    0x8000000000008089,
+    0x8000000000008003,
+    0x8000000000008002,
+    0x8000000000000080,
+    0x000000000000800a,
+    0x800000008000000a,
+    0x8000000080008081,
+    0x8000000000008080,
+    0x0000000080000001,
+    0x8000000080008008,
+];
+
+const KECCAK_ROTATIONS: [u32; 24] = [
+    1, 3, 6, 10, 15, 21, 28, 36, 45, 55, 2, 14, 27, 41, 56, 8, 25, 43, 62, 18, 39, 61, 20, 44,
+];
+
+const KECCAK_PI_LANES: [usize; 24] = [
+    10, 7, 11, 17, 18, 3, 5, 16, 8, 21, 24, 4, 15, 23, 19, 13, 12, 2, 20, 14, 22, 9, 6, 1,
+];
+
+/// keccak256 rate, in bytes
+const KECCAK_RATE: usize = 136;
+
+/// keccak-f[1600] permutation
+const fn keccak_f(mut state: [u64; 25]) -> [u64; 25] {
+    let mut round = 0;
+    while round < 24 {
+        // theta
+        let mut columns = [0u64; 5];
+        let mut i = 0;
+        while i < 5 {
+            columns[i] = state[i] ^ state[i + 5] ^ state[i + 10] ^ state[i + 15] ^ state[i + 20];
+            i += 1;
+        }
+        i = 0;
+        while i < 5 {
+            let t = columns[(i + 4) % 5] ^ columns[(i + 1) % 5].rotate_left(1);
+            let mut j = 0;
+            while j < 25 {
+                state[j + i] ^= t;
+                j += 5;
+            }
+            i += 1;
+        }
+
+        // rho and pi
+        let mut last = state[1];
+        i = 0;
+        while i < 24 {
+            let lane = KECCAK_PI_LANES[i];
+            let next = state[lane];
+            state[lane] = last.rotate_left(KECCAK_ROTATIONS[i]);
+            last = next;
+            i += 1;
+        }
+
+        // chi
+        let mut j = 0;
+        while j < 25 {
+            let row = [
+                state[j],
+                state[j + 1],
+                state[j + 2],
+                state[j + 3],
+                state[j + 4],
+            ];
+            i = 0;
+            while i < 5 {
+                state[j + i] ^= !row[(i + 1) % 5] & row[(i + 2) % 5];
+                i += 1;
+            }
+            j += 5;
+        }
+
+        // iota
+        state[0] ^= KECCAK_ROUND_CONSTANTS[round];
+        round += 1;
+    }
+    state
+}
+
+/// Computes the keccak256 hash of `input`.
+///
+/// This is a `const fn`, so hashes of constant inputs can be computed at
+/// compile time. It is considerably slower than a table-driven
+/// implementation, and is intended for constants.
+pub const fn keccak256(input: &[u8]) -> [u8; 32] {
+    let mut state = [0u64; 25];
+
+    // absorb full blocks
+    let mut offset = 0;
+    while input.len() - offset >= KECCAK_RATE {
+        let mut i = 0;
+        while i < KECCAK_RATE {
+            state[i / 8] ^= (input[offset + i] as u64) << (8 * (i % 8));
+            i += 1;
+        }
+        state = keccak_f(state);
+        offset += KECCAK_RATE;
+    }
+
+    // absorb the remainder, with keccak padding
+    let mut i = 0;
+    while offset + i < input.len() {
+        state[i / 8] ^= (input[offset + i] as u64) << (8 * (i % 8));
+        i += 1;
+    }
+    state[i / 8] ^= 0x01 << (8 * (i % 8));
+    state[(KECCAK_RATE - 1) / 8] ^= 0x80 << (8 * ((KECCAK_RATE - 1) % 8));
+    state = keccak_f(state);
+
+    // squeeze
+    let mut out = [0u8; 32];
+    i = 0;
+    while i < 32 {
+        out[i] = (state[i / 8] >> (8 * (i % 8))) as u8;
+        i += 1;
+    }
+    out
+}
+
+/// Computes the 4-byte function selector of a function signature, e.g.
+/// `transfer(address,uint256)`.
+///
+/// Selectors computed in a `const` can be used as match patterns:
+///
+/// ```
+/// use ethers_abi_enc::selector;
+///
+/// const TRANSFER: [u8; 4] = selector("transfer(address,uint256)");
+/// const APPROVE: [u8; 4] = selector("approve(address,uint256)");
+///
+/// fn method_name(calldata: &[u8]) -> Option<&'static str> {
+///     match calldata.get(..4)?.try_into().ok()? {
+///         TRANSFER => Some("transfer"),
+///         APPROVE => Some("approve"),
+///         _ => None,
+///     }
+/// }
+///
+/// assert_eq!(method_name(&[0xa9, 0x05, 0x9c, 0xbb]), Some("transfer"));
+/// ```
+pub const fn selector(signature: &str) -> [u8; 4] {
+    let hash = keccak256(signature.as_bytes());
+    [hash[0], hash[1], hash[2], hash[3]]
+}
+
+/// Computes the topic of a non-anonymous event from its signature, e.g.
+/// `Transfer(address,address,uint256)`
+pub const fn event_topic(signature: &str) -> Hash {
+    B256(keccak256(signature.as_bytes()))
+}
+
 #[cfg(test)]
 mod tests {
-    use super::pad_u32;
-    use hex_literal::hex;
+    use super::{event_topic, keccak256, pad_u32, selector};
+    use ethers_primitives::hex;
 
     #[test]
     fn test_pad_u32() {
@@ -43,4 +212,33 @@ mod tests {
             hex!("00000000000000000000000000000000000000000000000000000000ffffffff").to_vec()
         );
     }
+
+    #[test]
+    fn test_keccak256() {
+        assert_eq!(
+            keccak256(b""),
+            hex!("c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470")
+        );
+        assert_eq!(
+            keccak256(b"hello world"),
+            hex!("47173285a8d7341e5e972fc677286384f802f8ef42a5ec5f03bbfa254cb01fad")
+        );
+        // crosses the 136 byte block boundary
+        assert_eq!(
+            keccak256(&[0u8; 136]),
+            hex!("3a5912a7c5faa06ee4fe906253e339467a9ce87d533c65be3c15cb231cdb25f9")
+        );
+    }
+
+    #[test]
+    fn test_const_selectors() {
+        const TRANSFER: [u8; 4] = selector("transfer(address,uint256)");
+        const TRANSFER_EVENT: crate::Hash = event_topic("Transfer(address,address,uint256)");
+
+        assert_eq!(TRANSFER, hex!("a9059cbb"));
+        assert_eq!(
+            TRANSFER_EVENT,
+            hex!("ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef").into()
+        );
+    }
 }
@@ -0,0 +1,74 @@
+/*
+ * C interface to the ethers-abi-ffi ABI encoder and decoder.
+ *
+ * Types are signature strings, e.g. "transfer(address,uint256)" or
+ * "(bool,string)". Values are JSON arrays:
+ *
+ *   address, bytesN, bytes  "0x"-prefixed hex strings
+ *   bool                    true / false
+ *   intN, uintN             decimal strings. Encoding also accepts numbers
+ *                           and "0x"-prefixed hex strings
+ *   string                  strings
+ *   arrays, tuples          arrays
+ *
+ * Memory ownership:
+ *
+ *   - Input pointers are borrowed for the duration of the call.
+ *   - Output buffers and strings are owned by the caller, and must be
+ *     released with ethers_abi_free_bytes and ethers_abi_free_string, never
+ *     with free().
+ *   - On error, only *err is written, if err is not NULL.
+ */
+
+#ifndef ETHERS_ABI_H
+#define ETHERS_ABI_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define ETHERS_ABI_OK 0
+#define ETHERS_ABI_ERR -1
+
+/*
+ * Encode values_json as the parameters of signature. If the signature has a
+ * function name, the output starts with its selector.
+ */
+int ethers_abi_encode(const char *signature, const char *values_json,
+                      uint8_t **out, size_t *out_len, char **err);
+
+/*
+ * Decode data as the parameters of signature into a JSON array. If the
+ * signature has a function name, data must start with its selector.
+ */
+int ethers_abi_decode(const char *signature, const uint8_t *data, size_t len,
+                      char **out_json, char **err);
+
+/*
+ * Redact arguments of data, which is calldata if the signature has a
+ * function name. redactions_json selects the arguments by parameter path
+ * and by kind, e.g. {"params": [[1], [0, 2]], "kinds": ["address"]}. Kinds
+ * are address, bool, integer, fixedbytes, bytes and string. The output is
+ * a valid encoding of the same length as data.
+ */
+int ethers_abi_redact(const char *signature, const uint8_t *data, size_t len,
+                      const char *redactions_json, uint8_t **out,
+                      size_t *out_len, char **err);
+
+/*
+ * Release a buffer returned by ethers_abi_encode or ethers_abi_redact. NULL
+ * is ignored.
+ */
+void ethers_abi_free_bytes(uint8_t *ptr, size_t len);
+
+/* Release a string returned by this library. NULL is ignored. */
+void ethers_abi_free_string(char *ptr);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* ETHERS_ABI_H */
@@ -0,0 +1,355 @@
+#![warn(
+    missing_debug_implementations,
+    missing_docs,
+    unreachable_pub,
+    unused_crate_dependencies
+)]
+#![deny(unused_must_use, rust_2018_idioms)]
+
+//! A C ABI for the ABI encoder and decoder.
+//!
+//! This crate is a thin wrapper over [`ethers_abi_dyn`], which Rust users
+//! should depend on directly.
+//!
+//! Types are given as signature strings, e.g. `transfer(address,uint256)`
+//! or `(bool,string)`, and values as JSON arrays. See
+//! [`ethers_abi_dyn::codec`] for the JSON representation of each type.
+//! Arguments can be redacted from calldata before sharing it, see
+//! [`ethers_abi_dyn::redact`].
+//!
+//! # Memory ownership
+//!
+//! - Input pointers are borrowed for the duration of the call
+//! - Output buffers and strings are allocated by this library, and must be
+//!   released with [`ethers_abi_free_bytes`] and [`ethers_abi_free_string`].
+//!   They must not be released with `free`
+//! - Output pointers are only written on the documented outcome. On error,
+//!   only the error string is written
+//!
+//! The C declarations are in `include/ethers_abi.h`.
+
+use std::{
+    ffi::{c_char, c_int, CStr, CString},
+    panic::{catch_unwind, UnwindSafe},
+    ptr, slice,
+};
+
+use ethers_abi_dyn::{codec, DynAbiError, Redactor, Signature};
+
+/// FFI errors
+#[derive(Debug, thiserror::Error)]
+pub enum FfiError {
+    /// A type, value or encoding was invalid
+    #[error(transparent)]
+    Abi(#[from] DynAbiError),
+    /// The values are not valid JSON
+    #[error("invalid JSON: {0}")]
+    Json(#[from] serde_json::Error),
+    /// A pointer argument was null, or a string was not UTF-8
+    #[error("invalid argument: {0}")]
+    InvalidArgument(&'static str),
+    /// The library panicked
+    #[error("internal error")]
+    Panic,
+}
+
+/// The call succeeded
+pub const ETHERS_ABI_OK: c_int = 0;
+/// The call failed. The error string is set
+pub const ETHERS_ABI_ERR: c_int = -1;
+
+/// Read a borrowed, nul-terminated, UTF-8 string
+///
+/// # Safety
+///
+/// `ptr` must be null or point to a nul-terminated string
+unsafe fn read_str<'a>(ptr: *const c_char, name: &'static str) -> Result<&'a str, FfiError> {
+    if ptr.is_null() {
+        return Err(FfiError::InvalidArgument(name));
+    }
+    CStr::from_ptr(ptr)
+        .to_str()
+        .map_err(|_| FfiError::InvalidArgument(name))
+}
+
+/// Read a borrowed buffer
+///
+/// # Safety
+///
+/// `ptr` must be valid for reads of `len` bytes, or null if `len` is 0
+unsafe fn read_bytes<'a>(ptr: *const u8, len: usize) -> Result<&'a [u8], FfiError> {
+    match (ptr.is_null(), len) {
+        (_, 0) => Ok(&[]),
+        (true, _) => Err(FfiError::InvalidArgument("data")),
+        (false, _) => Ok(slice::from_raw_parts(ptr, len)),
+    }
+}
+
+/// Convert a string to an owned C string. Interior nul bytes are dropped
+fn into_c_string(s: String) -> *mut c_char {
+    let s = CString::new(s).unwrap_or_else(|e| {
+        let mut bytes = e.into_vec();
+        bytes.retain(|b| *b != 0);
+        CString::new(bytes).expect("nul bytes removed")
+    });
+    s.into_raw()
+}
+
+/// Run a fallible call, catching panics and writing the error string on
+/// failure
+///
+/// # Safety
+///
+/// `err` must be null or valid for writes
+unsafe fn run(
+    err: *mut *mut c_char,
+    f: impl FnOnce() -> Result<(), FfiError> + UnwindSafe,
+) -> c_int {
+    let res = catch_unwind(f).unwrap_or(Err(FfiError::Panic));
+    match res {
+        Ok(()) => ETHERS_ABI_OK,
+        Err(e) => {
+            if !err.is_null() {
+                *err = into_c_string(e.to_string());
+            }
+            ETHERS_ABI_ERR
+        }
+    }
+}
+
+/// Encode JSON values as the parameters of a signature. If the signature
+/// has a function name, the output is prefixed with its selector.
+///
+/// On success, writes the encoding to `out` and `out_len`, and returns
+/// [`ETHERS_ABI_OK`]. Release it with [`ethers_abi_free_bytes`]. On
+/// failure, writes an error message to `err`, if not null, and returns
+/// [`ETHERS_ABI_ERR`]. Release it with [`ethers_abi_free_string`].
+///
+/// # Safety
+///
+/// `signature` and `values_json` must be nul-terminated strings. `out` and
+/// `out_len` must be valid for writes. `err` must be null or valid for
+/// writes
+#[no_mangle]
+pub unsafe extern "C" fn ethers_abi_encode(
+    signature: *const c_char,
+    values_json: *const c_char,
+    out: *mut *mut u8,
+    out_len: *mut usize,
+    err: *mut *mut c_char,
+) -> c_int {
+    run(err, || {
+        if out.is_null() || out_len.is_null() {
+            return Err(FfiError::InvalidArgument("out"));
+        }
+        let signature = Signature::parse(read_str(signature, "signature")?)?;
+        let values = serde_json::from_str(read_str(values_json, "values_json")?)?;
+        let data = codec::encode(&signature, &values)?.into_boxed_slice();
+        *out_len = data.len();
+        *out = Box::into_raw(data).cast();
+        Ok(())
+    })
+}
+
+/// Decode the parameters of a signature into a JSON array. If the
+/// signature has a function name, the data must start with its selector.
+///
+/// On success, writes the JSON string to `out_json` and returns
+/// [`ETHERS_ABI_OK`]. On failure, writes an error message to `err`, if not
+/// null, and returns [`ETHERS_ABI_ERR`]. Release either string with
+/// [`ethers_abi_free_string`].
+///
+/// # Safety
+///
+/// `signature` must be a nul-terminated string. `data` must be valid for
+/// reads of `len` bytes, or null if `len` is 0. `out_json` must be valid
+/// for writes. `err` must be null or valid for writes
+#[no_mangle]
+pub unsafe extern "C" fn ethers_abi_decode(
+    signature: *const c_char,
+    data: *const u8,
+    len: usize,
+    out_json: *mut *mut c_char,
+    err: *mut *mut c_char,
+) -> c_int {
+    run(err, || {
+        if out_json.is_null() {
+            return Err(FfiError::InvalidArgument("out_json"));
+        }
+        let data = read_bytes(data, len)?;
+        let signature = Signature::parse(read_str(signature, "signature")?)?;
+        let values = codec::decode(&signature, data)?;
+        *out_json = into_c_string(values.to_string());
+        Ok(())
+    })
+}
+
+/// Redact arguments of calldata, or of bare parameters if the signature
+/// has no function name. `redactions_json` selects the arguments, e.g.
+/// `{"params": [[1]], "kinds": ["address"]}`. See [`Redactor`].
+///
+/// On success, writes the redacted data, which has the same length as the
+/// input, to `out` and `out_len`, and returns [`ETHERS_ABI_OK`]. Release it
+/// with [`ethers_abi_free_bytes`]. On failure, writes an error message to
+/// `err`, if not null, and returns [`ETHERS_ABI_ERR`]. Release it with
+/// [`ethers_abi_free_string`].
+///
+/// # Safety
+///
+/// `signature` and `redactions_json` must be nul-terminated strings.
+/// `data` must be valid for reads of `len` bytes, or null if `len` is 0.
+/// `out` and `out_len` must be valid for writes. `err` must be null or
+/// valid for writes
+#[no_mangle]
+pub unsafe extern "C" fn ethers_abi_redact(
+    signature: *const c_char,
+    data: *const u8,
+    len: usize,
+    redactions_json: *const c_char,
+    out: *mut *mut u8,
+    out_len: *mut usize,
+    err: *mut *mut c_char,
+) -> c_int {
+    run(err, || {
+        if out.is_null() || out_len.is_null() {
+            return Err(FfiError::InvalidArgument("out"));
+        }
+        let data = read_bytes(data, len)?;
+        let signature = Signature::parse(read_str(signature, "signature")?)?;
+        let redactor: Redactor =
+            serde_json::from_str(read_str(redactions_json, "redactions_json")?)?;
+        let data = redactor.redact(&signature, data)?.into_boxed_slice();
+        *out_len = data.len();
+        *out = Box::into_raw(data).cast();
+        Ok(())
+    })
+}
+
+/// Release a buffer returned by [`ethers_abi_encode`] or
+/// [`ethers_abi_redact`]. Null is ignored
+///
+/// # Safety
+///
+/// `ptr` and `len` must have been returned by this library, and not
+/// released before
+#[no_mangle]
+pub unsafe extern "C" fn ethers_abi_free_bytes(ptr: *mut u8, len: usize) {
+    if !ptr.is_null() {
+        drop(Box::from_raw(ptr::slice_from_raw_parts_mut(ptr, len)));
+    }
+}
+
+/// Release a string returned by this library. Null is ignored
+///
+/// # Safety
+///
+/// `ptr` must have been returned by this library, and not released before
+#[no_mangle]
+pub unsafe extern "C" fn ethers_abi_free_string(ptr: *mut c_char) {
+    if !ptr.is_null() {
+        drop(CString::from_raw(ptr));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_encodes_and_decodes_through_the_c_abi() {
+        let signature = CString::new("transfer(address,uint256)").unwrap();
+        let values =
+            CString::new(r#"["0x1111111111111111111111111111111111111111","1000"]"#).unwrap();
+
+        unsafe {
+            let mut out = ptr::null_mut();
+            let mut out_len = 0;
+            let mut err = ptr::null_mut();
+            let code = ethers_abi_encode(
+                signature.as_ptr(),
+                values.as_ptr(),
+                &mut out,
+                &mut out_len,
+                &mut err,
+            );
+            assert_eq!(code, ETHERS_ABI_OK);
+            assert!(err.is_null());
+            assert_eq!(out_len, 4 + 64);
+
+            let mut json = ptr::null_mut();
+            let code = ethers_abi_decode(signature.as_ptr(), out, out_len, &mut json, &mut err);
+            assert_eq!(code, ETHERS_ABI_OK);
+            assert_eq!(CStr::from_ptr(json), values.as_c_str());
+
+            ethers_abi_free_string(json);
+            ethers_abi_free_bytes(out, out_len);
+        }
+    }
+
+    #[test]
+    fn it_reports_errors() {
+        let signature = CString::new("(uint8)").unwrap();
+        let values = CString::new(r#"["256"]"#).unwrap();
+
+        unsafe {
+            let mut out = ptr::null_mut();
+            let mut out_len = 0;
+            let mut err = ptr::null_mut();
+            let code = ethers_abi_encode(
+                signature.as_ptr(),
+                values.as_ptr(),
+                &mut out,
+                &mut out_len,
+                &mut err,
+            );
+            assert_eq!(code, ETHERS_ABI_ERR);
+            assert!(out.is_null());
+            assert_eq!(
+                CStr::from_ptr(err).to_str().unwrap(),
+                r#"invalid value for uint8: "256""#
+            );
+            ethers_abi_free_string(err);
+
+            // the error string is optional
+            let code = ethers_abi_decode(
+                ptr::null(),
+                ptr::null(),
+                0,
+                &mut ptr::null_mut(),
+                ptr::null_mut(),
+            );
+            assert_eq!(code, ETHERS_ABI_ERR);
+        }
+    }
+
+    #[test]
+    fn it_redacts_through_the_c_abi() {
+        let signature = CString::new("transfer(address,uint256)").unwrap();
+        let redactions = CString::new(r#"{"kinds": ["address"]}"#).unwrap();
+        let data = codec::encode(
+            &Signature::parse("transfer(address,uint256)").unwrap(),
+            &serde_json::json!(["0x1111111111111111111111111111111111111111", "1"]),
+        )
+        .unwrap();
+
+        unsafe {
+            let mut out = ptr::null_mut();
+            let mut out_len = 0;
+            let code = ethers_abi_redact(
+                signature.as_ptr(),
+                data.as_ptr(),
+                data.len(),
+                redactions.as_ptr(),
+                &mut out,
+                &mut out_len,
+                ptr::null_mut(),
+            );
+            assert_eq!(code, ETHERS_ABI_OK);
+            let redacted = slice::from_raw_parts(out, out_len);
+            assert_eq!(redacted[..4], data[..4]);
+            assert_eq!(redacted[4..36], [0; 32]);
+            assert_eq!(redacted[36..], data[36..]);
+            ethers_abi_free_bytes(out, out_len);
+        }
+    }
+}
@@ -0,0 +1,329 @@
+//! The items of a [JSON ABI] file.
+//!
+//! [JSON ABI]: https://docs.soliditylang.org/en/latest/abi-spec.html#json
+
+use ethers_abi_enc::SolType;
+use serde::{Deserialize, Serialize};
+
+#[cfg(not(feature = "std"))]
+use crate::no_std_prelude::*;
+
+/// A function, constructor or error parameter, or a struct field
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Param {
+    /// The name. Empty if the parameter is unnamed
+    #[serde(default)]
+    pub name: String,
+    /// The canonical type, e.g. `uint256` or `tuple[]`
+    #[serde(rename = "type")]
+    pub ty: String,
+    /// The solidity type, e.g. `struct Pool.Key`, if the compiler provided
+    /// it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub internal_type: Option<String>,
+    /// The fields, if the type is a tuple or an array of tuples
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub components: Vec<Param>,
+}
+
+impl Param {
+    /// Instantiate a parameter from a type name. Tuple types like
+    /// `tuple(address,uint256)[]` or `(address,uint256)[]` are split into
+    /// unnamed components
+    pub fn new(name: impl Into<String>, ty: &str) -> Self {
+        let (ty, components) = parse_type(ty);
+        Self {
+            name: name.into(),
+            ty,
+            internal_type: None,
+            components,
+        }
+    }
+
+    /// Instantiate a parameter of a [`SolType`]
+    pub fn of<T: SolType>(name: impl Into<String>) -> Self {
+        Self::new(name, &T::sol_type_name())
+    }
+}
+
+/// An event parameter
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventParam {
+    /// The name. Empty if the parameter is unnamed
+    #[serde(default)]
+    pub name: String,
+    /// The canonical type, e.g. `uint256` or `tuple[]`
+    #[serde(rename = "type")]
+    pub ty: String,
+    /// Whether the parameter is a topic
+    #[serde(default)]
+    pub indexed: bool,
+    /// The solidity type, if the compiler provided it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub internal_type: Option<String>,
+    /// The fields, if the type is a tuple or an array of tuples
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub components: Vec<Param>,
+}
+
+impl EventParam {
+    /// Instantiate an event parameter from a type name. See [`Param::new`]
+    pub fn new(name: impl Into<String>, ty: &str, indexed: bool) -> Self {
+        let (ty, components) = parse_type(ty);
+        Self {
+            name: name.into(),
+            ty,
+            indexed,
+            internal_type: None,
+            components,
+        }
+    }
+
+    /// Instantiate an event parameter of a [`SolType`]
+    pub fn of<T: SolType>(name: impl Into<String>, indexed: bool) -> Self {
+        Self::new(name, &T::sol_type_name(), indexed)
+    }
+}
+
+/// Split a type name into its canonical ABI type and tuple components
+fn parse_type(ty: &str) -> (String, Vec<Param>) {
+    let inner = ty.strip_prefix("tuple").unwrap_or(ty);
+    if !inner.starts_with('(') {
+        return (ty.to_owned(), vec![]);
+    }
+
+    let mut depth = 0;
+    let mut start = 1;
+    let mut components = vec![];
+    for (i, c) in inner.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ',' if depth == 1 => {
+                components.push(Param::new("", &inner[start..i]));
+                start = i + 1;
+            }
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    if i > start {
+                        components.push(Param::new("", &inner[start..i]));
+                    }
+                    return (format!("tuple{}", &inner[i + 1..]), components);
+                }
+            }
+            _ => {}
+        }
+    }
+    // unbalanced parentheses. Keep the name as-is
+    (ty.to_owned(), vec![])
+}
+
+/// Function state mutability
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StateMutability {
+    /// Does not read or modify state
+    Pure,
+    /// Reads but does not modify state
+    View,
+    /// Modifies state, and does not accept ether
+    #[default]
+    NonPayable,
+    /// Modifies state, and accepts ether
+    Payable,
+}
+
+/// A function
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Function {
+    /// The name
+    pub name: String,
+    /// The parameters
+    #[serde(default)]
+    pub inputs: Vec<Param>,
+    /// The return values
+    #[serde(default)]
+    pub outputs: Vec<Param>,
+    /// The state mutability
+    #[serde(default)]
+    pub state_mutability: StateMutability,
+}
+
+/// An event
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Event {
+    /// The name
+    pub name: String,
+    /// The parameters
+    #[serde(default)]
+    pub inputs: Vec<EventParam>,
+    /// Whether the event signature is omitted from the topics
+    #[serde(default)]
+    pub anonymous: bool,
+}
+
+/// A custom error
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CustomError {
+    /// The name
+    pub name: String,
+    /// The parameters
+    #[serde(default)]
+    pub inputs: Vec<Param>,
+}
+
+/// A constructor
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Constructor {
+    /// The parameters
+    #[serde(default)]
+    pub inputs: Vec<Param>,
+    /// The state mutability
+    #[serde(default)]
+    pub state_mutability: StateMutability,
+}
+
+/// The fallback function
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Fallback {
+    /// The state mutability
+    #[serde(default)]
+    pub state_mutability: StateMutability,
+}
+
+/// The receive function
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Receive {
+    /// The state mutability. Always payable
+    #[serde(default)]
+    pub state_mutability: StateMutability,
+}
+
+/// An item of a JSON ABI
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum AbiItem {
+    /// A function
+    Function(Function),
+    /// An event
+    Event(Event),
+    /// A custom error
+    Error(CustomError),
+    /// The constructor
+    Constructor(Constructor),
+    /// The fallback function
+    Fallback(Fallback),
+    /// The receive function
+    Receive(Receive),
+}
+
+/// A JSON ABI: the list of a contract's items
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct JsonAbi {
+    /// The items, in file order
+    pub items: Vec<AbiItem>,
+}
+
+impl JsonAbi {
+    /// The functions
+    pub fn functions(&self) -> impl Iterator<Item = &Function> {
+        self.items.iter().filter_map(|item| match item {
+            AbiItem::Function(function) => Some(function),
+            _ => None,
+        })
+    }
+
+    /// The events
+    pub fn events(&self) -> impl Iterator<Item = &Event> {
+        self.items.iter().filter_map(|item| match item {
+            AbiItem::Event(event) => Some(event),
+            _ => None,
+        })
+    }
+
+    /// The custom errors
+    pub fn errors(&self) -> impl Iterator<Item = &CustomError> {
+        self.items.iter().filter_map(|item| match item {
+            AbiItem::Error(error) => Some(error),
+            _ => None,
+        })
+    }
+
+    /// The constructor, if the contract declares one
+    pub fn constructor(&self) -> Option<&Constructor> {
+        self.items.iter().find_map(|item| match item {
+            AbiItem::Constructor(constructor) => Some(constructor),
+            _ => None,
+        })
+    }
+}
+
+impl FromIterator<AbiItem> for JsonAbi {
+    fn from_iter<I: IntoIterator<Item = AbiItem>>(iter: I) -> Self {
+        Self {
+            items: iter.into_iter().collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ethers_abi_enc::sol_type;
+
+    use super::*;
+
+    #[test]
+    fn it_parses_json_abis() {
+        let json = r#"[
+            {"type":"constructor","inputs":[{"name":"supply","type":"uint256"}],"stateMutability":"nonpayable"},
+            {"type":"function","name":"balanceOf","inputs":[{"name":"owner","type":"address","internalType":"address"}],"outputs":[{"name":"","type":"uint256"}],"stateMutability":"view"},
+            {"type":"event","name":"Transfer","inputs":[{"name":"from","type":"address","indexed":true},{"name":"value","type":"uint256","indexed":false}],"anonymous":false},
+            {"type":"error","name":"Unauthorized","inputs":[]},
+            {"type":"receive","stateMutability":"payable"}
+        ]"#;
+        let abi: JsonAbi = serde_json::from_str(json).unwrap();
+        assert_eq!(abi.items.len(), 5);
+        assert_eq!(abi.constructor().unwrap().inputs[0].ty, "uint256");
+
+        let balance_of = abi.functions().next().unwrap();
+        assert_eq!(balance_of.state_mutability, StateMutability::View);
+        assert_eq!(
+            balance_of.inputs[0].internal_type.as_deref(),
+            Some("address")
+        );
+
+        let transfer = abi.events().next().unwrap();
+        assert!(transfer.inputs[0].indexed);
+        assert!(!transfer.inputs[1].indexed);
+        assert_eq!(abi.errors().count(), 1);
+
+        let round_trip: JsonAbi =
+            serde_json::from_value(serde_json::to_value(&abi).unwrap()).unwrap();
+        assert_eq!(round_trip, abi);
+    }
+
+    #[test]
+    fn it_builds_params_from_sol_types() {
+        let param = Param::of::<sol_type::Uint<256>>("amount");
+        assert_eq!(param, Param::new("amount", "uint256"));
+        assert!(param.components.is_empty());
+
+        type Key = sol_type::Array<(sol_type::Address, (sol_type::Bool, sol_type::Bytes))>;
+        let param = Param::of::<Key>("keys");
+        assert_eq!(param.ty, "tuple[]");
+        assert_eq!(param.components.len(), 2);
+        assert_eq!(param.components[0].ty, "address");
+        assert_eq!(param.components[1].ty, "tuple");
+        assert_eq!(
+            param.components[1].components,
+            vec![Param::new("", "bool"), Param::new("", "bytes")]
+        );
+    }
+}
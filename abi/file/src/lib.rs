@@ -1 +1,23 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+#![warn(missing_docs)]
 
+//! JSON ABI files, and Solidity interface generation
+
+#[cfg_attr(not(feature = "std"), macro_use)]
+extern crate alloc;
+#[cfg(not(feature = "std"))]
+mod no_std_prelude {
+    pub use alloc::{
+        borrow::ToOwned,
+        string::{String, ToString},
+        vec::Vec,
+    };
+}
+
+mod item;
+pub use item::{
+    AbiItem, Constructor, CustomError, Event, EventParam, Fallback, Function, JsonAbi, Param,
+    Receive, StateMutability,
+};
+
+mod sol;
@@ -0,0 +1,308 @@
+//! Solidity interface generation
+
+use core::fmt::Write;
+
+use crate::{AbiItem, Event, Function, JsonAbi, Param, StateMutability};
+
+#[cfg(not(feature = "std"))]
+use crate::no_std_prelude::*;
+
+/// A struct declared by the interface
+struct SolStruct {
+    name: String,
+    components: Vec<Param>,
+    fields: Vec<String>,
+}
+
+/// Collects the structs used by the interface's items
+#[derive(Default)]
+struct Structs(Vec<SolStruct>);
+
+impl Structs {
+    /// The Solidity type of a parameter, declaring a struct for tuples
+    fn ty(&mut self, ty: &str, internal_type: Option<&str>, components: &[Param]) -> String {
+        match ty.strip_prefix("tuple") {
+            Some(dims) => format!("{}{}", self.declare(internal_type, components), dims),
+            None => ty.to_owned(),
+        }
+    }
+
+    /// Declare a struct, and return its name. Structs are named after the
+    /// internal type, e.g. `struct Pool.Key[]` declares `Key`. Tuples
+    /// without an internal type reuse a struct with the same components, or
+    /// get a generated name
+    fn declare(&mut self, internal_type: Option<&str>, components: &[Param]) -> String {
+        let declared = internal_type
+            .and_then(|ty| ty.strip_prefix("struct "))
+            .and_then(|ty| ty.split('[').next())
+            .and_then(|ty| ty.rsplit('.').next());
+
+        let existing = self.0.iter().find(|s| match declared {
+            Some(name) => s.name == name,
+            None => s.components == components,
+        });
+        if let Some(existing) = existing {
+            return existing.name.clone();
+        }
+
+        let fields = components
+            .iter()
+            .enumerate()
+            .map(|(i, field)| {
+                let ty = self.ty(&field.ty, field.internal_type.as_deref(), &field.components);
+                if field.name.is_empty() {
+                    format!("{ty} _{i};")
+                } else {
+                    format!("{ty} {};", field.name)
+                }
+            })
+            .collect();
+        // nested structs are declared first
+        let name = match declared {
+            Some(name) => name.to_owned(),
+            None => format!("Struct{}", self.0.len()),
+        };
+        self.0.push(SolStruct {
+            name: name.clone(),
+            components: components.to_vec(),
+            fields,
+        });
+        name
+    }
+
+    /// Render function or error parameters, with a data location if
+    /// required
+    fn params(&mut self, params: &[Param], location: Option<&str>) -> String {
+        let params: Vec<String> = params
+            .iter()
+            .map(|param| {
+                let mut out = self.ty(&param.ty, param.internal_type.as_deref(), &param.components);
+                if let Some(location) = location.filter(|_| is_reference(&param.ty)) {
+                    out.push(' ');
+                    out.push_str(location);
+                }
+                if !param.name.is_empty() {
+                    out.push(' ');
+                    out.push_str(&param.name);
+                }
+                out
+            })
+            .collect();
+        params.join(", ")
+    }
+
+    fn event(&mut self, event: &Event) -> String {
+        let params: Vec<String> = event
+            .inputs
+            .iter()
+            .map(|param| {
+                let mut out = self.ty(&param.ty, param.internal_type.as_deref(), &param.components);
+                if param.indexed {
+                    out.push_str(" indexed");
+                }
+                if !param.name.is_empty() {
+                    out.push(' ');
+                    out.push_str(&param.name);
+                }
+                out
+            })
+            .collect();
+        let anonymous = if event.anonymous { " anonymous" } else { "" };
+        format!("event {}({}){};", event.name, params.join(", "), anonymous)
+    }
+
+    fn function(&mut self, function: &Function) -> String {
+        let mut out = format!(
+            "function {}({}) external{}",
+            function.name,
+            self.params(&function.inputs, Some("calldata")),
+            mutability(function.state_mutability),
+        );
+        if !function.outputs.is_empty() {
+            let _ = write!(
+                out,
+                " returns ({})",
+                self.params(&function.outputs, Some("memory"))
+            );
+        }
+        out.push(';');
+        out
+    }
+}
+
+/// Arrays, structs, `bytes` and `string` require a data location
+fn is_reference(ty: &str) -> bool {
+    ty.ends_with(']') || ty.starts_with("tuple") || ty == "bytes" || ty == "string"
+}
+
+fn mutability(state_mutability: StateMutability) -> &'static str {
+    match state_mutability {
+        StateMutability::Pure => " pure",
+        StateMutability::View => " view",
+        StateMutability::NonPayable => "",
+        StateMutability::Payable => " payable",
+    }
+}
+
+impl JsonAbi {
+    /// Generate the source of a Solidity `interface` declaring the ABI's
+    /// functions, events, custom errors, and the structs they use.
+    ///
+    /// Constructors are omitted, as interfaces cannot declare them
+    pub fn to_sol(&self, name: &str) -> String {
+        let mut structs = Structs::default();
+        let mut events = vec![];
+        let mut errors = vec![];
+        let mut functions = vec![];
+        for item in &self.items {
+            match item {
+                AbiItem::Function(function) => functions.push(structs.function(function)),
+                AbiItem::Event(event) => events.push(structs.event(event)),
+                AbiItem::Error(error) => errors.push(format!(
+                    "error {}({});",
+                    error.name,
+                    structs.params(&error.inputs, None)
+                )),
+                AbiItem::Constructor(_) => {}
+                AbiItem::Fallback(fallback) => functions.push(format!(
+                    "fallback() external{};",
+                    mutability(fallback.state_mutability)
+                )),
+                AbiItem::Receive(_) => functions.push("receive() external payable;".to_owned()),
+            }
+        }
+
+        let structs = structs.0.into_iter().map(|s| {
+            let mut out = format!("struct {} {{\n", s.name);
+            for field in s.fields {
+                let _ = writeln!(out, "        {field}");
+            }
+            out.push_str("    }");
+            out
+        });
+        let sections: Vec<String> = [structs.collect(), events, errors, functions]
+            .into_iter()
+            .filter(|section: &Vec<String>| !section.is_empty())
+            .map(|section| {
+                section
+                    .iter()
+                    .map(|line| format!("    {line}\n"))
+                    .collect::<String>()
+            })
+            .collect();
+
+        format!(
+            "// SPDX-License-Identifier: UNLICENSED\npragma solidity ^0.8.4;\n\ninterface {} {{\n{}}}\n",
+            name,
+            sections.join("\n")
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ethers_abi_enc::sol_type;
+
+    use crate::{CustomError, EventParam};
+
+    use super::*;
+
+    #[test]
+    fn it_generates_interfaces() {
+        let json = r#"[
+            {"type":"constructor","inputs":[],"stateMutability":"nonpayable"},
+            {"type":"function","name":"swap","inputs":[
+                {"name":"key","type":"tuple","internalType":"struct Pool.Key","components":[
+                    {"name":"token","type":"address"},
+                    {"name":"fee","type":"uint24"}
+                ]},
+                {"name":"path","type":"bytes"}
+            ],"outputs":[{"name":"","type":"int256[]"}],"stateMutability":"payable"},
+            {"type":"function","name":"keys","inputs":[],"outputs":[
+                {"name":"","type":"tuple[]","internalType":"struct Pool.Key[]","components":[
+                    {"name":"token","type":"address"},
+                    {"name":"fee","type":"uint24"}
+                ]}
+            ],"stateMutability":"view"},
+            {"type":"event","name":"Swap","inputs":[
+                {"name":"sender","type":"address","indexed":true},
+                {"name":"amount","type":"uint256","indexed":false}
+            ],"anonymous":false},
+            {"type":"error","name":"Unauthorized","inputs":[{"name":"caller","type":"address"}]},
+            {"type":"receive","stateMutability":"payable"}
+        ]"#;
+        let abi: JsonAbi = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            abi.to_sol("IPool"),
+            "\
+// SPDX-License-Identifier: UNLICENSED
+pragma solidity ^0.8.4;
+
+interface IPool {
+    struct Key {
+        address token;
+        uint24 fee;
+    }
+
+    event Swap(address indexed sender, uint256 amount);
+
+    error Unauthorized(address caller);
+
+    function swap(Key calldata key, bytes calldata path) external payable returns (int256[] memory);
+    function keys() external view returns (Key[] memory);
+    receive() external payable;
+}
+"
+        );
+    }
+
+    #[test]
+    fn it_generates_interfaces_from_sol_types() {
+        type Order = (sol_type::Address, (sol_type::Bool, sol_type::String));
+
+        let abi: JsonAbi = [
+            AbiItem::Function(Function {
+                name: "submit".to_owned(),
+                inputs: vec![Param::of::<sol_type::Array<Order>>("orders")],
+                outputs: vec![Param::of::<sol_type::Uint<256>>("")],
+                ..Default::default()
+            }),
+            AbiItem::Event(Event {
+                name: "Submitted".to_owned(),
+                inputs: vec![EventParam::of::<Order>("order", false)],
+                anonymous: true,
+            }),
+            AbiItem::Error(CustomError {
+                name: "Rejected".to_owned(),
+                inputs: vec![Param::of::<sol_type::String>("reason")],
+            }),
+        ]
+        .into_iter()
+        .collect();
+
+        assert_eq!(
+            abi.to_sol("IOrders"),
+            "\
+// SPDX-License-Identifier: UNLICENSED
+pragma solidity ^0.8.4;
+
+interface IOrders {
+    struct Struct0 {
+        bool _0;
+        string _1;
+    }
+    struct Struct1 {
+        address _0;
+        Struct0 _1;
+    }
+
+    event Submitted(Struct1 order) anonymous;
+
+    error Rejected(string reason);
+
+    function submit(Struct1[] calldata orders) external returns (uint256);
+}
+"
+        );
+    }
+}
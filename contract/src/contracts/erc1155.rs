@@ -0,0 +1,360 @@
+//! ERC-1155 multi-tokens
+
+use ethers_abi_enc::{
+    selector,
+    sol_type::{self, Array, Bool, Uint},
+    Error,
+};
+use ethers_primitives::{Address, U256};
+use ethers_provider::Provider;
+use ethers_rpc_types::{BlockId, Log, TransactionRequest};
+use ethers_transports::Connection;
+
+pub use super::erc721::ApprovalForAll;
+use super::{decode_data, decode_topic, indexed_topics};
+use crate::{ContractError, ContractInstance, EthEvent, EventFilter};
+
+const URI: [u8; 4] = selector("uri(uint256)");
+const BALANCE_OF: [u8; 4] = selector("balanceOf(address,uint256)");
+const BALANCE_OF_BATCH: [u8; 4] = selector("balanceOfBatch(address[],uint256[])");
+const IS_APPROVED_FOR_ALL: [u8; 4] = selector("isApprovedForAll(address,address)");
+const SAFE_TRANSFER_FROM: [u8; 4] =
+    selector("safeTransferFrom(address,address,uint256,uint256,bytes)");
+const SAFE_BATCH_TRANSFER_FROM: [u8; 4] =
+    selector("safeBatchTransferFrom(address,address,uint256[],uint256[],bytes)");
+const SET_APPROVAL_FOR_ALL: [u8; 4] = selector("setApprovalForAll(address,bool)");
+
+type TransferParams = (
+    sol_type::Address,
+    sol_type::Address,
+    Uint<256>,
+    Uint<256>,
+    sol_type::Bytes,
+);
+type BatchTransferParams = (
+    sol_type::Address,
+    sol_type::Address,
+    Array<Uint<256>>,
+    Array<Uint<256>>,
+    sol_type::Bytes,
+);
+
+/// An ERC-1155 token contract
+#[derive(Debug, Clone)]
+pub struct Erc1155<'a, T> {
+    instance: ContractInstance<'a, T>,
+}
+
+impl<'a, T> Erc1155<'a, T> {
+    /// Instantiate a token contract bound to a provider
+    pub fn new(address: Address, provider: &'a Provider<T>) -> Self {
+        Self {
+            instance: ContractInstance::new(address, provider),
+        }
+    }
+
+    /// The underlying contract instance
+    pub fn instance(&self) -> &ContractInstance<'a, T> {
+        &self.instance
+    }
+
+    /// The token address
+    pub fn address(&self) -> Address {
+        self.instance.address()
+    }
+
+    /// A transaction sending `amount` of token `id` from `from` to `to`.
+    /// `data` is passed to the recipient's receive hook
+    pub fn safe_transfer_from(
+        &self,
+        from: Address,
+        to: Address,
+        id: U256,
+        amount: U256,
+        data: Vec<u8>,
+    ) -> TransactionRequest {
+        self.instance
+            .transaction::<TransferParams>(SAFE_TRANSFER_FROM, (from, to, id, amount, data))
+    }
+
+    /// A transaction sending `amounts[i]` of each token `ids[i]` from `from`
+    /// to `to`
+    pub fn safe_batch_transfer_from(
+        &self,
+        from: Address,
+        to: Address,
+        ids: Vec<U256>,
+        amounts: Vec<U256>,
+        data: Vec<u8>,
+    ) -> TransactionRequest {
+        self.instance.transaction::<BatchTransferParams>(
+            SAFE_BATCH_TRANSFER_FROM,
+            (from, to, ids, amounts, data),
+        )
+    }
+
+    /// A transaction allowing or disallowing `operator` to transfer all of
+    /// the sender's tokens
+    pub fn set_approval_for_all(&self, operator: Address, approved: bool) -> TransactionRequest {
+        self.instance
+            .transaction::<(sol_type::Address, Bool)>(SET_APPROVAL_FOR_ALL, (operator, approved))
+    }
+
+    /// A filter for the token's [`TransferSingle`] logs
+    pub fn transfer_single_filter(&self) -> EventFilter<TransferSingle> {
+        TransferSingle::filter().address(self.address())
+    }
+
+    /// A filter for the token's [`TransferBatch`] logs
+    pub fn transfer_batch_filter(&self) -> EventFilter<TransferBatch> {
+        TransferBatch::filter().address(self.address())
+    }
+
+    /// A filter for the token's [`ApprovalForAll`] logs
+    pub fn approval_for_all_filter(&self) -> EventFilter<ApprovalForAll> {
+        ApprovalForAll::filter().address(self.address())
+    }
+}
+
+impl<T> Erc1155<'_, T>
+where
+    T: Connection + Unpin,
+{
+    /// The metadata URI of a token. Clients substitute `{id}` with the hex
+    /// token id. Defaults to the latest block
+    pub async fn uri(&self, id: U256, block: Option<BlockId>) -> Result<String, ContractError> {
+        let (uri,) = self
+            .instance
+            .call::<(Uint<256>,), (sol_type::String,)>(URI, (id,), block)
+            .await?;
+        Ok(uri)
+    }
+
+    /// The balance of token `id` of an account. Defaults to the latest block
+    pub async fn balance_of(
+        &self,
+        owner: Address,
+        id: U256,
+        block: Option<BlockId>,
+    ) -> Result<U256, ContractError> {
+        let (balance,) = self
+            .instance
+            .call::<(sol_type::Address, Uint<256>), (Uint<256>,)>(BALANCE_OF, (owner, id), block)
+            .await?;
+        Ok(balance)
+    }
+
+    /// The balances of token `ids[i]` of each account `owners[i]`. Defaults
+    /// to the latest block
+    pub async fn balance_of_batch(
+        &self,
+        owners: Vec<Address>,
+        ids: Vec<U256>,
+        block: Option<BlockId>,
+    ) -> Result<Vec<U256>, ContractError> {
+        let (balances,) = self
+            .instance
+            .call::<(Array<sol_type::Address>, Array<Uint<256>>), (Array<Uint<256>>,)>(
+                BALANCE_OF_BATCH,
+                (owners, ids),
+                block,
+            )
+            .await?;
+        Ok(balances)
+    }
+
+    /// `true` if `operator` may transfer all of `owner`'s tokens. Defaults
+    /// to the latest block
+    pub async fn is_approved_for_all(
+        &self,
+        owner: Address,
+        operator: Address,
+        block: Option<BlockId>,
+    ) -> Result<bool, ContractError> {
+        let (approved,) = self
+            .instance
+            .call::<(sol_type::Address, sol_type::Address), (Bool,)>(
+                IS_APPROVED_FOR_ALL,
+                (owner, operator),
+                block,
+            )
+            .await?;
+        Ok(approved)
+    }
+}
+
+/// `TransferSingle(address indexed operator, address indexed from, address
+/// indexed to, uint256 id, uint256 value)`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TransferSingle {
+    /// The account that sent the transfer
+    pub operator: Address,
+    /// The previous owner, or the zero address for mints
+    pub from: Address,
+    /// The new owner, or the zero address for burns
+    pub to: Address,
+    /// The token
+    pub id: U256,
+    /// The amount transferred
+    pub value: U256,
+}
+
+impl EthEvent for TransferSingle {
+    const SIGNATURE: &'static str = "TransferSingle(address,address,address,uint256,uint256)";
+}
+
+impl TransferSingle {
+    /// Decode a log of the event
+    pub fn decode_log(log: &Log) -> Result<Self, Error> {
+        let topics = indexed_topics::<Self>(log, 3)?;
+        let (id, value) = decode_data::<(Uint<256>, Uint<256>)>(log)?;
+        Ok(Self {
+            operator: decode_topic::<sol_type::Address>(&topics[0])?,
+            from: decode_topic::<sol_type::Address>(&topics[1])?,
+            to: decode_topic::<sol_type::Address>(&topics[2])?,
+            id,
+            value,
+        })
+    }
+}
+
+/// `TransferBatch(address indexed operator, address indexed from, address
+/// indexed to, uint256[] ids, uint256[] values)`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TransferBatch {
+    /// The account that sent the transfer
+    pub operator: Address,
+    /// The previous owner, or the zero address for mints
+    pub from: Address,
+    /// The new owner, or the zero address for burns
+    pub to: Address,
+    /// The tokens
+    pub ids: Vec<U256>,
+    /// The amount transferred of each token
+    pub values: Vec<U256>,
+}
+
+impl EthEvent for TransferBatch {
+    const SIGNATURE: &'static str = "TransferBatch(address,address,address,uint256[],uint256[])";
+}
+
+impl TransferBatch {
+    /// Decode a log of the event
+    pub fn decode_log(log: &Log) -> Result<Self, Error> {
+        let topics = indexed_topics::<Self>(log, 3)?;
+        let (ids, values) = decode_data::<(Array<Uint<256>>, Array<Uint<256>>)>(log)?;
+        Ok(Self {
+            operator: decode_topic::<sol_type::Address>(&topics[0])?,
+            from: decode_topic::<sol_type::Address>(&topics[1])?,
+            to: decode_topic::<sol_type::Address>(&topics[2])?,
+            ids,
+            values,
+        })
+    }
+}
+
+/// `URI(string value, uint256 indexed id)`, emitted when the metadata URI
+/// of a token changes
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Uri {
+    /// The new URI
+    pub value: String,
+    /// The token
+    pub id: U256,
+}
+
+impl EthEvent for Uri {
+    const SIGNATURE: &'static str = "URI(string,uint256)";
+}
+
+impl Uri {
+    /// Decode a log of the event
+    pub fn decode_log(log: &Log) -> Result<Self, Error> {
+        let topics = indexed_topics::<Self>(log, 1)?;
+        let (value,) = decode_data::<(sol_type::String,)>(log)?;
+        Ok(Self {
+            value,
+            id: decode_topic::<Uint<256>>(&topics[0])?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ethers_abi_enc::SolType;
+    use ethers_transports::transports::MockConnection;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn it_calls_views() {
+        let mock = MockConnection::new();
+        let provider = Provider::new(mock.clone());
+        let token = Erc1155::new(Address::repeat_byte(0x11), &provider);
+        let owners = vec![Address::repeat_byte(0x22), Address::repeat_byte(0x33)];
+        let balances = vec![U256::from(1), U256::from(2)];
+
+        mock.push_response(
+            "eth_call",
+            <(Array<Uint<256>>,)>::encode_params((balances.clone(),)),
+        );
+        assert_eq!(
+            token
+                .balance_of_batch(owners, vec![U256::from(7); 2], None)
+                .await
+                .unwrap(),
+            balances
+        );
+        let data = mock.requests()[0].params.as_ref().unwrap()[0]["data"]
+            .as_str()
+            .unwrap()
+            .to_owned();
+        assert!(data.starts_with("0x4e1273f4"));
+    }
+
+    #[test]
+    fn it_decodes_events() {
+        let operator = Address::repeat_byte(0x22);
+        let to = Address::repeat_byte(0x33);
+        let log = Log {
+            topics: vec![
+                TransferBatch::signature_hash(),
+                sol_type::Address::encode_topic(operator),
+                sol_type::Address::encode_topic(Address::default()),
+                sol_type::Address::encode_topic(to),
+            ],
+            data: <(Array<Uint<256>>, Array<Uint<256>>)>::encode_params((
+                vec![U256::from(1), U256::from(2)],
+                vec![U256::from(10), U256::from(20)],
+            )),
+            ..Default::default()
+        };
+        assert_eq!(
+            TransferBatch::decode_log(&log).unwrap(),
+            TransferBatch {
+                operator,
+                from: Address::default(),
+                to,
+                ids: vec![U256::from(1), U256::from(2)],
+                values: vec![U256::from(10), U256::from(20)],
+            }
+        );
+        assert!(TransferSingle::decode_log(&log).is_err());
+
+        let log = Log {
+            topics: vec![
+                Uri::signature_hash(),
+                Uint::<256>::encode_topic(U256::from(1)),
+            ],
+            data: <(sol_type::String,)>::encode_params(("ipfs://{id}".to_owned(),)),
+            ..Default::default()
+        };
+        assert_eq!(
+            Uri::decode_log(&log).unwrap(),
+            Uri {
+                value: "ipfs://{id}".to_owned(),
+                id: U256::from(1)
+            }
+        );
+    }
+}
@@ -0,0 +1,308 @@
+//! ERC-20 fungible tokens
+
+use ethers_abi_enc::{
+    selector,
+    sol_type::{self, Uint},
+    Error,
+};
+use ethers_primitives::{Address, U256};
+use ethers_provider::Provider;
+use ethers_rpc_types::{BlockId, Log, TransactionRequest};
+use ethers_transports::Connection;
+
+use super::{decode_data, decode_topic, indexed_topics};
+use crate::{ContractError, ContractInstance, EthEvent, EventFilter};
+
+const NAME: [u8; 4] = selector("name()");
+const SYMBOL: [u8; 4] = selector("symbol()");
+const DECIMALS: [u8; 4] = selector("decimals()");
+const TOTAL_SUPPLY: [u8; 4] = selector("totalSupply()");
+const BALANCE_OF: [u8; 4] = selector("balanceOf(address)");
+const ALLOWANCE: [u8; 4] = selector("allowance(address,address)");
+const TRANSFER: [u8; 4] = selector("transfer(address,uint256)");
+const APPROVE: [u8; 4] = selector("approve(address,uint256)");
+const TRANSFER_FROM: [u8; 4] = selector("transferFrom(address,address,uint256)");
+
+/// An ERC-20 token contract
+#[derive(Debug, Clone)]
+pub struct Erc20<'a, T> {
+    instance: ContractInstance<'a, T>,
+}
+
+impl<'a, T> Erc20<'a, T> {
+    /// Instantiate a token contract bound to a provider
+    pub fn new(address: Address, provider: &'a Provider<T>) -> Self {
+        Self {
+            instance: ContractInstance::new(address, provider),
+        }
+    }
+
+    /// The underlying contract instance
+    pub fn instance(&self) -> &ContractInstance<'a, T> {
+        &self.instance
+    }
+
+    /// The token address
+    pub fn address(&self) -> Address {
+        self.instance.address()
+    }
+
+    /// A transaction sending `amount` of the sender's tokens to `to`
+    pub fn transfer(&self, to: Address, amount: U256) -> TransactionRequest {
+        self.instance
+            .transaction::<(sol_type::Address, Uint<256>)>(TRANSFER, (to, amount))
+    }
+
+    /// A transaction allowing `spender` to transfer up to `amount` of the
+    /// sender's tokens
+    pub fn approve(&self, spender: Address, amount: U256) -> TransactionRequest {
+        self.instance
+            .transaction::<(sol_type::Address, Uint<256>)>(APPROVE, (spender, amount))
+    }
+
+    /// A transaction sending `amount` of `from`'s tokens to `to`, spending
+    /// the sender's allowance
+    pub fn transfer_from(&self, from: Address, to: Address, amount: U256) -> TransactionRequest {
+        self.instance
+            .transaction::<(sol_type::Address, sol_type::Address, Uint<256>)>(
+                TRANSFER_FROM,
+                (from, to, amount),
+            )
+    }
+
+    /// A filter for the token's [`Transfer`] logs
+    pub fn transfer_filter(&self) -> EventFilter<Transfer> {
+        Transfer::filter().address(self.address())
+    }
+
+    /// A filter for the token's [`Approval`] logs
+    pub fn approval_filter(&self) -> EventFilter<Approval> {
+        Approval::filter().address(self.address())
+    }
+}
+
+impl<T> Erc20<'_, T>
+where
+    T: Connection + Unpin,
+{
+    /// The token name. Defaults to the latest block
+    pub async fn name(&self, block: Option<BlockId>) -> Result<String, ContractError> {
+        let (name,) = self
+            .instance
+            .call_raw::<(sol_type::String,)>(NAME, block)
+            .await?;
+        Ok(name)
+    }
+
+    /// The token symbol. Defaults to the latest block
+    pub async fn symbol(&self, block: Option<BlockId>) -> Result<String, ContractError> {
+        let (symbol,) = self
+            .instance
+            .call_raw::<(sol_type::String,)>(SYMBOL, block)
+            .await?;
+        Ok(symbol)
+    }
+
+    /// The number of decimals of token amounts. Defaults to the latest block
+    pub async fn decimals(&self, block: Option<BlockId>) -> Result<u8, ContractError> {
+        let (decimals,) = self
+            .instance
+            .call_raw::<(Uint<8>,)>(DECIMALS, block)
+            .await?;
+        Ok(decimals)
+    }
+
+    /// The total supply of the token. Defaults to the latest block
+    pub async fn total_supply(&self, block: Option<BlockId>) -> Result<U256, ContractError> {
+        let (supply,) = self
+            .instance
+            .call_raw::<(Uint<256>,)>(TOTAL_SUPPLY, block)
+            .await?;
+        Ok(supply)
+    }
+
+    /// The token balance of an account. Defaults to the latest block
+    pub async fn balance_of(
+        &self,
+        owner: Address,
+        block: Option<BlockId>,
+    ) -> Result<U256, ContractError> {
+        let (balance,) = self
+            .instance
+            .call::<(sol_type::Address,), (Uint<256>,)>(BALANCE_OF, (owner,), block)
+            .await?;
+        Ok(balance)
+    }
+
+    /// The amount `spender` may transfer from `owner`. Defaults to the
+    /// latest block
+    pub async fn allowance(
+        &self,
+        owner: Address,
+        spender: Address,
+        block: Option<BlockId>,
+    ) -> Result<U256, ContractError> {
+        let (allowance,) = self
+            .instance
+            .call::<(sol_type::Address, sol_type::Address), (Uint<256>,)>(
+                ALLOWANCE,
+                (owner, spender),
+                block,
+            )
+            .await?;
+        Ok(allowance)
+    }
+}
+
+/// `Transfer(address indexed from, address indexed to, uint256 value)`.
+/// Mints are from, and burns are to, the zero address
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Transfer {
+    /// The sender
+    pub from: Address,
+    /// The recipient
+    pub to: Address,
+    /// The amount transferred
+    pub value: U256,
+}
+
+impl EthEvent for Transfer {
+    const SIGNATURE: &'static str = "Transfer(address,address,uint256)";
+}
+
+impl Transfer {
+    /// Decode a log of the event. ERC-721 transfers, which share the
+    /// signature but index the token id, are rejected
+    pub fn decode_log(log: &Log) -> Result<Self, Error> {
+        let topics = indexed_topics::<Self>(log, 2)?;
+        let (value,) = decode_data::<(Uint<256>,)>(log)?;
+        Ok(Self {
+            from: decode_topic::<sol_type::Address>(&topics[0])?,
+            to: decode_topic::<sol_type::Address>(&topics[1])?,
+            value,
+        })
+    }
+}
+
+/// `Approval(address indexed owner, address indexed spender, uint256 value)`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Approval {
+    /// The token owner
+    pub owner: Address,
+    /// The approved spender
+    pub spender: Address,
+    /// The new allowance
+    pub value: U256,
+}
+
+impl EthEvent for Approval {
+    const SIGNATURE: &'static str = "Approval(address,address,uint256)";
+}
+
+impl Approval {
+    /// Decode a log of the event
+    pub fn decode_log(log: &Log) -> Result<Self, Error> {
+        let topics = indexed_topics::<Self>(log, 2)?;
+        let (value,) = decode_data::<(Uint<256>,)>(log)?;
+        Ok(Self {
+            owner: decode_topic::<sol_type::Address>(&topics[0])?,
+            spender: decode_topic::<sol_type::Address>(&topics[1])?,
+            value,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ethers_abi_enc::SolType;
+    use ethers_rpc_types::Bytes;
+    use ethers_transports::transports::MockConnection;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn it_calls_views() {
+        let mock = MockConnection::new();
+        let provider = Provider::new(mock.clone());
+        let token = Erc20::new(Address::repeat_byte(0x11), &provider);
+        let owner = Address::repeat_byte(0x22);
+
+        mock.push_response("eth_call", <(Uint<256>,)>::encode_params((U256::from(5),)));
+        mock.push_response("eth_call", <(Uint<8>,)>::encode_params((18,)));
+        mock.push_response(
+            "eth_call",
+            <(sol_type::String,)>::encode_params(("TKN".to_owned(),)),
+        );
+
+        assert_eq!(token.balance_of(owner, None).await.unwrap(), U256::from(5));
+        assert_eq!(token.decimals(None).await.unwrap(), 18);
+        assert_eq!(token.symbol(None).await.unwrap(), "TKN");
+
+        let requests = mock.requests();
+        let data = requests[0].params.as_ref().unwrap()[0]["data"]
+            .as_str()
+            .unwrap()
+            .to_owned();
+        assert_eq!(
+            data,
+            format!(
+                "0x70a08231{:0>64}",
+                ethers_pub_use::hex::encode(owner.as_bytes())
+            )
+        );
+        assert_eq!(
+            requests[1].params.as_ref().unwrap()[0]["data"],
+            "0x313ce567"
+        );
+    }
+
+    #[test]
+    fn it_builds_transactions() {
+        let mock = MockConnection::new();
+        let provider = Provider::new(mock);
+        let token = Erc20::new(Address::repeat_byte(0x11), &provider);
+
+        let tx = token.transfer(Address::repeat_byte(0x22), U256::from(1));
+        let data = tx.data.unwrap();
+        assert_eq!(data[..4], [0xa9, 0x05, 0x9c, 0xbb]);
+        assert_eq!(data.len(), 4 + 64);
+        assert_eq!(
+            token
+                .approve(Address::repeat_byte(0x22), U256::MAX)
+                .data
+                .unwrap()[..4],
+            [0x09, 0x5e, 0xa7, 0xb3]
+        );
+    }
+
+    #[test]
+    fn it_decodes_events() {
+        let from = Address::repeat_byte(0x22);
+        let to = Address::repeat_byte(0x33);
+        let log = Log {
+            address: Address::repeat_byte(0x11),
+            topics: vec![
+                Transfer::signature_hash(),
+                sol_type::Address::encode_topic(from),
+                sol_type::Address::encode_topic(to),
+            ],
+            data: <(Uint<256>,)>::encode_params((U256::from(7),)),
+            ..Default::default()
+        };
+        assert_eq!(
+            Transfer::decode_log(&log).unwrap(),
+            Transfer {
+                from,
+                to,
+                value: U256::from(7)
+            }
+        );
+        assert!(Approval::decode_log(&log).is_err());
+
+        // an ERC-721 transfer
+        let mut nft = log;
+        nft.topics.push(Uint::<256>::encode_topic(U256::from(7)));
+        nft.data = Bytes::default();
+        assert!(Transfer::decode_log(&nft).is_err());
+    }
+}
@@ -0,0 +1,361 @@
+//! ERC-721 non-fungible tokens
+
+use ethers_abi_enc::{
+    selector,
+    sol_type::{self, Bool, Uint},
+    Error,
+};
+use ethers_primitives::{Address, U256};
+use ethers_provider::Provider;
+use ethers_rpc_types::{BlockId, Log, TransactionRequest};
+use ethers_transports::Connection;
+
+use super::{decode_data, decode_topic, indexed_topics};
+use crate::{ContractError, ContractInstance, EthEvent, EventFilter};
+
+const NAME: [u8; 4] = selector("name()");
+const SYMBOL: [u8; 4] = selector("symbol()");
+const TOKEN_URI: [u8; 4] = selector("tokenURI(uint256)");
+const BALANCE_OF: [u8; 4] = selector("balanceOf(address)");
+const OWNER_OF: [u8; 4] = selector("ownerOf(uint256)");
+const GET_APPROVED: [u8; 4] = selector("getApproved(uint256)");
+const IS_APPROVED_FOR_ALL: [u8; 4] = selector("isApprovedForAll(address,address)");
+const TRANSFER_FROM: [u8; 4] = selector("transferFrom(address,address,uint256)");
+const SAFE_TRANSFER_FROM: [u8; 4] = selector("safeTransferFrom(address,address,uint256)");
+const APPROVE: [u8; 4] = selector("approve(address,uint256)");
+const SET_APPROVAL_FOR_ALL: [u8; 4] = selector("setApprovalForAll(address,bool)");
+
+/// An ERC-721 token contract
+#[derive(Debug, Clone)]
+pub struct Erc721<'a, T> {
+    instance: ContractInstance<'a, T>,
+}
+
+impl<'a, T> Erc721<'a, T> {
+    /// Instantiate a token contract bound to a provider
+    pub fn new(address: Address, provider: &'a Provider<T>) -> Self {
+        Self {
+            instance: ContractInstance::new(address, provider),
+        }
+    }
+
+    /// The underlying contract instance
+    pub fn instance(&self) -> &ContractInstance<'a, T> {
+        &self.instance
+    }
+
+    /// The token address
+    pub fn address(&self) -> Address {
+        self.instance.address()
+    }
+
+    /// A transaction sending a token from `from` to `to`. The sender must
+    /// own or be approved for the token
+    pub fn transfer_from(&self, from: Address, to: Address, token_id: U256) -> TransactionRequest {
+        self.instance
+            .transaction::<(sol_type::Address, sol_type::Address, Uint<256>)>(
+                TRANSFER_FROM,
+                (from, to, token_id),
+            )
+    }
+
+    /// A transaction sending a token from `from` to `to`, which reverts if
+    /// `to` is a contract that does not accept ERC-721 tokens
+    pub fn safe_transfer_from(
+        &self,
+        from: Address,
+        to: Address,
+        token_id: U256,
+    ) -> TransactionRequest {
+        self.instance
+            .transaction::<(sol_type::Address, sol_type::Address, Uint<256>)>(
+                SAFE_TRANSFER_FROM,
+                (from, to, token_id),
+            )
+    }
+
+    /// A transaction allowing `approved` to transfer a token. The zero
+    /// address clears the approval
+    pub fn approve(&self, approved: Address, token_id: U256) -> TransactionRequest {
+        self.instance
+            .transaction::<(sol_type::Address, Uint<256>)>(APPROVE, (approved, token_id))
+    }
+
+    /// A transaction allowing or disallowing `operator` to transfer all of
+    /// the sender's tokens
+    pub fn set_approval_for_all(&self, operator: Address, approved: bool) -> TransactionRequest {
+        self.instance
+            .transaction::<(sol_type::Address, Bool)>(SET_APPROVAL_FOR_ALL, (operator, approved))
+    }
+
+    /// A filter for the token's [`Transfer`] logs
+    pub fn transfer_filter(&self) -> EventFilter<Transfer> {
+        Transfer::filter().address(self.address())
+    }
+
+    /// A filter for the token's [`Approval`] logs
+    pub fn approval_filter(&self) -> EventFilter<Approval> {
+        Approval::filter().address(self.address())
+    }
+
+    /// A filter for the token's [`ApprovalForAll`] logs
+    pub fn approval_for_all_filter(&self) -> EventFilter<ApprovalForAll> {
+        ApprovalForAll::filter().address(self.address())
+    }
+}
+
+impl<T> Erc721<'_, T>
+where
+    T: Connection + Unpin,
+{
+    /// The collection name. Defaults to the latest block
+    pub async fn name(&self, block: Option<BlockId>) -> Result<String, ContractError> {
+        let (name,) = self
+            .instance
+            .call_raw::<(sol_type::String,)>(NAME, block)
+            .await?;
+        Ok(name)
+    }
+
+    /// The collection symbol. Defaults to the latest block
+    pub async fn symbol(&self, block: Option<BlockId>) -> Result<String, ContractError> {
+        let (symbol,) = self
+            .instance
+            .call_raw::<(sol_type::String,)>(SYMBOL, block)
+            .await?;
+        Ok(symbol)
+    }
+
+    /// The metadata URI of a token. Defaults to the latest block
+    pub async fn token_uri(
+        &self,
+        token_id: U256,
+        block: Option<BlockId>,
+    ) -> Result<String, ContractError> {
+        let (uri,) = self
+            .instance
+            .call::<(Uint<256>,), (sol_type::String,)>(TOKEN_URI, (token_id,), block)
+            .await?;
+        Ok(uri)
+    }
+
+    /// The number of tokens an account owns. Defaults to the latest block
+    pub async fn balance_of(
+        &self,
+        owner: Address,
+        block: Option<BlockId>,
+    ) -> Result<U256, ContractError> {
+        let (balance,) = self
+            .instance
+            .call::<(sol_type::Address,), (Uint<256>,)>(BALANCE_OF, (owner,), block)
+            .await?;
+        Ok(balance)
+    }
+
+    /// The owner of a token. Defaults to the latest block
+    pub async fn owner_of(
+        &self,
+        token_id: U256,
+        block: Option<BlockId>,
+    ) -> Result<Address, ContractError> {
+        let (owner,) = self
+            .instance
+            .call::<(Uint<256>,), (sol_type::Address,)>(OWNER_OF, (token_id,), block)
+            .await?;
+        Ok(owner)
+    }
+
+    /// The account approved for a token, or the zero address. Defaults to
+    /// the latest block
+    pub async fn get_approved(
+        &self,
+        token_id: U256,
+        block: Option<BlockId>,
+    ) -> Result<Address, ContractError> {
+        let (approved,) = self
+            .instance
+            .call::<(Uint<256>,), (sol_type::Address,)>(GET_APPROVED, (token_id,), block)
+            .await?;
+        Ok(approved)
+    }
+
+    /// `true` if `operator` may transfer all of `owner`'s tokens. Defaults
+    /// to the latest block
+    pub async fn is_approved_for_all(
+        &self,
+        owner: Address,
+        operator: Address,
+        block: Option<BlockId>,
+    ) -> Result<bool, ContractError> {
+        let (approved,) = self
+            .instance
+            .call::<(sol_type::Address, sol_type::Address), (Bool,)>(
+                IS_APPROVED_FOR_ALL,
+                (owner, operator),
+                block,
+            )
+            .await?;
+        Ok(approved)
+    }
+}
+
+/// `Transfer(address indexed from, address indexed to, uint256 indexed
+/// tokenId)`. Mints are from, and burns are to, the zero address
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Transfer {
+    /// The previous owner
+    pub from: Address,
+    /// The new owner
+    pub to: Address,
+    /// The token transferred
+    pub token_id: U256,
+}
+
+impl EthEvent for Transfer {
+    const SIGNATURE: &'static str = "Transfer(address,address,uint256)";
+}
+
+impl Transfer {
+    /// Decode a log of the event. ERC-20 transfers, which share the
+    /// signature but do not index the amount, are rejected
+    pub fn decode_log(log: &Log) -> Result<Self, Error> {
+        let topics = indexed_topics::<Self>(log, 3)?;
+        Ok(Self {
+            from: decode_topic::<sol_type::Address>(&topics[0])?,
+            to: decode_topic::<sol_type::Address>(&topics[1])?,
+            token_id: decode_topic::<Uint<256>>(&topics[2])?,
+        })
+    }
+}
+
+/// `Approval(address indexed owner, address indexed approved, uint256
+/// indexed tokenId)`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Approval {
+    /// The token owner
+    pub owner: Address,
+    /// The approved account, or the zero address
+    pub approved: Address,
+    /// The token
+    pub token_id: U256,
+}
+
+impl EthEvent for Approval {
+    const SIGNATURE: &'static str = "Approval(address,address,uint256)";
+}
+
+impl Approval {
+    /// Decode a log of the event
+    pub fn decode_log(log: &Log) -> Result<Self, Error> {
+        let topics = indexed_topics::<Self>(log, 3)?;
+        Ok(Self {
+            owner: decode_topic::<sol_type::Address>(&topics[0])?,
+            approved: decode_topic::<sol_type::Address>(&topics[1])?,
+            token_id: decode_topic::<Uint<256>>(&topics[2])?,
+        })
+    }
+}
+
+/// `ApprovalForAll(address indexed owner, address indexed operator, bool
+/// approved)`. Shared by ERC-721 and ERC-1155
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ApprovalForAll {
+    /// The token owner
+    pub owner: Address,
+    /// The operator
+    pub operator: Address,
+    /// `true` if the operator was approved, `false` if revoked
+    pub approved: bool,
+}
+
+impl EthEvent for ApprovalForAll {
+    const SIGNATURE: &'static str = "ApprovalForAll(address,address,bool)";
+}
+
+impl ApprovalForAll {
+    /// Decode a log of the event
+    pub fn decode_log(log: &Log) -> Result<Self, Error> {
+        let topics = indexed_topics::<Self>(log, 2)?;
+        let (approved,) = decode_data::<(Bool,)>(log)?;
+        Ok(Self {
+            owner: decode_topic::<sol_type::Address>(&topics[0])?,
+            operator: decode_topic::<sol_type::Address>(&topics[1])?,
+            approved,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ethers_abi_enc::SolType;
+    use ethers_transports::transports::MockConnection;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn it_calls_views() {
+        let mock = MockConnection::new();
+        let provider = Provider::new(mock.clone());
+        let token = Erc721::new(Address::repeat_byte(0x11), &provider);
+        let owner = Address::repeat_byte(0x22);
+
+        mock.push_response("eth_call", <(sol_type::Address,)>::encode_params((owner,)));
+        mock.push_response(
+            "eth_call",
+            <(sol_type::String,)>::encode_params(("ipfs://1".to_owned(),)),
+        );
+
+        assert_eq!(token.owner_of(U256::from(1), None).await.unwrap(), owner);
+        assert_eq!(
+            token.token_uri(U256::from(1), None).await.unwrap(),
+            "ipfs://1"
+        );
+        assert_eq!(
+            mock.requests()[0].params.as_ref().unwrap()[0]["data"],
+            format!("0x6352211e{:0>64}", 1)
+        );
+    }
+
+    #[test]
+    fn it_decodes_events() {
+        let from = Address::repeat_byte(0x22);
+        let to = Address::repeat_byte(0x33);
+        let log = Log {
+            address: Address::repeat_byte(0x11),
+            topics: vec![
+                Transfer::signature_hash(),
+                sol_type::Address::encode_topic(from),
+                sol_type::Address::encode_topic(to),
+                Uint::<256>::encode_topic(U256::from(9)),
+            ],
+            ..Default::default()
+        };
+        assert_eq!(
+            Transfer::decode_log(&log).unwrap(),
+            Transfer {
+                from,
+                to,
+                token_id: U256::from(9)
+            }
+        );
+        assert!(crate::contracts::erc20::Transfer::decode_log(&log).is_err());
+
+        let log = Log {
+            topics: vec![
+                ApprovalForAll::signature_hash(),
+                sol_type::Address::encode_topic(from),
+                sol_type::Address::encode_topic(to),
+            ],
+            data: <(Bool,)>::encode_params((true,)),
+            ..Default::default()
+        };
+        assert_eq!(
+            ApprovalForAll::decode_log(&log).unwrap(),
+            ApprovalForAll {
+                owner: from,
+                operator: to,
+                approved: true
+            }
+        );
+    }
+}
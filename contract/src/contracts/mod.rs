@@ -0,0 +1,48 @@
+//! Typed bindings for the standard token interfaces.
+//!
+//! Each binding wraps a [`ContractInstance`] with methods for the
+//! interface's functions. Views are called at a block, and state-changing
+//! functions return the [`TransactionRequest`] to send. Events implement
+//! [`EthEvent`], and decode from logs.
+//!
+//! [`ContractInstance`]: crate::ContractInstance
+//! [`TransactionRequest`]: ethers_rpc_types::TransactionRequest
+//! [`EthEvent`]: crate::EthEvent
+
+use ethers_abi_enc::{Error, SolType};
+use ethers_primitives::B256;
+use ethers_rpc_types::Log;
+
+use crate::EthEvent;
+
+pub mod erc1155;
+pub use erc1155::Erc1155;
+
+pub mod erc20;
+pub use erc20::Erc20;
+
+pub mod erc721;
+pub use erc721::Erc721;
+
+/// The indexed topics of a log of `E`, after the signature topic. Fails if
+/// the log has another signature, or does not have `count` indexed topics
+fn indexed_topics<E: EthEvent>(log: &Log, count: usize) -> Result<&[B256], Error> {
+    match log.topics.split_first() {
+        Some((signature, topics)) if *signature == E::signature_hash() && topics.len() == count => {
+            Ok(topics)
+        }
+        _ => Err(Error::Other(
+            format!("not a log of {}", E::SIGNATURE).into(),
+        )),
+    }
+}
+
+/// Decode an indexed parameter of a static type
+fn decode_topic<T: SolType>(topic: &B256) -> Result<T::RustType, Error> {
+    T::detokenize(&ethers_abi_enc::decode::<T>(topic.as_bytes())?)
+}
+
+/// Decode the non-indexed parameters. `T` must be their tuple type
+fn decode_data<T: SolType>(log: &Log) -> Result<T::RustType, Error> {
+    T::decode_params(&log.data)
+}
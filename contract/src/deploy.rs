@@ -0,0 +1,203 @@
+//! Contract deployment
+
+use std::time::Duration;
+
+use ethers_abi_enc::SolType;
+use ethers_primitives::{create_address, Address, B256, U256};
+use ethers_provider::{Provider, ProviderError};
+use ethers_pub_use::thiserror;
+use ethers_rpc_types::{BlockNumber, Bytes, TransactionReceipt, TransactionRequest};
+use ethers_transports::Connection;
+
+use crate::ContractInstance;
+
+/// Deployment errors
+#[derive(Debug, thiserror::Error)]
+pub enum DeployError {
+    /// A request failed
+    #[error(transparent)]
+    Provider(#[from] ProviderError),
+    /// The deployment has no sender, so its address is unknown
+    #[error("deployment has no sender")]
+    MissingSender,
+    /// The deployment transaction reverted
+    #[error("deployment {0} reverted")]
+    Reverted(B256),
+    /// The receipt reports a contract address other than the one computed
+    /// from the sender and nonce
+    #[error("expected contract at {expected}, receipt reports {actual}")]
+    AddressMismatch {
+        /// The computed address
+        expected: Address,
+        /// The address in the receipt
+        actual: Address,
+    },
+    /// The deployment succeeded, but left no code at the contract address
+    #[error("no code at {0} after deployment")]
+    NoCode(Address),
+}
+
+/// Deploys a contract from its creation bytecode and constructor
+/// arguments, sending the deployment from an account managed by the node.
+///
+/// The nonce is fixed before sending, so the contract address is known in
+/// advance. After the deployment is mined, the address in the receipt and
+/// the code at the address are checked
+#[derive(Debug, Clone)]
+pub struct Deployer<'a, T> {
+    provider: &'a Provider<T>,
+    bytecode: Bytes,
+    args: Bytes,
+    tx: TransactionRequest,
+    timeout: Option<Duration>,
+}
+
+impl<'a, T> Deployer<'a, T> {
+    /// Instantiate a deployer for a contract without constructor arguments
+    pub fn new(provider: &'a Provider<T>, bytecode: impl Into<Bytes>) -> Self {
+        Self {
+            provider,
+            bytecode: bytecode.into(),
+            args: Bytes::new(),
+            tx: TransactionRequest::default(),
+            timeout: None,
+        }
+    }
+
+    /// Set the constructor arguments. `Args` must be the tuple of the
+    /// constructor parameter types
+    #[must_use = "Builder method outputs must be used"]
+    pub fn with_args<Args: SolType>(mut self, args: Args::RustType) -> Self {
+        self.args = Args::encode_params(args);
+        self
+    }
+
+    /// Set the sender. Required
+    #[must_use = "Builder method outputs must be used"]
+    pub fn from(mut self, from: Address) -> Self {
+        self.tx = self.tx.from(from);
+        self
+    }
+
+    /// Set the value endowed to the contract
+    #[must_use = "Builder method outputs must be used"]
+    pub fn value(mut self, value: U256) -> Self {
+        self.tx = self.tx.value(value);
+        self
+    }
+
+    /// Set the gas limit. Defaults to the node's estimate
+    #[must_use = "Builder method outputs must be used"]
+    pub fn gas(mut self, gas: U256) -> Self {
+        self.tx = self.tx.gas(gas);
+        self
+    }
+
+    /// Give up waiting for the deployment to be mined after `timeout`.
+    /// Defaults to waiting indefinitely
+    #[must_use = "Builder method outputs must be used"]
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// The deployment transaction, without a nonce
+    pub fn request(&self) -> TransactionRequest {
+        let mut data = self.bytecode.to_vec();
+        data.extend_from_slice(&self.args);
+        self.tx.clone().data(data)
+    }
+}
+
+impl<'a, T> Deployer<'a, T>
+where
+    T: Connection + Unpin,
+{
+    /// Send the deployment, and wait for it to be mined. Returns the
+    /// deployed contract and the deployment receipt
+    pub async fn send(self) -> Result<(ContractInstance<'a, T>, TransactionReceipt), DeployError> {
+        let from = self.tx.from.ok_or(DeployError::MissingSender)?;
+        let nonce = self
+            .provider
+            .get_transaction_count(from, Some(BlockNumber::Pending.into()))
+            .await
+            .map_err(ProviderError::from)?
+            .map_err(ProviderError::from)?;
+        let address = create_address(from, nonce.to());
+
+        let mut tx = self.request();
+        tx.nonce = Some(nonce);
+        let hash = self.provider.send_transaction(tx).await?;
+        let receipt = self.provider.wait_for_receipt(hash, self.timeout).await?;
+        if !receipt.is_success() {
+            return Err(DeployError::Reverted(hash));
+        }
+        if let Some(actual) = receipt.contract_address.filter(|actual| *actual != address) {
+            return Err(DeployError::AddressMismatch {
+                expected: address,
+                actual,
+            });
+        }
+
+        let code = self
+            .provider
+            .get_code(address, None)
+            .await
+            .map_err(ProviderError::from)?
+            .map_err(ProviderError::from)?;
+        if code.is_empty() {
+            return Err(DeployError::NoCode(address));
+        }
+        Ok((ContractInstance::new(address, self.provider), receipt))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ethers_abi_enc::{selector, sol_type};
+    use ethers_primitives::hex;
+    use ethers_test_node::InMemory;
+
+    use super::*;
+
+    /// Creation code of a contract that stores its constructor argument,
+    /// and returns it from every call
+    const CREATION_CODE: [u8; 36] = hex!(
+        // codecopy the argument, sstore it, and return the runtime code
+        "60206024600039600051600055600b6019600039600b6000f3"
+        // sload, and return it
+        "60005460005260206000f3"
+    );
+
+    #[tokio::test]
+    async fn it_deploys_contracts() {
+        let node = InMemory::new();
+        let from = Address::repeat_byte(0x11);
+        node.set_balance(from, U256::from(10u64.pow(18)));
+        let provider = Provider::new(node);
+
+        assert!(matches!(
+            Deployer::new(&provider, &CREATION_CODE[..]).send().await,
+            Err(DeployError::MissingSender)
+        ));
+
+        let (contract, receipt) = Deployer::new(&provider, &CREATION_CODE[..])
+            .with_args::<(sol_type::Uint<256>,)>((U256::from(42),))
+            .from(from)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(contract.address(), create_address(from, 0));
+        assert_eq!(receipt.contract_address, Some(contract.address()));
+
+        let value = contract
+            .call::<(sol_type::Uint<256>,), (sol_type::Uint<256>,)>(
+                selector("value(uint256)"),
+                (U256::ZERO,),
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(value, (U256::from(42),));
+    }
+}
@@ -0,0 +1,248 @@
+//! Typed log filters for contract events
+
+use std::{fmt, marker::PhantomData, ops::Deref};
+
+use ethers_abi_enc::{event_topic, SolType};
+use ethers_primitives::{Address, B256};
+use ethers_rpc_types::{BlockNumber, Filter, ValueOrArray};
+
+/// A contract event
+///
+/// ```
+/// # use ethers_contract::EthEvent;
+/// struct Transfer;
+///
+/// impl EthEvent for Transfer {
+///     const SIGNATURE: &'static str = "Transfer(address,address,uint256)";
+/// }
+/// ```
+pub trait EthEvent {
+    /// The event signature, e.g. `Transfer(address,address,uint256)`
+    const SIGNATURE: &'static str;
+
+    /// `true` if the event is anonymous, so its logs have no signature topic
+    const ANONYMOUS: bool = false;
+
+    /// The signature hash, which is the first topic of non-anonymous events
+    fn signature_hash() -> B256 {
+        event_topic(Self::SIGNATURE)
+    }
+
+    /// A filter for logs of this event. Non-anonymous events match the
+    /// signature hash
+    fn filter() -> EventFilter<Self>
+    where
+        Self: Sized,
+    {
+        EventFilter::new()
+    }
+}
+
+/// A [`Filter`] for the logs of an event, with typed indexed parameters.
+///
+/// Dereferences to the [`Filter`], to pass to `Provider::get_logs`
+pub struct EventFilter<E> {
+    filter: Filter,
+    _event: PhantomData<fn() -> E>,
+}
+
+impl<E> EventFilter<E>
+where
+    E: EthEvent,
+{
+    /// Instantiate a filter for logs of the event
+    pub fn new() -> Self {
+        let filter = if E::ANONYMOUS {
+            Filter::new()
+        } else {
+            Filter::new().event_signature(E::signature_hash())
+        };
+        Self {
+            filter,
+            _event: PhantomData,
+        }
+    }
+
+    /// The topic position of an indexed parameter
+    fn position(index: usize) -> usize {
+        if E::ANONYMOUS {
+            index
+        } else {
+            index + 1
+        }
+    }
+
+    fn map(self, f: impl FnOnce(Filter) -> Filter) -> Self {
+        Self {
+            filter: f(self.filter),
+            _event: PhantomData,
+        }
+    }
+
+    /// Match logs emitted by a contract, or by any of several contracts
+    #[must_use = "Builder method outputs must be used"]
+    pub fn address(self, address: impl Into<ValueOrArray<Address>>) -> Self {
+        self.map(|filter| filter.address(address))
+    }
+
+    /// Set the first block to search
+    #[must_use = "Builder method outputs must be used"]
+    pub fn from_block(self, block: impl Into<BlockNumber>) -> Self {
+        self.map(|filter| filter.from_block(block))
+    }
+
+    /// Set the last block to search
+    #[must_use = "Builder method outputs must be used"]
+    pub fn to_block(self, block: impl Into<BlockNumber>) -> Self {
+        self.map(|filter| filter.to_block(block))
+    }
+
+    /// Search only the block with this hash, replacing any block range
+    #[must_use = "Builder method outputs must be used"]
+    pub fn at_block_hash(self, hash: B256) -> Self {
+        self.map(|filter| filter.at_block_hash(hash))
+    }
+
+    /// Match the indexed parameter at `index`, counting from 0, against a
+    /// value of its Solidity type `T`
+    ///
+    /// # Panics
+    ///
+    /// If the position is past the last topic
+    #[must_use = "Builder method outputs must be used"]
+    pub fn indexed<T: SolType>(self, index: usize, value: T::RustType) -> Self {
+        let topic = T::encode_topic(value);
+        self.map(|filter| filter.topic(Self::position(index), topic))
+    }
+
+    /// Match the indexed parameter at `index` against any of several values
+    ///
+    /// # Panics
+    ///
+    /// If the position is past the last topic
+    #[must_use = "Builder method outputs must be used"]
+    pub fn indexed_any<T: SolType>(
+        self,
+        index: usize,
+        values: impl IntoIterator<Item = T::RustType>,
+    ) -> Self {
+        let topics: Vec<_> = values.into_iter().map(T::encode_topic).collect();
+        self.map(|filter| filter.topic(Self::position(index), topics))
+    }
+
+    /// Match the first indexed parameter
+    #[must_use = "Builder method outputs must be used"]
+    pub fn topic1<T: SolType>(self, value: T::RustType) -> Self {
+        self.indexed::<T>(0, value)
+    }
+
+    /// Match the second indexed parameter
+    #[must_use = "Builder method outputs must be used"]
+    pub fn topic2<T: SolType>(self, value: T::RustType) -> Self {
+        self.indexed::<T>(1, value)
+    }
+
+    /// Match the third indexed parameter
+    #[must_use = "Builder method outputs must be used"]
+    pub fn topic3<T: SolType>(self, value: T::RustType) -> Self {
+        self.indexed::<T>(2, value)
+    }
+}
+
+impl<E> EventFilter<E> {
+    /// Unwrap the filter
+    pub fn into_filter(self) -> Filter {
+        self.filter
+    }
+}
+
+impl<E> Default for EventFilter<E>
+where
+    E: EthEvent,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<E> Clone for EventFilter<E> {
+    fn clone(&self) -> Self {
+        Self {
+            filter: self.filter.clone(),
+            _event: PhantomData,
+        }
+    }
+}
+
+impl<E> fmt::Debug for EventFilter<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("EventFilter").field(&self.filter).finish()
+    }
+}
+
+impl<E> Deref for EventFilter<E> {
+    type Target = Filter;
+
+    fn deref(&self) -> &Self::Target {
+        &self.filter
+    }
+}
+
+impl<E> From<EventFilter<E>> for Filter {
+    fn from(filter: EventFilter<E>) -> Self {
+        filter.filter
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ethers_abi_enc::sol_type;
+    use ethers_primitives::{hex, U256};
+
+    use super::*;
+
+    struct Transfer;
+
+    impl EthEvent for Transfer {
+        const SIGNATURE: &'static str = "Transfer(address,address,uint256)";
+    }
+
+    struct Anonymous;
+
+    impl EthEvent for Anonymous {
+        const SIGNATURE: &'static str = "Anonymous(uint256)";
+        const ANONYMOUS: bool = true;
+    }
+
+    #[test]
+    fn it_builds_event_filters() {
+        let to = Address::repeat_byte(0x22);
+        let filter = Transfer::filter()
+            .address(Address::repeat_byte(0x11))
+            .from_block(1)
+            .topic2::<sol_type::Address>(to);
+
+        assert_eq!(
+            filter.topics[0],
+            Some(
+                B256(hex!(
+                    "ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef"
+                ))
+                .into()
+            )
+        );
+        assert_eq!(filter.topics[1], None);
+        let mut word = [0u8; 32];
+        word[12..].copy_from_slice(to.as_bytes());
+        assert_eq!(filter.topics[2], Some(B256(word).into()));
+
+        let filter = Anonymous::filter()
+            .indexed_any::<sol_type::Uint<256>>(0, [U256::from(1), U256::from(2)])
+            .into_filter();
+        assert_eq!(filter.topics.len(), 1);
+        assert!(filter.topics[0]
+            .as_ref()
+            .unwrap()
+            .matches(&B256::from(U256::from(2))));
+    }
+}
@@ -0,0 +1,87 @@
+//! A deployed contract, bound to a provider
+
+use ethers_abi_enc::SolType;
+use ethers_primitives::Address;
+use ethers_provider::{Provider, ProviderError};
+use ethers_pub_use::thiserror;
+use ethers_rpc_types::{BlockId, Bytes, TransactionRequest};
+use ethers_transports::Connection;
+
+/// Contract call errors
+#[derive(Debug, thiserror::Error)]
+pub enum ContractError {
+    /// The `eth_call` failed, e.g. because the call reverted
+    #[error(transparent)]
+    Provider(#[from] ProviderError),
+    /// The return data could not be decoded
+    #[error("invalid return data: {0}")]
+    Abi(#[from] ethers_abi_enc::Error),
+}
+
+/// A contract at an address, bound to the provider used to call it
+#[derive(Debug, Clone)]
+pub struct ContractInstance<'a, T> {
+    provider: &'a Provider<T>,
+    address: Address,
+}
+
+impl<'a, T> ContractInstance<'a, T> {
+    /// Instantiate a contract bound to a provider
+    pub fn new(address: Address, provider: &'a Provider<T>) -> Self {
+        Self { provider, address }
+    }
+
+    /// The contract address
+    pub fn address(&self) -> Address {
+        self.address
+    }
+
+    /// The provider
+    pub fn provider(&self) -> &'a Provider<T> {
+        self.provider
+    }
+
+    /// A transaction calling a function. `Args` must be the tuple of the
+    /// parameter types
+    pub fn transaction<Args: SolType>(
+        &self,
+        selector: [u8; 4],
+        args: Args::RustType,
+    ) -> TransactionRequest {
+        TransactionRequest::default()
+            .to(self.address)
+            .data(Args::encode_with_selector(selector, args))
+    }
+}
+
+impl<T> ContractInstance<'_, T>
+where
+    T: Connection + Unpin,
+{
+    /// Call a function without creating a transaction, and decode its
+    /// return data. `Args` and `Ret` must be the tuples of the parameter and
+    /// return types. Defaults to the latest block
+    pub async fn call<Args: SolType, Ret: SolType>(
+        &self,
+        selector: [u8; 4],
+        args: Args::RustType,
+        block: Option<BlockId>,
+    ) -> Result<Ret::RustType, ContractError> {
+        self.call_raw::<Ret>(Args::encode_with_selector(selector, args), block)
+            .await
+    }
+
+    /// Call the contract with already encoded calldata, such as a bare
+    /// selector, and decode its return data. See [`ContractInstance::call`]
+    pub async fn call_raw<Ret: SolType>(
+        &self,
+        calldata: impl Into<Bytes>,
+        block: Option<BlockId>,
+    ) -> Result<Ret::RustType, ContractError> {
+        let tx = TransactionRequest::default()
+            .to(self.address)
+            .data(calldata);
+        let data = self.provider.call(tx, block).await?;
+        Ok(Ret::decode_params(&data)?)
+    }
+}
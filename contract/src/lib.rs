@@ -0,0 +1,28 @@
+#![warn(
+    missing_debug_implementations,
+    missing_docs,
+    unreachable_pub,
+    unused_crate_dependencies
+)]
+#![deny(unused_must_use, rust_2018_idioms)]
+#![doc(test(
+    no_crate_inject,
+    attr(deny(warnings, rust_2018_idioms), allow(dead_code, unused_variables))
+))]
+
+//! Helpers for interacting with deployed contracts
+
+pub mod contracts;
+pub use contracts::{Erc1155, Erc20, Erc721};
+
+pub mod deploy;
+pub use deploy::{DeployError, Deployer};
+
+pub mod event;
+pub use event::{EthEvent, EventFilter};
+
+pub mod instance;
+pub use instance::{ContractError, ContractInstance};
+
+pub mod multicall;
+pub use multicall::{Multicall, MulticallError, MulticallResult};
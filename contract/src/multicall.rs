@@ -0,0 +1,335 @@
+//! Aggregate many calls into one `eth_call`, using [Multicall3].
+//!
+//! [Multicall3]: https://github.com/mds1/multicall
+
+use ethers_abi_enc::{selector, sol_type, SolType};
+use ethers_primitives::Address;
+use ethers_provider::{Provider, ProviderError};
+use ethers_pub_use::thiserror;
+use ethers_rpc_types::{BlockId, Bytes, TransactionRequest};
+use ethers_transports::Connection;
+
+/// The Multicall3 contract, deployed at the same address on most chains
+pub const MULTICALL3_ADDRESS: Address = Address::new([
+    0xca, 0x11, 0xbd, 0xe0, 0x59, 0x77, 0xb3, 0x63, 0x11, 0x67, 0x02, 0x88, 0x62, 0xbe, 0x2a, 0x17,
+    0x39, 0x76, 0xca, 0x11,
+]);
+
+/// The default maximum `aggregate3` calldata size, in bytes. Larger
+/// multicalls are split into several `eth_call`s
+pub const DEFAULT_MAX_CALLDATA_SIZE: usize = 64 * 1024;
+
+const AGGREGATE3: [u8; 4] = selector("aggregate3((address,bool,bytes)[])");
+
+type Call3 = (sol_type::Address, sol_type::Bool, sol_type::Bytes);
+type Aggregate3Params = (sol_type::Array<Call3>,);
+type Aggregate3Return = (sol_type::Array<(sol_type::Bool, sol_type::Bytes)>,);
+
+/// Multicall errors
+#[derive(Debug, thiserror::Error)]
+pub enum MulticallError {
+    /// The `eth_call` failed. If a call that does not allow failure
+    /// reverts, the whole multicall reverts
+    #[error(transparent)]
+    Provider(#[from] ProviderError),
+    /// A response could not be decoded
+    #[error("invalid multicall response: {0}")]
+    Abi(#[from] ethers_abi_enc::Error),
+    /// The number of results does not match the number of calls
+    #[error("expected {expected} multicall results, got {actual}")]
+    LengthMismatch {
+        /// Number of calls
+        expected: usize,
+        /// Number of results
+        actual: usize,
+    },
+    /// A call reverted
+    #[error("call reverted with {0}")]
+    Reverted(Bytes),
+}
+
+/// A call in a multicall
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Call {
+    /// The contract to call
+    pub target: Address,
+    /// If `false`, a revert of this call reverts the whole multicall
+    pub allow_failure: bool,
+    /// The calldata
+    pub data: Vec<u8>,
+}
+
+impl Call {
+    /// Size of the call in the `aggregate3` calldata: the offset of its
+    /// tuple, the 3 tuple words, the calldata length, and the padded
+    /// calldata
+    fn encoded_size(&self) -> usize {
+        32 * 5 + self.data.len().div_ceil(32) * 32
+    }
+}
+
+/// The result of a call in a multicall
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MulticallResult {
+    /// Whether the call succeeded
+    pub success: bool,
+    /// The return data, or the revert data if the call failed
+    pub return_data: Bytes,
+}
+
+impl MulticallResult {
+    /// Decode the return data. `S` must be the tuple of the return types.
+    /// Fails with the revert data if the call failed
+    pub fn decode<S: SolType>(&self) -> Result<S::RustType, MulticallError> {
+        if !self.success {
+            return Err(MulticallError::Reverted(self.return_data.clone()));
+        }
+        let token = ethers_abi_enc::decode_params::<S>(&self.return_data)?;
+        Ok(S::detokenize(&token)?)
+    }
+}
+
+/// Aggregates calls into Multicall3 `aggregate3` invocations.
+///
+/// Results are returned in call order. If the `aggregate3` calldata would
+/// exceed the maximum calldata size, the calls are split into sequential
+/// `eth_call`s. Calls in different chunks may observe different blocks
+/// unless a block is set.
+#[derive(Debug, Clone)]
+pub struct Multicall<'a, T> {
+    provider: &'a Provider<T>,
+    address: Address,
+    block: Option<BlockId>,
+    max_calldata_size: Option<usize>,
+    calls: Vec<Call>,
+}
+
+impl<'a, T> Multicall<'a, T> {
+    /// Instantiate a multicall using the canonical Multicall3 deployment
+    pub fn new(provider: &'a Provider<T>) -> Self {
+        Self {
+            provider,
+            address: MULTICALL3_ADDRESS,
+            block: None,
+            max_calldata_size: Some(DEFAULT_MAX_CALLDATA_SIZE),
+            calls: vec![],
+        }
+    }
+
+    /// Use a Multicall3 deployment at another address
+    #[must_use = "Builder method outputs must be used"]
+    pub fn with_address(mut self, address: Address) -> Self {
+        self.address = address;
+        self
+    }
+
+    /// Execute the calls at a block. Defaults to the latest block
+    #[must_use = "Builder method outputs must be used"]
+    pub fn with_block(mut self, block: BlockId) -> Self {
+        self.block = Some(block);
+        self
+    }
+
+    /// Set the maximum `aggregate3` calldata size. `None` never splits
+    #[must_use = "Builder method outputs must be used"]
+    pub fn with_max_calldata_size(mut self, max: Option<usize>) -> Self {
+        self.max_calldata_size = max;
+        self
+    }
+
+    /// Add a call that reverts the whole multicall if it reverts
+    #[must_use = "Builder method outputs must be used"]
+    pub fn add_call(self, target: Address, data: impl Into<Vec<u8>>) -> Self {
+        self.add(target, data, false)
+    }
+
+    /// Add a call that may revert without reverting the other calls
+    #[must_use = "Builder method outputs must be used"]
+    pub fn add_fallible_call(self, target: Address, data: impl Into<Vec<u8>>) -> Self {
+        self.add(target, data, true)
+    }
+
+    fn add(mut self, target: Address, data: impl Into<Vec<u8>>, allow_failure: bool) -> Self {
+        self.calls.push(Call {
+            target,
+            allow_failure,
+            data: data.into(),
+        });
+        self
+    }
+
+    /// The calls
+    pub fn calls(&self) -> &[Call] {
+        &self.calls
+    }
+
+    /// Number of calls
+    pub fn len(&self) -> usize {
+        self.calls.len()
+    }
+
+    /// True if there are no calls
+    pub fn is_empty(&self) -> bool {
+        self.calls.is_empty()
+    }
+
+    /// Remove all calls
+    pub fn clear(&mut self) {
+        self.calls.clear();
+    }
+
+    /// Split the calls into chunks whose `aggregate3` calldata respects the
+    /// maximum size. A call larger than the maximum is sent alone
+    fn chunks(&self) -> Vec<&[Call]> {
+        let Some(max) = self.max_calldata_size else {
+            return vec![&self.calls[..]];
+        };
+
+        // the selector, the array offset and the array length
+        const BASE: usize = 4 + 32 * 2;
+        let mut chunks = vec![];
+        let mut start = 0;
+        let mut size = BASE;
+        for (i, call) in self.calls.iter().enumerate() {
+            let call_size = call.encoded_size();
+            if i > start && size + call_size > max {
+                chunks.push(&self.calls[start..i]);
+                start = i;
+                size = BASE;
+            }
+            size += call_size;
+        }
+        chunks.push(&self.calls[start..]);
+        chunks
+    }
+}
+
+impl<'a, T> Multicall<'a, T>
+where
+    T: Connection + Unpin,
+{
+    /// Execute the calls
+    pub async fn call(&self) -> Result<Vec<MulticallResult>, MulticallError> {
+        let mut results = Vec::with_capacity(self.calls.len());
+        if self.calls.is_empty() {
+            return Ok(results);
+        }
+
+        for chunk in self.chunks() {
+            let calls = chunk
+                .iter()
+                .map(|call| (call.target, call.allow_failure, call.data.clone()))
+                .collect();
+            let data = Aggregate3Params::encode_with_selector(AGGREGATE3, (calls,));
+
+            let tx = TransactionRequest::default().to(self.address).data(data);
+            let resp = self.provider.call(tx, self.block).await?;
+
+            let token = ethers_abi_enc::decode_params::<Aggregate3Return>(&resp)?;
+            let (chunk_results,) = Aggregate3Return::detokenize(&token)?;
+            if chunk_results.len() != chunk.len() {
+                return Err(MulticallError::LengthMismatch {
+                    expected: chunk.len(),
+                    actual: chunk_results.len(),
+                });
+            }
+            results.extend(chunk_results.into_iter().map(|(success, return_data)| {
+                MulticallResult {
+                    success,
+                    return_data: return_data.into(),
+                }
+            }));
+        }
+        Ok(results)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::borrow::Cow;
+
+    use ethers_pub_use::serde_json::{self, value::to_raw_value, Value};
+    use ethers_transports::{
+        common::RpcOutcome,
+        transports::{MockConnection, MockRequest},
+    };
+
+    use super::*;
+
+    /// Serves `aggregate3`, echoing each call's calldata. Calls whose
+    /// calldata starts with `0xff` revert
+    fn mock_multicall(req: &MockRequest) -> RpcOutcome {
+        let (tx, _): (TransactionRequest, Value) =
+            serde_json::from_value(req.params.clone().unwrap()).unwrap();
+        let data = tx.data.unwrap();
+        assert_eq!(data[..4], AGGREGATE3);
+
+        let token = ethers_abi_enc::decode_params::<Aggregate3Params>(&data[4..]).unwrap();
+        let (calls,) = Aggregate3Params::detokenize(&token).unwrap();
+        let results = calls
+            .into_iter()
+            .map(|(_, _, data)| (data.first() != Some(&0xff), data))
+            .collect();
+        let resp = Aggregate3Return::encode_params((results,));
+        Ok(Ok(Cow::Owned(to_raw_value(&resp).unwrap())))
+    }
+
+    fn word(n: u8) -> Vec<u8> {
+        let mut word = vec![0; 32];
+        word[31] = n;
+        word
+    }
+
+    #[tokio::test]
+    async fn it_aggregates_calls() {
+        let mock = MockConnection::new().with_handler(mock_multicall);
+        let provider = Provider::new(mock.clone());
+        let target = Address::repeat_byte(0x11);
+
+        let results = Multicall::new(&provider)
+            .add_call(target, word(1))
+            .add_fallible_call(target, vec![0xff, 0x00])
+            .add_call(target, word(3))
+            .call()
+            .await
+            .unwrap();
+
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].decode::<(sol_type::Uint<8>,)>().unwrap(), (1,));
+        assert!(matches!(
+            results[1].decode::<(sol_type::Uint<8>,)>(),
+            Err(MulticallError::Reverted(data)) if data[..] == [0xff, 0x00]
+        ));
+        assert_eq!(results[2].decode::<(sol_type::Uint<8>,)>().unwrap(), (3,));
+        assert_eq!(mock.requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn it_chunks_large_multicalls() {
+        let mock = MockConnection::new().with_handler(mock_multicall);
+        let provider = Provider::new(mock.clone());
+        let target = Address::repeat_byte(0x11);
+
+        // each call is 6 words, so 2 calls fit in 68 + 2 * 192 bytes
+        let mut multicall = Multicall::new(&provider).with_max_calldata_size(Some(68 + 2 * 192));
+        for i in 0..5 {
+            multicall = multicall.add_call(target, word(i));
+        }
+        assert_eq!(
+            multicall
+                .chunks()
+                .iter()
+                .map(|c| c.len())
+                .collect::<Vec<_>>(),
+            vec![2, 2, 1]
+        );
+
+        let results = multicall.call().await.unwrap();
+        let decoded: Vec<u8> = results
+            .iter()
+            .map(|result| result.decode::<(sol_type::Uint<8>,)>().unwrap().0)
+            .collect();
+        assert_eq!(decoded, vec![0, 1, 2, 3, 4]);
+        assert_eq!(mock.requests().len(), 3);
+    }
+}
@@ -1,6 +1,7 @@
 pub use async_trait;
 pub use eyre;
 pub use futures_channel;
+pub use futures_util;
 pub use hex;
 pub use once_cell;
 pub use pin_project;
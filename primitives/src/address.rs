@@ -0,0 +1,385 @@
+extern crate alloc;
+
+use alloc::string::String;
+use core::{fmt, str::FromStr};
+
+#[cfg(any(test, feature = "arbitrary"))]
+use arbitrary::Arbitrary;
+#[cfg(any(test, feature = "arbitrary"))]
+use proptest_derive::Arbitrary as PropTestArbitrary;
+
+use crate::{keccak256, B160};
+
+/// An Ethereum address: the last 20 bytes of the hash of an account's
+/// public key, or derived from a contract's creation.
+///
+/// A distinct type from [`B160`], so that hashes and other 20-byte values
+/// are not mistaken for addresses. Formats as its [EIP-55] checksummed hex,
+/// and parsing rejects mixed-case strings whose checksum is wrong. It
+/// serializes as lowercase hex, as JSON-RPC nodes return addresses.
+///
+/// ```
+/// use ethers_primitives::Address;
+///
+/// let weth: Address = "0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2".parse().unwrap();
+/// assert_eq!(weth.to_string(), "0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2");
+/// assert!("0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2".parse::<Address>().is_ok());
+/// assert!("0xC02AAA39b223FE8D0A0e5C4F27eAD9083C756Cc2".parse::<Address>().is_err());
+/// ```
+///
+/// [EIP-55]: https://eips.ethereum.org/EIPS/eip-55
+#[cfg_attr(any(test, feature = "arbitrary"), derive(Arbitrary, PropTestArbitrary))]
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct Address(pub B160);
+
+impl Address {
+    /// The zero address
+    pub const ZERO: Self = Self(B160([0; 20]));
+
+    /// Wrap the bytes of an address
+    pub const fn new(bytes: [u8; 20]) -> Self {
+        Self(B160(bytes))
+    }
+
+    /// The zero address
+    pub const fn zero() -> Self {
+        Self::ZERO
+    }
+
+    /// An address of 20 copies of a byte
+    pub const fn repeat_byte(byte: u8) -> Self {
+        Self::new([byte; 20])
+    }
+
+    /// Copy an address from a slice
+    ///
+    /// # Panics
+    ///
+    /// If the slice is not 20 bytes long
+    pub fn from_slice(bytes: &[u8]) -> Self {
+        Self(B160::from_slice(bytes))
+    }
+
+    /// The address as a [`B160`]
+    pub const fn as_b160(&self) -> &B160 {
+        &self.0
+    }
+
+    /// Unwrap the address into a [`B160`]
+    pub const fn into_inner(self) -> B160 {
+        self.0
+    }
+
+    /// The bytes of the address
+    pub const fn as_bytes(&self) -> &[u8; 20] {
+        &self.0 .0
+    }
+
+    /// The bytes of the address
+    pub const fn to_fixed_bytes(self) -> [u8; 20] {
+        self.0 .0
+    }
+
+    /// True if this is the zero address
+    pub fn is_zero(&self) -> bool {
+        *self == Self::ZERO
+    }
+
+    /// The [EIP-55] checksummed hex of the address, with a `0x` prefix
+    ///
+    /// [EIP-55]: https://eips.ethereum.org/EIPS/eip-55
+    pub fn to_checksum(&self) -> String {
+        let checksum = self.checksum_ascii();
+        core::str::from_utf8(&checksum)
+            .expect("hex is ASCII")
+            .into()
+    }
+
+    /// Parse a checksummed address, with or without a `0x` prefix. Unlike
+    /// [`FromStr`], all-lowercase and all-uppercase strings are rejected
+    /// unless they happen to be the checksummed form
+    pub fn parse_checksummed(s: &str) -> Result<Self, ParseAddressError> {
+        let digits = s.strip_prefix("0x").unwrap_or(s);
+        let address = Self::parse_hex(digits)?;
+        if address.checksum_ascii()[2..] != *digits.as_bytes() {
+            return Err(ParseAddressError::InvalidChecksum);
+        }
+        Ok(address)
+    }
+
+    /// Parse 40 hex digits, ignoring case
+    fn parse_hex(digits: &str) -> Result<Self, ParseAddressError> {
+        if digits.len() != 40 {
+            return Err(ParseAddressError::InvalidLength);
+        }
+        // `from_str_radix` alone would also accept a `+` sign
+        if !digits.bytes().all(|c| c.is_ascii_hexdigit()) {
+            return Err(ParseAddressError::InvalidHex);
+        }
+        let mut bytes = [0u8; 20];
+        for (byte, pair) in bytes.iter_mut().zip(digits.as_bytes().chunks(2)) {
+            let pair = core::str::from_utf8(pair).expect("hex digits are ASCII");
+            *byte = u8::from_str_radix(pair, 16).expect("checked hex digits");
+        }
+        Ok(Self::new(bytes))
+    }
+
+    /// `0x` and the checksummed hex digits, as ASCII
+    fn checksum_ascii(&self) -> [u8; 42] {
+        const DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+        let mut out = [0u8; 42];
+        out[..2].copy_from_slice(b"0x");
+        for (i, byte) in self.0 .0.iter().enumerate() {
+            out[2 + 2 * i] = DIGITS[(byte >> 4) as usize];
+            out[3 + 2 * i] = DIGITS[(byte & 0xf) as usize];
+        }
+
+        // uppercase letters whose nibble in the hash of the lowercase hex
+        // is 8 or more
+        let hash = keccak256(&out[2..]);
+        for (i, c) in out[2..].iter_mut().enumerate() {
+            let nibble = (hash[i / 2] >> (4 * (1 - i % 2))) & 0xf;
+            if nibble >= 8 {
+                c.make_ascii_uppercase();
+            }
+        }
+        out
+    }
+}
+
+impl fmt::Debug for Address {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+/// The [EIP-55] checksummed hex of the address, with a `0x` prefix
+///
+/// [EIP-55]: https://eips.ethereum.org/EIPS/eip-55
+impl fmt::Display for Address {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let checksum = self.checksum_ascii();
+        f.write_str(core::str::from_utf8(&checksum).expect("hex is ASCII"))
+    }
+}
+
+/// Lowercase hex digits. The `#` flag adds a `0x` prefix
+impl fmt::LowerHex for Address {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::LowerHex::fmt(&self.0, f)
+    }
+}
+
+/// Errors parsing an [`Address`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseAddressError {
+    /// The string is not 40 hex digits, with an optional `0x` prefix
+    InvalidLength,
+    /// A character is not a hex digit
+    InvalidHex,
+    /// The string is mixed-case, or checksummed was required, and the
+    /// case does not match the EIP-55 checksum
+    InvalidChecksum,
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseAddressError {}
+
+impl fmt::Display for ParseAddressError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidLength => f.write_str("address must be 40 hex digits"),
+            Self::InvalidHex => f.write_str("invalid hex character in address"),
+            Self::InvalidChecksum => f.write_str("invalid address checksum"),
+        }
+    }
+}
+
+/// Parses 40 hex digits, with or without a `0x` prefix. All-lowercase and
+/// all-uppercase digits are accepted, and mixed-case digits must match the
+/// EIP-55 checksum
+impl FromStr for Address {
+    type Err = ParseAddressError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let digits = s.strip_prefix("0x").unwrap_or(s);
+        let address = Self::parse_hex(digits)?;
+        let lower = !digits.bytes().any(|c| c.is_ascii_uppercase());
+        let upper = !digits.bytes().any(|c| c.is_ascii_lowercase());
+        if lower || upper {
+            return Ok(address);
+        }
+        if address.checksum_ascii()[2..] != *digits.as_bytes() {
+            return Err(ParseAddressError::InvalidChecksum);
+        }
+        Ok(address)
+    }
+}
+
+impl From<B160> for Address {
+    fn from(b160: B160) -> Self {
+        Self(b160)
+    }
+}
+
+impl From<Address> for B160 {
+    fn from(address: Address) -> Self {
+        address.0
+    }
+}
+
+impl From<[u8; 20]> for Address {
+    fn from(bytes: [u8; 20]) -> Self {
+        Self::new(bytes)
+    }
+}
+
+impl From<Address> for [u8; 20] {
+    fn from(address: Address) -> Self {
+        address.0 .0
+    }
+}
+
+impl AsRef<[u8]> for Address {
+    fn as_ref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Address {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        self.0.serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Address {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        struct Visitor;
+
+        impl serde::de::Visitor<'_> for Visitor {
+            type Value = Address;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a 0x-prefixed hex address")
+            }
+
+            fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<Self::Value, E> {
+                if !v.starts_with("0x") {
+                    return Err(E::custom("address must start with 0x"));
+                }
+                v.parse().map_err(E::custom)
+            }
+        }
+
+        deserializer.deserialize_str(Visitor)
+    }
+}
+
+#[cfg(feature = "rlp")]
+mod rlp {
+    use super::{Address, B160};
+    use ethers_rlp::{MaxEncodedLen, MaxEncodedLenAssoc};
+
+    impl ethers_rlp::Decodable for Address {
+        fn decode(buf: &mut &[u8]) -> Result<Self, ethers_rlp::DecodeError> {
+            B160::decode(buf).map(Self)
+        }
+    }
+
+    impl ethers_rlp::Encodable for Address {
+        fn length(&self) -> usize {
+            self.0.length()
+        }
+
+        fn encode(&self, out: &mut dyn bytes::BufMut) {
+            self.0.encode(out)
+        }
+    }
+
+    ethers_rlp::impl_max_encoded_len!(Address, { ethers_rlp::length_of_length(20) + 20 });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // test vectors from EIP-55
+    const CHECKSUMMED: [&str; 4] = [
+        "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed",
+        "0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359",
+        "0xdbF03B407c01E7cD3CBea99509d93f8DDDC8C6FB",
+        "0xD1220A0cf47c7B9Be7A2E6BA89F429762e7b9aDb",
+    ];
+
+    #[test]
+    fn it_checksums() {
+        for s in CHECKSUMMED {
+            let address: Address = s.parse().unwrap();
+            assert_eq!(address.to_string(), s);
+            assert_eq!(address.to_checksum(), s);
+            assert_eq!(format!("{address:?}"), s);
+            assert_eq!(format!("{address:x}"), s[2..].to_lowercase());
+            assert_eq!(Address::parse_checksummed(s), Ok(address));
+        }
+    }
+
+    #[test]
+    fn it_validates_checksums() {
+        let s = CHECKSUMMED[0];
+        let address: Address = s.parse().unwrap();
+        assert_eq!(s.to_lowercase().parse(), Ok(address));
+        assert_eq!(s[2..].to_uppercase().parse(), Ok(address));
+        assert_eq!(s[2..].parse(), Ok(address));
+
+        let wrong = s.replace('a', "A");
+        assert_eq!(
+            wrong.parse::<Address>(),
+            Err(ParseAddressError::InvalidChecksum)
+        );
+        assert_eq!(
+            Address::parse_checksummed(&s.to_lowercase()),
+            Err(ParseAddressError::InvalidChecksum)
+        );
+        assert_eq!(
+            "0x1234".parse::<Address>(),
+            Err(ParseAddressError::InvalidLength)
+        );
+        assert_eq!(
+            s.replace('b', "g").parse::<Address>(),
+            Err(ParseAddressError::InvalidHex)
+        );
+        // `u8::from_str_radix` accepts a sign
+        let signed = format!("0x{}", "+f".repeat(20));
+        assert_eq!(
+            signed.parse::<Address>(),
+            Err(ParseAddressError::InvalidHex)
+        );
+        assert_eq!(
+            Address::parse_checksummed(&signed),
+            Err(ParseAddressError::InvalidHex)
+        );
+    }
+
+    #[test]
+    fn it_converts() {
+        let address = Address::repeat_byte(0x11);
+        assert_eq!(B160::from(address), B160([0x11; 20]));
+        assert_eq!(Address::from(B160([0x11; 20])), address);
+        assert_eq!(<[u8; 20]>::from(address), [0x11; 20]);
+        assert_eq!(address.as_bytes(), &[0x11; 20]);
+        assert_eq!(address.as_b160(), &B160([0x11; 20]));
+        assert_eq!(address.into_inner(), B160([0x11; 20]));
+        assert_eq!(Address::from_slice(&[0x11; 20]), address);
+        assert!(Address::zero().is_zero());
+    }
+}
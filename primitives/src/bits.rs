@@ -27,6 +27,13 @@ construct_fixed_hash! {
     pub struct B512(64);
 }
 
+construct_fixed_hash! {
+    /// 2048 bits logs bloom filter
+    #[cfg_attr(any(test, feature = "arbitrary"), derive(Arbitrary, PropTestArbitrary))]
+    #[derive(AsRef,Deref)]
+    pub struct Bloom(256);
+}
+
 impl From<u64> for B160 {
     fn from(fr: u64) -> Self {
         let x_bytes = fr.to_be_bytes();
@@ -161,9 +168,32 @@ impl<'de> serde::Deserialize<'de> for B512 {
     }
 }
 
+#[cfg(feature = "serde")]
+impl serde::Serialize for Bloom {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let mut slice = [0u8; 2 + 2 * 256];
+        serialize::serialize_raw(&mut slice, &self.0, serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Bloom {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let mut bytes = [0u8; 256];
+        serialize::deserialize_check_len(deserializer, serialize::ExpectedLen::Exact(&mut bytes))?;
+        Ok(Bloom(bytes))
+    }
+}
+
 // code optained from: https://docs.rs/impl-serde/0.4.0/impl_serde/
 #[cfg(feature = "serde")]
-mod serialize {
+pub(crate) mod serialize {
     extern crate alloc;
     use alloc::string::String;
     use core::{fmt, result::Result};
@@ -355,7 +385,7 @@ mod serialize {
 
 #[cfg(feature = "rlp")]
 mod rlp {
-    use super::{B160, B256, B512};
+    use super::{Bloom, B160, B256, B512};
     use ethers_rlp::{MaxEncodedLen, MaxEncodedLenAssoc};
     macro_rules! fixed_hash_impl {
         ($t:ty) => {
@@ -381,6 +411,7 @@ mod rlp {
     fixed_hash_impl!(B160);
     fixed_hash_impl!(B256);
     fixed_hash_impl!(B512);
+    fixed_hash_impl!(Bloom);
 }
 
 #[cfg(test)]
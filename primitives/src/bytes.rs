@@ -0,0 +1,339 @@
+extern crate alloc;
+
+use alloc::vec::Vec;
+use core::{fmt, ops::Deref};
+
+/// Arbitrary bytes, formatted and serialized as a `0x`-prefixed hex string.
+///
+/// Wraps [`bytes::Bytes`], so clones share the underlying buffer rather than
+/// copying it
+#[derive(Clone, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Bytes(pub bytes::Bytes);
+
+impl Bytes {
+    /// Instantiate empty bytes
+    pub const fn new() -> Self {
+        Self(bytes::Bytes::new())
+    }
+
+    /// Wrap a static slice without copying it
+    pub const fn from_static(bytes: &'static [u8]) -> Self {
+        Self(bytes::Bytes::from_static(bytes))
+    }
+
+    /// Copy a slice
+    pub fn copy_from_slice(bytes: &[u8]) -> Self {
+        Self(bytes::Bytes::copy_from_slice(bytes))
+    }
+
+    /// A subrange of these bytes, sharing their buffer
+    ///
+    /// # Panics
+    ///
+    /// If the range is out of bounds
+    pub fn slice(&self, range: impl core::ops::RangeBounds<usize>) -> Self {
+        Self(self.0.slice(range))
+    }
+
+    /// Copy the bytes into a vector
+    pub fn to_vec(&self) -> Vec<u8> {
+        self.0.to_vec()
+    }
+}
+
+impl fmt::Debug for Bytes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Bytes({self})")
+    }
+}
+
+impl fmt::Display for Bytes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("0x")?;
+        fmt::LowerHex::fmt(self, f)
+    }
+}
+
+/// Hex digits without a `0x` prefix
+impl fmt::LowerHex for Bytes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.iter().try_for_each(|byte| write!(f, "{byte:02x}"))
+    }
+}
+
+impl Deref for Bytes {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl AsRef<[u8]> for Bytes {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl IntoIterator for Bytes {
+    type Item = u8;
+    type IntoIter = bytes::buf::IntoIter<bytes::Bytes>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Bytes {
+    type Item = &'a u8;
+    type IntoIter = core::slice::Iter<'a, u8>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl FromIterator<u8> for Bytes {
+    fn from_iter<I: IntoIterator<Item = u8>>(iter: I) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+impl From<bytes::Bytes> for Bytes {
+    fn from(bytes: bytes::Bytes) -> Self {
+        Self(bytes)
+    }
+}
+
+impl From<Vec<u8>> for Bytes {
+    fn from(bytes: Vec<u8>) -> Self {
+        Self(bytes.into())
+    }
+}
+
+impl From<&[u8]> for Bytes {
+    fn from(bytes: &[u8]) -> Self {
+        Self::copy_from_slice(bytes)
+    }
+}
+
+impl<const N: usize> From<[u8; N]> for Bytes {
+    fn from(bytes: [u8; N]) -> Self {
+        bytes.to_vec().into()
+    }
+}
+
+impl From<Bytes> for bytes::Bytes {
+    fn from(bytes: Bytes) -> Self {
+        bytes.0
+    }
+}
+
+impl From<Bytes> for Vec<u8> {
+    fn from(bytes: Bytes) -> Self {
+        bytes.0.into()
+    }
+}
+
+impl PartialEq<[u8]> for Bytes {
+    fn eq(&self, other: &[u8]) -> bool {
+        self[..] == *other
+    }
+}
+
+impl PartialEq<&[u8]> for Bytes {
+    fn eq(&self, other: &&[u8]) -> bool {
+        self[..] == **other
+    }
+}
+
+impl<const N: usize> PartialEq<[u8; N]> for Bytes {
+    fn eq(&self, other: &[u8; N]) -> bool {
+        self[..] == other[..]
+    }
+}
+
+impl<const N: usize> PartialEq<&[u8; N]> for Bytes {
+    fn eq(&self, other: &&[u8; N]) -> bool {
+        self[..] == other[..]
+    }
+}
+
+impl PartialEq<Vec<u8>> for Bytes {
+    fn eq(&self, other: &Vec<u8>) -> bool {
+        self[..] == other[..]
+    }
+}
+
+impl PartialEq<Bytes> for Vec<u8> {
+    fn eq(&self, other: &Bytes) -> bool {
+        self[..] == other[..]
+    }
+}
+
+impl PartialEq<Bytes> for [u8] {
+    fn eq(&self, other: &Bytes) -> bool {
+        *self == other[..]
+    }
+}
+
+/// Error parsing bytes from a hex string
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParseBytesError {
+    /// A character is not a hex digit
+    InvalidCharacter {
+        /// The character
+        character: char,
+        /// Its index in the string, including any `0x` prefix
+        index: usize,
+    },
+    /// The string has an odd number of hex digits
+    OddLength,
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseBytesError {}
+
+impl fmt::Display for ParseBytesError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidCharacter { character, index } => {
+                write!(f, "invalid hex character {character:?} at {index}")
+            }
+            Self::OddLength => f.write_str("odd number of hex digits"),
+        }
+    }
+}
+
+/// Parses hex digits, with or without a `0x` prefix
+impl core::str::FromStr for Bytes {
+    type Err = ParseBytesError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (digits, offset) = s.strip_prefix("0x").map_or((s, 0), |digits| (digits, 2));
+        if let Some((index, character)) =
+            digits.char_indices().find(|(_, c)| !c.is_ascii_hexdigit())
+        {
+            return Err(ParseBytesError::InvalidCharacter {
+                character,
+                index: index + offset,
+            });
+        }
+        if digits.len() % 2 != 0 {
+            return Err(ParseBytesError::OddLength);
+        }
+        let nibble = |c: u8| char::from(c).to_digit(16).unwrap_or_default() as u8;
+        Ok(digits
+            .as_bytes()
+            .chunks_exact(2)
+            .map(|pair| nibble(pair[0]) << 4 | nibble(pair[1]))
+            .collect::<Vec<u8>>()
+            .into())
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use core::fmt;
+
+    use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::Bytes;
+
+    impl Serialize for Bytes {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            serializer.collect_str(self)
+        }
+    }
+
+    impl<'de> Deserialize<'de> for Bytes {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            struct Visitor;
+
+            impl de::Visitor<'_> for Visitor {
+                type Value = Bytes;
+
+                fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                    f.write_str("a hex string")
+                }
+
+                fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+                    v.parse().map_err(E::custom)
+                }
+            }
+
+            deserializer.deserialize_str(Visitor)
+        }
+    }
+}
+
+/// Encoded as an RLP string. Note that `Vec<u8>` itself encodes as a list of
+/// integers, as with any other `Vec`
+#[cfg(feature = "rlp")]
+mod rlp {
+    use ethers_rlp::{BufMut, Decodable, DecodeError, Encodable, Header};
+
+    use super::Bytes;
+
+    impl Encodable for Bytes {
+        fn length(&self) -> usize {
+            self.as_ref().length()
+        }
+
+        fn encode(&self, out: &mut dyn BufMut) {
+            self.as_ref().encode(out)
+        }
+    }
+
+    impl Decodable for Bytes {
+        fn decode(buf: &mut &[u8]) -> Result<Self, DecodeError> {
+            let header = Header::decode(buf)?;
+            if header.list {
+                return Err(DecodeError::UnexpectedList);
+            }
+            if buf.len() < header.payload_length {
+                return Err(DecodeError::InputTooShort);
+            }
+            let (bytes, rest) = buf.split_at(header.payload_length);
+            *buf = rest;
+            Ok(Bytes::copy_from_slice(bytes))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_formats_and_parses_hex() {
+        let bytes = Bytes::from_static(&[0xde, 0xad, 0x00]);
+        assert_eq!(bytes.to_string(), "0xdead00");
+        assert_eq!(format!("{bytes:?}"), "Bytes(0xdead00)");
+        assert_eq!("0xDEAD00".parse::<Bytes>().unwrap(), bytes);
+        assert_eq!("dead00".parse::<Bytes>().unwrap(), bytes);
+        assert_eq!("0x".parse::<Bytes>().unwrap(), Bytes::new());
+        assert_eq!("0xabc".parse::<Bytes>(), Err(ParseBytesError::OddLength));
+        assert_eq!(
+            "0xzz".parse::<Bytes>(),
+            Err(ParseBytesError::InvalidCharacter {
+                character: 'z',
+                index: 2
+            })
+        );
+    }
+
+    #[test]
+    fn it_shares_buffers() {
+        let bytes = Bytes::from(vec![1, 2, 3, 4]);
+        let slice = bytes.slice(1..3);
+        assert_eq!(slice, [2u8, 3][..]);
+        assert_eq!(bytes.clone().0.as_ptr(), bytes.0.as_ptr());
+    }
+}
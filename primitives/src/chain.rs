@@ -0,0 +1,143 @@
+use core::time::Duration;
+
+/// Well-known chains, and their chain-specific constants
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Chain {
+    /// Ethereum mainnet
+    Mainnet,
+    /// Ethereum Sepolia testnet
+    Sepolia,
+    /// Ethereum Holesky testnet
+    Holesky,
+    /// OP Mainnet
+    Optimism,
+    /// OP Sepolia testnet
+    OptimismSepolia,
+    /// Arbitrum One
+    Arbitrum,
+    /// Arbitrum Sepolia testnet
+    ArbitrumSepolia,
+    /// Base
+    Base,
+    /// Base Sepolia testnet
+    BaseSepolia,
+    /// Polygon PoS
+    Polygon,
+    /// Polygon Amoy testnet
+    PolygonAmoy,
+    /// BNB Smart Chain
+    BinanceSmartChain,
+    /// Gnosis Chain
+    Gnosis,
+    /// Avalanche C-Chain
+    Avalanche,
+}
+
+impl Chain {
+    /// All known chains
+    pub const ALL: [Chain; 14] = [
+        Chain::Mainnet,
+        Chain::Sepolia,
+        Chain::Holesky,
+        Chain::Optimism,
+        Chain::OptimismSepolia,
+        Chain::Arbitrum,
+        Chain::ArbitrumSepolia,
+        Chain::Base,
+        Chain::BaseSepolia,
+        Chain::Polygon,
+        Chain::PolygonAmoy,
+        Chain::BinanceSmartChain,
+        Chain::Gnosis,
+        Chain::Avalanche,
+    ];
+
+    /// The EIP-155 chain id
+    pub const fn id(self) -> u64 {
+        match self {
+            Chain::Mainnet => 1,
+            Chain::Sepolia => 11155111,
+            Chain::Holesky => 17000,
+            Chain::Optimism => 10,
+            Chain::OptimismSepolia => 11155420,
+            Chain::Arbitrum => 42161,
+            Chain::ArbitrumSepolia => 421614,
+            Chain::Base => 8453,
+            Chain::BaseSepolia => 84532,
+            Chain::Polygon => 137,
+            Chain::PolygonAmoy => 80002,
+            Chain::BinanceSmartChain => 56,
+            Chain::Gnosis => 100,
+            Chain::Avalanche => 43114,
+        }
+    }
+
+    /// The chain with the given id, if known
+    pub const fn from_id(id: u64) -> Option<Self> {
+        let mut i = 0;
+        while i < Self::ALL.len() {
+            if Self::ALL[i].id() == id {
+                return Some(Self::ALL[i]);
+            }
+            i += 1;
+        }
+        None
+    }
+
+    /// Decimals of the native currency
+    pub const fn native_decimals(self) -> u8 {
+        18
+    }
+
+    /// The average time between blocks
+    pub const fn average_block_time(self) -> Duration {
+        match self {
+            Chain::Mainnet | Chain::Sepolia | Chain::Holesky => Duration::from_secs(12),
+            Chain::Optimism
+            | Chain::OptimismSepolia
+            | Chain::Base
+            | Chain::BaseSepolia
+            | Chain::Polygon
+            | Chain::PolygonAmoy
+            | Chain::Avalanche => Duration::from_secs(2),
+            Chain::Arbitrum | Chain::ArbitrumSepolia => Duration::from_millis(250),
+            Chain::BinanceSmartChain => Duration::from_secs(3),
+            Chain::Gnosis => Duration::from_secs(5),
+        }
+    }
+
+    /// Whether the chain supports EIP-1559 transactions. Others only accept
+    /// legacy gas pricing
+    pub const fn supports_eip1559(self) -> bool {
+        !matches!(self, Chain::BinanceSmartChain)
+    }
+}
+
+impl From<Chain> for u64 {
+    fn from(chain: Chain) -> Self {
+        chain.id()
+    }
+}
+
+impl TryFrom<u64> for Chain {
+    type Error = u64;
+
+    /// Fails with the id of unknown chains
+    fn try_from(id: u64) -> Result<Self, Self::Error> {
+        Self::from_id(id).ok_or(id)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_round_trips_chain_ids() {
+        for chain in Chain::ALL {
+            assert_eq!(Chain::try_from(chain.id()), Ok(chain));
+        }
+        assert_eq!(Chain::from_id(8453), Some(Chain::Base));
+        assert_eq!(Chain::try_from(31337), Err(31337));
+    }
+}
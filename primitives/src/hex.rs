@@ -0,0 +1,159 @@
+//! Compile-time hex decoding, for embedding constants.
+//!
+//! The [`hex!`](crate::hex!), [`address!`](crate::address!),
+//! [`b256!`](crate::b256!) and [`bytes!`](crate::bytes!) macros decode a
+//! string literal in a `const` item, so malformed literals fail to compile.
+//! Literals may have a `0x` prefix, and may contain whitespace, e.g. to lay
+//! out ABI words one per line
+
+/// Skip leading whitespace and a `0x` prefix
+const fn digits_start(s: &[u8]) -> usize {
+    let mut i = 0;
+    while i < s.len() && s[i].is_ascii_whitespace() {
+        i += 1;
+    }
+    if i + 1 < s.len() && s[i] == b'0' && s[i + 1] == b'x' {
+        i += 2;
+    }
+    i
+}
+
+const fn nibble(c: u8) -> u8 {
+    match c {
+        b'0'..=b'9' => c - b'0',
+        b'a'..=b'f' => c - b'a' + 10,
+        b'A'..=b'F' => c - b'A' + 10,
+        _ => panic!("invalid hex character"),
+    }
+}
+
+/// The number of bytes a hex string decodes to
+///
+/// # Panics
+///
+/// If the string has an odd number of hex digits
+pub const fn decoded_len(s: &str) -> usize {
+    let s = s.as_bytes();
+    let mut i = digits_start(s);
+    let mut digits = 0;
+    while i < s.len() {
+        if !s[i].is_ascii_whitespace() {
+            digits += 1;
+        }
+        i += 1;
+    }
+    if digits % 2 != 0 {
+        panic!("odd number of hex digits");
+    }
+    digits / 2
+}
+
+/// Decode a hex string of exactly `N` bytes
+///
+/// # Panics
+///
+/// If the string contains a non-hex character, or does not decode to `N`
+/// bytes
+pub const fn decode_array<const N: usize>(s: &str) -> [u8; N] {
+    let s = s.as_bytes();
+    let mut out = [0u8; N];
+    let mut i = digits_start(s);
+    let mut digit = 0;
+    while i < s.len() {
+        let c = s[i];
+        i += 1;
+        if c.is_ascii_whitespace() {
+            continue;
+        }
+        if digit / 2 >= N {
+            panic!("hex string is longer than expected");
+        }
+        let shift = if digit % 2 == 0 { 4 } else { 0 };
+        out[digit / 2] |= nibble(c) << shift;
+        digit += 1;
+    }
+    if digit != 2 * N {
+        panic!("hex string is shorter than expected");
+    }
+    out
+}
+
+/// Decode hex string literals to a byte array at compile time. Several
+/// literals are concatenated
+///
+/// ```
+/// # use ethers_primitives::hex;
+/// const SELECTOR: [u8; 4] = hex!("0xa9059cbb");
+/// assert_eq!(SELECTOR, [0xa9, 0x05, 0x9c, 0xbb]);
+/// ```
+#[macro_export]
+macro_rules! hex {
+    ($($s:literal)+) => {{
+        const BYTES: [u8; $crate::hex::decoded_len(concat!($($s),+))] =
+            $crate::hex::decode_array(concat!($($s),+));
+        BYTES
+    }};
+}
+
+/// Decode a hex string literal to an [`Address`](crate::Address) at compile
+/// time
+///
+/// ```
+/// # use ethers_primitives::{address, Address};
+/// const WETH: Address = address!("0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2");
+/// ```
+#[macro_export]
+macro_rules! address {
+    ($s:literal) => {{
+        const ADDRESS: $crate::Address = $crate::Address::new($crate::hex::decode_array::<20>($s));
+        ADDRESS
+    }};
+}
+
+/// Decode a hex string literal to a [`B256`](crate::B256) at compile time
+#[macro_export]
+macro_rules! b256 {
+    ($s:literal) => {{
+        const WORD: $crate::B256 = $crate::B256($crate::hex::decode_array::<32>($s));
+        WORD
+    }};
+}
+
+/// Decode a hex string literal of any length to a `&'static [u8]` at
+/// compile time
+#[macro_export]
+macro_rules! bytes {
+    ($($s:literal)+) => {{
+        const BYTES: &[u8] = &$crate::hex!($($s)+);
+        BYTES
+    }};
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{keccak256, Address};
+
+    #[test]
+    fn it_decodes_literals() {
+        assert_eq!(hex!("0x"), [0u8; 0]);
+        assert_eq!(hex!("00fF10"), [0x00, 0xff, 0x10]);
+        assert_eq!(
+            hex!(
+                "
+                0001
+                0203
+                "
+            ),
+            [0, 1, 2, 3]
+        );
+        assert_eq!(hex!("00" "01"), [0, 1]);
+        assert_eq!(bytes!("0xdeadbeef"), &[0xde, 0xad, 0xbe, 0xef]);
+
+        let address: Address = address!("0x0101010101010101010101010101010101010101");
+        assert_eq!(address, Address::repeat_byte(1));
+        assert_eq!(
+            b256!("c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470"),
+            keccak256([])
+        );
+    }
+}
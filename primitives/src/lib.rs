@@ -9,12 +9,35 @@
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
+mod address;
+pub use address::{Address, ParseAddressError};
+
 mod bits;
 
-pub use bits::{B160, B256, B512};
+pub use bits::{Bloom, B160, B256, B512};
+
+mod bytes;
+pub use self::bytes::{Bytes, ParseBytesError};
+
+mod chain;
+pub use chain::Chain;
+
+pub mod hex;
+
+#[cfg(feature = "k256")]
+mod signature;
+#[cfg(feature = "k256")]
+pub use signature::{public_key_address, Signature};
+
+#[cfg(feature = "serde")]
+pub mod serde_helper;
+
+pub mod uint;
+pub use uint::U256Ext;
+
+mod utils;
+pub use utils::{create_address, format_units, keccak256, parse_units, Units, UnitsError};
 
-/// Address type is first 20 bytes of hash of ethereum account
-pub type Address = B160;
 /// Hash, in Ethereum usually kecack256.
 pub type Hash = B256;
 
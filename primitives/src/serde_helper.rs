@@ -0,0 +1,115 @@
+//! Serde helpers for the JSON-RPC encodings of numbers
+//!
+//! JSON-RPC distinguishes quantities, which are minimal `0x`-prefixed hex
+//! (`0x0`, `0x1b4`), from data, which is hex padded to its full byte length.
+//! Use these modules with `#[serde(with = "...")]` on fields whose Rust type
+//! does not already serialize the right way.
+
+/// Serialize an integer as a JSON-RPC quantity, e.g. a `u64` nonce as `0x1b4`
+pub mod quantity {
+    use crate::U256;
+    use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+    /// Serialize as minimal `0x`-prefixed hex
+    pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Copy + Into<U256>,
+        S: Serializer,
+    {
+        (*value).into().serialize(serializer)
+    }
+
+    /// Deserialize from a hex string, failing if the value does not fit in `T`
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: TryFrom<U256>,
+        D: Deserializer<'de>,
+    {
+        let value = U256::deserialize(deserializer)?;
+        T::try_from(value).map_err(|_| de::Error::custom("quantity out of range"))
+    }
+
+    /// Serialize an optional integer as a JSON-RPC quantity or `null`
+    pub mod opt {
+        use crate::U256;
+        use serde::{Deserialize, Deserializer, Serializer};
+
+        /// Serialize as minimal `0x`-prefixed hex, or `null`
+        pub fn serialize<T, S>(value: &Option<T>, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            T: Copy + Into<U256>,
+            S: Serializer,
+        {
+            match value {
+                Some(value) => super::serialize(value, serializer),
+                None => serializer.serialize_none(),
+            }
+        }
+
+        /// Deserialize from a hex string or `null`
+        pub fn deserialize<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
+        where
+            T: TryFrom<U256>,
+            D: Deserializer<'de>,
+        {
+            #[derive(Deserialize)]
+            #[serde(transparent)]
+            struct Quantity<T: TryFrom<U256>>(#[serde(with = "super")] T);
+
+            Ok(Option::<Quantity<T>>::deserialize(deserializer)?.map(|q| q.0))
+        }
+    }
+}
+
+/// Serialize a `U256` as 32 bytes of JSON-RPC data, e.g. a storage value
+pub mod data {
+    use crate::{B256, U256};
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    /// Serialize as `0x`-prefixed hex padded to 32 bytes
+    pub fn serialize<S>(value: &U256, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        B256::from(*value).serialize(serializer)
+    }
+
+    /// Deserialize from a 32-byte hex string
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<U256, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        B256::deserialize(deserializer).map(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{data, quantity};
+    use crate::U256;
+    use serde::de::{
+        value::{Error, StrDeserializer, UnitDeserializer},
+        IntoDeserializer,
+    };
+
+    fn de(value: &str) -> StrDeserializer<'_, Error> {
+        value.into_deserializer()
+    }
+
+    #[test]
+    fn it_deserializes_quantities() {
+        assert_eq!(quantity::deserialize(de("0x0")), Ok(0u8));
+        assert_eq!(quantity::deserialize(de("0xff")), Ok(255u8));
+        assert!(quantity::deserialize::<u8, _>(de("0x100")).is_err());
+
+        let null: UnitDeserializer<Error> = ().into_deserializer();
+        assert_eq!(quantity::opt::deserialize::<u64, _>(null), Ok(None));
+    }
+
+    #[test]
+    fn it_deserializes_data() {
+        let slot = "0x00000000000000000000000000000000000000000000000000000000000001b4";
+        assert_eq!(data::deserialize(de(slot)), Ok(U256::from(0x1b4)));
+        assert!(data::deserialize(de("0x1b4")).is_err());
+    }
+}
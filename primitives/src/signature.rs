@@ -0,0 +1,203 @@
+use k256::ecdsa::{self, RecoveryId, VerifyingKey};
+
+use crate::{keccak256, uint, Address, B256, U256};
+
+/// Half the order of the secp256k1 curve. EIP-2 requires `s` to be at most
+/// this
+const SECP256K1N_HALF: U256 =
+    uint!(0x7FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF5D576E7357A4501DDFE92F46681B20A0_U256);
+
+/// A recoverable secp256k1 signature
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Signature {
+    /// The `r` value
+    pub r: U256,
+    /// The `s` value
+    pub s: U256,
+    /// The recovery id plus 27
+    pub v: u64,
+}
+
+impl Signature {
+    /// The 65-byte `r || s || v` encoding
+    pub fn to_bytes(&self) -> [u8; 65] {
+        let mut bytes = [0u8; 65];
+        bytes[..32].copy_from_slice(&self.r.to_be_bytes::<32>());
+        bytes[32..64].copy_from_slice(&self.s.to_be_bytes::<32>());
+        bytes[64] = self.v as u8;
+        bytes
+    }
+
+    /// Parse the 65-byte `r || s || v` encoding. A `v` of 0 or 1 is read as
+    /// the bare recovery id
+    pub fn from_bytes(bytes: &[u8; 65]) -> Self {
+        let v = bytes[64] as u64;
+        Self {
+            r: U256::from_be_slice(&bytes[..32]),
+            s: U256::from_be_slice(&bytes[32..64]),
+            v: if v < 27 { v + 27 } else { v },
+        }
+    }
+
+    /// Whether `s` is in the lower half of the curve order, as EIP-2
+    /// requires
+    pub fn is_normalized(&self) -> bool {
+        self.s <= SECP256K1N_HALF
+    }
+
+    /// The equivalent signature with `s` in the lower half of the curve
+    /// order, flipping the recovery id if `s` is negated
+    #[must_use]
+    pub fn normalize_s(self) -> Self {
+        if self.is_normalized() {
+            return self;
+        }
+        let n = SECP256K1N_HALF * U256::from(2) + U256::from(1);
+        Self {
+            r: self.r,
+            s: n - self.s,
+            v: if self.v == 27 { 28 } else { 27 },
+        }
+    }
+
+    /// Recover the address that signed a message hash. Signatures with a
+    /// high `s` are rejected
+    pub fn recover(&self, message_hash: B256) -> Option<Address> {
+        let (sig, recid) = self.to_k256()?;
+        VerifyingKey::recover_from_prehash(message_hash.as_bytes(), &sig, recid)
+            .ok()
+            .map(|key| public_key_address(&key))
+    }
+
+    /// Whether `address` signed the message hash
+    pub fn verify(&self, message_hash: B256, address: Address) -> bool {
+        self.recover(message_hash) == Some(address)
+    }
+
+    fn to_k256(self) -> Option<(ecdsa::Signature, RecoveryId)> {
+        let recid = RecoveryId::from_byte(self.v.checked_sub(27)?.try_into().ok()?)?;
+        let mut rs = [0u8; 64];
+        rs.copy_from_slice(&self.to_bytes()[..64]);
+        let sig = ecdsa::Signature::from_slice(&rs).ok()?;
+        Some((sig, recid))
+    }
+}
+
+/// Converts the output of `k256` recoverable signing, normalizing `s`
+impl From<(ecdsa::Signature, RecoveryId)> for Signature {
+    fn from((sig, recid): (ecdsa::Signature, RecoveryId)) -> Self {
+        let (r, s) = sig.split_bytes();
+        Self {
+            r: U256::from_be_slice(&r),
+            s: U256::from_be_slice(&s),
+            v: 27 + recid.to_byte() as u64,
+        }
+        .normalize_s()
+    }
+}
+
+impl core::fmt::Display for Signature {
+    /// `0x`-prefixed hex of the 65-byte encoding
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("0x")?;
+        self.to_bytes()
+            .iter()
+            .try_for_each(|byte| write!(f, "{byte:02x}"))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Signature {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let mut slice = [0u8; 2 + 2 * 65];
+        crate::bits::serialize::serialize_raw(&mut slice, &self.to_bytes(), serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Signature {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        use crate::bits::serialize::{deserialize_check_len, ExpectedLen};
+
+        let mut bytes = [0u8; 65];
+        deserialize_check_len(deserializer, ExpectedLen::Exact(&mut bytes))?;
+        Ok(Self::from_bytes(&bytes))
+    }
+}
+
+/// The address of a public key
+pub fn public_key_address(key: &VerifyingKey) -> Address {
+    let point = key.to_encoded_point(false);
+    Address::from_slice(&keccak256(&point.as_bytes()[1..])[12..])
+}
+
+#[cfg(test)]
+mod test {
+    use k256::ecdsa::SigningKey;
+
+    use super::*;
+
+    fn key() -> SigningKey {
+        SigningKey::from_slice(&[0x42; 32]).unwrap()
+    }
+
+    #[test]
+    fn it_recovers_and_verifies() {
+        let hash = keccak256("hello");
+        let address = public_key_address(key().verifying_key());
+        let sig: Signature = key()
+            .sign_prehash_recoverable(hash.as_bytes())
+            .unwrap()
+            .into();
+
+        assert_eq!(sig.recover(hash), Some(address));
+        assert!(sig.verify(hash, address));
+        assert!(!sig.verify(keccak256("world"), address));
+        assert_eq!(Signature::from_bytes(&sig.to_bytes()), sig);
+    }
+
+    #[test]
+    fn it_normalizes_s() {
+        let hash = keccak256("hello");
+        let sig: Signature = key()
+            .sign_prehash_recoverable(hash.as_bytes())
+            .unwrap()
+            .into();
+        assert!(sig.is_normalized());
+
+        let n = SECP256K1N_HALF * U256::from(2) + U256::from(1);
+        let high = Signature {
+            s: n - sig.s,
+            v: if sig.v == 27 { 28 } else { 27 },
+            ..sig
+        };
+        assert!(!high.is_normalized());
+        assert_eq!(high.recover(hash), None);
+        assert_eq!(high.normalize_s(), sig);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn it_deserializes_hex() {
+        use serde::{de::IntoDeserializer, Deserialize};
+
+        let sig = Signature {
+            r: U256::from(1),
+            s: U256::from(2),
+            v: 28,
+        };
+        let hex = sig.to_string();
+        assert_eq!(hex.len(), 2 + 130);
+        assert!(hex.ends_with("021c"));
+
+        let de: serde::de::value::StrDeserializer<'_, serde::de::value::Error> =
+            hex.as_str().into_deserializer();
+        assert_eq!(Signature::deserialize(de).unwrap(), sig);
+    }
+}
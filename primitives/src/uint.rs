@@ -0,0 +1,85 @@
+//! Parsing, conversions and overflow-safe math for [`U256`].
+//!
+//! Most of this is provided by `ruint` on [`U256`] itself:
+//!
+//! - `checked_*`, `saturating_*`, `wrapping_*` and `overflowing_*`
+//!   arithmetic
+//! - `TryFrom<U256>` for primitive integers, failing with
+//!   [`FromUintError`], and [`U256::saturating_to`]
+//! - [`FromStr`](core::str::FromStr), accepting `0x`-prefixed hex or
+//!   decimal, and [`U256::from_str_radix`]
+//!
+//! [`B256`](crate::B256) converts to and from [`U256`] with `From`, as a
+//! big-endian word. Use `into()`: `U256::from` resolves to `ruint`'s inherent
+//! `from` instead. [`U256Ext`] adds the rest.
+//!
+//! ```
+//! use ethers_primitives::{U256Ext, B256, U256};
+//!
+//! let wei = U256::from_dec_str("1000000000000000000").unwrap();
+//! assert!(u64::try_from(wei * U256::from(100)).is_err());
+//! let word: B256 = wei.into();
+//! assert_eq!(U256::from_be_bytes(word.0), wei);
+//! assert_eq!(Into::<U256>::into(word), wei);
+//!
+//! // 112.5% of the fee, without overflowing on the product
+//! let bumped = U256::MAX.mul_div(U256::from(1125), U256::from(1000));
+//! assert_eq!(bumped, None);
+//! ```
+
+use ruint::{
+    aliases::{U256, U512},
+    UintTryFrom,
+};
+
+pub use ruint::{FromUintError, ParseError, ToUintError};
+
+/// Helpers for [`U256`] that `ruint` does not provide
+pub trait U256Ext: Sized {
+    /// Parse a decimal string, without a radix prefix
+    fn from_dec_str(s: &str) -> Result<Self, ParseError>;
+
+    /// `self * numerator / denominator`, rounding down, computed without
+    /// overflowing on the product. `None` if the denominator is zero or the
+    /// result does not fit
+    fn mul_div(self, numerator: Self, denominator: Self) -> Option<Self>;
+}
+
+impl U256Ext for U256 {
+    fn from_dec_str(s: &str) -> Result<Self, ParseError> {
+        Self::from_str_radix(s, 10)
+    }
+
+    fn mul_div(self, numerator: Self, denominator: Self) -> Option<Self> {
+        if denominator.is_zero() {
+            return None;
+        }
+        let product: U512 = self.widening_mul(numerator);
+        U256::uint_try_from(product / U512::from(denominator)).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_parses_decimal() {
+        assert_eq!(U256::from_dec_str("42"), Ok(U256::from(42)));
+        assert!(U256::from_dec_str("0x2a").is_err());
+        assert!(U256::from_dec_str(&"9".repeat(80)).is_err());
+    }
+
+    #[test]
+    fn it_mul_divs() {
+        assert_eq!(
+            U256::from(30).mul_div(U256::from(1125), U256::from(1000)),
+            Some(U256::from(33))
+        );
+        assert_eq!(
+            U256::MAX.mul_div(U256::from(3), U256::from(4)),
+            Some(U256::MAX / U256::from(4) * U256::from(3) + U256::from(2))
+        );
+        assert_eq!(U256::from(1).mul_div(U256::from(1), U256::ZERO), None);
+    }
+}
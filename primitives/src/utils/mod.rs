@@ -0,0 +1,84 @@
+use tiny_keccak::{Hasher, Keccak};
+
+use crate::{Address, B256};
+
+mod units;
+pub use units::{format_units, parse_units, Units, UnitsError};
+
+/// Compute the keccak256 hash of the input
+pub fn keccak256(bytes: impl AsRef<[u8]>) -> B256 {
+    let mut output = [0u8; 32];
+    let mut hasher = Keccak::v256();
+    hasher.update(bytes.as_ref());
+    hasher.finalize(&mut output);
+    B256(output)
+}
+
+/// The address of a contract created by `sender` with a `CREATE`
+/// transaction or opcode at `nonce`: the last 20 bytes of the hash of
+/// `rlp([sender, nonce])`
+pub fn create_address(sender: Address, nonce: u64) -> Address {
+    let nonce_bytes = nonce.to_be_bytes();
+    let nonce_bytes = &nonce_bytes[nonce.leading_zeros() as usize / 8..];
+
+    let mut rlp = [0u8; 1 + 21 + 9];
+    rlp[1] = 0x80 + 20;
+    rlp[2..22].copy_from_slice(sender.as_bytes());
+    let len = match nonce_bytes {
+        [] => {
+            rlp[22] = 0x80;
+            23
+        }
+        [byte] if *byte < 0x80 => {
+            rlp[22] = *byte;
+            23
+        }
+        _ => {
+            rlp[22] = 0x80 + nonce_bytes.len() as u8;
+            rlp[23..23 + nonce_bytes.len()].copy_from_slice(nonce_bytes);
+            23 + nonce_bytes.len()
+        }
+    };
+    rlp[0] = 0xc0 + (len - 1) as u8;
+    Address::from_slice(&keccak256(&rlp[..len])[12..])
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_hashes() {
+        assert_eq!(
+            keccak256([]),
+            "0xc5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470"
+                .parse()
+                .unwrap()
+        );
+        assert_eq!(
+            keccak256("hello world"),
+            "0x47173285a8d7341e5e972fc677286384f802f8ef42a5ec5f03bbfa254cb01fad"
+                .parse()
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn it_computes_create_addresses() {
+        let sender: Address = "0x6ac7ea33f8831ea9dcc53393aaa88b25a785dbf0"
+            .parse()
+            .unwrap();
+        let expected = [
+            "0xcd234a471b72ba2f1ccf0a70fcaba648a5eecd8d",
+            "0x343c43a37d37dff08ae8c4a11544c718abb4fcf8",
+            "0xf778b86fa74e846c4f0a1fbd1335fe81c00a0c91",
+            "0xfffd933a0bc612844eaf0c6fe3e5b8e9b6c1d19c",
+        ];
+        for (nonce, expected) in expected.iter().enumerate() {
+            assert_eq!(
+                create_address(sender, nonce as u64),
+                expected.parse().unwrap()
+            );
+        }
+    }
+}
@@ -0,0 +1,249 @@
+extern crate alloc;
+
+use alloc::string::{String, ToString};
+use core::fmt;
+
+use crate::U256;
+
+/// The largest number of decimals whose scale still fits in a `U256`
+const MAX_DECIMALS: u8 = 77;
+
+/// A denomination of ether, or an arbitrary number of decimals
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Units {
+    /// 10^0
+    Wei,
+    /// 10^3
+    Kwei,
+    /// 10^6
+    Mwei,
+    /// 10^9
+    Gwei,
+    /// 10^12
+    Szabo,
+    /// 10^15
+    Finney,
+    /// 10^18
+    Ether,
+    /// An arbitrary number of decimals, at most 77
+    Other(u8),
+}
+
+impl Units {
+    /// The number of decimals of this denomination
+    pub const fn decimals(self) -> u8 {
+        match self {
+            Self::Wei => 0,
+            Self::Kwei => 3,
+            Self::Mwei => 6,
+            Self::Gwei => 9,
+            Self::Szabo => 12,
+            Self::Finney => 15,
+            Self::Ether => 18,
+            Self::Other(decimals) => decimals,
+        }
+    }
+
+    fn scale(self) -> U256 {
+        U256::from(10u64).pow(U256::from(self.decimals()))
+    }
+}
+
+impl TryFrom<&str> for Units {
+    type Error = UnitsError;
+
+    fn try_from(name: &str) -> Result<Self, Self::Error> {
+        Ok(match name.to_ascii_lowercase().as_str() {
+            "wei" => Self::Wei,
+            "kwei" | "babbage" => Self::Kwei,
+            "mwei" | "lovelace" => Self::Mwei,
+            "gwei" | "shannon" => Self::Gwei,
+            "szabo" | "microether" => Self::Szabo,
+            "finney" | "milliether" => Self::Finney,
+            "ether" | "eth" => Self::Ether,
+            _ => return Err(UnitsError::InvalidUnit(name.to_string())),
+        })
+    }
+}
+
+macro_rules! impl_try_from_decimals {
+    ($($t:ty),*) => {$(
+        impl TryFrom<$t> for Units {
+            type Error = UnitsError;
+
+            fn try_from(decimals: $t) -> Result<Self, Self::Error> {
+                match u8::try_from(decimals) {
+                    Ok(decimals) if decimals <= MAX_DECIMALS => Ok(Self::Other(decimals)),
+                    _ => Err(UnitsError::InvalidUnit(decimals.to_string())),
+                }
+            }
+        }
+    )*};
+}
+
+impl_try_from_decimals!(u8, u32, i32, usize);
+
+/// Error parsing or formatting units
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum UnitsError {
+    /// Unknown unit name, or too many decimals
+    InvalidUnit(String),
+    /// The amount is not a decimal number
+    InvalidNumber(String),
+    /// The amount has more fractional digits than the unit has decimals
+    TooManyDecimals(String),
+    /// The amount does not fit in a `U256`
+    Overflow(String),
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for UnitsError {}
+
+impl fmt::Display for UnitsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidUnit(unit) => write!(f, "invalid unit: {unit}"),
+            Self::InvalidNumber(amount) => write!(f, "invalid number: {amount}"),
+            Self::TooManyDecimals(amount) => write!(f, "too many decimals: {amount}"),
+            Self::Overflow(amount) => write!(f, "amount overflows U256: {amount}"),
+        }
+    }
+}
+
+/// Parse a decimal amount in the given units into its base value, e.g.
+/// `parse_units("1.5", "gwei")` is 1_500_000_000
+///
+/// The conversion is exact: amounts with more significant fractional digits
+/// than the unit has decimals are rejected rather than rounded
+pub fn parse_units<K>(amount: &str, units: K) -> Result<U256, UnitsError>
+where
+    K: TryInto<Units, Error = UnitsError>,
+{
+    let units = units.try_into()?;
+    let decimals = units.decimals() as usize;
+    let invalid = || UnitsError::InvalidNumber(amount.to_string());
+
+    let (int, frac) = amount.split_once('.').unwrap_or((amount, ""));
+    if int.is_empty() && frac.is_empty() {
+        return Err(invalid());
+    }
+    if !int.bytes().chain(frac.bytes()).all(|b| b.is_ascii_digit()) {
+        return Err(invalid());
+    }
+    let frac = frac.trim_end_matches('0');
+    if frac.len() > decimals {
+        return Err(UnitsError::TooManyDecimals(amount.to_string()));
+    }
+
+    let overflow = || UnitsError::Overflow(amount.to_string());
+    let digits = |s: &str| {
+        if s.is_empty() {
+            Ok(U256::ZERO)
+        } else {
+            U256::from_str_radix(s, 10).map_err(|_| overflow())
+        }
+    };
+    let frac = digits(frac)? * U256::from(10u64).pow(U256::from(decimals - frac.len()));
+    digits(int)?
+        .checked_mul(units.scale())
+        .and_then(|int| int.checked_add(frac))
+        .ok_or_else(overflow)
+}
+
+/// Format a base value as a decimal amount in the given units, e.g.
+/// `format_units(U256::from(1_500_000_000u64), "gwei")` is `"1.5"`
+///
+/// Trailing fractional zeros are dropped, so the output round-trips through
+/// [`parse_units`]
+pub fn format_units<K>(amount: U256, units: K) -> Result<String, UnitsError>
+where
+    K: TryInto<Units, Error = UnitsError>,
+{
+    let units = units.try_into()?;
+    let scale = units.scale();
+    let int = amount / scale;
+    let frac = (amount % scale).to_string();
+    if frac == "0" {
+        return Ok(int.to_string());
+    }
+
+    let decimals = units.decimals() as usize;
+    let padding = "0".repeat(decimals - frac.len());
+    Ok(alloc::format!(
+        "{int}.{padding}{}",
+        frac.trim_end_matches('0')
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_parses_units() {
+        let ether = U256::from(10u64).pow(U256::from(18));
+        assert_eq!(
+            parse_units("1.5", "ether"),
+            Ok(ether * U256::from(3) / U256::from(2))
+        );
+        assert_eq!(parse_units("1", "Gwei"), Ok(U256::from(1_000_000_000u64)));
+        assert_eq!(parse_units(".25", 2), Ok(U256::from(25)));
+        assert_eq!(parse_units("7.", "wei"), Ok(U256::from(7)));
+        assert_eq!(parse_units("1.000", "wei"), Ok(U256::from(1)));
+        assert_eq!(parse_units("0", 18), Ok(U256::ZERO));
+
+        assert!(matches!(
+            parse_units("1.5", "wei"),
+            Err(UnitsError::TooManyDecimals(_))
+        ));
+        assert!(matches!(
+            parse_units("-1", "wei"),
+            Err(UnitsError::InvalidNumber(_))
+        ));
+        assert!(matches!(
+            parse_units(".", "wei"),
+            Err(UnitsError::InvalidNumber(_))
+        ));
+        assert!(matches!(
+            parse_units("1e9", "wei"),
+            Err(UnitsError::InvalidNumber(_))
+        ));
+        assert!(matches!(
+            parse_units("1", "dogecoin"),
+            Err(UnitsError::InvalidUnit(_))
+        ));
+        assert!(matches!(
+            parse_units("1", 78),
+            Err(UnitsError::InvalidUnit(_))
+        ));
+        assert!(matches!(
+            parse_units(&U256::MAX.to_string(), 1),
+            Err(UnitsError::Overflow(_))
+        ));
+    }
+
+    #[test]
+    fn it_formats_units() {
+        let ether = U256::from(10u64).pow(U256::from(18));
+        assert_eq!(
+            format_units(ether * U256::from(3) / U256::from(2), 18).unwrap(),
+            "1.5"
+        );
+        assert_eq!(
+            format_units(U256::from(1), "ether").unwrap(),
+            "0.000000000000000001"
+        );
+        assert_eq!(
+            format_units(U256::from(2_000_000_000u64), "gwei").unwrap(),
+            "2"
+        );
+        assert_eq!(format_units(U256::ZERO, "ether").unwrap(), "0");
+        assert_eq!(
+            format_units(U256::MAX, "wei").unwrap(),
+            U256::MAX.to_string()
+        );
+
+        let amount = format_units(U256::MAX, 77).unwrap();
+        assert_eq!(parse_units(&amount, 77), Ok(U256::MAX));
+    }
+}
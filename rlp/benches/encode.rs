@@ -0,0 +1,49 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use ethers_rlp::{encode_list, Encodable, Header};
+
+fn header(c: &mut Criterion) {
+    let mut group = c.benchmark_group("header");
+    let mut out = Vec::with_capacity(16);
+    for (name, list, payload_length) in [
+        ("short_string", false, 32),
+        ("long_string", false, 1 << 20),
+        ("short_list", true, 55),
+        ("long_list", true, 1 << 16),
+    ] {
+        let header = Header {
+            list,
+            payload_length,
+        };
+        group.bench_function(name, |b| {
+            b.iter(|| {
+                out.clear();
+                black_box(&header).encode(&mut out);
+            })
+        });
+    }
+    group.finish();
+}
+
+fn values(c: &mut Criterion) {
+    let mut group = c.benchmark_group("encode");
+    let bytes = vec![0xab_u8; 1 << 16];
+    let mut out = Vec::with_capacity(bytes.len() + 8);
+    group.bench_function("bytes", |b| {
+        b.iter(|| {
+            out.clear();
+            black_box(&bytes[..]).encode(&mut out);
+        })
+    });
+    let list: Vec<u64> = (0..1_000).collect();
+    let mut out = Vec::with_capacity(list.len() * 9 + 8);
+    group.bench_function("u64_list", |b| {
+        b.iter(|| {
+            out.clear();
+            encode_list::<u64, u64>(black_box(&list), &mut out);
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, header, values);
+criterion_main!(benches);
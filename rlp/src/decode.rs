@@ -223,6 +223,26 @@ decode_integer!(u32);
 decode_integer!(u64);
 decode_integer!(u128);
 
+#[cfg(feature = "ruint")]
+impl<const BITS: usize, const LIMBS: usize> Decodable for ruint::Uint<BITS, LIMBS> {
+    fn decode(buf: &mut &[u8]) -> Result<Self, DecodeError> {
+        let h = Header::decode(buf)?;
+        if h.list {
+            return Err(DecodeError::UnexpectedList);
+        }
+        if h.payload_length > Self::BYTES {
+            return Err(DecodeError::Overflow);
+        }
+        let payload = &buf[..h.payload_length];
+        if payload.first() == Some(&0) {
+            return Err(DecodeError::LeadingZero);
+        }
+        let v = Self::try_from_be_slice(payload).ok_or(DecodeError::Overflow)?;
+        buf.advance(h.payload_length);
+        Ok(v)
+    }
+}
+
 impl Decodable for bool {
     fn decode(buf: &mut &[u8]) -> Result<Self, DecodeError> {
         Ok(match u8::decode(buf)? {
@@ -438,6 +458,30 @@ mod tests {
         ])
     }
 
+    #[cfg(feature = "ruint")]
+    #[test]
+    fn rlp_ruint() {
+        use ruint::aliases::U256;
+
+        check_decode(vec![
+            (Ok(U256::from(9)), &hex!("09")[..]),
+            (Ok(U256::ZERO), &hex!("80")[..]),
+            (Ok(U256::from(0x0505)), &hex!("820505")[..]),
+            (
+                Ok(U256::MAX),
+                &hex!("A0FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF")[..],
+            ),
+            (Err(DecodeError::UnexpectedList), &hex!("C0")[..]),
+            (Err(DecodeError::LeadingZero), &hex!("00")[..]),
+            (Err(DecodeError::NonCanonicalSingleByte), &hex!("8105")[..]),
+            (Err(DecodeError::LeadingZero), &hex!("8200F4")[..]),
+            (
+                Err(DecodeError::Overflow),
+                &hex!("A101000000000000000000000000000000000000008B000000000000000000000000")[..],
+            ),
+        ])
+    }
+
     #[test]
     fn rlp_vectors() {
         check_decode_list(vec![
@@ -55,7 +55,7 @@ pub trait Encodable {
     }
 }
 
-impl<'a> Encodable for &'a [u8] {
+impl Encodable for &[u8] {
     fn length(&self) -> usize {
         let mut len = self.len();
         if self.len() != 1 || self[0] >= EMPTY_STRING_CODE {
@@ -144,6 +144,45 @@ max_encoded_len_uint!(u64);
 encodable_uint!(u128);
 max_encoded_len_uint!(u128);
 
+#[cfg(feature = "ruint")]
+mod ruint_support {
+    use super::*;
+    use ruint::Uint;
+
+    impl<const BITS: usize, const LIMBS: usize> Encodable for Uint<BITS, LIMBS> {
+        fn length(&self) -> usize {
+            let bytes = self.bit_len().div_ceil(8);
+            if self.bit_len() <= 7 {
+                1
+            } else {
+                length_of_length(bytes) + bytes
+            }
+        }
+
+        fn encode(&self, out: &mut dyn BufMut) {
+            let bytes = self.bit_len().div_ceil(8);
+            if self.bit_len() == 0 {
+                out.put_u8(EMPTY_STRING_CODE);
+            } else if self.bit_len() <= 7 {
+                out.put_u8(self.byte(0));
+            } else {
+                Header {
+                    list: false,
+                    payload_length: bytes,
+                }
+                .encode(out);
+                for i in (0..bytes).rev() {
+                    out.put_u8(self.byte(i));
+                }
+            }
+        }
+    }
+
+    unsafe impl<const BITS: usize, const LIMBS: usize> MaxEncodedLenAssoc for Uint<BITS, LIMBS> {
+        const LEN: usize = length_of_length(Self::BYTES) + Self::BYTES;
+    }
+}
+
 impl Encodable for bool {
     fn length(&self) -> usize {
         (*self as u8).length()
@@ -392,6 +431,27 @@ mod tests {
         )])
     }
 
+    #[cfg(feature = "ruint")]
+    fn ruint_u256_fixtures() -> impl IntoIterator<Item = (ruint::aliases::U256, &'static [u8])> {
+        u128_fixtures()
+            .into_iter()
+            .map(|(k, v)| (ruint::aliases::U256::from(k), v))
+            .chain(vec![
+                (
+                    ruint::aliases::U256::from_str_radix(
+                        "0100020003000400050006000700080009000A0B4B000C000D000E01",
+                        16,
+                    )
+                    .unwrap(),
+                    &hex!("9c0100020003000400050006000700080009000a0b4b000c000d000e01")[..],
+                ),
+                (
+                    ruint::aliases::U256::MAX,
+                    &hex!("a0ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff")[..],
+                ),
+            ])
+    }
+
     #[cfg(feature = "ethereum-types")]
     fn eth_u64_fixtures() -> impl IntoIterator<Item = (ethereum_types::U64, &'static [u8])> {
         c(u64_fixtures()).chain(vec![
@@ -463,6 +523,11 @@ mod tests {
         uint_rlp_test!(u128_fixtures());
         #[cfg(feature = "ethnum")]
         uint_rlp_test!(u256_fixtures());
+        #[cfg(feature = "ruint")]
+        for (input, output) in ruint_u256_fixtures() {
+            assert_eq!(input.length(), output.len());
+            assert_eq!(encoded(input), output);
+        }
     }
 
     #[cfg(feature = "ethereum-types")]
@@ -474,6 +539,21 @@ mod tests {
         uint_rlp_test!(eth_u512_fixtures());
     }
 
+    #[test]
+    fn rlp_header_length() {
+        for payload_length in [0, 1, 55, 56, 255, 256, 65_536, usize::MAX] {
+            for list in [false, true] {
+                let header = Header {
+                    list,
+                    payload_length,
+                };
+                let mut out = BytesMut::new();
+                header.encode(&mut out);
+                assert_eq!(header.length(), out.len());
+            }
+        }
+    }
+
     #[test]
     fn rlp_list() {
         assert_eq!(encoded_list::<u64>(&[]), &hex!("c0")[..]);
@@ -1,4 +1,6 @@
-use bytes::{BufMut, BytesMut};
+use bytes::BufMut;
+
+use crate::length_of_length;
 
 #[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct Header {
@@ -26,10 +28,8 @@ impl Header {
     }
 
     /// Returns the length of the encoded header
-    pub fn length(&self) -> usize {
-        let mut out = BytesMut::new();
-        self.encode(&mut out);
-        out.len()
+    pub const fn length(&self) -> usize {
+        length_of_length(self.payload_length)
     }
 }
 
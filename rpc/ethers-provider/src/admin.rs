@@ -0,0 +1,158 @@
+use ethers_primitives::U64;
+use ethers_rpc_types::admin::{NodeInfo, NodeRecord, PeerInfo};
+use ethers_transports::{Connection, RpcCall};
+
+use crate::Provider;
+
+/// Typed methods for the `admin_` and `net_` namespaces.
+///
+/// The `admin_` namespace is usually only exposed on a node's private
+/// interfaces. Import this trait to manage a node's peers.
+pub trait AdminApi: Connection + Sized {
+    /// Request the node connect to a peer. Returns `true` if the request was
+    /// accepted, not once the peer is connected
+    fn add_peer(&self, record: NodeRecord) -> RpcCall<&Self, Self, (NodeRecord,), bool> {
+        self.request("admin_addPeer", (record,))
+    }
+
+    /// Request the node disconnect from a peer
+    fn remove_peer(&self, record: NodeRecord) -> RpcCall<&Self, Self, (NodeRecord,), bool> {
+        self.request("admin_removePeer", (record,))
+    }
+
+    /// Get networking information about the node
+    fn node_info(&self) -> RpcCall<&Self, Self, (), NodeInfo> {
+        self.request("admin_nodeInfo", ())
+    }
+
+    /// Get the node's connected peers
+    fn peers(&self) -> RpcCall<&Self, Self, (), Vec<PeerInfo>> {
+        self.request("admin_peers", ())
+    }
+
+    /// Get the network id, as a decimal string
+    fn net_version(&self) -> RpcCall<&Self, Self, (), String> {
+        self.request("net_version", ())
+    }
+
+    /// Check if the node is listening for network connections
+    fn net_listening(&self) -> RpcCall<&Self, Self, (), bool> {
+        self.request("net_listening", ())
+    }
+
+    /// Get the number of connected peers
+    fn net_peer_count(&self) -> RpcCall<&Self, Self, (), U64> {
+        self.request("net_peerCount", ())
+    }
+}
+
+impl<T> AdminApi for Provider<T> where T: Connection {}
+
+#[cfg(test)]
+mod test {
+    use ethers_pub_use::serde_json::{json, Value};
+    use ethers_transports::transports::MockConnection;
+
+    use super::*;
+
+    const ENODE: &str = "enode://44826a5d6a55f88a18298bca4773fca5749cdc3a5c9f308aa7d810e9b31123f3e7c5fba0b1d70aac5308426f47df2a128a6747040a3815cc7dd7167d03be320d@10.0.0.2:30303";
+
+    #[tokio::test]
+    async fn it_manages_peers() {
+        let mock = MockConnection::new();
+        let provider = Provider::new(mock.clone());
+        let record: NodeRecord = ENODE.parse().unwrap();
+
+        mock.push_response("admin_addPeer", true);
+        mock.push_response("admin_removePeer", false);
+        mock.push_response(
+            "admin_peers",
+            json!([{
+                "enode": ENODE,
+                "id": "1ca5c8ae8f5d2b0e8a0f9c3aa10a4d2b6a8e3c1b3d4a5f6e7d8c9b0a1f2e3d4c",
+                "name": "Geth/v1.11.5-stable/linux-amd64/go1.20.2",
+                "caps": ["eth/68"],
+                "network": {
+                    "localAddress": "10.0.0.1:50412",
+                    "remoteAddress": "10.0.0.2:30303",
+                    "inbound": false,
+                    "trusted": false,
+                    "static": true
+                },
+                "protocols": { "eth": { "version": 68 } }
+            }]),
+        );
+
+        assert!(provider.add_peer(record).await.unwrap().unwrap());
+        assert!(!provider.remove_peer(record).await.unwrap().unwrap());
+        let peers = provider.peers().await.unwrap().unwrap();
+        assert_eq!(peers[0].enode, ENODE);
+
+        let reqs: Vec<_> = mock
+            .requests()
+            .into_iter()
+            .map(|req| (req.method, req.params.unwrap()))
+            .collect();
+        assert_eq!(
+            reqs,
+            vec![
+                ("admin_addPeer".to_owned(), json!([ENODE])),
+                ("admin_removePeer".to_owned(), json!([ENODE])),
+                ("admin_peers".to_owned(), Value::Null),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn it_queries_the_network() {
+        let mock = MockConnection::new();
+        let provider = Provider::new(mock.clone());
+
+        mock.push_response(
+            "admin_nodeInfo",
+            json!({
+                "enode": ENODE,
+                "id": "44826a5d6a55f88a18298bca4773fca5749cdc3a5c9f308aa7d810e9b31123f3e7c5fba0b1d70aac5308426f47df2a128a6747040a3815cc7dd7167d03be320d",
+                "ip": "10.0.0.2",
+                "listenAddr": "[::]:30303",
+                "name": "reth",
+                "ports": { "discovery": 30303, "listener": 30303 },
+                "protocols": {
+                    "eth": {
+                        "difficulty": "0x10",
+                        "genesis": "0xd4e56740f876aef8c010b86a40d5f56745a118d0906a34e69aec8c0db1cb8fa3",
+                        "head": "0xb83f73fbe6220c111136aefd27b160bf4a34085c65ba89f24246b3162257c36a",
+                        "network": 1
+                    }
+                }
+            }),
+        );
+        mock.push_response("net_version", "1");
+        mock.push_response("net_listening", true);
+        mock.push_response("net_peerCount", "0x19");
+
+        let info = provider.node_info().await.unwrap().unwrap();
+        assert_eq!(info.enode, ENODE.parse().unwrap());
+        assert_eq!(provider.net_version().await.unwrap().unwrap(), "1");
+        assert!(provider.net_listening().await.unwrap().unwrap());
+        assert_eq!(
+            provider.net_peer_count().await.unwrap().unwrap(),
+            U64::from(25)
+        );
+
+        let reqs: Vec<_> = mock
+            .requests()
+            .into_iter()
+            .map(|req| (req.method, req.params.unwrap()))
+            .collect();
+        assert_eq!(
+            reqs,
+            vec![
+                ("admin_nodeInfo".to_owned(), Value::Null),
+                ("net_version".to_owned(), Value::Null),
+                ("net_listening".to_owned(), Value::Null),
+                ("net_peerCount".to_owned(), Value::Null),
+            ]
+        );
+    }
+}
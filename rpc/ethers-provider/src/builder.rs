@@ -0,0 +1,314 @@
+//! Declarative construction of providers over stacked connection wrappers
+
+use std::{sync::Arc, time::Duration};
+
+use ethers_primitives::Chain;
+use ethers_transports::{CircuitBreaker, Connection, Instrumented, RateLimit, Throttle};
+
+use ethers_signer::Signer;
+
+use crate::{
+    CachedProvider, Provider, ProviderLimits, Retry, RetryPolicy, SignerProvider, ValidationMode,
+};
+
+/// Builds a [`Provider`], wrapping its transport in layers.
+///
+/// Each layer wraps the connection built so far, so the first layer added
+/// is closest to the transport, and the last sees requests first. Layers
+/// without a shortcut method, e.g. a [`Throttle`] with per-method classes,
+/// are added with [`ProviderBuilder::layer`]. Adding a
+/// [signer](ProviderBuilder::signer) ends the connection layers, and builds
+/// a [`SignerProvider`], optionally with a nonce manager.
+///
+/// There is no quorum layer yet: [`crate::quorum`] is a placeholder.
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use ethers_provider::{ProviderBuilder, RetryPolicy};
+/// use ethers_signer::LocalWallet;
+/// use ethers_transports::{Http, RateLimit};
+///
+/// let http: Http = "http://localhost:8545".parse().unwrap();
+/// let wallet = LocalWallet::from_slice(&[0x42; 32]).unwrap();
+/// let provider = ProviderBuilder::new(http)
+///     .circuit_breaker("local")
+///     .retry(RetryPolicy::default())
+///     .throttle(RateLimit::per_second(10.0))
+///     .cache()
+///     .timeout(Duration::from_secs(10))
+///     .signer(wallet)
+///     .nonce_manager()
+///     .build();
+/// ```
+#[derive(Debug, Clone)]
+#[must_use = "Builder method outputs must be used"]
+pub struct ProviderBuilder<T> {
+    transport: T,
+    interval: Option<Duration>,
+    timeout: Option<Duration>,
+    limits: Option<ProviderLimits>,
+    chain: Option<Chain>,
+    validation: Option<ValidationMode>,
+}
+
+impl<T> ProviderBuilder<T> {
+    /// Start building a provider over `transport`
+    pub fn new(transport: T) -> Self {
+        Self {
+            transport,
+            interval: None,
+            timeout: None,
+            limits: None,
+            chain: None,
+            validation: None,
+        }
+    }
+
+    /// Wrap the connection built so far
+    pub fn layer<C, F>(self, layer: F) -> ProviderBuilder<C>
+    where
+        F: FnOnce(T) -> C,
+    {
+        ProviderBuilder {
+            transport: layer(self.transport),
+            interval: self.interval,
+            timeout: self.timeout,
+            limits: self.limits,
+            chain: self.chain,
+            validation: self.validation,
+        }
+    }
+
+    /// Stop sending requests while the endpoint is failing. See
+    /// [`CircuitBreaker`]
+    pub fn circuit_breaker(
+        self,
+        endpoint: impl Into<Arc<str>>,
+    ) -> ProviderBuilder<CircuitBreaker<T>> {
+        self.layer(|inner| CircuitBreaker::new(inner, endpoint))
+    }
+
+    /// Trace requests and report slow calls. See [`Instrumented`]
+    pub fn instrument(self, endpoint: impl Into<Arc<str>>) -> ProviderBuilder<Instrumented<T>> {
+        self.layer(|inner| Instrumented::new(inner, endpoint))
+    }
+
+    /// Resend requests that fail transiently. See [`Retry`]
+    pub fn retry(self, policy: RetryPolicy) -> ProviderBuilder<Retry<T>> {
+        self.layer(|inner| Retry::new(inner, policy))
+    }
+
+    /// Limit the rate and concurrency of all requests. See [`Throttle`]
+    pub fn throttle(self, limit: RateLimit) -> ProviderBuilder<Throttle<T>> {
+        self.layer(|inner| Throttle::new(inner, limit))
+    }
+
+    /// Cache immutable results. See [`CachedProvider`]
+    pub fn cache(self) -> ProviderBuilder<CachedProvider<T>> {
+        self.layer(CachedProvider::new)
+    }
+
+    /// Set the polling interval. See [`Provider::with_interval`]
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = Some(interval);
+        self
+    }
+
+    /// Set the default request timeout. See [`Provider::with_timeout`]
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Set the server-side limits. See [`Provider::with_limits`]
+    pub fn limits(mut self, limits: ProviderLimits) -> Self {
+        self.limits = Some(limits);
+        self
+    }
+
+    /// Set the chain the provider is connected to. See
+    /// [`Provider::with_chain`]
+    pub fn chain(mut self, chain: Chain) -> Self {
+        self.chain = Some(chain);
+        self
+    }
+
+    /// Set how typed responses are validated. See
+    /// [`Provider::with_validation`]
+    pub fn validation(mut self, mode: ValidationMode) -> Self {
+        self.validation = Some(mode);
+        self
+    }
+
+    /// Sign transactions with `signer`. See [`SignerProvider`]
+    pub fn signer<S>(self, signer: S) -> SignerProviderBuilder<T, S> {
+        SignerProviderBuilder {
+            provider: self,
+            signer,
+            nonce_manager: false,
+        }
+    }
+}
+
+impl<T> ProviderBuilder<T>
+where
+    T: Connection,
+{
+    /// Build the provider
+    pub fn build(self) -> Provider<T> {
+        let mut provider = Provider::new(self.transport);
+        if let Some(interval) = self.interval {
+            provider.set_interval(interval);
+        }
+        provider.set_timeout(self.timeout);
+        if let Some(limits) = self.limits {
+            provider.set_limits(limits);
+        }
+        if let Some(chain) = self.chain {
+            provider.set_chain(chain);
+        }
+        if let Some(mode) = self.validation {
+            provider.set_validation(mode);
+        }
+        provider
+    }
+}
+
+/// Builds a [`SignerProvider`]. See [`ProviderBuilder::signer`]
+#[derive(Debug, Clone)]
+#[must_use = "Builder method outputs must be used"]
+pub struct SignerProviderBuilder<T, S> {
+    provider: ProviderBuilder<T>,
+    signer: S,
+    nonce_manager: bool,
+}
+
+impl<T, S> SignerProviderBuilder<T, S> {
+    /// Assign nonces locally. See [`SignerProvider::with_nonce_manager`]
+    pub fn nonce_manager(mut self) -> Self {
+        self.nonce_manager = true;
+        self
+    }
+}
+
+impl<T, S> SignerProviderBuilder<T, S>
+where
+    T: Connection,
+    S: Signer,
+{
+    /// Build the provider
+    pub fn build(self) -> SignerProvider<T, S> {
+        let provider = SignerProvider::new(self.provider.build(), self.signer);
+        if self.nonce_manager {
+            provider.with_nonce_manager()
+        } else {
+            provider
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        borrow::Cow,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    use ethers_primitives::B256;
+    use ethers_pub_use::serde_json::value::to_raw_value;
+    use ethers_rpc_types::transaction::{Eip1559Transaction, TypedTransaction};
+    use ethers_signer::LocalWallet;
+    use ethers_transports::{transports::MockConnection, TransportError};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn it_stacks_layers() {
+        let mock = MockConnection::new();
+        mock.push_response("eth_chainId", "0x1");
+
+        let provider = ProviderBuilder::new(mock.clone())
+            .circuit_breaker("mock")
+            .throttle(RateLimit::per_second(100.0))
+            .cache()
+            .layer(|inner| Instrumented::new(inner, "mock"))
+            .interval(Duration::from_secs(1))
+            .timeout(Duration::from_secs(5))
+            .validation(ValidationMode::Warn)
+            .build();
+
+        for _ in 0..2 {
+            let chain_id: String = provider.request("eth_chainId", ()).await.unwrap().unwrap();
+            assert_eq!(chain_id, "0x1");
+        }
+        assert_eq!(mock.requests().len(), 1);
+        assert_eq!(provider.interval(), Duration::from_secs(1));
+        assert_eq!(provider.default_timeout(), Some(Duration::from_secs(5)));
+        assert_eq!(provider.validation(), ValidationMode::Warn);
+    }
+
+    #[tokio::test]
+    async fn it_builds_signer_providers() {
+        let mock = MockConnection::new();
+        let count = Arc::new(AtomicUsize::new(0));
+        let flaky = mock.clone().with_handler({
+            let count = count.clone();
+            move |req| {
+                // the first request times out
+                if count.fetch_add(1, Ordering::SeqCst) == 0 {
+                    return Err(TransportError::Timeout(Duration::from_secs(1)));
+                }
+                match req.method.as_str() {
+                    "eth_chainId" => Ok(Ok(Cow::Owned(to_raw_value("0x1").unwrap()))),
+                    "eth_getTransactionCount" => Ok(Ok(Cow::Owned(to_raw_value("0x3").unwrap()))),
+                    _ => Ok(Ok(Cow::Owned(to_raw_value(&B256::default()).unwrap()))),
+                }
+            }
+        });
+        let wallet = LocalWallet::from_slice(&[0x42; 32]).unwrap();
+        let provider = ProviderBuilder::new(flaky)
+            .retry(RetryPolicy::new(1).with_backoff(Duration::ZERO, Duration::ZERO))
+            .signer(wallet.clone())
+            .nonce_manager()
+            .build();
+        assert_eq!(provider.address(), wallet.address());
+
+        let tx = TypedTransaction::Eip1559(Eip1559Transaction {
+            chain_id: 1,
+            ..Default::default()
+        });
+        provider.send_transaction(&tx).await.unwrap();
+        provider.send_transaction(&tx).await.unwrap();
+
+        let methods: Vec<_> = mock.requests().into_iter().map(|req| req.method).collect();
+        assert_eq!(
+            methods,
+            [
+                // retried
+                "eth_getTransactionCount",
+                "eth_getTransactionCount",
+                "eth_chainId",
+                "eth_sendRawTransaction",
+                // the nonce is managed, and the chain id cached
+                "eth_sendRawTransaction",
+            ]
+        );
+    }
+
+    #[test]
+    fn it_polls_at_the_chain_block_time() {
+        let remote = MockConnection::new().with_local(false);
+        let provider = ProviderBuilder::new(remote.clone()).build();
+        assert_eq!(provider.interval(), crate::DEFAULT_POLL_INTERVAL);
+
+        let provider = ProviderBuilder::new(remote).chain(Chain::Arbitrum).build();
+        assert_eq!(provider.chain(), Some(Chain::Arbitrum));
+        assert_eq!(provider.interval(), Duration::from_millis(125));
+
+        let provider = ProviderBuilder::new(MockConnection::new())
+            .chain(Chain::Mainnet)
+            .build();
+        assert_eq!(provider.interval(), crate::DEFAULT_LOCAL_POLL_INTERVAL);
+    }
+}
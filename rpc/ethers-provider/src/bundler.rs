@@ -0,0 +1,174 @@
+use ethers_primitives::{Address, B256};
+use ethers_rpc_types::erc4337::{
+    UserOperation, UserOperationByHash, UserOperationGasEstimate, UserOperationReceipt,
+};
+use ethers_transports::{Connection, RpcCall};
+
+use crate::Provider;
+
+/// Typed methods for the ERC-4337 bundler namespace.
+///
+/// Bundlers collect user operations and submit them to an EntryPoint
+/// contract. Connect to a bundler's endpoint and import this trait to send
+/// and track user operations.
+pub trait BundlerApi: Connection + Sized {
+    /// Submit a user operation to the bundler's mempool. Returns the
+    /// operation hash. See [`UserOperation::hash`]
+    fn send_user_operation(
+        &self,
+        op: UserOperation,
+        entry_point: Address,
+    ) -> RpcCall<&Self, Self, (UserOperation, Address), B256> {
+        self.request("eth_sendUserOperation", (op, entry_point))
+    }
+
+    /// Estimate the gas limits of a user operation. The signature need not
+    /// be valid, but should have the right length
+    fn estimate_user_operation_gas(
+        &self,
+        op: UserOperation,
+        entry_point: Address,
+    ) -> RpcCall<&Self, Self, (UserOperation, Address), UserOperationGasEstimate> {
+        self.request("eth_estimateUserOperationGas", (op, entry_point))
+    }
+
+    /// Get a user operation by its hash. `None` if it is unknown
+    fn get_user_operation_by_hash(
+        &self,
+        hash: B256,
+    ) -> RpcCall<&Self, Self, (B256,), Option<UserOperationByHash>> {
+        self.request("eth_getUserOperationByHash", (hash,))
+    }
+
+    /// Get the receipt of a user operation. `None` if it is unknown or not
+    /// yet included
+    fn get_user_operation_receipt(
+        &self,
+        hash: B256,
+    ) -> RpcCall<&Self, Self, (B256,), Option<UserOperationReceipt>> {
+        self.request("eth_getUserOperationReceipt", (hash,))
+    }
+
+    /// Get the EntryPoint contracts the bundler supports, in order of
+    /// preference
+    fn supported_entry_points(&self) -> RpcCall<&Self, Self, (), Vec<Address>> {
+        self.request("eth_supportedEntryPoints", ())
+    }
+}
+
+impl<T> BundlerApi for Provider<T> where T: Connection {}
+
+#[cfg(test)]
+mod test {
+    use ethers_primitives::U256;
+    use ethers_pub_use::serde_json::{self, json, Value};
+    use ethers_transports::transports::MockConnection;
+
+    use super::*;
+
+    fn params(mock: &MockConnection) -> Vec<(String, Value)> {
+        mock.requests()
+            .into_iter()
+            .map(|req| (req.method, req.params.unwrap()))
+            .collect()
+    }
+
+    fn op() -> UserOperation {
+        UserOperation {
+            sender: Address::repeat_byte(1),
+            nonce: U256::from(3),
+            call_gas_limit: U256::from(50_000),
+            signature: vec![0xff; 65].into(),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn it_sends_user_operations() {
+        let mock = MockConnection::new();
+        let provider = Provider::new(mock.clone());
+        let entry_point = Address::repeat_byte(2);
+
+        mock.push_response(
+            "eth_estimateUserOperationGas",
+            json!({
+                "preVerificationGas": "0xb000",
+                "verificationGasLimit": "0x10000",
+                "callGasLimit": "0xc350"
+            }),
+        );
+        mock.push_response("eth_sendUserOperation", B256::repeat_byte(3));
+
+        let estimate = provider
+            .estimate_user_operation_gas(op(), entry_point)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(estimate.call_gas_limit, U256::from(50_000));
+        let hash = provider
+            .send_user_operation(op(), entry_point)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(hash, B256::repeat_byte(3));
+
+        let op = serde_json::to_value(op()).unwrap();
+        assert_eq!(op["callGasLimit"], "0xc350");
+        assert_eq!(
+            params(&mock),
+            vec![
+                (
+                    "eth_estimateUserOperationGas".to_owned(),
+                    json!([op, entry_point]),
+                ),
+                ("eth_sendUserOperation".to_owned(), json!([op, entry_point])),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn it_tracks_user_operations() {
+        let mock = MockConnection::new();
+        let provider = Provider::new(mock.clone());
+        let hash = B256::repeat_byte(3);
+        let pending = UserOperationByHash {
+            user_operation: op(),
+            entry_point: Address::repeat_byte(2),
+            ..Default::default()
+        };
+
+        mock.push_response("eth_getUserOperationByHash", &pending);
+        mock.push_response("eth_getUserOperationReceipt", Value::Null);
+        mock.push_response("eth_supportedEntryPoints", [Address::repeat_byte(2)]);
+
+        assert_eq!(
+            provider
+                .get_user_operation_by_hash(hash)
+                .await
+                .unwrap()
+                .unwrap(),
+            Some(pending)
+        );
+        assert_eq!(
+            provider
+                .get_user_operation_receipt(hash)
+                .await
+                .unwrap()
+                .unwrap(),
+            None
+        );
+        assert_eq!(
+            provider.supported_entry_points().await.unwrap().unwrap(),
+            vec![Address::repeat_byte(2)]
+        );
+
+        assert_eq!(
+            params(&mock),
+            vec![
+                ("eth_getUserOperationByHash".to_owned(), json!([hash])),
+                ("eth_getUserOperationReceipt".to_owned(), json!([hash])),
+                ("eth_supportedEntryPoints".to_owned(), Value::Null),
+            ]
+        );
+    }
+}
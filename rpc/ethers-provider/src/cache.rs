@@ -0,0 +1,610 @@
+//! Response caching for immutable RPC results
+
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, HashMap},
+    fmt,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex, MutexGuard,
+    },
+    time::Duration,
+};
+
+use ethers_primitives::U64;
+use ethers_pub_use::{
+    futures_channel::mpsc,
+    serde::Deserialize,
+    serde_json::{self, value::RawValue, Value},
+};
+use ethers_transports::{
+    common::{BatchRpcFuture, Id, JsonRpcResultOwned, Request, RpcFuture},
+    time::Instant,
+    Connection, PubSubConnection, TransportError,
+};
+
+/// The default maximum number of cached responses
+pub const DEFAULT_CACHE_CAPACITY: usize = 10_000;
+
+/// How long the finalized block number is trusted before it is refreshed
+pub const FINALIZED_REFRESH_INTERVAL: Duration = Duration::from_secs(12);
+
+/// When a response may be cached
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Policy {
+    /// The result can never change
+    Always,
+    /// The result is fixed once its block is finalized
+    IfFinalized,
+    /// The result may change, e.g. state at `latest`, so is always fetched
+    Never,
+}
+
+/// True if the last param is an EIP-1898 block hash, which pins the result
+fn pinned_by_hash(params: &Value) -> bool {
+    params
+        .as_array()
+        .and_then(|params| params.last())
+        .is_some_and(|block| block.get("blockHash").is_some())
+}
+
+fn policy(req: &Request<'_>) -> Policy {
+    match req.method.as_ref() {
+        "eth_chainId"
+        | "eth_getBlockByHash"
+        | "eth_getBlockTransactionCountByHash"
+        | "eth_getTransactionByBlockHashAndIndex" => Policy::Always,
+        "eth_getTransactionReceipt" | "eth_getTransactionByHash" => Policy::IfFinalized,
+        "eth_call"
+        | "eth_getBalance"
+        | "eth_getCode"
+        | "eth_getStorageAt"
+        | "eth_getTransactionCount"
+        | "eth_getProof" => {
+            let pinned = req
+                .params
+                .as_ref()
+                .and_then(|params| serde_json::from_str(params.get()).ok())
+                .is_some_and(|params| pinned_by_hash(&params));
+            if pinned {
+                Policy::Always
+            } else {
+                Policy::Never
+            }
+        }
+        _ => Policy::Never,
+    }
+}
+
+fn cache_key(req: &Request<'_>) -> String {
+    let params = req.params.as_ref().map_or("", |params| params.get());
+    format!("{}:{params}", req.method)
+}
+
+fn to_owned(req: &Request<'_>) -> Request<'static> {
+    Request::owned(
+        req.id.clone().into_owned(),
+        req.method.clone().into_owned(),
+        req.params
+            .as_ref()
+            .map(|params| params.clone().into_owned()),
+    )
+}
+
+#[derive(Debug)]
+struct Entry {
+    value: Box<RawValue>,
+    inserted: Instant,
+    last_used: u64,
+}
+
+/// Cache hit and miss counts
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct CacheStats {
+    /// Cached responses
+    pub entries: usize,
+    /// Requests served from the cache
+    pub hits: u64,
+    /// Cacheable requests sent to the endpoint
+    pub misses: u64,
+}
+
+#[derive(Debug, Default)]
+struct CacheInner {
+    entries: HashMap<String, Entry>,
+    /// The keys of the entries, by last use. The first is the least
+    /// recently used
+    recency: BTreeMap<u64, String>,
+    /// Incremented on every access, to order entries by last use
+    clock: u64,
+    /// The last known finalized block number, and when it was fetched
+    finalized: Option<(u64, Instant)>,
+}
+
+impl CacheInner {
+    fn tick(&mut self) -> u64 {
+        self.clock += 1;
+        self.clock
+    }
+
+    fn remove(&mut self, key: &str) {
+        if let Some(entry) = self.entries.remove(key) {
+            self.recency.remove(&entry.last_used);
+        }
+    }
+
+    fn evict_least_recently_used(&mut self) {
+        if let Some((_, key)) = self.recency.pop_first() {
+            self.entries.remove(&key);
+        }
+    }
+
+    fn clear(&mut self) {
+        self.entries.clear();
+        self.recency.clear();
+    }
+}
+
+#[derive(Debug, Default)]
+struct Cache {
+    inner: Mutex<CacheInner>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+/// A [`Connection`] wrapper that caches immutable results, for indexers and
+/// other consumers that re-read the same data.
+///
+/// Responses are keyed by method and params. The following are cached:
+///
+/// - `eth_chainId`, and blocks and transactions looked up by block hash
+/// - Receipts and transactions by hash, once their block is finalized. The
+///   finalized block number is fetched when needed, at most once every
+///   [`FINALIZED_REFRESH_INTERVAL`]
+/// - State queries at a block given by hash, as in EIP-1898
+///
+/// `null` results and errors are never cached. When full, the least
+/// recently used response is evicted. Use it as the transport of a
+/// [`Provider`](crate::Provider).
+pub struct CachedProvider<T> {
+    inner: Arc<T>,
+    capacity: usize,
+    ttl: Option<Duration>,
+    cache: Arc<Cache>,
+}
+
+impl<T> Clone for CachedProvider<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            capacity: self.capacity,
+            ttl: self.ttl,
+            cache: self.cache.clone(),
+        }
+    }
+}
+
+impl<T> CachedProvider<T> {
+    /// Instantiate an empty cache of [`DEFAULT_CACHE_CAPACITY`] responses,
+    /// which never expire
+    pub fn new(inner: T) -> Self {
+        Self {
+            inner: Arc::new(inner),
+            capacity: DEFAULT_CACHE_CAPACITY,
+            ttl: None,
+            cache: Default::default(),
+        }
+    }
+
+    /// Set the maximum number of cached responses. At least 1
+    #[must_use = "Builder method outputs must be used"]
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity.max(1);
+        self
+    }
+
+    /// Expire cached responses after `ttl`
+    #[must_use = "Builder method outputs must be used"]
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    /// The wrapped connection
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+
+    /// Cache hit and miss counts
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            entries: self.lock().entries.len(),
+            hits: self.cache.hits.load(Ordering::Relaxed),
+            misses: self.cache.misses.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Remove all cached responses
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    fn lock(&self) -> MutexGuard<'_, CacheInner> {
+        self.cache.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn lookup(&self, key: &str) -> Option<JsonRpcResultOwned> {
+        let mut inner = self.lock();
+        let clock = inner.tick();
+        let inner = &mut *inner;
+        let expired = match inner.entries.get_mut(key) {
+            Some(entry) if self.ttl.is_none_or(|ttl| entry.inserted.elapsed() < ttl) => {
+                let key = inner
+                    .recency
+                    .remove(&entry.last_used)
+                    .expect("entries are indexed by last use");
+                inner.recency.insert(clock, key);
+                entry.last_used = clock;
+                self.cache.hits.fetch_add(1, Ordering::Relaxed);
+                return Some(Ok(Cow::Owned(entry.value.clone())));
+            }
+            Some(_) => true,
+            None => false,
+        };
+        if expired {
+            inner.remove(key);
+        }
+        self.cache.misses.fetch_add(1, Ordering::Relaxed);
+        None
+    }
+
+    fn insert(&self, key: String, value: Box<RawValue>) {
+        let mut inner = self.lock();
+        inner.remove(&key);
+        if inner.entries.len() >= self.capacity {
+            inner.evict_least_recently_used();
+        }
+        let last_used = inner.tick();
+        inner.recency.insert(last_used, key.clone());
+        let entry = Entry {
+            value,
+            inserted: Instant::now(),
+            last_used,
+        };
+        inner.entries.insert(key, entry);
+    }
+}
+
+impl<T> CachedProvider<T>
+where
+    T: Connection,
+{
+    /// True if the block is at or below the finalized block. Refreshes the
+    /// finalized block number if the block is above it and it is stale
+    async fn is_finalized(&self, number: u64) -> bool {
+        let known = self.lock().finalized;
+        match known {
+            Some((finalized, _)) if number <= finalized => return true,
+            Some((_, fetched)) if fetched.elapsed() < FINALIZED_REFRESH_INTERVAL => return false,
+            _ => {}
+        }
+
+        #[derive(Deserialize)]
+        #[serde(crate = "ethers_pub_use::serde")]
+        struct Header {
+            number: U64,
+        }
+
+        let params =
+            RawValue::from_string(r#"["finalized",false]"#.to_owned()).expect("valid json");
+        let req = Request::owned(
+            Id::Number(self.inner.increment_id()),
+            "eth_getBlockByNumber",
+            Some(params),
+        );
+        let finalized = match self.inner.json_rpc_request(&req).await {
+            Ok(Ok(block)) => serde_json::from_str::<Header>(block.get()).ok(),
+            _ => None,
+        };
+        let Some(finalized) = finalized.map(|header| header.number.to::<u64>()) else {
+            return false;
+        };
+        self.lock().finalized = Some((finalized, Instant::now()));
+        number <= finalized
+    }
+
+    /// Cache a response, if its policy allows
+    async fn store(&self, key: String, policy: Policy, resp: &JsonRpcResultOwned) {
+        let Ok(value) = resp else {
+            return;
+        };
+        if value.get() == "null" {
+            return;
+        }
+        if policy == Policy::IfFinalized {
+            #[derive(Deserialize)]
+            #[serde(crate = "ethers_pub_use::serde")]
+            struct Mined {
+                #[serde(rename = "blockNumber")]
+                block_number: Option<U64>,
+            }
+
+            let mined = serde_json::from_str::<Mined>(value.get())
+                .ok()
+                .and_then(|mined| mined.block_number);
+            match mined {
+                Some(number) if self.is_finalized(number.to()).await => {}
+                _ => return,
+            }
+        }
+        self.insert(key, value.clone().into_owned());
+    }
+}
+
+impl<T> fmt::Debug for CachedProvider<T>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CachedProvider")
+            .field("inner", &self.inner)
+            .field("capacity", &self.capacity)
+            .field("ttl", &self.ttl)
+            .field("stats", &self.stats())
+            .finish()
+    }
+}
+
+impl<T> Connection for CachedProvider<T>
+where
+    T: Connection + 'static,
+{
+    fn is_local(&self) -> bool {
+        self.inner.is_local()
+    }
+
+    fn increment_id(&self) -> u64 {
+        self.inner.increment_id()
+    }
+
+    fn default_timeout(&self) -> Option<Duration> {
+        self.inner.default_timeout()
+    }
+
+    fn json_rpc_request(&self, req: &Request<'_>) -> RpcFuture {
+        let policy = policy(req);
+        if policy == Policy::Never {
+            return self.inner.json_rpc_request(req);
+        }
+        let key = cache_key(req);
+        if let Some(hit) = self.lookup(&key) {
+            return Box::pin(async move { Ok(hit) });
+        }
+
+        let fut = self.inner.json_rpc_request(req);
+        let this = self.clone();
+        Box::pin(async move {
+            let resp = fut.await?;
+            this.store(key, policy, &resp).await;
+            Ok(resp)
+        })
+    }
+
+    /// Serve cached responses, and send the rest as a single batch
+    fn batch_request(&self, reqs: &[Request<'_>]) -> BatchRpcFuture {
+        let mut resps = Vec::with_capacity(reqs.len());
+        let mut misses = vec![];
+        let mut miss_reqs = vec![];
+        for req in reqs {
+            let policy = policy(req);
+            let key = (policy != Policy::Never).then(|| cache_key(req));
+            match key.as_deref().and_then(|key| self.lookup(key)) {
+                Some(hit) => resps.push(Some(hit)),
+                None => {
+                    resps.push(None);
+                    misses.push((key, policy));
+                    miss_reqs.push(to_owned(req));
+                }
+            }
+        }
+        if misses.is_empty() {
+            return Box::pin(async move { Ok(resps.into_iter().flatten().collect()) });
+        }
+
+        let fut = self.inner.batch_request(&miss_reqs);
+        let this = self.clone();
+        Box::pin(async move {
+            let mut fetched = fut.await?.into_iter();
+            let mut misses = misses.into_iter();
+            let mut out = Vec::with_capacity(resps.len());
+            for resp in resps {
+                let resp = match resp {
+                    Some(hit) => hit,
+                    None => {
+                        let (key, policy) = misses.next().expect("one miss per empty slot");
+                        let resp = fetched.next().ok_or_else(|| {
+                            TransportError::Custom("batch response too short".into())
+                        })?;
+                        if let Some(key) = key {
+                            this.store(key, policy, &resp).await;
+                        }
+                        resp
+                    }
+                };
+                out.push(resp);
+            }
+            Ok(out)
+        })
+    }
+}
+
+impl<T> PubSubConnection for CachedProvider<T>
+where
+    T: PubSubConnection + 'static,
+{
+    fn uninstall_listener(&self, id: [u8; 32]) -> Result<(), TransportError> {
+        self.inner.uninstall_listener(id)
+    }
+
+    fn install_listener(
+        &self,
+        id: [u8; 32],
+    ) -> Result<mpsc::UnboundedReceiver<Cow<'static, RawValue>>, TransportError> {
+        self.inner.install_listener(id)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ethers_pub_use::serde_json::json;
+    use ethers_transports::transports::MockConnection;
+
+    use super::*;
+
+    fn request<'a>(
+        cached: &CachedProvider<MockConnection>,
+        method: &'a str,
+        params: Value,
+    ) -> Request<'a> {
+        let params = serde_json::value::to_raw_value(&params).unwrap();
+        Request::owned(cached.next_id(), method, Some(params))
+    }
+
+    async fn send(cached: &CachedProvider<MockConnection>, method: &str, params: Value) -> String {
+        let req = request(cached, method, params);
+        cached
+            .json_rpc_request(&req)
+            .await
+            .unwrap()
+            .unwrap()
+            .get()
+            .to_owned()
+    }
+
+    #[tokio::test]
+    async fn it_caches_immutable_results() {
+        let mock = MockConnection::new();
+        mock.push_response("eth_chainId", "0x1");
+        mock.push_response("eth_getBlockByHash", Value::Null);
+        mock.push_response("eth_getBlockByHash", json!({"number": "0x1"}));
+        mock.push_response("eth_blockNumber", "0x1");
+        mock.push_response("eth_blockNumber", "0x2");
+        let cached = CachedProvider::new(mock.clone());
+
+        assert_eq!(send(&cached, "eth_chainId", json!([])).await, r#""0x1""#);
+        assert_eq!(send(&cached, "eth_chainId", json!([])).await, r#""0x1""#);
+
+        // unknown blocks are not cached
+        let params = json!(["0xaa", false]);
+        assert_eq!(
+            send(&cached, "eth_getBlockByHash", params.clone()).await,
+            "null"
+        );
+        send(&cached, "eth_getBlockByHash", params.clone()).await;
+        send(&cached, "eth_getBlockByHash", params).await;
+
+        assert_eq!(
+            send(&cached, "eth_blockNumber", json!([])).await,
+            r#""0x1""#
+        );
+        assert_eq!(
+            send(&cached, "eth_blockNumber", json!([])).await,
+            r#""0x2""#
+        );
+
+        assert_eq!(mock.requests().len(), 5);
+        assert_eq!(
+            cached.stats(),
+            CacheStats {
+                entries: 2,
+                hits: 2,
+                misses: 3,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn it_caches_finalized_receipts() {
+        let mock = MockConnection::new();
+        mock.push_response("eth_getBlockByNumber", json!({"number": "0xa"}));
+        mock.push_response("eth_getTransactionReceipt", json!({"blockNumber": "0x5"}));
+        mock.push_response("eth_getTransactionReceipt", json!({"blockNumber": "0xb"}));
+        mock.push_response("eth_getTransactionReceipt", json!({"blockNumber": "0xb"}));
+        let cached = CachedProvider::new(mock.clone());
+
+        for _ in 0..2 {
+            send(&cached, "eth_getTransactionReceipt", json!(["0x01"])).await;
+        }
+        // above the finalized block, which is not refreshed yet
+        for _ in 0..2 {
+            send(&cached, "eth_getTransactionReceipt", json!(["0x02"])).await;
+        }
+        assert_eq!(mock.pending_responses(), 0);
+        assert_eq!(cached.stats().entries, 1);
+    }
+
+    #[test]
+    fn it_evicts_the_least_recently_used() {
+        let cached = CachedProvider::new(MockConnection::new()).with_capacity(2);
+        let value = || RawValue::from_string("1".to_owned()).unwrap();
+        cached.insert("a".to_owned(), value());
+        cached.insert("b".to_owned(), value());
+        assert!(cached.lookup("a").is_some());
+        cached.insert("c".to_owned(), value());
+        assert!(cached.lookup("b").is_none());
+        assert!(cached.lookup("a").is_some());
+
+        // replacing an entry does not evict another
+        cached.insert("c".to_owned(), value());
+        cached.insert("d".to_owned(), value());
+        assert!(cached.lookup("a").is_none());
+        assert!(cached.lookup("c").is_some() && cached.lookup("d").is_some());
+
+        let inner = cached.lock();
+        assert_eq!(inner.entries.len(), 2);
+        assert_eq!(inner.recency.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn it_serves_batches_and_evicts() {
+        let mock = MockConnection::new();
+        mock.push_response("eth_chainId", "0x1");
+        mock.push_response("eth_getBalance", "0x0");
+        mock.push_response("eth_getBlockByHash", json!({"number": "0x1"}));
+        let cached = CachedProvider::new(mock.clone()).with_capacity(1);
+        send(&cached, "eth_chainId", json!([])).await;
+
+        let reqs = [
+            request(&cached, "eth_getBalance", json!(["0xab", "latest"])),
+            request(&cached, "eth_chainId", json!([])),
+            request(&cached, "eth_getBlockByHash", json!(["0xaa", false])),
+        ];
+        let resps: Vec<_> = cached
+            .batch_request(&reqs)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|resp| resp.unwrap().get().to_owned())
+            .collect();
+        assert_eq!(resps, [r#""0x0""#, r#""0x1""#, r#"{"number":"0x1"}"#]);
+        assert_eq!(mock.requests().len(), 3);
+
+        // the block evicted the chain id
+        assert_eq!(cached.stats().entries, 1);
+        assert!(cached.lookup(&cache_key(&reqs[1])).is_none());
+        assert!(cached.lookup(&cache_key(&reqs[2])).is_some());
+    }
+
+    #[test]
+    fn it_caches_state_pinned_by_hash() {
+        let cached = CachedProvider::new(MockConnection::new());
+        let pinned = request(
+            &cached,
+            "eth_getBalance",
+            json!(["0xab", {"blockHash": "0xaa"}]),
+        );
+        let latest = request(&cached, "eth_getBalance", json!(["0xab", "latest"]));
+        assert_eq!(policy(&pinned), Policy::Always);
+        assert_eq!(policy(&latest), Policy::Never);
+    }
+}
@@ -0,0 +1,375 @@
+//! Call traces decoded with ABI metadata.
+//!
+//! [`Provider::debug_decoded_trace`] replays a transaction with the geth
+//! call tracer, and resolves each frame's function, arguments and revert
+//! reason with a [`TraceDecoder`]. The resulting [`DecodedCallFrame`]
+//! displays as an indented call tree.
+//!
+//! [`Provider::debug_decoded_trace`]: crate::Provider::debug_decoded_trace
+
+use std::fmt;
+
+use ethers_abi_enc::{Revert, RevertDecoder};
+use ethers_abi_dyn::{codec, AbiRegistry, DynType, DynValue, Signature};
+use ethers_primitives::{Address, U256};
+use ethers_pub_use::{hex, serde_json::Value};
+use ethers_rpc_types::{trace::geth::CallFrame, Bytes};
+
+/// Resolves the ABI metadata of the calls in a trace. Implemented by
+/// [`AbiRegistry`], for ABIs of many contracts
+pub trait TraceDecoder {
+    /// The function with a selector. `None` if it is unknown
+    fn function(&self, selector: [u8; 4]) -> Option<Signature>;
+
+    /// Decode revert data. Defaults to recognizing `Error(string)` and
+    /// `Panic(uint256)` only
+    fn revert(&self, data: &[u8]) -> Revert {
+        RevertDecoder::new().decode(data)
+    }
+
+    /// A label for a contract, e.g. its name. `None` if it is unknown
+    fn contract(&self, _address: Address) -> Option<String> {
+        None
+    }
+}
+
+/// Resolves functions from a list of signatures
+impl TraceDecoder for [Signature] {
+    fn function(&self, selector: [u8; 4]) -> Option<Signature> {
+        self.iter()
+            .find(|signature| signature.selector() == Some(selector))
+            .cloned()
+    }
+}
+
+/// Resolves functions, custom errors and contract labels from the registry
+impl TraceDecoder for AbiRegistry {
+    fn function(&self, selector: [u8; 4]) -> Option<Signature> {
+        AbiRegistry::function(self, selector).cloned()
+    }
+
+    fn revert(&self, data: &[u8]) -> Revert {
+        match RevertDecoder::new().decode(data) {
+            Revert::Unknown(data) => match self.decode_error(&data) {
+                Some(error) => Revert::Custom {
+                    name: error.name().to_owned(),
+                    signature: error.signature.to_string(),
+                    args: DynValue::Tuple(error.values).into_token(),
+                },
+                None => Revert::Unknown(data),
+            },
+            revert => revert,
+        }
+    }
+
+    fn contract(&self, address: Address) -> Option<String> {
+        self.label(address).map(ToOwned::to_owned)
+    }
+}
+
+/// A decoded function call
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DecodedCall {
+    /// The function signature
+    pub signature: Signature,
+    /// The arguments, in the JSON representation of [`codec`]
+    pub args: Vec<Value>,
+}
+
+impl fmt::Display for DecodedCall {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = self.signature.name.as_deref().unwrap_or_default();
+        write!(f, "{name}(")?;
+        fmt_values(f, &self.signature.params, &self.args)?;
+        f.write_str(")")
+    }
+}
+
+/// Write values separated by commas
+fn fmt_values<'a>(
+    f: &mut fmt::Formatter<'_>,
+    types: impl IntoIterator<Item = &'a DynType>,
+    values: &[Value],
+) -> fmt::Result {
+    for (i, (ty, value)) in types.into_iter().zip(values).enumerate() {
+        if i > 0 {
+            f.write_str(", ")?;
+        }
+        fmt_value(f, ty, value)?;
+    }
+    Ok(())
+}
+
+/// Write a value. Strings are quoted, other scalars are written bare
+fn fmt_value(f: &mut fmt::Formatter<'_>, ty: &DynType, value: &Value) -> fmt::Result {
+    match (ty, value) {
+        (DynType::String, Value::String(s)) => write!(f, "{s:?}"),
+        (DynType::Array(inner) | DynType::FixedArray(inner, _), Value::Array(values)) => {
+            f.write_str("[")?;
+            fmt_values(f, std::iter::repeat_n(&**inner, values.len()), values)?;
+            f.write_str("]")
+        }
+        (DynType::Tuple(types), Value::Array(values)) => {
+            f.write_str("(")?;
+            fmt_values(f, types, values)?;
+            f.write_str(")")
+        }
+        (_, Value::String(s)) => f.write_str(s),
+        (_, value) => write!(f, "{value}"),
+    }
+}
+
+/// A call frame, with its function, arguments and revert reason decoded
+#[derive(Clone, Debug, PartialEq)]
+pub struct DecodedCallFrame {
+    /// Call type, e.g. `CALL` or `CREATE2`
+    pub typ: String,
+    /// Caller
+    pub from: Address,
+    /// Callee. `None` if a creation failed
+    pub to: Option<Address>,
+    /// The callee's label, if known to the decoder
+    pub contract: Option<String>,
+    /// Value transferred
+    pub value: Option<U256>,
+    /// Gas used by the call
+    pub gas_used: U256,
+    /// Calldata or init code
+    pub input: Bytes,
+    /// The decoded call. `None` for creations, and for unknown functions
+    /// or calldata that does not decode
+    pub call: Option<DecodedCall>,
+    /// Returndata or deployed code
+    pub output: Option<Bytes>,
+    /// Error, if the call failed
+    pub error: Option<String>,
+    /// Decoded revert data, if the call failed
+    pub revert: Option<Revert>,
+    /// Child calls
+    pub calls: Vec<DecodedCallFrame>,
+}
+
+impl DecodedCallFrame {
+    /// Decode a call tracer frame and its children
+    pub fn decode<D>(frame: CallFrame, decoder: &D) -> Self
+    where
+        D: TraceDecoder + ?Sized,
+    {
+        let call = match frame.input.split_first_chunk::<4>() {
+            Some((selector, _)) if !frame.typ.starts_with("CREATE") => {
+                decoder.function(*selector).and_then(|signature| {
+                    let Value::Array(args) = codec::decode(&signature, &frame.input).ok()? else {
+                        return None;
+                    };
+                    Some(DecodedCall { signature, args })
+                })
+            }
+            _ => None,
+        };
+        let revert = frame
+            .error
+            .as_ref()
+            .map(|_| decoder.revert(frame.output.as_deref().unwrap_or_default()));
+
+        Self {
+            contract: frame.to.and_then(|to| decoder.contract(to)),
+            call,
+            revert,
+            calls: frame
+                .calls
+                .into_iter()
+                .map(|frame| Self::decode(frame, decoder))
+                .collect(),
+            typ: frame.typ,
+            from: frame.from,
+            to: frame.to,
+            value: frame.value,
+            gas_used: frame.gas_used,
+            input: frame.input,
+            output: frame.output,
+            error: frame.error,
+        }
+    }
+
+    /// `true` if the call failed
+    pub fn is_error(&self) -> bool {
+        self.error.is_some()
+    }
+
+    /// The frames of the tree in depth-first order, with their depth. The
+    /// root has depth 0
+    pub fn frames(&self) -> Vec<(usize, &DecodedCallFrame)> {
+        let mut frames = vec![];
+        let mut stack = vec![(0, self)];
+        while let Some((depth, frame)) = stack.pop() {
+            frames.push((depth, frame));
+            stack.extend(frame.calls.iter().rev().map(|call| (depth + 1, call)));
+        }
+        frames
+    }
+
+    fn fmt_line(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ", self.typ)?;
+        match (&self.contract, self.to) {
+            (Some(contract), _) => f.write_str(contract)?,
+            (None, Some(to)) => write!(f, "{to:?}")?,
+            (None, None) => f.write_str("<failed creation>")?,
+        }
+        if !self.typ.starts_with("CREATE") {
+            match &self.call {
+                Some(call) => write!(f, "::{call}")?,
+                None if self.input.is_empty() => {}
+                None => write!(
+                    f,
+                    "::0x{}",
+                    hex::encode(&self.input[..self.input.len().min(4)])
+                )?,
+            }
+        }
+        match self.value {
+            Some(value) if value != U256::ZERO => write!(f, " {{value: {value}}}")?,
+            _ => {}
+        }
+        match (&self.revert, &self.error) {
+            (Some(Revert::Empty | Revert::Unknown(_)) | None, Some(error)) => {
+                write!(f, " [{error}]")
+            }
+            (Some(revert), _) => write!(f, " [{revert}]"),
+            (None, None) => Ok(()),
+        }
+    }
+}
+
+/// Write the call tree, one frame per line, indented by depth
+impl fmt::Display for DecodedCallFrame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, (depth, frame)) in self.frames().into_iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{:width$}", "", width = depth * 2)?;
+            frame.fmt_line(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ethers_abi_enc::{selector, sol_type, SolType};
+    use ethers_primitives::B256;
+    use ethers_pub_use::serde_json::json;
+    use ethers_transports::transports::MockConnection;
+
+    use super::*;
+    use crate::Provider;
+
+    fn frame(typ: &str, to: Address, input: Vec<u8>) -> CallFrame {
+        CallFrame {
+            typ: typ.to_owned(),
+            from: Address::repeat_byte(0x01),
+            to: Some(to),
+            value: None,
+            gas: U256::from(100_000),
+            gas_used: U256::from(21_000),
+            input: input.into(),
+            output: None,
+            error: None,
+            revert_reason: None,
+            calls: vec![],
+        }
+    }
+
+    struct Labels(Vec<Signature>);
+
+    impl TraceDecoder for Labels {
+        fn function(&self, selector: [u8; 4]) -> Option<Signature> {
+            self.0.function(selector)
+        }
+
+        fn contract(&self, address: Address) -> Option<String> {
+            (address == Address::repeat_byte(0x11)).then(|| "Router".to_owned())
+        }
+    }
+
+    #[test]
+    fn it_decodes_call_trees() {
+        let token = Address::repeat_byte(0x22);
+        let recipient = Address::repeat_byte(0x33);
+
+        let mut transfer = selector("transfer(address,uint256)").to_vec();
+        transfer.extend(<(sol_type::Address, sol_type::Uint<256>)>::encode_params((
+            recipient,
+            U256::from(1000),
+        )));
+        let mut inner = frame("CALL", token, transfer);
+        inner.error = Some("execution reverted".to_owned());
+        let mut revert = selector("Error(string)").to_vec();
+        revert.extend(<(sol_type::String,)>::encode_params((
+            "insufficient balance".to_owned(),
+        )));
+        inner.output = Some(revert.into());
+
+        let mut swap = selector("swap(string,uint8[])").to_vec();
+        swap.extend(
+            <(sol_type::String, sol_type::Array<sol_type::Uint<8>>)>::encode_params((
+                "exact in".to_owned(),
+                vec![1, 2],
+            )),
+        );
+        let mut root = frame("CALL", Address::repeat_byte(0x11), swap);
+        root.error = Some("execution reverted".to_owned());
+        root.calls = vec![
+            inner,
+            frame("STATICCALL", token, vec![0xde, 0xad, 0xbe, 0xef]),
+        ];
+
+        let decoder = Labels(vec![
+            Signature::parse("transfer(address,uint256)").unwrap(),
+            Signature::parse("swap(string,uint8[])").unwrap(),
+        ]);
+        let trace = DecodedCallFrame::decode(root, &decoder);
+
+        assert_eq!(trace.contract.as_deref(), Some("Router"));
+        let call = trace.calls[0].call.as_ref().unwrap();
+        assert_eq!(call.signature.to_string(), "transfer(address,uint256)");
+        assert_eq!(call.args, [json!(format!("{recipient:?}")), json!("1000")]);
+        assert_eq!(
+            trace.calls[0].revert,
+            Some(Revert::Error("insufficient balance".to_owned()))
+        );
+        assert!(trace.calls[1].call.is_none());
+        assert_eq!(trace.revert, Some(Revert::Empty));
+
+        assert_eq!(
+            trace.to_string(),
+            format!(
+                "CALL Router::swap(\"exact in\", [1, 2]) [execution reverted]\n  \
+                 CALL {token:?}::transfer({recipient:?}, 1000) [reverted: insufficient balance]\n  \
+                 STATICCALL {token:?}::0xdeadbeef"
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn it_traces_with_the_call_tracer() {
+        let mock = MockConnection::new();
+        let provider = Provider::new(mock.clone());
+        let token = Address::repeat_byte(0x22);
+
+        let mut input = selector("balanceOf(address)").to_vec();
+        input.extend(<(sol_type::Address,)>::encode_params((token,)));
+        mock.push_response("debug_traceTransaction", frame("CALL", token, input));
+
+        let signatures = [Signature::parse("balanceOf(address)").unwrap()];
+        let trace = provider
+            .debug_decoded_trace(B256::repeat_byte(0xaa), &signatures[..])
+            .await
+            .unwrap();
+        assert!(!trace.is_error());
+        assert_eq!(trace.call.unwrap().args, [json!(format!("{token:?}"))]);
+
+        let params = mock.requests()[0].params.clone().unwrap();
+        assert_eq!(params[1], json!({ "tracer": "callTracer" }));
+    }
+}
@@ -0,0 +1,273 @@
+use ethers_primitives::B256;
+use ethers_rpc_types::engine::{
+    ExecutionPayloadEnvelopeV2, ExecutionPayloadEnvelopeV3, ExecutionPayloadV1, ExecutionPayloadV2,
+    ExecutionPayloadV3, ForkchoiceState, ForkchoiceUpdated, PayloadAttributes, PayloadId,
+    PayloadStatus,
+};
+use ethers_transports::{Connection, RpcCall};
+
+use crate::Provider;
+
+/// Typed methods for the `engine_` namespace.
+///
+/// The Engine API is served on a separate, authenticated port. Connect to
+/// it with [`Http::new_with_jwt`]. Import this trait to drive an execution
+/// client as a consensus client would.
+///
+/// [`Http::new_with_jwt`]: ethers_transports::Http::new_with_jwt
+pub trait EngineApi: Connection + Sized {
+    /// Validate and execute a payload
+    fn new_payload_v1(
+        &self,
+        payload: ExecutionPayloadV1,
+    ) -> RpcCall<&Self, Self, (ExecutionPayloadV1,), PayloadStatus> {
+        self.request("engine_newPayloadV1", (payload,))
+    }
+
+    /// Validate and execute a payload, since Shanghai
+    fn new_payload_v2(
+        &self,
+        payload: ExecutionPayloadV2,
+    ) -> RpcCall<&Self, Self, (ExecutionPayloadV2,), PayloadStatus> {
+        self.request("engine_newPayloadV2", (payload,))
+    }
+
+    /// Validate and execute a payload, since Cancun. The versioned hashes
+    /// must match the blob transactions of the payload
+    fn new_payload_v3(
+        &self,
+        payload: ExecutionPayloadV3,
+        versioned_hashes: Vec<B256>,
+        parent_beacon_block_root: B256,
+    ) -> RpcCall<&Self, Self, (ExecutionPayloadV3, Vec<B256>, B256), PayloadStatus> {
+        self.request(
+            "engine_newPayloadV3",
+            (payload, versioned_hashes, parent_beacon_block_root),
+        )
+    }
+
+    /// Update the head, safe and finalized blocks. If attributes are given,
+    /// start building a payload on the new head
+    fn fork_choice_updated_v1(
+        &self,
+        state: ForkchoiceState,
+        attributes: Option<PayloadAttributes>,
+    ) -> RpcCall<&Self, Self, (ForkchoiceState, Option<PayloadAttributes>), ForkchoiceUpdated> {
+        self.request("engine_forkchoiceUpdatedV1", (state, attributes))
+    }
+
+    /// Update the fork choice, since Shanghai. Attributes must include
+    /// withdrawals
+    fn fork_choice_updated_v2(
+        &self,
+        state: ForkchoiceState,
+        attributes: Option<PayloadAttributes>,
+    ) -> RpcCall<&Self, Self, (ForkchoiceState, Option<PayloadAttributes>), ForkchoiceUpdated> {
+        self.request("engine_forkchoiceUpdatedV2", (state, attributes))
+    }
+
+    /// Update the fork choice, since Cancun. Attributes must include
+    /// withdrawals and the parent beacon block root
+    fn fork_choice_updated_v3(
+        &self,
+        state: ForkchoiceState,
+        attributes: Option<PayloadAttributes>,
+    ) -> RpcCall<&Self, Self, (ForkchoiceState, Option<PayloadAttributes>), ForkchoiceUpdated> {
+        self.request("engine_forkchoiceUpdatedV3", (state, attributes))
+    }
+
+    /// Get a payload started by a fork choice update
+    fn get_payload_v1(
+        &self,
+        id: PayloadId,
+    ) -> RpcCall<&Self, Self, (PayloadId,), ExecutionPayloadV1> {
+        self.request("engine_getPayloadV1", (id,))
+    }
+
+    /// Get a payload and its value, since Shanghai
+    fn get_payload_v2(
+        &self,
+        id: PayloadId,
+    ) -> RpcCall<&Self, Self, (PayloadId,), ExecutionPayloadEnvelopeV2> {
+        self.request("engine_getPayloadV2", (id,))
+    }
+
+    /// Get a payload, its value and its blobs, since Cancun
+    fn get_payload_v3(
+        &self,
+        id: PayloadId,
+    ) -> RpcCall<&Self, Self, (PayloadId,), ExecutionPayloadEnvelopeV3> {
+        self.request("engine_getPayloadV3", (id,))
+    }
+
+    /// Exchange the lists of supported Engine API methods
+    fn exchange_capabilities(
+        &self,
+        capabilities: Vec<String>,
+    ) -> RpcCall<&Self, Self, (Vec<String>,), Vec<String>> {
+        self.request("engine_exchangeCapabilities", (capabilities,))
+    }
+}
+
+impl<T> EngineApi for Provider<T> where T: Connection {}
+
+#[cfg(test)]
+mod test {
+    use ethers_primitives::{Address, H64, U256, U64};
+    use ethers_pub_use::serde_json::{self, json, Value};
+    use ethers_rpc_types::engine::{BlobsBundleV1, PayloadStatusKind};
+    use ethers_transports::transports::MockConnection;
+
+    use super::*;
+
+    fn params(mock: &MockConnection) -> Vec<(String, Value)> {
+        mock.requests()
+            .into_iter()
+            .map(|req| (req.method, req.params.unwrap()))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn it_sends_payloads() {
+        let mock = MockConnection::new();
+        let provider = Provider::new(mock.clone());
+        let payload = ExecutionPayloadV3 {
+            payload_inner: ExecutionPayloadV2 {
+                payload_inner: ExecutionPayloadV1 {
+                    block_number: U64::from(1),
+                    ..Default::default()
+                },
+                withdrawals: vec![],
+            },
+            blob_gas_used: U64::from(0x20000),
+            excess_blob_gas: U64::ZERO,
+        };
+        let hashes = vec![B256::repeat_byte(1)];
+        let root = B256::repeat_byte(2);
+
+        mock.push_response(
+            "engine_newPayloadV3",
+            json!({ "status": "VALID", "latestValidHash": B256::repeat_byte(3) }),
+        );
+        let status = provider
+            .new_payload_v3(payload.clone(), hashes.clone(), root)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(status.is_valid());
+        assert_eq!(status.latest_valid_hash, Some(B256::repeat_byte(3)));
+
+        assert_eq!(
+            params(&mock),
+            vec![(
+                "engine_newPayloadV3".to_owned(),
+                json!([serde_json::to_value(&payload).unwrap(), hashes, root]),
+            )]
+        );
+    }
+
+    #[tokio::test]
+    async fn it_updates_the_fork_choice() {
+        let mock = MockConnection::new();
+        let provider = Provider::new(mock.clone());
+        let state = ForkchoiceState {
+            head_block_hash: B256::repeat_byte(1),
+            ..Default::default()
+        };
+        let attributes = PayloadAttributes {
+            timestamp: U64::from(12),
+            suggested_fee_recipient: Address::repeat_byte(4),
+            withdrawals: Some(vec![]),
+            parent_beacon_block_root: Some(B256::repeat_byte(5)),
+            ..Default::default()
+        };
+
+        mock.push_response(
+            "engine_forkchoiceUpdatedV3",
+            json!({ "payloadStatus": { "status": "SYNCING", "latestValidHash": null }, "payloadId": null }),
+        );
+        mock.push_response(
+            "engine_forkchoiceUpdatedV3",
+            json!({
+                "payloadStatus": { "status": "VALID", "latestValidHash": B256::repeat_byte(1) },
+                "payloadId": "0x0000000000000007"
+            }),
+        );
+        let updated = provider
+            .fork_choice_updated_v3(state, None)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(updated.payload_status.status, PayloadStatusKind::Syncing);
+        assert_eq!(updated.payload_id, None);
+        let updated = provider
+            .fork_choice_updated_v3(state, Some(attributes.clone()))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(updated.payload_id, Some(H64::from(U64::from(7))));
+
+        assert_eq!(
+            params(&mock),
+            vec![
+                (
+                    "engine_forkchoiceUpdatedV3".to_owned(),
+                    json!([state, null]),
+                ),
+                (
+                    "engine_forkchoiceUpdatedV3".to_owned(),
+                    json!([state, attributes]),
+                ),
+            ]
+        );
+        let sent = &params(&mock)[1].1[1];
+        assert_eq!(sent["parentBeaconBlockRoot"], json!(B256::repeat_byte(5)));
+        assert_eq!(
+            sent["suggestedFeeRecipient"],
+            json!(Address::repeat_byte(4))
+        );
+    }
+
+    #[tokio::test]
+    async fn it_gets_payloads() {
+        let mock = MockConnection::new();
+        let provider = Provider::new(mock.clone());
+        let id = H64::from(U64::from(7));
+        let envelope = ExecutionPayloadEnvelopeV3 {
+            execution_payload: Default::default(),
+            block_value: U256::from(1),
+            blobs_bundle: BlobsBundleV1::default(),
+            should_override_builder: false,
+        };
+
+        mock.push_response("engine_getPayloadV3", &envelope);
+        mock.push_response("engine_exchangeCapabilities", ["engine_newPayloadV3"]);
+        assert_eq!(
+            provider.get_payload_v3(id).await.unwrap().unwrap(),
+            envelope
+        );
+        let capabilities = provider
+            .exchange_capabilities(vec![
+                "engine_newPayloadV3".to_owned(),
+                "engine_getPayloadV4".to_owned(),
+            ])
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(capabilities, vec!["engine_newPayloadV3"]);
+
+        assert_eq!(
+            params(&mock),
+            vec![
+                (
+                    "engine_getPayloadV3".to_owned(),
+                    json!(["0x0000000000000007"])
+                ),
+                (
+                    "engine_exchangeCapabilities".to_owned(),
+                    json!([["engine_newPayloadV3", "engine_getPayloadV4"]]),
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,365 @@
+//! [ENS] name resolution, using the registry and resolver contracts.
+//!
+//! Names are expected to already be normalized. Resolution follows the
+//! registry's resolver for the name, and does not support [ENSIP-10]
+//! wildcard resolution.
+//!
+//! [ENS]: https://docs.ens.domains
+//! [ENSIP-10]: https://docs.ens.domains/ens-improvement-proposals/ensip-10-wildcard-resolution
+
+use ethers_abi_enc::{selector, sol_type, SolType};
+use ethers_primitives::{keccak256, Address, B256, U256};
+use ethers_pub_use::{hex, thiserror};
+use ethers_rpc_types::{BlockId, Bytes, NameOrAddress, TransactionRequest};
+use ethers_transports::{Connection, RpcCall};
+
+use crate::{Provider, ProviderError};
+
+/// The ENS registry, at the same address on mainnet and the main testnets
+pub const ENS_ADDRESS: Address = Address::new([
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x0c, 0x2e, 0x07, 0x4e, 0xc6, 0x9a, 0x0d, 0xfb, 0x29, 0x97, 0xba,
+    0x6c, 0x7d, 0x2e, 0x1e,
+]);
+
+/// The [SLIP-44] coin type of ether, used by [ENSIP-9] multi-coin records
+///
+/// [SLIP-44]: https://github.com/satoshilabs/slips/blob/master/slip-0044.md
+/// [ENSIP-9]: https://docs.ens.domains/ens-improvement-proposals/ensip-9-multichain-address-resolution
+pub const ETH_COIN_TYPE: u64 = 60;
+
+const RESOLVER: [u8; 4] = selector("resolver(bytes32)");
+const ADDR: [u8; 4] = selector("addr(bytes32)");
+const ADDR_COIN: [u8; 4] = selector("addr(bytes32,uint256)");
+const NAME: [u8; 4] = selector("name(bytes32)");
+const TEXT: [u8; 4] = selector("text(bytes32,string)");
+
+/// ENS resolution errors
+#[derive(Debug, thiserror::Error)]
+pub enum EnsError {
+    /// The registry has no resolver for the name
+    #[error("no ENS resolver for {0}")]
+    NoResolver(String),
+    /// The resolver has no record of the requested kind for the name
+    #[error("no ENS {record} record for {name}")]
+    NoRecord {
+        /// The record kind, e.g. `addr`
+        record: &'static str,
+        /// The name
+        name: String,
+    },
+    /// The reverse record of an address names an ENS name that does not
+    /// resolve back to the address
+    #[error("reverse record {name} of {address:?} does not resolve to it")]
+    ReverseMismatch {
+        /// The address
+        address: Address,
+        /// The name in the reverse record
+        name: String,
+    },
+    /// A registry or resolver response could not be decoded
+    #[error("invalid ENS response: {0}")]
+    Abi(#[from] ethers_abi_enc::Error),
+}
+
+/// Compute the [namehash] of a name
+///
+/// [namehash]: https://docs.ens.domains/contract-api-reference/name-processing#hashing-names
+pub fn namehash(name: &str) -> B256 {
+    name.rsplit('.')
+        .filter(|label| !label.is_empty())
+        .fold(B256::zero(), |node, label| {
+            let mut buf = [0u8; 64];
+            buf[..32].copy_from_slice(node.as_bytes());
+            buf[32..].copy_from_slice(keccak256(label).as_bytes());
+            keccak256(buf)
+        })
+}
+
+/// The name of the reverse record of an address
+pub fn reverse_name(address: Address) -> String {
+    format!("{}.addr.reverse", hex::encode(address.as_bytes()))
+}
+
+/// Decode a single return value
+fn decode_return<S: SolType>(data: &[u8]) -> Result<S::RustType, EnsError> {
+    let token = ethers_abi_enc::decode_params::<(S,)>(data)?;
+    Ok(<(S,)>::detokenize(&token)?.0)
+}
+
+impl<T> Provider<T>
+where
+    T: Connection + Unpin,
+{
+    fn ens_call(
+        &self,
+        to: Address,
+        data: Bytes,
+    ) -> RpcCall<&Self, Self, (TransactionRequest, BlockId), Bytes> {
+        let tx = TransactionRequest::default().to(to).data(data);
+        self.request("eth_call", (tx, BlockId::default()))
+    }
+
+    /// Get the resolver of a name from the registry
+    pub async fn ens_resolver(&self, name: &str) -> Result<Address, ProviderError> {
+        let data =
+            <(sol_type::FixedBytes<32>,)>::encode_with_selector(RESOLVER, (namehash(name).0,));
+        let resp = self.ens_call(ENS_ADDRESS, data).await??;
+        // an empty response means there is no registry on this chain
+        if resp.is_empty() {
+            return Err(EnsError::NoResolver(name.to_owned()).into());
+        }
+        let resolver = decode_return::<sol_type::Address>(&resp)?;
+        if resolver.is_zero() {
+            return Err(EnsError::NoResolver(name.to_owned()).into());
+        }
+        Ok(resolver)
+    }
+
+    /// Query a name's resolver, returning `None` for empty responses
+    async fn query_resolver(
+        &self,
+        name: &str,
+        data: Bytes,
+    ) -> Result<Option<Bytes>, ProviderError> {
+        let resolver = self.ens_resolver(name).await?;
+        let resp = self.ens_call(resolver, data).await??;
+        Ok((!resp.is_empty()).then_some(resp))
+    }
+
+    /// Resolve an ENS name to an address, e.g. `vitalik.eth`
+    pub async fn resolve_name(&self, name: &str) -> Result<Address, ProviderError> {
+        let node = namehash(name).0;
+        let no_record = || EnsError::NoRecord {
+            record: "addr",
+            name: name.to_owned(),
+        };
+
+        let resp = self
+            .query_resolver(
+                name,
+                <(sol_type::FixedBytes<32>,)>::encode_with_selector(ADDR, (node,)),
+            )
+            .await?
+            .ok_or_else(no_record)?;
+        let address = decode_return::<sol_type::Address>(&resp)?;
+        if address.is_zero() {
+            return Err(no_record().into());
+        }
+        Ok(address)
+    }
+
+    /// Look up the primary ENS name of an address, using its reverse record.
+    ///
+    /// Anyone can set a reverse record naming any ENS name, so the name is
+    /// checked to resolve back to the address
+    pub async fn lookup_address(&self, address: Address) -> Result<String, ProviderError> {
+        let reverse = reverse_name(address);
+        let no_record = || EnsError::NoRecord {
+            record: "name",
+            name: reverse.clone(),
+        };
+
+        let resp = self
+            .query_resolver(
+                &reverse,
+                <(sol_type::FixedBytes<32>,)>::encode_with_selector(NAME, (namehash(&reverse).0,)),
+            )
+            .await?
+            .ok_or_else(no_record)?;
+        let name = decode_return::<sol_type::String>(&resp)?;
+        if name.is_empty() {
+            return Err(no_record().into());
+        }
+
+        if self.resolve_name(&name).await? != address {
+            return Err(EnsError::ReverseMismatch { address, name }.into());
+        }
+        Ok(name)
+    }
+
+    /// Get a text record of an ENS name, e.g. `url` or `com.twitter`
+    pub async fn resolve_text(&self, name: &str, key: &str) -> Result<String, ProviderError> {
+        let resp = self
+            .query_resolver(
+                name,
+                <(sol_type::FixedBytes<32>, sol_type::String)>::encode_with_selector(
+                    TEXT,
+                    (namehash(name).0, key.to_owned()),
+                ),
+            )
+            .await?;
+        let text = match resp {
+            Some(resp) => decode_return::<sol_type::String>(&resp)?,
+            None => Default::default(),
+        };
+        if text.is_empty() {
+            return Err(EnsError::NoRecord {
+                record: "text",
+                name: name.to_owned(),
+            }
+            .into());
+        }
+        Ok(text)
+    }
+
+    /// Get the avatar record of an ENS name.
+    ///
+    /// This is the raw record: an `https`, `ipfs` or `data` URI, or an
+    /// `eip155` NFT reference. NFT references are not resolved to an image
+    pub async fn resolve_avatar(&self, name: &str) -> Result<String, ProviderError> {
+        self.resolve_text(name, "avatar").await
+    }
+
+    /// Get the [ENSIP-9] address of an ENS name for another chain. The
+    /// address is in the chain's native binary format
+    ///
+    /// [ENSIP-9]: https://docs.ens.domains/ens-improvement-proposals/ensip-9-multichain-address-resolution
+    pub async fn resolve_coin_address(
+        &self,
+        name: &str,
+        coin_type: u64,
+    ) -> Result<Bytes, ProviderError> {
+        let resp = self
+            .query_resolver(
+                name,
+                <(sol_type::FixedBytes<32>, sol_type::Uint<256>)>::encode_with_selector(
+                    ADDR_COIN,
+                    (namehash(name).0, U256::from(coin_type)),
+                ),
+            )
+            .await?;
+        let address = match resp {
+            Some(resp) => decode_return::<sol_type::Bytes>(&resp)?,
+            None => Default::default(),
+        };
+        if address.is_empty() {
+            return Err(EnsError::NoRecord {
+                record: "addr",
+                name: name.to_owned(),
+            }
+            .into());
+        }
+        Ok(address.into())
+    }
+
+    /// Resolve a name or address to an address
+    pub async fn resolve_name_or_address(
+        &self,
+        name_or_address: &NameOrAddress,
+    ) -> Result<Address, ProviderError> {
+        match name_or_address {
+            NameOrAddress::Address(address) => Ok(*address),
+            NameOrAddress::Name(name) => self.resolve_name(name).await,
+        }
+    }
+
+    /// Resolve an ENS name in the recipient of a transaction request
+    pub async fn resolve_request(
+        &self,
+        mut tx: TransactionRequest,
+    ) -> Result<TransactionRequest, ProviderError> {
+        if let Some(to) = &tx.to {
+            tx.to = Some(self.resolve_name_or_address(to).await?.into());
+        }
+        Ok(tx)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::borrow::Cow;
+
+    use ethers_pub_use::serde_json::{self, value::to_raw_value, Value};
+    use ethers_transports::{
+        common::RpcOutcome,
+        transports::{MockConnection, MockRequest},
+    };
+
+    use super::*;
+
+    #[test]
+    fn it_hashes_names() {
+        assert_eq!(namehash(""), B256::zero());
+        assert_eq!(
+            namehash("eth"),
+            "0x93cdeb708b7545dc668eb9280176169d1c33cfd8ed6f04690a0bcc88a93fc4ae"
+                .parse()
+                .unwrap()
+        );
+        assert_eq!(
+            namehash("foo.eth"),
+            "0xde9b09fd7c5f901e23a3f19fecc54828e9c848539801e86591bd9801b019f84f"
+                .parse()
+                .unwrap()
+        );
+        assert_eq!(
+            reverse_name(Address::repeat_byte(0xab)),
+            "abababababababababababababababababababab.addr.reverse"
+        );
+    }
+
+    const RESOLVER_ADDRESS: Address = Address::repeat_byte(0x22);
+    const OWNER: Address = Address::repeat_byte(0x33);
+
+    /// Serves `eth_call` with a registry where every name has the same
+    /// resolver, and a resolver with fixed records
+    fn mock_ens(req: &MockRequest) -> RpcOutcome {
+        assert_eq!(req.method, "eth_call");
+        let (tx, _): (TransactionRequest, Value) =
+            serde_json::from_value(req.params.clone().unwrap()).unwrap();
+        let to = *tx.to.unwrap().as_address().unwrap();
+        let data = tx.data.unwrap();
+
+        let sel = &data[..4];
+        let resp = match to {
+            _ if to == ENS_ADDRESS && sel == RESOLVER => {
+                <(sol_type::Address,)>::encode_params((RESOLVER_ADDRESS,))
+            }
+            _ if to == RESOLVER_ADDRESS && sel == ADDR => {
+                let owner = if data[4..] == namehash("owner.eth").0 {
+                    OWNER
+                } else {
+                    Address::zero()
+                };
+                <(sol_type::Address,)>::encode_params((owner,))
+            }
+            _ if to == RESOLVER_ADDRESS && sel == NAME => {
+                <(sol_type::String,)>::encode_params(("owner.eth".to_owned(),))
+            }
+            _ if to == RESOLVER_ADDRESS && sel == TEXT => {
+                <(sol_type::String,)>::encode_params(("ipfs://avatar".to_owned(),))
+            }
+            _ => Bytes::new(),
+        };
+        Ok(Ok(Cow::Owned(to_raw_value(&resp).unwrap())))
+    }
+
+    #[tokio::test]
+    async fn it_resolves_names() {
+        let provider = Provider::new(MockConnection::new().with_handler(mock_ens));
+
+        assert_eq!(provider.resolve_name("owner.eth").await.unwrap(), OWNER);
+        assert!(matches!(
+            provider.resolve_name("nobody.eth").await,
+            Err(ProviderError::Ens(EnsError::NoRecord {
+                record: "addr",
+                ..
+            }))
+        ));
+        assert_eq!(provider.lookup_address(OWNER).await.unwrap(), "owner.eth");
+        assert!(matches!(
+            provider.lookup_address(Address::repeat_byte(0x44)).await,
+            Err(ProviderError::Ens(EnsError::ReverseMismatch { .. }))
+        ));
+        assert_eq!(
+            provider.resolve_avatar("owner.eth").await.unwrap(),
+            "ipfs://avatar"
+        );
+
+        let tx = provider
+            .resolve_request(TransactionRequest::default().to("owner.eth"))
+            .await
+            .unwrap();
+        assert_eq!(tx.to, Some(NameOrAddress::Address(OWNER)));
+    }
+}
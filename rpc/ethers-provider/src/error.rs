@@ -0,0 +1,125 @@
+use ethers_abi_enc::{Revert, RevertDecoder};
+use ethers_pub_use::{
+    hex,
+    serde_json::{self, Value},
+    thiserror,
+};
+use ethers_rpc_types::BlockNumber;
+use ethers_transports::{common::ErrorObject, TransportError};
+
+use crate::{ens::EnsError, limits::LimitError, node_client::UnknownClient, validate::Violation};
+
+/// Errors produced by provider methods that do more than forward a single
+/// request
+#[derive(Debug, thiserror::Error)]
+pub enum ProviderError {
+    /// The transport failed
+    #[error(transparent)]
+    Transport(#[from] TransportError),
+
+    /// The node responded with a JSON-RPC error
+    #[error("JSON-RPC error {}: {}", .0.code(), .0.message())]
+    JsonRpc(ErrorObject<'static>),
+
+    /// The node's client could not be identified
+    #[error(transparent)]
+    UnknownClient(#[from] UnknownClient),
+
+    /// The node does not serve the method. Legacy methods, such as the
+    /// mining methods, are removed by many post-merge nodes
+    #[error("method {0} is not supported by the node")]
+    UnsupportedMethod(&'static str),
+
+    /// The node has no block for the tag, e.g. `safe` or `finalized` on a
+    /// chain that has not yet finalized a block
+    #[error("the node has no {0} block")]
+    UnknownBlock(BlockNumber),
+
+    /// ENS resolution failed
+    #[error(transparent)]
+    Ens(#[from] EnsError),
+
+    /// The request would exceed a server-side limit. See
+    /// [`ProviderLimits`](crate::ProviderLimits)
+    #[error(transparent)]
+    Limit(#[from] LimitError),
+
+    /// The response violated an invariant. See [`ValidationMode`]
+    ///
+    /// [`ValidationMode`]: crate::ValidationMode
+    #[error("invalid response to {method}: {}", .violations[0])]
+    InvalidResponse {
+        /// The request method
+        method: String,
+        /// The violated invariants. Not empty
+        violations: Vec<Violation>,
+    },
+}
+
+impl From<ErrorObject<'static>> for ProviderError {
+    fn from(err: ErrorObject<'static>) -> Self {
+        Self::JsonRpc(err)
+    }
+}
+
+impl ProviderError {
+    /// The revert data of a failed call or gas estimation. See
+    /// [`revert_data`]
+    pub fn revert_data(&self) -> Option<Vec<u8>> {
+        match self {
+            Self::JsonRpc(err) => revert_data(err),
+            _ => None,
+        }
+    }
+
+    /// Decode the revert data of a failed call or gas estimation
+    pub fn revert(&self, decoder: &RevertDecoder) -> Option<Revert> {
+        self.revert_data().map(|data| decoder.decode(&data))
+    }
+}
+
+/// Extract revert data from a JSON-RPC error. Nodes return it as a hex
+/// string in the error data, or nested in a `data` field. Reverts without
+/// data have empty revert data
+pub fn revert_data(err: &ErrorObject<'_>) -> Option<Vec<u8>> {
+    let data = err
+        .data()
+        .and_then(|data| serde_json::from_str::<Value>(data.get()).ok());
+    let hex_data = match &data {
+        Some(Value::String(s)) => Some(s.as_str()),
+        Some(Value::Object(obj)) => obj.get("data").and_then(Value::as_str),
+        _ => None,
+    };
+    match hex_data {
+        Some(s) => hex::decode(s.strip_prefix("0x").unwrap_or(s)).ok(),
+        None if err.message().contains("revert") => Some(vec![]),
+        None => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ethers_abi_enc::PANIC_SELECTOR;
+    use ethers_pub_use::serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn it_extracts_revert_data() {
+        let data = format!("0x{}{:064x}", hex::encode(PANIC_SELECTOR), 1);
+        let err: ProviderError =
+            ErrorObject::owned(3, "execution reverted: assertion failed", Some(&data)).into();
+        assert_eq!(
+            err.revert(&RevertDecoder::new()).unwrap().to_string(),
+            "panicked: assertion failed"
+        );
+
+        let nested = ErrorObject::owned(-32603, "error", Some(json!({ "data": data })));
+        assert_eq!(revert_data(&nested).unwrap().len(), 36);
+
+        let bare = ErrorObject::owned(-32000, "execution reverted", None::<()>);
+        assert_eq!(revert_data(&bare), Some(vec![]));
+        let other = ErrorObject::owned(-32000, "nonce too low", None::<()>);
+        assert_eq!(revert_data(&other), None);
+    }
+}
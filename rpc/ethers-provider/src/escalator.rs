@@ -0,0 +1,458 @@
+//! Rebroadcasting transactions with bumped fees until they are mined.
+//!
+//! A transaction priced for the fees of the block it was sent in may be
+//! stuck when fees rise. [`GasEscalator`] replaces it, with the same nonce
+//! and higher fees, every few blocks until one of the versions it sent is
+//! mined
+
+use ethers_primitives::{keccak256, U256Ext, B256, U256};
+use ethers_rpc_types::{transaction::TypedTransaction, TransactionReceipt};
+use ethers_signer::Signer;
+use ethers_transports::{common::ErrorObject, time, Connection};
+
+use crate::{ProviderError, SignerProvider, SignerProviderError};
+
+/// The fee increase, in percent, that nodes require of a replacement
+/// transaction. Bumps are rounded up to at least this much
+pub const MIN_REPLACEMENT_BUMP_PERCENT: u64 = 10;
+
+/// The blob fee increase, in percent, that geth's blob pool requires of a
+/// replacement blob transaction. Blob fee bumps are at least this much
+pub const MIN_BLOB_REPLACEMENT_BUMP_PERCENT: u64 = 100;
+
+/// How fees increase at each replacement
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EscalationPolicy {
+    /// Multiply the fees by `(100 + percent) / 100`
+    Geometric {
+        /// The increase, in percent
+        percent: u64,
+    },
+    /// Add a fixed amount to the fees
+    Linear {
+        /// The increase, in wei
+        increment: U256,
+    },
+}
+
+impl Default for EscalationPolicy {
+    fn default() -> Self {
+        Self::Geometric { percent: 12 }
+    }
+}
+
+impl EscalationPolicy {
+    /// The bumped fee. At least [`MIN_REPLACEMENT_BUMP_PERCENT`] above
+    /// `fee`, so that nodes accept the replacement
+    pub fn bump(&self, fee: U256) -> U256 {
+        let bumped = match self {
+            Self::Geometric { percent } => fee
+                .mul_div(U256::from(100 + percent), U256::from(100))
+                .unwrap_or(U256::MAX),
+            Self::Linear { increment } => fee.saturating_add(*increment),
+        };
+        bumped.max(min_replacement_fee(fee))
+    }
+}
+
+/// `fee` increased by [`MIN_REPLACEMENT_BUMP_PERCENT`], rounded up
+fn min_replacement_fee(fee: U256) -> U256 {
+    fee.mul_div(
+        U256::from(100 + MIN_REPLACEMENT_BUMP_PERCENT),
+        U256::from(100),
+    )
+    .unwrap_or(U256::MAX)
+    .saturating_add(U256::from(1))
+}
+
+/// What a node's rejection of a replacement means
+enum Rejection {
+    /// The replacement's fees are too low to replace the pending version
+    Underpriced,
+    /// The node already has the replacement
+    Known,
+    /// A transaction with the nonce was mined
+    NonceUsed,
+}
+
+fn rejection(err: &ErrorObject<'_>) -> Option<Rejection> {
+    let message = err.message().to_lowercase();
+    if message.contains("underpriced") {
+        Some(Rejection::Underpriced)
+    } else if message.contains("already known") || message.contains("known transaction") {
+        Some(Rejection::Known)
+    } else if message.contains("nonce too low") {
+        Some(Rejection::NonceUsed)
+    } else {
+        None
+    }
+}
+
+/// Sends transactions with a [`SignerProvider`], and replaces them with
+/// higher fees every `blocks_per_bump` blocks until they are mined.
+///
+/// Fees are bumped according to an [`EscalationPolicy`], up to an optional
+/// cap, after which the last version is left to be mined. Blob
+/// transactions have their blob fee bumped too, by at least
+/// [`MIN_BLOB_REPLACEMENT_BUMP_PERCENT`]. The blob fee is not capped
+#[derive(Debug, Clone)]
+pub struct GasEscalator<T, S> {
+    inner: SignerProvider<T, S>,
+    policy: EscalationPolicy,
+    blocks_per_bump: u64,
+    max_fee: Option<U256>,
+}
+
+impl<T, S> GasEscalator<T, S> {
+    /// Instantiate an escalator that bumps fees by `policy` every 3 blocks,
+    /// without a cap
+    pub fn new(inner: SignerProvider<T, S>, policy: EscalationPolicy) -> Self {
+        Self {
+            inner,
+            policy,
+            blocks_per_bump: 3,
+            max_fee: None,
+        }
+    }
+
+    /// Set the number of blocks to wait for a version to be mined before
+    /// replacing it. At least 1
+    #[must_use = "Builder method outputs must be used"]
+    pub fn blocks_per_bump(mut self, blocks: u64) -> Self {
+        self.blocks_per_bump = blocks.max(1);
+        self
+    }
+
+    /// Never bump the gas price, or max fee per gas, above `max_fee`
+    #[must_use = "Builder method outputs must be used"]
+    pub fn max_fee(mut self, max_fee: U256) -> Self {
+        self.max_fee = Some(max_fee);
+        self
+    }
+
+    /// The signing provider
+    pub fn inner(&self) -> &SignerProvider<T, S> {
+        &self.inner
+    }
+
+    /// `fee` bumped by the policy and capped. `None` if the cap does not
+    /// leave room for a replacement nodes would accept
+    fn bump_fee(&self, fee: U256) -> Option<U256> {
+        let bumped = self.policy.bump(fee);
+        let capped = self.max_fee.map_or(bumped, |max| bumped.min(max));
+        (capped >= min_replacement_fee(fee)).then_some(capped)
+    }
+
+    /// `priority_fee` bumped by the policy, and capped by the bumped
+    /// `max_fee`. `None` if that does not leave room for a replacement
+    /// nodes would accept, as they require the priority fee to be bumped too
+    fn bump_priority_fee(&self, priority_fee: U256, max_fee: U256) -> Option<U256> {
+        let bumped = self.policy.bump(priority_fee).min(max_fee);
+        (bumped >= min_replacement_fee(priority_fee)).then_some(bumped)
+    }
+
+    /// `blob_fee` bumped by the policy, and at least by
+    /// [`MIN_BLOB_REPLACEMENT_BUMP_PERCENT`]
+    fn bump_blob_fee(&self, blob_fee: U256) -> U256 {
+        let min = blob_fee
+            .mul_div(
+                U256::from(100 + MIN_BLOB_REPLACEMENT_BUMP_PERCENT),
+                U256::from(100),
+            )
+            .unwrap_or(U256::MAX);
+        self.policy.bump(blob_fee).max(min)
+    }
+
+    /// A replacement of `tx` with bumped fees. `None` if the fees are at
+    /// the cap, or the priority fee cannot be bumped enough
+    fn bump(&self, tx: &TypedTransaction) -> Option<TypedTransaction> {
+        let mut tx = tx.clone();
+        match &mut tx {
+            TypedTransaction::Legacy(tx) => tx.gas_price = self.bump_fee(tx.gas_price)?,
+            TypedTransaction::Eip2930(tx) => tx.gas_price = self.bump_fee(tx.gas_price)?,
+            TypedTransaction::Eip1559(tx) => {
+                tx.max_fee_per_gas = self.bump_fee(tx.max_fee_per_gas)?;
+                tx.max_priority_fee_per_gas =
+                    self.bump_priority_fee(tx.max_priority_fee_per_gas, tx.max_fee_per_gas)?;
+            }
+            TypedTransaction::Eip4844(tx) => {
+                tx.max_fee_per_gas = self.bump_fee(tx.max_fee_per_gas)?;
+                tx.max_priority_fee_per_gas =
+                    self.bump_priority_fee(tx.max_priority_fee_per_gas, tx.max_fee_per_gas)?;
+                tx.max_fee_per_blob_gas = self.bump_blob_fee(tx.max_fee_per_blob_gas);
+            }
+        }
+        Some(tx)
+    }
+}
+
+impl<T, S> GasEscalator<T, S>
+where
+    T: Connection + Unpin,
+    S: Signer,
+{
+    /// Sign and send a transaction, replacing it with bumped fees until
+    /// one of the versions sent is mined. Returns the receipt of the mined
+    /// version.
+    ///
+    /// Underpriced replacements are retried with higher fees at the next
+    /// bump. If the nonce is used by a transaction this escalator did not
+    /// send, fails with the node's error once no version is mined within
+    /// `blocks_per_bump` blocks
+    pub async fn send_transaction(
+        &self,
+        tx: TypedTransaction,
+    ) -> Result<TransactionReceipt, SignerProviderError<S::Error>> {
+        let provider = self.inner.provider();
+        // replacements reuse the nonce, so it is assigned once
+        let mut tx = tx;
+        self.inner.fill_nonce(&mut tx).await?;
+        let first = self.inner.send_with_nonce(&tx).await;
+        if first.is_err() {
+            self.inner.reset_nonce();
+        }
+        let mut sent = vec![first?];
+        let mut latest = tx;
+        let mut bumped_at = self.block_number().await?;
+        let mut nonce_used: Option<(u64, ErrorObject<'static>)> = None;
+
+        loop {
+            // the latest version is the most likely to be mined
+            for hash in sent.iter().rev() {
+                if let Some(receipt) = provider.get_transaction_receipt(*hash).await? {
+                    return Ok(receipt);
+                }
+            }
+
+            let block = self.block_number().await?;
+            if let Some((used_at, err)) = &nonce_used {
+                if block >= used_at + self.blocks_per_bump {
+                    return Err(ProviderError::JsonRpc(err.clone()).into());
+                }
+            } else if block >= bumped_at + self.blocks_per_bump {
+                bumped_at = block;
+                if let Some(replacement) = self.bump(&latest) {
+                    match self.replace(&replacement).await? {
+                        Ok(hash) => sent.push(hash),
+                        // known replacements are returned as sent
+                        Err((Rejection::Underpriced | Rejection::Known, _)) => {}
+                        Err((Rejection::NonceUsed, err)) => nonce_used = Some((block, err)),
+                    }
+                    latest = replacement;
+                }
+            }
+
+            time::sleep(provider.interval()).await;
+        }
+    }
+
+    async fn block_number(&self) -> Result<u64, ProviderError> {
+        Ok(self.inner.provider().get_block_number().await??.to())
+    }
+
+    /// Sign and send a replacement, returning its hash. Rejections that are
+    /// expected of replacements are returned, rather than failing
+    async fn replace(
+        &self,
+        tx: &TypedTransaction,
+    ) -> Result<Result<B256, (Rejection, ErrorObject<'static>)>, SignerProviderError<S::Error>>
+    {
+        let raw = self.inner.sign_transaction(tx).await?;
+        let hash = keccak256(&raw);
+        match self
+            .inner
+            .provider()
+            .send_raw_transaction(raw)
+            .await
+            .map_err(ProviderError::from)?
+        {
+            Ok(hash) => Ok(Ok(hash)),
+            Err(err) => match rejection(&err) {
+                Some(Rejection::Known) => Ok(Ok(hash)),
+                Some(rejection) => Ok(Err((rejection, err))),
+                None => Err(ProviderError::from(err).into()),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{convert::Infallible, time::Duration};
+
+    use ethers_primitives::{Address, Signature};
+    use ethers_pub_use::{async_trait::async_trait, serde_json};
+    use ethers_rpc_types::transaction::{Eip1559Transaction, Eip4844Transaction};
+    use ethers_transports::transports::MockConnection;
+
+    use super::*;
+    use crate::Provider;
+
+    #[derive(Debug)]
+    struct Fixed;
+
+    #[async_trait]
+    impl Signer for Fixed {
+        type Error = Infallible;
+
+        fn address(&self) -> Address {
+            Address::repeat_byte(0x11)
+        }
+
+        async fn sign_hash(&self, _digest: B256) -> Result<Signature, Infallible> {
+            Ok(Signature {
+                r: U256::from(1),
+                s: U256::from(2),
+                v: 27,
+            })
+        }
+    }
+
+    #[test]
+    fn it_bumps_at_least_the_replacement_minimum() {
+        let policy = EscalationPolicy::Geometric { percent: 20 };
+        assert_eq!(policy.bump(U256::from(100)), U256::from(120));
+        let policy = EscalationPolicy::Linear {
+            increment: U256::from(1),
+        };
+        assert_eq!(policy.bump(U256::from(100)), U256::from(111));
+        assert_eq!(EscalationPolicy::default().bump(U256::MAX), U256::MAX);
+    }
+
+    #[test]
+    fn it_caps_fees() {
+        let escalator = GasEscalator::new(
+            SignerProvider::new(Provider::new(MockConnection::new()), Fixed),
+            EscalationPolicy::Geometric { percent: 50 },
+        )
+        .max_fee(U256::from(130));
+        let tx = TypedTransaction::Eip1559(Eip1559Transaction {
+            max_fee_per_gas: U256::from(100),
+            max_priority_fee_per_gas: U256::from(100),
+            ..Default::default()
+        });
+
+        let TypedTransaction::Eip1559(bumped) = escalator.bump(&tx).unwrap() else {
+            unreachable!()
+        };
+        assert_eq!(bumped.max_fee_per_gas, U256::from(130));
+        assert_eq!(bumped.max_priority_fee_per_gas, U256::from(130));
+        assert!(escalator.bump(&bumped.into()).is_none());
+    }
+
+    #[test]
+    fn it_bumps_the_priority_fee_by_the_replacement_minimum() {
+        let escalator = GasEscalator::new(
+            SignerProvider::new(Provider::new(MockConnection::new()), Fixed),
+            EscalationPolicy::default(),
+        )
+        .max_fee(U256::from(300));
+        let tx = |max_fee: u64, priority_fee: u64| {
+            TypedTransaction::Eip1559(Eip1559Transaction {
+                max_fee_per_gas: U256::from(max_fee),
+                max_priority_fee_per_gas: U256::from(priority_fee),
+                ..Default::default()
+            })
+        };
+
+        let TypedTransaction::Eip1559(bumped) = escalator.bump(&tx(100, 10)).unwrap() else {
+            unreachable!()
+        };
+        assert_eq!(bumped.max_fee_per_gas, U256::from(112));
+        assert_eq!(bumped.max_priority_fee_per_gas, U256::from(12));
+
+        // the max fee can be bumped, but caps the priority fee below the
+        // replacement minimum
+        assert!(escalator.bump(&tx(100, 250)).is_none());
+    }
+
+    #[test]
+    fn it_doubles_blob_fees() {
+        let escalator = GasEscalator::new(
+            SignerProvider::new(Provider::new(MockConnection::new()), Fixed),
+            EscalationPolicy::default(),
+        );
+        let tx = |blob_fee: u64| {
+            TypedTransaction::Eip4844(Eip4844Transaction {
+                max_fee_per_gas: U256::from(100),
+                max_priority_fee_per_gas: U256::from(10),
+                max_fee_per_blob_gas: U256::from(blob_fee),
+                ..Default::default()
+            })
+        };
+        let blob_fee = |tx: TypedTransaction| match tx {
+            TypedTransaction::Eip4844(tx) => tx.max_fee_per_blob_gas,
+            _ => unreachable!(),
+        };
+
+        assert_eq!(blob_fee(escalator.bump(&tx(50)).unwrap()), U256::from(100));
+        assert_eq!(blob_fee(escalator.bump(&tx(0)).unwrap()), U256::from(1));
+        // policies bumping more than the minimum are followed
+        let escalator = GasEscalator::new(
+            SignerProvider::new(Provider::new(MockConnection::new()), Fixed),
+            EscalationPolicy::Geometric { percent: 150 },
+        );
+        assert_eq!(blob_fee(escalator.bump(&tx(50)).unwrap()), U256::from(125));
+    }
+
+    #[tokio::test]
+    async fn it_replaces_stuck_transactions() {
+        let mock = MockConnection::new();
+        let provider = Provider::new(mock.clone()).with_interval(Duration::ZERO);
+        let escalator = GasEscalator::new(
+            SignerProvider::new(provider, Fixed),
+            EscalationPolicy::default(),
+        )
+        .blocks_per_bump(1);
+
+        let first = B256::repeat_byte(1);
+        let second = B256::repeat_byte(2);
+        mock.push_response("eth_chainId", "0x1");
+        mock.push_response("eth_sendRawTransaction", first);
+        mock.push_response("eth_blockNumber", "0x10");
+        // not mined in the first block, and the first replacement is
+        // underpriced
+        mock.push_response("eth_getTransactionReceipt", ());
+        mock.push_response("eth_blockNumber", "0x11");
+        mock.push_error(
+            "eth_sendRawTransaction",
+            -32000,
+            "replacement transaction underpriced",
+        );
+        mock.push_response("eth_getTransactionReceipt", ());
+        mock.push_response("eth_blockNumber", "0x12");
+        mock.push_response("eth_sendRawTransaction", second);
+        mock.push_response(
+            "eth_getTransactionReceipt",
+            TransactionReceipt {
+                transaction_hash: second,
+                ..Default::default()
+            },
+        );
+
+        let tx = TypedTransaction::Eip1559(Eip1559Transaction {
+            chain_id: 1,
+            max_fee_per_gas: U256::from(100),
+            max_priority_fee_per_gas: U256::from(10),
+            ..Default::default()
+        });
+        let receipt = escalator.send_transaction(tx).await.unwrap();
+        assert_eq!(receipt.transaction_hash, second);
+        assert_eq!(mock.pending_responses(), 0);
+
+        let fees: Vec<U256> = mock
+            .requests()
+            .iter()
+            .filter(|req| req.method == "eth_sendRawTransaction")
+            .map(|req| {
+                let raw: ethers_rpc_types::Bytes =
+                    serde_json::from_value(req.params.as_ref().unwrap()[0].clone()).unwrap();
+                match TypedTransaction::decode_raw(&raw).unwrap().tx {
+                    TypedTransaction::Eip1559(tx) => tx.max_fee_per_gas,
+                    _ => unreachable!(),
+                }
+            })
+            .collect();
+        assert_eq!(fees, [U256::from(100), U256::from(112), U256::from(125)]);
+    }
+}
@@ -0,0 +1,178 @@
+//! Node health checks, for load balancers and quorums to score endpoints
+
+use std::time::Duration;
+
+use ethers_primitives::{Chain, U256, U64};
+use ethers_pub_use::serde::Deserialize;
+use ethers_rpc_types::{BlockNumber, SyncStatus};
+use ethers_transports::{
+    time::{SystemTime, UNIX_EPOCH},
+    Connection, RpcCall,
+};
+
+use crate::{provider::is_method_not_found, AdminApi, Provider, ProviderError};
+
+/// The age past which a remote node's latest block is considered stale
+pub const MAX_BLOCK_AGE: Duration = Duration::from_secs(60);
+
+/// A problem found by [`Provider::health_check`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HealthIssue {
+    /// The node serves a different chain than the provider is configured for
+    WrongChain {
+        /// The provider's chain
+        expected: Chain,
+        /// The node's chain id
+        actual: u64,
+    },
+    /// The node's latest block is older than [`MAX_BLOCK_AGE`]
+    StaleHead(Duration),
+    /// The node has no peers
+    NoPeers,
+    /// The node is syncing
+    Syncing,
+}
+
+/// The result of [`Provider::health_check`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Health {
+    /// The node's chain id
+    pub chain_id: u64,
+    /// The number of the node's latest block
+    pub block_number: u64,
+    /// Time since the node's latest block was produced
+    pub block_age: Duration,
+    /// The node's peer count. `None` if the node does not serve
+    /// `net_peerCount`
+    pub peer_count: Option<u64>,
+    /// The problems found. Empty if the node is healthy
+    pub issues: Vec<HealthIssue>,
+}
+
+impl Health {
+    /// True if no problems were found
+    pub fn is_healthy(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+/// The fields of the latest block used to judge recency
+#[derive(Deserialize)]
+#[serde(crate = "ethers_pub_use::serde")]
+struct LatestBlock {
+    number: Option<U64>,
+    timestamp: U256,
+}
+
+impl<T> Provider<T>
+where
+    T: Connection + Unpin,
+{
+    /// Get the node's sync status
+    pub fn syncing(&self) -> RpcCall<&Self, Self, (), SyncStatus> {
+        self.request("eth_syncing", ())
+    }
+
+    /// Check that the node serves the provider's chain, if it has one, and
+    /// is at the head of it.
+    ///
+    /// Remote nodes must also have peers, and a latest block no older than
+    /// [`MAX_BLOCK_AGE`]. Local dev nodes often have neither, so these
+    /// checks are skipped for them. Failed requests are returned as errors,
+    /// not issues
+    pub async fn health_check(&self) -> Result<Health, ProviderError> {
+        let chain_id = self.get_chain_id().await??.to::<u64>();
+        let block: Option<LatestBlock> = self
+            .request("eth_getBlockByNumber", (BlockNumber::Latest, false))
+            .await??;
+        let (number, timestamp) = block.map_or((None, U256::ZERO), |b| (b.number, b.timestamp));
+        let syncing = self.syncing().await??;
+        let peer_count = match self.net_peer_count().await? {
+            Ok(count) => Some(count.to::<u64>()),
+            Err(err) if is_method_not_found(&err) => None,
+            Err(err) => return Err(err.into()),
+        };
+
+        let produced = UNIX_EPOCH + Duration::from_secs(timestamp.saturating_to());
+        let block_age = SystemTime::now()
+            .duration_since(produced)
+            .unwrap_or_default();
+
+        let mut issues = vec![];
+        match self.chain() {
+            Some(expected) if expected.id() != chain_id => issues.push(HealthIssue::WrongChain {
+                expected,
+                actual: chain_id,
+            }),
+            _ => {}
+        }
+        if syncing.is_syncing() {
+            issues.push(HealthIssue::Syncing);
+        }
+        if !self.is_local() {
+            if block_age > MAX_BLOCK_AGE {
+                issues.push(HealthIssue::StaleHead(block_age));
+            }
+            if peer_count == Some(0) {
+                issues.push(HealthIssue::NoPeers);
+            }
+        }
+
+        Ok(Health {
+            chain_id,
+            block_number: number.unwrap_or_default().to(),
+            block_age,
+            peer_count,
+            issues,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ethers_pub_use::serde_json::json;
+    use ethers_transports::transports::MockConnection;
+
+    use super::*;
+
+    fn node(timestamp: u64, peers: &str) -> MockConnection {
+        let mock = MockConnection::new().with_local(false);
+        mock.push_response("eth_chainId", "0x1");
+        mock.push_response(
+            "eth_getBlockByNumber",
+            json!({ "number": "0x10", "timestamp": format!("{timestamp:#x}") }),
+        );
+        mock.push_response("eth_syncing", false);
+        mock.push_response("net_peerCount", peers);
+        mock
+    }
+
+    fn now() -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+    }
+
+    #[tokio::test]
+    async fn it_checks_health() {
+        let provider = Provider::new(node(now(), "0x5")).with_chain(Chain::Mainnet);
+        let health = provider.health_check().await.unwrap();
+        assert!(health.is_healthy(), "{health:?}");
+        assert_eq!(health.block_number, 16);
+        assert_eq!(health.peer_count, Some(5));
+
+        let provider = Provider::new(node(now() - 600, "0x0")).with_chain(Chain::Sepolia);
+        let health = provider.health_check().await.unwrap();
+        assert_eq!(health.issues.len(), 3);
+        assert_eq!(
+            health.issues[0],
+            HealthIssue::WrongChain {
+                expected: Chain::Sepolia,
+                actual: 1
+            }
+        );
+        assert!(matches!(health.issues[1], HealthIssue::StaleHead(_)));
+        assert_eq!(health.issues[2], HealthIssue::NoPeers);
+    }
+}
@@ -10,13 +10,78 @@
     attr(deny(warnings, rust_2018_idioms), allow(dead_code, unused_variables))
 ))]
 
+pub mod admin;
+pub use admin::AdminApi;
+
+pub mod builder;
+pub use builder::{ProviderBuilder, SignerProviderBuilder};
+
+pub mod bundler;
+pub use bundler::BundlerApi;
+
+pub mod cache;
+pub use cache::CachedProvider;
+
+pub mod decoded_trace;
+pub use decoded_trace::{DecodedCall, DecodedCallFrame, TraceDecoder};
+
+pub mod engine;
+pub use engine::EngineApi;
+
+pub mod ens;
+pub use ens::EnsError;
+
+mod error;
+pub use error::{revert_data, ProviderError};
+
+pub mod escalator;
+pub use escalator::{EscalationPolicy, GasEscalator};
+
+pub mod health;
+pub use health::{Health, HealthIssue};
+
+pub mod limits;
+pub use limits::ProviderLimits;
+
+pub mod logs;
+pub use logs::LogTracker;
+
+pub mod middleware;
+pub use middleware::{Middleware, MiddlewareFuture};
+
+pub mod node_client;
+pub use node_client::NodeClient;
+
+pub mod paginate;
+pub use paginate::LogPaginator;
+
+pub mod paymaster;
+pub use paymaster::{PaymasterClient, PaymasterError};
+
 pub mod provider;
 pub use provider::{HttpProvider, Provider};
 
 pub mod quorum;
+
+pub mod reorg;
+pub use reorg::{BlockTracker, ChainUpdate, ReorgAwareStream, ReorgEvent};
+
 pub mod retry;
+pub use retry::{Retry, RetryPolicy};
+
 pub mod rw;
 
+pub mod signatures;
+pub use signatures::{SignatureDb, SignatureSource};
+
+pub mod signer;
+pub use signer::{SignerProvider, SignerProviderError};
+
+mod trace;
+
+pub mod validate;
+pub use validate::{Validate, ValidationMode, Violation};
+
 use std::time::Duration;
 // The default polling interval for filters and pending transactions
 pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_millis(7000);
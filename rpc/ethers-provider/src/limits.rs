@@ -0,0 +1,326 @@
+use std::ops::RangeInclusive;
+
+use ethers_pub_use::thiserror;
+use url::Url;
+
+/// Errors produced when a request would exceed a known server-side limit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum LimitError {
+    /// The connection already holds the maximum number of subscriptions
+    #[error("subscription limit reached: {active} active, max {max}")]
+    TooManySubscriptions {
+        /// Currently active subscriptions
+        active: usize,
+        /// Maximum allowed by the server
+        max: usize,
+    },
+}
+
+/// Server-side limits imposed by an RPC endpoint.
+///
+/// Hosted providers reject requests that exceed per-connection or per-query
+/// limits. Knowing these limits up front allows us to split requests before
+/// the server errors. A `None` limit is treated as unbounded.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ProviderLimits {
+    /// Maximum number of concurrent subscriptions on a single connection
+    pub max_subscriptions: Option<usize>,
+    /// Maximum number of addresses in a single log filter
+    pub max_filter_addresses: Option<usize>,
+    /// Maximum number of blocks spanned by a single `eth_getLogs` query
+    pub max_block_range: Option<u64>,
+    /// Maximum number of requests in a single batch
+    pub max_batch_len: Option<usize>,
+    /// Maximum size of a single serialized batch, in bytes
+    pub max_batch_size: Option<usize>,
+}
+
+impl ProviderLimits {
+    /// No limits. Appropriate for self-hosted nodes
+    pub const fn self_hosted() -> Self {
+        Self {
+            max_subscriptions: None,
+            max_filter_addresses: None,
+            max_block_range: None,
+            max_batch_len: None,
+            max_batch_size: None,
+        }
+    }
+
+    /// Conservative limits for Infura endpoints
+    pub const fn infura() -> Self {
+        Self {
+            max_subscriptions: Some(1_000),
+            max_filter_addresses: Some(1_000),
+            max_block_range: Some(10_000),
+            max_batch_len: None,
+            max_batch_size: None,
+        }
+    }
+
+    /// Conservative limits for Alchemy endpoints
+    pub const fn alchemy() -> Self {
+        Self {
+            max_subscriptions: Some(1_000),
+            max_filter_addresses: Some(1_000),
+            max_block_range: Some(2_000),
+            max_batch_len: None,
+            max_batch_size: None,
+        }
+    }
+
+    /// Guess the limit profile from the host of an endpoint url. Unknown
+    /// hosts and invalid urls are treated as self-hosted
+    pub fn for_url(url: &str) -> Self {
+        let Ok(url) = Url::parse(url) else {
+            return Self::self_hosted();
+        };
+        let host = url.host_str().unwrap_or_default();
+        // the domain, or one of its subdomains
+        let is = |domain: &str| {
+            host.strip_suffix(domain)
+                .is_some_and(|rest| rest.is_empty() || rest.ends_with('.'))
+        };
+        if is("infura.io") {
+            Self::infura()
+        } else if is("alchemy.com") || is("alchemyapi.io") {
+            Self::alchemy()
+        } else {
+            Self::self_hosted()
+        }
+    }
+
+    /// Override the subscription limit
+    #[must_use = "Builder method outputs must be used"]
+    pub const fn with_max_subscriptions(mut self, max: Option<usize>) -> Self {
+        self.max_subscriptions = max;
+        self
+    }
+
+    /// Override the filter address limit
+    #[must_use = "Builder method outputs must be used"]
+    pub const fn with_max_filter_addresses(mut self, max: Option<usize>) -> Self {
+        self.max_filter_addresses = max;
+        self
+    }
+
+    /// Override the `eth_getLogs` block range limit
+    #[must_use = "Builder method outputs must be used"]
+    pub const fn with_max_block_range(mut self, max: Option<u64>) -> Self {
+        self.max_block_range = max;
+        self
+    }
+
+    /// Override the batch length limit
+    #[must_use = "Builder method outputs must be used"]
+    pub const fn with_max_batch_len(mut self, max: Option<usize>) -> Self {
+        self.max_batch_len = max;
+        self
+    }
+
+    /// Override the batch size limit, in bytes
+    #[must_use = "Builder method outputs must be used"]
+    pub const fn with_max_batch_size(mut self, max: Option<usize>) -> Self {
+        self.max_batch_size = max;
+        self
+    }
+
+    /// Check whether another subscription may be opened on a connection
+    /// that currently has `active` subscriptions
+    pub fn check_subscriptions(&self, active: usize) -> Result<(), LimitError> {
+        match self.max_subscriptions {
+            Some(max) if active >= max => Err(LimitError::TooManySubscriptions { active, max }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Split an inclusive block range into consecutive sub-ranges that each
+    /// respect the `eth_getLogs` block range limit
+    pub fn split_block_range(&self, from: u64, to: u64) -> BlockRangeChunks {
+        BlockRangeChunks {
+            next: from,
+            to,
+            step: self.max_block_range.map(|step| step.max(1)),
+            done: from > to,
+        }
+    }
+
+    /// Split a filter's address list into chunks that each respect the
+    /// filter address limit
+    pub fn split_addresses<'a, T>(&self, addresses: &'a [T]) -> std::slice::Chunks<'a, T> {
+        let size = self.max_filter_addresses.unwrap_or(addresses.len()).max(1);
+        addresses.chunks(size)
+    }
+
+    /// Split a batch into consecutive chunks that each respect the batch
+    /// length and size limits. `size_of` gives the serialized size of an
+    /// item. An item larger than the size limit is sent in a chunk of its own
+    pub fn split_batch<'a, T>(
+        &self,
+        items: &'a [T],
+        size_of: impl Fn(&T) -> usize,
+    ) -> Vec<&'a [T]> {
+        let max_len = self.max_batch_len.unwrap_or(usize::MAX).max(1);
+        let Some(max_size) = self.max_batch_size else {
+            return items.chunks(max_len).collect();
+        };
+
+        let mut chunks = vec![];
+        let mut start = 0;
+        // the enclosing brackets
+        let mut size: usize = 2;
+        for (i, item) in items.iter().enumerate() {
+            // the item, and a separating comma if it is not the first
+            let item_size = size_of(item) + usize::from(i > start);
+            let len = i - start;
+            if len > 0 && (len >= max_len || size.saturating_add(item_size) > max_size) {
+                chunks.push(&items[start..i]);
+                start = i;
+                size = 2 + size_of(item);
+            } else {
+                size = size.saturating_add(item_size);
+            }
+        }
+        if start < items.len() {
+            chunks.push(&items[start..]);
+        }
+        chunks
+    }
+}
+
+/// Iterator over inclusive block sub-ranges. See
+/// [`ProviderLimits::split_block_range`]
+#[derive(Debug, Clone)]
+pub struct BlockRangeChunks {
+    next: u64,
+    to: u64,
+    step: Option<u64>,
+    done: bool,
+}
+
+impl Iterator for BlockRangeChunks {
+    type Item = RangeInclusive<u64>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let start = self.next;
+        let end = match self.step {
+            Some(step) => start.saturating_add(step - 1).min(self.to),
+            None => self.to,
+        };
+        if end == self.to {
+            self.done = true;
+        } else {
+            self.next = end + 1;
+        }
+        Some(start..=end)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_splits_block_ranges() {
+        let limits = ProviderLimits::self_hosted().with_max_block_range(Some(10));
+        let chunks: Vec<_> = limits.split_block_range(0, 25).collect();
+        assert_eq!(chunks, vec![0..=9, 10..=19, 20..=25]);
+
+        let chunks: Vec<_> = limits.split_block_range(5, 5).collect();
+        assert_eq!(chunks, vec![5..=5]);
+
+        assert_eq!(limits.split_block_range(6, 5).count(), 0);
+
+        let unbounded = ProviderLimits::self_hosted();
+        let chunks: Vec<_> = unbounded.split_block_range(0, u64::MAX).collect();
+        assert_eq!(chunks, vec![0..=u64::MAX]);
+    }
+
+    #[test]
+    fn it_splits_addresses() {
+        let addrs = [1, 2, 3, 4, 5];
+        let limits = ProviderLimits::self_hosted().with_max_filter_addresses(Some(2));
+        let chunks: Vec<_> = limits.split_addresses(&addrs).collect();
+        assert_eq!(chunks, vec![&[1, 2][..], &[3, 4], &[5]]);
+
+        let unbounded = ProviderLimits::self_hosted();
+        assert_eq!(unbounded.split_addresses(&addrs).count(), 1);
+        assert_eq!(unbounded.split_addresses::<u8>(&[]).count(), 0);
+    }
+
+    #[test]
+    fn it_splits_batches() {
+        let items = [1, 2, 3, 4, 5];
+
+        let limits = ProviderLimits::self_hosted().with_max_batch_len(Some(2));
+        let chunks = limits.split_batch(&items, |_| 10);
+        assert_eq!(chunks, vec![&[1, 2][..], &[3, 4], &[5]]);
+
+        // `[` + 3 items of 10 bytes + 2 commas + `]` is 34 bytes
+        let limits = ProviderLimits::self_hosted().with_max_batch_size(Some(34));
+        let chunks = limits.split_batch(&items, |_| 10);
+        assert_eq!(chunks, vec![&[1, 2, 3][..], &[4, 5]]);
+
+        let limits = ProviderLimits::self_hosted().with_max_batch_size(Some(33));
+        let chunks = limits.split_batch(&items, |_| 10);
+        assert_eq!(chunks, vec![&[1, 2][..], &[3, 4], &[5]]);
+
+        // oversized items are sent alone
+        let limits = ProviderLimits::self_hosted().with_max_batch_size(Some(5));
+        assert_eq!(limits.split_batch(&items, |_| 10).len(), 5);
+
+        let unbounded = ProviderLimits::self_hosted();
+        assert_eq!(unbounded.split_batch(&items, |_| 10), vec![&items[..]]);
+        assert!(unbounded.split_batch::<u8>(&[], |_| 10).is_empty());
+    }
+
+    #[test]
+    fn it_checks_subscriptions() {
+        let limits = ProviderLimits::self_hosted().with_max_subscriptions(Some(2));
+        assert!(limits.check_subscriptions(1).is_ok());
+        assert_eq!(
+            limits.check_subscriptions(2),
+            Err(LimitError::TooManySubscriptions { active: 2, max: 2 })
+        );
+        assert!(ProviderLimits::self_hosted()
+            .check_subscriptions(usize::MAX)
+            .is_ok());
+    }
+
+    #[test]
+    fn it_detects_profiles() {
+        assert_eq!(
+            ProviderLimits::for_url("https://mainnet.infura.io/v3/key"),
+            ProviderLimits::infura()
+        );
+        assert_eq!(
+            ProviderLimits::for_url("https://eth-mainnet.g.alchemy.com/v2/key"),
+            ProviderLimits::alchemy()
+        );
+        assert_eq!(
+            ProviderLimits::for_url("wss://mainnet.infura.io/ws/v3/key"),
+            ProviderLimits::infura()
+        );
+        assert_eq!(
+            ProviderLimits::for_url("http://127.0.0.1:8545"),
+            ProviderLimits::self_hosted()
+        );
+
+        // only the host counts
+        for url in [
+            "https://node.example.com/?via=infura.io",
+            "https://infura.io.example.com",
+            "https://notinfura.io",
+            "infura.io",
+        ] {
+            assert_eq!(
+                ProviderLimits::for_url(url),
+                ProviderLimits::self_hosted(),
+                "{url}"
+            );
+        }
+    }
+}
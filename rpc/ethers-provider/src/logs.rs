@@ -0,0 +1,232 @@
+//! Reorg-aware log delivery for log subscriptions and polling streams
+
+use std::collections::BTreeMap;
+
+use ethers_primitives::B256;
+use ethers_rpc_types::{Log, LogEvent};
+
+/// The default number of blocks below the head for which delivered logs are
+/// remembered
+pub const DEFAULT_REORG_DEPTH: u64 = 64;
+
+#[derive(Debug, Clone)]
+struct BlockLogs {
+    hash: B256,
+    logs: Vec<Log>,
+}
+
+/// Tracks delivered logs by block, turning subscription and polling
+/// results into [`LogEvent`]s.
+///
+/// - Logs with the `removed` flag are reported as [`LogEvent::Removed`]
+/// - Logs that were already delivered are not delivered again
+/// - When a block is replaced, either by a log with a new block hash at the
+///   same height or by a new head from [`LogTracker::on_block`], logs
+///   delivered in the replaced blocks are reported as removed, newest first
+///
+/// Logs are remembered for `depth` blocks below the most recent head. Logs
+/// without a block hash and number, which are pending, are passed through.
+#[derive(Debug, Clone)]
+pub struct LogTracker {
+    depth: u64,
+    blocks: BTreeMap<u64, BlockLogs>,
+}
+
+impl Default for LogTracker {
+    fn default() -> Self {
+        Self::new(DEFAULT_REORG_DEPTH)
+    }
+}
+
+impl LogTracker {
+    /// Instantiate a tracker that remembers logs for `depth` blocks
+    pub fn new(depth: u64) -> Self {
+        Self {
+            depth,
+            blocks: Default::default(),
+        }
+    }
+
+    /// Process a log from a subscription or a poll
+    pub fn on_log(&mut self, log: Log) -> Vec<LogEvent> {
+        let (Some(hash), Some(number)) = (log.block_hash, log.block_number) else {
+            return vec![log.into()];
+        };
+        let number = number.to::<u64>();
+
+        if log.removed {
+            if let Some(block) = self.blocks.get_mut(&number) {
+                if block.hash == hash {
+                    block.logs.retain(|known| !same_log(known, &log));
+                }
+            }
+            return vec![LogEvent::Removed(log)];
+        }
+
+        let mut events = match self.blocks.get(&number) {
+            Some(block) if block.hash != hash => self.roll_back(number),
+            _ => vec![],
+        };
+
+        let block = self
+            .blocks
+            .entry(number)
+            .or_insert_with(|| BlockLogs { hash, logs: vec![] });
+        if !block.logs.iter().any(|known| same_log(known, &log)) {
+            block.logs.push(log.clone());
+            events.push(LogEvent::Added(log));
+        }
+        events
+    }
+
+    /// Process a new head from a head tracker. If the head replaces a block
+    /// with delivered logs, logs delivered at and above its height are
+    /// reported as removed.
+    ///
+    /// Logs above the head are kept, as polled logs may be ahead of the
+    /// head tracker
+    pub fn on_block(&mut self, number: u64, hash: B256) -> Vec<LogEvent> {
+        let events = match self.blocks.get(&number) {
+            Some(block) if block.hash != hash => self.roll_back(number),
+            _ => vec![],
+        };
+
+        let oldest = number.saturating_sub(self.depth);
+        self.blocks = self.blocks.split_off(&oldest);
+        events
+    }
+
+    /// Forget all delivered logs
+    pub fn clear(&mut self) {
+        self.blocks.clear();
+    }
+
+    /// Forget logs delivered at and above a height, reporting them as
+    /// removed, newest first. For reorgs detected by a block tracker
+    pub fn roll_back(&mut self, from: u64) -> Vec<LogEvent> {
+        let removed = self.blocks.split_off(&from);
+        removed
+            .into_values()
+            .rev()
+            .flat_map(|block| block.logs.into_iter().rev())
+            .map(|log| {
+                LogEvent::Removed(Log {
+                    removed: true,
+                    ..log
+                })
+            })
+            .collect()
+    }
+}
+
+/// Logs in the same block are identified by their index, if the node
+/// provides it
+fn same_log(a: &Log, b: &Log) -> bool {
+    match (a.log_index, b.log_index) {
+        (Some(a_index), Some(b_index)) => a_index == b_index,
+        _ => a.transaction_hash == b.transaction_hash && a.topics == b.topics && a.data == b.data,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ethers_primitives::{U256, U64};
+
+    use super::*;
+
+    fn log(number: u64, hash: u8, index: u64) -> Log {
+        Log {
+            block_hash: Some(B256::repeat_byte(hash)),
+            block_number: Some(U64::from(number)),
+            log_index: Some(U256::from(index)),
+            ..Default::default()
+        }
+    }
+
+    fn removed(log: Log) -> LogEvent {
+        LogEvent::Removed(Log {
+            removed: true,
+            ..log
+        })
+    }
+
+    #[test]
+    fn it_deduplicates_logs() {
+        let mut tracker = LogTracker::default();
+        assert_eq!(
+            tracker.on_log(log(1, 0xa, 0)),
+            vec![LogEvent::Added(log(1, 0xa, 0))]
+        );
+        assert!(tracker.on_log(log(1, 0xa, 0)).is_empty());
+        assert_eq!(tracker.on_log(log(1, 0xa, 1)).len(), 1);
+
+        let pending = Log::default();
+        assert_eq!(
+            tracker.on_log(pending.clone()),
+            vec![LogEvent::Added(pending)]
+        );
+    }
+
+    #[test]
+    fn it_reports_removed_flags() {
+        let mut tracker = LogTracker::default();
+        tracker.on_log(log(1, 0xa, 0));
+
+        let flagged = Log {
+            removed: true,
+            ..log(1, 0xa, 0)
+        };
+        assert_eq!(
+            tracker.on_log(flagged.clone()),
+            vec![LogEvent::Removed(flagged)]
+        );
+        // the log may be re-added by the new chain
+        assert_eq!(tracker.on_log(log(1, 0xa, 0)).len(), 1);
+    }
+
+    #[test]
+    fn it_detects_replaced_blocks_from_logs() {
+        let mut tracker = LogTracker::default();
+        tracker.on_log(log(1, 0xa, 0));
+        tracker.on_log(log(2, 0xb, 0));
+        tracker.on_log(log(2, 0xb, 1));
+
+        assert_eq!(
+            tracker.on_log(log(2, 0xc, 0)),
+            vec![
+                removed(log(2, 0xb, 1)),
+                removed(log(2, 0xb, 0)),
+                LogEvent::Added(log(2, 0xc, 0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn it_detects_reorgs_from_heads() {
+        let mut tracker = LogTracker::default();
+        tracker.on_log(log(1, 0xa, 0));
+        tracker.on_log(log(2, 0xb, 0));
+        tracker.on_log(log(3, 0xc, 0));
+
+        assert!(tracker.on_block(3, B256::repeat_byte(0xc)).is_empty());
+        assert_eq!(
+            tracker.on_block(2, B256::repeat_byte(0xd)),
+            vec![removed(log(3, 0xc, 0)), removed(log(2, 0xb, 0))]
+        );
+        assert!(tracker.on_log(log(1, 0xa, 0)).is_empty());
+
+        // logs ahead of the head are kept
+        tracker.on_log(log(5, 0xe, 0));
+        assert!(tracker.on_block(4, B256::repeat_byte(0xf)).is_empty());
+        assert!(tracker.on_log(log(5, 0xe, 0)).is_empty());
+    }
+
+    #[test]
+    fn it_forgets_old_blocks() {
+        let mut tracker = LogTracker::new(2);
+        tracker.on_log(log(1, 0xa, 0));
+        tracker.on_block(10, B256::repeat_byte(0xb));
+        // no longer remembered, so delivered again
+        assert_eq!(tracker.on_log(log(1, 0xa, 0)).len(), 1);
+    }
+}
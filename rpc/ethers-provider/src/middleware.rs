@@ -0,0 +1,460 @@
+//! An object-safe interface over the typed provider methods.
+//!
+//! Libraries that only need to read chain state or send transactions can
+//! accept `M: Middleware`, or a `&dyn Middleware`, instead of a
+//! [`Provider`] over a concrete transport. Layers that change one or two
+//! methods, such as a nonce manager or a gas oracle, implement the trait
+//! and forward the rest to the layer they wrap with
+//! [`delegate_middleware!`]
+
+use std::{fmt::Debug, future::Future, pin::Pin, sync::Arc};
+
+use ethers_primitives::{Address, B256, U256};
+use ethers_rpc_types::{
+    BlockId, Bytes, Filter, Header, Log, TransactionReceipt, TransactionRequest,
+};
+use ethers_transports::Connection;
+
+use crate::{Provider, ProviderError};
+
+/// The future returned by [`Middleware`] methods
+#[cfg(not(target_arch = "wasm32"))]
+pub type MiddlewareFuture<'a, T> =
+    Pin<Box<dyn Future<Output = Result<T, ProviderError>> + Send + 'a>>;
+/// The future returned by [`Middleware`] methods
+#[cfg(target_arch = "wasm32")]
+pub type MiddlewareFuture<'a, T> = Pin<Box<dyn Future<Output = Result<T, ProviderError>> + 'a>>;
+
+/// Typed access to a node. See the [module docs](self)
+pub trait Middleware: Debug + Send + Sync {
+    /// The chain id the node signs transactions for
+    fn chain_id(&self) -> MiddlewareFuture<'_, u64>;
+
+    /// Get the number of the most recent block
+    fn get_block_number(&self) -> MiddlewareFuture<'_, u64>;
+
+    /// Get the header of a block. `None` if the block is unknown
+    fn get_block(&self, block: BlockId) -> MiddlewareFuture<'_, Option<Header>>;
+
+    /// Get the balance of an account at a block. Defaults to the latest
+    /// block
+    fn get_balance(&self, address: Address, block: Option<BlockId>) -> MiddlewareFuture<'_, U256>;
+
+    /// Get the number of transactions sent from an account at a block.
+    /// Defaults to the latest block
+    fn get_transaction_count(
+        &self,
+        address: Address,
+        block: Option<BlockId>,
+    ) -> MiddlewareFuture<'_, U256>;
+
+    /// Get the code of an account at a block. Defaults to the latest block
+    fn get_code(&self, address: Address, block: Option<BlockId>) -> MiddlewareFuture<'_, Bytes>;
+
+    /// Get the value of a storage slot of an account at a block. Defaults to
+    /// the latest block
+    fn get_storage_at(
+        &self,
+        address: Address,
+        slot: U256,
+        block: Option<BlockId>,
+    ) -> MiddlewareFuture<'_, B256>;
+
+    /// Get the current gas price
+    fn get_gas_price(&self) -> MiddlewareFuture<'_, U256>;
+
+    /// Get the logs matching a filter
+    fn get_logs<'a>(&'a self, filter: &'a Filter) -> MiddlewareFuture<'a, Vec<Log>>;
+
+    /// Execute a call without creating a transaction. Defaults to the latest
+    /// block
+    fn call(&self, tx: TransactionRequest, block: Option<BlockId>) -> MiddlewareFuture<'_, Bytes>;
+
+    /// Estimate the gas a transaction would use. Defaults to the latest
+    /// block
+    fn estimate_gas(
+        &self,
+        tx: TransactionRequest,
+        block: Option<BlockId>,
+    ) -> MiddlewareFuture<'_, U256>;
+
+    /// Send a transaction. Returns the transaction hash
+    fn send_transaction(&self, tx: TransactionRequest) -> MiddlewareFuture<'_, B256>;
+
+    /// Send a signed transaction. Returns the transaction hash
+    fn send_raw_transaction(&self, tx: Bytes) -> MiddlewareFuture<'_, B256>;
+
+    /// Get the receipt of a mined transaction. `None` if the transaction is
+    /// unknown or pending
+    fn get_transaction_receipt(
+        &self,
+        hash: B256,
+    ) -> MiddlewareFuture<'_, Option<TransactionReceipt>>;
+}
+
+/// Implement [`Middleware`] methods by forwarding them to a field that is
+/// itself a [`Middleware`].
+///
+/// `delegate_middleware!(inner)` forwards every method to `self.inner`.
+/// `delegate_middleware!(inner => get_balance, call)` forwards only the
+/// listed methods, so the rest can be implemented by hand. The target may
+/// also be an accessor, as in `delegate_middleware!(|this| &**this)`.
+///
+/// ```
+/// use ethers_provider::{delegate_middleware, Middleware, MiddlewareFuture};
+/// use ethers_primitives::B256;
+/// use ethers_rpc_types::TransactionRequest;
+///
+/// #[derive(Debug)]
+/// struct ReadOnly<M>(M);
+///
+/// impl<M: Middleware> Middleware for ReadOnly<M> {
+///     delegate_middleware!(0 =>
+///         chain_id, get_block_number, get_block, get_balance,
+///         get_transaction_count, get_code, get_storage_at, get_gas_price,
+///         get_logs, call, estimate_gas, get_transaction_receipt,
+///     );
+///
+///     fn send_transaction(&self, _tx: TransactionRequest) -> MiddlewareFuture<'_, B256> {
+///         unimplemented!("read only")
+///     }
+///
+///     fn send_raw_transaction(&self, _tx: ethers_rpc_types::Bytes) -> MiddlewareFuture<'_, B256> {
+///         unimplemented!("read only")
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! delegate_middleware {
+    (|$this:ident| $target:expr) => {
+        $crate::delegate_middleware!(|$this| $target =>
+            chain_id, get_block_number, get_block, get_balance,
+            get_transaction_count, get_code, get_storage_at, get_gas_price,
+            get_logs, call, estimate_gas, send_transaction,
+            send_raw_transaction, get_transaction_receipt,
+        );
+    };
+    (|$this:ident| $target:expr => $($method:ident),* $(,)?) => {
+        $($crate::delegate_middleware!(@method [$this, $target] $method);)*
+    };
+    ($field:tt $(=> $($method:ident),* $(,)?)?) => {
+        $crate::delegate_middleware!(|this| &this.$field $(=> $($method),*)?);
+    };
+
+    (@method [$this:ident, $target:expr] chain_id) => {
+        fn chain_id(&self) -> $crate::MiddlewareFuture<'_, u64> {
+            let $this = self;
+            $crate::Middleware::chain_id($target)
+        }
+    };
+    (@method [$this:ident, $target:expr] get_block_number) => {
+        fn get_block_number(&self) -> $crate::MiddlewareFuture<'_, u64> {
+            let $this = self;
+            $crate::Middleware::get_block_number($target)
+        }
+    };
+    (@method [$this:ident, $target:expr] get_block) => {
+        fn get_block(
+            &self,
+            block: $crate::middleware::reexports::BlockId,
+        ) -> $crate::MiddlewareFuture<'_, Option<$crate::middleware::reexports::Header>> {
+            let $this = self;
+            $crate::Middleware::get_block($target, block)
+        }
+    };
+    (@method [$this:ident, $target:expr] get_balance) => {
+        fn get_balance(
+            &self,
+            address: $crate::middleware::reexports::Address,
+            block: Option<$crate::middleware::reexports::BlockId>,
+        ) -> $crate::MiddlewareFuture<'_, $crate::middleware::reexports::U256> {
+            let $this = self;
+            $crate::Middleware::get_balance($target, address, block)
+        }
+    };
+    (@method [$this:ident, $target:expr] get_transaction_count) => {
+        fn get_transaction_count(
+            &self,
+            address: $crate::middleware::reexports::Address,
+            block: Option<$crate::middleware::reexports::BlockId>,
+        ) -> $crate::MiddlewareFuture<'_, $crate::middleware::reexports::U256> {
+            let $this = self;
+            $crate::Middleware::get_transaction_count($target, address, block)
+        }
+    };
+    (@method [$this:ident, $target:expr] get_code) => {
+        fn get_code(
+            &self,
+            address: $crate::middleware::reexports::Address,
+            block: Option<$crate::middleware::reexports::BlockId>,
+        ) -> $crate::MiddlewareFuture<'_, $crate::middleware::reexports::Bytes> {
+            let $this = self;
+            $crate::Middleware::get_code($target, address, block)
+        }
+    };
+    (@method [$this:ident, $target:expr] get_storage_at) => {
+        fn get_storage_at(
+            &self,
+            address: $crate::middleware::reexports::Address,
+            slot: $crate::middleware::reexports::U256,
+            block: Option<$crate::middleware::reexports::BlockId>,
+        ) -> $crate::MiddlewareFuture<'_, $crate::middleware::reexports::B256> {
+            let $this = self;
+            $crate::Middleware::get_storage_at($target, address, slot, block)
+        }
+    };
+    (@method [$this:ident, $target:expr] get_gas_price) => {
+        fn get_gas_price(&self) -> $crate::MiddlewareFuture<'_, $crate::middleware::reexports::U256> {
+            let $this = self;
+            $crate::Middleware::get_gas_price($target)
+        }
+    };
+    (@method [$this:ident, $target:expr] get_logs) => {
+        fn get_logs<'a>(
+            &'a self,
+            filter: &'a $crate::middleware::reexports::Filter,
+        ) -> $crate::MiddlewareFuture<'a, Vec<$crate::middleware::reexports::Log>> {
+            let $this = self;
+            $crate::Middleware::get_logs($target, filter)
+        }
+    };
+    (@method [$this:ident, $target:expr] call) => {
+        fn call(
+            &self,
+            tx: $crate::middleware::reexports::TransactionRequest,
+            block: Option<$crate::middleware::reexports::BlockId>,
+        ) -> $crate::MiddlewareFuture<'_, $crate::middleware::reexports::Bytes> {
+            let $this = self;
+            $crate::Middleware::call($target, tx, block)
+        }
+    };
+    (@method [$this:ident, $target:expr] estimate_gas) => {
+        fn estimate_gas(
+            &self,
+            tx: $crate::middleware::reexports::TransactionRequest,
+            block: Option<$crate::middleware::reexports::BlockId>,
+        ) -> $crate::MiddlewareFuture<'_, $crate::middleware::reexports::U256> {
+            let $this = self;
+            $crate::Middleware::estimate_gas($target, tx, block)
+        }
+    };
+    (@method [$this:ident, $target:expr] send_transaction) => {
+        fn send_transaction(
+            &self,
+            tx: $crate::middleware::reexports::TransactionRequest,
+        ) -> $crate::MiddlewareFuture<'_, $crate::middleware::reexports::B256> {
+            let $this = self;
+            $crate::Middleware::send_transaction($target, tx)
+        }
+    };
+    (@method [$this:ident, $target:expr] send_raw_transaction) => {
+        fn send_raw_transaction(
+            &self,
+            tx: $crate::middleware::reexports::Bytes,
+        ) -> $crate::MiddlewareFuture<'_, $crate::middleware::reexports::B256> {
+            let $this = self;
+            $crate::Middleware::send_raw_transaction($target, tx)
+        }
+    };
+    (@method [$this:ident, $target:expr] get_transaction_receipt) => {
+        fn get_transaction_receipt(
+            &self,
+            hash: $crate::middleware::reexports::B256,
+        ) -> $crate::MiddlewareFuture<
+            '_,
+            Option<$crate::middleware::reexports::TransactionReceipt>,
+        > {
+            let $this = self;
+            $crate::Middleware::get_transaction_receipt($target, hash)
+        }
+    };
+}
+
+/// Types named by [`delegate_middleware!`]
+#[doc(hidden)]
+pub mod reexports {
+    pub use ethers_primitives::{Address, B256, U256};
+    pub use ethers_rpc_types::{
+        BlockId, Bytes, Filter, Header, Log, TransactionReceipt, TransactionRequest,
+    };
+}
+
+impl<T> Middleware for Provider<T>
+where
+    T: Connection + Unpin,
+{
+    fn chain_id(&self) -> MiddlewareFuture<'_, u64> {
+        Box::pin(Provider::chain_id(self))
+    }
+
+    fn get_block_number(&self) -> MiddlewareFuture<'_, u64> {
+        Box::pin(async move { Ok(Provider::get_block_number(self).await??.to()) })
+    }
+
+    fn get_block(&self, block: BlockId) -> MiddlewareFuture<'_, Option<Header>> {
+        Box::pin(Provider::get_block(self, block))
+    }
+
+    fn get_balance(&self, address: Address, block: Option<BlockId>) -> MiddlewareFuture<'_, U256> {
+        Box::pin(async move { Ok(Provider::get_balance(self, address, block).await??) })
+    }
+
+    fn get_transaction_count(
+        &self,
+        address: Address,
+        block: Option<BlockId>,
+    ) -> MiddlewareFuture<'_, U256> {
+        Box::pin(async move { Ok(Provider::get_transaction_count(self, address, block).await??) })
+    }
+
+    fn get_code(&self, address: Address, block: Option<BlockId>) -> MiddlewareFuture<'_, Bytes> {
+        Box::pin(async move { Ok(Provider::get_code(self, address, block).await??) })
+    }
+
+    fn get_storage_at(
+        &self,
+        address: Address,
+        slot: U256,
+        block: Option<BlockId>,
+    ) -> MiddlewareFuture<'_, B256> {
+        Box::pin(async move { Ok(Provider::get_storage_at(self, address, slot, block).await??) })
+    }
+
+    fn get_gas_price(&self) -> MiddlewareFuture<'_, U256> {
+        Box::pin(async move { Ok(Provider::get_gas_price(self).await??) })
+    }
+
+    fn get_logs<'a>(&'a self, filter: &'a Filter) -> MiddlewareFuture<'a, Vec<Log>> {
+        Box::pin(async move { Ok(Provider::get_logs(self, filter).await??) })
+    }
+
+    fn call(&self, tx: TransactionRequest, block: Option<BlockId>) -> MiddlewareFuture<'_, Bytes> {
+        Box::pin(Provider::call(self, tx, block))
+    }
+
+    fn estimate_gas(
+        &self,
+        tx: TransactionRequest,
+        block: Option<BlockId>,
+    ) -> MiddlewareFuture<'_, U256> {
+        Box::pin(Provider::estimate_gas(self, tx, block))
+    }
+
+    fn send_transaction(&self, tx: TransactionRequest) -> MiddlewareFuture<'_, B256> {
+        Box::pin(Provider::send_transaction(self, tx))
+    }
+
+    fn send_raw_transaction(&self, tx: Bytes) -> MiddlewareFuture<'_, B256> {
+        Box::pin(async move { Ok(Provider::send_raw_transaction(self, tx).await??) })
+    }
+
+    fn get_transaction_receipt(
+        &self,
+        hash: B256,
+    ) -> MiddlewareFuture<'_, Option<TransactionReceipt>> {
+        Box::pin(Provider::get_transaction_receipt(self, hash))
+    }
+}
+
+macro_rules! impl_middleware_for_pointer {
+    ($($ptr:ident),*) => {$(
+        impl<M> Middleware for $ptr<M>
+        where
+            M: Middleware + ?Sized,
+        {
+            delegate_middleware!(|this| &**this);
+        }
+    )*};
+}
+
+impl_middleware_for_pointer!(Arc, Box);
+
+impl<M> Middleware for &M
+where
+    M: Middleware + ?Sized,
+{
+    delegate_middleware!(|this| &**this);
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use ethers_primitives::U64;
+    use ethers_transports::transports::MockConnection;
+
+    use super::*;
+
+    /// Counts the calls it sends, and forwards everything else
+    #[derive(Debug)]
+    struct CountCalls<M> {
+        inner: M,
+        calls: AtomicUsize,
+    }
+
+    impl<M: Middleware> Middleware for CountCalls<M> {
+        delegate_middleware!(inner =>
+            chain_id, get_block_number, get_block, get_balance,
+            get_transaction_count, get_code, get_storage_at, get_gas_price,
+            get_logs, estimate_gas, send_transaction, send_raw_transaction,
+            get_transaction_receipt,
+        );
+
+        fn call(
+            &self,
+            tx: TransactionRequest,
+            block: Option<BlockId>,
+        ) -> MiddlewareFuture<'_, Bytes> {
+            self.calls.fetch_add(1, Ordering::Relaxed);
+            self.inner.call(tx, block)
+        }
+    }
+
+    async fn balance_and_call(middleware: &dyn Middleware) -> (U256, Bytes) {
+        let balance = middleware
+            .get_balance(Address::repeat_byte(0x11), None)
+            .await
+            .unwrap();
+        let output = middleware
+            .call(TransactionRequest::default(), None)
+            .await
+            .unwrap();
+        (balance, output)
+    }
+
+    #[tokio::test]
+    async fn it_is_object_safe() {
+        let mock = MockConnection::new();
+        mock.push_response("eth_getBalance", U256::from(7));
+        mock.push_response("eth_call", Bytes::from(vec![1, 2]));
+        mock.push_response("eth_blockNumber", U64::from(3));
+
+        let middleware: Arc<dyn Middleware> = Arc::new(CountCalls {
+            inner: Provider::new(mock.clone()),
+            calls: AtomicUsize::new(0),
+        });
+        assert_eq!(
+            balance_and_call(&*middleware).await,
+            (U256::from(7), Bytes::from(vec![1, 2]))
+        );
+        assert_eq!(middleware.get_block_number().await.unwrap(), 3);
+        assert_eq!(mock.pending_responses(), 0);
+    }
+
+    #[tokio::test]
+    async fn it_counts_delegated_calls() {
+        let mock = MockConnection::new();
+        mock.push_response("eth_call", Bytes::new());
+        let middleware = CountCalls {
+            inner: Provider::new(mock),
+            calls: AtomicUsize::new(0),
+        };
+        // through the pointer impls
+        let boxed: Box<dyn Middleware + '_> = Box::new(&middleware);
+        boxed
+            .call(TransactionRequest::default(), None)
+            .await
+            .unwrap();
+        assert_eq!(middleware.calls.load(Ordering::Relaxed), 1);
+    }
+}
@@ -0,0 +1,192 @@
+use std::{fmt, str::FromStr};
+
+use ethers_pub_use::thiserror;
+
+/// A node client's release version. Pre-release and build metadata are
+/// ignored
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Version {
+    /// Major version
+    pub major: u64,
+    /// Minor version
+    pub minor: u64,
+    /// Patch version
+    pub patch: u64,
+}
+
+impl Version {
+    /// Instantiate a version
+    pub const fn new(major: u64, minor: u64, patch: u64) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+        }
+    }
+
+    /// Parse the leading `major[.minor[.patch]]` of a version string, with
+    /// an optional `v` prefix. Anything after the numeric part is ignored
+    fn parse_lenient(s: &str) -> Option<Self> {
+        let s = s.strip_prefix('v').unwrap_or(s);
+        let numeric = s
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .map_or(s, |end| &s[..end]);
+
+        let mut parts = numeric.split('.').map(str::parse::<u64>);
+        let major = parts.next()?.ok()?;
+        let minor = parts.next().and_then(Result::ok).unwrap_or_default();
+        let patch = parts.next().and_then(Result::ok).unwrap_or_default();
+        Some(Self::new(major, minor, patch))
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// Error parsing a `web3_clientVersion` response
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("unrecognized client version: {0}")]
+pub struct UnknownClient(pub String);
+
+/// Node Clients
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum NodeClient {
+    /// Geth
+    Geth(Version),
+    /// Erigon
+    Erigon(Version),
+    /// OpenEthereum
+    OpenEthereum(Version),
+    /// Nethermind
+    Nethermind(Version),
+    /// Besu
+    Besu(Version),
+    /// Reth
+    Reth(Version),
+    /// Anvil
+    Anvil(Version),
+}
+
+impl NodeClient {
+    /// The client's version
+    pub fn version(&self) -> Version {
+        match self {
+            NodeClient::Geth(v)
+            | NodeClient::Erigon(v)
+            | NodeClient::OpenEthereum(v)
+            | NodeClient::Nethermind(v)
+            | NodeClient::Besu(v)
+            | NodeClient::Reth(v)
+            | NodeClient::Anvil(v) => *v,
+        }
+    }
+
+    /// The client's name
+    pub fn name(&self) -> &'static str {
+        match self {
+            NodeClient::Geth(_) => "Geth",
+            NodeClient::Erigon(_) => "Erigon",
+            NodeClient::OpenEthereum(_) => "OpenEthereum",
+            NodeClient::Nethermind(_) => "Nethermind",
+            NodeClient::Besu(_) => "Besu",
+            NodeClient::Reth(_) => "Reth",
+            NodeClient::Anvil(_) => "Anvil",
+        }
+    }
+}
+
+impl fmt::Display for NodeClient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} v{}", self.name(), self.version())
+    }
+}
+
+impl FromStr for NodeClient {
+    type Err = UnknownClient;
+
+    /// Parse a `web3_clientVersion` response, e.g.
+    /// `Geth/v1.11.5-stable-a38f4108/linux-amd64/go1.20.2`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let unknown = || UnknownClient(s.to_owned());
+
+        let mut segments = s.split('/');
+        let name = segments.next().ok_or_else(unknown)?;
+        let client: fn(Version) -> NodeClient = match name.to_ascii_lowercase().as_str() {
+            "geth" => NodeClient::Geth,
+            "erigon" => NodeClient::Erigon,
+            "openethereum" => NodeClient::OpenEthereum,
+            "nethermind" => NodeClient::Nethermind,
+            "besu" => NodeClient::Besu,
+            "reth" => NodeClient::Reth,
+            "anvil" => NodeClient::Anvil,
+            _ => return Err(unknown()),
+        };
+
+        // some clients include an empty segment or an identity before the
+        // version, e.g. `OpenEthereum//v3.3.5-stable/...`
+        segments
+            .find_map(Version::parse_lenient)
+            .map(client)
+            .ok_or_else(unknown)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_parses_client_versions() {
+        let cases = [
+            (
+                "Geth/v1.11.5-stable-a38f4108/linux-amd64/go1.20.2",
+                NodeClient::Geth(Version::new(1, 11, 5)),
+            ),
+            (
+                "erigon/2.42.0/linux-amd64/go1.20.2",
+                NodeClient::Erigon(Version::new(2, 42, 0)),
+            ),
+            (
+                "OpenEthereum//v3.3.5-stable/x86_64-linux-musl/rustc1.59.0",
+                NodeClient::OpenEthereum(Version::new(3, 3, 5)),
+            ),
+            (
+                "Nethermind/v1.17.3+da0e4b5c/linux-x64/dotnet7.0.4",
+                NodeClient::Nethermind(Version::new(1, 17, 3)),
+            ),
+            (
+                "besu/v23.1.2/linux-x86_64/openjdk-java-17",
+                NodeClient::Besu(Version::new(23, 1, 2)),
+            ),
+            (
+                "reth/v0.1.0-alpha.1/x86_64-unknown-linux-gnu",
+                NodeClient::Reth(Version::new(0, 1, 0)),
+            ),
+            ("anvil/v0.1.0", NodeClient::Anvil(Version::new(0, 1, 0))),
+        ];
+        for (s, client) in cases {
+            assert_eq!(s.parse::<NodeClient>().unwrap(), client, "{s}");
+        }
+
+        assert_eq!(
+            "HardhatNetwork/2.14.0/@nomicfoundation/ethereumjs-vm/0.0.1".parse::<NodeClient>(),
+            Err(UnknownClient(
+                "HardhatNetwork/2.14.0/@nomicfoundation/ethereumjs-vm/0.0.1".to_owned()
+            ))
+        );
+        assert!("Geth".parse::<NodeClient>().is_err());
+        assert!("Geth/unstable".parse::<NodeClient>().is_err());
+    }
+
+    #[test]
+    fn it_orders_versions() {
+        assert!(Version::new(1, 11, 5) > Version::new(1, 9, 25));
+        assert_eq!(
+            NodeClient::Geth(Version::new(1, 11, 5)).to_string(),
+            "Geth v1.11.5"
+        );
+    }
+}
@@ -0,0 +1,324 @@
+//! Paginated log queries over large block ranges
+
+use ethers_pub_use::futures_util::{stream, Stream};
+use ethers_rpc_types::{BlockNumber, Filter, Header, Log};
+use ethers_transports::{common::ErrorObject, Connection};
+
+use crate::{Provider, ProviderError};
+
+/// Messages nodes and providers use to reject log queries that match too
+/// many logs or span too many blocks, lowercased
+const LOG_LIMIT_MESSAGES: &[&str] = &[
+    // geth, infura
+    "query returned more than",
+    // alchemy
+    "log response size exceeded",
+    // reth
+    "query exceeds max results",
+    "query exceeds max block range",
+    // erigon, bor
+    "exceed maximum block range",
+    // besu
+    "exceeds maximum rpc range limit",
+    // ankr
+    "block range is too wide",
+    // quicknode
+    "limited to a 10,000 range",
+];
+
+/// True if the node rejected a log query for matching too many logs or
+/// spanning too many blocks. Only known messages are recognized: the error
+/// codes are shared with unrelated errors, such as rate limiting
+pub fn is_log_limit_error(err: &ErrorObject<'_>) -> bool {
+    let message = err.message().to_lowercase();
+    LOG_LIMIT_MESSAGES
+        .iter()
+        .any(|known| message.contains(known))
+}
+
+/// The state of a paginated log query. See
+/// [`Provider::get_logs_paginated`]
+#[derive(Debug)]
+pub struct LogPaginator<'a, T> {
+    provider: &'a Provider<T>,
+    filter: Filter,
+    page_size: u64,
+    chunk: u64,
+    /// The next and last block to query. `None` until resolved
+    range: Option<(u64, u64)>,
+    done: bool,
+}
+
+impl<'a, T> LogPaginator<'a, T>
+where
+    T: Connection + Unpin,
+{
+    /// Instantiate a paginator over the filter's block range, querying at
+    /// most `page_size` blocks at a time. The page size is capped by the
+    /// provider's block range limit
+    pub fn new(provider: &'a Provider<T>, filter: Filter, page_size: u64) -> Self {
+        let page_size = provider
+            .limits()
+            .max_block_range
+            .map_or(page_size, |max| page_size.min(max))
+            .max(1);
+        Self {
+            provider,
+            filter,
+            page_size,
+            chunk: page_size,
+            range: None,
+            done: false,
+        }
+    }
+
+    /// The number of blocks the next page will span, at most the page size
+    pub fn chunk_size(&self) -> u64 {
+        self.chunk
+    }
+
+    /// Get the logs of the next page, or `None` once the range is
+    /// exhausted. When the node rejects a page as too large, the page is
+    /// halved and retried. After a successful page, the page grows back
+    /// towards the page size
+    pub async fn next_page(&mut self) -> Option<Result<Vec<Log>, ProviderError>> {
+        if self.done {
+            return None;
+        }
+        let res = self.fetch_page().await;
+        if res.is_err() {
+            self.done = true;
+        }
+        res.transpose()
+    }
+
+    /// Convert into a [`Stream`] of pages
+    pub fn into_stream(self) -> impl Stream<Item = Result<Vec<Log>, ProviderError>> + 'a {
+        stream::unfold(self, |mut this| async move {
+            let page = this.next_page().await?;
+            Some((page, this))
+        })
+    }
+
+    async fn fetch_page(&mut self) -> Result<Option<Vec<Log>>, ProviderError> {
+        // a block hash filter matches a single block
+        if self.filter.block_hash.is_some() {
+            self.done = true;
+            return Ok(Some(self.provider.get_logs(&self.filter).await??));
+        }
+
+        let (from, to) = match self.range {
+            Some(range) => range,
+            None => {
+                let range = (
+                    self.resolve(self.filter.from_block).await?,
+                    self.resolve(self.filter.to_block).await?,
+                );
+                *self.range.insert(range)
+            }
+        };
+        if from > to {
+            self.done = true;
+            return Ok(None);
+        }
+
+        loop {
+            let end = from.saturating_add(self.chunk - 1).min(to);
+            let filter = self.filter.clone().from_block(from).to_block(end);
+            match self.provider.get_logs(&filter).await? {
+                Ok(logs) => {
+                    self.chunk = self.chunk.saturating_mul(2).min(self.page_size);
+                    if end == to {
+                        self.done = true;
+                    } else {
+                        self.range = Some((end + 1, to));
+                    }
+                    return Ok(Some(logs));
+                }
+                Err(err) if self.chunk > 1 && is_log_limit_error(&err) => {
+                    self.chunk /= 2;
+                }
+                Err(err) => return Err(err.into()),
+            }
+        }
+    }
+
+    /// The number of a filter's bound. Missing bounds are the latest block,
+    /// as they are for `eth_getLogs`
+    async fn resolve(&self, block: Option<BlockNumber>) -> Result<u64, ProviderError> {
+        match block.unwrap_or_default() {
+            BlockNumber::Number(number) => Ok(number),
+            BlockNumber::Earliest => Ok(0),
+            BlockNumber::Latest | BlockNumber::Pending => {
+                Ok(self.provider.get_block_number().await??.to())
+            }
+            tag => {
+                let header: Option<Header> = self
+                    .provider
+                    .request("eth_getBlockByNumber", (tag, false))
+                    .await??;
+                header
+                    .and_then(|header| header.number)
+                    .map(|number| number.to())
+                    .ok_or(ProviderError::UnknownBlock(tag))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ethers_primitives::{Address, U64};
+    use ethers_pub_use::futures_util::StreamExt;
+    use ethers_transports::transports::MockConnection;
+
+    use super::*;
+    use crate::ProviderLimits;
+
+    fn log(number: u64) -> Log {
+        Log {
+            address: Address::repeat_byte(0x11),
+            block_number: Some(U64::from(number)),
+            ..Default::default()
+        }
+    }
+
+    fn ranges(mock: &MockConnection) -> Vec<(String, String)> {
+        mock.requests()
+            .into_iter()
+            .filter(|req| req.method == "eth_getLogs")
+            .map(|req| {
+                let filter = &req.params.unwrap()[0];
+                (
+                    filter["fromBlock"].as_str().unwrap().to_owned(),
+                    filter["toBlock"].as_str().unwrap().to_owned(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn it_recognizes_limit_errors() {
+        for (code, message) in [
+            (-32005, "query returned more than 10000 results"),
+            (-32602, "Log response size exceeded."),
+            (-32000, "exceed maximum block range: 5000"),
+            (
+                -32602,
+                "query exceeds max results 20000, retry with the range 1-10",
+            ),
+        ] {
+            assert!(is_log_limit_error(&ErrorObject::owned(
+                code,
+                message.to_owned(),
+                None::<()>
+            )));
+        }
+        for (code, message) in [
+            (-32000, "header not found"),
+            // infura rate limiting shares the limit code
+            (-32005, "daily request count exceeded, request rate limited"),
+            (
+                -32000,
+                "max fee per gas less than block base fee: more than 1 gwei short",
+            ),
+            (-32000, "too many open files"),
+        ] {
+            assert!(!is_log_limit_error(&ErrorObject::owned(
+                code,
+                message.to_owned(),
+                None::<()>
+            )));
+        }
+    }
+
+    #[tokio::test]
+    async fn it_reports_unknown_tags() {
+        let mock = MockConnection::new();
+        let provider = Provider::new(mock.clone());
+        mock.push_response("eth_getBlockByNumber", ());
+
+        let filter = Filter::new()
+            .from_block(BlockNumber::Finalized)
+            .to_block(10);
+        let pages: Vec<_> = provider.get_logs_paginated(&filter, 5).collect().await;
+        assert_eq!(pages.len(), 1);
+        assert!(matches!(
+            pages[0],
+            Err(ProviderError::UnknownBlock(BlockNumber::Finalized))
+        ));
+        assert_eq!(
+            pages[0].as_ref().unwrap_err().to_string(),
+            "the node has no finalized block"
+        );
+    }
+
+    #[tokio::test]
+    async fn it_paginates_logs() {
+        let mock = MockConnection::new();
+        let provider = Provider::new(mock.clone());
+        mock.push_response("eth_blockNumber", U64::from(24));
+        mock.push_response("eth_getLogs", vec![log(3)]);
+        mock.push_error(
+            "eth_getLogs",
+            -32005,
+            "query returned more than 10000 results",
+        );
+        mock.push_response("eth_getLogs", vec![log(12)]);
+        mock.push_response("eth_getLogs", vec![log(24)]);
+
+        let filter = Filter::new().from_block(0);
+        let pages: Vec<_> = provider
+            .get_logs_paginated(&filter, 10)
+            .map(Result::unwrap)
+            .collect()
+            .await;
+
+        assert_eq!(pages, vec![vec![log(3)], vec![log(12)], vec![log(24)]]);
+        assert_eq!(
+            ranges(&mock),
+            [
+                ("0x0", "0x9"),
+                ("0xa", "0x13"),
+                // halved after the error
+                ("0xa", "0xe"),
+                // grown back, and capped by the end of the range
+                ("0xf", "0x18"),
+            ]
+            .map(|(from, to)| (from.to_owned(), to.to_owned()))
+        );
+        assert_eq!(mock.pending_responses(), 0);
+    }
+
+    #[tokio::test]
+    async fn it_respects_the_block_range_limit() {
+        let mock = MockConnection::new();
+        let provider = Provider::new(mock.clone())
+            .with_limits(ProviderLimits::self_hosted().with_max_block_range(Some(2)));
+        mock.push_response("eth_getLogs", Vec::<Log>::new());
+        mock.push_response("eth_getLogs", Vec::<Log>::new());
+
+        let filter = Filter::new().from_block(1).to_block(4);
+        let mut paginator = LogPaginator::new(&provider, filter, 100);
+        assert_eq!(paginator.chunk_size(), 2);
+        while let Some(page) = paginator.next_page().await {
+            page.unwrap();
+        }
+        assert_eq!(
+            ranges(&mock),
+            [("0x1", "0x2"), ("0x3", "0x4")].map(|(from, to)| (from.to_owned(), to.to_owned()))
+        );
+    }
+
+    #[tokio::test]
+    async fn it_fails_on_other_errors() {
+        let mock = MockConnection::new();
+        let provider = Provider::new(mock.clone());
+        mock.push_error("eth_getLogs", -32000, "header not found");
+
+        let filter = Filter::new().from_block(0).to_block(10);
+        let pages: Vec<_> = provider.get_logs_paginated(&filter, 5).collect().await;
+        assert_eq!(pages.len(), 1);
+        assert!(matches!(pages[0], Err(ProviderError::JsonRpc(_))));
+    }
+}
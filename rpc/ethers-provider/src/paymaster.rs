@@ -0,0 +1,236 @@
+//! Sponsoring ERC-4337 user operations with a paymaster.
+//!
+//! A [`PaymasterClient`] produces the `paymasterAndData` field of a user
+//! operation. Paymaster services differ in how they are reached, so the
+//! client is pluggable: [`VerifyingPaymaster`] signs sponsorships with a
+//! local [`Signer`], and [`TokenPaymaster`] pays in ERC-20 tokens
+
+use std::error::Error;
+
+use ethers_primitives::{Address, U256};
+use ethers_pub_use::{async_trait::async_trait, thiserror};
+use ethers_rpc_types::erc4337::{
+    PaymasterAndData, PaymasterDataError, SponsorshipData, UserOperation,
+};
+use ethers_signer::Signer;
+
+/// Errors produced while sponsoring a user operation
+#[derive(Debug, thiserror::Error)]
+pub enum PaymasterError<E: Error + 'static> {
+    /// The sponsorship could not be encoded
+    #[error(transparent)]
+    Data(#[from] PaymasterDataError),
+
+    /// The signer failed
+    #[error("signing failed: {0}")]
+    Signer(#[source] E),
+}
+
+/// Sign a sponsorship of `op` by the verifying paymaster at `paymaster`,
+/// valid between `valid_after` and `valid_until`. See
+/// [`SponsorshipData::hash`]
+pub async fn sign_sponsorship<S: Signer>(
+    signer: &S,
+    op: &UserOperation,
+    paymaster: Address,
+    chain_id: u64,
+    valid_until: u64,
+    valid_after: u64,
+) -> Result<PaymasterAndData, PaymasterError<S::Error>> {
+    let data = SponsorshipData::new(valid_until, valid_after)?;
+    let signature = signer
+        .sign_message(data.hash(op, paymaster, chain_id))
+        .await
+        .map_err(PaymasterError::Signer)?;
+    let data = data.with_signature(&signature.to_bytes())?;
+    Ok(PaymasterAndData::sponsored(paymaster, data))
+}
+
+/// A source of `paymasterAndData` for user operations
+#[async_trait]
+pub trait PaymasterClient: Send + Sync {
+    /// Sponsorship errors
+    type Error: Error + Send + Sync + 'static;
+
+    /// The `paymasterAndData` paying for `op`. The operation's gas must be
+    /// set, as paymasters may sign over it
+    async fn paymaster_and_data(
+        &self,
+        op: &UserOperation,
+        entry_point: Address,
+    ) -> Result<PaymasterAndData, Self::Error>;
+
+    /// Set the paymaster of `op`. See [`PaymasterClient::paymaster_and_data`]
+    async fn sponsor(
+        &self,
+        op: UserOperation,
+        entry_point: Address,
+    ) -> Result<UserOperation, Self::Error> {
+        let data = self.paymaster_and_data(&op, entry_point).await?;
+        Ok(op.paymaster(&data))
+    }
+}
+
+/// A verifying paymaster whose off-chain signer is held locally
+#[derive(Debug, Clone)]
+pub struct VerifyingPaymaster<S> {
+    paymaster: Address,
+    signer: S,
+    chain_id: u64,
+    valid_until: u64,
+    valid_after: u64,
+}
+
+impl<S> VerifyingPaymaster<S> {
+    /// Instantiate a client for the paymaster contract at `paymaster` on
+    /// `chain_id`, signing with `signer`. Sponsorships do not expire
+    pub fn new(paymaster: Address, signer: S, chain_id: u64) -> Self {
+        Self {
+            paymaster,
+            signer,
+            chain_id,
+            valid_until: 0,
+            valid_after: 0,
+        }
+    }
+
+    /// Set the validity window of sponsorships. A `valid_until` of 0 means
+    /// no expiry
+    #[must_use = "Builder method outputs must be used"]
+    pub fn validity(mut self, valid_until: u64, valid_after: u64) -> Self {
+        self.valid_until = valid_until;
+        self.valid_after = valid_after;
+        self
+    }
+}
+
+#[async_trait]
+impl<S: Signer> PaymasterClient for VerifyingPaymaster<S> {
+    type Error = PaymasterError<S::Error>;
+
+    async fn paymaster_and_data(
+        &self,
+        op: &UserOperation,
+        _entry_point: Address,
+    ) -> Result<PaymasterAndData, Self::Error> {
+        sign_sponsorship(
+            &self.signer,
+            op,
+            self.paymaster,
+            self.chain_id,
+            self.valid_until,
+            self.valid_after,
+        )
+        .await
+    }
+}
+
+/// A token paymaster, paid in an ERC-20 token up to a fixed cost
+#[derive(Debug, Clone, Copy)]
+pub struct TokenPaymaster {
+    /// The paymaster contract
+    pub paymaster: Address,
+    /// The token the sender pays in
+    pub token: Address,
+    /// The maximum number of tokens the sender pays per operation
+    pub max_token_cost: U256,
+}
+
+#[async_trait]
+impl PaymasterClient for TokenPaymaster {
+    type Error = PaymasterDataError;
+
+    async fn paymaster_and_data(
+        &self,
+        _op: &UserOperation,
+        _entry_point: Address,
+    ) -> Result<PaymasterAndData, Self::Error> {
+        Ok(PaymasterAndData::token(
+            self.paymaster,
+            self.token,
+            self.max_token_cost,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ethers_rpc_types::erc4337::PaymasterMode;
+    use ethers_signer::{hash_message, LocalWallet, Signature};
+
+    use super::*;
+
+    fn op() -> UserOperation {
+        UserOperation {
+            sender: Address::repeat_byte(0x11),
+            nonce: U256::from(1),
+            call_gas_limit: U256::from(100_000),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn it_signs_sponsorships() {
+        let signer = LocalWallet::from_slice(&[0x42; 32]).unwrap();
+        let paymaster = Address::repeat_byte(0x22);
+        let field = sign_sponsorship(&signer, &op(), paymaster, 1, 1_700_000_000, 0)
+            .await
+            .unwrap();
+
+        let PaymasterAndData {
+            paymaster: signed_by,
+            mode: PaymasterMode::Sponsored(data),
+        } = PaymasterAndData::decode_sponsored(&field.encode()).unwrap()
+        else {
+            unreachable!()
+        };
+        assert_eq!(signed_by, paymaster);
+        assert_eq!(data.valid_until, 1_700_000_000);
+        let signature = Signature::from_bytes(&data.signature.clone().try_into().unwrap());
+        assert_eq!(
+            signature.recover(hash_message(data.hash(&op(), paymaster, 1))),
+            Some(signer.address())
+        );
+
+        assert!(matches!(
+            sign_sponsorship(&signer, &op(), paymaster, 1, u64::MAX, 0).await,
+            Err(PaymasterError::Data(PaymasterDataError::TimestampOverflow(
+                u64::MAX
+            )))
+        ));
+    }
+
+    #[tokio::test]
+    async fn it_sponsors_with_clients() {
+        let signer = LocalWallet::from_slice(&[0x42; 32]).unwrap();
+        let entry_point = Address::repeat_byte(0x5f);
+        let verifying = VerifyingPaymaster::new(Address::repeat_byte(0x22), signer, 1)
+            .validity(1_700_000_000, 1_600_000_000);
+        let sponsored = verifying.sponsor(op(), entry_point).await.unwrap();
+        let expected = verifying
+            .paymaster_and_data(&op(), entry_point)
+            .await
+            .unwrap();
+        assert_eq!(sponsored.paymaster_and_data[..], expected.encode()[..]);
+        assert_eq!(sponsored.paymaster_and_data.len(), 20 + 64 + 65);
+
+        let token = TokenPaymaster {
+            paymaster: Address::repeat_byte(0x33),
+            token: Address::repeat_byte(0x44),
+            max_token_cost: U256::from(1_000_000),
+        };
+        let sponsored = token.sponsor(op(), entry_point).await.unwrap();
+        assert_eq!(
+            PaymasterAndData::decode_token(&sponsored.paymaster_and_data).unwrap(),
+            PaymasterAndData::token(token.paymaster, token.token, token.max_token_cost)
+        );
+        // the rest of the operation is untouched
+        assert_eq!(
+            UserOperation {
+                paymaster_and_data: Default::default(),
+                ..sponsored
+            },
+            op()
+        );
+    }
+}
@@ -1,50 +1,55 @@
-use std::{borrow::Cow, fmt::Debug, str::FromStr, sync::Arc, time::Duration};
+use std::{
+    borrow::Cow,
+    fmt::Debug,
+    str::FromStr,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
 
+use ethers_primitives::{Address, Chain, Signature, B256, U256, U64};
 use ethers_pub_use::{
-    futures_channel::mpsc, once_cell::sync::OnceCell, serde_json::value::RawValue,
+    futures_channel::mpsc,
+    futures_util::Stream,
+    once_cell::sync::OnceCell,
+    serde::{Deserialize, Serialize},
+    serde_json::{self, value::RawValue},
+};
+use ethers_rpc_types::{
+    pubsub::SubscriptionKind, transaction::AccessListWithGasUsed, BlockId, BlockNumber, Bytes,
+    EIP1186ProofResponse, FeeHistory, Filter, Header, Log, SimulatePayload, SimulatedBlock,
+    StateOverride, TransactionReceipt, TransactionRequest, ValueOrArray,
 };
 use ethers_transports::{
-    common::*, transports::Http, Connection, PubSubConnection, TransportError,
+    common::*,
+    time::{self, Instant},
+    transports::Http,
+    BoxedConnection, Connection, PubSubConnection, RpcCall, TransportError,
 };
 
-use crate::{DEFAULT_LOCAL_POLL_INTERVAL, DEFAULT_POLL_INTERVAL};
+use crate::{
+    LogPaginator, NodeClient, ProviderError, ProviderLimits, ValidationMode,
+    DEFAULT_LOCAL_POLL_INTERVAL, DEFAULT_POLL_INTERVAL,
+};
 
 /// An `HttpProvider` is a [`Provider`] backed by an [`Http`] transport. See the
 /// provider docs for full details
 pub type HttpProvider = Provider<ethers_transports::transports::Http>;
 
-/// Node Clients
-#[derive(Copy, Clone, Debug)]
-pub enum NodeClient {
-    /// Geth
-    Geth,
-    /// Erigon
-    Erigon,
-    /// OpenEthereum
-    OpenEthereum,
-    /// Nethermind
-    Nethermind,
-    /// Besu
-    Besu,
-}
-
-impl std::fmt::Display for NodeClient {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            NodeClient::Geth => write!(f, "Geth"),
-            NodeClient::Erigon => write!(f, "Erigon"),
-            NodeClient::OpenEthereum => write!(f, "OpenEthereum"),
-            NodeClient::Nethermind => write!(f, "Nethermind"),
-            NodeClient::Besu => write!(f, "Besu"),
-        }
-    }
-}
-
 #[derive(Clone)]
 pub struct Provider<T> {
     transport: T,
     node_client: Arc<OnceCell<NodeClient>>,
+    chain_id: Arc<OnceCell<u64>>,
     interval: Option<Duration>,
+    timeout: Option<Duration>,
+    limits: ProviderLimits,
+    /// Subscriptions opened by [`Provider::subscribe`] and not yet closed
+    subscriptions: Arc<AtomicUsize>,
+    chain: Option<Chain>,
+    pub(crate) validation: ValidationMode,
 }
 
 impl<T> Provider<T> {
@@ -52,7 +57,13 @@ impl<T> Provider<T> {
         Self {
             transport,
             node_client: Default::default(),
+            chain_id: Default::default(),
             interval: None,
+            timeout: None,
+            limits: Default::default(),
+            subscriptions: Default::default(),
+            chain: None,
+            validation: Default::default(),
         }
     }
 
@@ -65,6 +76,53 @@ impl<T> Provider<T> {
     pub fn set_interval(&mut self, interval: Duration) {
         self.interval = Some(interval);
     }
+
+    /// Fail requests that receive no response within `timeout` with
+    /// [`TransportError::Timeout`]. Individual calls may override it with
+    /// [`RpcCall::timeout`]
+    #[must_use = "Builder method outputs must be used"]
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.set_timeout(Some(timeout));
+        self
+    }
+
+    pub fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.timeout = timeout;
+    }
+
+    /// Set the server-side limits this provider should respect. See
+    /// [`ProviderLimits`]
+    #[must_use = "Builder method outputs must be used"]
+    pub fn with_limits(mut self, limits: ProviderLimits) -> Self {
+        self.set_limits(limits);
+        self
+    }
+
+    pub fn set_limits(&mut self, limits: ProviderLimits) {
+        self.limits = limits;
+    }
+
+    /// The server-side limits this provider respects
+    pub fn limits(&self) -> &ProviderLimits {
+        &self.limits
+    }
+
+    /// Set the chain the provider is connected to. Unless an interval is
+    /// set, remote providers poll at half the chain's block time
+    #[must_use = "Builder method outputs must be used"]
+    pub fn with_chain(mut self, chain: Chain) -> Self {
+        self.set_chain(chain);
+        self
+    }
+
+    pub fn set_chain(&mut self, chain: Chain) {
+        self.chain = Some(chain);
+    }
+
+    /// The chain the provider is connected to, if set
+    pub fn chain(&self) -> Option<Chain> {
+        self.chain
+    }
 }
 
 impl<T> Provider<T>
@@ -72,13 +130,544 @@ where
     T: Connection,
 {
     pub fn interval(&self) -> Duration {
-        self.interval.unwrap_or_else(|| match self.is_local() {
-            true => DEFAULT_LOCAL_POLL_INTERVAL,
-            false => DEFAULT_POLL_INTERVAL,
-        })
+        self.interval
+            .unwrap_or_else(|| match (self.is_local(), self.chain) {
+                (true, _) => DEFAULT_LOCAL_POLL_INTERVAL,
+                (false, Some(chain)) => chain.average_block_time() / 2,
+                (false, None) => DEFAULT_POLL_INTERVAL,
+            })
+    }
+
+    /// Get the node's client version string, e.g.
+    /// `Geth/v1.11.5-stable-a38f4108/linux-amd64/go1.20.2`
+    pub fn client_version(&self) -> RpcCall<&Self, Self, (), String> {
+        self.request("web3_clientVersion", ())
+    }
+
+    /// Get the node's client and version, for behavior that differs between
+    /// clients. The result is cached after the first successful request
+    pub async fn node_client(&self) -> Result<NodeClient, ProviderError>
+    where
+        T: Unpin,
+    {
+        if let Some(client) = self.node_client.get() {
+            return Ok(*client);
+        }
+        let version = self.client_version().await??;
+        let client = version.parse()?;
+        Ok(*self.node_client.get_or_init(|| client))
+    }
+
+    /// Execute a call without creating a transaction. An ENS name recipient
+    /// is resolved first. Defaults to the latest block
+    pub async fn call(
+        &self,
+        tx: TransactionRequest,
+        block: Option<BlockId>,
+    ) -> Result<Bytes, ProviderError>
+    where
+        T: Unpin,
+    {
+        let tx = self.resolve_request(tx).await?;
+        let resp: Bytes = self
+            .request("eth_call", (tx, block.unwrap_or_default()))
+            .await??;
+        Ok(resp)
+    }
+
+    /// Execute a call against temporarily overridden account state, such as
+    /// a balance or an allowance the sender does not have. See
+    /// [`Provider::call`]
+    pub async fn call_with_overrides(
+        &self,
+        tx: TransactionRequest,
+        block: Option<BlockId>,
+        overrides: &StateOverride,
+    ) -> Result<Bytes, ProviderError>
+    where
+        T: Unpin,
+    {
+        let tx = self.resolve_request(tx).await?;
+        let resp: Bytes = self
+            .request("eth_call", (tx, block.unwrap_or_default(), overrides))
+            .await??;
+        Ok(resp)
+    }
+
+    /// Estimate the gas a transaction would use. An ENS name recipient is
+    /// resolved first. Defaults to the latest block
+    pub async fn estimate_gas(
+        &self,
+        tx: TransactionRequest,
+        block: Option<BlockId>,
+    ) -> Result<U256, ProviderError>
+    where
+        T: Unpin,
+    {
+        let tx = self.resolve_request(tx).await?;
+        let gas: U256 = self
+            .request("eth_estimateGas", (tx, block.unwrap_or_default()))
+            .await??;
+        Ok(gas)
+    }
+
+    /// Estimate the gas a transaction would use against temporarily
+    /// overridden account state. See [`Provider::estimate_gas`]
+    pub async fn estimate_gas_with_overrides(
+        &self,
+        tx: TransactionRequest,
+        block: Option<BlockId>,
+        overrides: &StateOverride,
+    ) -> Result<U256, ProviderError>
+    where
+        T: Unpin,
+    {
+        let tx = self.resolve_request(tx).await?;
+        let gas: U256 = self
+            .request(
+                "eth_estimateGas",
+                (tx, block.unwrap_or_default(), overrides),
+            )
+            .await??;
+        Ok(gas)
+    }
+
+    /// Simulate sequences of calls across one or more blocks built on top of
+    /// `block`, with `eth_simulateV1`. Returns each simulated block, with
+    /// the return data, logs and gas of its calls. ENS name recipients are
+    /// resolved first. Defaults to the latest block
+    pub async fn simulate(
+        &self,
+        mut payload: SimulatePayload,
+        block: Option<BlockId>,
+    ) -> Result<Vec<SimulatedBlock>, ProviderError>
+    where
+        T: Unpin,
+    {
+        for sim_block in &mut payload.block_state_calls {
+            for call in &mut sim_block.calls {
+                *call = self.resolve_request(std::mem::take(call)).await?;
+            }
+        }
+        let blocks: Vec<SimulatedBlock> = self
+            .request("eth_simulateV1", (payload, block.unwrap_or_default()))
+            .await??;
+        Ok(blocks)
+    }
+
+    /// Generate the access list of a transaction, and the gas it uses with
+    /// it. An ENS name recipient is resolved first. Defaults to the latest
+    /// block
+    pub async fn create_access_list(
+        &self,
+        tx: TransactionRequest,
+        block: Option<BlockId>,
+    ) -> Result<AccessListWithGasUsed, ProviderError>
+    where
+        T: Unpin,
+    {
+        let tx = self.resolve_request(tx).await?;
+        let generated: AccessListWithGasUsed = self
+            .request("eth_createAccessList", (tx, block.unwrap_or_default()))
+            .await??;
+        Ok(generated)
+    }
+
+    /// Attach the generated access list to a transaction request, if it
+    /// lowers the gas the transaction uses. Requests with an access list are
+    /// sent as EIP-2930 or EIP-1559 transactions
+    pub async fn attach_access_list(
+        &self,
+        tx: TransactionRequest,
+        block: Option<BlockId>,
+    ) -> Result<TransactionRequest, ProviderError>
+    where
+        T: Unpin,
+    {
+        let mut tx = self.resolve_request(tx).await?;
+        tx.access_list = None;
+        let generated = self.create_access_list(tx.clone(), block).await?;
+        if generated.error.is_some() {
+            return Ok(tx);
+        }
+        let gas = self.estimate_gas(tx.clone(), block).await?;
+        if generated.gas_used < gas {
+            tx.access_list = Some(generated.access_list);
+        }
+        Ok(tx)
+    }
+
+    /// Send a transaction from an account managed by the node, which must be
+    /// unlocked. An ENS name recipient is resolved first. Returns the
+    /// transaction hash
+    pub async fn send_transaction(&self, tx: TransactionRequest) -> Result<B256, ProviderError>
+    where
+        T: Unpin,
+    {
+        let tx = self.resolve_request(tx).await?;
+        let hash: B256 = self.request("eth_sendTransaction", (tx,)).await??;
+        Ok(hash)
+    }
+
+    /// Poll for the receipt of a transaction at the provider's interval
+    /// until it is mined. Fails with [`TransportError::Timeout`] if it is not
+    /// mined within `timeout`
+    pub async fn wait_for_receipt(
+        &self,
+        hash: B256,
+        timeout: Option<Duration>,
+    ) -> Result<TransactionReceipt, ProviderError>
+    where
+        T: Unpin,
+    {
+        let started = Instant::now();
+        loop {
+            if let Some(receipt) = self.get_transaction_receipt(hash).await? {
+                return Ok(receipt);
+            }
+            let elapsed = started.elapsed();
+            if timeout.is_some_and(|timeout| elapsed >= timeout) {
+                return Err(TransportError::Timeout(elapsed).into());
+            }
+            time::sleep(self.interval()).await;
+        }
+    }
+
+    /// Get the chain id the node signs transactions for
+    pub fn get_chain_id(&self) -> RpcCall<&Self, Self, (), U64> {
+        self.request("eth_chainId", ())
+    }
+
+    /// The chain id the node signs transactions for. The result is cached
+    /// after the first successful request
+    pub async fn chain_id(&self) -> Result<u64, ProviderError>
+    where
+        T: Unpin,
+    {
+        if let Some(chain_id) = self.chain_id.get() {
+            return Ok(*chain_id);
+        }
+        let chain_id = self.get_chain_id().await??.to::<u64>();
+        Ok(*self.chain_id.get_or_init(|| chain_id))
+    }
+
+    /// Get the number of the most recent block
+    pub fn get_block_number(&self) -> RpcCall<&Self, Self, (), U64> {
+        self.request("eth_blockNumber", ())
+    }
+
+    /// Get the header of a block, without its transactions. `None` if the
+    /// block is unknown
+    pub async fn get_block(&self, block: BlockId) -> Result<Option<Header>, ProviderError>
+    where
+        T: Unpin,
+    {
+        let header: Option<Header> = match block {
+            BlockId::Hash { hash, .. } => {
+                self.request("eth_getBlockByHash", (hash, false)).await??
+            }
+            BlockId::Number(number) => {
+                self.request("eth_getBlockByNumber", (number, false))
+                    .await??
+            }
+        };
+        Ok(header)
+    }
+
+    /// Get the balance of an account at a block. Defaults to the latest block
+    pub fn get_balance(
+        &self,
+        address: Address,
+        block: Option<BlockId>,
+    ) -> RpcCall<&Self, Self, (Address, BlockId), U256> {
+        self.request("eth_getBalance", (address, block.unwrap_or_default()))
+    }
+
+    /// Get the number of transactions sent from an account at a block.
+    /// Defaults to the latest block
+    pub fn get_transaction_count(
+        &self,
+        address: Address,
+        block: Option<BlockId>,
+    ) -> RpcCall<&Self, Self, (Address, BlockId), U256> {
+        self.request(
+            "eth_getTransactionCount",
+            (address, block.unwrap_or_default()),
+        )
+    }
+
+    /// Get the code of an account at a block. Defaults to the latest block
+    pub fn get_code(
+        &self,
+        address: Address,
+        block: Option<BlockId>,
+    ) -> RpcCall<&Self, Self, (Address, BlockId), Bytes> {
+        self.request("eth_getCode", (address, block.unwrap_or_default()))
+    }
+
+    /// Get the value of a storage slot of an account at a block. Defaults to
+    /// the latest block
+    pub fn get_storage_at(
+        &self,
+        address: Address,
+        slot: U256,
+        block: Option<BlockId>,
+    ) -> RpcCall<&Self, Self, (Address, U256, BlockId), B256> {
+        self.request(
+            "eth_getStorageAt",
+            (address, slot, block.unwrap_or_default()),
+        )
+    }
+
+    /// Get an account and some of its storage slots, with Merkle-Patricia
+    /// proofs against the state root of a block. Defaults to the latest
+    /// block. See [`EIP1186ProofResponse::verify`]
+    pub fn get_proof(
+        &self,
+        address: Address,
+        keys: Vec<B256>,
+        block: Option<BlockId>,
+    ) -> RpcCall<&Self, Self, (Address, Vec<B256>, BlockId), EIP1186ProofResponse> {
+        self.request("eth_getProof", (address, keys, block.unwrap_or_default()))
+    }
+
+    /// Get the current gas price
+    pub fn get_gas_price(&self) -> RpcCall<&Self, Self, (), U256> {
+        self.request("eth_gasPrice", ())
+    }
+
+    /// Send a signed transaction. Returns the transaction hash
+    pub fn send_raw_transaction(&self, tx: Bytes) -> RpcCall<&Self, Self, (Bytes,), B256> {
+        self.request("eth_sendRawTransaction", (tx,))
+    }
+
+    /// Get the logs matching a filter. Queries that exceed the provider's
+    /// block range or filter address [limits](ProviderLimits) are split, and
+    /// the logs of all parts are returned in chain order. The block range is
+    /// split only if both bounds are block numbers. See
+    /// [`Provider::get_logs_paginated`] for ranges bounded by tags
+    pub async fn get_logs(
+        &self,
+        filter: &Filter,
+    ) -> Result<Result<Vec<Log>, ErrorObject<'static>>, TransportError>
+    where
+        T: Unpin,
+    {
+        let ranges: Vec<_> = match (filter.from_block, filter.to_block) {
+            (Some(BlockNumber::Number(from)), Some(BlockNumber::Number(to)))
+                if filter.block_hash.is_none() && from <= to =>
+            {
+                self.limits.split_block_range(from, to).map(Some).collect()
+            }
+            _ => vec![None],
+        };
+        let addresses: Vec<_> = match &filter.address {
+            Some(ValueOrArray::Array(addresses))
+                if self
+                    .limits
+                    .max_filter_addresses
+                    .is_some_and(|max| addresses.len() > max) =>
+            {
+                self.limits
+                    .split_addresses(addresses)
+                    .map(|chunk| Some(ValueOrArray::Array(chunk.to_vec())))
+                    .collect()
+            }
+            address => vec![address.clone()],
+        };
+        if ranges.len() == 1 && addresses.len() == 1 {
+            return self.request("eth_getLogs", (filter.clone(),)).await;
+        }
+
+        let mut logs = vec![];
+        for range in ranges {
+            let start = logs.len();
+            for address in &addresses {
+                let mut part = filter.clone();
+                if let Some(range) = &range {
+                    part = part.from_block(*range.start()).to_block(*range.end());
+                }
+                part.address = address.clone();
+                match self.request::<_, Vec<Log>>("eth_getLogs", (part,)).await? {
+                    Ok(page) => logs.extend(page),
+                    Err(err) => return Ok(Err(err)),
+                }
+            }
+            // the parts of a range match disjoint addresses in the same
+            // blocks
+            if addresses.len() > 1 {
+                logs[start..].sort_by_key(|log| (log.block_number, log.log_index));
+            }
+        }
+        Ok(Ok(logs))
+    }
+
+    /// Get the logs matching a filter in pages of at most `page_size`
+    /// blocks, for ranges too large for a single query. Pages the node
+    /// rejects as too large are split. See [`LogPaginator`]
+    pub fn get_logs_paginated(
+        &self,
+        filter: &Filter,
+        page_size: u64,
+    ) -> impl Stream<Item = Result<Vec<Log>, ProviderError>> + '_
+    where
+        T: Unpin,
+    {
+        LogPaginator::new(self, filter.clone(), page_size).into_stream()
+    }
+
+    /// Get the base fees and gas usage of `block_count` blocks up to
+    /// `newest_block`, and the priority fees paid in each at the given
+    /// percentiles
+    pub fn fee_history(
+        &self,
+        block_count: u64,
+        newest_block: BlockNumber,
+        reward_percentiles: &[f64],
+    ) -> RpcCall<&Self, Self, (U64, BlockNumber, Vec<f64>), FeeHistory> {
+        self.request(
+            "eth_feeHistory",
+            (
+                U64::from(block_count),
+                newest_block,
+                reward_percentiles.to_vec(),
+            ),
+        )
+    }
+
+    /// Sign a message with the EIP-191 prefix using an account managed by
+    /// the node, which must be unlocked
+    pub fn personal_sign(
+        &self,
+        message: impl AsRef<[u8]>,
+        address: Address,
+    ) -> RpcCall<&Self, Self, (Bytes, Address), Signature> {
+        self.request("personal_sign", (Bytes::from(message.as_ref()), address))
+    }
+
+    /// Get the address that receives the node's mining rewards. Legacy, see
+    /// [`ProviderError::UnsupportedMethod`]
+    pub async fn get_coinbase(&self) -> Result<Address, ProviderError>
+    where
+        T: Unpin,
+    {
+        self.legacy_request("eth_coinbase").await
+    }
+
+    /// Check if the node is mining. Legacy, see
+    /// [`ProviderError::UnsupportedMethod`]
+    pub async fn is_mining(&self) -> Result<bool, ProviderError>
+    where
+        T: Unpin,
+    {
+        self.legacy_request("eth_mining").await
+    }
+
+    /// Get the node's mining hashrate, in hashes per second. Legacy, see
+    /// [`ProviderError::UnsupportedMethod`]
+    pub async fn get_hashrate(&self) -> Result<U64, ProviderError>
+    where
+        T: Unpin,
+    {
+        self.legacy_request("eth_hashrate").await
+    }
+
+    /// Send a parameterless request for a method that nodes may have
+    /// removed, reporting its absence as [`ProviderError::UnsupportedMethod`]
+    async fn legacy_request<Resp>(&self, method: &'static str) -> Result<Resp, ProviderError>
+    where
+        T: Unpin,
+        Resp: for<'de> Deserialize<'de> + Unpin,
+    {
+        match self.request(method, ()).await? {
+            Ok(resp) => Ok(resp),
+            Err(err) if is_method_not_found(&err) => Err(ProviderError::UnsupportedMethod(method)),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Get the receipt of a mined transaction. `None` if the transaction is
+    /// unknown or pending. The receipt is checked according to the
+    /// provider's [`ValidationMode`]
+    pub async fn get_transaction_receipt(
+        &self,
+        hash: B256,
+    ) -> Result<Option<TransactionReceipt>, ProviderError>
+    where
+        T: Unpin,
+    {
+        let method = "eth_getTransactionReceipt";
+        let receipt: Option<TransactionReceipt> = self.request(method, (hash,)).await??;
+        self.check_response(method, receipt)
     }
 }
 
+impl<T> Provider<T>
+where
+    T: Connection,
+{
+    /// Send a request for a method that does not (yet) have typed support.
+    ///
+    /// This is an escape hatch for experimental or client-specific
+    /// namespaces. Prefer the typed methods where they exist.
+    pub fn raw_request<Params, Resp>(
+        &self,
+        method: &str,
+        params: Params,
+    ) -> RpcCall<&Self, Self, Params, Resp>
+    where
+        Params: Serialize,
+        Resp: for<'de> Deserialize<'de>,
+    {
+        RpcCall::new(self, method.to_owned(), params, self.next_id())
+    }
+
+    /// Send a batch of requests for methods that do not (yet) have typed
+    /// support. Responses are returned in request order. Batches that
+    /// exceed the provider's [batch limits](ProviderLimits) are split.
+    ///
+    /// See [`Provider::raw_request`].
+    pub async fn raw_batch_request<'a, Params, Resp>(
+        &self,
+        calls: impl IntoIterator<Item = (&'a str, Params)>,
+    ) -> Result<Vec<Result<Resp, ErrorObject<'static>>>, TransportError>
+    where
+        Params: Serialize,
+        Resp: for<'de> Deserialize<'de>,
+    {
+        let reqs = calls
+            .into_iter()
+            .map(|(method, params)| {
+                serde_json::value::to_raw_value(&params)
+                    .map(|params| Request::owned(self.next_id(), method, Some(params)))
+                    .map_err(TransportError::ser_err)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        self.batch_request(&reqs)
+            .await?
+            .into_iter()
+            .map(|res| match res {
+                Ok(val) => serde_json::from_str(val.get())
+                    .map(Ok)
+                    .map_err(|err| TransportError::deser_err(err, val.get())),
+                Err(err) => Ok(Err(err)),
+            })
+            .collect()
+    }
+}
+
+/// The JSON-RPC error code for unknown methods
+const METHOD_NOT_FOUND: i32 = -32601;
+
+/// `true` if the error reports that the node does not serve the method
+pub(crate) fn is_method_not_found(err: &ErrorObject<'_>) -> bool {
+    let message = err.message().to_lowercase();
+    err.code() == METHOD_NOT_FOUND
+        || message.contains("does not exist")
+        || message.contains("not supported")
+}
+
 impl<T> std::fmt::Debug for Provider<T>
 where
     T: Debug,
@@ -92,7 +681,11 @@ where
         f.debug_struct("Provider")
             .field("transport", &self.transport)
             .field("_node_client", &node)
+            .field("_chain_id", &self.chain_id.get())
             .field("interval", &self.interval)
+            .field("timeout", &self.timeout)
+            .field("limits", &self.limits)
+            .field("validation", &self.validation)
             .finish()
     }
 }
@@ -101,7 +694,18 @@ impl FromStr for Provider<Http> {
     type Err = <Http as FromStr>::Err;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        s.parse().map(Self::new)
+        s.parse()
+            .map(Self::new)
+            .map(|p| p.with_limits(ProviderLimits::for_url(s)))
+    }
+}
+
+impl Provider<BoxedConnection> {
+    /// Connect to an endpoint, choosing the transport from the url scheme.
+    /// See [`ethers_transports::connect`]
+    pub fn connect(url: &str) -> Result<Self, TransportError> {
+        let transport = ethers_transports::connect(url)?;
+        Ok(Self::new(transport).with_limits(ProviderLimits::for_url(url)))
     }
 }
 
@@ -121,8 +725,104 @@ where
         self.transport.json_rpc_request(req)
     }
 
+    fn default_timeout(&self) -> Option<Duration> {
+        self.timeout.or_else(|| self.transport.default_timeout())
+    }
+
+    /// Send a batch, split into sequential chunks that respect the batch
+    /// limits. Responses are returned in request order
     fn batch_request(&self, reqs: &[Request<'_>]) -> BatchRpcFuture {
-        self.transport.batch_request(reqs)
+        let chunks = self.limits.split_batch(reqs, |req| {
+            serde_json::to_vec(req).map_or(0, |json| json.len())
+        });
+        if chunks.len() <= 1 {
+            return self.transport.batch_request(reqs);
+        }
+
+        let futs: Vec<_> = chunks
+            .into_iter()
+            .map(|chunk| self.transport.batch_request(chunk))
+            .collect();
+        Box::pin(async move {
+            let mut resps = Vec::with_capacity(futs.len());
+            for fut in futs {
+                resps.extend(fut.await?);
+            }
+            Ok(resps)
+        })
+    }
+}
+
+impl<T> Provider<T>
+where
+    T: PubSubConnection + Unpin,
+{
+    /// Open an `eth_subscribe` subscription, with a filter for
+    /// [`SubscriptionKind::Logs`]. Returns the server-assigned id and a
+    /// receiver of the notification payloads.
+    ///
+    /// Fails with [`LimitError::TooManySubscriptions`] if the provider's
+    /// subscriptions already reach the subscription limit. Close
+    /// subscriptions with [`Provider::unsubscribe`] to free their slots
+    ///
+    /// [`LimitError::TooManySubscriptions`]: crate::limits::LimitError::TooManySubscriptions
+    pub async fn subscribe(
+        &self,
+        kind: SubscriptionKind,
+        filter: Option<&Filter>,
+    ) -> Result<(U256, mpsc::UnboundedReceiver<Cow<'static, RawValue>>), ProviderError> {
+        // reserve a slot, so concurrent subscriptions cannot overshoot
+        let mut active = self.subscriptions.load(Ordering::Relaxed);
+        loop {
+            self.limits.check_subscriptions(active)?;
+            match self.subscriptions.compare_exchange_weak(
+                active,
+                active + 1,
+                Ordering::Relaxed,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => break,
+                Err(current) => active = current,
+            }
+        }
+
+        let res = async {
+            let id: U256 = match filter {
+                Some(filter) => {
+                    self.request("eth_subscribe", (kind, filter.clone()))
+                        .await??
+                }
+                None => self.request("eth_subscribe", (kind,)).await??,
+            };
+            let rx = self.install_listener(id.to_be_bytes())?;
+            Ok((id, rx))
+        }
+        .await;
+        if res.is_err() {
+            self.subscriptions.fetch_sub(1, Ordering::Relaxed);
+        }
+        res
+    }
+
+    /// Close a subscription opened by [`Provider::subscribe`], and remove its
+    /// listener. Returns `false` if the node did not know the subscription
+    pub async fn unsubscribe(&self, id: U256) -> Result<bool, ProviderError> {
+        self.uninstall_listener(id.to_be_bytes())?;
+        let closed: bool = self.request("eth_unsubscribe", (id,)).await??;
+        if closed {
+            // saturating, so closing a subscription opened elsewhere cannot
+            // underflow
+            let _ = self
+                .subscriptions
+                .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| n.checked_sub(1));
+        }
+        Ok(closed)
+    }
+
+    /// The number of subscriptions opened by [`Provider::subscribe`] and not
+    /// yet closed. Shared by clones of the provider
+    pub fn active_subscriptions(&self) -> usize {
+        self.subscriptions.load(Ordering::Relaxed)
     }
 }
 
@@ -137,7 +837,367 @@ where
     fn install_listener(
         &self,
         id: [u8; 32],
-    ) -> Result<mpsc::UnboundedReceiver<Cow<'_, RawValue>>, TransportError> {
+    ) -> Result<mpsc::UnboundedReceiver<Cow<'static, RawValue>>, TransportError> {
         self.transport.install_listener(id)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use ethers_pub_use::serde_json::{json, Value};
+    use ethers_transports::transports::{MockConnection, MockRequest};
+
+    use super::*;
+
+    /// Serves the mining methods as a post-merge node would, and echoes the
+    /// params of other requests
+    fn echo() -> MockConnection {
+        MockConnection::new().with_handler(|req| {
+            let params = req.params.clone().unwrap_or_default();
+            let resp = match req.method.as_str() {
+                "eth_hashrate" => json!("0x0"),
+                // the number of params, to check overrides are sent
+                "eth_estimateGas" => json!(format!("0x{}", params.as_array().unwrap().len())),
+                "eth_createAccessList" => json!({
+                    "accessList": [{
+                        "address": "0x0101010101010101010101010101010101010101",
+                        "storageKeys": []
+                    }],
+                    "gasUsed": "0x1"
+                }),
+                "personal_sign" => json!(format!("0x{}1c", "11".repeat(64))),
+                "eth_mining" => {
+                    return Ok(Err(ErrorObject::owned(
+                        METHOD_NOT_FOUND,
+                        "the method eth_mining does not exist/is not available",
+                        None::<()>,
+                    )))
+                }
+                "eth_coinbase" => {
+                    return Ok(Err(ErrorObject::owned(
+                        -32000,
+                        "etherbase must be explicitly specified",
+                        None::<()>,
+                    )))
+                }
+                _ => params,
+            };
+            Ok(Ok(Cow::Owned(
+                serde_json::value::to_raw_value(&resp).unwrap(),
+            )))
+        })
+    }
+
+    #[tokio::test]
+    async fn it_splits_batches() {
+        let mock = echo();
+        let provider = Provider::new(mock.clone())
+            .with_limits(ProviderLimits::self_hosted().with_max_batch_len(Some(2)));
+
+        let resps: Vec<Result<[u64; 1], _>> = provider
+            .raw_batch_request((0..5u64).map(|i| ("eth_chainId", [i])))
+            .await
+            .unwrap();
+        let resps: Vec<u64> = resps.into_iter().map(|resp| resp.unwrap()[0]).collect();
+        assert_eq!(resps, vec![0, 1, 2, 3, 4]);
+        assert_eq!(mock.batches(), vec![2, 2, 1]);
+    }
+
+    #[tokio::test]
+    async fn it_sends_raw_requests() {
+        let mock = MockConnection::new();
+        let provider = Provider::new(mock.clone());
+
+        let trace = json!({ "gas": 21000, "failed": false, "structLogs": [] });
+        mock.push_response("debug_traceTransaction", &trace);
+        let resp: Value = provider
+            .raw_request(
+                "debug_traceTransaction",
+                (B256::repeat_byte(1), json!({ "disableStorage": true })),
+            )
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(resp, trace);
+        assert_eq!(
+            mock.requests(),
+            vec![MockRequest {
+                method: "debug_traceTransaction".to_owned(),
+                params: Some(json!([B256::repeat_byte(1), { "disableStorage": true }])),
+            }]
+        );
+
+        mock.push_error("txpool_status", METHOD_NOT_FOUND, "not available");
+        let err = provider
+            .raw_request::<_, Value>("txpool_status", ())
+            .await
+            .unwrap()
+            .unwrap_err();
+        assert_eq!(err.code(), METHOD_NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn it_sends_raw_batches() {
+        let mock = MockConnection::new();
+        let provider = Provider::new(mock.clone());
+
+        mock.push_response("eth_getBalance", "0x1");
+        mock.push_error("eth_getBalance", -32000, "header not found");
+        mock.push_response("eth_getBalance", "0x3");
+        let resps: Vec<Result<U256, _>> = provider
+            .raw_batch_request(
+                [
+                    BlockNumber::Latest,
+                    BlockNumber::Number(1),
+                    BlockNumber::Pending,
+                ]
+                .map(|block| ("eth_getBalance", (Address::repeat_byte(2), block))),
+            )
+            .await
+            .unwrap();
+        assert_eq!(resps[0].as_ref().unwrap(), &U256::from(1));
+        assert_eq!(resps[1].as_ref().unwrap_err().message(), "header not found");
+        assert_eq!(resps[2].as_ref().unwrap(), &U256::from(3));
+
+        assert_eq!(mock.batches(), vec![3]);
+        let params: Vec<_> = mock
+            .requests()
+            .into_iter()
+            .map(|req| {
+                assert_eq!(req.method, "eth_getBalance");
+                req.params.unwrap()
+            })
+            .collect();
+        assert_eq!(
+            params,
+            vec![
+                json!([Address::repeat_byte(2), "latest"]),
+                json!([Address::repeat_byte(2), "0x1"]),
+                json!([Address::repeat_byte(2), "pending"]),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn it_splits_log_queries() {
+        let mock = MockConnection::new();
+        let provider = Provider::new(mock.clone()).with_limits(
+            ProviderLimits::self_hosted()
+                .with_max_block_range(Some(10))
+                .with_max_filter_addresses(Some(2)),
+        );
+        let log = |address: u8, number: u64, index: u64| Log {
+            address: Address::repeat_byte(address),
+            block_number: Some(U64::from(number)),
+            log_index: Some(U256::from(index)),
+            ..Default::default()
+        };
+        // two block ranges, each queried for two address chunks
+        mock.push_response("eth_getLogs", [log(1, 5, 1)]);
+        mock.push_response("eth_getLogs", [log(3, 5, 0), log(3, 6, 0)]);
+        mock.push_response("eth_getLogs", Vec::<Log>::new());
+        mock.push_response("eth_getLogs", [log(3, 12, 0)]);
+
+        let addresses: Vec<_> = (1..=3).map(Address::repeat_byte).collect();
+        let filter = Filter::new().from_block(0).to_block(15).address(addresses);
+        let logs = provider.get_logs(&filter).await.unwrap().unwrap();
+        assert_eq!(
+            logs,
+            vec![log(3, 5, 0), log(1, 5, 1), log(3, 6, 0), log(3, 12, 0)]
+        );
+
+        let parts: Vec<_> = mock
+            .requests()
+            .into_iter()
+            .map(|req| {
+                let filter = &req.params.unwrap()[0];
+                (
+                    filter["fromBlock"].as_str().unwrap().to_owned(),
+                    filter["toBlock"].as_str().unwrap().to_owned(),
+                    filter["address"].as_array().unwrap().len(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            parts,
+            [
+                ("0x0".into(), "0x9".into(), 2),
+                ("0x0".into(), "0x9".into(), 1),
+                ("0xa".into(), "0xf".into(), 2),
+                ("0xa".into(), "0xf".into(), 1),
+            ]
+        );
+
+        // tag bounds are sent as one query
+        mock.push_response("eth_getLogs", Vec::<Log>::new());
+        let filter = Filter::new().from_block(0).to_block(BlockNumber::Latest);
+        assert!(provider
+            .get_logs(&filter)
+            .await
+            .unwrap()
+            .unwrap()
+            .is_empty());
+        assert_eq!(mock.requests().len(), 5);
+    }
+
+    #[tokio::test]
+    async fn it_limits_subscriptions() {
+        use crate::limits::LimitError;
+
+        let mock = MockConnection::new();
+        let provider = Provider::new(mock.clone())
+            .with_limits(ProviderLimits::self_hosted().with_max_subscriptions(Some(1)));
+
+        mock.push_response("eth_subscribe", "0x1");
+        let filter = Filter::new().address(Address::repeat_byte(1));
+        let (id, mut rx) = provider
+            .subscribe(SubscriptionKind::Logs, Some(&filter))
+            .await
+            .unwrap();
+        assert_eq!(id, U256::from(1));
+        assert_eq!(provider.clone().active_subscriptions(), 1);
+        assert_eq!(
+            mock.requests()[0].params,
+            Some(json!([
+                "logs",
+                { "address": "0x0101010101010101010101010101010101010101" }
+            ]))
+        );
+        assert!(mock.notify(id.to_be_bytes(), 7));
+        assert_eq!(rx.try_recv().unwrap().get(), "7");
+
+        // rejected without a request
+        assert!(matches!(
+            provider.subscribe(SubscriptionKind::NewHeads, None).await,
+            Err(ProviderError::Limit(LimitError::TooManySubscriptions {
+                active: 1,
+                max: 1
+            }))
+        ));
+        assert_eq!(mock.requests().len(), 1);
+
+        mock.push_response("eth_unsubscribe", true);
+        assert!(provider.unsubscribe(id).await.unwrap());
+        assert_eq!(provider.active_subscriptions(), 0);
+        assert!(!mock.notify(id.to_be_bytes(), 8));
+
+        // failed subscriptions release their slot
+        mock.push_error("eth_subscribe", -32000, "no");
+        assert!(provider
+            .subscribe(SubscriptionKind::NewHeads, None)
+            .await
+            .is_err());
+        mock.push_response("eth_subscribe", "0x2");
+        provider
+            .subscribe(SubscriptionKind::NewHeads, None)
+            .await
+            .unwrap();
+        assert_eq!(
+            mock.requests().last().unwrap().params,
+            Some(json!(["newHeads"]))
+        );
+    }
+
+    #[tokio::test]
+    async fn it_accepts_boxed_connections() {
+        let provider: Provider<Box<dyn Connection>> = Provider::new(Box::new(echo()));
+        assert_eq!(provider.get_hashrate().await.unwrap(), U64::ZERO);
+
+        let provider = Provider::connect("http://127.0.0.1:8545").unwrap();
+        assert!(provider.is_local());
+        assert!(Provider::connect("ipc:///tmp/geth.ipc").is_err());
+    }
+
+    #[tokio::test]
+    async fn it_applies_the_default_timeout() {
+        let provider = Provider::new(echo()).with_timeout(Duration::from_secs(5));
+        assert_eq!(provider.default_timeout(), Some(Duration::from_secs(5)));
+        assert_eq!(provider.get_hashrate().await.unwrap(), U64::ZERO);
+
+        let provider = Provider::new(echo());
+        assert_eq!(provider.default_timeout(), None);
+    }
+
+    #[tokio::test]
+    async fn it_simulates_call_bundles() {
+        use ethers_rpc_types::{BlockOverrides, SimulatedBlockCalls};
+
+        let mock = MockConnection::new();
+        let provider = Provider::new(mock.clone());
+        let mut block = serde_json::to_value(Header::default()).unwrap();
+        block["calls"] = json!([
+            { "status": "0x1", "returnData": "0x01", "gasUsed": "0x5208", "logs": [] }
+        ]);
+        mock.push_response("eth_simulateV1", [block]);
+
+        let payload = SimulatePayload::default().block(
+            SimulatedBlockCalls::default()
+                .block_overrides(BlockOverrides::default().base_fee_per_gas(U256::ZERO))
+                .call(TransactionRequest::default().to(Address::repeat_byte(1))),
+        );
+        let blocks = provider.simulate(payload, None).await.unwrap();
+        assert_eq!(&blocks[0].calls[0].return_data[..], [1]);
+
+        let params = mock.requests()[0].params.clone().unwrap();
+        assert_eq!(
+            params[0]["blockStateCalls"][0]["blockOverrides"]["baseFeePerGas"],
+            "0x0"
+        );
+        assert_eq!(params[1], "latest");
+    }
+
+    #[tokio::test]
+    async fn it_sends_state_overrides() {
+        let provider = Provider::new(echo());
+        let tx = TransactionRequest::default().to(Address::repeat_byte(1));
+        let gas = provider.estimate_gas(tx.clone(), None).await.unwrap();
+        assert_eq!(gas, U256::from(2));
+
+        let overrides = StateOverride::from([(Address::repeat_byte(2), Default::default())]);
+        let gas = provider
+            .estimate_gas_with_overrides(tx, None, &overrides)
+            .await
+            .unwrap();
+        assert_eq!(gas, U256::from(3));
+    }
+
+    #[tokio::test]
+    async fn it_attaches_access_lists_that_save_gas() {
+        let provider = Provider::new(echo());
+        let tx = TransactionRequest::default().to(Address::repeat_byte(1));
+        let generated = provider.create_access_list(tx.clone(), None).await.unwrap();
+        assert_eq!(generated.gas_used, U256::from(1));
+
+        // the echo node estimates 2 gas without the access list
+        let tx = provider.attach_access_list(tx, None).await.unwrap();
+        assert_eq!(tx.access_list, Some(generated.access_list));
+    }
+
+    #[tokio::test]
+    async fn it_parses_personal_signatures() {
+        let provider = Provider::new(echo());
+        let sig = provider
+            .personal_sign("hello", Address::repeat_byte(1))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(sig.r, U256::from_be_bytes([0x11; 32]));
+        assert_eq!(sig.s, U256::from_be_bytes([0x11; 32]));
+        assert_eq!(sig.v, 28);
+    }
+
+    #[tokio::test]
+    async fn it_reports_unsupported_legacy_methods() {
+        let provider = Provider::new(echo());
+
+        assert_eq!(provider.get_hashrate().await.unwrap(), U64::ZERO);
+        assert!(matches!(
+            provider.is_mining().await,
+            Err(ProviderError::UnsupportedMethod("eth_mining"))
+        ));
+        assert!(matches!(
+            provider.get_coinbase().await,
+            Err(ProviderError::JsonRpc(_))
+        ));
+    }
+}
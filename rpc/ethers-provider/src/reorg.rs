@@ -0,0 +1,465 @@
+//! Reorg-aware streaming of blocks and logs
+
+use std::{
+    collections::{BTreeMap, VecDeque},
+    future,
+    pin::Pin,
+};
+
+use ethers_primitives::B256;
+use ethers_pub_use::futures_util::{stream, Stream};
+use ethers_rpc_types::{Filter, Header, Log, LogEvent};
+use ethers_transports::Connection;
+
+use crate::{logs::DEFAULT_REORG_DEPTH, LogTracker, Provider, ProviderError};
+
+/// An item of a block or log subscription, or of a polling stream
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ChainUpdate {
+    /// A new head
+    Block(Header),
+    /// A log, which may have the `removed` flag
+    Log(Log),
+}
+
+impl From<Header> for ChainUpdate {
+    fn from(header: Header) -> Self {
+        Self::Block(header)
+    }
+}
+
+impl From<Log> for ChainUpdate {
+    fn from(log: Log) -> Self {
+        Self::Log(log)
+    }
+}
+
+/// A change to the canonical chain, as seen by a [`ReorgAwareStream`]
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ReorgEvent {
+    /// The block was added to the canonical chain
+    Block(Header),
+    /// The block was removed from the canonical chain by a reorganization
+    Reverted(Header),
+    /// The log was added to the canonical chain
+    Log(Log),
+    /// The log was removed from the canonical chain by a reorganization
+    Removed(Log),
+}
+
+impl From<LogEvent> for ReorgEvent {
+    fn from(event: LogEvent) -> Self {
+        match event {
+            LogEvent::Added(log) => Self::Log(log),
+            LogEvent::Removed(log) => Self::Removed(log),
+        }
+    }
+}
+
+/// Tracks the ancestry of recent canonical blocks, turning new heads into
+/// [`ReorgEvent`]s.
+///
+/// A head replaces the blocks remembered at and above its height. If the
+/// block remembered at the parent height is not the head's parent, or no
+/// block is remembered there, it is replaced too; process the head's
+/// ancestors first, oldest first, to find where the chains fork. See
+/// [`BlockTracker::missing_parent`]
+///
+/// Blocks are remembered for `depth` blocks below the most recent head.
+/// Headers without a hash and number, which are pending, are passed
+/// through
+#[derive(Debug, Clone)]
+pub struct BlockTracker {
+    depth: u64,
+    blocks: BTreeMap<u64, Header>,
+}
+
+impl Default for BlockTracker {
+    fn default() -> Self {
+        Self::new(DEFAULT_REORG_DEPTH)
+    }
+}
+
+impl BlockTracker {
+    /// Instantiate a tracker that remembers `depth` blocks
+    pub fn new(depth: u64) -> Self {
+        Self {
+            depth,
+            blocks: Default::default(),
+        }
+    }
+
+    /// The canonical block at a height, if remembered
+    pub fn get(&self, number: u64) -> Option<&Header> {
+        self.blocks.get(&number)
+    }
+
+    /// The parent hash of `header`, if a different block is remembered at
+    /// the parent height, or if no block is remembered there but the parent
+    /// height is within or above the remembered blocks, as when heads skip a
+    /// height. The parent must be processed before the header to find where
+    /// the chains fork
+    pub fn missing_parent(&self, header: &Header) -> Option<B256> {
+        let number = header.number?.to::<u64>().checked_sub(1)?;
+        let oldest = *self.blocks.keys().next()?;
+        let missing = match self.blocks.get(&number) {
+            Some(parent) => parent.hash != Some(header.parent_hash),
+            None => number > oldest,
+        };
+        missing.then_some(header.parent_hash)
+    }
+
+    /// Process a new head. Returns the blocks it replaces, newest first,
+    /// then the head itself. Heads that were already processed are ignored
+    pub fn on_block(&mut self, header: Header) -> Vec<ReorgEvent> {
+        let (Some(hash), Some(number)) = (header.hash, header.number) else {
+            return vec![ReorgEvent::Block(header)];
+        };
+        let number = number.to::<u64>();
+        if self
+            .get(number)
+            .is_some_and(|known| known.hash == Some(hash))
+        {
+            return vec![];
+        }
+
+        let from = match self.missing_parent(&header) {
+            Some(_) => number - 1,
+            None => number,
+        };
+        let mut events: Vec<_> = self
+            .blocks
+            .split_off(&from)
+            .into_values()
+            .rev()
+            .map(ReorgEvent::Reverted)
+            .collect();
+
+        self.blocks.insert(number, header.clone());
+        self.blocks = self.blocks.split_off(&number.saturating_sub(self.depth));
+        events.push(ReorgEvent::Block(header));
+        events
+    }
+
+    /// Forget all blocks
+    pub fn clear(&mut self) {
+        self.blocks.clear();
+    }
+}
+
+/// Wraps a stream of new heads and logs, such as block and log
+/// subscriptions merged into one stream of [`ChainUpdate`]s, and reports
+/// reorganizations.
+///
+/// When a head does not extend the remembered chain, its ancestors are
+/// fetched until they connect to it, or for up to the reorg depth. The
+/// replaced blocks are then reported as [`ReorgEvent::Reverted`], newest
+/// first, after the logs delivered in them, which are reported as
+/// [`ReorgEvent::Removed`]. The new blocks follow, oldest first.
+///
+/// Logs are deduplicated and reorgs they reveal are handled as by a
+/// [`LogTracker`]. With [`ReorgAwareStream::refetch_logs`], the logs of
+/// the new canonical blocks are fetched after a reorg, for streams without
+/// a log subscription to re-deliver them
+#[derive(Debug)]
+pub struct ReorgAwareStream<'a, T, S> {
+    provider: &'a Provider<T>,
+    updates: S,
+    blocks: BlockTracker,
+    logs: LogTracker,
+    filter: Option<Filter>,
+    queue: VecDeque<ReorgEvent>,
+}
+
+impl<'a, T, S> ReorgAwareStream<'a, T, S> {
+    /// Wrap a stream of updates. Ancestors of new heads are fetched with
+    /// `provider`
+    pub fn new(provider: &'a Provider<T>, updates: S) -> Self {
+        Self {
+            provider,
+            updates,
+            blocks: Default::default(),
+            logs: Default::default(),
+            filter: None,
+            queue: Default::default(),
+        }
+    }
+
+    /// Set how many blocks below the head are tracked. Reorgs deeper than
+    /// this are not fully reported. Defaults to [`DEFAULT_REORG_DEPTH`]
+    #[must_use = "Builder method outputs must be used"]
+    pub fn with_depth(mut self, depth: u64) -> Self {
+        self.blocks = BlockTracker::new(depth);
+        self.logs = LogTracker::new(depth);
+        self
+    }
+
+    /// After a reorg, fetch the logs matching `filter` in each new
+    /// canonical block. The filter's block range is ignored
+    #[must_use = "Builder method outputs must be used"]
+    pub fn refetch_logs(mut self, filter: Filter) -> Self {
+        self.filter = Some(filter);
+        self
+    }
+
+    /// The block tracker
+    pub fn blocks(&self) -> &BlockTracker {
+        &self.blocks
+    }
+
+    /// The log tracker
+    pub fn logs(&self) -> &LogTracker {
+        &self.logs
+    }
+}
+
+impl<'a, T, S> ReorgAwareStream<'a, T, S>
+where
+    T: Connection + Unpin,
+    S: Stream + Unpin,
+    S::Item: Into<ChainUpdate>,
+{
+    /// The next event. `None` once the wrapped stream ends and all events
+    /// have been delivered. If fetching ancestors or logs fails, the error
+    /// is returned and the update that required them is dropped
+    pub async fn next(&mut self) -> Option<Result<ReorgEvent, ProviderError>> {
+        loop {
+            if let Some(event) = self.queue.pop_front() {
+                return Some(Ok(event));
+            }
+            let update = future::poll_fn(|cx| Pin::new(&mut self.updates).poll_next(cx)).await?;
+            if let Err(err) = self.process(update.into()).await {
+                return Some(Err(err));
+            }
+        }
+    }
+
+    /// Convert into a [`Stream`] of events
+    pub fn into_stream(self) -> impl Stream<Item = Result<ReorgEvent, ProviderError>> + 'a
+    where
+        S: 'a,
+    {
+        stream::unfold(self, |mut this| async move {
+            let event = this.next().await?;
+            Some((event, this))
+        })
+    }
+
+    async fn process(&mut self, update: ChainUpdate) -> Result<(), ProviderError> {
+        let header = match update {
+            ChainUpdate::Log(log) => {
+                let events = self.logs.on_log(log);
+                self.queue.extend(events.into_iter().map(Into::into));
+                return Ok(());
+            }
+            ChainUpdate::Block(header) => header,
+        };
+
+        // the head, then its ancestors back to the remembered chain
+        let mut chain = vec![header];
+        while let Some(parent) = chain.last().and_then(|h| self.blocks.missing_parent(h)) {
+            if chain.len() as u64 > self.blocks.depth {
+                break;
+            }
+            let header: Option<Header> = self
+                .provider
+                .request("eth_getBlockByHash", (parent, false))
+                .await??;
+            let Some(header) = header else {
+                break;
+            };
+            chain.push(header);
+        }
+
+        let mut reorged = false;
+        let mut added = vec![];
+        for header in chain.into_iter().rev() {
+            let events = self.blocks.on_block(header);
+            let reverted_from = events
+                .iter()
+                .filter_map(|event| match event {
+                    ReorgEvent::Reverted(header) => header.number,
+                    _ => None,
+                })
+                .min();
+            if let Some(from) = reverted_from {
+                reorged = true;
+                let removed = self.logs.roll_back(from.to());
+                self.queue.extend(removed.into_iter().map(Into::into));
+            }
+            for event in events {
+                if let ReorgEvent::Block(header) = &event {
+                    if let (Some(number), Some(hash)) = (header.number, header.hash) {
+                        let removed = self.logs.on_block(number.to(), hash);
+                        self.queue.extend(removed.into_iter().map(Into::into));
+                        added.push(hash);
+                    }
+                }
+                self.queue.push_back(event);
+            }
+        }
+
+        let Some(filter) = self.filter.as_ref().filter(|_| reorged) else {
+            return Ok(());
+        };
+        for hash in added {
+            let logs = self
+                .provider
+                .get_logs(&filter.clone().at_block_hash(hash))
+                .await??;
+            for log in logs {
+                let events = self.logs.on_log(log);
+                self.queue.extend(events.into_iter().map(Into::into));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ethers_primitives::{Address, U256, U64};
+    use ethers_pub_use::{futures_util::StreamExt, serde_json};
+    use ethers_transports::transports::MockConnection;
+
+    use super::*;
+
+    fn header(number: u64, hash: u8, parent: u8) -> Header {
+        Header {
+            hash: Some(B256::repeat_byte(hash)),
+            parent_hash: B256::repeat_byte(parent),
+            number: Some(U64::from(number)),
+            ..Default::default()
+        }
+    }
+
+    fn log(number: u64, hash: u8) -> Log {
+        Log {
+            address: Address::repeat_byte(0x11),
+            block_hash: Some(B256::repeat_byte(hash)),
+            block_number: Some(U64::from(number)),
+            log_index: Some(U256::ZERO),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn it_tracks_ancestry() {
+        let mut tracker = BlockTracker::default();
+        assert_eq!(
+            tracker.on_block(header(1, 0xa, 0)),
+            vec![ReorgEvent::Block(header(1, 0xa, 0))]
+        );
+        assert!(tracker.on_block(header(1, 0xa, 0)).is_empty());
+        tracker.on_block(header(2, 0xb, 0xa));
+
+        // a sibling of block 2
+        assert_eq!(
+            tracker.missing_parent(&header(3, 0xd, 0xc)),
+            Some(B256::repeat_byte(0xc))
+        );
+        assert_eq!(tracker.missing_parent(&header(2, 0xc, 0xa)), None);
+        // a head that skips a height
+        assert_eq!(
+            tracker.missing_parent(&header(4, 0xe, 0xd)),
+            Some(B256::repeat_byte(0xd))
+        );
+        // older than the remembered blocks
+        assert_eq!(tracker.missing_parent(&header(1, 0xf, 0xe)), None);
+        assert_eq!(
+            tracker.on_block(header(2, 0xc, 0xa)),
+            vec![
+                ReorgEvent::Reverted(header(2, 0xb, 0xa)),
+                ReorgEvent::Block(header(2, 0xc, 0xa)),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn it_reports_reorgs() {
+        let mock = MockConnection::new();
+        let provider = Provider::new(mock.clone());
+        // the ancestor of the new head, fetched by hash
+        mock.push_response("eth_getBlockByHash", header(2, 0xc, 0xa));
+        mock.push_response("eth_getLogs", vec![log(2, 0xc)]);
+        mock.push_response("eth_getLogs", Vec::<Log>::new());
+
+        let updates = stream::iter(vec![
+            ChainUpdate::from(header(1, 0xa, 0)),
+            header(2, 0xb, 0xa).into(),
+            log(2, 0xb).into(),
+            header(3, 0xd, 0xc).into(),
+        ]);
+        let events: Vec<_> = ReorgAwareStream::new(&provider, updates)
+            .refetch_logs(Filter::new())
+            .into_stream()
+            .map(Result::unwrap)
+            .collect()
+            .await;
+
+        assert_eq!(
+            events,
+            vec![
+                ReorgEvent::Block(header(1, 0xa, 0)),
+                ReorgEvent::Block(header(2, 0xb, 0xa)),
+                ReorgEvent::Log(log(2, 0xb)),
+                ReorgEvent::Removed(Log {
+                    removed: true,
+                    ..log(2, 0xb)
+                }),
+                ReorgEvent::Reverted(header(2, 0xb, 0xa)),
+                ReorgEvent::Block(header(2, 0xc, 0xa)),
+                ReorgEvent::Block(header(3, 0xd, 0xc)),
+                ReorgEvent::Log(log(2, 0xc)),
+            ]
+        );
+        assert_eq!(mock.pending_responses(), 0);
+    }
+
+    #[tokio::test]
+    async fn it_reports_reorgs_behind_skipped_heights() {
+        let mock = MockConnection::new();
+        let provider = Provider::new(mock.clone());
+        // the skipped height, then the fork point
+        mock.push_response("eth_getBlockByHash", header(4, 0xe, 0xd));
+        mock.push_response("eth_getBlockByHash", header(3, 0xd, 0xb));
+
+        let updates = stream::iter(vec![
+            ChainUpdate::from(header(1, 0xa, 0)),
+            header(2, 0xb, 0xa).into(),
+            header(3, 0xc, 0xb).into(),
+            log(3, 0xc).into(),
+            header(5, 0xf, 0xe).into(),
+        ]);
+        let events: Vec<_> = ReorgAwareStream::new(&provider, updates)
+            .into_stream()
+            .map(Result::unwrap)
+            .collect()
+            .await;
+
+        assert_eq!(
+            events[4..],
+            [
+                ReorgEvent::Removed(Log {
+                    removed: true,
+                    ..log(3, 0xc)
+                }),
+                ReorgEvent::Reverted(header(3, 0xc, 0xb)),
+                ReorgEvent::Block(header(3, 0xd, 0xb)),
+                ReorgEvent::Block(header(4, 0xe, 0xd)),
+                ReorgEvent::Block(header(5, 0xf, 0xe)),
+            ]
+        );
+        let fetched: Vec<_> = mock
+            .requests()
+            .into_iter()
+            .map(|req| req.params.unwrap()[0].clone())
+            .collect();
+        assert_eq!(
+            fetched,
+            [0xe, 0xd].map(|hash| serde_json::to_value(B256::repeat_byte(hash)).unwrap())
+        );
+    }
+}
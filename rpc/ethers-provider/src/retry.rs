@@ -0,0 +1,321 @@
+//! Retrying requests that fail transiently
+
+use std::{borrow::Cow, fmt, time::Duration};
+
+use ethers_pub_use::{futures_channel::mpsc, serde_json::value::RawValue};
+use ethers_transports::{
+    common::{BatchRpcFuture, ErrorObject, Request, RpcFuture, RpcOutcome},
+    time, Connection, PubSubConnection, TransportError,
+};
+
+/// The default number of retries after the first attempt
+pub const DEFAULT_MAX_RETRIES: u32 = 3;
+/// The default wait before the first retry. It doubles at each retry
+pub const DEFAULT_INITIAL_BACKOFF: Duration = Duration::from_millis(250);
+/// The default longest wait between retries
+pub const DEFAULT_MAX_BACKOFF: Duration = Duration::from_secs(8);
+
+/// HTTP status code some providers use as a JSON-RPC error code when rate
+/// limiting
+const TOO_MANY_REQUESTS: i32 = 429;
+
+/// Which failures are retried, how often, and how long to wait in between
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    max_retries: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: DEFAULT_MAX_RETRIES,
+            initial_backoff: DEFAULT_INITIAL_BACKOFF,
+            max_backoff: DEFAULT_MAX_BACKOFF,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Instantiate a policy retrying up to `max_retries` times, with the
+    /// default backoff
+    pub fn new(max_retries: u32) -> Self {
+        Self {
+            max_retries,
+            ..Default::default()
+        }
+    }
+
+    /// Set the wait before the first retry, and the longest wait
+    #[must_use = "Builder method outputs must be used"]
+    pub fn with_backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max.max(initial);
+        self
+    }
+
+    /// The number of retries after the first attempt
+    pub fn max_retries(&self) -> u32 {
+        self.max_retries
+    }
+
+    /// The wait before retry number `retry`, counted from 0
+    pub fn backoff(&self, retry: u32) -> Duration {
+        self.initial_backoff
+            .saturating_mul(2u32.saturating_pow(retry))
+            .min(self.max_backoff)
+    }
+
+    /// True if a request that failed with `err` may succeed if sent again.
+    /// HTTP failures and timeouts are retried. Serialization errors are
+    /// not, as the same request would fail the same way
+    pub fn should_retry_transport(&self, err: &TransportError) -> bool {
+        matches!(err, TransportError::Reqwest(_) | TransportError::Timeout(_))
+    }
+
+    /// True if the node's error response means it is rate limiting, rather
+    /// than rejecting the request
+    pub fn should_retry_response(&self, err: &ErrorObject<'_>) -> bool {
+        let message = err.message().to_lowercase();
+        err.code() == TOO_MANY_REQUESTS
+            || message.contains("rate limit")
+            || message.contains("too many requests")
+    }
+
+    fn should_retry(&self, outcome: &RpcOutcome) -> bool {
+        match outcome {
+            Ok(Ok(_)) => false,
+            Ok(Err(err)) => self.should_retry_response(err),
+            Err(err) => self.should_retry_transport(err),
+        }
+    }
+}
+
+/// A [`Connection`] wrapper that resends requests that fail transiently,
+/// with exponential backoff. See [`RetryPolicy`].
+///
+/// Batches are resent whole, and only on transport failures: the requests
+/// of a batch may have been executed even if some responses are errors.
+/// The wrapped connection is cloned into each request, so it should be
+/// cheap to clone
+#[derive(Clone)]
+pub struct Retry<T> {
+    inner: T,
+    policy: RetryPolicy,
+}
+
+impl<T> Retry<T> {
+    /// Wrap a connection
+    pub fn new(inner: T, policy: RetryPolicy) -> Self {
+        Self { inner, policy }
+    }
+
+    /// The retry policy
+    pub fn policy(&self) -> &RetryPolicy {
+        &self.policy
+    }
+
+    /// The wrapped connection
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+}
+
+impl<T> fmt::Debug for Retry<T>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Retry")
+            .field("inner", &self.inner)
+            .field("policy", &self.policy)
+            .finish()
+    }
+}
+
+/// An owned copy of a request, to resend it after the original is dropped
+fn to_owned(req: &Request<'_>) -> Request<'static> {
+    Request::owned(
+        req.id.clone().into_owned(),
+        req.method.to_string(),
+        req.params
+            .as_ref()
+            .map(|params| params.clone().into_owned()),
+    )
+}
+
+impl<T> Connection for Retry<T>
+where
+    T: Connection + Clone + 'static,
+{
+    fn is_local(&self) -> bool {
+        self.inner.is_local()
+    }
+
+    fn increment_id(&self) -> u64 {
+        self.inner.increment_id()
+    }
+
+    fn default_timeout(&self) -> Option<Duration> {
+        self.inner.default_timeout()
+    }
+
+    fn json_rpc_request(&self, req: &Request<'_>) -> RpcFuture {
+        let inner = self.inner.clone();
+        let policy = self.policy;
+        let req = to_owned(req);
+        Box::pin(async move {
+            let mut retry = 0;
+            loop {
+                let outcome = inner.json_rpc_request(&req).await;
+                if retry >= policy.max_retries || !policy.should_retry(&outcome) {
+                    return outcome;
+                }
+                time::sleep(policy.backoff(retry)).await;
+                retry += 1;
+            }
+        })
+    }
+
+    fn batch_request(&self, reqs: &[Request<'_>]) -> BatchRpcFuture {
+        let inner = self.inner.clone();
+        let policy = self.policy;
+        let reqs: Vec<_> = reqs.iter().map(to_owned).collect();
+        Box::pin(async move {
+            let mut retry = 0;
+            loop {
+                let outcome = inner.batch_request(&reqs).await;
+                let transient = matches!(&outcome, Err(err) if policy.should_retry_transport(err));
+                if retry >= policy.max_retries || !transient {
+                    return outcome;
+                }
+                time::sleep(policy.backoff(retry)).await;
+                retry += 1;
+            }
+        })
+    }
+}
+
+impl<T> PubSubConnection for Retry<T>
+where
+    T: PubSubConnection + Clone + 'static,
+{
+    fn uninstall_listener(&self, id: [u8; 32]) -> Result<(), TransportError> {
+        self.inner.uninstall_listener(id)
+    }
+
+    fn install_listener(
+        &self,
+        id: [u8; 32],
+    ) -> Result<mpsc::UnboundedReceiver<Cow<'static, RawValue>>, TransportError> {
+        self.inner.install_listener(id)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use ethers_transports::transports::MockConnection;
+
+    use super::*;
+
+    /// Fails the first `failures` requests with `err`, then answers `0x1`
+    fn failing(failures: usize, err: fn() -> RpcOutcome) -> MockConnection {
+        let count = Arc::new(AtomicUsize::new(0));
+        MockConnection::new().with_handler(move |_| {
+            if count.fetch_add(1, Ordering::SeqCst) < failures {
+                return err();
+            }
+            Ok(Ok(Cow::Owned(
+                RawValue::from_string("\"0x1\"".into()).unwrap(),
+            )))
+        })
+    }
+
+    fn timeout() -> RpcOutcome {
+        Err(TransportError::Timeout(Duration::from_secs(1)))
+    }
+
+    fn policy(retries: u32) -> RetryPolicy {
+        RetryPolicy::new(retries).with_backoff(Duration::ZERO, Duration::ZERO)
+    }
+
+    #[test]
+    fn it_backs_off_exponentially() {
+        let policy = RetryPolicy::default();
+        assert_eq!(policy.backoff(0), Duration::from_millis(250));
+        assert_eq!(policy.backoff(2), Duration::from_secs(1));
+        assert_eq!(policy.backoff(10), DEFAULT_MAX_BACKOFF);
+        assert_eq!(policy.backoff(u32::MAX), DEFAULT_MAX_BACKOFF);
+    }
+
+    #[tokio::test]
+    async fn it_retries_transient_failures() {
+        let mock = failing(2, timeout);
+        let retry = Retry::new(mock.clone(), policy(2));
+        let chain_id: String = retry.request("eth_chainId", ()).await.unwrap().unwrap();
+        assert_eq!(chain_id, "0x1");
+        assert_eq!(mock.requests().len(), 3);
+
+        let mock = failing(3, timeout);
+        let retry = Retry::new(mock.clone(), policy(2));
+        assert!(matches!(
+            retry.request::<_, String>("eth_chainId", ()).await,
+            Err(TransportError::Timeout(_))
+        ));
+        assert_eq!(mock.requests().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn it_retries_rate_limits_only() {
+        let mock = failing(1, || {
+            Ok(Err(ErrorObject::owned(
+                TOO_MANY_REQUESTS,
+                "Too Many Requests",
+                None::<()>,
+            )))
+        });
+        let retry = Retry::new(mock.clone(), policy(1));
+        assert!(retry
+            .request::<_, String>("eth_chainId", ())
+            .await
+            .unwrap()
+            .is_ok());
+        assert_eq!(mock.requests().len(), 2);
+
+        let mock = failing(1, || {
+            Ok(Err(ErrorObject::owned(
+                -32000,
+                "execution reverted",
+                None::<()>,
+            )))
+        });
+        let retry = Retry::new(mock.clone(), policy(3));
+        let err = retry
+            .request::<_, String>("eth_call", ())
+            .await
+            .unwrap()
+            .unwrap_err();
+        assert_eq!(err.message(), "execution reverted");
+        assert_eq!(mock.requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn it_resends_the_same_request() {
+        let mock = failing(1, timeout);
+        let retry = Retry::new(mock.clone(), policy(1));
+        let _: String = retry
+            .request("eth_getBalance", ("0x01", "latest"))
+            .await
+            .unwrap()
+            .unwrap();
+        let reqs = mock.requests();
+        assert_eq!(reqs.len(), 2);
+        assert_eq!(reqs[0], reqs[1]);
+    }
+}
@@ -0,0 +1,512 @@
+//! Lookup of function and error signatures by selector, for selectors an
+//! [`AbiRegistry`] does not know.
+//!
+//! A [`SignatureDb`] caches the answers of a [`SignatureSource`], such as
+//! the [openchain] signature database with the `openchain` feature, and
+//! registers them in a registry. Selectors are not unique, so a lookup may
+//! find several signatures, or a signature unrelated to the called
+//! contract.
+//!
+//! [openchain]: https://openchain.xyz/signatures
+
+use std::{
+    collections::BTreeMap,
+    fmt::Debug,
+    fs,
+    future::Future,
+    path::PathBuf,
+    pin::Pin,
+    sync::{Mutex, MutexGuard},
+};
+
+use ethers_abi_dyn::AbiRegistry;
+use ethers_abi_enc::{ERROR_SELECTOR, PANIC_SELECTOR};
+use ethers_pub_use::{hex, serde_json, thiserror};
+use ethers_rpc_types::trace::geth::CallFrame;
+
+/// The future returned by [`SignatureSource::lookup`]
+#[cfg(not(target_arch = "wasm32"))]
+pub type LookupFuture<'a> = Pin<
+    Box<dyn Future<Output = Result<BTreeMap<[u8; 4], Vec<String>>, SignatureDbError>> + Send + 'a>,
+>;
+/// The future returned by [`SignatureSource::lookup`]
+#[cfg(target_arch = "wasm32")]
+pub type LookupFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<BTreeMap<[u8; 4], Vec<String>>, SignatureDbError>> + 'a>>;
+
+/// Errors looking up signatures
+#[derive(Debug, thiserror::Error)]
+pub enum SignatureDbError {
+    /// The source could not be queried, or its response not understood
+    #[error("signature lookup failed: {0}")]
+    Lookup(String),
+    /// The cache file could not be read or written
+    #[error("signature cache: {0}")]
+    Io(#[from] std::io::Error),
+    /// The cache file is not valid
+    #[error("signature cache: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// A database of signatures, keyed by selector
+pub trait SignatureSource: Debug + Send + Sync {
+    /// Look up the signatures of selectors. Selectors without a known
+    /// signature may be omitted
+    fn lookup<'a>(&'a self, selectors: &'a [[u8; 4]]) -> LookupFuture<'a>;
+}
+
+/// A caching client of a [`SignatureSource`]. Selectors without a known
+/// signature are cached too, so they are looked up once
+#[derive(Debug)]
+pub struct SignatureDb<S> {
+    source: S,
+    cache: Mutex<BTreeMap<[u8; 4], Vec<String>>>,
+    cache_file: Option<PathBuf>,
+}
+
+impl<S> SignatureDb<S>
+where
+    S: SignatureSource,
+{
+    /// Instantiate a client with an empty cache
+    pub fn new(source: S) -> Self {
+        Self {
+            source,
+            cache: Default::default(),
+            cache_file: None,
+        }
+    }
+
+    /// Persist the cache in a JSON file, loading it first if it exists.
+    /// The file is rewritten after each lookup that reaches the source
+    pub fn with_cache_file(mut self, path: impl Into<PathBuf>) -> Result<Self, SignatureDbError> {
+        let path = path.into();
+        if path.exists() {
+            let entries: BTreeMap<String, Vec<String>> = serde_json::from_slice(&fs::read(&path)?)?;
+            let mut cache = self.lock();
+            for (selector, signatures) in entries {
+                let selector = hex::decode(selector.trim_start_matches("0x"))
+                    .ok()
+                    .and_then(|selector| <[u8; 4]>::try_from(selector).ok())
+                    .ok_or_else(|| {
+                        std::io::Error::new(std::io::ErrorKind::InvalidData, "invalid selector")
+                    })?;
+                cache.insert(selector, signatures);
+            }
+        }
+        self.cache_file = Some(path);
+        Ok(self)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, BTreeMap<[u8; 4], Vec<String>>> {
+        self.cache.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn save(&self) -> Result<(), SignatureDbError> {
+        let Some(path) = &self.cache_file else {
+            return Ok(());
+        };
+        let entries: BTreeMap<_, _> = self
+            .lock()
+            .iter()
+            .map(|(selector, signatures)| {
+                (format!("0x{}", hex::encode(selector)), signatures.clone())
+            })
+            .collect();
+        fs::write(path, serde_json::to_vec_pretty(&entries)?)?;
+        Ok(())
+    }
+
+    /// The cached signatures of a selector. `None` if it was not looked up
+    pub fn cached(&self, selector: [u8; 4]) -> Option<Vec<String>> {
+        self.lock().get(&selector).cloned()
+    }
+
+    /// Look up the signatures of selectors, querying the source for those
+    /// not cached. Selectors without a known signature are omitted
+    pub async fn lookup(
+        &self,
+        selectors: &[[u8; 4]],
+    ) -> Result<BTreeMap<[u8; 4], Vec<String>>, SignatureDbError> {
+        let mut missing: Vec<_> = {
+            let cache = self.lock();
+            selectors
+                .iter()
+                .filter(|selector| !cache.contains_key(*selector))
+                .copied()
+                .collect()
+        };
+        missing.sort_unstable();
+        missing.dedup();
+
+        if !missing.is_empty() {
+            let mut found = self.source.lookup(&missing).await?;
+            {
+                let mut cache = self.lock();
+                for selector in missing {
+                    cache.insert(selector, found.remove(&selector).unwrap_or_default());
+                }
+            }
+            self.save()?;
+        }
+
+        let cache = self.lock();
+        Ok(selectors
+            .iter()
+            .filter_map(|selector| {
+                let signatures = cache.get(selector).filter(|s| !s.is_empty())?;
+                Some((*selector, signatures.clone()))
+            })
+            .collect())
+    }
+
+    /// Look up the selectors of the calls and custom errors of a trace that
+    /// the registry does not know, and register their signatures. Returns
+    /// the number of signatures registered
+    pub async fn resolve_trace(
+        &self,
+        registry: &mut AbiRegistry,
+        trace: &CallFrame,
+    ) -> Result<usize, SignatureDbError> {
+        let mut functions = vec![];
+        let mut errors = vec![];
+        let mut stack = vec![trace];
+        while let Some(frame) = stack.pop() {
+            stack.extend(&frame.calls);
+            if let Some((selector, _)) = frame.input.split_first_chunk::<4>() {
+                if !frame.typ.starts_with("CREATE") && registry.function(*selector).is_none() {
+                    functions.push(*selector);
+                }
+            }
+            let revert = frame.error.as_ref().and(frame.output.as_ref());
+            if let Some((selector, _)) = revert.and_then(|output| output.split_first_chunk::<4>()) {
+                let standard = [ERROR_SELECTOR, PANIC_SELECTOR].contains(selector);
+                if !standard && registry.error(*selector).is_none() {
+                    errors.push(*selector);
+                }
+            }
+        }
+
+        let selectors = [&functions[..], &errors[..]].concat();
+        let found = self.lookup(&selectors).await?;
+        let mut registered = 0;
+        for (selector, signatures) in found {
+            for signature in &signatures {
+                if functions.contains(&selector) && registry.register_function(signature).is_ok() {
+                    registered += 1;
+                }
+                if errors.contains(&selector) && registry.register_error(signature).is_ok() {
+                    registered += 1;
+                }
+            }
+        }
+        Ok(registered)
+    }
+}
+
+#[cfg(feature = "openchain")]
+pub use openchain::OpenChain;
+
+#[cfg(feature = "openchain")]
+mod openchain {
+    use std::collections::BTreeMap;
+
+    use ethers_pub_use::{hex, serde::Deserialize};
+
+    use super::{LookupFuture, SignatureDbError, SignatureSource};
+
+    /// The default openchain lookup endpoint
+    const DEFAULT_URL: &str = "https://api.openchain.xyz/signature-database/v1/lookup";
+
+    #[derive(Deserialize)]
+    #[serde(crate = "ethers_pub_use::serde")]
+    struct Response {
+        ok: bool,
+        #[serde(default)]
+        error: Option<String>,
+        #[serde(default)]
+        result: Option<Results>,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(crate = "ethers_pub_use::serde")]
+    struct Results {
+        #[serde(default)]
+        function: Option<BTreeMap<String, Option<Vec<Entry>>>>,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(crate = "ethers_pub_use::serde")]
+    struct Entry {
+        name: String,
+    }
+
+    /// The [openchain] signature database, which also serves the 4byte
+    /// directory's signatures. Signatures flagged as spam are filtered out
+    ///
+    /// [openchain]: https://openchain.xyz/signatures
+    #[derive(Debug, Clone)]
+    pub struct OpenChain {
+        client: reqwest::Client,
+        url: String,
+    }
+
+    impl Default for OpenChain {
+        fn default() -> Self {
+            Self::new(DEFAULT_URL)
+        }
+    }
+
+    impl OpenChain {
+        /// Instantiate a client of a lookup endpoint
+        pub fn new(url: impl Into<String>) -> Self {
+            Self {
+                client: Default::default(),
+                url: url.into(),
+            }
+        }
+    }
+
+    impl SignatureSource for OpenChain {
+        fn lookup<'a>(&'a self, selectors: &'a [[u8; 4]]) -> LookupFuture<'a> {
+            Box::pin(async move {
+                let query: Vec<_> = selectors
+                    .iter()
+                    .map(|selector| format!("0x{}", hex::encode(selector)))
+                    .collect();
+                let response: Response = self
+                    .client
+                    .get(&self.url)
+                    .query(&[("function", query.join(",")), ("filter", "true".to_owned())])
+                    .send()
+                    .await
+                    .and_then(|response| response.error_for_status())
+                    .map_err(|e| SignatureDbError::Lookup(e.to_string()))?
+                    .json()
+                    .await
+                    .map_err(|e| SignatureDbError::Lookup(e.to_string()))?;
+                parse(response)
+            })
+        }
+    }
+
+    /// The signatures in a lookup response, keyed by selector
+    fn parse(response: Response) -> Result<BTreeMap<[u8; 4], Vec<String>>, SignatureDbError> {
+        let results = match (response.ok, response.result) {
+            (true, Some(results)) => results,
+            _ => {
+                let error = response.error.unwrap_or_else(|| "not ok".to_owned());
+                return Err(SignatureDbError::Lookup(error));
+            }
+        };
+
+        let mut found = BTreeMap::new();
+        for (selector, entries) in results.function.unwrap_or_default() {
+            let selector = hex::decode(selector.trim_start_matches("0x"))
+                .ok()
+                .and_then(|selector| <[u8; 4]>::try_from(selector).ok());
+            if let (Some(selector), Some(entries)) = (selector, entries) {
+                found.insert(
+                    selector,
+                    entries.into_iter().map(|entry| entry.name).collect(),
+                );
+            }
+        }
+        Ok(found)
+    }
+
+    #[cfg(test)]
+    mod test {
+        use ethers_abi_dyn::AbiRegistry;
+        use ethers_abi_enc::selector;
+        use ethers_pub_use::serde_json;
+
+        use super::*;
+        use crate::SignatureDb;
+
+        /// Serves a canned lookup response
+        #[derive(Debug)]
+        struct Canned(&'static str);
+
+        impl SignatureSource for Canned {
+            fn lookup<'a>(&'a self, _selectors: &'a [[u8; 4]]) -> LookupFuture<'a> {
+                Box::pin(async move { parse(serde_json::from_str(self.0)?) })
+            }
+        }
+
+        const RESPONSE: &str = r#"{
+            "ok": true,
+            "result": {
+                "event": {},
+                "function": {
+                    "0xa9059cbb": [
+                        { "name": "transfer(address,uint256)", "filtered": false }
+                    ],
+                    "0x095ea7b3": [
+                        { "name": "approve(address,uint256)", "filtered": false },
+                        { "name": "sign_szabo_bytecode(bytes16,uint128)", "filtered": false }
+                    ],
+                    "0xdeadbeef": null,
+                    "0x12345678": []
+                }
+            }
+        }"#;
+
+        #[tokio::test]
+        async fn it_parses_lookups() {
+            let db = SignatureDb::new(Canned(RESPONSE));
+            let transfer = selector("transfer(address,uint256)");
+            let approve = selector("approve(address,uint256)");
+            let selectors = [
+                transfer,
+                approve,
+                [0xde, 0xad, 0xbe, 0xef],
+                [0x12, 0x34, 0x56, 0x78],
+            ];
+
+            let found = db.lookup(&selectors).await.unwrap();
+            assert_eq!(found.len(), 2);
+            assert_eq!(found[&transfer], ["transfer(address,uint256)"]);
+            assert_eq!(found[&approve].len(), 2);
+            assert_eq!(db.cached([0xde, 0xad, 0xbe, 0xef]), Some(vec![]));
+
+            let mut registry = AbiRegistry::new();
+            for signature in found.values().flatten() {
+                registry.register_function(signature).unwrap();
+            }
+            assert!(registry.function(transfer).is_some());
+            assert!(registry.function(approve).is_some());
+        }
+
+        #[tokio::test]
+        async fn it_handles_empty_responses() {
+            let db = SignatureDb::new(Canned(r#"{"ok": true, "result": {"function": {}}}"#));
+            assert!(db
+                .lookup(&[[0xde, 0xad, 0xbe, 0xef]])
+                .await
+                .unwrap()
+                .is_empty());
+
+            let db = SignatureDb::new(Canned(r#"{"ok": true, "result": {"function": null}}"#));
+            assert!(db
+                .lookup(&[[0xde, 0xad, 0xbe, 0xef]])
+                .await
+                .unwrap()
+                .is_empty());
+            assert_eq!(db.cached([0xde, 0xad, 0xbe, 0xef]), Some(vec![]));
+
+            let db = SignatureDb::new(Canned(
+                r#"{"ok": false, "error": "invalid selector", "result": null}"#,
+            ));
+            assert!(matches!(
+                db.lookup(&[[0xde, 0xad, 0xbe, 0xef]]).await,
+                Err(SignatureDbError::Lookup(error)) if error == "invalid selector"
+            ));
+            // failed lookups are not cached
+            assert_eq!(db.cached([0xde, 0xad, 0xbe, 0xef]), None);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use ethers_abi_enc::selector;
+    use ethers_primitives::{Address, U256};
+
+    use super::*;
+
+    #[derive(Debug, Default)]
+    struct Static {
+        queries: AtomicUsize,
+    }
+
+    impl SignatureSource for Static {
+        fn lookup<'a>(&'a self, selectors: &'a [[u8; 4]]) -> LookupFuture<'a> {
+            self.queries.fetch_add(1, Ordering::Relaxed);
+            Box::pin(async move {
+                let known = ["transfer(address,uint256)", "Unauthorized(address)"];
+                Ok(known
+                    .into_iter()
+                    .map(|signature| (selector(signature), vec![signature.to_owned()]))
+                    .filter(|(selector, _)| selectors.contains(selector))
+                    .collect())
+            })
+        }
+    }
+
+    fn frame(input: Vec<u8>) -> CallFrame {
+        CallFrame {
+            typ: "CALL".to_owned(),
+            from: Address::repeat_byte(0x01),
+            to: Some(Address::repeat_byte(0x02)),
+            value: None,
+            gas: U256::from(100_000),
+            gas_used: U256::from(21_000),
+            input: input.into(),
+            output: None,
+            error: None,
+            revert_reason: None,
+            calls: vec![],
+        }
+    }
+
+    #[tokio::test]
+    async fn it_caches_lookups() {
+        let db = SignatureDb::new(Static::default());
+        let transfer = selector("transfer(address,uint256)");
+        let unknown = [0xde, 0xad, 0xbe, 0xef];
+
+        let found = db.lookup(&[transfer, unknown]).await.unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[&transfer], ["transfer(address,uint256)"]);
+        assert_eq!(db.cached(unknown), Some(vec![]));
+
+        db.lookup(&[unknown, transfer]).await.unwrap();
+        assert_eq!(db.source.queries.load(Ordering::Relaxed), 1);
+    }
+
+    #[tokio::test]
+    async fn it_resolves_traces() {
+        let db = SignatureDb::new(Static::default());
+        let mut registry = AbiRegistry::new();
+
+        let mut inner = frame(selector("transfer(address,uint256)").to_vec());
+        inner.error = Some("execution reverted".to_owned());
+        inner.output = Some(selector("Unauthorized(address)").to_vec().into());
+        let mut root = frame(vec![0xde, 0xad, 0xbe, 0xef]);
+        root.calls = vec![inner];
+
+        assert_eq!(db.resolve_trace(&mut registry, &root).await.unwrap(), 2);
+        assert!(registry
+            .function(selector("transfer(address,uint256)"))
+            .is_some());
+        assert!(registry.error(selector("Unauthorized(address)")).is_some());
+
+        // known selectors are not looked up again
+        assert_eq!(db.resolve_trace(&mut registry, &root).await.unwrap(), 0);
+        assert_eq!(db.source.queries.load(Ordering::Relaxed), 1);
+    }
+
+    #[tokio::test]
+    async fn it_persists_the_cache() {
+        let path = std::env::temp_dir().join(format!("signatures-{}.json", std::process::id()));
+        let transfer = selector("transfer(address,uint256)");
+
+        let db = SignatureDb::new(Static::default())
+            .with_cache_file(&path)
+            .unwrap();
+        db.lookup(&[transfer]).await.unwrap();
+
+        let db = SignatureDb::new(Static::default())
+            .with_cache_file(&path)
+            .unwrap();
+        assert_eq!(
+            db.lookup(&[transfer]).await.unwrap()[&transfer],
+            ["transfer(address,uint256)"]
+        );
+        assert_eq!(db.source.queries.load(Ordering::Relaxed), 0);
+        fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,318 @@
+//! Signing transactions locally, and sending them with
+//! `eth_sendRawTransaction`
+
+use std::{
+    error::Error,
+    sync::{Arc, Mutex},
+};
+
+use ethers_primitives::{Address, B256, U256};
+use ethers_pub_use::thiserror;
+use ethers_rpc_types::{transaction::TypedTransaction, BlockNumber, Bytes};
+use ethers_signer::Signer;
+use ethers_transports::Connection;
+
+use crate::{Provider, ProviderError};
+
+/// Errors produced by a [`SignerProvider`]
+#[derive(Debug, thiserror::Error)]
+pub enum SignerProviderError<E: Error + 'static> {
+    /// A request failed
+    #[error(transparent)]
+    Provider(#[from] ProviderError),
+
+    /// The signer failed
+    #[error("signing failed: {0}")]
+    Signer(#[source] E),
+
+    /// The transaction is not replay protected for the node's chain. See
+    /// [`SignerProvider::allow_chain_id_mismatch`]
+    #[error("transaction chain id {actual:?} does not match the node's chain id {expected}")]
+    ChainIdMismatch {
+        /// The node's chain id
+        expected: u64,
+        /// The transaction's chain id. `None` for legacy transactions
+        /// without EIP-155 replay protection
+        actual: Option<u64>,
+    },
+}
+
+/// A provider that signs transactions with a local [`Signer`].
+///
+/// Transactions are only signed if their chain id is the node's, as
+/// reported by [`Provider::chain_id`]. A transaction signed for another
+/// chain, or a legacy transaction without EIP-155 replay protection, could
+/// be replayed on chains it was not meant for.
+///
+/// With a [nonce manager](SignerProvider::with_nonce_manager), nonces are
+/// assigned locally, so that transactions can be sent in quick succession
+/// without waiting for the node to count the previous ones
+#[derive(Debug, Clone)]
+pub struct SignerProvider<T, S> {
+    provider: Provider<T>,
+    signer: S,
+    allow_chain_id_mismatch: bool,
+    /// The next nonce to assign, if managed. `None` inside until synced
+    /// with the node
+    nonces: Option<Arc<Mutex<Option<u64>>>>,
+}
+
+impl<T, S> SignerProvider<T, S>
+where
+    S: Signer,
+{
+    /// Instantiate a provider that signs with `signer`
+    pub fn new(provider: Provider<T>, signer: S) -> Self {
+        Self {
+            provider,
+            signer,
+            allow_chain_id_mismatch: false,
+            nonces: None,
+        }
+    }
+
+    /// Sign transactions whatever their chain id, including unprotected
+    /// legacy transactions
+    #[must_use = "Builder method outputs must be used"]
+    pub fn allow_chain_id_mismatch(mut self) -> Self {
+        self.allow_chain_id_mismatch = true;
+        self
+    }
+
+    /// Assign the nonces of sent transactions locally. The first nonce is
+    /// the account's pending transaction count. Clones share the nonces
+    #[must_use = "Builder method outputs must be used"]
+    pub fn with_nonce_manager(mut self) -> Self {
+        self.nonces = Some(Default::default());
+        self
+    }
+
+    /// Forget the managed nonce, so that the next is fetched from the node.
+    /// Called when a send fails, as the nonce may not have been used
+    pub fn reset_nonce(&self) {
+        if let Some(nonces) = &self.nonces {
+            *nonces.lock().unwrap_or_else(|e| e.into_inner()) = None;
+        }
+    }
+
+    /// The provider
+    pub fn provider(&self) -> &Provider<T> {
+        &self.provider
+    }
+
+    /// The signer
+    pub fn signer(&self) -> &S {
+        &self.signer
+    }
+
+    /// The address transactions are signed for
+    pub fn address(&self) -> Address {
+        self.signer.address()
+    }
+}
+
+impl<T, S> SignerProvider<T, S>
+where
+    T: Connection + Unpin,
+    S: Signer,
+{
+    /// Sign a transaction. Returns its EIP-2718 encoding, as sent with
+    /// `eth_sendRawTransaction`
+    pub async fn sign_transaction(
+        &self,
+        tx: &TypedTransaction,
+    ) -> Result<Bytes, SignerProviderError<S::Error>> {
+        if !self.allow_chain_id_mismatch {
+            let expected = self.provider.chain_id().await?;
+            if tx.chain_id() != Some(expected) {
+                return Err(SignerProviderError::ChainIdMismatch {
+                    expected,
+                    actual: tx.chain_id(),
+                });
+            }
+        }
+        let signature = self
+            .signer
+            .sign_hash(tx.signature_hash())
+            .await
+            .map_err(SignerProviderError::Signer)?;
+        Ok(tx.rlp_signed(&signature).into())
+    }
+
+    /// Set the nonce of `tx` to the next managed nonce. Does nothing
+    /// without a nonce manager
+    pub async fn fill_nonce(&self, tx: &mut TypedTransaction) -> Result<(), ProviderError> {
+        let Some(nonces) = &self.nonces else {
+            return Ok(());
+        };
+        if let Some(nonce) = take_nonce(nonces, None) {
+            tx.set_nonce(nonce);
+            return Ok(());
+        }
+        let count: U256 = self
+            .provider
+            .get_transaction_count(self.address(), Some(BlockNumber::Pending.into()))
+            .await??;
+        // another send may have synced in the meantime
+        if let Some(nonce) = take_nonce(nonces, Some(count.saturating_to())) {
+            tx.set_nonce(nonce);
+        }
+        Ok(())
+    }
+
+    /// Sign and send a transaction. With a nonce manager, its nonce is
+    /// assigned first. Returns the transaction hash
+    pub async fn send_transaction(
+        &self,
+        tx: &TypedTransaction,
+    ) -> Result<B256, SignerProviderError<S::Error>> {
+        let mut tx = tx.clone();
+        self.fill_nonce(&mut tx).await?;
+        let res = self.send_with_nonce(&tx).await;
+        if res.is_err() {
+            self.reset_nonce();
+        }
+        res
+    }
+
+    /// Sign and send a transaction with its nonce as set, bypassing the
+    /// nonce manager. Returns the transaction hash
+    pub async fn send_with_nonce(
+        &self,
+        tx: &TypedTransaction,
+    ) -> Result<B256, SignerProviderError<S::Error>> {
+        let raw = self.sign_transaction(tx).await?;
+        let hash: B256 = self
+            .provider
+            .send_raw_transaction(raw)
+            .await
+            .map_err(ProviderError::from)?
+            .map_err(ProviderError::from)?;
+        Ok(hash)
+    }
+}
+
+/// Take the next managed nonce, syncing it to `count` if it is not known
+fn take_nonce(nonces: &Mutex<Option<u64>>, count: Option<u64>) -> Option<u64> {
+    let mut next = nonces.lock().unwrap_or_else(|e| e.into_inner());
+    let nonce = next.or(count)?;
+    *next = Some(nonce + 1);
+    Some(nonce)
+}
+
+#[cfg(test)]
+mod test {
+    use std::convert::Infallible;
+
+    use ethers_primitives::{keccak256, Signature};
+    use ethers_pub_use::{async_trait::async_trait, serde_json};
+    use ethers_rpc_types::transaction::{Eip1559Transaction, LegacyTransaction};
+    use ethers_transports::transports::MockConnection;
+
+    use super::*;
+
+    /// Signs everything with the same signature
+    #[derive(Debug)]
+    struct Fixed;
+
+    #[async_trait]
+    impl Signer for Fixed {
+        type Error = Infallible;
+
+        fn address(&self) -> Address {
+            Address::repeat_byte(0x11)
+        }
+
+        async fn sign_hash(&self, _digest: B256) -> Result<Signature, Infallible> {
+            Ok(Signature {
+                r: U256::from(1),
+                s: U256::from(2),
+                v: 27,
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn it_refuses_mismatched_chain_ids() {
+        let mock = MockConnection::new();
+        mock.push_response("eth_chainId", "0x1");
+        let provider = SignerProvider::new(Provider::new(mock.clone()), Fixed);
+
+        let tx = TypedTransaction::Eip1559(Eip1559Transaction {
+            chain_id: 1,
+            ..Default::default()
+        });
+        let raw = provider.sign_transaction(&tx).await.unwrap();
+        mock.push_response("eth_sendRawTransaction", keccak256(&raw));
+        assert_eq!(
+            provider.send_transaction(&tx).await.unwrap(),
+            keccak256(&raw)
+        );
+
+        let goerli = TypedTransaction::Eip1559(Eip1559Transaction {
+            chain_id: 5,
+            ..Default::default()
+        });
+        assert!(matches!(
+            provider.sign_transaction(&goerli).await,
+            Err(SignerProviderError::ChainIdMismatch {
+                expected: 1,
+                actual: Some(5)
+            })
+        ));
+        let unprotected = TypedTransaction::Legacy(LegacyTransaction::default());
+        assert!(matches!(
+            provider.sign_transaction(&unprotected).await,
+            Err(SignerProviderError::ChainIdMismatch { actual: None, .. })
+        ));
+
+        // the chain id was requested once
+        let chain_id_requests = mock
+            .requests()
+            .iter()
+            .filter(|req| req.method == "eth_chainId")
+            .count();
+        assert_eq!(chain_id_requests, 1);
+
+        let provider = provider.allow_chain_id_mismatch();
+        assert!(provider.sign_transaction(&unprotected).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn it_manages_nonces() {
+        let mock = MockConnection::new();
+        let provider = SignerProvider::new(Provider::new(mock.clone()), Fixed)
+            .allow_chain_id_mismatch()
+            .with_nonce_manager();
+        mock.push_response("eth_getTransactionCount", "0x7");
+        for _ in 0..2 {
+            mock.push_response("eth_sendRawTransaction", B256::default());
+        }
+        mock.push_error("eth_sendRawTransaction", -32000, "nonce too low");
+        // resynced after the failure
+        mock.push_response("eth_getTransactionCount", "0xa");
+        mock.push_response("eth_sendRawTransaction", B256::default());
+
+        let tx = TypedTransaction::Eip1559(Eip1559Transaction::default());
+        for _ in 0..2 {
+            provider.send_transaction(&tx).await.unwrap();
+        }
+        assert!(provider.send_transaction(&tx).await.is_err());
+        provider.send_transaction(&tx).await.unwrap();
+
+        let nonces: Vec<u64> = mock
+            .requests()
+            .iter()
+            .filter(|req| req.method == "eth_sendRawTransaction")
+            .map(|req| {
+                let raw: Bytes =
+                    serde_json::from_value(req.params.as_ref().unwrap()[0].clone()).unwrap();
+                TypedTransaction::decode_raw(&raw).unwrap().tx.nonce()
+            })
+            .collect();
+        assert_eq!(nonces, [7, 8, 9, 10]);
+        let count = &mock.requests()[0];
+        assert_eq!(count.params.as_ref().unwrap()[1], "pending");
+        assert_eq!(mock.pending_responses(), 0);
+    }
+}
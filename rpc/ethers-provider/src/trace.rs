@@ -0,0 +1,362 @@
+//! Typed methods for the parity `trace_*` and geth `debug_trace*`
+//! namespaces. These are only served by nodes with tracing enabled.
+
+use ethers_primitives::B256;
+use ethers_rpc_types::{
+    trace::{
+        geth::{CallFrame, GethBuiltInTracer, GethDebugTracingOptions, GethTrace},
+        parity::{LocalizedTransactionTrace, TraceFilter, TraceResults, TraceType},
+    },
+    BlockId, BlockNumber, TransactionRequest,
+};
+use ethers_transports::{Connection, RpcCall};
+
+use crate::{DecodedCallFrame, Provider, ProviderError, TraceDecoder};
+
+impl<T> Provider<T>
+where
+    T: Connection,
+{
+    /// Get the call traces of a transaction
+    pub fn trace_transaction(
+        &self,
+        hash: B256,
+    ) -> RpcCall<&Self, Self, (B256,), Vec<LocalizedTransactionTrace>> {
+        self.request("trace_transaction", (hash,))
+    }
+
+    /// Get the call traces of all transactions in a block, including
+    /// rewards. Defaults to the latest block
+    pub fn trace_block(
+        &self,
+        block: Option<BlockNumber>,
+    ) -> RpcCall<&Self, Self, (BlockNumber,), Vec<LocalizedTransactionTrace>> {
+        self.request("trace_block", (block.unwrap_or_default(),))
+    }
+
+    /// Get the call traces matching a filter
+    pub fn trace_filter(
+        &self,
+        filter: TraceFilter,
+    ) -> RpcCall<&Self, Self, (TraceFilter,), Vec<LocalizedTransactionTrace>> {
+        self.request("trace_filter", (filter,))
+    }
+
+    /// Execute a call without submitting it, and trace it. Defaults to the
+    /// latest block
+    pub fn trace_call(
+        &self,
+        request: TransactionRequest,
+        trace_types: Vec<TraceType>,
+        block: Option<BlockId>,
+    ) -> RpcCall<&Self, Self, (TransactionRequest, Vec<TraceType>, BlockId), TraceResults> {
+        self.request(
+            "trace_call",
+            (request, trace_types, block.unwrap_or_default()),
+        )
+    }
+
+    /// Replay a mined transaction, and trace it
+    pub fn trace_replay_transaction(
+        &self,
+        hash: B256,
+        trace_types: Vec<TraceType>,
+    ) -> RpcCall<&Self, Self, (B256, Vec<TraceType>), TraceResults> {
+        self.request("trace_replayTransaction", (hash, trace_types))
+    }
+
+    /// Replay a mined transaction with a geth tracer
+    pub fn debug_trace_transaction(
+        &self,
+        hash: B256,
+        options: GethDebugTracingOptions,
+    ) -> RpcCall<&Self, Self, (B256, GethDebugTracingOptions), GethTrace> {
+        self.request("debug_traceTransaction", (hash, options))
+    }
+
+    /// Replay a mined transaction with the call tracer, and decode the
+    /// function, arguments and revert reason of each call with `decoder`.
+    /// See [`DecodedCallFrame`]
+    pub async fn debug_decoded_trace<D>(
+        &self,
+        hash: B256,
+        decoder: &D,
+    ) -> Result<DecodedCallFrame, ProviderError>
+    where
+        T: Unpin,
+        D: TraceDecoder + ?Sized,
+    {
+        let options = GethDebugTracingOptions::default().with_tracer(GethBuiltInTracer::CallTracer);
+        let frame: CallFrame = self
+            .request("debug_traceTransaction", (hash, options))
+            .await??;
+        Ok(DecodedCallFrame::decode(frame, decoder))
+    }
+
+    /// Execute a call without submitting it, and trace it with a geth
+    /// tracer. Defaults to the latest block
+    pub fn debug_trace_call(
+        &self,
+        request: TransactionRequest,
+        block: Option<BlockId>,
+        options: GethDebugTracingOptions,
+    ) -> RpcCall<&Self, Self, (TransactionRequest, BlockId, GethDebugTracingOptions), GethTrace>
+    {
+        self.request(
+            "debug_traceCall",
+            (request, block.unwrap_or_default(), options),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ethers_primitives::{Address, U256, U64};
+    use ethers_pub_use::serde_json::{json, Value};
+    use ethers_rpc_types::trace::{
+        geth::{GethDefaultTracingOptions, PreStateFrame},
+        parity::{Action, CallType, Delta, TraceOutput},
+    };
+    use ethers_transports::transports::MockConnection;
+
+    use super::*;
+
+    const TX_HASH: &str = "0x3c5d1bb4c0e5e1ef0e3e6a7a9d4e5d4a8b0a0e4c5b1c3e6f2c2f8e9b1a2d3c4e";
+    const BLOCK_HASH: &str = "0x6d6b2ffe5c6e8d2b1c0e47b5b0c67e9e0d0b2f9f3d1dd5a3e64c7a25b13a1c2d";
+
+    /// A parity trace of a token transfer at the top of a transaction
+    fn call_trace() -> Value {
+        json!({
+            "action": {
+                "from": "0x25e4a0a8d4d3e3e1c0f0c2bfc4e7d7b39f6cd9a5",
+                "callType": "call",
+                "gas": "0x7148",
+                "input": "0xa9059cbb",
+                "to": "0x1c39ba39e4735cb65978d4db400ddd70a72dc750",
+                "value": "0x0"
+            },
+            "blockHash": BLOCK_HASH,
+            "blockNumber": 17000000,
+            "result": { "gasUsed": "0x5208", "output": "0x" },
+            "subtraces": 0,
+            "traceAddress": [],
+            "transactionHash": TX_HASH,
+            "transactionPosition": 3,
+            "type": "call"
+        })
+    }
+
+    fn requests(mock: &MockConnection) -> Vec<(String, Value)> {
+        mock.requests()
+            .into_iter()
+            .map(|req| (req.method, req.params.unwrap_or_default()))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn it_gets_parity_traces() {
+        let mock = MockConnection::new();
+        let provider = Provider::new(mock.clone());
+        let hash: B256 = TX_HASH.parse().unwrap();
+        let filter = TraceFilter {
+            from_block: Some(BlockNumber::Number(17_000_000)),
+            to_address: Some(vec![Address::repeat_byte(0x11)]),
+            count: Some(10),
+            ..Default::default()
+        };
+
+        mock.push_response("trace_transaction", json!([call_trace()]));
+        mock.push_response("trace_block", json!([call_trace()]));
+        mock.push_response("trace_filter", json!([]));
+
+        let traces = provider.trace_transaction(hash).await.unwrap().unwrap();
+        let trace = &traces[0];
+        let Action::Call(call) = &trace.trace.action else {
+            panic!("expected a call")
+        };
+        assert_eq!(call.call_type, CallType::Call);
+        assert_eq!(call.gas, U256::from(0x7148));
+        assert_eq!(
+            trace.trace.result,
+            Some(TraceOutput::Call {
+                gas_used: U256::from(21_000),
+                output: Default::default()
+            })
+        );
+        assert_eq!(trace.block_number, Some(U64::from(17_000_000)));
+        assert_eq!(trace.transaction_hash, Some(hash));
+        assert_eq!(trace.transaction_position, Some(3));
+
+        let block = provider.trace_block(None).await.unwrap().unwrap();
+        assert_eq!(block, traces);
+        assert!(provider
+            .trace_filter(filter)
+            .await
+            .unwrap()
+            .unwrap()
+            .is_empty());
+
+        assert_eq!(
+            requests(&mock),
+            vec![
+                ("trace_transaction".to_owned(), json!([TX_HASH])),
+                ("trace_block".to_owned(), json!(["latest"])),
+                (
+                    "trace_filter".to_owned(),
+                    json!([{
+                        "fromBlock": "0x1036640",
+                        "toAddress": ["0x1111111111111111111111111111111111111111"],
+                        "count": 10
+                    }])
+                ),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn it_replays_with_parity_tracers() {
+        let mock = MockConnection::new();
+        let provider = Provider::new(mock.clone());
+        let hash: B256 = TX_HASH.parse().unwrap();
+        let request = TransactionRequest::default().to(Address::repeat_byte(0x11));
+        let sender = "0x25e4a0a8d4d3e3e1c0f0c2bfc4e7d7b39f6cd9a5";
+        let results = json!({
+            "output": "0x0000000000000000000000000000000000000000000000000000000000000001",
+            "stateDiff": {
+                (sender): {
+                    "balance": { "*": { "from": "0x10", "to": "0x8" } },
+                    "nonce": { "*": { "from": "0x1", "to": "0x2" } },
+                    "code": "=",
+                    "storage": {}
+                }
+            },
+            "trace": [{
+                "action": call_trace()["action"],
+                "result": { "gasUsed": "0x5208", "output": "0x" },
+                "subtraces": 0,
+                "traceAddress": [],
+                "type": "call"
+            }],
+            "vmTrace": null
+        });
+
+        mock.push_response("trace_call", results.clone());
+        mock.push_response("trace_replayTransaction", results);
+
+        let results = provider
+            .trace_call(
+                request.clone(),
+                vec![TraceType::Trace, TraceType::StateDiff],
+                None,
+            )
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(results.output[31], 1);
+        assert_eq!(results.trace.as_ref().unwrap()[0].subtraces, 0);
+        let diff = &results.state_diff.as_ref().unwrap()[&sender.parse::<Address>().unwrap()];
+        assert!(matches!(&diff.nonce, Delta::Changed(nonce) if nonce.to == U256::from(2)));
+        assert_eq!(diff.code, Delta::Unchanged);
+        assert!(results.vm_trace.is_none());
+
+        let replayed = provider
+            .trace_replay_transaction(hash, vec![TraceType::Trace, TraceType::StateDiff])
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(replayed, results);
+
+        let reqs = requests(&mock);
+        assert_eq!(
+            reqs[0],
+            (
+                "trace_call".to_owned(),
+                json!([request, ["trace", "stateDiff"], "latest"])
+            )
+        );
+        assert_eq!(
+            reqs[1],
+            (
+                "trace_replayTransaction".to_owned(),
+                json!([TX_HASH, ["trace", "stateDiff"]])
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn it_traces_with_geth_tracers() {
+        let mock = MockConnection::new();
+        let provider = Provider::new(mock.clone());
+        let hash: B256 = TX_HASH.parse().unwrap();
+        let request = TransactionRequest::default().to(Address::repeat_byte(0x11));
+        let struct_logger = GethDebugTracingOptions {
+            config: GethDefaultTracingOptions {
+                disable_storage: Some(true),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let prestate = GethDebugTracingOptions::default()
+            .with_tracer(GethBuiltInTracer::PreStateTracer)
+            .with_timeout("10s");
+
+        mock.push_response(
+            "debug_traceTransaction",
+            json!({
+                "failed": false,
+                "gas": 21000,
+                "returnValue": "",
+                "structLogs": [{ "pc": 0, "op": "STOP", "gas": 0, "gasCost": 0, "depth": 1 }]
+            }),
+        );
+        mock.push_response(
+            "debug_traceCall",
+            json!({
+                "0x1111111111111111111111111111111111111111": { "balance": "0x0", "nonce": 0 }
+            }),
+        );
+
+        let trace = provider
+            .debug_trace_transaction(hash, struct_logger)
+            .await
+            .unwrap()
+            .unwrap();
+        let GethTrace::Default(frame) = trace else {
+            panic!("expected a struct log trace")
+        };
+        assert_eq!(frame.gas, 21_000);
+        assert_eq!(frame.struct_logs[0].op, "STOP");
+
+        let trace = provider
+            .debug_trace_call(
+                request.clone(),
+                Some(BlockNumber::Number(17_000_000).into()),
+                prestate,
+            )
+            .await
+            .unwrap()
+            .unwrap();
+        let GethTrace::PreStateTracer(PreStateFrame::Default(accounts)) = trace else {
+            panic!("expected a prestate trace")
+        };
+        assert_eq!(accounts[&Address::repeat_byte(0x11)].nonce, Some(0));
+
+        assert_eq!(
+            requests(&mock),
+            vec![
+                (
+                    "debug_traceTransaction".to_owned(),
+                    json!([TX_HASH, { "disableStorage": true }])
+                ),
+                (
+                    "debug_traceCall".to_owned(),
+                    json!([
+                        request,
+                        "0x1036640",
+                        { "tracer": "prestateTracer", "timeout": "10s" }
+                    ])
+                ),
+            ]
+        );
+    }
+}
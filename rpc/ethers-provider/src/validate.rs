@@ -0,0 +1,400 @@
+//! Opt-in validation of typed responses against invariants that a correct
+//! node always upholds. Violations point at buggy or malicious endpoints.
+//!
+//! Validation is off by default. Enable it with
+//! [`Provider::with_validation`], or validate a single response with
+//! [`Provider::validate`].
+
+use std::fmt;
+
+use ethers_pub_use::tracing;
+use ethers_rpc_types::{Header, Log, TransactionReceipt};
+
+use crate::{Provider, ProviderError};
+
+/// What a provider does with responses that violate an invariant
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ValidationMode {
+    /// Responses are not validated
+    #[default]
+    Off,
+    /// Violations are logged as warnings, and the response is returned
+    Warn,
+    /// Violations are logged as warnings, and the response is rejected with
+    /// [`ProviderError::InvalidResponse`]
+    Reject,
+}
+
+/// A broken response invariant
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    /// A short, stable name for the invariant, e.g. `log_block_hash`
+    pub rule: &'static str,
+    /// What was wrong
+    pub detail: String,
+}
+
+impl Violation {
+    fn new(rule: &'static str, detail: impl Into<String>) -> Self {
+        Self {
+            rule,
+            detail: detail.into(),
+        }
+    }
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.rule, self.detail)
+    }
+}
+
+/// A response with invariants that can be checked locally
+pub trait Validate {
+    /// Push every violated invariant to `violations`
+    fn validate(&self, violations: &mut Vec<Violation>);
+
+    /// Collect every violated invariant
+    fn violations(&self) -> Vec<Violation> {
+        let mut violations = vec![];
+        self.validate(&mut violations);
+        violations
+    }
+}
+
+impl<T: Validate> Validate for Option<T> {
+    fn validate(&self, violations: &mut Vec<Violation>) {
+        if let Some(inner) = self {
+            inner.validate(violations);
+        }
+    }
+}
+
+impl Validate for Log {
+    /// Block and transaction fields are either all set, for mined logs, or
+    /// all unset, for pending logs
+    fn validate(&self, violations: &mut Vec<Violation>) {
+        let set = [
+            self.block_hash.is_some(),
+            self.block_number.is_some(),
+            self.transaction_hash.is_some(),
+            self.transaction_index.is_some(),
+            self.log_index.is_some(),
+        ];
+        if set.contains(&true) && set.contains(&false) {
+            violations.push(Violation::new(
+                "log_partially_mined",
+                "log has some but not all block and transaction fields",
+            ));
+        }
+        if self.topics.len() > 4 {
+            violations.push(Violation::new(
+                "log_topics",
+                format!("log has {} topics", self.topics.len()),
+            ));
+        }
+    }
+}
+
+impl Validate for Vec<Log> {
+    /// Each log is valid, logs in the same block agree on its hash, and
+    /// logs are ordered by block, then by log index
+    fn validate(&self, violations: &mut Vec<Violation>) {
+        self.iter().for_each(|log| log.validate(violations));
+
+        for pair in self.windows(2) {
+            let (prev, next) = (&pair[0], &pair[1]);
+            let (Some(prev_number), Some(next_number)) = (prev.block_number, next.block_number)
+            else {
+                continue;
+            };
+            if prev_number > next_number {
+                violations.push(Violation::new(
+                    "log_order",
+                    format!("block {next_number} follows block {prev_number}"),
+                ));
+            } else if prev_number == next_number {
+                if prev.block_hash != next.block_hash {
+                    violations.push(Violation::new(
+                        "log_block_hash",
+                        format!("logs in block {prev_number} have different block hashes"),
+                    ));
+                }
+                check_log_index(prev, next, violations);
+            }
+        }
+    }
+}
+
+impl Validate for Header {
+    /// A mined header has both a hash and a number, and does not use more
+    /// gas than its limit
+    fn validate(&self, violations: &mut Vec<Violation>) {
+        if self.hash.is_some() != self.number.is_some() {
+            violations.push(Violation::new(
+                "header_partially_mined",
+                "header has only one of hash and number",
+            ));
+        }
+        if self.gas_used > self.gas_limit {
+            violations.push(Violation::new(
+                "header_gas",
+                format!(
+                    "gas used {} exceeds gas limit {}",
+                    self.gas_used, self.gas_limit
+                ),
+            ));
+        }
+    }
+}
+
+impl Validate for TransactionReceipt {
+    /// Each log belongs to the receipt's block and transaction, log indices
+    /// increase, and the transaction's gas fits in the cumulative gas
+    fn validate(&self, violations: &mut Vec<Violation>) {
+        for log in &self.logs {
+            log.validate(violations);
+            if log.block_hash.is_some() && log.block_hash != self.block_hash {
+                violations.push(Violation::new(
+                    "receipt_log_block_hash",
+                    "log block hash does not match the receipt",
+                ));
+            }
+            if log.block_number.is_some() && log.block_number != self.block_number {
+                violations.push(Violation::new(
+                    "receipt_log_block_number",
+                    "log block number does not match the receipt",
+                ));
+            }
+            if log
+                .transaction_hash
+                .is_some_and(|hash| hash != self.transaction_hash)
+            {
+                violations.push(Violation::new(
+                    "receipt_log_transaction_hash",
+                    "log transaction hash does not match the receipt",
+                ));
+            }
+            if log
+                .transaction_index
+                .is_some_and(|index| index != self.transaction_index)
+            {
+                violations.push(Violation::new(
+                    "receipt_log_transaction_index",
+                    "log transaction index does not match the receipt",
+                ));
+            }
+        }
+        for pair in self.logs.windows(2) {
+            check_log_index(&pair[0], &pair[1], violations);
+        }
+        if self
+            .gas_used
+            .is_some_and(|gas| gas > self.cumulative_gas_used)
+        {
+            violations.push(Violation::new(
+                "receipt_gas",
+                "gas used exceeds cumulative gas used",
+            ));
+        }
+        if self.contract_address.is_some() && self.to.is_some() {
+            violations.push(Violation::new(
+                "receipt_contract_address",
+                "receipt has both a recipient and a created contract",
+            ));
+        }
+    }
+}
+
+impl Validate for Vec<TransactionReceipt> {
+    /// Each receipt is valid, and receipts in the same block are ordered by
+    /// strictly increasing transaction index and non-decreasing cumulative
+    /// gas
+    fn validate(&self, violations: &mut Vec<Violation>) {
+        self.iter().for_each(|receipt| receipt.validate(violations));
+
+        for pair in self.windows(2) {
+            let (prev, next) = (&pair[0], &pair[1]);
+            if prev.block_hash.is_none() || prev.block_hash != next.block_hash {
+                continue;
+            }
+            if prev.transaction_index >= next.transaction_index {
+                violations.push(Violation::new(
+                    "receipt_order",
+                    format!(
+                        "transaction index {} follows {}",
+                        next.transaction_index, prev.transaction_index
+                    ),
+                ));
+            }
+            if prev.cumulative_gas_used > next.cumulative_gas_used {
+                violations.push(Violation::new(
+                    "receipt_cumulative_gas",
+                    "cumulative gas used decreases within the block",
+                ));
+            }
+        }
+    }
+}
+
+/// Within a block, log indices strictly increase
+fn check_log_index(prev: &Log, next: &Log, violations: &mut Vec<Violation>) {
+    let (Some(prev_index), Some(next_index)) = (prev.log_index, next.log_index) else {
+        return;
+    };
+    if prev_index >= next_index {
+        violations.push(Violation::new(
+            "log_index",
+            format!("log index {next_index} follows {prev_index}"),
+        ));
+    }
+    if prev.transaction_index > next.transaction_index && next_index > prev_index {
+        violations.push(Violation::new(
+            "log_transaction_index",
+            "transaction index decreases as log index increases",
+        ));
+    }
+}
+
+impl<T> Provider<T> {
+    /// Set how typed responses are validated. See [`ValidationMode`]
+    #[must_use = "Builder method outputs must be used"]
+    pub fn with_validation(mut self, mode: ValidationMode) -> Self {
+        self.set_validation(mode);
+        self
+    }
+
+    pub fn set_validation(&mut self, mode: ValidationMode) {
+        self.validation = mode;
+    }
+
+    /// How typed responses are validated
+    pub fn validation(&self) -> ValidationMode {
+        self.validation
+    }
+
+    /// Validate a response to `method` according to the provider's
+    /// [`ValidationMode`]
+    pub fn check_response<R: Validate>(&self, method: &str, resp: R) -> Result<R, ProviderError> {
+        self.validate_with(self.validation, method, resp)
+    }
+
+    /// Validate a single response, regardless of the provider's
+    /// [`ValidationMode`]. Use this to opt in per call
+    pub fn validate<R: Validate>(&self, method: &str, resp: R) -> Result<R, ProviderError> {
+        self.validate_with(ValidationMode::Reject, method, resp)
+    }
+
+    fn validate_with<R: Validate>(
+        &self,
+        mode: ValidationMode,
+        method: &str,
+        resp: R,
+    ) -> Result<R, ProviderError> {
+        if mode == ValidationMode::Off {
+            return Ok(resp);
+        }
+        let violations = resp.violations();
+        if violations.is_empty() {
+            return Ok(resp);
+        }
+        for violation in &violations {
+            tracing::warn!(
+                method,
+                rule = violation.rule,
+                detail = %violation.detail,
+                "invalid RPC response"
+            );
+        }
+        match mode {
+            ValidationMode::Reject => Err(ProviderError::InvalidResponse {
+                method: method.to_owned(),
+                violations,
+            }),
+            _ => Ok(resp),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ethers_primitives::{B256, U256, U64};
+
+    use super::*;
+
+    fn log(block: u64, hash: u8, tx: u64, index: u64) -> Log {
+        Log {
+            block_hash: Some(B256::repeat_byte(hash)),
+            block_number: Some(U64::from(block)),
+            transaction_hash: Some(B256::repeat_byte(0xaa)),
+            transaction_index: Some(U64::from(tx)),
+            log_index: Some(U256::from(index)),
+            ..Default::default()
+        }
+    }
+
+    fn rules(violations: Vec<Violation>) -> Vec<&'static str> {
+        violations.into_iter().map(|v| v.rule).collect()
+    }
+
+    #[test]
+    fn it_validates_logs() {
+        let logs = vec![log(1, 1, 0, 0), log(1, 1, 1, 1), log(2, 2, 0, 0)];
+        assert!(logs.violations().is_empty());
+
+        let logs = vec![log(1, 1, 0, 0), log(1, 2, 0, 0), log(0, 3, 0, 0)];
+        assert_eq!(
+            rules(logs.violations()),
+            ["log_block_hash", "log_index", "log_order"]
+        );
+
+        let pending = Log {
+            block_hash: None,
+            ..log(1, 1, 0, 0)
+        };
+        assert_eq!(rules(pending.violations()), ["log_partially_mined"]);
+        assert!(Log::default().violations().is_empty());
+    }
+
+    #[test]
+    fn it_validates_receipts() {
+        let mut receipt = TransactionReceipt {
+            transaction_hash: B256::repeat_byte(0xaa),
+            transaction_index: U64::from(3),
+            block_hash: Some(B256::repeat_byte(1)),
+            block_number: Some(U64::from(1)),
+            cumulative_gas_used: U256::from(50_000),
+            gas_used: Some(U256::from(21_000)),
+            logs: vec![log(1, 1, 3, 4), log(1, 1, 3, 5)],
+            ..Default::default()
+        };
+        assert!(receipt.violations().is_empty());
+
+        receipt.logs[1].block_hash = Some(B256::repeat_byte(2));
+        receipt.gas_used = Some(U256::from(60_000));
+        assert_eq!(
+            rules(receipt.violations()),
+            ["receipt_log_block_hash", "receipt_gas"]
+        );
+    }
+
+    #[test]
+    fn it_applies_the_validation_mode() {
+        let logs = vec![log(2, 1, 0, 0), log(1, 1, 0, 0)];
+
+        let provider = Provider::new(());
+        assert_eq!(provider.validation(), ValidationMode::Off);
+        assert!(provider.check_response("eth_getLogs", logs.clone()).is_ok());
+
+        let provider = provider.with_validation(ValidationMode::Warn);
+        assert!(provider.check_response("eth_getLogs", logs.clone()).is_ok());
+
+        let provider = provider.with_validation(ValidationMode::Reject);
+        let err = provider.check_response("eth_getLogs", logs).unwrap_err();
+        let ProviderError::InvalidResponse { method, violations } = err else {
+            panic!("unexpected error {err}");
+        };
+        assert_eq!(method, "eth_getLogs");
+        assert_eq!(rules(violations), ["log_order"]);
+    }
+}
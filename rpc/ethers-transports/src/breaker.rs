@@ -0,0 +1,462 @@
+use std::{
+    borrow::Cow,
+    collections::VecDeque,
+    fmt,
+    sync::{Arc, Mutex, MutexGuard},
+    time::Duration,
+};
+
+use ethers_pub_use::{futures_channel::mpsc, serde_json::value::RawValue, tracing};
+
+use crate::{
+    common::{BatchRpcFuture, DynFuture, Request, RpcFuture},
+    time::Instant,
+    Connection, PubSubConnection, TransportError,
+};
+
+/// The default failure rate at which the circuit opens
+pub const DEFAULT_FAILURE_RATE: f64 = 0.5;
+/// The default number of recent requests over which the failure rate is
+/// measured
+pub const DEFAULT_WINDOW: usize = 20;
+/// The default minimum number of requests in the window before the circuit
+/// may open
+pub const DEFAULT_MIN_REQUESTS: usize = 5;
+/// The default time the circuit stays open before probing the endpoint
+pub const DEFAULT_OPEN_DURATION: Duration = Duration::from_secs(30);
+/// The default number of successful probes required to close the circuit
+pub const DEFAULT_PROBES: u32 = 1;
+
+/// The state of a circuit breaker
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BreakerState {
+    /// The endpoint is healthy. Requests are sent
+    Closed,
+    /// The endpoint is failing. Requests are rejected until the open
+    /// duration has elapsed
+    Open,
+    /// The open duration has elapsed. A limited number of probe requests
+    /// are sent to decide whether to close the circuit or re-open it
+    HalfOpen,
+}
+
+#[derive(Debug)]
+struct BreakerInner {
+    state: BreakerState,
+    /// Recent outcomes while closed. `true` for failures
+    outcomes: VecDeque<bool>,
+    opened_at: Instant,
+    probes_in_flight: u32,
+    probe_successes: u32,
+}
+
+/// Whether a request was admitted while closed, or as a probe
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Admission {
+    Normal,
+    Probe,
+}
+
+/// A [`Connection`] wrapper that stops sending requests to a failing
+/// endpoint.
+///
+/// The breaker tracks the outcomes of the most recent requests. Once at
+/// least the minimum number of requests have completed and the failure rate
+/// reaches the threshold, the circuit opens and requests fail immediately
+/// with [`TransportError::CircuitOpen`]. After the open duration, probe
+/// requests are let through. If enough probes succeed the circuit closes,
+/// and if one fails it opens again.
+///
+/// Only transport errors count as failures. JSON-RPC error responses show
+/// that the endpoint is up.
+///
+/// Load-balancing and quorum connections should skip endpoints that are not
+/// [available](CircuitBreaker::is_available).
+#[derive(Clone)]
+pub struct CircuitBreaker<T> {
+    inner: T,
+    endpoint: Arc<str>,
+    failure_rate: f64,
+    window: usize,
+    min_requests: usize,
+    open_duration: Duration,
+    probes: u32,
+    state: Arc<Mutex<BreakerInner>>,
+}
+
+impl<T> CircuitBreaker<T> {
+    /// Instantiate a closed circuit breaker with the default thresholds
+    pub fn new(inner: T, endpoint: impl Into<Arc<str>>) -> Self {
+        Self {
+            inner,
+            endpoint: endpoint.into(),
+            failure_rate: DEFAULT_FAILURE_RATE,
+            window: DEFAULT_WINDOW,
+            min_requests: DEFAULT_MIN_REQUESTS,
+            open_duration: DEFAULT_OPEN_DURATION,
+            probes: DEFAULT_PROBES,
+            state: Arc::new(Mutex::new(BreakerInner {
+                state: BreakerState::Closed,
+                outcomes: VecDeque::new(),
+                opened_at: Instant::now(),
+                probes_in_flight: 0,
+                probe_successes: 0,
+            })),
+        }
+    }
+
+    /// Set the failure rate, between 0 and 1, at which the circuit opens
+    #[must_use = "Builder method outputs must be used"]
+    pub fn with_failure_rate(mut self, rate: f64) -> Self {
+        self.failure_rate = rate.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Set the number of recent requests over which the failure rate is
+    /// measured, and the minimum number of requests before the circuit may
+    /// open
+    #[must_use = "Builder method outputs must be used"]
+    pub fn with_window(mut self, window: usize, min_requests: usize) -> Self {
+        self.window = window.max(1);
+        self.min_requests = min_requests.clamp(1, self.window);
+        self
+    }
+
+    /// Set the time the circuit stays open before probing the endpoint
+    #[must_use = "Builder method outputs must be used"]
+    pub fn with_open_duration(mut self, duration: Duration) -> Self {
+        self.open_duration = duration;
+        self
+    }
+
+    /// Set the number of successful probes required to close the circuit.
+    /// This is also the number of probes sent concurrently
+    #[must_use = "Builder method outputs must be used"]
+    pub fn with_probes(mut self, probes: u32) -> Self {
+        self.probes = probes.max(1);
+        self
+    }
+
+    /// The endpoint label
+    pub fn endpoint(&self) -> &str {
+        &self.endpoint
+    }
+
+    /// The wrapped connection
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+
+    /// The current state. An open circuit whose open duration has elapsed
+    /// is reported as half-open
+    pub fn state(&self) -> BreakerState {
+        let mut inner = self.lock();
+        self.poll_open(&mut inner);
+        inner.state
+    }
+
+    /// True if a request sent now would reach the endpoint
+    pub fn is_available(&self) -> bool {
+        let mut inner = self.lock();
+        self.poll_open(&mut inner);
+        match inner.state {
+            BreakerState::Closed => true,
+            BreakerState::Open => false,
+            BreakerState::HalfOpen => inner.probes_in_flight < self.probes,
+        }
+    }
+
+    /// Close the circuit and forget recent outcomes
+    pub fn reset(&self) {
+        let mut inner = self.lock();
+        inner.state = BreakerState::Closed;
+        inner.outcomes.clear();
+        inner.probes_in_flight = 0;
+        inner.probe_successes = 0;
+    }
+
+    fn lock(&self) -> MutexGuard<'_, BreakerInner> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Move from open to half-open once the open duration has elapsed
+    fn poll_open(&self, inner: &mut BreakerInner) {
+        if inner.state == BreakerState::Open && inner.opened_at.elapsed() >= self.open_duration {
+            inner.state = BreakerState::HalfOpen;
+            inner.probes_in_flight = 0;
+            inner.probe_successes = 0;
+        }
+    }
+
+    fn admit(&self) -> Option<Admission> {
+        let mut inner = self.lock();
+        self.poll_open(&mut inner);
+        match inner.state {
+            BreakerState::Closed => Some(Admission::Normal),
+            BreakerState::Open => None,
+            BreakerState::HalfOpen if inner.probes_in_flight < self.probes => {
+                inner.probes_in_flight += 1;
+                Some(Admission::Probe)
+            }
+            BreakerState::HalfOpen => None,
+        }
+    }
+
+    /// Wrap a request future, recording its outcome
+    fn guard<O: 'static>(
+        &self,
+        fut: impl FnOnce() -> DynFuture<'static, Result<O, TransportError>>,
+    ) -> DynFuture<'static, Result<O, TransportError>> {
+        let Some(admission) = self.admit() else {
+            return Box::pin(async { Err(TransportError::CircuitOpen) });
+        };
+        let fut = fut();
+        let mut recorder = Recorder {
+            admission: Some(admission),
+            endpoint: self.endpoint.clone(),
+            failure_rate: self.failure_rate,
+            window: self.window,
+            min_requests: self.min_requests,
+            probes: self.probes,
+            state: self.state.clone(),
+        };
+        Box::pin(async move {
+            let res = fut.await;
+            recorder.record(res.is_err());
+            res
+        })
+    }
+}
+
+/// The parts of the breaker needed to record an outcome after the request
+/// completes. If the request is dropped before completing, its probe slot
+/// is released
+struct Recorder {
+    admission: Option<Admission>,
+    endpoint: Arc<str>,
+    failure_rate: f64,
+    window: usize,
+    min_requests: usize,
+    probes: u32,
+    state: Arc<Mutex<BreakerInner>>,
+}
+
+impl Recorder {
+    fn record(&mut self, failed: bool) {
+        let Some(admission) = self.admission.take() else {
+            return;
+        };
+        let mut inner = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        match (admission, inner.state) {
+            (Admission::Normal, BreakerState::Closed) => {
+                if inner.outcomes.len() == self.window {
+                    inner.outcomes.pop_front();
+                }
+                inner.outcomes.push_back(failed);
+
+                let failures = inner.outcomes.iter().filter(|failed| **failed).count();
+                let total = inner.outcomes.len();
+                if total >= self.min_requests && failures as f64 >= self.failure_rate * total as f64
+                {
+                    tracing::warn!(endpoint = %self.endpoint, failures, total, "circuit opened");
+                    Self::open(&mut inner);
+                }
+            }
+            (Admission::Probe, BreakerState::HalfOpen) => {
+                inner.probes_in_flight = inner.probes_in_flight.saturating_sub(1);
+                if failed {
+                    tracing::warn!(endpoint = %self.endpoint, "probe failed, circuit re-opened");
+                    Self::open(&mut inner);
+                } else {
+                    inner.probe_successes += 1;
+                    if inner.probe_successes >= self.probes {
+                        tracing::info!(endpoint = %self.endpoint, "circuit closed");
+                        inner.state = BreakerState::Closed;
+                        inner.outcomes.clear();
+                    }
+                }
+            }
+            // requests admitted before the last state change are ignored
+            _ => {}
+        }
+    }
+
+    fn open(inner: &mut BreakerInner) {
+        inner.state = BreakerState::Open;
+        inner.opened_at = Instant::now();
+        inner.outcomes.clear();
+        inner.probes_in_flight = 0;
+        inner.probe_successes = 0;
+    }
+}
+
+impl Drop for Recorder {
+    fn drop(&mut self) {
+        if self.admission.take() == Some(Admission::Probe) {
+            let mut inner = self.state.lock().unwrap_or_else(|e| e.into_inner());
+            if inner.state == BreakerState::HalfOpen {
+                inner.probes_in_flight = inner.probes_in_flight.saturating_sub(1);
+            }
+        }
+    }
+}
+
+impl<T> fmt::Debug for CircuitBreaker<T>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CircuitBreaker")
+            .field("inner", &self.inner)
+            .field("endpoint", &self.endpoint)
+            .field("state", &self.lock().state)
+            .finish()
+    }
+}
+
+impl<T> Connection for CircuitBreaker<T>
+where
+    T: Connection,
+{
+    fn is_local(&self) -> bool {
+        self.inner.is_local()
+    }
+
+    fn increment_id(&self) -> u64 {
+        self.inner.increment_id()
+    }
+
+    fn default_timeout(&self) -> Option<Duration> {
+        self.inner.default_timeout()
+    }
+
+    fn json_rpc_request(&self, req: &Request<'_>) -> RpcFuture {
+        self.guard(|| self.inner.json_rpc_request(req))
+    }
+
+    fn batch_request(&self, reqs: &[Request<'_>]) -> BatchRpcFuture {
+        self.guard(|| self.inner.batch_request(reqs))
+    }
+}
+
+impl<T> PubSubConnection for CircuitBreaker<T>
+where
+    T: PubSubConnection,
+{
+    fn uninstall_listener(&self, id: [u8; 32]) -> Result<(), TransportError> {
+        self.inner.uninstall_listener(id)
+    }
+
+    fn install_listener(
+        &self,
+        id: [u8; 32],
+    ) -> Result<mpsc::UnboundedReceiver<Cow<'static, RawValue>>, TransportError> {
+        self.inner.install_listener(id)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    use ethers_pub_use::serde_json::{self, value::to_raw_value};
+
+    use super::*;
+    use crate::transports::MockConnection;
+
+    /// Echoes params, or fails with a transport error while the flag is
+    /// set
+    fn flaky() -> (MockConnection, Arc<AtomicBool>) {
+        let failing = Arc::new(AtomicBool::new(false));
+        let flag = failing.clone();
+        let mock = MockConnection::new().with_handler(move |req| {
+            if flag.load(Ordering::Relaxed) {
+                let err = serde_json::from_str::<u64>("").unwrap_err();
+                return Err(TransportError::ser_err(err));
+            }
+            Ok(Ok(Cow::Owned(to_raw_value(&req.params).unwrap())))
+        });
+        (mock, failing)
+    }
+
+    async fn send(breaker: &CircuitBreaker<MockConnection>) -> Result<(), TransportError> {
+        let req = Request::owned(
+            breaker.next_id(),
+            "eth_chainId",
+            Some(to_raw_value(&[1u64]).unwrap()),
+        );
+        breaker.json_rpc_request(&req).await.map(|_| ())
+    }
+
+    #[tokio::test]
+    async fn it_opens_on_failures() {
+        let (mock, failing) = flaky();
+        let breaker = CircuitBreaker::new(mock.clone(), "flaky").with_window(4, 4);
+
+        send(&breaker).await.unwrap();
+        send(&breaker).await.unwrap();
+        failing.store(true, Ordering::Relaxed);
+        assert!(send(&breaker).await.is_err());
+        assert_eq!(breaker.state(), BreakerState::Closed);
+        assert!(send(&breaker).await.is_err());
+
+        // 2 failures in 4 requests
+        assert_eq!(breaker.state(), BreakerState::Open);
+        assert!(!breaker.is_available());
+        assert!(matches!(
+            send(&breaker).await,
+            Err(TransportError::CircuitOpen)
+        ));
+        assert_eq!(mock.requests().len(), 4);
+    }
+
+    #[tokio::test]
+    async fn it_probes_and_recovers() {
+        let (mock, failing) = flaky();
+        let breaker = CircuitBreaker::new(mock, "flaky")
+            .with_window(2, 1)
+            .with_open_duration(Duration::ZERO)
+            .with_probes(2);
+
+        failing.store(true, Ordering::Relaxed);
+        assert!(send(&breaker).await.is_err());
+        assert_eq!(breaker.state(), BreakerState::HalfOpen);
+
+        // a failed probe re-opens the circuit
+        assert!(send(&breaker).await.is_err());
+        assert_eq!(breaker.state(), BreakerState::HalfOpen);
+
+        failing.store(false, Ordering::Relaxed);
+        send(&breaker).await.unwrap();
+        assert_eq!(breaker.state(), BreakerState::HalfOpen);
+        send(&breaker).await.unwrap();
+        assert_eq!(breaker.state(), BreakerState::Closed);
+    }
+
+    #[tokio::test]
+    async fn it_limits_concurrent_probes() {
+        let (mock, failing) = flaky();
+        let breaker = CircuitBreaker::new(mock, "flaky")
+            .with_window(1, 1)
+            .with_open_duration(Duration::ZERO);
+
+        failing.store(true, Ordering::Relaxed);
+        assert!(send(&breaker).await.is_err());
+        failing.store(false, Ordering::Relaxed);
+
+        let req = Request::owned(
+            breaker.next_id(),
+            "eth_chainId",
+            Some(to_raw_value(&[1u64]).unwrap()),
+        );
+        let probe = breaker.json_rpc_request(&req);
+        assert!(!breaker.is_available());
+        assert!(matches!(
+            send(&breaker).await,
+            Err(TransportError::CircuitOpen)
+        ));
+
+        probe.await.unwrap().unwrap();
+        assert_eq!(breaker.state(), BreakerState::Closed);
+    }
+}
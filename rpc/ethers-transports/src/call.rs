@@ -1,9 +1,10 @@
 use std::{
-    borrow::Borrow,
+    borrow::{Borrow, Cow},
     future::Future,
     marker::PhantomData,
     pin::Pin,
-    task::{ready, Context, Poll},
+    task::{Context, Poll},
+    time::Duration,
 };
 
 use ethers_pub_use::serde::{Deserialize, Serialize};
@@ -11,6 +12,7 @@ use jsonrpsee_types::ErrorObjectOwned;
 
 use crate::{
     common::{Id, Request, RpcFuture, RpcOutcome},
+    time::{self, Instant, Sleep},
     utils::{from_json, to_json_raw_value},
     Connection, TransportError,
 };
@@ -18,7 +20,7 @@ use crate::{
 pub(crate) enum CallState<B, T, Params> {
     Prepared {
         connection: B,
-        method: &'static str,
+        method: Cow<'static, str>,
         params: Params,
         id: Id<'static>,
         _pd: PhantomData<T>,
@@ -47,7 +49,7 @@ impl<B, T, Params> std::fmt::Debug for CallState<B, T, Params> {
 impl<B, T, Params> CallState<B, T, Params> {
     pub(crate) fn new(
         connection: B,
-        method: &'static str,
+        method: Cow<'static, str>,
         params: Params,
         id: Id<'static>,
     ) -> CallState<B, T, Params> {
@@ -128,19 +130,81 @@ where
     }
 }
 
+/// When a call gives up waiting for its response
+#[derive(Debug)]
+enum Deadline {
+    None,
+    After(Duration),
+    At(Instant),
+    Running { started: Instant, sleep: Sleep },
+}
+
+impl Deadline {
+    /// Start the timer on first poll. Returns the time spent waiting if the
+    /// deadline has passed
+    fn poll_expired(&mut self, cx: &mut Context<'_>) -> Option<Duration> {
+        let start = |at: Instant| Self::Running {
+            started: Instant::now(),
+            sleep: time::sleep_until(at),
+        };
+        match *self {
+            Self::None => return None,
+            Self::After(timeout) => *self = start(Instant::now() + timeout),
+            Self::At(at) => *self = start(at),
+            Self::Running { .. } => {}
+        }
+        let Self::Running { started, sleep } = self else {
+            unreachable!()
+        };
+        Pin::new(sleep)
+            .poll(cx)
+            .is_ready()
+            .then(|| started.elapsed())
+    }
+}
+
+/// A typed JSON-RPC request, resolved by awaiting it.
+///
+/// A call may be given a [timeout](Self::timeout) or a
+/// [deadline](Self::deadline), after which it fails with
+/// [`TransportError::Timeout`]. Natively, timers require a tokio runtime
+/// with the time driver enabled. See [`time`](crate::time)
 #[derive(Debug)]
 pub struct RpcCall<B, T, Params, Resp> {
     state: CallState<B, T, Params>,
+    deadline: Deadline,
     resp: PhantomData<fn() -> Resp>,
 }
 
 impl<B, T, Params, Resp> RpcCall<B, T, Params, Resp> {
-    pub fn new(connection: B, method: &'static str, params: Params, id: Id<'static>) -> Self {
+    pub fn new(
+        connection: B,
+        method: impl Into<Cow<'static, str>>,
+        params: Params,
+        id: Id<'static>,
+    ) -> Self {
         Self {
-            state: CallState::new(connection, method, params, id),
+            state: CallState::new(connection, method.into(), params, id),
+            deadline: Deadline::None,
             resp: PhantomData,
         }
     }
+
+    /// Fail if no response arrives within `timeout` of the call first being
+    /// polled. Replaces any previous timeout or deadline
+    #[must_use = "Builder method outputs must be used"]
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.deadline = Deadline::After(timeout);
+        self
+    }
+
+    /// Fail if no response arrives by `deadline`. Replaces any previous
+    /// timeout or deadline
+    #[must_use = "Builder method outputs must be used"]
+    pub fn deadline(mut self, deadline: Instant) -> Self {
+        self.deadline = Deadline::At(deadline);
+        self
+    }
 }
 
 impl<B, T, Params, Resp> Future for RpcCall<B, T, Params, Resp>
@@ -153,8 +217,17 @@ where
     type Output = Result<Result<Resp, ErrorObjectOwned>, TransportError>;
 
     fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
-        let state = Pin::new(&mut self.get_mut().state);
-        let res = ready!(state.poll(cx));
+        let this = self.get_mut();
+        let res = match Pin::new(&mut this.state).poll(cx) {
+            Poll::Ready(res) => res,
+            Poll::Pending => {
+                let Some(elapsed) = this.deadline.poll_expired(cx) else {
+                    return Poll::Pending;
+                };
+                this.state = CallState::Complete;
+                return Poll::Ready(Err(TransportError::Timeout(elapsed)));
+            }
+        };
 
         match res {
             Ok(Ok(val)) => Poll::Ready(from_json(val.get()).map(Result::Ok)),
@@ -163,3 +236,34 @@ where
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::transports::MockConnection;
+
+    #[tokio::test]
+    async fn it_times_out() {
+        // responds long after the deadlines
+        let conn = MockConnection::new().with_latency(Duration::from_secs(3600));
+        conn.push_response("eth_chainId", "0x1");
+        conn.push_response("eth_chainId", "0x1");
+
+        let res = conn
+            .request::<_, String>("eth_chainId", ())
+            .timeout(Duration::from_millis(10))
+            .await;
+        match res {
+            Err(TransportError::Timeout(elapsed)) => {
+                assert!(elapsed >= Duration::from_millis(10))
+            }
+            other => panic!("expected a timeout, got {other:?}"),
+        }
+
+        let res = conn
+            .request::<_, String>("eth_chainId", ())
+            .deadline(Instant::now())
+            .await;
+        assert!(matches!(res, Err(TransportError::Timeout(_))));
+    }
+}
@@ -6,6 +6,8 @@ pub use jsonrpsee_types::{ErrorObject, ErrorResponse, Id, RequestSer as Request,
 
 use crate::TransportError;
 
+pub use crate::manager::{Handled, RequestManager};
+
 #[cfg(target_arch = "wasm32")]
 pub(crate) type DynFuture<'a, T> = Pin<Box<dyn Future<Output = T> + 'a>>;
 #[cfg(not(target_arch = "wasm32"))]
@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use ethers_pub_use::{serde_json, thiserror};
 
 #[derive(thiserror::Error, Debug)]
@@ -12,9 +14,47 @@ pub enum TransportError {
     /// Http transport
     #[error(transparent)]
     Reqwest(#[from] reqwest::Error),
+
+    /// Any other transport failure, e.g. from a wrapped client
+    #[error(transparent)]
+    Custom(Box<dyn std::error::Error + Send + Sync>),
+
+    /// The endpoint's circuit breaker is open
+    #[error("circuit breaker open")]
+    CircuitOpen,
+
+    /// No response arrived before the call's timeout or deadline. Carries
+    /// the time spent waiting
+    #[error("request timed out after {0:?}")]
+    Timeout(Duration),
+
+    /// A request was registered with the id of a request still awaiting a
+    /// response. Carries the id, as JSON
+    #[error("request id {0} is already in flight")]
+    DuplicateId(String),
+
+    /// No transport is available for the url scheme
+    #[error("unsupported url scheme: {0}")]
+    UnsupportedScheme(String),
 }
 
 impl TransportError {
+    /// A short, stable name for the kind of failure, for logs and metrics
+    pub fn class(&self) -> &'static str {
+        match self {
+            Self::SerdeJson { .. } => "serde",
+            Self::Reqwest(err) if err.is_timeout() => "timeout",
+            Self::Reqwest(err) if err.is_connect() => "connect",
+            Self::Reqwest(err) if err.is_status() => "http_status",
+            Self::Reqwest(_) => "http",
+            Self::Custom(_) => "custom",
+            Self::CircuitOpen => "circuit_open",
+            Self::Timeout(_) => "timeout",
+            Self::DuplicateId(_) => "duplicate_id",
+            Self::UnsupportedScheme(_) => "unsupported_scheme",
+        }
+    }
+
     pub fn ser_err(err: serde_json::Error) -> Self {
         Self::SerdeJson {
             err,
@@ -0,0 +1,380 @@
+use std::{borrow::Cow, fmt, sync::Arc, time::Duration};
+
+use ethers_pub_use::{
+    futures_channel::mpsc,
+    serde_json::{self, value::RawValue},
+    tracing::{self, field, Instrument},
+};
+
+use crate::{
+    common::{BatchRpcFuture, BatchRpcOutcome, DynFuture, Request, RpcFuture, RpcOutcome},
+    time::Instant,
+    Connection, PubSubConnection, TransportError,
+};
+
+/// The default threshold above which a request is reported as slow
+pub const DEFAULT_SLOW_CALL_THRESHOLD: Duration = Duration::from_secs(1);
+
+/// A request that took longer than the configured threshold
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SlowCall {
+    /// The method name. For batches, the comma-separated method names
+    pub method: String,
+    /// Number of requests. 1 for single requests
+    pub batch_len: usize,
+    /// Total size of the serialized params, in bytes
+    pub params_size: usize,
+    /// The endpoint label of the connection
+    pub endpoint: Arc<str>,
+    /// Time from dispatch to response
+    pub elapsed: Duration,
+}
+
+type SlowCallHook = Arc<dyn Fn(&SlowCall) + Send + Sync>;
+
+/// A [`Connection`] wrapper that instruments requests.
+///
+/// Every request and batch runs in a `debug` level `rpc` span, recording its
+/// method, id, endpoint, duration and error class. With the `metrics`
+/// feature, it also records the `ethers_rpc_requests_total` and
+/// `ethers_rpc_errors_total` counters and the
+/// `ethers_rpc_request_duration_seconds` histogram, labelled by method and
+/// endpoint. Batches are labelled with the method `batch`.
+///
+/// Any request or batch that takes longer than the threshold is logged as a
+/// `tracing` warning with its method, params size and endpoint, and passed to
+/// the slow call hook, if one is set. This is useful for finding
+/// pathological queries, like unbounded `eth_getLogs`.
+///
+/// The endpoint label is included in logs as-is. Do not use urls that embed
+/// API keys.
+#[derive(Clone)]
+pub struct Instrumented<T> {
+    inner: T,
+    endpoint: Arc<str>,
+    slow_call_threshold: Option<Duration>,
+    on_slow_call: Option<SlowCallHook>,
+}
+
+impl<T> Instrumented<T> {
+    /// Instantiate a new instrumented connection, with the default slow call
+    /// threshold
+    pub fn new(inner: T, endpoint: impl Into<Arc<str>>) -> Self {
+        Self {
+            inner,
+            endpoint: endpoint.into(),
+            slow_call_threshold: Some(DEFAULT_SLOW_CALL_THRESHOLD),
+            on_slow_call: None,
+        }
+    }
+
+    /// Set the slow call threshold. `None` disables slow call reporting
+    #[must_use = "Builder method outputs must be used"]
+    pub fn with_slow_call_threshold(mut self, threshold: Option<Duration>) -> Self {
+        self.set_slow_call_threshold(threshold);
+        self
+    }
+
+    pub fn set_slow_call_threshold(&mut self, threshold: Option<Duration>) {
+        self.slow_call_threshold = threshold;
+    }
+
+    /// Set a hook that is called with every slow request, in addition to
+    /// the `tracing` warning
+    #[must_use = "Builder method outputs must be used"]
+    pub fn with_slow_call_hook<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&SlowCall) + Send + Sync + 'static,
+    {
+        self.on_slow_call = Some(Arc::new(hook));
+        self
+    }
+
+    /// The endpoint label
+    pub fn endpoint(&self) -> &str {
+        &self.endpoint
+    }
+
+    /// The wrapped connection
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+
+    /// Unwrap the connection
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    /// Wrap a request future in a span recording its duration and error
+    /// class, and report it if it exceeds the threshold. `id` is `None`
+    /// for batches
+    fn watch<O>(
+        &self,
+        fut: DynFuture<'static, O>,
+        method: String,
+        id: Option<String>,
+        batch_len: usize,
+        params_size: usize,
+    ) -> DynFuture<'static, O>
+    where
+        O: Outcome + 'static,
+    {
+        let threshold = self.slow_call_threshold;
+        let endpoint = self.endpoint.clone();
+        let hook = self.on_slow_call.clone();
+        let span = tracing::debug_span!(
+            "rpc",
+            %method,
+            id,
+            batch_len,
+            endpoint = %endpoint,
+            elapsed_ms = field::Empty,
+            error = field::Empty,
+        );
+        let start = Instant::now();
+
+        let fut = async move {
+            let res = fut.await;
+            let elapsed = start.elapsed();
+            let error = res.error_class();
+
+            let span = tracing::Span::current();
+            span.record("elapsed_ms", elapsed.as_millis() as u64);
+            if let Some(error) = error {
+                span.record("error", error);
+            }
+            #[cfg(feature = "metrics")]
+            record_metrics(
+                if id.is_some() { &method } else { "batch" },
+                &endpoint,
+                elapsed,
+                error,
+            );
+
+            if threshold.is_some_and(|threshold| elapsed >= threshold) {
+                let call = SlowCall {
+                    method,
+                    batch_len,
+                    params_size,
+                    endpoint,
+                    elapsed,
+                };
+                tracing::warn!(
+                    method = %call.method,
+                    batch_len,
+                    params_size,
+                    endpoint = %call.endpoint,
+                    elapsed_ms = elapsed.as_millis() as u64,
+                    "slow RPC call"
+                );
+                if let Some(hook) = hook {
+                    hook(&call);
+                }
+            }
+            res
+        };
+        Box::pin(fut.instrument(span))
+    }
+}
+
+/// The outcome of a request or batch, for reporting
+trait Outcome {
+    /// The class of the failure, if any. See [`TransportError::class`].
+    /// JSON-RPC error responses are classed as `rpc`
+    fn error_class(&self) -> Option<&'static str>;
+}
+
+impl Outcome for RpcOutcome {
+    fn error_class(&self) -> Option<&'static str> {
+        match self {
+            Ok(Ok(_)) => None,
+            Ok(Err(_)) => Some("rpc"),
+            Err(err) => Some(err.class()),
+        }
+    }
+}
+
+impl Outcome for BatchRpcOutcome {
+    fn error_class(&self) -> Option<&'static str> {
+        match self {
+            Ok(resps) if resps.iter().any(Result::is_err) => Some("rpc"),
+            Ok(_) => None,
+            Err(err) => Some(err.class()),
+        }
+    }
+}
+
+/// Record the request count, duration and errors. Batches are labelled
+/// with the method `batch`
+#[cfg(feature = "metrics")]
+fn record_metrics(method: &str, endpoint: &str, elapsed: Duration, error: Option<&'static str>) {
+    let labels = [
+        ("method", method.to_owned()),
+        ("endpoint", endpoint.to_owned()),
+    ];
+    metrics::counter!("ethers_rpc_requests_total", &labels).increment(1);
+    metrics::histogram!("ethers_rpc_request_duration_seconds", &labels)
+        .record(elapsed.as_secs_f64());
+    if let Some(class) = error {
+        metrics::counter!(
+            "ethers_rpc_errors_total",
+            "method" => method.to_owned(),
+            "endpoint" => endpoint.to_owned(),
+            "class" => class,
+        )
+        .increment(1);
+    }
+}
+
+fn params_size(req: &Request<'_>) -> usize {
+    req.params.as_ref().map_or(0, |params| params.get().len())
+}
+
+impl<T> fmt::Debug for Instrumented<T>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Instrumented")
+            .field("inner", &self.inner)
+            .field("endpoint", &self.endpoint)
+            .field("slow_call_threshold", &self.slow_call_threshold)
+            .field("on_slow_call", &self.on_slow_call.is_some())
+            .finish()
+    }
+}
+
+impl<T> Connection for Instrumented<T>
+where
+    T: Connection,
+{
+    fn is_local(&self) -> bool {
+        self.inner.is_local()
+    }
+
+    fn increment_id(&self) -> u64 {
+        self.inner.increment_id()
+    }
+
+    fn default_timeout(&self) -> Option<Duration> {
+        self.inner.default_timeout()
+    }
+
+    fn json_rpc_request(&self, req: &Request<'_>) -> RpcFuture {
+        let fut = self.inner.json_rpc_request(req);
+        let id = serde_json::to_string(&req.id).ok();
+        self.watch(fut, req.method.to_string(), id, 1, params_size(req))
+    }
+
+    fn batch_request(&self, reqs: &[Request<'_>]) -> BatchRpcFuture {
+        let fut = self.inner.batch_request(reqs);
+        let method = reqs
+            .iter()
+            .map(|req| req.method.as_ref())
+            .collect::<Vec<_>>()
+            .join(",");
+        let size = reqs.iter().map(params_size).sum();
+        self.watch(fut, method, None, reqs.len(), size)
+    }
+}
+
+impl<T> PubSubConnection for Instrumented<T>
+where
+    T: PubSubConnection,
+{
+    fn uninstall_listener(&self, id: [u8; 32]) -> Result<(), TransportError> {
+        self.inner.uninstall_listener(id)
+    }
+
+    fn install_listener(
+        &self,
+        id: [u8; 32],
+    ) -> Result<mpsc::UnboundedReceiver<Cow<'static, RawValue>>, TransportError> {
+        self.inner.install_listener(id)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Mutex;
+
+    use ethers_pub_use::serde_json::value::to_raw_value;
+
+    use super::*;
+    use crate::transports::MockConnection;
+
+    /// Echoes params
+    fn echo() -> MockConnection {
+        MockConnection::new()
+            .with_handler(|req| Ok(Ok(Cow::Owned(to_raw_value(&req.params).unwrap()))))
+    }
+
+    fn recording(
+        threshold: Option<Duration>,
+    ) -> (Instrumented<MockConnection>, Arc<Mutex<Vec<SlowCall>>>) {
+        let calls: Arc<Mutex<Vec<SlowCall>>> = Default::default();
+        let recorded = calls.clone();
+        let conn = Instrumented::new(echo(), "echo")
+            .with_slow_call_threshold(threshold)
+            .with_slow_call_hook(move |call| recorded.lock().unwrap().push(call.clone()));
+        (conn, calls)
+    }
+
+    #[tokio::test]
+    async fn it_reports_slow_calls() {
+        let (conn, calls) = recording(Some(Duration::ZERO));
+
+        let resp: Vec<u64> = conn
+            .request("eth_getLogs", [1u64, 2])
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(resp, vec![1, 2]);
+
+        let params = to_raw_value(&[1u64]).unwrap();
+        let reqs = vec![
+            Request::owned(conn.next_id(), "eth_chainId", Some(params.clone())),
+            Request::owned(conn.next_id(), "eth_blockNumber", Some(params)),
+        ];
+        conn.batch_request(&reqs).await.unwrap();
+
+        let calls = calls.lock().unwrap();
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[0].method, "eth_getLogs");
+        assert_eq!(calls[0].batch_len, 1);
+        assert_eq!(calls[0].params_size, "[1,2]".len());
+        assert_eq!(&*calls[0].endpoint, "echo");
+        assert_eq!(calls[1].method, "eth_chainId,eth_blockNumber");
+        assert_eq!(calls[1].batch_len, 2);
+        assert_eq!(calls[1].params_size, 2 * "[1]".len());
+    }
+
+    #[tokio::test]
+    async fn it_ignores_fast_calls() {
+        let (conn, calls) = recording(Some(Duration::from_secs(60)));
+        let _: Vec<u64> = conn.request("eth_call", [1u64]).await.unwrap().unwrap();
+        assert!(calls.lock().unwrap().is_empty());
+
+        let (conn, calls) = recording(None);
+        let _: Vec<u64> = conn.request("eth_call", [1u64]).await.unwrap().unwrap();
+        assert!(calls.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn it_classifies_outcomes() {
+        let resp: Cow<'static, RawValue> = Cow::Owned(to_raw_value(&1).unwrap());
+        let err = crate::common::ErrorObject::owned(-32000, "failed", None::<()>);
+
+        let ok: RpcOutcome = Ok(Ok(resp.clone()));
+        assert_eq!(ok.error_class(), None);
+        let rpc_err: RpcOutcome = Ok(Err(err.clone()));
+        assert_eq!(rpc_err.error_class(), Some("rpc"));
+        let circuit_open: RpcOutcome = Err(TransportError::CircuitOpen);
+        assert_eq!(circuit_open.error_class(), Some("circuit_open"));
+
+        let batch: BatchRpcOutcome = Ok(vec![Ok(resp), Err(err)]);
+        assert_eq!(batch.error_class(), Some("rpc"));
+        let batch: BatchRpcOutcome = Err(TransportError::Custom("down".into()));
+        assert_eq!(batch.error_class(), Some("custom"));
+    }
+}
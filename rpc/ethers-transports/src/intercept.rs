@@ -0,0 +1,223 @@
+use std::{borrow::Cow, fmt, sync::Arc, time::Duration};
+
+use ethers_pub_use::{futures_channel::mpsc, serde_json::value::RawValue};
+
+use crate::{
+    common::{BatchRpcFuture, JsonRpcResultOwned, Request, RpcFuture},
+    Connection, PubSubConnection, TransportError,
+};
+
+/// A hook that can modify outgoing requests and inspect their responses,
+/// e.g. to rewrite methods for a provider's proprietary namespace, or to tag
+/// params with tracing ids.
+///
+/// Closures taking `&mut Request<'static>` are interceptors that only modify
+/// requests. Changing a request's id breaks response matching in most
+/// transports
+pub trait RequestInterceptor: Send + Sync {
+    /// Modify a request before it is sent
+    fn on_request(&self, _req: &mut Request<'static>) {}
+
+    /// Inspect the response to a request. `Err` if the transport failed
+    fn on_response(
+        &self,
+        _req: &Request<'static>,
+        _resp: Result<&JsonRpcResultOwned, &TransportError>,
+    ) {
+    }
+}
+
+impl<F> RequestInterceptor for F
+where
+    F: Fn(&mut Request<'static>) + Send + Sync,
+{
+    fn on_request(&self, req: &mut Request<'static>) {
+        self(req)
+    }
+}
+
+/// A [`Connection`] wrapper that passes every request, including each
+/// request of a batch, through a [`RequestInterceptor`]. Nest wrappers to
+/// apply several interceptors
+#[derive(Clone)]
+pub struct Intercepted<T> {
+    inner: T,
+    interceptor: Arc<dyn RequestInterceptor>,
+}
+
+impl<T> Intercepted<T> {
+    /// Instantiate a new intercepted connection
+    pub fn new<I>(inner: T, interceptor: I) -> Self
+    where
+        I: RequestInterceptor + 'static,
+    {
+        Self {
+            inner,
+            interceptor: Arc::new(interceptor),
+        }
+    }
+
+    /// The wrapped connection
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+
+    /// Unwrap the connection
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    /// Copy a request, and pass it to the interceptor
+    fn intercept(&self, req: &Request<'_>) -> Request<'static> {
+        let mut req = Request {
+            jsonrpc: req.jsonrpc,
+            id: req.id.clone().into_owned(),
+            method: req.method.to_string().into(),
+            params: req
+                .params
+                .as_ref()
+                .map(|params| Cow::Owned(params.clone().into_owned())),
+        };
+        self.interceptor.on_request(&mut req);
+        req
+    }
+}
+
+impl<T> fmt::Debug for Intercepted<T>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Intercepted")
+            .field("inner", &self.inner)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T> Connection for Intercepted<T>
+where
+    T: Connection,
+{
+    fn is_local(&self) -> bool {
+        self.inner.is_local()
+    }
+
+    fn increment_id(&self) -> u64 {
+        self.inner.increment_id()
+    }
+
+    fn default_timeout(&self) -> Option<Duration> {
+        self.inner.default_timeout()
+    }
+
+    fn json_rpc_request(&self, req: &Request<'_>) -> RpcFuture {
+        let req = self.intercept(req);
+        let fut = self.inner.json_rpc_request(&req);
+        let interceptor = self.interceptor.clone();
+        Box::pin(async move {
+            let resp = fut.await;
+            interceptor.on_response(&req, resp.as_ref());
+            resp
+        })
+    }
+
+    fn batch_request(&self, reqs: &[Request<'_>]) -> BatchRpcFuture {
+        let reqs: Vec<_> = reqs.iter().map(|req| self.intercept(req)).collect();
+        let fut = self.inner.batch_request(&reqs);
+        let interceptor = self.interceptor.clone();
+        Box::pin(async move {
+            let resps = fut.await;
+            for (i, req) in reqs.iter().enumerate() {
+                match &resps {
+                    Ok(resps) => {
+                        if let Some(resp) = resps.get(i) {
+                            interceptor.on_response(req, Ok(resp));
+                        }
+                    }
+                    Err(err) => interceptor.on_response(req, Err(err)),
+                }
+            }
+            resps
+        })
+    }
+}
+
+impl<T> PubSubConnection for Intercepted<T>
+where
+    T: PubSubConnection,
+{
+    fn uninstall_listener(&self, id: [u8; 32]) -> Result<(), TransportError> {
+        self.inner.uninstall_listener(id)
+    }
+
+    fn install_listener(
+        &self,
+        id: [u8; 32],
+    ) -> Result<mpsc::UnboundedReceiver<Cow<'static, RawValue>>, TransportError> {
+        self.inner.install_listener(id)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Mutex;
+
+    use super::*;
+    use crate::transports::MockConnection;
+
+    /// Records the methods and outcomes of responses
+    #[derive(Clone, Default)]
+    struct Recorder(Arc<Mutex<Vec<String>>>);
+
+    impl RequestInterceptor for Recorder {
+        fn on_response(
+            &self,
+            req: &Request<'static>,
+            resp: Result<&JsonRpcResultOwned, &TransportError>,
+        ) {
+            let outcome = match resp {
+                Ok(Ok(_)) => "ok",
+                Ok(Err(_)) => "rpc error",
+                Err(_) => "transport error",
+            };
+            self.0
+                .lock()
+                .unwrap()
+                .push(format!("{}: {outcome}", req.method));
+        }
+    }
+
+    #[tokio::test]
+    async fn it_rewrites_requests() {
+        let mock = MockConnection::new();
+        mock.push_response("alchemy_getTokenBalances", "0x1");
+        let conn = Intercepted::new(mock, |req: &mut Request<'static>| {
+            if req.method == "eth_getTokenBalances" {
+                req.method = "alchemy_getTokenBalances".into();
+            }
+        });
+
+        let resp: String = conn
+            .request("eth_getTokenBalances", ())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(resp, "0x1");
+    }
+
+    #[tokio::test]
+    async fn it_inspects_responses() {
+        let mock = MockConnection::new();
+        mock.push_response("eth_chainId", "0x1");
+        mock.push_error("eth_blockNumber", -32000, "gone");
+        let recorder = Recorder::default();
+        let conn = Intercepted::new(mock, recorder.clone());
+
+        let _ = conn.request::<_, String>("eth_chainId", ()).await;
+        let _ = conn.request::<_, String>("eth_blockNumber", ()).await;
+        assert_eq!(
+            *recorder.0.lock().unwrap(),
+            vec!["eth_chainId: ok", "eth_blockNumber: rpc error"]
+        );
+    }
+}
@@ -0,0 +1,116 @@
+//! JWT authentication, as required by the Engine API.
+//!
+//! Engine API endpoints authenticate each request with an HS256 token whose
+//! `iat` claim must be within 60 seconds of the node's clock, so tokens are
+//! issued per request rather than once per connection. See the
+//! [Engine API authentication spec].
+//!
+//! [Engine API authentication spec]: https://github.com/ethereum/execution-apis/blob/main/src/engine/authentication.md
+
+use std::{fmt, str::FromStr};
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use ethers_pub_use::{hex, thiserror};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+use crate::{
+    common::Authorization,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// The length of a JWT secret, in bytes
+pub const JWT_SECRET_LEN: usize = 32;
+
+/// The JWT secret could not be parsed
+#[derive(Debug, thiserror::Error)]
+#[error("JWT secret must be {JWT_SECRET_LEN} hex-encoded bytes")]
+pub struct InvalidJwtSecret;
+
+/// A 256-bit secret shared with the node, e.g. the contents of geth's
+/// `jwt.hex`
+#[derive(Clone, PartialEq, Eq)]
+pub struct JwtSecret([u8; JWT_SECRET_LEN]);
+
+impl JwtSecret {
+    /// Instantiate a secret from its bytes
+    pub fn new(secret: [u8; JWT_SECRET_LEN]) -> Self {
+        Self(secret)
+    }
+
+    /// Issue a token with the given `iat` claim, in seconds since the unix
+    /// epoch
+    pub fn token_at(&self, iat: u64) -> String {
+        let header = URL_SAFE_NO_PAD.encode(r#"{"alg":"HS256","typ":"JWT"}"#);
+        let claims = URL_SAFE_NO_PAD.encode(format!(r#"{{"iat":{iat}}}"#));
+        let message = format!("{header}.{claims}");
+
+        let mut mac = Hmac::<Sha256>::new_from_slice(&self.0).expect("any key length");
+        mac.update(message.as_bytes());
+        let signature = URL_SAFE_NO_PAD.encode(mac.finalize().into_bytes());
+        format!("{message}.{signature}")
+    }
+
+    /// Issue a token for the current time
+    pub fn token(&self) -> String {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        self.token_at(now)
+    }
+
+    /// Bearer authorization with a token for the current time
+    pub fn authorization(&self) -> Authorization {
+        Authorization::bearer(self.token())
+    }
+}
+
+impl FromStr for JwtSecret {
+    type Err = InvalidJwtSecret;
+
+    /// Parse a hex-encoded secret. Surrounding whitespace and a `0x` prefix
+    /// are accepted
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let mut secret = [0u8; JWT_SECRET_LEN];
+        hex::decode_to_slice(s.strip_prefix("0x").unwrap_or(s), &mut secret)
+            .map_err(|_| InvalidJwtSecret)?;
+        Ok(Self(secret))
+    }
+}
+
+impl fmt::Debug for JwtSecret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("JwtSecret(..)")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_issues_tokens() {
+        let secret: JwtSecret =
+            "0x7365637265747365637265747365637265747365637265747365637265747365\n"
+                .parse()
+                .unwrap();
+        let token = secret.token_at(1_700_000_000);
+
+        let parts: Vec<_> = token.split('.').collect();
+        assert_eq!(parts.len(), 3);
+        assert_eq!(
+            URL_SAFE_NO_PAD.decode(parts[1]).unwrap(),
+            br#"{"iat":1700000000}"#
+        );
+
+        let mut mac = Hmac::<Sha256>::new_from_slice(&secret.0).unwrap();
+        mac.update(format!("{}.{}", parts[0], parts[1]).as_bytes());
+        mac.verify_slice(&URL_SAFE_NO_PAD.decode(parts[2]).unwrap())
+            .unwrap();
+
+        assert_eq!(format!("{secret:?}"), "JwtSecret(..)");
+        assert!("0x1234".parse::<JwtSecret>().is_err());
+    }
+}
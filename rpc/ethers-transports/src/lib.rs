@@ -16,10 +16,43 @@ pub(crate) mod utils;
 mod error;
 pub use error::TransportError;
 
+mod breaker;
+pub use breaker::{
+    BreakerState, CircuitBreaker, DEFAULT_FAILURE_RATE, DEFAULT_MIN_REQUESTS,
+    DEFAULT_OPEN_DURATION, DEFAULT_PROBES, DEFAULT_WINDOW,
+};
+
 mod call;
+pub use call::RpcCall;
+
+mod intercept;
+pub use intercept::{Intercepted, RequestInterceptor};
+
+mod instrumented;
+pub use instrumented::{Instrumented, SlowCall, DEFAULT_SLOW_CALL_THRESHOLD};
+
+pub mod jwt;
+pub use jwt::JwtSecret;
+
+pub mod listeners;
+pub use listeners::{ListenerStats, Listeners, DEFAULT_MAX_LISTENERS};
+
+mod manager;
+
+#[cfg(feature = "raw-logs")]
+mod raw_logs;
+
+mod throttle;
+pub use throttle::{RateLimit, Throttle};
+
+pub mod time;
 
 mod transport;
-pub use transport::{Connection, PubSubConnection};
+pub use transport::{connect, BoxedConnection, Connection, PubSubConnection};
 
 pub mod transports;
+#[cfg(target_arch = "wasm32")]
+pub use transports::Eip1193;
 pub use transports::Http;
+#[cfg(feature = "jsonrpsee")]
+pub use transports::JsonRpseeClient;
@@ -0,0 +1,208 @@
+//! A registry of subscription listeners for [`PubSubConnection`]
+//! implementations.
+//!
+//! Servers may drop subscriptions without notice, and consumers may drop
+//! their receivers without unsubscribing. The registry bounds its size,
+//! evicting the least recently active listener when full, and sweeps
+//! listeners whose receiver is gone or, optionally, which have been idle
+//! for too long.
+//!
+//! [`PubSubConnection`]: crate::PubSubConnection
+
+use std::{borrow::Cow, collections::HashMap, time::Duration};
+
+use ethers_pub_use::{
+    futures_channel::mpsc::{self, UnboundedReceiver, UnboundedSender},
+    serde_json::value::RawValue,
+    tracing,
+};
+
+use crate::time::Instant;
+
+/// The default maximum number of listeners
+pub const DEFAULT_MAX_LISTENERS: usize = 1024;
+
+#[derive(Debug)]
+struct Listener {
+    sender: UnboundedSender<Cow<'static, RawValue>>,
+    last_active: Instant,
+}
+
+/// Counts of the listeners in a registry
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ListenerStats {
+    /// Installed listeners
+    pub active: usize,
+    /// Listeners evicted because the registry was full
+    pub evicted: u64,
+    /// Listeners removed by sweeps
+    pub swept: u64,
+}
+
+/// Subscription listeners, keyed by subscription id
+#[derive(Debug)]
+pub struct Listeners {
+    listeners: HashMap<[u8; 32], Listener>,
+    capacity: usize,
+    max_idle: Option<Duration>,
+    evicted: u64,
+    swept: u64,
+}
+
+impl Default for Listeners {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_LISTENERS)
+    }
+}
+
+impl Listeners {
+    /// Instantiate a registry holding at most `capacity` listeners
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            listeners: Default::default(),
+            capacity: capacity.max(1),
+            max_idle: None,
+            evicted: 0,
+            swept: 0,
+        }
+    }
+
+    /// Sweep listeners that have not received a notification for
+    /// `max_idle`. Off by default, as quiet subscriptions, e.g. for rare
+    /// logs, are indistinguishable from dropped ones
+    #[must_use = "Builder method outputs must be used"]
+    pub fn with_max_idle(mut self, max_idle: Duration) -> Self {
+        self.max_idle = Some(max_idle);
+        self
+    }
+
+    /// Install a listener, replacing any listener with the same id. Sweeps
+    /// dead listeners, then evicts the least recently active listener if
+    /// the registry is full
+    pub fn install(&mut self, id: [u8; 32]) -> UnboundedReceiver<Cow<'static, RawValue>> {
+        self.sweep();
+        if !self.listeners.contains_key(&id) && self.listeners.len() >= self.capacity {
+            let oldest = self
+                .listeners
+                .iter()
+                .min_by_key(|(_, listener)| listener.last_active)
+                .map(|(id, _)| *id);
+            if let Some(oldest) = oldest {
+                self.listeners.remove(&oldest);
+                self.evicted += 1;
+                tracing::warn!(
+                    capacity = self.capacity,
+                    "listener registry full, evicted least recently active subscription"
+                );
+            }
+        }
+
+        let (sender, receiver) = mpsc::unbounded();
+        let listener = Listener {
+            sender,
+            last_active: Instant::now(),
+        };
+        self.listeners.insert(id, listener);
+        receiver
+    }
+
+    /// Remove a listener
+    pub fn uninstall(&mut self, id: &[u8; 32]) {
+        self.listeners.remove(id);
+    }
+
+    /// Deliver a notification. Returns `false` if there is no listener for
+    /// the id, or if its receiver was dropped, in which case it is removed
+    pub fn deliver(&mut self, id: &[u8; 32], notification: Cow<'static, RawValue>) -> bool {
+        let Some(listener) = self.listeners.get_mut(id) else {
+            return false;
+        };
+        if listener.sender.unbounded_send(notification).is_err() {
+            self.listeners.remove(id);
+            self.swept += 1;
+            return false;
+        }
+        listener.last_active = Instant::now();
+        true
+    }
+
+    /// Remove listeners whose receiver was dropped, or which have been idle
+    /// for longer than the maximum idle time. Returns the number removed
+    pub fn sweep(&mut self) -> usize {
+        let before = self.listeners.len();
+        let max_idle = self.max_idle;
+        self.listeners.retain(|_, listener| {
+            !listener.sender.is_closed()
+                && max_idle.is_none_or(|max| listener.last_active.elapsed() <= max)
+        });
+        let removed = before - self.listeners.len();
+        if removed > 0 {
+            self.swept += removed as u64;
+            tracing::debug!(removed, active = self.listeners.len(), "swept listeners");
+        }
+        removed
+    }
+
+    /// The number of installed listeners
+    pub fn len(&self) -> usize {
+        self.listeners.len()
+    }
+
+    /// `true` if no listeners are installed
+    pub fn is_empty(&self) -> bool {
+        self.listeners.is_empty()
+    }
+
+    /// Counts of active, evicted and swept listeners
+    pub fn stats(&self) -> ListenerStats {
+        ListenerStats {
+            active: self.listeners.len(),
+            evicted: self.evicted,
+            swept: self.swept,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn notification() -> Cow<'static, RawValue> {
+        Cow::Owned(RawValue::from_string("1".to_owned()).unwrap())
+    }
+
+    #[test]
+    fn it_evicts_the_least_recently_active_listener() {
+        let mut listeners = Listeners::new(2);
+        let _a = listeners.install([1; 32]);
+        let _b = listeners.install([2; 32]);
+        assert!(listeners.deliver(&[1; 32], notification()));
+
+        let _c = listeners.install([3; 32]);
+        assert_eq!(listeners.len(), 2);
+        assert!(!listeners.deliver(&[2; 32], notification()));
+        assert!(listeners.deliver(&[1; 32], notification()));
+        assert_eq!(listeners.stats().evicted, 1);
+    }
+
+    #[test]
+    fn it_sweeps_dead_listeners() {
+        let mut listeners = Listeners::default();
+        let a = listeners.install([1; 32]);
+        let _b = listeners.install([2; 32]);
+        drop(a);
+        assert_eq!(listeners.sweep(), 1);
+
+        let mut listeners = listeners.with_max_idle(Duration::ZERO);
+        std::thread::sleep(Duration::from_millis(2));
+        assert_eq!(listeners.sweep(), 1);
+        assert_eq!(
+            listeners.stats(),
+            ListenerStats {
+                active: 0,
+                evicted: 0,
+                swept: 2,
+            }
+        );
+    }
+}
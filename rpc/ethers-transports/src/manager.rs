@@ -0,0 +1,306 @@
+//! Matching of responses to requests, for transports that multiplex
+//! requests and subscriptions over one connection, like websockets and IPC.
+//!
+//! Servers may answer requests, and the requests of a batch, in any order,
+//! and interleave subscription notifications with responses. The transport
+//! registers each request's id before sending it, and passes every incoming
+//! message to the manager, which routes responses to their requests and
+//! notifications to their [`Listeners`].
+
+use std::{
+    borrow::Cow,
+    collections::{HashMap, HashSet},
+};
+
+use ethers_pub_use::{
+    futures_channel::oneshot,
+    hex,
+    serde::Deserialize,
+    serde_json::{self, value::RawValue},
+    tracing,
+};
+use jsonrpsee_types::{ErrorResponse, Notification, Response};
+
+use crate::{
+    common::{Id, JsonRpcResultOwned},
+    listeners::Listeners,
+    TransportError,
+};
+
+#[derive(Debug)]
+enum Pending {
+    Single(oneshot::Sender<JsonRpcResultOwned>),
+    Batch { batch: u64, index: usize },
+}
+
+#[derive(Debug)]
+struct PendingBatch {
+    results: Vec<Option<JsonRpcResultOwned>>,
+    remaining: usize,
+    sender: oneshot::Sender<Vec<JsonRpcResultOwned>>,
+}
+
+/// The `params` of a subscription notification
+#[derive(Deserialize)]
+#[serde(crate = "ethers_pub_use::serde")]
+struct SubscriptionParams<'a> {
+    #[serde(borrow)]
+    subscription: Cow<'a, str>,
+    #[serde(borrow)]
+    result: &'a RawValue,
+}
+
+/// Counts of what an incoming message contained
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Handled {
+    /// Responses matched to a pending request
+    pub responses: usize,
+    /// Notifications delivered to a listener
+    pub notifications: usize,
+    /// Responses and notifications nothing was waiting for
+    pub unmatched: usize,
+}
+
+/// Pending requests and subscription listeners of a connection
+#[derive(Debug, Default)]
+pub struct RequestManager {
+    pending: HashMap<Id<'static>, Pending>,
+    batches: HashMap<u64, PendingBatch>,
+    next_batch: u64,
+    listeners: Listeners,
+}
+
+impl RequestManager {
+    /// Instantiate a manager routing notifications to `listeners`
+    pub fn new(listeners: Listeners) -> Self {
+        Self {
+            listeners,
+            ..Default::default()
+        }
+    }
+
+    /// The subscription listeners
+    pub fn listeners(&mut self) -> &mut Listeners {
+        &mut self.listeners
+    }
+
+    /// The number of requests awaiting a response. A batch counts each of
+    /// its requests
+    pub fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// `true` if no requests are awaiting a response
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// Register a request before sending it. The receiver resolves with its
+    /// response. Fails if a request with the same id is pending
+    pub fn register(
+        &mut self,
+        id: Id<'static>,
+    ) -> Result<oneshot::Receiver<JsonRpcResultOwned>, TransportError> {
+        if self.pending.contains_key(&id) {
+            return Err(TransportError::DuplicateId(id_string(&id)));
+        }
+        let (sender, receiver) = oneshot::channel();
+        self.pending.insert(id, Pending::Single(sender));
+        Ok(receiver)
+    }
+
+    /// Register the requests of a batch before sending it. The receiver
+    /// resolves once every request has a response, with the responses in
+    /// the order of `ids`. Fails if any id is repeated or pending
+    pub fn register_batch(
+        &mut self,
+        ids: Vec<Id<'static>>,
+    ) -> Result<oneshot::Receiver<Vec<JsonRpcResultOwned>>, TransportError> {
+        let mut seen = HashSet::with_capacity(ids.len());
+        if let Some(id) = ids
+            .iter()
+            .find(|id| self.pending.contains_key(*id) || !seen.insert(*id))
+        {
+            return Err(TransportError::DuplicateId(id_string(id)));
+        }
+
+        let (sender, receiver) = oneshot::channel();
+        if ids.is_empty() {
+            let _ = sender.send(vec![]);
+            return Ok(receiver);
+        }
+
+        let batch = self.next_batch;
+        self.next_batch += 1;
+        self.batches.insert(
+            batch,
+            PendingBatch {
+                results: ids.iter().map(|_| None).collect(),
+                remaining: ids.len(),
+                sender,
+            },
+        );
+        for (index, id) in ids.into_iter().enumerate() {
+            self.pending.insert(id, Pending::Batch { batch, index });
+        }
+        Ok(receiver)
+    }
+
+    /// Drop all pending requests, e.g. when the connection closes. Their
+    /// receivers resolve with [`oneshot::Canceled`]
+    pub fn cancel_all(&mut self) {
+        self.pending.clear();
+        self.batches.clear();
+    }
+
+    /// Route an incoming message: a response, a notification, or an array
+    /// of them. Fails if the message is not valid JSON-RPC
+    pub fn handle_message(&mut self, msg: &str) -> Result<Handled, TransportError> {
+        let mut handled = Handled::default();
+        if msg.trim_start().starts_with('[') {
+            let items: Vec<&RawValue> =
+                serde_json::from_str(msg).map_err(|err| TransportError::deser_err(err, msg))?;
+            for item in items {
+                self.handle_item(item.get(), &mut handled)?;
+            }
+        } else {
+            self.handle_item(msg, &mut handled)?;
+        }
+        Ok(handled)
+    }
+
+    fn handle_item(&mut self, item: &str, handled: &mut Handled) -> Result<(), TransportError> {
+        let (id, resp) = if let Ok(err) = serde_json::from_str::<ErrorResponse<'_>>(item) {
+            let resp = Err(err.error_object().clone().into_owned());
+            (err.id().clone().into_owned(), resp)
+        } else if let Ok(resp) = serde_json::from_str::<Response<'_, Cow<'_, RawValue>>>(item) {
+            (
+                resp.id.into_owned(),
+                Ok(Cow::Owned(resp.result.into_owned())),
+            )
+        } else {
+            let notification: Notification<'_, SubscriptionParams<'_>> =
+                serde_json::from_str(item).map_err(|err| TransportError::deser_err(err, item))?;
+            let params = notification.params;
+            let delivered = subscription_key(&params.subscription).is_some_and(|key| {
+                let result = Cow::Owned(params.result.to_owned());
+                self.listeners.deliver(&key, result)
+            });
+            if delivered {
+                handled.notifications += 1;
+            } else {
+                tracing::debug!(subscription = %params.subscription, "notification without a listener");
+                handled.unmatched += 1;
+            }
+            return Ok(());
+        };
+
+        if self.resolve(&id, resp) {
+            handled.responses += 1;
+        } else {
+            tracing::debug!(id = %id_string(&id), "response without a pending request");
+            handled.unmatched += 1;
+        }
+        Ok(())
+    }
+
+    /// Send a response to its request. Returns `false` if no request with
+    /// the id is pending
+    fn resolve(&mut self, id: &Id<'static>, resp: JsonRpcResultOwned) -> bool {
+        match self.pending.remove(id) {
+            None => false,
+            Some(Pending::Single(sender)) => {
+                let _ = sender.send(resp);
+                true
+            }
+            Some(Pending::Batch { batch, index }) => {
+                let Some(pending) = self.batches.get_mut(&batch) else {
+                    return false;
+                };
+                pending.results[index] = Some(resp);
+                pending.remaining -= 1;
+                if pending.remaining == 0 {
+                    let pending = self.batches.remove(&batch).expect("batch is pending");
+                    let results = pending.results.into_iter().flatten().collect();
+                    let _ = pending.sender.send(results);
+                }
+                true
+            }
+        }
+    }
+}
+
+/// The listener key of a subscription id: the id's hex value, right-aligned
+/// in 32 bytes
+pub(crate) fn subscription_key(subscription: &str) -> Option<[u8; 32]> {
+    let digits = subscription.strip_prefix("0x").unwrap_or(subscription);
+    if digits.len() > 64 {
+        return None;
+    }
+    let padded = format!("{digits:0>64}");
+    let mut key = [0u8; 32];
+    hex::decode_to_slice(padded, &mut key).ok()?;
+    Some(key)
+}
+
+fn id_string(id: &Id<'_>) -> String {
+    serde_json::to_string(id).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_matches_out_of_order_batch_responses() {
+        let mut manager = RequestManager::default();
+        let mut batch = manager
+            .register_batch(vec![Id::Number(1), Id::Number(2)])
+            .unwrap();
+        let mut single = manager.register(Id::Str("a".into())).unwrap();
+        assert!(manager.register(Id::Number(2)).is_err());
+        assert_eq!(manager.len(), 3);
+
+        let handled = manager
+            .handle_message(
+                r#"[{"jsonrpc":"2.0","id":2,"result":"0x2"},{"jsonrpc":"2.0","id":7,"result":null}]"#,
+            )
+            .unwrap();
+        assert_eq!(handled.responses, 1);
+        assert_eq!(handled.unmatched, 1);
+        assert!(batch.try_recv().unwrap().is_none());
+
+        manager
+            .handle_message(
+                r#"{"jsonrpc":"2.0","id":"a","error":{"code":-32000,"message":"gone"}}"#,
+            )
+            .unwrap();
+        manager
+            .handle_message(r#"{"jsonrpc":"2.0","id":1,"result":null}"#)
+            .unwrap();
+        assert!(manager.is_empty());
+
+        let results = batch.try_recv().unwrap().unwrap();
+        assert_eq!(results[0].as_ref().unwrap().get(), "null");
+        assert_eq!(results[1].as_ref().unwrap().get(), r#""0x2""#);
+        let err = single.try_recv().unwrap().unwrap().unwrap_err();
+        assert_eq!(err.message(), "gone");
+    }
+
+    #[test]
+    fn it_delivers_notifications() {
+        let mut manager = RequestManager::default();
+        let key = subscription_key("0x9cef478923ff08bf67fde6c64013158d").unwrap();
+        let mut listener = manager.listeners().install(key);
+
+        let handled = manager
+            .handle_message(
+                r#"{"jsonrpc":"2.0","method":"eth_subscription","params":{"subscription":"0x9cef478923ff08bf67fde6c64013158d","result":"0x1"}}"#,
+            )
+            .unwrap();
+        assert_eq!(handled.notifications, 1);
+        assert_eq!(listener.try_recv().unwrap().get(), r#""0x1""#);
+
+        assert!(manager.handle_message(r#"{"jsonrpc":"2.0"}"#).is_err());
+    }
+}
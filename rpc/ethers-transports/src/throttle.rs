@@ -0,0 +1,420 @@
+use std::{
+    borrow::Cow,
+    fmt,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use ethers_pub_use::{futures_channel::mpsc, serde_json::value::RawValue, tracing};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+use crate::{
+    common::{BatchRpcFuture, Request, RpcFuture},
+    time::{self, Instant},
+    Connection, PubSubConnection, TransportError,
+};
+
+/// A rate limit and concurrency limit for a class of methods
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateLimit {
+    per_second: Option<f64>,
+    burst: u32,
+    max_in_flight: Option<usize>,
+}
+
+impl Default for RateLimit {
+    fn default() -> Self {
+        Self::unlimited()
+    }
+}
+
+impl RateLimit {
+    /// No rate limit, and no limit on requests in flight
+    pub const fn unlimited() -> Self {
+        Self {
+            per_second: None,
+            burst: 1,
+            max_in_flight: None,
+        }
+    }
+
+    /// Allow `rate` requests per second on average. Bursts of up to one
+    /// second's worth of requests are allowed, see [`RateLimit::with_burst`]
+    pub fn per_second(rate: f64) -> Self {
+        let rate = rate.max(f64::MIN_POSITIVE);
+        Self {
+            per_second: Some(rate),
+            burst: 1,
+            max_in_flight: None,
+        }
+        .with_burst(rate.ceil().min(u32::MAX as f64) as u32)
+    }
+
+    /// Set the number of requests that may be sent at once after a quiet
+    /// period. At least 1
+    #[must_use = "Builder method outputs must be used"]
+    pub fn with_burst(mut self, burst: u32) -> Self {
+        self.burst = burst.max(1);
+        self
+    }
+
+    /// Set the maximum number of requests awaiting a response. A batch
+    /// counts as one request
+    #[must_use = "Builder method outputs must be used"]
+    pub fn with_max_in_flight(mut self, max_in_flight: usize) -> Self {
+        self.max_in_flight = Some(max_in_flight.max(1));
+        self
+    }
+}
+
+/// A token bucket. Tokens may go negative, reserving future capacity for
+/// waiting requests in arrival order
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+/// The state shared by the methods of a class
+#[derive(Debug)]
+struct Class {
+    methods: Vec<Cow<'static, str>>,
+    limit: RateLimit,
+    bucket: Mutex<Bucket>,
+    in_flight: Option<Arc<Semaphore>>,
+}
+
+impl Class {
+    fn new(methods: Vec<Cow<'static, str>>, limit: RateLimit) -> Self {
+        Self {
+            methods,
+            limit,
+            bucket: Mutex::new(Bucket {
+                tokens: limit.burst as f64,
+                updated: Instant::now(),
+            }),
+            in_flight: limit.max_in_flight.map(|n| Arc::new(Semaphore::new(n))),
+        }
+    }
+
+    /// True if the method is listed, or matches a listed `prefix*`
+    fn matches(&self, method: &str) -> bool {
+        self.methods.iter().any(|m| match m.strip_suffix('*') {
+            Some(prefix) => method.starts_with(prefix),
+            None => method == m,
+        })
+    }
+
+    /// Take `n` tokens, returning the time to wait before sending
+    fn reserve(&self, n: usize) -> Duration {
+        let Some(rate) = self.limit.per_second else {
+            return Duration::ZERO;
+        };
+        let mut bucket = self.bucket.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Instant::now();
+        let refill = now.duration_since(bucket.updated).as_secs_f64() * rate;
+        bucket.tokens = (bucket.tokens + refill).min(self.limit.burst as f64) - n as f64;
+        bucket.updated = now;
+        if bucket.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-bucket.tokens / rate)
+        }
+    }
+
+    async fn acquire(&self) -> Option<OwnedSemaphorePermit> {
+        match &self.in_flight {
+            Some(semaphore) => semaphore.clone().acquire_owned().await.ok(),
+            None => None,
+        }
+    }
+}
+
+/// A [`Connection`] wrapper that limits the rate and concurrency of
+/// requests, e.g. to stay within the limits of a hosted RPC plan.
+///
+/// Methods are grouped into classes, each with its own [`RateLimit`].
+/// Methods that are not in any class share the default limit. Requests
+/// over the rate limit wait for capacity, in the order they were sent.
+/// Every request in a batch counts against the rate limit of its class, and
+/// the batch holds one in-flight slot of each class it contains.
+///
+/// ```
+/// use ethers_transports::{transports::MockConnection, RateLimit, Throttle};
+///
+/// let throttle = Throttle::new(MockConnection::new(), RateLimit::per_second(25.0))
+///     .with_class(
+///         ["eth_getLogs", "trace_*", "debug_*"],
+///         RateLimit::per_second(2.0).with_max_in_flight(1),
+///     );
+/// ```
+pub struct Throttle<T> {
+    inner: Arc<T>,
+    default: Arc<Class>,
+    classes: Vec<Arc<Class>>,
+}
+
+impl<T> Clone for Throttle<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            default: self.default.clone(),
+            classes: self.classes.clone(),
+        }
+    }
+}
+
+impl<T> Throttle<T> {
+    /// Instantiate a throttle applying `limit` to every method
+    pub fn new(inner: T, limit: RateLimit) -> Self {
+        Self {
+            inner: Arc::new(inner),
+            default: Arc::new(Class::new(vec![], limit)),
+            classes: vec![],
+        }
+    }
+
+    /// Add a class of methods sharing `limit`. A method ending in `*`
+    /// matches any method with that prefix. If a method matches several
+    /// classes, the first one added applies
+    #[must_use = "Builder method outputs must be used"]
+    pub fn with_class<I>(mut self, methods: I, limit: RateLimit) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<Cow<'static, str>>,
+    {
+        let methods = methods.into_iter().map(Into::into).collect();
+        self.classes.push(Arc::new(Class::new(methods, limit)));
+        self
+    }
+
+    /// The wrapped connection
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+
+    /// The limit applied to a method
+    pub fn limit(&self, method: &str) -> RateLimit {
+        self.class(method).limit
+    }
+
+    fn class(&self, method: &str) -> &Arc<Class> {
+        self.classes
+            .iter()
+            .find(|class| class.matches(method))
+            .unwrap_or(&self.default)
+    }
+}
+
+impl<T> fmt::Debug for Throttle<T>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Throttle")
+            .field("inner", &self.inner)
+            .field("default", &self.default.limit)
+            .field("classes", &self.classes.len())
+            .finish()
+    }
+}
+
+fn to_owned(req: &Request<'_>) -> Request<'static> {
+    Request::owned(
+        req.id.clone().into_owned(),
+        req.method.clone().into_owned(),
+        req.params
+            .as_ref()
+            .map(|params| params.clone().into_owned()),
+    )
+}
+
+async fn wait(method: &str, wait: Duration) {
+    if !wait.is_zero() {
+        tracing::debug!(method, wait_ms = wait.as_millis() as u64, "throttled");
+        time::sleep(wait).await;
+    }
+}
+
+impl<T> Connection for Throttle<T>
+where
+    T: Connection + 'static,
+{
+    fn is_local(&self) -> bool {
+        self.inner.is_local()
+    }
+
+    fn increment_id(&self) -> u64 {
+        self.inner.increment_id()
+    }
+
+    fn default_timeout(&self) -> Option<Duration> {
+        self.inner.default_timeout()
+    }
+
+    fn json_rpc_request(&self, req: &Request<'_>) -> RpcFuture {
+        let inner = self.inner.clone();
+        let class = self.class(&req.method).clone();
+        let req = to_owned(req);
+        Box::pin(async move {
+            let _permit = class.acquire().await;
+            wait(&req.method, class.reserve(1)).await;
+            inner.json_rpc_request(&req).await
+        })
+    }
+
+    fn batch_request(&self, reqs: &[Request<'_>]) -> BatchRpcFuture {
+        let inner = self.inner.clone();
+        let mut classes: Vec<(Arc<Class>, usize)> = vec![];
+        for req in reqs {
+            let class = self.class(&req.method);
+            match classes.iter_mut().find(|(c, _)| Arc::ptr_eq(c, class)) {
+                Some((_, n)) => *n += 1,
+                None => classes.push((class.clone(), 1)),
+            }
+        }
+        // acquire in a consistent order, so concurrent batches cannot
+        // deadlock
+        classes.sort_by_key(|(class, _)| Arc::as_ptr(class));
+        let reqs: Vec<_> = reqs.iter().map(to_owned).collect();
+        Box::pin(async move {
+            let mut permits = Vec::with_capacity(classes.len());
+            for (class, _) in &classes {
+                permits.push(class.acquire().await);
+            }
+            let delay = classes
+                .iter()
+                .map(|(class, n)| class.reserve(*n))
+                .max()
+                .unwrap_or_default();
+            wait("batch", delay).await;
+            inner.batch_request(&reqs).await
+        })
+    }
+}
+
+impl<T> PubSubConnection for Throttle<T>
+where
+    T: PubSubConnection + 'static,
+{
+    fn uninstall_listener(&self, id: [u8; 32]) -> Result<(), TransportError> {
+        self.inner.uninstall_listener(id)
+    }
+
+    fn install_listener(
+        &self,
+        id: [u8; 32],
+    ) -> Result<mpsc::UnboundedReceiver<Cow<'static, RawValue>>, TransportError> {
+        self.inner.install_listener(id)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ethers_pub_use::serde_json::value::to_raw_value;
+
+    use super::*;
+    use crate::{common::Id, transports::MockConnection};
+
+    /// Responds after a delay
+    fn slow() -> MockConnection {
+        MockConnection::new()
+            .with_latency(Duration::from_millis(10))
+            .with_handler(|_| Ok(Ok(Cow::Owned(to_raw_value(&1u64).unwrap()))))
+    }
+
+    fn request(method: &'static str) -> Request<'static> {
+        Request::owned(Id::Number(0), method, None)
+    }
+
+    #[test]
+    fn it_classifies_methods() {
+        let throttle = Throttle::new(slow(), RateLimit::unlimited())
+            .with_class(["eth_getLogs", "trace_*"], RateLimit::per_second(2.0))
+            .with_class(["trace_block"], RateLimit::per_second(1.0));
+
+        assert_eq!(throttle.limit("eth_chainId"), RateLimit::unlimited());
+        assert_eq!(throttle.limit("eth_getLogs"), RateLimit::per_second(2.0));
+        assert_eq!(throttle.limit("trace_block"), RateLimit::per_second(2.0));
+        assert_eq!(
+            RateLimit::per_second(2.5),
+            RateLimit::per_second(2.5).with_burst(3)
+        );
+    }
+
+    /// Responds immediately, so elapsed time is all throttling
+    fn fast() -> MockConnection {
+        MockConnection::new().with_handler(|_| Ok(Ok(Cow::Owned(to_raw_value(&1u64).unwrap()))))
+    }
+
+    /// True if `wait` is `expected`, give or take the refill while testing
+    fn about(wait: Duration, expected: Duration) -> bool {
+        wait <= expected && wait + Duration::from_millis(5) >= expected
+    }
+
+    #[test]
+    fn it_reserves_tokens() {
+        let class = Class::new(vec![], RateLimit::per_second(10.0).with_burst(2));
+        assert_eq!(class.reserve(1), Duration::ZERO);
+        assert_eq!(class.reserve(1), Duration::ZERO);
+        // the bucket is empty, waiting requests queue up behind each other
+        assert!(about(class.reserve(1), Duration::from_millis(100)));
+        assert!(about(class.reserve(1), Duration::from_millis(200)));
+        assert!(about(class.reserve(3), Duration::from_millis(500)));
+
+        let class = Class::new(vec![], RateLimit::unlimited());
+        assert_eq!(class.reserve(1_000), Duration::ZERO);
+    }
+
+    #[tokio::test]
+    async fn it_limits_the_rate() {
+        let throttle = Throttle::new(fast(), RateLimit::per_second(20.0).with_burst(1));
+        let start = Instant::now();
+        for _ in 0..3 {
+            throttle
+                .json_rpc_request(&request("eth_chainId"))
+                .await
+                .unwrap()
+                .unwrap();
+        }
+        // the first request uses the burst, the others wait 50ms each
+        assert!(start.elapsed() >= Duration::from_millis(95));
+
+        let reqs = [request("eth_chainId"), request("eth_chainId")];
+        let start = Instant::now();
+        assert_eq!(throttle.batch_request(&reqs).await.unwrap().len(), 2);
+        assert!(start.elapsed() >= Duration::from_millis(95));
+
+        // without a limit, the same requests are not delayed
+        let throttle = Throttle::new(fast(), RateLimit::unlimited());
+        let start = Instant::now();
+        for _ in 0..3 {
+            throttle
+                .json_rpc_request(&request("eth_chainId"))
+                .await
+                .unwrap()
+                .unwrap();
+        }
+        assert!(throttle.batch_request(&reqs).await.is_ok());
+        assert!(start.elapsed() < Duration::from_millis(50));
+    }
+
+    #[tokio::test]
+    async fn it_limits_requests_in_flight() {
+        let mock = slow();
+        let throttle = Throttle::new(mock.clone(), RateLimit::unlimited()).with_class(
+            ["eth_getLogs"],
+            RateLimit::unlimited().with_max_in_flight(1),
+        );
+
+        let (a, b) = (request("eth_getLogs"), request("eth_getLogs"));
+        let (a, b) = tokio::join!(throttle.json_rpc_request(&a), throttle.json_rpc_request(&b));
+        assert!(a.is_ok() && b.is_ok());
+        assert_eq!(mock.peak_in_flight(), 1);
+
+        let (a, b) = (request("eth_call"), request("eth_call"));
+        let (a, b) = tokio::join!(throttle.json_rpc_request(&a), throttle.json_rpc_request(&b));
+        assert!(a.is_ok() && b.is_ok());
+        assert_eq!(mock.peak_in_flight(), 2);
+    }
+}
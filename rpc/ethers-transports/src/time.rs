@@ -0,0 +1,76 @@
+//! Clocks and timers that work both natively and in the browser.
+//!
+//! Natively, timers require a tokio runtime with the time driver enabled.
+//! On `wasm32`, where `std::time::Instant` panics and there is no tokio
+//! timer, the clock is `performance.now()` and timers use `setTimeout`
+
+use std::{
+    fmt,
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+#[cfg(not(target_arch = "wasm32"))]
+pub use std::time::{Instant, SystemTime, UNIX_EPOCH};
+#[cfg(target_arch = "wasm32")]
+pub use web_time::{Instant, SystemTime, UNIX_EPOCH};
+
+use crate::common::DynFuture;
+
+/// A timer, resolved after some duration
+pub struct Sleep(DynFuture<'static, ()>);
+
+impl fmt::Debug for Sleep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sleep").finish_non_exhaustive()
+    }
+}
+
+impl Future for Sleep {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        self.0.as_mut().poll(cx)
+    }
+}
+
+/// Wait for `duration`
+#[cfg(not(target_arch = "wasm32"))]
+pub fn sleep(duration: Duration) -> Sleep {
+    Sleep(Box::pin(tokio::time::sleep(duration)))
+}
+
+/// Wait until `deadline`
+#[cfg(not(target_arch = "wasm32"))]
+pub fn sleep_until(deadline: Instant) -> Sleep {
+    Sleep(Box::pin(tokio::time::sleep_until(deadline.into())))
+}
+
+/// Wait for `duration`
+#[cfg(target_arch = "wasm32")]
+pub fn sleep(duration: Duration) -> Sleep {
+    use wasm_bindgen::prelude::*;
+
+    #[wasm_bindgen]
+    extern "C" {
+        #[wasm_bindgen(js_name = setTimeout)]
+        fn set_timeout(handler: &js_sys::Function, timeout: i32) -> JsValue;
+    }
+
+    let millis = duration.as_millis().try_into().unwrap_or(i32::MAX);
+    let promise = js_sys::Promise::new(&mut |resolve, _reject| {
+        set_timeout(&resolve, millis);
+    });
+    let fut = wasm_bindgen_futures::JsFuture::from(promise);
+    Sleep(Box::pin(async move {
+        let _ = fut.await;
+    }))
+}
+
+/// Wait until `deadline`
+#[cfg(target_arch = "wasm32")]
+pub fn sleep_until(deadline: Instant) -> Sleep {
+    sleep(deadline.saturating_duration_since(Instant::now()))
+}
@@ -4,10 +4,18 @@ use ethers_pub_use::{
     serde_json::value::RawValue,
 };
 
-use std::{borrow::Cow, fmt::Debug};
+use std::{borrow::Cow, fmt::Debug, sync::Arc, time::Duration};
 
-use crate::{call::RpcCall, common::*, TransportError};
+use crate::{call::RpcCall, common::*, transports::Http, TransportError};
 
+/// A JSON-RPC connection.
+///
+/// Custom transports, e.g. over gRPC or an in-process node, implement this
+/// trait, and [`PubSubConnection`] if they support subscriptions. Wrappers
+/// like [`CircuitBreaker`](crate::CircuitBreaker) and
+/// [`Instrumented`](crate::Instrumented) compose with any implementation.
+/// To choose a transport at runtime, erase its type with
+/// [`BoxedConnection`].
 pub trait Connection: Debug + Send + Sync {
     fn is_local(&self) -> bool;
 
@@ -21,6 +29,12 @@ pub trait Connection: Debug + Send + Sync {
 
     fn batch_request(&self, reqs: &[Request<'_>]) -> BatchRpcFuture;
 
+    /// The timeout applied to calls made with [`Connection::request`], if
+    /// any. Individual calls may override it with [`RpcCall::timeout`]
+    fn default_timeout(&self) -> Option<Duration> {
+        None
+    }
+
     fn request<Params, Resp>(
         &self,
         method: &'static str,
@@ -31,10 +45,80 @@ pub trait Connection: Debug + Send + Sync {
         Params: Serialize,
         Resp: for<'de> Deserialize<'de>,
     {
-        RpcCall::new(self, method, params, self.next_id())
+        let call = RpcCall::new(self, method, params, self.next_id());
+        match self.default_timeout() {
+            Some(timeout) => call.timeout(timeout),
+            None => call,
+        }
+    }
+}
+
+/// A type-erased [`Connection`], for transports chosen at runtime
+pub type BoxedConnection = Arc<dyn Connection>;
+
+/// Connect to a JSON-RPC endpoint, choosing the transport from the url
+/// scheme. Only `http` and `https` are supported
+pub fn connect(url: &str) -> Result<BoxedConnection, TransportError> {
+    let url: reqwest::Url = url
+        .parse()
+        .map_err(|err| TransportError::Custom(Box::new(err)))?;
+    match url.scheme() {
+        "http" | "https" => Ok(Arc::new(Http::new(url))),
+        scheme => Err(TransportError::UnsupportedScheme(scheme.to_owned())),
     }
 }
 
+macro_rules! impl_connection_for_pointer {
+    ($($ptr:ident),*) => {$(
+        impl<T> Connection for $ptr<T>
+        where
+            T: Connection + ?Sized,
+        {
+            fn is_local(&self) -> bool {
+                (**self).is_local()
+            }
+
+            fn increment_id(&self) -> u64 {
+                (**self).increment_id()
+            }
+
+            fn next_id(&self) -> Id<'static> {
+                (**self).next_id()
+            }
+
+            fn json_rpc_request(&self, req: &Request<'_>) -> RpcFuture {
+                (**self).json_rpc_request(req)
+            }
+
+            fn batch_request(&self, reqs: &[Request<'_>]) -> BatchRpcFuture {
+                (**self).batch_request(reqs)
+            }
+
+            fn default_timeout(&self) -> Option<Duration> {
+                (**self).default_timeout()
+            }
+        }
+
+        impl<T> PubSubConnection for $ptr<T>
+        where
+            T: PubSubConnection + ?Sized,
+        {
+            fn uninstall_listener(&self, id: [u8; 32]) -> Result<(), TransportError> {
+                (**self).uninstall_listener(id)
+            }
+
+            fn install_listener(
+                &self,
+                id: [u8; 32],
+            ) -> Result<UnboundedReceiver<Cow<'static, RawValue>>, TransportError> {
+                (**self).install_listener(id)
+            }
+        }
+    )*};
+}
+
+impl_connection_for_pointer!(Box, Arc);
+
 pub trait PubSubConnection: Connection {
     #[doc(hidden)]
     fn uninstall_listener(&self, id: [u8; 32]) -> Result<(), TransportError>;
@@ -43,12 +127,14 @@ pub trait PubSubConnection: Connection {
     fn install_listener(
         &self,
         id: [u8; 32],
-    ) -> Result<UnboundedReceiver<Cow<'_, RawValue>>, TransportError>;
+    ) -> Result<UnboundedReceiver<Cow<'static, RawValue>>, TransportError>;
 }
 
 #[cfg(test)]
 mod test {
-    use crate::{Connection, PubSubConnection};
+    use crate::{
+        connect, transports::MockConnection, Connection, PubSubConnection, TransportError,
+    };
 
     fn __compile_check() -> Box<dyn Connection> {
         todo!()
@@ -56,4 +142,24 @@ mod test {
     fn __compile_check_pubsub() -> Box<dyn PubSubConnection> {
         todo!()
     }
+
+    #[tokio::test]
+    async fn it_erases_connection_types() {
+        let mock = MockConnection::new();
+        mock.push_response("eth_chainId", "0x1");
+        let boxed: Box<dyn Connection> = Box::new(mock.clone());
+        let chain_id: String = boxed.request("eth_chainId", ()).await.unwrap().unwrap();
+        assert_eq!(chain_id, "0x1");
+        assert_eq!(mock.requests().len(), 1);
+    }
+
+    #[test]
+    fn it_connects_by_scheme() {
+        assert!(connect("http://localhost:8545").unwrap().is_local());
+        assert!(matches!(
+            connect("wss://example.com"),
+            Err(TransportError::UnsupportedScheme(scheme)) if scheme == "wss"
+        ));
+        assert!(connect("not a url").is_err());
+    }
 }
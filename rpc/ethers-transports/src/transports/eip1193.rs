@@ -0,0 +1,293 @@
+use std::{
+    borrow::Cow,
+    fmt,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use ethers_pub_use::{
+    futures_channel::mpsc::UnboundedReceiver,
+    serde::{Deserialize, Serialize},
+    serde_json::{self, value::RawValue},
+    tracing,
+};
+use js_sys::{Function, Promise, Reflect, JSON};
+use wasm_bindgen::{prelude::*, JsCast};
+use wasm_bindgen_futures::JsFuture;
+
+use crate::{
+    common::{self, ErrorObject, RpcOutcome},
+    listeners::{ListenerStats, Listeners},
+    manager::subscription_key,
+    Connection, PubSubConnection, TransportError,
+};
+
+#[wasm_bindgen]
+extern "C" {
+    /// An EIP-1193 provider object
+    #[derive(Debug, Clone)]
+    type Ethereum;
+
+    #[wasm_bindgen(method, catch)]
+    fn request(this: &Ethereum, args: &JsValue) -> Result<Promise, JsValue>;
+
+    #[wasm_bindgen(method)]
+    fn on(this: &Ethereum, event: &str, listener: &Function);
+
+    #[wasm_bindgen(method, js_name = removeListener)]
+    fn remove_listener(this: &Ethereum, event: &str, listener: &Function);
+}
+
+/// The argument of `request`
+#[derive(Serialize)]
+#[serde(crate = "ethers_pub_use::serde")]
+struct RequestArgs<'a> {
+    method: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    params: Option<&'a RawValue>,
+}
+
+/// A `message` event
+#[derive(Deserialize)]
+#[serde(crate = "ethers_pub_use::serde")]
+struct Message {
+    #[serde(rename = "type")]
+    kind: String,
+    data: Box<RawValue>,
+}
+
+/// The data of an `eth_subscription` message
+#[derive(Deserialize)]
+#[serde(crate = "ethers_pub_use::serde")]
+struct Notification {
+    subscription: String,
+    result: Box<RawValue>,
+}
+
+/// The provider object, and the `message` listener registered on it
+struct Inner {
+    ethereum: Ethereum,
+    on_message: Closure<dyn FnMut(JsValue)>,
+}
+
+// SAFETY: without the atomics target feature, wasm32 is single threaded,
+// so the JS objects are never accessed from another thread
+#[cfg(not(target_feature = "atomics"))]
+unsafe impl Send for Inner {}
+#[cfg(not(target_feature = "atomics"))]
+unsafe impl Sync for Inner {}
+
+impl Drop for Inner {
+    fn drop(&mut self) {
+        self.ethereum
+            .remove_listener("message", self.on_message.as_ref().unchecked_ref());
+    }
+}
+
+/// A transport over an EIP-1193 provider injected by a browser wallet,
+/// such as MetaMask's `window.ethereum`. Only available on `wasm32`.
+///
+/// Requests are sent with the provider's `request` method, which has no
+/// batches, so batch requests are sent one at a time. Subscription
+/// notifications arrive as `message` events, and are delivered to the
+/// installed listeners
+#[derive(Clone)]
+pub struct Eip1193 {
+    inner: Arc<Inner>,
+    id: Arc<AtomicU64>,
+    listeners: Arc<Mutex<Listeners>>,
+}
+
+impl fmt::Debug for Eip1193 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Eip1193")
+            .field("listeners", &self.listeners)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Eip1193 {
+    /// Wrap the provider injected as `window.ethereum`. Fails if the page
+    /// has none, e.g. because no wallet is installed
+    pub fn injected() -> Result<Self, TransportError> {
+        let ethereum = Reflect::get(&js_sys::global(), &"ethereum".into()).map_err(js_error)?;
+        if ethereum.is_undefined() || ethereum.is_null() {
+            return Err(TransportError::Custom(
+                "no injected EIP-1193 provider".into(),
+            ));
+        }
+        Self::new(ethereum)
+    }
+
+    /// Wrap an EIP-1193 provider object. Fails if it has no `request`
+    /// method
+    pub fn new(ethereum: JsValue) -> Result<Self, TransportError> {
+        let request = Reflect::get(&ethereum, &"request".into()).map_err(js_error)?;
+        if !request.is_function() {
+            return Err(TransportError::Custom(
+                "not an EIP-1193 provider: no request method".into(),
+            ));
+        }
+        let ethereum: Ethereum = ethereum.unchecked_into();
+
+        let listeners: Arc<Mutex<Listeners>> = Default::default();
+        let on_message = {
+            let listeners = listeners.clone();
+            Closure::<dyn FnMut(JsValue)>::new(move |message: JsValue| {
+                deliver(&listeners, &message)
+            })
+        };
+        ethereum.on("message", on_message.as_ref().unchecked_ref());
+
+        Ok(Self {
+            inner: Arc::new(Inner {
+                ethereum,
+                on_message,
+            }),
+            id: Default::default(),
+            listeners,
+        })
+    }
+
+    /// Set the registry for subscription listeners, e.g. to change its
+    /// capacity. Replaces any installed listeners. See [`Listeners`]
+    #[must_use = "Builder method outputs must be used"]
+    pub fn with_listeners(self, listeners: Listeners) -> Self {
+        *self.listeners.lock().unwrap() = listeners;
+        self
+    }
+
+    /// Counts of the subscription listeners
+    pub fn listener_stats(&self) -> ListenerStats {
+        self.listeners.lock().unwrap().stats()
+    }
+}
+
+/// Deliver an `eth_subscription` message to its listener. Other messages
+/// are ignored
+fn deliver(listeners: &Mutex<Listeners>, message: &JsValue) {
+    let Ok(json) = to_raw(message) else {
+        return;
+    };
+    let Ok(message) = serde_json::from_str::<Message>(json.get()) else {
+        return;
+    };
+    if message.kind != "eth_subscription" {
+        return;
+    }
+    let notification = match serde_json::from_str::<Notification>(message.data.get()) {
+        Ok(notification) => notification,
+        Err(err) => {
+            tracing::warn!(%err, "invalid subscription notification");
+            return;
+        }
+    };
+    let Some(id) = subscription_key(&notification.subscription) else {
+        tracing::warn!(id = %notification.subscription, "invalid subscription id");
+        return;
+    };
+    listeners
+        .lock()
+        .unwrap()
+        .deliver(&id, Cow::Owned(notification.result));
+}
+
+/// A JS exception as a transport failure
+fn js_error(err: JsValue) -> TransportError {
+    let message = Reflect::get(&err, &"message".into())
+        .ok()
+        .and_then(|message| message.as_string())
+        .or_else(|| err.as_string())
+        .unwrap_or_else(|| format!("{err:?}"));
+    TransportError::Custom(message.into())
+}
+
+/// A JS value as JSON. `undefined` is `null`
+fn to_raw(value: &JsValue) -> Result<Box<RawValue>, TransportError> {
+    let json = if value.is_undefined() {
+        "null".to_owned()
+    } else {
+        String::from(JSON::stringify(value).map_err(js_error)?)
+    };
+    RawValue::from_string(json.clone()).map_err(|err| TransportError::deser_err(err, json))
+}
+
+/// The `request` argument of a JSON-RPC request
+fn request_args(req: &common::Request<'_>) -> Result<JsValue, TransportError> {
+    let args = RequestArgs {
+        method: &req.method,
+        params: req.params.as_deref(),
+    };
+    let json = serde_json::to_string(&args).map_err(TransportError::ser_err)?;
+    JSON::parse(&json).map_err(js_error)
+}
+
+/// Send a request. Rejections that carry a numeric `code`, as EIP-1193
+/// errors do, are JSON-RPC errors. Other rejections are transport failures
+async fn send(ethereum: Ethereum, args: Result<JsValue, TransportError>) -> RpcOutcome {
+    let promise = ethereum.request(&args?).map_err(js_error)?;
+    let err = match JsFuture::from(promise).await {
+        Ok(result) => return Ok(Ok(Cow::Owned(to_raw(&result)?))),
+        Err(err) => err,
+    };
+
+    let Some(code) = Reflect::get(&err, &"code".into())
+        .ok()
+        .and_then(|code| code.as_f64())
+    else {
+        return Err(js_error(err));
+    };
+    let message = Reflect::get(&err, &"message".into())
+        .ok()
+        .and_then(|message| message.as_string())
+        .unwrap_or_default();
+    let data = Reflect::get(&err, &"data".into())
+        .ok()
+        .filter(|data| !data.is_undefined())
+        .map(|data| to_raw(&data))
+        .transpose()?;
+    Ok(Err(ErrorObject::owned(code as i32, message, data)))
+}
+
+impl Connection for Eip1193 {
+    fn is_local(&self) -> bool {
+        false
+    }
+
+    fn increment_id(&self) -> u64 {
+        self.id.fetch_add(1, Ordering::Relaxed)
+    }
+
+    fn json_rpc_request(&self, req: &common::Request<'_>) -> common::RpcFuture {
+        Box::pin(send(self.inner.ethereum.clone(), request_args(req)))
+    }
+
+    fn batch_request(&self, reqs: &[common::Request<'_>]) -> common::BatchRpcFuture {
+        let ethereum = self.inner.ethereum.clone();
+        let args: Vec<_> = reqs.iter().map(request_args).collect();
+
+        Box::pin(async move {
+            let mut resps = Vec::with_capacity(args.len());
+            for args in args {
+                resps.push(send(ethereum.clone(), args).await?);
+            }
+            Ok(resps)
+        })
+    }
+}
+
+impl PubSubConnection for Eip1193 {
+    fn uninstall_listener(&self, id: [u8; 32]) -> Result<(), TransportError> {
+        self.listeners.lock().unwrap().uninstall(&id);
+        Ok(())
+    }
+
+    fn install_listener(
+        &self,
+        id: [u8; 32],
+    ) -> Result<UnboundedReceiver<Cow<'static, RawValue>>, TransportError> {
+        Ok(self.listeners.lock().unwrap().install(id))
+    }
+}
@@ -7,11 +7,15 @@ use std::{
     },
 };
 
-use ethers_pub_use::serde_json::{self, value::RawValue};
-use reqwest::{header::HeaderValue, Client, Url};
+use ethers_pub_use::{
+    serde::Serialize,
+    serde_json::{self, value::RawValue},
+};
+use reqwest::{header::HeaderValue, Client, RequestBuilder, Url};
 
 use crate::{
-    common::{self, Authorization, BatchRpcOutcome, RpcFuture},
+    common::{self, Authorization, BatchRpcOutcome, DynFuture, RpcFuture},
+    jwt::JwtSecret,
     transport::Connection,
     utils::deser_rpc_result,
     TransportError,
@@ -22,6 +26,7 @@ pub struct HttpInternal {
     id: AtomicU64,
     client: Client,
     url: Url,
+    jwt: Option<JwtSecret>,
 }
 
 impl HttpInternal {
@@ -30,6 +35,7 @@ impl HttpInternal {
             id: Default::default(),
             client: Default::default(),
             url,
+            jwt: None,
         }
     }
 }
@@ -63,6 +69,7 @@ impl Http {
             id: Default::default(),
             client,
             url,
+            jwt: None,
         }))
     }
 
@@ -80,6 +87,53 @@ impl Http {
 
         Self::new_with_client(url, client)
     }
+
+    /// Instantiate a transport that authenticates with a JWT, as Engine API
+    /// endpoints require. A fresh token is issued for each request
+    pub fn new_with_jwt(url: Url, secret: JwtSecret) -> Self {
+        Self(Arc::new(HttpInternal {
+            id: Default::default(),
+            client: Default::default(),
+            url,
+            jwt: Some(secret),
+        }))
+    }
+
+    fn post(&self) -> RequestBuilder {
+        let req = self.client.post(self.url.as_ref());
+        match &self.jwt {
+            Some(secret) => req.bearer_auth(secret.token()),
+            None => req,
+        }
+    }
+
+    /// Post a JSON body, and read the response body
+    fn send(&self, body: &impl Serialize) -> DynFuture<'static, Result<String, TransportError>> {
+        let req = self.post().json(body).build();
+        let client = self.client.clone();
+
+        Box::pin(async move {
+            let req = req?;
+            #[cfg(feature = "raw-logs")]
+            let endpoint = crate::raw_logs::redact_url(req.url());
+            #[cfg(feature = "raw-logs")]
+            crate::raw_logs::request(
+                &endpoint,
+                &crate::raw_logs::redact_headers(req.headers()),
+                req.body()
+                    .and_then(|body| body.as_bytes())
+                    .unwrap_or_default(),
+            );
+
+            let res = client.execute(req).await?;
+            #[cfg(feature = "raw-logs")]
+            let status = res.status().as_u16();
+            let body = res.text().await?;
+            #[cfg(feature = "raw-logs")]
+            crate::raw_logs::response(&endpoint, status, &body);
+            Ok(body)
+        })
+    }
 }
 
 impl Connection for Http {
@@ -92,21 +146,19 @@ impl Connection for Http {
     }
 
     fn json_rpc_request(&self, req: &common::Request<'_>) -> RpcFuture {
-        let fut = self.client.post(self.url.as_ref()).json(&req).send();
+        let fut = self.send(req);
 
         Box::pin(async move {
-            let res = fut.await?;
-            let body = res.text().await?;
+            let body = fut.await?;
             deser_rpc_result(&body)
         })
     }
 
     fn batch_request(&self, reqs: &[common::Request<'_>]) -> common::BatchRpcFuture {
-        let fut = self.client.post(self.url.as_ref()).json(&reqs).send();
+        let fut = self.send(&reqs);
 
         Box::pin(async move {
-            let res = fut.await?;
-            let body = res.text().await?;
+            let body = fut.await?;
 
             let resps: Result<Vec<&'_ RawValue>, _> = serde_json::from_str(&body);
 
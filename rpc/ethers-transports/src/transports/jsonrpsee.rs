@@ -0,0 +1,322 @@
+use std::{
+    borrow::Cow,
+    fmt::Debug,
+    future::Future,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use ethers_pub_use::{
+    futures_channel::mpsc::UnboundedReceiver, hex, serde::Deserialize, serde_json::value::RawValue,
+    tracing,
+};
+use jsonrpsee_core::{
+    client::{ClientT, SubscriptionClientT},
+    params::BatchRequestBuilder,
+    traits::ToRpcParams,
+    Error as JsonRpseeError,
+};
+use jsonrpsee_types::error::CallError;
+
+use crate::{
+    common::{self, JsonRpcResultOwned, RpcFuture},
+    listeners::{ListenerStats, Listeners},
+    Connection, PubSubConnection, TransportError,
+};
+
+/// Already-serialized params
+struct RawParams(Option<Box<RawValue>>);
+
+impl ToRpcParams for RawParams {
+    fn to_rpc_params(self) -> Result<Option<Box<RawValue>>, JsonRpseeError> {
+        Ok(self.0)
+    }
+}
+
+/// Adapts a `jsonrpsee` client, such as an `HttpClient` or a `WsClient`,
+/// to [`Connection`]. The client's connection pool, middleware and
+/// reconnection settings are reused.
+///
+/// Clients that implement `SubscriptionClientT` also implement
+/// [`PubSubConnection`], once [`JsonRpseeClient::notifications`] is
+/// running.
+#[derive(Debug)]
+pub struct JsonRpseeClient<C> {
+    inner: Arc<C>,
+    id: Arc<AtomicU64>,
+    local: bool,
+    listeners: Arc<Mutex<Listeners>>,
+}
+
+impl<C> Clone for JsonRpseeClient<C> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            id: self.id.clone(),
+            local: self.local,
+            listeners: self.listeners.clone(),
+        }
+    }
+}
+
+impl<C> JsonRpseeClient<C> {
+    /// Wrap a client
+    pub fn new(client: C) -> Self {
+        Self {
+            inner: Arc::new(client),
+            id: Default::default(),
+            local: false,
+            listeners: Default::default(),
+        }
+    }
+
+    /// Set whether the client is connected to a local node. See
+    /// [`Connection::is_local`]
+    #[must_use = "Builder method outputs must be used"]
+    pub fn with_local(mut self, local: bool) -> Self {
+        self.local = local;
+        self
+    }
+
+    /// Set the registry for subscription listeners, e.g. to change its
+    /// capacity. See [`Listeners`]
+    #[must_use = "Builder method outputs must be used"]
+    pub fn with_listeners(mut self, listeners: Listeners) -> Self {
+        self.listeners = Arc::new(Mutex::new(listeners));
+        self
+    }
+
+    /// Counts of the subscription listeners
+    pub fn listener_stats(&self) -> ListenerStats {
+        self.listeners.lock().unwrap().stats()
+    }
+
+    /// The wrapped client
+    pub fn inner(&self) -> &C {
+        &self.inner
+    }
+}
+
+/// Split a `jsonrpsee` error into a JSON-RPC error response or a transport
+/// failure
+fn into_outcome(err: JsonRpseeError) -> Result<JsonRpcResultOwned, TransportError> {
+    match err {
+        JsonRpseeError::Call(CallError::Custom(err)) => Ok(Err(err)),
+        err => Err(TransportError::Custom(Box::new(err))),
+    }
+}
+
+impl<C> Connection for JsonRpseeClient<C>
+where
+    C: ClientT + Debug + Send + Sync + 'static,
+{
+    fn is_local(&self) -> bool {
+        self.local
+    }
+
+    fn increment_id(&self) -> u64 {
+        self.id.fetch_add(1, Ordering::Relaxed)
+    }
+
+    fn json_rpc_request(&self, req: &common::Request<'_>) -> RpcFuture {
+        let inner = self.inner.clone();
+        let method = req.method.to_string();
+        let params = RawParams(req.params.as_ref().map(|p| p.clone().into_owned()));
+
+        Box::pin(async move {
+            match inner.request::<Box<RawValue>, _>(&method, params).await {
+                Ok(resp) => Ok(Ok(Cow::Owned(resp))),
+                Err(err) => into_outcome(err),
+            }
+        })
+    }
+
+    fn batch_request(&self, reqs: &[common::Request<'_>]) -> common::BatchRpcFuture {
+        let inner = self.inner.clone();
+        let reqs: Vec<_> = reqs
+            .iter()
+            .map(|req| {
+                let params = req.params.as_ref().map(|p| p.clone().into_owned());
+                (req.method.to_string(), params)
+            })
+            .collect();
+
+        Box::pin(async move {
+            let mut batch = BatchRequestBuilder::new();
+            for (method, params) in &reqs {
+                batch
+                    .insert(method, RawParams(params.clone()))
+                    .map_err(|err| TransportError::Custom(Box::new(err)))?;
+            }
+            let resps = match inner.batch_request::<Box<RawValue>>(batch).await {
+                Ok(resps) => resps,
+                Err(err) => return into_outcome(err).map(|_| vec![]),
+            };
+            Ok(resps
+                .into_iter()
+                .map(|resp| resp.map(Cow::Owned).map_err(|err| err.into_owned()))
+                .collect())
+        })
+    }
+}
+
+/// The `params` of an `eth_subscription` notification
+#[derive(Deserialize)]
+#[serde(crate = "ethers_pub_use::serde")]
+struct Notification {
+    subscription: String,
+    result: Box<RawValue>,
+}
+
+/// Parse a hex subscription id into a big-endian 32-byte id
+fn parse_subscription_id(id: &str) -> Option<[u8; 32]> {
+    let digits = id.strip_prefix("0x").unwrap_or(id);
+    if digits.len() > 64 {
+        return None;
+    }
+    let padded = format!("{digits:0>64}");
+    let mut out = [0u8; 32];
+    hex::decode_to_slice(padded, &mut out).ok()?;
+    Some(out)
+}
+
+impl<C> JsonRpseeClient<C>
+where
+    C: SubscriptionClientT + Debug + Send + Sync + 'static,
+{
+    /// Listen for `eth_subscription` notifications. Returns a future that
+    /// delivers them to installed listeners. Spawn it before subscribing.
+    /// It resolves when the client disconnects
+    pub async fn notifications(
+        &self,
+    ) -> Result<impl Future<Output = ()> + Send + 'static, TransportError> {
+        let mut notifications = self
+            .inner
+            .subscribe_to_method::<Notification>("eth_subscription")
+            .await
+            .map_err(|err| TransportError::Custom(Box::new(err)))?;
+        let listeners = self.listeners.clone();
+
+        Ok(async move {
+            while let Some(notification) = notifications.next().await {
+                let notification = match notification {
+                    Ok(notification) => notification,
+                    Err(err) => {
+                        tracing::warn!(%err, "invalid subscription notification");
+                        continue;
+                    }
+                };
+                let Some(id) = parse_subscription_id(&notification.subscription) else {
+                    tracing::warn!(id = %notification.subscription, "invalid subscription id");
+                    continue;
+                };
+                listeners
+                    .lock()
+                    .unwrap()
+                    .deliver(&id, Cow::Owned(notification.result));
+            }
+        })
+    }
+}
+
+impl<C> PubSubConnection for JsonRpseeClient<C>
+where
+    C: SubscriptionClientT + Debug + Send + Sync + 'static,
+{
+    fn uninstall_listener(&self, id: [u8; 32]) -> Result<(), TransportError> {
+        self.listeners.lock().unwrap().uninstall(&id);
+        Ok(())
+    }
+
+    fn install_listener(
+        &self,
+        id: [u8; 32],
+    ) -> Result<UnboundedReceiver<Cow<'static, RawValue>>, TransportError> {
+        Ok(self.listeners.lock().unwrap().install(id))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ethers_pub_use::{async_trait::async_trait, serde::de::DeserializeOwned, serde_json};
+    use jsonrpsee_core::client::BatchResponse;
+    use jsonrpsee_types::ErrorObject;
+
+    use super::*;
+
+    /// Echoes params, and fails `eth_fail` with a JSON-RPC error
+    #[derive(Debug)]
+    struct Echo;
+
+    #[async_trait]
+    impl ClientT for Echo {
+        async fn notification<Params>(&self, _: &str, _: Params) -> Result<(), JsonRpseeError>
+        where
+            Params: ToRpcParams + Send,
+        {
+            Ok(())
+        }
+
+        async fn request<R, Params>(
+            &self,
+            method: &str,
+            params: Params,
+        ) -> Result<R, JsonRpseeError>
+        where
+            R: DeserializeOwned,
+            Params: ToRpcParams + Send,
+        {
+            if method == "eth_fail" {
+                let err = ErrorObject::owned(-32000, "failed", None::<()>);
+                return Err(CallError::Custom(err).into());
+            }
+            let params = params.to_rpc_params()?.unwrap();
+            Ok(serde_json::from_str(params.get())?)
+        }
+
+        async fn batch_request<'a, R>(
+            &self,
+            _: BatchRequestBuilder<'a>,
+        ) -> Result<BatchResponse<'a, R>, JsonRpseeError>
+        where
+            R: DeserializeOwned + std::fmt::Debug + 'a,
+        {
+            Err(JsonRpseeError::Custom("batches unsupported".into()))
+        }
+    }
+
+    #[tokio::test]
+    async fn it_adapts_clients() {
+        let client = JsonRpseeClient::new(Echo);
+
+        let resp: Vec<u64> = client
+            .request("eth_echo", [1u64, 2])
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(resp, [1, 2]);
+
+        let err = client
+            .request::<_, u64>("eth_fail", ())
+            .await
+            .unwrap()
+            .unwrap_err();
+        assert_eq!(err.code(), -32000);
+
+        let err = client.batch_request(&[]).await.unwrap_err();
+        assert!(matches!(err, TransportError::Custom(_)));
+    }
+
+    #[test]
+    fn it_parses_subscription_ids() {
+        let id = parse_subscription_id("0x9cef478923ff08bf67fde6c64013158d").unwrap();
+        assert_eq!(id[..16], [0; 16]);
+        assert_eq!(id[16], 0x9c);
+        assert_eq!(id[31], 0x8d);
+
+        assert!(parse_subscription_id("0xzz").is_none());
+        assert!(parse_subscription_id(&"1".repeat(65)).is_none());
+    }
+}
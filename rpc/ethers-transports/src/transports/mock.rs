@@ -0,0 +1,397 @@
+use std::{
+    borrow::Cow,
+    collections::{HashMap, VecDeque},
+    fmt,
+    sync::{
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+use ethers_pub_use::{
+    futures_channel::mpsc::UnboundedReceiver,
+    serde::Serialize,
+    serde_json::{self, value::RawValue, Value},
+};
+
+use crate::{
+    common::{self, DynFuture, ErrorObject, JsonRpcResultOwned, RpcOutcome},
+    listeners::Listeners,
+    time, Connection, PubSubConnection, TransportError,
+};
+
+/// A request received by a [`MockConnection`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct MockRequest {
+    /// The method
+    pub method: String,
+    /// The params, if any
+    pub params: Option<Value>,
+}
+
+#[derive(Debug, Default)]
+struct MockState {
+    responses: HashMap<String, VecDeque<JsonRpcResultOwned>>,
+    requests: Vec<MockRequest>,
+    batches: Vec<usize>,
+    listeners: Listeners,
+}
+
+/// Computes the response to a request with no queued response
+type Handler = Arc<dyn Fn(&MockRequest) -> RpcOutcome + Send + Sync>;
+
+/// Counts of requests awaiting their response
+#[derive(Debug, Default)]
+struct InFlight {
+    active: AtomicUsize,
+    peak: AtomicUsize,
+}
+
+/// Marks a request in flight until dropped
+struct InFlightGuard(Arc<InFlight>);
+
+impl InFlightGuard {
+    fn new(in_flight: Arc<InFlight>) -> Self {
+        let active = in_flight.active.fetch_add(1, Ordering::SeqCst) + 1;
+        in_flight.peak.fetch_max(active, Ordering::SeqCst);
+        Self(in_flight)
+    }
+}
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        self.0.active.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// A connection that serves canned responses, for testing providers and
+/// middleware without a node.
+///
+/// Responses are queued per method and served in order. Requests with no
+/// queued response are passed to the [handler](MockConnection::with_handler),
+/// if any. Every request is recorded. Clones share their queues and history,
+/// so a test can keep a handle while a provider owns the connection.
+///
+/// ```
+/// # async fn example() {
+/// use ethers_transports::{transports::MockConnection, Connection};
+///
+/// let mock = MockConnection::new();
+/// mock.push_response("eth_blockNumber", "0x10");
+///
+/// let block: String = mock.request("eth_blockNumber", ()).await.unwrap().unwrap();
+/// assert_eq!(block, "0x10");
+/// assert_eq!(mock.requests()[0].method, "eth_blockNumber");
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct MockConnection {
+    state: Arc<Mutex<MockState>>,
+    id: Arc<AtomicU64>,
+    local: bool,
+    handler: Option<Handler>,
+    latency: Option<Duration>,
+    in_flight: Arc<InFlight>,
+}
+
+impl fmt::Debug for MockConnection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MockConnection")
+            .field("state", &self.state)
+            .field("id", &self.id)
+            .field("local", &self.local)
+            .field("handler", &self.handler.is_some())
+            .field("latency", &self.latency)
+            .field("in_flight", &self.in_flight)
+            .finish()
+    }
+}
+
+impl Default for MockConnection {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MockConnection {
+    /// Instantiate a connection with no queued responses. It reports itself
+    /// as local
+    pub fn new() -> Self {
+        Self {
+            state: Default::default(),
+            id: Default::default(),
+            local: true,
+            handler: None,
+            latency: None,
+            in_flight: Default::default(),
+        }
+    }
+
+    /// Compute responses to requests that have no queued response, e.g. to
+    /// echo params or emulate a contract. Without a handler, such requests
+    /// fail with [`TransportError::Custom`]
+    #[must_use = "Builder method outputs must be used"]
+    pub fn with_handler(
+        mut self,
+        handler: impl Fn(&MockRequest) -> RpcOutcome + Send + Sync + 'static,
+    ) -> Self {
+        self.handler = Some(Arc::new(handler));
+        self
+    }
+
+    /// Delay every response by `latency`. Requires a tokio runtime with the
+    /// time driver enabled
+    #[must_use = "Builder method outputs must be used"]
+    pub fn with_latency(mut self, latency: Duration) -> Self {
+        self.latency = Some(latency);
+        self
+    }
+
+    /// Set whether the connection reports itself as local
+    #[must_use = "Builder method outputs must be used"]
+    pub fn with_local(mut self, local: bool) -> Self {
+        self.local = local;
+        self
+    }
+
+    fn push(&self, method: &str, resp: JsonRpcResultOwned) {
+        self.state
+            .lock()
+            .unwrap()
+            .responses
+            .entry(method.to_owned())
+            .or_default()
+            .push_back(resp);
+    }
+
+    /// Queue a successful response to `method`
+    ///
+    /// # Panics
+    ///
+    /// If the response cannot be serialized
+    pub fn push_response(&self, method: &str, resp: impl Serialize) {
+        let resp = serde_json::value::to_raw_value(&resp).expect("serializable response");
+        self.push(method, Ok(Cow::Owned(resp)));
+    }
+
+    /// Queue a JSON-RPC error response to `method`
+    pub fn push_error(&self, method: &str, code: i32, message: impl Into<String>) {
+        self.push(
+            method,
+            Err(ErrorObject::owned(code, message.into(), None::<()>)),
+        );
+    }
+
+    /// The requests received so far, in order
+    pub fn requests(&self) -> Vec<MockRequest> {
+        self.state.lock().unwrap().requests.clone()
+    }
+
+    /// The lengths of the batches received so far, in order
+    pub fn batches(&self) -> Vec<usize> {
+        self.state.lock().unwrap().batches.clone()
+    }
+
+    /// The largest number of requests that have awaited their response at
+    /// the same time. A batch counts as one request
+    pub fn peak_in_flight(&self) -> usize {
+        self.in_flight.peak.load(Ordering::SeqCst)
+    }
+
+    /// The number of queued responses not yet served
+    pub fn pending_responses(&self) -> usize {
+        self.state
+            .lock()
+            .unwrap()
+            .responses
+            .values()
+            .map(VecDeque::len)
+            .sum()
+    }
+
+    /// Deliver a notification to the listener for a subscription. Returns
+    /// `false` if no listener is installed
+    ///
+    /// # Panics
+    ///
+    /// If the notification cannot be serialized
+    pub fn notify(&self, id: [u8; 32], notification: impl Serialize) -> bool {
+        let notification =
+            serde_json::value::to_raw_value(&notification).expect("serializable notification");
+        self.state
+            .lock()
+            .unwrap()
+            .listeners
+            .deliver(&id, Cow::Owned(notification))
+    }
+
+    /// Record a request and serve its next queued response, or the
+    /// handler's response
+    fn serve(&self, req: &common::Request<'_>) -> RpcOutcome {
+        let params = req
+            .params
+            .as_ref()
+            .map(|params| serde_json::from_str(params.get()))
+            .transpose()
+            .map_err(TransportError::ser_err)?;
+        let req = MockRequest {
+            method: req.method.to_string(),
+            params,
+        };
+
+        let queued = {
+            let mut state = self.state.lock().unwrap();
+            state.requests.push(req.clone());
+            state
+                .responses
+                .get_mut(&req.method)
+                .and_then(VecDeque::pop_front)
+        };
+        // the lock is released, so handlers may use the connection
+        match (queued, &self.handler) {
+            (Some(resp), _) => Ok(resp),
+            (None, Some(handler)) => handler(&req),
+            (None, None) => Err(TransportError::Custom(
+                format!("no mock response for {}", req.method).into(),
+            )),
+        }
+    }
+
+    /// Resolve to `resp` after the latency, counting the request in flight
+    /// until then
+    fn respond<T: Send + 'static>(&self, resp: T) -> DynFuture<'static, T> {
+        let guard = InFlightGuard::new(self.in_flight.clone());
+        let latency = self.latency;
+        Box::pin(async move {
+            let _guard = guard;
+            if let Some(latency) = latency {
+                time::sleep(latency).await;
+            }
+            resp
+        })
+    }
+}
+
+impl Connection for MockConnection {
+    fn is_local(&self) -> bool {
+        self.local
+    }
+
+    fn increment_id(&self) -> u64 {
+        self.id.fetch_add(1, Ordering::Relaxed)
+    }
+
+    fn json_rpc_request(&self, req: &common::Request<'_>) -> common::RpcFuture {
+        let resp = self.serve(req);
+        self.respond(resp)
+    }
+
+    fn batch_request(&self, reqs: &[common::Request<'_>]) -> common::BatchRpcFuture {
+        self.state.lock().unwrap().batches.push(reqs.len());
+        let resps = reqs.iter().map(|req| self.serve(req)).collect();
+        self.respond(resps)
+    }
+}
+
+impl PubSubConnection for MockConnection {
+    fn uninstall_listener(&self, id: [u8; 32]) -> Result<(), TransportError> {
+        self.state.lock().unwrap().listeners.uninstall(&id);
+        Ok(())
+    }
+
+    fn install_listener(
+        &self,
+        id: [u8; 32],
+    ) -> Result<UnboundedReceiver<Cow<'static, RawValue>>, TransportError> {
+        Ok(self.state.lock().unwrap().listeners.install(id))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ethers_pub_use::serde_json::json;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn it_serves_queued_responses() {
+        let mock = MockConnection::new();
+        mock.push_response("eth_chainId", "0x1");
+        mock.push_error("eth_chainId", -32000, "gone");
+
+        let handle = mock.clone();
+        let chain_id: String = mock.request("eth_chainId", ()).await.unwrap().unwrap();
+        assert_eq!(chain_id, "0x1");
+        let err = mock
+            .request::<_, String>("eth_chainId", ())
+            .await
+            .unwrap()
+            .unwrap_err();
+        assert_eq!(err.message(), "gone");
+        assert!(matches!(
+            mock.request::<_, String>("eth_chainId", ()).await,
+            Err(TransportError::Custom(_))
+        ));
+
+        assert_eq!(handle.pending_responses(), 0);
+        assert_eq!(handle.requests().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn it_records_batches_and_notifies() {
+        let mock = MockConnection::new();
+        mock.push_response("eth_getBalance", "0x0");
+        mock.push_response("eth_getBalance", "0x1");
+
+        let reqs = [
+            common::Request::owned(mock.next_id(), "eth_getBalance", None),
+            common::Request::owned(
+                mock.next_id(),
+                "eth_getBalance",
+                Some(serde_json::value::to_raw_value(&json!(["0xab"])).unwrap()),
+            ),
+        ];
+        let resps = mock.batch_request(&reqs).await.unwrap();
+        assert_eq!(resps[1].as_ref().unwrap().get(), r#""0x1""#);
+        assert_eq!(mock.batches(), vec![2]);
+        assert_eq!(mock.requests()[1].params, Some(json!(["0xab"])));
+
+        let mut rx = mock.install_listener([7; 32]).unwrap();
+        assert!(mock.notify([7; 32], json!({"number": "0x1"})));
+        assert_eq!(rx.try_recv().unwrap().get(), r#"{"number":"0x1"}"#);
+        mock.uninstall_listener([7; 32]).unwrap();
+        assert!(!mock.notify([7; 32], 1));
+    }
+
+    #[tokio::test]
+    async fn it_falls_back_to_the_handler() {
+        let mock = MockConnection::new().with_handler(|req| {
+            let params = serde_json::value::to_raw_value(&req.params).unwrap();
+            Ok(Ok(Cow::Owned(params)))
+        });
+        mock.push_response("eth_chainId", "0x1");
+
+        let chain_id: String = mock.request("eth_chainId", ()).await.unwrap().unwrap();
+        assert_eq!(chain_id, "0x1");
+        let echoed: Vec<u64> = mock.request("eth_chainId", [2]).await.unwrap().unwrap();
+        assert_eq!(echoed, vec![2]);
+        assert_eq!(mock.requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn it_delays_responses() {
+        let mock = MockConnection::new().with_latency(Duration::from_millis(10));
+        mock.push_response("eth_chainId", "0x1");
+        mock.push_response("eth_chainId", "0x1");
+
+        let start = time::Instant::now();
+        let (a, b) = tokio::join!(
+            mock.request::<_, String>("eth_chainId", ()),
+            mock.request::<_, String>("eth_chainId", ())
+        );
+        assert!(a.is_ok() && b.is_ok());
+        assert!(start.elapsed() >= Duration::from_millis(10));
+        assert_eq!(mock.peak_in_flight(), 2);
+    }
+}
@@ -1,3 +1,16 @@
 mod http;
 
 pub use http::Http;
+
+#[cfg(target_arch = "wasm32")]
+mod eip1193;
+#[cfg(target_arch = "wasm32")]
+pub use eip1193::Eip1193;
+
+mod mock;
+pub use mock::{MockConnection, MockRequest};
+
+#[cfg(feature = "jsonrpsee")]
+mod jsonrpsee;
+#[cfg(feature = "jsonrpsee")]
+pub use self::jsonrpsee::JsonRpseeClient;
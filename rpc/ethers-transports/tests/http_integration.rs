@@ -1,19 +1,20 @@
+use ethers_test_node::Anvil;
 use ethers_transports::*;
 
-// TODO: start anvil for these tests
 #[tokio::test]
 async fn it_calls() {
-    let http: Http = "http://127.0.0.1:8545".parse().unwrap();
+    let anvil = Anvil::new().spawn();
+    let http: Http = anvil.endpoint().parse().unwrap();
     let resp: String = http.request("eth_chainId", ()).await.unwrap().unwrap();
     dbg!(resp);
 }
 
 #[tokio::test]
 async fn it_batch_calls() {
-    let http: Http = "http://127.0.0.1:8545".parse().unwrap();
+    let anvil = Anvil::new().spawn();
+    let http: Http = anvil.endpoint().parse().unwrap();
 
-    let reqs = std::iter::repeat("eth_chainId")
-        .take(5)
+    let reqs = std::iter::repeat_n("eth_chainId", 5)
         .map(|method| common::Request::owned(http.next_id(), method, None))
         .collect::<Vec<_>>();
     let resp = http.batch_request(&reqs).await.unwrap();
@@ -205,10 +205,9 @@ pub struct NetworkStatus {
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct EthProtocolInfo {
     /// The current difficulty at the head of the chain.
-    #[cfg_attr(
-        feature = "serde",
-        serde(deserialize_with = "reth_primitives::serde_helper::deserialize_json_u256")
-    )]
+    ///
+    /// Geth reports this as a JSON number, which may exceed `u64`
+    #[serde(deserialize_with = "crate::serde_helpers::deserialize_json_u256")]
     pub difficulty: U256,
     /// The block hash of the head of the chain.
     pub head: B256,
@@ -282,15 +281,55 @@ pub struct Ports {
     pub listener: u16,
 }
 
+/// Represents an entry of the `admin_peers` response.
+///
+/// Note: this format is not standardized, and follows Geth's format. Ids are
+/// kept as strings, as clients disagree on their encoding.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PeerInfo {
+    /// Enode of the peer in URL format.
+    pub enode: String,
+    /// ENR of the peer, if known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enr: Option<String>,
+    /// ID of the peer.
+    pub id: String,
+    /// Client identifier advertised by the peer.
+    pub name: String,
+    /// Capabilities advertised by the peer, e.g. `eth/68`.
+    pub caps: Vec<String>,
+    /// Connection details.
+    pub network: PeerNetworkInfo,
+    /// Per-protocol details. Protocols still handshaking may report a bare
+    /// string.
+    pub protocols: BTreeMap<String, Value>,
+}
+
+/// Connection details of a peer.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PeerNetworkInfo {
+    /// Local endpoint of the connection.
+    pub local_address: SocketAddr,
+    /// Remote endpoint of the connection.
+    pub remote_address: SocketAddr,
+    /// Whether the peer initiated the connection.
+    pub inbound: bool,
+    /// Whether the peer is trusted.
+    pub trusted: bool,
+    /// Whether the peer is a static peer.
+    #[serde(rename = "static")]
+    pub static_node: bool,
+}
+
 #[cfg(test)]
 mod tests {
     use ethers_pub_use::serde_json;
 
     use super::*;
 
-    // TODO
-    // #[test]
-    fn _test_parse_node_info_roundtrip() {
+    #[test]
+    fn test_parse_node_info_roundtrip() {
         let sample = r#"{"enode":"enode://44826a5d6a55f88a18298bca4773fca5749cdc3a5c9f308aa7d810e9b31123f3e7c5fba0b1d70aac5308426f47df2a128a6747040a3815cc7dd7167d03be320d@[::]:30303","id":"44826a5d6a55f88a18298bca4773fca5749cdc3a5c9f308aa7d810e9b31123f3e7c5fba0b1d70aac5308426f47df2a128a6747040a3815cc7dd7167d03be320d","ip":"::","listenAddr":"[::]:30303","name":"reth","ports":{"discovery":30303,"listener":30303},"protocols":{"eth":{"difficulty":17334254859343145000,"genesis":"0xd4e56740f876aef8c010b86a40d5f56745a118d0906a34e69aec8c0db1cb8fa3","head":"0xb83f73fbe6220c111136aefd27b160bf4a34085c65ba89f24246b3162257c36a","network":1}}}"#;
 
         let info: NodeInfo = serde_json::from_str(sample).unwrap();
@@ -298,4 +337,27 @@ mod tests {
         let de_serialized: NodeInfo = serde_json::from_str(&serialized).unwrap();
         assert_eq!(info, de_serialized)
     }
+
+    #[test]
+    fn test_parse_geth_node_info() {
+        // geth reports the total difficulty as a bare JSON number
+        let sample = r#"{"eth":{"difficulty":58750003716598352816469,"genesis":"0xd4e56740f876aef8c010b86a40d5f56745a118d0906a34e69aec8c0db1cb8fa3","head":"0xb83f73fbe6220c111136aefd27b160bf4a34085c65ba89f24246b3162257c36a","network":1},"snap":{}}"#;
+        let protocols: Protocols = serde_json::from_str(sample).unwrap();
+        assert!(protocols.eth.difficulty > U256::from(u64::MAX));
+        assert!(protocols.other.contains_key("snap"));
+
+        let sample = r#"{"difficulty":"0x10","genesis":"0xd4e56740f876aef8c010b86a40d5f56745a118d0906a34e69aec8c0db1cb8fa3","head":"0xb83f73fbe6220c111136aefd27b160bf4a34085c65ba89f24246b3162257c36a","network":1}"#;
+        let info: EthProtocolInfo = serde_json::from_str(sample).unwrap();
+        assert_eq!(info.difficulty, U256::from(16));
+    }
+
+    #[test]
+    fn test_parse_peers() {
+        let sample = r#"[{"enode":"enode://44826a5d6a55f88a18298bca4773fca5749cdc3a5c9f308aa7d810e9b31123f3e7c5fba0b1d70aac5308426f47df2a128a6747040a3815cc7dd7167d03be320d@10.0.0.2:30303","id":"1ca5c8ae8f5d2b0e8a0f9c3aa10a4d2b6a8e3c1b3d4a5f6e7d8c9b0a1f2e3d4c","name":"Geth/v1.11.5-stable/linux-amd64/go1.20.2","caps":["eth/66","eth/67","snap/1"],"network":{"localAddress":"10.0.0.1:50412","remoteAddress":"10.0.0.2:30303","inbound":false,"trusted":false,"static":true},"protocols":{"eth":{"version":67},"snap":"handshake"}}]"#;
+        let peers: Vec<PeerInfo> = serde_json::from_str(sample).unwrap();
+        assert_eq!(peers.len(), 1);
+        assert!(peers[0].network.static_node);
+        assert_eq!(peers[0].caps.len(), 3);
+        assert_eq!(peers[0].protocols["snap"], "handshake");
+    }
 }
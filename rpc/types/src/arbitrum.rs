@@ -0,0 +1,103 @@
+//! Arbitrum extensions: L1 gas fields of receipts, and the `NodeInterface`
+//! gas estimate, which splits L2 execution from L1 posting costs.
+
+use ethers_pub_use::serde::{Deserialize, Serialize};
+
+use ethers_primitives::{address, keccak256, Address, B256, U256, U64};
+
+/// The `NodeInterface` virtual contract, only callable via `eth_call` and
+/// `eth_estimateGas`
+pub const NODE_INTERFACE: Address = address!("0x00000000000000000000000000000000000000C8");
+
+/// The L1 fields of an Arbitrum receipt. Flattened into
+/// [`TransactionReceipt`](crate::TransactionReceipt)
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArbitrumReceiptFields {
+    /// L2 gas charged to pay for posting the transaction to L1. Included in
+    /// `gas_used`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gas_used_for_l1: Option<U256>,
+    /// The L1 block number the L2 block was derived at
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub l1_block_number: Option<U64>,
+}
+
+/// The result of `NodeInterface.gasEstimateComponents`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct GasEstimateComponents {
+    /// The total gas estimate, including L1 costs
+    pub gas_estimate: u64,
+    /// The part of the estimate paying for L1 posting
+    pub gas_estimate_for_l1: u64,
+    /// The L2 base fee
+    pub base_fee: U256,
+    /// The estimated L1 base fee
+    pub l1_base_fee_estimate: U256,
+}
+
+impl GasEstimateComponents {
+    /// Calldata of `gasEstimateComponents(address to, bool contractCreation,
+    /// bytes data)`, to be sent to [`NODE_INTERFACE`] with `eth_call`. The
+    /// call's `from`, `value` and fees should match the estimated
+    /// transaction
+    pub fn calldata(to: Address, contract_creation: bool, data: &[u8]) -> Vec<u8> {
+        let selector = keccak256("gasEstimateComponents(address,bool,bytes)");
+        let padded_len = data.len().div_ceil(32) * 32;
+
+        let mut out = Vec::with_capacity(4 + 32 * 4 + padded_len);
+        out.extend_from_slice(&selector[..4]);
+        let mut to_word = B256::default();
+        to_word.0[12..].copy_from_slice(to.as_bytes());
+        out.extend_from_slice(&to_word.0);
+        out.extend_from_slice(&B256::from(U256::from(contract_creation as u8)).0);
+        out.extend_from_slice(&B256::from(U256::from(32 * 3)).0);
+        out.extend_from_slice(&B256::from(U256::from(data.len())).0);
+        out.extend_from_slice(data);
+        out.resize(4 + 32 * 4 + padded_len, 0);
+        out
+    }
+
+    /// Decode the return data of the call. `None` if it is malformed
+    pub fn decode(data: &[u8]) -> Option<Self> {
+        if data.len() != 32 * 4 {
+            return None;
+        }
+        let word = |i: usize| U256::try_from_be_slice(&data[i * 32..(i + 1) * 32]);
+        Some(Self {
+            gas_estimate: word(0)?.try_into().ok()?,
+            gas_estimate_for_l1: word(1)?.try_into().ok()?,
+            base_fee: word(2)?,
+            l1_base_fee_estimate: word(3)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_encodes_gas_estimate_components() {
+        let calldata =
+            GasEstimateComponents::calldata(Address::repeat_byte(0x11), false, &[0xab; 33]);
+        assert_eq!(&calldata[..4], &[0xc9, 0x4e, 0x6e, 0xeb]);
+        assert_eq!(calldata.len(), 4 + 32 * 4 + 64);
+        assert_eq!(calldata[4 + 32 * 3 + 31], 33);
+
+        let mut ret = Vec::new();
+        for value in [500_000u64, 200_000, 100_000_000, 30_000_000_000] {
+            ret.extend_from_slice(&B256::from(U256::from(value)).0);
+        }
+        assert_eq!(
+            GasEstimateComponents::decode(&ret),
+            Some(GasEstimateComponents {
+                gas_estimate: 500_000,
+                gas_estimate_for_l1: 200_000,
+                base_fee: U256::from(100_000_000),
+                l1_base_fee_estimate: U256::from(30_000_000_000u64),
+            })
+        );
+        assert_eq!(GasEstimateComponents::decode(&ret[1..]), None);
+    }
+}
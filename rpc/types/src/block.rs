@@ -0,0 +1,478 @@
+use ethers_pub_use::serde::{
+    de::{self, MapAccess, Visitor},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+use std::{fmt, str::FromStr};
+
+use ethers_primitives::{Address, Bloom, B256, H64, U256, U64};
+
+use crate::{
+    other::{OtherFields, WithOtherFields},
+    Bytes,
+};
+
+/// A block number or tag, as used in block-parameterized JSON-RPC methods.
+///
+/// Serializes as a tag string (`"latest"`, `"safe"`, ...) or as a
+/// `0x`-prefixed hex quantity.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum BlockNumber {
+    /// The most recent block in the canonical chain
+    #[default]
+    Latest,
+    /// The genesis block
+    Earliest,
+    /// The pending block, not yet part of the canonical chain
+    Pending,
+    /// The most recent block that is safe from reorgs under honest majority
+    Safe,
+    /// The most recent finalized block
+    Finalized,
+    /// A block number
+    Number(u64),
+}
+
+impl BlockNumber {
+    /// Returns the numeric block number, if it is not a tag
+    pub fn as_number(&self) -> Option<u64> {
+        match self {
+            BlockNumber::Number(num) => Some(*num),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if this is [`BlockNumber::Latest`]
+    pub fn is_latest(&self) -> bool {
+        matches!(self, BlockNumber::Latest)
+    }
+
+    /// Returns `true` if this is [`BlockNumber::Pending`]
+    pub fn is_pending(&self) -> bool {
+        matches!(self, BlockNumber::Pending)
+    }
+
+    /// Returns `true` if this is [`BlockNumber::Earliest`]
+    pub fn is_earliest(&self) -> bool {
+        matches!(self, BlockNumber::Earliest)
+    }
+}
+
+impl From<u64> for BlockNumber {
+    fn from(num: u64) -> Self {
+        BlockNumber::Number(num)
+    }
+}
+
+impl fmt::Display for BlockNumber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BlockNumber::Latest => f.write_str("latest"),
+            BlockNumber::Earliest => f.write_str("earliest"),
+            BlockNumber::Pending => f.write_str("pending"),
+            BlockNumber::Safe => f.write_str("safe"),
+            BlockNumber::Finalized => f.write_str("finalized"),
+            BlockNumber::Number(num) => write!(f, "{num:#x}"),
+        }
+    }
+}
+
+/// Error parsing a [`BlockNumber`] or [`BlockId`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseBlockError(String);
+
+impl fmt::Display for ParseBlockError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid block identifier: {}", self.0)
+    }
+}
+
+impl std::error::Error for ParseBlockError {}
+
+impl FromStr for BlockNumber {
+    type Err = ParseBlockError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let number = match s {
+            "latest" => BlockNumber::Latest,
+            "earliest" => BlockNumber::Earliest,
+            "pending" => BlockNumber::Pending,
+            "safe" => BlockNumber::Safe,
+            "finalized" => BlockNumber::Finalized,
+            _ => {
+                // `from_str_radix` accepts a leading sign
+                let hex = s
+                    .strip_prefix("0x")
+                    .filter(|hex| hex.bytes().all(|b| b.is_ascii_hexdigit()))
+                    .ok_or_else(|| ParseBlockError(s.to_owned()))?;
+                u64::from_str_radix(hex, 16)
+                    .map(BlockNumber::Number)
+                    .map_err(|_| ParseBlockError(s.to_owned()))?
+            }
+        };
+        Ok(number)
+    }
+}
+
+impl Serialize for BlockNumber {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for BlockNumber {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = <std::borrow::Cow<'_, str>>::deserialize(deserializer)?;
+        s.parse().map_err(de::Error::custom)
+    }
+}
+
+/// A block hash or block number, as used in block-parameterized JSON-RPC
+/// methods.
+///
+/// Hashes serialize as an [EIP-1898] object, numbers and tags as a plain
+/// string.
+///
+/// [EIP-1898]: https://eips.ethereum.org/EIPS/eip-1898
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum BlockId {
+    /// A block hash, and whether the block must be canonical
+    Hash {
+        /// The block hash
+        hash: B256,
+        /// If `Some(true)`, the node errors if the block is not canonical
+        require_canonical: Option<bool>,
+    },
+    /// A block number or tag
+    Number(BlockNumber),
+}
+
+impl BlockId {
+    /// The hash, if this is a [`BlockId::Hash`]
+    pub fn as_hash(&self) -> Option<B256> {
+        match self {
+            BlockId::Hash { hash, .. } => Some(*hash),
+            _ => None,
+        }
+    }
+
+    /// The block number, if this is a [`BlockId::Number`]
+    pub fn as_block_number(&self) -> Option<BlockNumber> {
+        match self {
+            BlockId::Number(num) => Some(*num),
+            _ => None,
+        }
+    }
+}
+
+impl Default for BlockId {
+    fn default() -> Self {
+        BlockNumber::Latest.into()
+    }
+}
+
+impl From<u64> for BlockId {
+    fn from(num: u64) -> Self {
+        BlockNumber::Number(num).into()
+    }
+}
+
+impl From<BlockNumber> for BlockId {
+    fn from(num: BlockNumber) -> Self {
+        BlockId::Number(num)
+    }
+}
+
+impl From<B256> for BlockId {
+    fn from(hash: B256) -> Self {
+        BlockId::Hash {
+            hash,
+            require_canonical: None,
+        }
+    }
+}
+
+impl Serialize for BlockId {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        use ethers_pub_use::serde::ser::SerializeStruct;
+
+        match self {
+            BlockId::Hash {
+                hash,
+                require_canonical,
+            } => {
+                let len = if require_canonical.is_some() { 2 } else { 1 };
+                let mut s = serializer.serialize_struct("BlockIdEip1898", len)?;
+                s.serialize_field("blockHash", hash)?;
+                if let Some(require_canonical) = require_canonical {
+                    s.serialize_field("requireCanonical", require_canonical)?;
+                }
+                s.end()
+            }
+            BlockId::Number(num) => num.serialize(serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for BlockId {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct BlockIdVisitor;
+
+        impl<'de> Visitor<'de> for BlockIdVisitor {
+            type Value = BlockId;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+                formatter.write_str("a block tag, number, hash, or EIP-1898 object")
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+                // a 32-byte hex string is a hash, anything else must be a
+                // number or tag
+                if v.len() == 66 && v.starts_with("0x") {
+                    let hash = B256::from_str(&v[2..]).map_err(E::custom)?;
+                    return Ok(hash.into());
+                }
+                v.parse::<BlockNumber>().map(Into::into).map_err(E::custom)
+            }
+
+            fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+            where
+                A: MapAccess<'de>,
+            {
+                let mut number = None;
+                let mut hash = None;
+                let mut require_canonical = None;
+
+                while let Some(key) = map.next_key::<String>()? {
+                    match key.as_str() {
+                        "blockNumber" => {
+                            if number.is_some() || hash.is_some() {
+                                return Err(de::Error::duplicate_field("blockNumber"));
+                            }
+                            number = Some(map.next_value::<BlockNumber>()?);
+                        }
+                        "blockHash" => {
+                            if number.is_some() || hash.is_some() {
+                                return Err(de::Error::duplicate_field("blockHash"));
+                            }
+                            hash = Some(map.next_value::<B256>()?);
+                        }
+                        "requireCanonical" => {
+                            require_canonical = Some(map.next_value::<bool>()?);
+                        }
+                        key => {
+                            return Err(de::Error::unknown_field(
+                                key,
+                                &["blockNumber", "blockHash", "requireCanonical"],
+                            ))
+                        }
+                    }
+                }
+
+                match (number, hash) {
+                    (Some(number), None) => Ok(BlockId::Number(number)),
+                    (None, Some(hash)) => Ok(BlockId::Hash {
+                        hash,
+                        require_canonical,
+                    }),
+                    _ => Err(de::Error::custom(
+                        "expected exactly one of `blockNumber` or `blockHash`",
+                    )),
+                }
+            }
+        }
+
+        deserializer.deserialize_any(BlockIdVisitor)
+    }
+}
+
+/// A block header, as returned in `newHeads` subscriptions and at the top
+/// level of `eth_getBlockBy*` responses.
+///
+/// `hash`, `number`, `nonce` and `mix_hash` are `None` for pending blocks.
+/// Fork-specific fields are `None` before the fork that introduced them.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Header {
+    /// Hash of the block
+    pub hash: Option<B256>,
+    /// Hash of the parent block
+    pub parent_hash: B256,
+    /// Hash of the uncles list
+    #[serde(rename = "sha3Uncles")]
+    pub uncles_hash: B256,
+    /// Beneficiary of the block rewards
+    pub miner: Address,
+    /// State trie root after the block
+    pub state_root: B256,
+    /// Transaction trie root
+    pub transactions_root: B256,
+    /// Receipt trie root
+    pub receipts_root: B256,
+    /// Bloom filter over the logs of the block
+    pub logs_bloom: Bloom,
+    /// Block difficulty. Zero after the merge
+    pub difficulty: U256,
+    /// Block number
+    pub number: Option<U64>,
+    /// Gas limit of the block
+    pub gas_limit: U256,
+    /// Gas used by all transactions in the block
+    pub gas_used: U256,
+    /// Block timestamp, in seconds since the unix epoch
+    pub timestamp: U256,
+    /// Arbitrary data set by the block producer
+    pub extra_data: Bytes,
+    /// Proof-of-work mix hash, or the beacon chain randomness after the merge
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mix_hash: Option<B256>,
+    /// Proof-of-work nonce
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<H64>,
+    /// Base fee per gas, since London
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_fee_per_gas: Option<U256>,
+    /// Withdrawals trie root, since Shanghai
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub withdrawals_root: Option<B256>,
+    /// Fields outside the spec, such as those added by L2s
+    #[serde(flatten)]
+    pub other: OtherFields,
+}
+
+impl WithOtherFields for Header {
+    fn other_fields(&self) -> &OtherFields {
+        &self.other
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ethers_pub_use::serde_json;
+
+    use super::*;
+    use crate::Strict;
+
+    #[test]
+    fn block_number_serde() {
+        let cases = [
+            (BlockNumber::Latest, "\"latest\""),
+            (BlockNumber::Earliest, "\"earliest\""),
+            (BlockNumber::Pending, "\"pending\""),
+            (BlockNumber::Safe, "\"safe\""),
+            (BlockNumber::Finalized, "\"finalized\""),
+            (BlockNumber::Number(0), "\"0x0\""),
+            (BlockNumber::Number(0x1b4), "\"0x1b4\""),
+        ];
+        for (num, json) in cases {
+            assert_eq!(serde_json::to_string(&num).unwrap(), json);
+            assert_eq!(serde_json::from_str::<BlockNumber>(json).unwrap(), num);
+        }
+        assert!(serde_json::from_str::<BlockNumber>("\"1b4\"").is_err());
+        assert!(serde_json::from_str::<BlockNumber>("\"newest\"").is_err());
+        for invalid in ["0x", "0x+1", "0x-1", "0x 1", "0x1g"] {
+            assert_eq!(
+                invalid.parse::<BlockNumber>(),
+                Err(ParseBlockError(invalid.to_owned()))
+            );
+        }
+    }
+
+    #[test]
+    fn block_id_serde() {
+        let hash = B256::repeat_byte(0xab);
+        let hash_str = format!("{hash:?}");
+
+        let id = BlockId::from(hash);
+        let json = serde_json::to_string(&id).unwrap();
+        assert_eq!(json, format!(r#"{{"blockHash":"{hash_str}"}}"#));
+        assert_eq!(serde_json::from_str::<BlockId>(&json).unwrap(), id);
+
+        let canonical = BlockId::Hash {
+            hash,
+            require_canonical: Some(true),
+        };
+        let json = serde_json::to_string(&canonical).unwrap();
+        assert_eq!(
+            json,
+            format!(r#"{{"blockHash":"{hash_str}","requireCanonical":true}}"#)
+        );
+        assert_eq!(serde_json::from_str::<BlockId>(&json).unwrap(), canonical);
+
+        let id = BlockId::from(0x10);
+        assert_eq!(serde_json::to_string(&id).unwrap(), "\"0x10\"");
+        assert_eq!(serde_json::from_str::<BlockId>("\"0x10\"").unwrap(), id);
+        assert_eq!(
+            serde_json::from_str::<BlockId>(r#"{"blockNumber":"0x10"}"#).unwrap(),
+            id
+        );
+        assert_eq!(
+            serde_json::from_str::<BlockId>(&format!("\"{hash_str}\"")).unwrap(),
+            BlockId::from(hash)
+        );
+        assert!(serde_json::from_str::<BlockId>(&format!(
+            r#"{{"blockNumber":"0x10","blockHash":"{hash_str}"}}"#
+        ))
+        .is_err());
+    }
+
+    #[test]
+    fn header_serde() {
+        // mainnet block 17_000_000, as delivered by a `newHeads` subscription
+        let json = r#"{
+            "baseFeePerGas": "0x4c9c1b7a5",
+            "difficulty": "0x0",
+            "extraData": "0x6265617665726275696c642e6f7267",
+            "gasLimit": "0x1c9c380",
+            "gasUsed": "0x1011e36",
+            "hash": "0xe9f1d0d4e0f7e3bc4e0f6a9a3d1e3c0c4a5e2c7ee0f8d2e3c7b7b7f4f1a0d1a2",
+            "logsBloom": "0x00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000",
+            "miner": "0x95222290dd7278aa3ddd389cc1e1d165cc4bafe5",
+            "mixHash": "0x6b6a2c7c3e2c8b2f3d0e0a1b9c8d7e6f5a4b3c2d1e0f9a8b7c6d5e4f3a2b1c0d",
+            "nonce": "0x0000000000000000",
+            "number": "0x1036640",
+            "parentHash": "0x2b9d2d8f3d2e1a8a5e1c3b4f6d7e8f9a0b1c2d3e4f5a6b7c8d9e0f1a2b3c4d5e",
+            "receiptsRoot": "0x4a5b6c7d8e9f0a1b2c3d4e5f6a7b8c9d0e1f2a3b4c5d6e7f8a9b0c1d2e3f4a5b",
+            "sha3Uncles": "0x1dcc4de8dec75d7aab85b567b6ccd41ad312451b948a7413f0a142fd40d49347",
+            "stateRoot": "0x5a6b7c8d9e0f1a2b3c4d5e6f7a8b9c0d1e2f3a4b5c6d7e8f9a0b1c2d3e4f5a6b",
+            "timestamp": "0x642b9d17",
+            "transactionsRoot": "0x6a7b8c9d0e1f2a3b4c5d6e7f8a9b0c1d2e3f4a5b6c7d8e9f0a1b2c3d4e5f6a7b",
+            "withdrawalsRoot": "0x7a8b9c0d1e2f3a4b5c6d7e8f9a0b1c2d3e4f5a6b7c8d9e0f1a2b3c4d5e6f7a8b"
+        }"#;
+        let header: Header = serde_json::from_str(json).unwrap();
+        assert_eq!(header.number, Some(U64::from(17_000_000)));
+        assert_eq!(&header.extra_data[..], b"beaverbuild.org");
+        assert_eq!(header.difficulty, U256::ZERO);
+        assert!(header.withdrawals_root.is_some());
+        assert!(header.other.is_empty());
+        assert!(serde_json::from_str::<Strict<Header>>(json).is_ok());
+
+        let round_trip: Header =
+            serde_json::from_value(serde_json::to_value(&header).unwrap()).unwrap();
+        assert_eq!(round_trip, header);
+    }
+
+    #[test]
+    fn header_other_fields() {
+        let mut json = serde_json::to_value(Header::default()).unwrap();
+        json["l1BlockNumber"] = "0x112a880".into();
+
+        let header: Header = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(header.other["l1BlockNumber"], "0x112a880");
+        assert_eq!(serde_json::to_value(&header).unwrap(), json);
+
+        let err = serde_json::from_value::<Strict<Header>>(json).unwrap_err();
+        assert_eq!(err.to_string(), "unknown field `l1BlockNumber`");
+    }
+}
@@ -0,0 +1,262 @@
+//! [Engine API] types, used by consensus clients to drive execution clients.
+//!
+//! Versioned types extend the previous version with the fields introduced
+//! by a fork: V2 by Shanghai, V3 by Cancun.
+//!
+//! [Engine API]: https://github.com/ethereum/execution-apis/tree/main/src/engine
+
+use ethers_pub_use::serde::{Deserialize, Serialize};
+
+use ethers_primitives::{Address, Bloom, B256, H64, U256, U64};
+
+use crate::Bytes;
+
+/// Identifies a payload being built by the execution client
+pub type PayloadId = H64;
+
+/// A validator withdrawal, since Shanghai
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Withdrawal {
+    /// Monotonically increasing index of the withdrawal
+    pub index: U64,
+    /// Index of the withdrawing validator
+    pub validator_index: U64,
+    /// Recipient of the withdrawn ether
+    pub address: Address,
+    /// Amount withdrawn, in gwei
+    pub amount: U64,
+}
+
+/// An execution payload, as introduced by the merge
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecutionPayloadV1 {
+    /// Hash of the parent block
+    pub parent_hash: B256,
+    /// Beneficiary of the priority fees
+    pub fee_recipient: Address,
+    /// State trie root after the block
+    pub state_root: B256,
+    /// Receipt trie root
+    pub receipts_root: B256,
+    /// Bloom filter over the logs of the block
+    pub logs_bloom: Bloom,
+    /// Beacon chain randomness
+    pub prev_randao: B256,
+    /// Block number
+    pub block_number: U64,
+    /// Gas limit of the block
+    pub gas_limit: U64,
+    /// Gas used by all transactions in the block
+    pub gas_used: U64,
+    /// Block timestamp, in seconds since the unix epoch
+    pub timestamp: U64,
+    /// Arbitrary data set by the block producer
+    pub extra_data: Bytes,
+    /// Base fee per gas
+    pub base_fee_per_gas: U256,
+    /// Hash of the block
+    pub block_hash: B256,
+    /// EIP-2718 encoded transactions
+    pub transactions: Vec<Bytes>,
+}
+
+/// An execution payload with withdrawals, since Shanghai
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecutionPayloadV2 {
+    /// The V1 fields
+    #[serde(flatten)]
+    pub payload_inner: ExecutionPayloadV1,
+    /// Validator withdrawals
+    pub withdrawals: Vec<Withdrawal>,
+}
+
+/// An execution payload with blob gas accounting, since Cancun
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecutionPayloadV3 {
+    /// The V2 fields
+    #[serde(flatten)]
+    pub payload_inner: ExecutionPayloadV2,
+    /// Blob gas used by all transactions in the block
+    pub blob_gas_used: U64,
+    /// Excess blob gas, for the blob base fee
+    pub excess_blob_gas: U64,
+}
+
+/// The result of validating a payload
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum PayloadStatusKind {
+    /// The payload is valid
+    Valid,
+    /// The payload, or one of its ancestors, is invalid
+    Invalid,
+    /// The client is syncing and could not validate the payload
+    Syncing,
+    /// The payload extends a side chain, and was not validated
+    Accepted,
+    /// The payload's block hash does not match its contents
+    InvalidBlockHash,
+}
+
+/// The status of a payload, as returned by `engine_newPayload*` and
+/// `engine_forkchoiceUpdated*`
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PayloadStatus {
+    /// The validation result
+    pub status: PayloadStatusKind,
+    /// Hash of the most recent valid block in the payload's chain
+    pub latest_valid_hash: Option<B256>,
+    /// Why the payload is invalid
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub validation_error: Option<String>,
+}
+
+impl PayloadStatus {
+    /// `true` if the payload is valid
+    pub fn is_valid(&self) -> bool {
+        self.status == PayloadStatusKind::Valid
+    }
+}
+
+/// The consensus client's view of the chain
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ForkchoiceState {
+    /// Hash of the head of the canonical chain
+    pub head_block_hash: B256,
+    /// Hash of the most recent justified block
+    pub safe_block_hash: B256,
+    /// Hash of the most recent finalized block
+    pub finalized_block_hash: B256,
+}
+
+/// Attributes of a payload to build on top of the new head.
+///
+/// `withdrawals` are required since Shanghai, and
+/// `parent_beacon_block_root` since Cancun.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PayloadAttributes {
+    /// Timestamp of the payload
+    pub timestamp: U64,
+    /// Beacon chain randomness for the payload
+    pub prev_randao: B256,
+    /// Beneficiary of the priority fees
+    pub suggested_fee_recipient: Address,
+    /// Withdrawals to include, since Shanghai
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub withdrawals: Option<Vec<Withdrawal>>,
+    /// Root of the parent beacon block, since Cancun
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_beacon_block_root: Option<B256>,
+}
+
+/// The response to `engine_forkchoiceUpdated*`
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ForkchoiceUpdated {
+    /// The status of the new head
+    pub payload_status: PayloadStatus,
+    /// The id of the payload being built, if attributes were given and the
+    /// head is valid
+    pub payload_id: Option<PayloadId>,
+}
+
+/// The response to `engine_getPayloadV2`
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecutionPayloadEnvelopeV2 {
+    /// The built payload. Has no withdrawals if built before Shanghai
+    pub execution_payload: ExecutionPayloadV2,
+    /// Fees paid to the fee recipient, in wei
+    pub block_value: U256,
+}
+
+/// Blobs of the transactions in a payload, with their commitments and proofs
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlobsBundleV1 {
+    /// KZG commitments to the blobs
+    pub commitments: Vec<Bytes>,
+    /// KZG proofs of the blobs
+    pub proofs: Vec<Bytes>,
+    /// The blobs
+    pub blobs: Vec<Bytes>,
+}
+
+/// The response to `engine_getPayloadV3`
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecutionPayloadEnvelopeV3 {
+    /// The built payload
+    pub execution_payload: ExecutionPayloadV3,
+    /// Fees paid to the fee recipient, in wei
+    pub block_value: U256,
+    /// Blobs of the payload's transactions
+    pub blobs_bundle: BlobsBundleV1,
+    /// `true` if the execution client suggests building locally rather
+    /// than using a builder's payload
+    pub should_override_builder: bool,
+}
+
+#[cfg(test)]
+mod test {
+    use ethers_pub_use::serde_json::{self, json};
+
+    use super::*;
+
+    #[test]
+    fn it_serializes_v3_payloads() {
+        let payload = ExecutionPayloadV3 {
+            payload_inner: ExecutionPayloadV2 {
+                payload_inner: ExecutionPayloadV1 {
+                    block_number: U64::from(1),
+                    transactions: vec![Bytes::from(vec![0x02, 0xf8])],
+                    ..Default::default()
+                },
+                withdrawals: vec![Withdrawal {
+                    index: U64::from(7),
+                    amount: U64::from(32_000_000_000u64),
+                    ..Default::default()
+                }],
+            },
+            blob_gas_used: U64::from(0x20000),
+            excess_blob_gas: U64::ZERO,
+        };
+
+        let json = serde_json::to_value(&payload).unwrap();
+        assert_eq!(json["blockNumber"], "0x1");
+        assert_eq!(json["transactions"], json!(["0x02f8"]));
+        assert_eq!(json["withdrawals"][0]["amount"], "0x773594000");
+        assert_eq!(json["blobGasUsed"], "0x20000");
+        assert_eq!(
+            serde_json::from_value::<ExecutionPayloadV3>(json).unwrap(),
+            payload
+        );
+    }
+
+    #[test]
+    fn it_deserializes_forkchoice_responses() {
+        let json = r#"{
+            "payloadStatus": {
+                "status": "VALID",
+                "latestValidHash": "0x3559e851470f6e7bbed1db474980683e8c315bfce99b2a6ef47c057c04de7858",
+                "validationError": null
+            },
+            "payloadId": "0xa247243752eb10b4"
+        }"#;
+        let resp: ForkchoiceUpdated = serde_json::from_str(json).unwrap();
+        assert!(resp.payload_status.is_valid());
+        assert!(resp.payload_id.is_some());
+
+        let status: PayloadStatus =
+            serde_json::from_str(r#"{"status": "INVALID_BLOCK_HASH", "latestValidHash": null}"#)
+                .unwrap();
+        assert_eq!(status.status, PayloadStatusKind::InvalidBlockHash);
+    }
+}
@@ -0,0 +1,130 @@
+use std::{fmt, str::FromStr};
+
+use ethers_pub_use::serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+use ethers_primitives::Address;
+
+/// An ENS name or an address.
+///
+/// Names must be resolved to addresses before a request is sent to a node.
+/// Nodes reject unresolved names
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum NameOrAddress {
+    /// An ENS name, e.g. `vitalik.eth`
+    Name(String),
+    /// An address
+    Address(Address),
+}
+
+impl NameOrAddress {
+    /// The address, if this is an address
+    pub fn as_address(&self) -> Option<&Address> {
+        match self {
+            NameOrAddress::Address(address) => Some(address),
+            NameOrAddress::Name(_) => None,
+        }
+    }
+
+    /// The name, if this is a name
+    pub fn as_name(&self) -> Option<&str> {
+        match self {
+            NameOrAddress::Name(name) => Some(name),
+            NameOrAddress::Address(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for NameOrAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NameOrAddress::Name(name) => f.write_str(name),
+            NameOrAddress::Address(address) => write!(f, "{address:?}"),
+        }
+    }
+}
+
+impl From<Address> for NameOrAddress {
+    fn from(address: Address) -> Self {
+        NameOrAddress::Address(address)
+    }
+}
+
+impl From<&str> for NameOrAddress {
+    fn from(s: &str) -> Self {
+        s.parse().expect("infallible")
+    }
+}
+
+impl From<String> for NameOrAddress {
+    fn from(s: String) -> Self {
+        match s.parse::<Address>() {
+            Ok(address) => NameOrAddress::Address(address),
+            Err(_) => NameOrAddress::Name(s),
+        }
+    }
+}
+
+impl FromStr for NameOrAddress {
+    type Err = std::convert::Infallible;
+
+    /// Parse an address, falling back to a name
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(s.to_owned().into())
+    }
+}
+
+impl Serialize for NameOrAddress {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            NameOrAddress::Name(name) => name.serialize(serializer),
+            NameOrAddress::Address(address) => address.serialize(serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for NameOrAddress {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        if s.starts_with("0x") {
+            return s
+                .parse::<Address>()
+                .map(NameOrAddress::Address)
+                .map_err(de::Error::custom);
+        }
+        Ok(NameOrAddress::Name(s))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ethers_pub_use::serde_json;
+
+    use super::*;
+
+    #[test]
+    fn it_parses_names_and_addresses() {
+        let address = Address::repeat_byte(0x11);
+        assert_eq!(
+            NameOrAddress::from("0x1111111111111111111111111111111111111111"),
+            NameOrAddress::Address(address)
+        );
+        assert_eq!(
+            NameOrAddress::from("vitalik.eth"),
+            NameOrAddress::Name("vitalik.eth".to_owned())
+        );
+
+        let json = serde_json::to_string(&NameOrAddress::Address(address)).unwrap();
+        assert_eq!(json, r#""0x1111111111111111111111111111111111111111""#);
+        assert_eq!(
+            serde_json::from_str::<NameOrAddress>(&json).unwrap(),
+            NameOrAddress::Address(address)
+        );
+        assert!(serde_json::from_str::<NameOrAddress>(r#""0x1234""#).is_err());
+    }
+}
@@ -0,0 +1,538 @@
+//! [ERC-4337] account abstraction types
+//!
+//! [ERC-4337]: https://eips.ethereum.org/EIPS/eip-4337
+
+use ethers_pub_use::{
+    serde::{Deserialize, Serialize},
+    thiserror,
+};
+
+use ethers_primitives::{keccak256, Address, Bytes, B256, U256, U64};
+
+use crate::{Log, TransactionReceipt};
+
+/// Length of an ECDSA signature in `r || s || v` form
+const SIGNATURE_LEN: usize = 65;
+
+/// Largest value of a solidity `uint48`, used for paymaster validity windows
+pub const MAX_UINT48: u64 = (1 << 48) - 1;
+
+/// Errors building or parsing `paymasterAndData`
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum PaymasterDataError {
+    /// The data is shorter than the paymaster address plus the mode data
+    #[error("paymasterAndData too short: expected at least {expected} bytes, got {actual}")]
+    TooShort {
+        /// Minimum length for the mode
+        expected: usize,
+        /// Actual length
+        actual: usize,
+    },
+    /// A validity timestamp does not fit in a `uint48`
+    #[error("timestamp {0} does not fit in a uint48")]
+    TimestampOverflow(u64),
+    /// A signature is not 65 bytes long
+    #[error("invalid paymaster signature length: {0}")]
+    SignatureLength(usize),
+    /// A word that should hold a `uint48` has high bits set
+    #[error("invalid uint48 encoding")]
+    InvalidUint48,
+}
+
+/// Data for a verifying (sponsoring) paymaster.
+///
+/// The paymaster's off-chain signer approves the user operation for the
+/// validity window, and the paymaster contract checks that signature
+/// on-chain. Encoded as `abi.encode(validUntil, validAfter) || signature`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SponsorshipData {
+    /// Timestamp after which the sponsorship expires. 0 means no expiry
+    pub valid_until: u64,
+    /// Timestamp before which the sponsorship is not valid
+    pub valid_after: u64,
+    /// Signature of the paymaster's signer. Empty while the sponsorship is
+    /// being requested
+    pub signature: Vec<u8>,
+}
+
+impl SponsorshipData {
+    /// Instantiate unsigned sponsorship data for a validity window
+    pub fn new(valid_until: u64, valid_after: u64) -> Result<Self, PaymasterDataError> {
+        for ts in [valid_until, valid_after] {
+            if ts > MAX_UINT48 {
+                return Err(PaymasterDataError::TimestampOverflow(ts));
+            }
+        }
+        Ok(Self {
+            valid_until,
+            valid_after,
+            signature: vec![],
+        })
+    }
+
+    /// Attach the paymaster signer's signature
+    pub fn with_signature(mut self, signature: &[u8]) -> Result<Self, PaymasterDataError> {
+        if signature.len() != SIGNATURE_LEN {
+            return Err(PaymasterDataError::SignatureLength(signature.len()));
+        }
+        self.signature = signature.to_vec();
+        Ok(self)
+    }
+
+    /// `true` if the signature has been attached
+    pub fn is_signed(&self) -> bool {
+        self.signature.len() == SIGNATURE_LEN
+    }
+
+    /// The hash the paymaster's signer approves, as computed by the
+    /// EntryPoint v0.6 `VerifyingPaymaster.getHash`. The signer signs it
+    /// with the EIP-191 prefix.
+    ///
+    /// The hash covers the operation's gas fields, but not its
+    /// `paymasterAndData` or signature, so the operation's gas must be set
+    /// before it is sponsored
+    pub fn hash(&self, op: &UserOperation, paymaster: Address, chain_id: u64) -> B256 {
+        let words = [
+            address_word(op.sender),
+            op.nonce.into(),
+            keccak256(&op.init_code),
+            keccak256(&op.call_data),
+            op.call_gas_limit.into(),
+            op.verification_gas_limit.into(),
+            op.pre_verification_gas.into(),
+            op.max_fee_per_gas.into(),
+            op.max_priority_fee_per_gas.into(),
+            U256::from(chain_id).into(),
+            address_word(paymaster),
+            U256::from(self.valid_until).into(),
+            U256::from(self.valid_after).into(),
+        ];
+        keccak256(words.iter().flat_map(|word| word.0).collect::<Vec<_>>())
+    }
+}
+
+/// Data for a token paymaster, which pays gas in exchange for ERC-20
+/// tokens. Encoded as `token || abi.encode(maxTokenCost)`
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TokenPaymasterData {
+    /// The ERC-20 token the sender pays in
+    pub token: Address,
+    /// The maximum number of tokens the sender is willing to pay
+    pub max_token_cost: U256,
+}
+
+/// The paymaster-specific part of `paymasterAndData`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PaymasterMode {
+    /// A verifying paymaster sponsors the operation
+    Sponsored(SponsorshipData),
+    /// A token paymaster is paid in ERC-20 tokens
+    Token(TokenPaymasterData),
+    /// Any other paymaster, with opaque data
+    Other(Vec<u8>),
+}
+
+/// The `paymasterAndData` field of a user operation: the paymaster address
+/// followed by paymaster-specific data.
+///
+/// Building this field by concatenating bytes by hand is error prone. This
+/// type encodes and parses the common layouts.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PaymasterAndData {
+    /// The paymaster contract
+    pub paymaster: Address,
+    /// The paymaster-specific data
+    pub mode: PaymasterMode,
+}
+
+impl PaymasterAndData {
+    /// `paymasterAndData` for a verifying paymaster
+    pub fn sponsored(paymaster: Address, data: SponsorshipData) -> Self {
+        Self {
+            paymaster,
+            mode: PaymasterMode::Sponsored(data),
+        }
+    }
+
+    /// `paymasterAndData` for a token paymaster
+    pub fn token(paymaster: Address, token: Address, max_token_cost: U256) -> Self {
+        Self {
+            paymaster,
+            mode: PaymasterMode::Token(TokenPaymasterData {
+                token,
+                max_token_cost,
+            }),
+        }
+    }
+
+    /// Encode the field
+    pub fn encode(&self) -> Vec<u8> {
+        let mut out = self.paymaster.as_bytes().to_vec();
+        match &self.mode {
+            PaymasterMode::Sponsored(data) => {
+                out.extend_from_slice(&U256::from(data.valid_until).to_be_bytes::<32>());
+                out.extend_from_slice(&U256::from(data.valid_after).to_be_bytes::<32>());
+                out.extend_from_slice(&data.signature);
+            }
+            PaymasterMode::Token(data) => {
+                out.extend_from_slice(data.token.as_bytes());
+                out.extend_from_slice(&data.max_token_cost.to_be_bytes::<32>());
+            }
+            PaymasterMode::Other(data) => out.extend_from_slice(data),
+        }
+        out
+    }
+
+    /// Parse a field known to be for a verifying paymaster. The signature
+    /// may be absent
+    pub fn decode_sponsored(data: &[u8]) -> Result<Self, PaymasterDataError> {
+        let (paymaster, rest) = split_paymaster(data, 64)?;
+        let valid_until = decode_uint48(&rest[..32])?;
+        let valid_after = decode_uint48(&rest[32..64])?;
+        let signature = &rest[64..];
+        if !signature.is_empty() && signature.len() != SIGNATURE_LEN {
+            return Err(PaymasterDataError::SignatureLength(signature.len()));
+        }
+        Ok(Self::sponsored(
+            paymaster,
+            SponsorshipData {
+                valid_until,
+                valid_after,
+                signature: signature.to_vec(),
+            },
+        ))
+    }
+
+    /// Parse a field known to be for a token paymaster
+    pub fn decode_token(data: &[u8]) -> Result<Self, PaymasterDataError> {
+        let (paymaster, rest) = split_paymaster(data, 52)?;
+        let token = Address::from_slice(&rest[..20]);
+        let max_token_cost = U256::from_be_slice(&rest[20..52]);
+        Ok(Self::token(paymaster, token, max_token_cost))
+    }
+}
+
+/// A user operation, as sent to an EntryPoint v0.6 bundler. The account
+/// `sender` executes `call_data`, after validating `signature`
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserOperation {
+    /// The account sending the operation
+    pub sender: Address,
+    /// Anti-replay nonce. The high 192 bits are the nonce key
+    pub nonce: U256,
+    /// Factory address and calldata deploying the account. Empty once the
+    /// account exists
+    pub init_code: Bytes,
+    /// Calldata of the account's execution call
+    pub call_data: Bytes,
+    /// Gas for the execution call
+    pub call_gas_limit: U256,
+    /// Gas for account creation and validation
+    pub verification_gas_limit: U256,
+    /// Gas paid to the bundler for overhead not metered on chain
+    pub pre_verification_gas: U256,
+    /// Maximum fee per gas, as in EIP-1559
+    pub max_fee_per_gas: U256,
+    /// Maximum priority fee per gas, as in EIP-1559
+    pub max_priority_fee_per_gas: U256,
+    /// Paymaster address and data. Empty if the sender pays. See
+    /// [`PaymasterAndData`]
+    pub paymaster_and_data: Bytes,
+    /// Signature checked by the account. Not covered by the hash
+    pub signature: Bytes,
+}
+
+impl UserOperation {
+    /// Set the paymaster
+    #[must_use = "Builder method outputs must be used"]
+    pub fn paymaster(mut self, paymaster: &PaymasterAndData) -> Self {
+        self.paymaster_and_data = paymaster.encode().into();
+        self
+    }
+
+    /// Set the gas limits to a bundler's estimate
+    #[must_use = "Builder method outputs must be used"]
+    pub fn gas(mut self, estimate: &UserOperationGasEstimate) -> Self {
+        self.call_gas_limit = estimate.call_gas_limit;
+        self.verification_gas_limit = estimate.verification_gas_limit;
+        self.pre_verification_gas = estimate.pre_verification_gas;
+        self
+    }
+
+    /// The operation as hashed by the EntryPoint: the ABI encoding of its
+    /// fields without the signature, with the dynamic fields replaced by
+    /// their hashes
+    pub fn pack(&self) -> Vec<u8> {
+        let words = [
+            address_word(self.sender),
+            self.nonce.into(),
+            keccak256(&self.init_code),
+            keccak256(&self.call_data),
+            self.call_gas_limit.into(),
+            self.verification_gas_limit.into(),
+            self.pre_verification_gas.into(),
+            self.max_fee_per_gas.into(),
+            self.max_priority_fee_per_gas.into(),
+            keccak256(&self.paymaster_and_data),
+        ];
+        words.iter().flat_map(|word| word.0).collect()
+    }
+
+    /// The hash the account signs, and the bundler identifies the operation
+    /// by. Binds the packed operation to an EntryPoint and a chain
+    pub fn hash(&self, entry_point: Address, chain_id: u64) -> B256 {
+        let mut preimage = keccak256(self.pack()).0.to_vec();
+        preimage.extend_from_slice(&address_word(entry_point).0);
+        preimage.extend_from_slice(&B256::from(U256::from(chain_id)).0);
+        keccak256(preimage)
+    }
+}
+
+/// The gas limits estimated by `eth_estimateUserOperationGas`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserOperationGasEstimate {
+    /// Gas paid to the bundler for overhead not metered on chain
+    pub pre_verification_gas: U256,
+    /// Gas for account creation and validation
+    pub verification_gas_limit: U256,
+    /// Gas for the execution call
+    pub call_gas_limit: U256,
+}
+
+/// A user operation, as returned by `eth_getUserOperationByHash`. The
+/// block and transaction are `None` while the operation is pending
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserOperationByHash {
+    /// The operation
+    pub user_operation: UserOperation,
+    /// The EntryPoint it was sent to
+    pub entry_point: Address,
+    /// Number of the containing block
+    pub block_number: Option<U64>,
+    /// Hash of the containing block
+    pub block_hash: Option<B256>,
+    /// Hash of the bundle transaction
+    pub transaction_hash: Option<B256>,
+}
+
+/// The receipt of an included user operation, as returned by
+/// `eth_getUserOperationReceipt`
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserOperationReceipt {
+    /// The operation hash
+    pub user_op_hash: B256,
+    /// The EntryPoint that executed it
+    pub entry_point: Address,
+    /// The account that sent it
+    pub sender: Address,
+    /// The operation nonce
+    pub nonce: U256,
+    /// The paymaster that paid for it, or the zero address
+    #[serde(default)]
+    pub paymaster: Address,
+    /// Gas cost charged to the sender or paymaster
+    pub actual_gas_cost: U256,
+    /// Gas used by the operation
+    pub actual_gas_used: U256,
+    /// `true` if the execution call succeeded
+    pub success: bool,
+    /// The revert reason, if the execution call failed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    /// Logs emitted by the operation
+    pub logs: Vec<Log>,
+    /// The receipt of the bundle transaction
+    pub receipt: TransactionReceipt,
+}
+
+/// An address as an ABI word
+fn address_word(address: Address) -> B256 {
+    let mut word = B256::default();
+    word.0[12..].copy_from_slice(address.as_bytes());
+    word
+}
+
+fn split_paymaster(data: &[u8], mode_len: usize) -> Result<(Address, &[u8]), PaymasterDataError> {
+    let expected = 20 + mode_len;
+    if data.len() < expected {
+        return Err(PaymasterDataError::TooShort {
+            expected,
+            actual: data.len(),
+        });
+    }
+    Ok((Address::from_slice(&data[..20]), &data[20..]))
+}
+
+fn decode_uint48(word: &[u8]) -> Result<u64, PaymasterDataError> {
+    let value = U256::from_be_slice(word);
+    if value > U256::from(MAX_UINT48) {
+        return Err(PaymasterDataError::InvalidUint48);
+    }
+    Ok(value.to::<u64>())
+}
+
+#[cfg(test)]
+mod test {
+    use ethers_pub_use::serde_json;
+
+    use super::*;
+
+    #[test]
+    fn it_round_trips_sponsorships() {
+        let paymaster = Address::repeat_byte(0x11);
+        let unsigned = SponsorshipData::new(1_700_000_000, 1_600_000_000).unwrap();
+        assert!(!unsigned.is_signed());
+
+        let field = PaymasterAndData::sponsored(paymaster, unsigned.clone());
+        let encoded = field.encode();
+        assert_eq!(encoded.len(), 20 + 64);
+        assert_eq!(PaymasterAndData::decode_sponsored(&encoded).unwrap(), field);
+
+        let signed = unsigned.with_signature(&[0xab; 65]).unwrap();
+        assert!(signed.is_signed());
+        let field = PaymasterAndData::sponsored(paymaster, signed);
+        let encoded = field.encode();
+        assert_eq!(encoded.len(), 20 + 64 + 65);
+        assert_eq!(&encoded[..20], paymaster.as_bytes());
+        assert_eq!(PaymasterAndData::decode_sponsored(&encoded).unwrap(), field);
+    }
+
+    #[test]
+    fn it_hashes_sponsorships() {
+        let op = UserOperation {
+            sender: Address::repeat_byte(0x11),
+            nonce: U256::from(1),
+            call_gas_limit: U256::from(100_000),
+            ..Default::default()
+        };
+        let paymaster = Address::repeat_byte(0x22);
+        let data = SponsorshipData::new(1_700_000_000, 1_600_000_000).unwrap();
+        let hash = data.hash(&op, paymaster, 1);
+
+        // the packed operation without its paymaster data hash, then the
+        // chain, paymaster and validity window
+        let mut preimage = op.pack()[..9 * 32].to_vec();
+        preimage.extend_from_slice(&U256::from(1).to_be_bytes::<32>());
+        preimage.extend_from_slice(&[0; 12]);
+        preimage.extend_from_slice(paymaster.as_bytes());
+        preimage.extend_from_slice(&U256::from(1_700_000_000).to_be_bytes::<32>());
+        preimage.extend_from_slice(&U256::from(1_600_000_000).to_be_bytes::<32>());
+        assert_eq!(hash, keccak256(preimage));
+
+        // paymaster data and signatures are not covered
+        let sponsored = UserOperation {
+            paymaster_and_data: vec![0xab; 84].into(),
+            signature: vec![0xab; 65].into(),
+            ..op.clone()
+        };
+        assert_eq!(data.hash(&sponsored, paymaster, 1), hash);
+        assert_ne!(data.hash(&op, paymaster, 5), hash);
+        assert_ne!(SponsorshipData::default().hash(&op, paymaster, 1), hash);
+    }
+
+    #[test]
+    fn it_validates_sponsorships() {
+        assert_eq!(
+            SponsorshipData::new(MAX_UINT48 + 1, 0),
+            Err(PaymasterDataError::TimestampOverflow(MAX_UINT48 + 1))
+        );
+        assert_eq!(
+            SponsorshipData::default().with_signature(&[0; 64]),
+            Err(PaymasterDataError::SignatureLength(64))
+        );
+        assert_eq!(
+            PaymasterAndData::decode_sponsored(&[0; 40]),
+            Err(PaymasterDataError::TooShort {
+                expected: 84,
+                actual: 40
+            })
+        );
+        assert_eq!(
+            PaymasterAndData::decode_sponsored(&[0xff; 84]),
+            Err(PaymasterDataError::InvalidUint48)
+        );
+    }
+
+    #[test]
+    fn it_hashes_user_operations() {
+        let op = UserOperation {
+            sender: Address::repeat_byte(0x11),
+            nonce: U256::from(1),
+            call_data: vec![0xde, 0xad].into(),
+            call_gas_limit: U256::from(100_000),
+            ..Default::default()
+        };
+        let packed = op.pack();
+        assert_eq!(packed.len(), 10 * 32);
+        assert_eq!(&packed[..12], &[0; 12]);
+        assert_eq!(&packed[12..32], op.sender.as_bytes());
+        assert_eq!(&packed[96..128], keccak256([0xde, 0xad]).as_bytes());
+        // empty init code and paymaster data are hashed, not zeroed
+        assert_eq!(&packed[64..96], keccak256([]).as_bytes());
+
+        let entry_point = Address::repeat_byte(0x5f);
+        let hash = op.hash(entry_point, 1);
+        let mut preimage = keccak256(&packed).0.to_vec();
+        preimage.extend_from_slice(&[0; 12]);
+        preimage.extend_from_slice(entry_point.as_bytes());
+        preimage.extend_from_slice(&U256::from(1).to_be_bytes::<32>());
+        assert_eq!(hash, keccak256(preimage));
+
+        let signed = UserOperation {
+            signature: vec![0xab; 65].into(),
+            ..op.clone()
+        };
+        assert_eq!(signed.hash(entry_point, 1), hash);
+        assert_ne!(op.hash(entry_point, 5), hash);
+        assert_ne!(op.hash(Address::repeat_byte(0x60), 1), hash);
+    }
+
+    #[test]
+    fn it_deserializes_user_operation_receipts() {
+        let receipt: UserOperationReceipt = serde_json::from_value(serde_json::json!({
+            "userOpHash": format!("0x{}", "11".repeat(32)),
+            "entryPoint": "0x5ff137d4b0fdcd49dca30c7cf57e578a026d2789",
+            "sender": format!("0x{}", "22".repeat(20)),
+            "nonce": "0x1",
+            "paymaster": format!("0x{}", "00".repeat(20)),
+            "actualGasCost": "0x2a",
+            "actualGasUsed": "0x15",
+            "success": false,
+            "reason": "0x08c379a0",
+            "logs": [],
+            "receipt": {
+                "transactionHash": format!("0x{}", "33".repeat(32)),
+                "transactionIndex": "0x0",
+                "blockHash": null,
+                "blockNumber": null,
+                "from": format!("0x{}", "44".repeat(20)),
+                "to": null,
+                "cumulativeGasUsed": "0x0",
+                "gasUsed": null,
+                "contractAddress": null,
+                "logs": [],
+                "logsBloom": format!("0x{}", "00".repeat(256)),
+            },
+        }))
+        .unwrap();
+        assert!(!receipt.success);
+        assert_eq!(receipt.actual_gas_cost, U256::from(42));
+        assert_eq!(receipt.reason.as_deref(), Some("0x08c379a0"));
+    }
+
+    #[test]
+    fn it_round_trips_token_data() {
+        let field = PaymasterAndData::token(
+            Address::repeat_byte(0x11),
+            Address::repeat_byte(0x22),
+            U256::from(1_000_000),
+        );
+        let encoded = field.encode();
+        assert_eq!(encoded.len(), 20 + 20 + 32);
+        assert_eq!(PaymasterAndData::decode_token(&encoded).unwrap(), field);
+    }
+}
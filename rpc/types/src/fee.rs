@@ -0,0 +1,113 @@
+use ethers_primitives::{U256, U64};
+use ethers_pub_use::serde::{Deserialize, Serialize};
+
+/// Fee history of a range of blocks, as returned by `eth_feeHistory`
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeeHistory {
+    /// Number of the oldest block in the range
+    pub oldest_block: U64,
+    /// Base fee per gas of each block, followed by the base fee of the block
+    /// after the newest one
+    pub base_fee_per_gas: Vec<U256>,
+    /// Ratio of gas used to the gas limit of each block
+    pub gas_used_ratio: Vec<f64>,
+    /// Base fee per blob gas of each block, followed by that of the block
+    /// after the newest one. Empty before Cancun
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub base_fee_per_blob_gas: Vec<U256>,
+    /// Ratio of blob gas used to the max blob gas of each block. Empty
+    /// before Cancun
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub blob_gas_used_ratio: Vec<f64>,
+    /// Priority fees per gas of each block, at each requested percentile.
+    /// Unset if no percentiles were requested
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reward: Option<Vec<Vec<U256>>>,
+}
+
+/// Suggested EIP-1559 fees
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Eip1559Fees {
+    /// Max fee per gas, in wei
+    pub max_fee_per_gas: U256,
+    /// Max priority fee per gas, in wei
+    pub max_priority_fee_per_gas: U256,
+}
+
+impl FeeHistory {
+    /// The base fee per gas of the block after the newest one
+    pub fn next_base_fee(&self) -> Option<U256> {
+        self.base_fee_per_gas.last().copied()
+    }
+
+    /// The median priority fee paid across the range, at the percentile of
+    /// index `percentile` in the request. Blocks with no priority fee, such
+    /// as empty blocks, are ignored
+    pub fn median_priority_fee(&self, percentile: usize) -> Option<U256> {
+        let mut fees: Vec<U256> = self
+            .reward
+            .as_ref()?
+            .iter()
+            .filter_map(|rewards| rewards.get(percentile).copied())
+            .filter(|fee| !fee.is_zero())
+            .collect();
+        fees.sort_unstable();
+        fees.get(fees.len() / 2).copied()
+    }
+
+    /// Suggest fees from the median priority fee at the percentile of index
+    /// `percentile` in the request. The max fee leaves room for the base fee
+    /// to double
+    pub fn suggest_fees(&self, percentile: usize) -> Option<Eip1559Fees> {
+        let max_priority_fee_per_gas = self.median_priority_fee(percentile).unwrap_or_default();
+        let base_fee = self.next_base_fee()?;
+        Some(Eip1559Fees {
+            max_fee_per_gas: base_fee * U256::from(2) + max_priority_fee_per_gas,
+            max_priority_fee_per_gas,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ethers_pub_use::serde_json;
+
+    use super::*;
+
+    #[test]
+    fn it_suggests_fees() {
+        let json = r#"{
+            "oldestBlock": "0x10",
+            "baseFeePerGas": ["0x3b9aca00", "0x3b9aca00", "0x0", "0x77359400"],
+            "gasUsedRatio": [0.5, 0, 1],
+            "reward": [["0x1", "0x5"], ["0x0", "0x0"], ["0x3", "0x7"]]
+        }"#;
+        let history: FeeHistory = serde_json::from_str(json).unwrap();
+        assert_eq!(history.oldest_block, U64::from(16));
+        assert_eq!(history.base_fee_per_gas[2], U256::ZERO);
+        assert!(history.base_fee_per_blob_gas.is_empty());
+
+        assert_eq!(history.next_base_fee(), Some(U256::from(2_000_000_000)));
+        assert_eq!(history.median_priority_fee(0), Some(U256::from(3)));
+        assert_eq!(history.median_priority_fee(1), Some(U256::from(7)));
+        assert_eq!(history.median_priority_fee(2), None);
+        assert_eq!(
+            history.suggest_fees(1),
+            Some(Eip1559Fees {
+                max_fee_per_gas: U256::from(4_000_000_007u64),
+                max_priority_fee_per_gas: U256::from(7),
+            })
+        );
+
+        let history = FeeHistory {
+            reward: None,
+            ..history
+        };
+        assert_eq!(history.median_priority_fee(0), None);
+        assert_eq!(
+            history.suggest_fees(0).unwrap().max_priority_fee_per_gas,
+            U256::ZERO
+        );
+    }
+}
@@ -0,0 +1,182 @@
+use ethers_pub_use::serde::{Deserialize, Serialize};
+
+use ethers_primitives::{Address, B256};
+
+use crate::BlockNumber;
+
+/// A single value, or any of several values
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ValueOrArray<T> {
+    /// A single value
+    Value(T),
+    /// Any of several values
+    Array(Vec<T>),
+}
+
+impl<T> ValueOrArray<T> {
+    /// `true` if `value` is the value, or one of the values
+    pub fn matches(&self, value: &T) -> bool
+    where
+        T: PartialEq,
+    {
+        match self {
+            ValueOrArray::Value(v) => v == value,
+            ValueOrArray::Array(values) => values.contains(value),
+        }
+    }
+}
+
+impl<T> From<T> for ValueOrArray<T> {
+    fn from(value: T) -> Self {
+        ValueOrArray::Value(value)
+    }
+}
+
+impl<T> From<Vec<T>> for ValueOrArray<T> {
+    fn from(values: Vec<T>) -> Self {
+        ValueOrArray::Array(values)
+    }
+}
+
+/// A topic position of a [`Filter`]. `None` matches any topic
+pub type Topic = Option<ValueOrArray<B256>>;
+
+/// A log filter, as used by `eth_getLogs` and `logs` subscriptions.
+///
+/// Logs match if they were emitted by one of the addresses, and each of
+/// their first topics matches the corresponding position of `topics`
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Filter {
+    /// First block to search. Ignored by subscriptions
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from_block: Option<BlockNumber>,
+    /// Last block to search. Ignored by subscriptions
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to_block: Option<BlockNumber>,
+    /// Search only this block. Exclusive with the block range
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_hash: Option<B256>,
+    /// Emitting contracts. `None` matches any contract
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub address: Option<ValueOrArray<Address>>,
+    /// Topics by position. Missing trailing positions match any topic
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub topics: Vec<Topic>,
+}
+
+impl Filter {
+    /// Instantiate a filter matching all logs of the latest block
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the first block to search
+    #[must_use = "Builder method outputs must be used"]
+    pub fn from_block(mut self, block: impl Into<BlockNumber>) -> Self {
+        self.block_hash = None;
+        self.from_block = Some(block.into());
+        self
+    }
+
+    /// Set the last block to search
+    #[must_use = "Builder method outputs must be used"]
+    pub fn to_block(mut self, block: impl Into<BlockNumber>) -> Self {
+        self.block_hash = None;
+        self.to_block = Some(block.into());
+        self
+    }
+
+    /// Search only the block with this hash, replacing any block range
+    #[must_use = "Builder method outputs must be used"]
+    pub fn at_block_hash(mut self, hash: B256) -> Self {
+        self.from_block = None;
+        self.to_block = None;
+        self.block_hash = Some(hash);
+        self
+    }
+
+    /// Match logs emitted by a contract, or by any of several contracts
+    #[must_use = "Builder method outputs must be used"]
+    pub fn address(mut self, address: impl Into<ValueOrArray<Address>>) -> Self {
+        self.address = Some(address.into());
+        self
+    }
+
+    /// Match the topic at `index`, which must be at most 3
+    ///
+    /// # Panics
+    ///
+    /// If `index` is greater than 3
+    #[must_use = "Builder method outputs must be used"]
+    pub fn topic(mut self, index: usize, topic: impl Into<ValueOrArray<B256>>) -> Self {
+        assert!(index < 4, "logs have at most 4 topics");
+        if self.topics.len() <= index {
+            self.topics.resize(index + 1, None);
+        }
+        self.topics[index] = Some(topic.into());
+        self
+    }
+
+    /// Match the event signature hash, which is the first topic of
+    /// non-anonymous events
+    #[must_use = "Builder method outputs must be used"]
+    pub fn event_signature(self, topic: impl Into<ValueOrArray<B256>>) -> Self {
+        self.topic(0, topic)
+    }
+
+    /// Match the first indexed parameter of a non-anonymous event
+    #[must_use = "Builder method outputs must be used"]
+    pub fn topic1(self, topic: impl Into<ValueOrArray<B256>>) -> Self {
+        self.topic(1, topic)
+    }
+
+    /// Match the second indexed parameter of a non-anonymous event
+    #[must_use = "Builder method outputs must be used"]
+    pub fn topic2(self, topic: impl Into<ValueOrArray<B256>>) -> Self {
+        self.topic(2, topic)
+    }
+
+    /// Match the third indexed parameter of a non-anonymous event
+    #[must_use = "Builder method outputs must be used"]
+    pub fn topic3(self, topic: impl Into<ValueOrArray<B256>>) -> Self {
+        self.topic(3, topic)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ethers_pub_use::serde_json::{self, json};
+
+    use super::*;
+
+    #[test]
+    fn it_serializes_filters() {
+        let transfer = B256::repeat_byte(0xdd);
+        let to = B256::repeat_byte(0x02);
+        let filter = Filter::new()
+            .from_block(100)
+            .to_block(BlockNumber::Latest)
+            .address(Address::repeat_byte(0x11))
+            .event_signature(transfer)
+            .topic2(vec![to, B256::zero()]);
+
+        let value = serde_json::to_value(&filter).unwrap();
+        assert_eq!(
+            value,
+            json!({
+                "fromBlock": "0x64",
+                "toBlock": "latest",
+                "address": "0x1111111111111111111111111111111111111111",
+                "topics": [transfer, null, [to, B256::zero()]],
+            })
+        );
+        assert_eq!(serde_json::from_value::<Filter>(value).unwrap(), filter);
+
+        let filter = filter.at_block_hash(B256::zero());
+        assert_eq!(filter.from_block, None);
+        assert_eq!(filter.topics.len(), 3);
+        assert!(filter.topics[2].as_ref().unwrap().matches(&to));
+    }
+}
@@ -12,5 +12,63 @@
 
 pub mod admin;
 
+#[cfg(feature = "arbitrum")]
+pub mod arbitrum;
+
+pub mod block;
+pub use block::{BlockId, BlockNumber, Header};
+
+pub use ethers_primitives::Bytes;
+
+pub mod engine;
+
+pub mod ens;
+pub use ens::NameOrAddress;
+
+pub mod erc4337;
+
+pub mod fee;
+pub use fee::{Eip1559Fees, FeeHistory};
+
+pub mod filter;
+pub use filter::{Filter, Topic, ValueOrArray};
+
+pub mod log;
+pub use log::{Log, LogEvent};
+
+#[cfg(feature = "optimism")]
+pub mod optimism;
+
+pub mod other;
+pub use other::{OtherFields, Strict};
+
+pub mod proof;
+pub use proof::{EIP1186ProofResponse, StorageProof};
+
+pub mod pubsub;
+pub use pubsub::{SubscriptionKind, SubscriptionNotification, SubscriptionResult};
+
+pub mod receipt;
+pub use receipt::{ReceiptOutcome, TransactionReceipt};
+
+pub mod request;
+pub use request::TransactionRequest;
+
+mod serde_helpers;
+
+pub mod simulate;
+pub use simulate::{BlockOverrides, SimulatePayload, SimulatedBlock, SimulatedBlockCalls};
+
+pub mod state;
+pub use state::{AccountOverride, StateOverride};
+
+pub mod sync;
+pub use sync::{SyncProgress, SyncStatus};
+
+pub mod trace;
+
+pub mod transaction;
+pub use transaction::{SignedTransaction, TypedTransaction};
+
 #[cfg(test)]
 mod tests {}
@@ -0,0 +1,125 @@
+use ethers_pub_use::serde::{Deserialize, Serialize};
+
+use ethers_primitives::{Address, B256, U256, U64};
+
+use crate::Bytes;
+
+/// A log emitted by a contract, as returned by `eth_getLogs`, in receipts,
+/// and in `logs` subscriptions.
+///
+/// Block and transaction fields are `None` for logs of pending transactions.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Log {
+    /// The emitting contract
+    pub address: Address,
+    /// Indexed event topics. The first topic is the event signature unless
+    /// the event is anonymous
+    pub topics: Vec<B256>,
+    /// Non-indexed event data
+    pub data: Bytes,
+    /// Hash of the containing block
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_hash: Option<B256>,
+    /// Number of the containing block
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_number: Option<U64>,
+    /// Hash of the emitting transaction
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transaction_hash: Option<B256>,
+    /// Index of the emitting transaction in the block
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transaction_index: Option<U64>,
+    /// Index of the log in the block
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub log_index: Option<U256>,
+    /// `true` if the log was removed by a chain reorganization
+    #[serde(default)]
+    pub removed: bool,
+}
+
+/// A log delivered by a log stream, tagged with whether it was added to or
+/// removed from the canonical chain.
+///
+/// Consumers that maintain state derived from logs should roll back
+/// `Removed` logs, which were previously delivered as `Added`
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum LogEvent {
+    /// The log was included in the canonical chain
+    Added(Log),
+    /// The log was removed from the canonical chain by a reorganization
+    Removed(Log),
+}
+
+impl LogEvent {
+    /// The log
+    pub fn log(&self) -> &Log {
+        match self {
+            LogEvent::Added(log) | LogEvent::Removed(log) => log,
+        }
+    }
+
+    /// Unwrap the log
+    pub fn into_log(self) -> Log {
+        match self {
+            LogEvent::Added(log) | LogEvent::Removed(log) => log,
+        }
+    }
+
+    /// `true` if the log was removed
+    pub fn is_removed(&self) -> bool {
+        matches!(self, LogEvent::Removed(_))
+    }
+}
+
+impl From<Log> for LogEvent {
+    /// Tag a log by its `removed` flag
+    fn from(log: Log) -> Self {
+        if log.removed {
+            LogEvent::Removed(log)
+        } else {
+            LogEvent::Added(log)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ethers_pub_use::serde_json;
+
+    use super::*;
+
+    #[test]
+    fn it_deserializes_logs() {
+        let json = r#"{
+            "address": "0xdac17f958d2ee523a2206206994597c13d831ec7",
+            "topics": [
+                "0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef"
+            ],
+            "data": "0x00000000000000000000000000000000000000000000000000000000000f4240",
+            "blockHash": "0x2b9d2d8f3d2e1a8a5e1c3b4f6d7e8f9a0b1c2d3e4f5a6b7c8d9e0f1a2b3c4d5e",
+            "blockNumber": "0x10d4f",
+            "transactionHash": "0x5c504ed432cb51138bcf09aa5e8a410dd4a1e204ef84bfed1be16dfba1b22060",
+            "transactionIndex": "0x1",
+            "logIndex": "0x3",
+            "removed": false
+        }"#;
+        let log: Log = serde_json::from_str(json).unwrap();
+        assert_eq!(log.topics.len(), 1);
+        assert_eq!(log.data.len(), 32);
+        assert_eq!(log.data[29..], [0x0f, 0x42, 0x40]);
+        assert_eq!(log.block_number, Some(U64::from(0x10d4f)));
+        assert_eq!(log.log_index, Some(U256::from(3)));
+        assert!(!log.removed);
+
+        let round_trip: Log = serde_json::from_value(serde_json::to_value(&log).unwrap()).unwrap();
+        assert_eq!(round_trip, log);
+
+        assert!(!LogEvent::from(log.clone()).is_removed());
+        let removed = Log {
+            removed: true,
+            ..log
+        };
+        assert_eq!(LogEvent::from(removed.clone()), LogEvent::Removed(removed));
+    }
+}
@@ -0,0 +1,98 @@
+//! Optimism extensions: L1 fee fields of receipts, and the parameters of
+//! the gas price oracle that sets them.
+//!
+//! Deposit transactions are [`DepositTransaction`].
+//!
+//! [`DepositTransaction`]: crate::transaction::DepositTransaction
+
+use ethers_pub_use::serde::{Deserialize, Serialize};
+
+use ethers_primitives::{address, Address, U256, U64};
+
+/// The `GasPriceOracle` predeploy, which reports the L1 fee parameters
+pub const GAS_PRICE_ORACLE: Address = address!("0x420000000000000000000000000000000000000F");
+
+/// Gas charged for the signature and RLP overhead that a raw transaction
+/// lacks before signing. 68 non-zero bytes at 16 gas each
+const UNSIGNED_TX_OVERHEAD: u64 = 68 * 16;
+
+/// The L1 data fee fields of an Optimism receipt. Flattened into
+/// [`TransactionReceipt`](crate::TransactionReceipt)
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OptimismReceiptFields {
+    /// L1 base fee used to compute the L1 fee
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub l1_gas_price: Option<U256>,
+    /// L1 gas charged for the transaction's data
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub l1_gas_used: Option<U256>,
+    /// L1 data fee paid, in wei
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub l1_fee: Option<U256>,
+    /// The fee scalar, as a decimal string such as `"0.684"`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub l1_fee_scalar: Option<String>,
+    /// Nonce of the sender on L2, for deposit transactions since Regolith
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deposit_nonce: Option<U64>,
+    /// Receipt version of deposit transactions, since Canyon
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deposit_receipt_version: Option<U64>,
+}
+
+/// The L1 fee parameters of the gas price oracle
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct L1FeeParams {
+    /// The L1 base fee, in wei
+    pub l1_base_fee: U256,
+    /// Fixed L1 gas added to each transaction
+    pub l1_fee_overhead: U256,
+    /// Fee multiplier, scaled by `10^decimals`
+    pub l1_fee_scalar: U256,
+    /// Decimals of the scalar
+    pub decimals: U256,
+}
+
+impl L1FeeParams {
+    /// L1 gas charged for an unsigned raw transaction: 4 gas per zero byte,
+    /// 16 per non-zero byte, plus the overhead
+    pub fn l1_gas_used(&self, raw_tx: &[u8]) -> U256 {
+        let zeros = raw_tx.iter().filter(|b| **b == 0).count() as u64;
+        let non_zeros = raw_tx.len() as u64 - zeros;
+        U256::from(zeros * 4 + non_zeros * 16 + UNSIGNED_TX_OVERHEAD)
+            .saturating_add(self.l1_fee_overhead)
+    }
+
+    /// The L1 data fee of an unsigned raw transaction, in wei
+    pub fn l1_fee(&self, raw_tx: &[u8]) -> U256 {
+        let scale = U256::from(10).saturating_pow(self.decimals);
+        self.l1_gas_used(raw_tx)
+            .saturating_mul(self.l1_base_fee)
+            .saturating_mul(self.l1_fee_scalar)
+            / scale
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_computes_l1_fees() {
+        let params = L1FeeParams {
+            l1_base_fee: U256::from(30_000_000_000u64),
+            l1_fee_overhead: U256::from(188),
+            l1_fee_scalar: U256::from(684_000),
+            decimals: U256::from(6),
+        };
+        let raw_tx = [0, 0, 1, 2];
+        let gas = 2 * 4 + 2 * 16 + 68 * 16 + 188;
+        assert_eq!(params.l1_gas_used(&raw_tx), U256::from(gas));
+        assert_eq!(
+            params.l1_fee(&raw_tx),
+            U256::from(gas * 30_000_000_000u64 * 684 / 1000)
+        );
+    }
+}
@@ -0,0 +1,97 @@
+//! Fields outside the spec, and strict deserialization that rejects them.
+//!
+//! Nodes and L2s add fields to blocks and receipts. By default they are
+//! kept in an [`OtherFields`] map, so that nothing is lost and they are
+//! serialized back out. For conformance testing, [`Strict`] rejects them
+//! instead.
+
+use std::{collections::BTreeMap, ops::Deref};
+
+use ethers_pub_use::{
+    serde::{de, Deserialize, Deserializer, Serialize, Serializer},
+    serde_json::Value,
+};
+
+/// Fields of a JSON object that are not part of the type
+pub type OtherFields = BTreeMap<String, Value>;
+
+/// A type that captures unknown fields into [`OtherFields`]
+pub trait WithOtherFields {
+    /// The captured fields
+    fn other_fields(&self) -> &OtherFields;
+}
+
+/// A deserialization wrapper that fails on fields unknown to `T`, rather
+/// than capturing them
+///
+/// ```
+/// use ethers_pub_use::serde_json;
+/// use ethers_rpc_types::{other::Strict, TransactionReceipt};
+///
+/// let json = r#"{"transactionHash":"0x5c504ed432cb51138bcf09aa5e8a410dd4a1e204ef84bfed1be16dfba1b22060","transactionIndex":"0x1","from":"0xa7d9ddbe1f17865597fbd27ec712455208b6b76d","cumulativeGasUsed":"0x0","logs":[],"logsBloom":"0x00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000","gasPrice":"0x1"}"#;
+///
+/// let lossy: TransactionReceipt = serde_json::from_str(json).unwrap();
+/// assert!(lossy.other.contains_key("gasPrice"));
+///
+/// let strict = serde_json::from_str::<Strict<TransactionReceipt>>(json);
+/// assert!(strict.unwrap_err().to_string().contains("unknown field `gasPrice`"));
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Strict<T>(pub T);
+
+impl<T> Strict<T> {
+    /// Unwrap the value
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Deref for Strict<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<'de, T> Deserialize<'de> for Strict<T>
+where
+    T: Deserialize<'de> + WithOtherFields,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = T::deserialize(deserializer)?;
+        match value.other_fields().keys().next() {
+            Some(field) => Err(de::Error::custom(format!("unknown field `{field}`"))),
+            None => Ok(Self(value)),
+        }
+    }
+}
+
+impl<T: Serialize> Serialize for Strict<T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.0.serialize(serializer)
+    }
+}
+
+impl<T: WithOtherFields> WithOtherFields for Vec<T> {
+    fn other_fields(&self) -> &OtherFields {
+        static EMPTY: OtherFields = BTreeMap::new();
+        self.iter()
+            .map(WithOtherFields::other_fields)
+            .find(|other| !other.is_empty())
+            .unwrap_or(&EMPTY)
+    }
+}
+
+impl<T: WithOtherFields> WithOtherFields for Option<T> {
+    fn other_fields(&self) -> &OtherFields {
+        static EMPTY: OtherFields = BTreeMap::new();
+        self.as_ref().map_or(&EMPTY, WithOtherFields::other_fields)
+    }
+}
@@ -0,0 +1,546 @@
+//! Account and storage proofs, as returned by `eth_getProof`, and their
+//! verification against a state root.
+//!
+//! Proofs are the Merkle-Patricia trie nodes on the path from the root to
+//! the hashed key. Verifying them establishes an account's state, or a
+//! storage slot, from a trusted block header alone
+
+use ethers_primitives::{b256, keccak256, Address, B256, U256, U64};
+use ethers_pub_use::{
+    serde::{Deserialize, Serialize},
+    thiserror,
+};
+use ethers_rlp::{DecodeError, Encodable, Header};
+
+use crate::Bytes;
+
+/// The root of an empty trie, `keccak256(rlp(""))`
+pub const EMPTY_ROOT_HASH: B256 =
+    b256!("56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421");
+
+/// The code hash of accounts without code, `keccak256("")`
+pub const KECCAK_EMPTY: B256 =
+    b256!("c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470");
+
+/// An account and some of its storage slots, with their proofs, as
+/// returned by `eth_getProof`. See [EIP-1186]
+///
+/// [EIP-1186]: https://eips.ethereum.org/EIPS/eip-1186
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EIP1186ProofResponse {
+    /// The account address
+    pub address: Address,
+    /// Balance, in wei
+    pub balance: U256,
+    /// Hash of the runtime bytecode
+    pub code_hash: B256,
+    /// Nonce
+    pub nonce: U64,
+    /// Root of the storage trie
+    pub storage_hash: B256,
+    /// Nodes of the state trie, from the state root to the account
+    pub account_proof: Vec<Bytes>,
+    /// The requested storage slots
+    pub storage_proof: Vec<StorageProof>,
+}
+
+/// A storage slot, with its proof
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StorageProof {
+    /// The slot
+    pub key: U256,
+    /// The value. Zero for unset slots
+    pub value: U256,
+    /// Nodes of the storage trie, from the storage root to the slot
+    pub proof: Vec<Bytes>,
+}
+
+/// Proof verification errors
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ProofError {
+    /// A node is not valid RLP
+    #[error("invalid trie node: {0}")]
+    Rlp(DecodeError),
+    /// A node is neither a branch, an extension nor a leaf
+    #[error("invalid trie node")]
+    InvalidNode,
+    /// The proof ends before reaching the key
+    #[error("proof ends before reaching the key")]
+    MissingNode,
+    /// A node does not have the hash its parent refers to it by
+    #[error("node hash {actual} does not match {expected}")]
+    HashMismatch {
+        /// The hash the parent refers to
+        expected: B256,
+        /// The hash of the node
+        actual: B256,
+    },
+    /// The proven value differs from the claimed value
+    #[error("proven value does not match the claimed value")]
+    ValueMismatch {
+        /// The claimed value, RLP encoded. `None` if absent
+        expected: Option<Vec<u8>>,
+        /// The proven value, RLP encoded. `None` if absent
+        actual: Option<Vec<u8>>,
+    },
+}
+
+impl From<DecodeError> for ProofError {
+    fn from(err: DecodeError) -> Self {
+        Self::Rlp(err)
+    }
+}
+
+impl EIP1186ProofResponse {
+    /// `true` if the account is empty, so is absent from the state trie
+    pub fn is_empty(&self) -> bool {
+        self.nonce == U64::ZERO
+            && self.balance == U256::ZERO
+            && self.code_hash == KECCAK_EMPTY
+            && self.storage_hash == EMPTY_ROOT_HASH
+    }
+
+    /// The RLP encoding of the account, as stored in the state trie
+    pub fn account_rlp(&self) -> Vec<u8> {
+        let nonce = self.nonce.to::<u64>();
+        let payload_length = nonce.length()
+            + self.balance.length()
+            + self.storage_hash.length()
+            + self.code_hash.length();
+        let header = Header {
+            list: true,
+            payload_length,
+        };
+
+        let mut out = Vec::with_capacity(header.length() + payload_length);
+        header.encode(&mut out);
+        nonce.encode(&mut out);
+        self.balance.encode(&mut out);
+        self.storage_hash.encode(&mut out);
+        self.code_hash.encode(&mut out);
+        out
+    }
+
+    /// Verify the account against a state root, e.g. of a trusted block
+    /// header
+    pub fn verify_account(&self, state_root: B256) -> Result<(), ProofError> {
+        let expected = (!self.is_empty()).then(|| self.account_rlp());
+        verify_proof(
+            state_root,
+            self.address.as_bytes(),
+            expected.as_deref(),
+            &self.account_proof,
+        )
+    }
+
+    /// Verify the storage slots against the account's storage root
+    pub fn verify_storage(&self) -> Result<(), ProofError> {
+        self.storage_proof
+            .iter()
+            .try_for_each(|slot| slot.verify(self.storage_hash))
+    }
+
+    /// Verify the account against a state root, and its storage slots
+    /// against the account's storage root
+    pub fn verify(&self, state_root: B256) -> Result<(), ProofError> {
+        self.verify_account(state_root)?;
+        self.verify_storage()
+    }
+}
+
+impl StorageProof {
+    /// Verify the slot against a storage root
+    pub fn verify(&self, storage_root: B256) -> Result<(), ProofError> {
+        let expected = (self.value != U256::ZERO).then(|| {
+            let mut out = Vec::with_capacity(self.value.length());
+            self.value.encode(&mut out);
+            out
+        });
+        verify_proof(
+            storage_root,
+            &self.key.to_be_bytes::<32>(),
+            expected.as_deref(),
+            &self.proof,
+        )
+    }
+}
+
+/// A reference from a node to its child
+enum NodeRef<'a> {
+    /// A node of 32 bytes or more, referred to by its hash
+    Hash(B256),
+    /// A shorter node, embedded in its parent
+    Inline(&'a [u8]),
+}
+
+impl<'a> NodeRef<'a> {
+    /// Read a child reference, which is a hash or an embedded node. `None`
+    /// if the child is empty
+    fn decode(item: &'a [u8]) -> Result<Option<Self>, ProofError> {
+        if item.first().is_some_and(|b| *b >= 0xc0) {
+            return Ok(Some(Self::Inline(item)));
+        }
+        match string(item)? {
+            [] => Ok(None),
+            hash if hash.len() == 32 => Ok(Some(Self::Hash(B256::from_slice(hash)))),
+            _ => Err(ProofError::InvalidNode),
+        }
+    }
+}
+
+/// Verify a proof that `key` maps to `expected` in the secure trie with
+/// root `root`, or is absent if `expected` is `None`. Keys are hashed, as in
+/// the state and storage tries, and values are RLP encoded
+pub fn verify_proof(
+    root: B256,
+    key: &[u8],
+    expected: Option<&[u8]>,
+    proof: &[Bytes],
+) -> Result<(), ProofError> {
+    let actual = proven_value(root, &nibbles(keccak256(key).as_bytes()), proof)?;
+    if actual != expected {
+        return Err(ProofError::ValueMismatch {
+            expected: expected.map(<[u8]>::to_vec),
+            actual: actual.map(<[u8]>::to_vec),
+        });
+    }
+    Ok(())
+}
+
+/// Walk the proof along `path`. Returns the value at the end of it, if any
+fn proven_value<'a>(
+    root: B256,
+    mut path: &[u8],
+    proof: &'a [Bytes],
+) -> Result<Option<&'a [u8]>, ProofError> {
+    if root == EMPTY_ROOT_HASH {
+        return Ok(None);
+    }
+
+    let mut nodes = proof.iter();
+    let mut next = NodeRef::Hash(root);
+    loop {
+        let node = match next {
+            NodeRef::Hash(expected) => {
+                let node = nodes.next().ok_or(ProofError::MissingNode)?;
+                let actual = keccak256(node);
+                if actual != expected {
+                    return Err(ProofError::HashMismatch { expected, actual });
+                }
+                &node[..]
+            }
+            NodeRef::Inline(node) => node,
+        };
+
+        let items = list(node)?;
+        let child = match items[..] {
+            // branch
+            [ref children @ .., value] if children.len() == 16 => {
+                let Some((index, rest)) = path.split_first() else {
+                    let value = string(value)?;
+                    return Ok((!value.is_empty()).then_some(value));
+                };
+                path = rest;
+                children[usize::from(*index)]
+            }
+            // leaf or extension
+            [encoded_path, child] => {
+                let (node_path, is_leaf) = compact_path(string(encoded_path)?)?;
+                let Some(rest) = path.strip_prefix(&node_path[..]) else {
+                    return Ok(None);
+                };
+                if is_leaf {
+                    return Ok(rest.is_empty().then_some(string(child)?));
+                }
+                path = rest;
+                child
+            }
+            _ => return Err(ProofError::InvalidNode),
+        };
+        match NodeRef::decode(child)? {
+            Some(child) => next = child,
+            None => return Ok(None),
+        }
+    }
+}
+
+/// Split a byte string into nibbles
+fn nibbles(bytes: &[u8]) -> Vec<u8> {
+    bytes.iter().flat_map(|b| [b >> 4, b & 0x0f]).collect()
+}
+
+/// Decode a hex-prefix encoded path. Returns its nibbles, and `true` if it
+/// is a leaf path
+fn compact_path(encoded: &[u8]) -> Result<(Vec<u8>, bool), ProofError> {
+    let (first, rest) = encoded.split_first().ok_or(ProofError::InvalidNode)?;
+    let flag = first >> 4;
+    if flag > 3 {
+        return Err(ProofError::InvalidNode);
+    }
+    let mut path = Vec::with_capacity(rest.len() * 2 + 1);
+    if flag & 1 == 1 {
+        path.push(first & 0x0f);
+    }
+    path.extend(nibbles(rest));
+    Ok((path, flag & 2 == 2))
+}
+
+/// The payload of an RLP string
+fn string(item: &[u8]) -> Result<&[u8], ProofError> {
+    let mut buf = item;
+    let header = Header::decode(&mut buf)?;
+    if header.list {
+        return Err(DecodeError::UnexpectedList.into());
+    }
+    buf.get(..header.payload_length)
+        .ok_or(DecodeError::InputTooShort.into())
+}
+
+/// The encoded items of an RLP list
+fn list(node: &[u8]) -> Result<Vec<&[u8]>, ProofError> {
+    let mut buf = node;
+    let header = Header::decode(&mut buf)?;
+    if !header.list {
+        return Err(DecodeError::UnexpectedString.into());
+    }
+    let mut payload = buf
+        .get(..header.payload_length)
+        .ok_or(DecodeError::InputTooShort)?;
+
+    let mut items = vec![];
+    while !payload.is_empty() {
+        let mut rest = payload;
+        let header = Header::decode(&mut rest)?;
+        let len = payload.len() - rest.len() + header.payload_length;
+        let item = payload.get(..len).ok_or(DecodeError::InputTooShort)?;
+        items.push(item);
+        payload = &payload[len..];
+    }
+    Ok(items)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Encode a list of already encoded items
+    fn encode_list(items: &[&[u8]]) -> Vec<u8> {
+        let payload: Vec<u8> = items.concat();
+        let mut out = vec![];
+        Header {
+            list: true,
+            payload_length: payload.len(),
+        }
+        .encode(&mut out);
+        out.extend(payload);
+        out
+    }
+
+    fn encode_string(bytes: &[u8]) -> Vec<u8> {
+        let mut out = vec![];
+        bytes.encode(&mut out);
+        out
+    }
+
+    /// A leaf at the end of `path`, which is a whole nibble path
+    fn leaf(path: &[u8], value: &[u8]) -> Vec<u8> {
+        let mut encoded = vec![if path.len() % 2 == 1 {
+            0x30 | path[0]
+        } else {
+            0x20
+        }];
+        let even = if path.len() % 2 == 1 {
+            &path[1..]
+        } else {
+            path
+        };
+        encoded.extend(even.chunks(2).map(|pair| pair[0] << 4 | pair[1]));
+        encode_list(&[&encode_string(&encoded), &encode_string(value)])
+    }
+
+    fn branch(children: &[(u8, B256)]) -> Vec<u8> {
+        let mut items = vec![encode_string(&[]); 17];
+        for (index, hash) in children {
+            items[usize::from(*index)] = encode_string(hash.as_bytes());
+        }
+        let items: Vec<&[u8]> = items.iter().map(Vec::as_slice).collect();
+        encode_list(&items)
+    }
+
+    fn slot(key: u64, value: u64, proof: &[&[u8]]) -> StorageProof {
+        StorageProof {
+            key: U256::from(key),
+            value: U256::from(value),
+            proof: proof
+                .iter()
+                .map(|node| Bytes::copy_from_slice(node))
+                .collect(),
+        }
+    }
+
+    fn path(key: u64) -> Vec<u8> {
+        nibbles(keccak256(U256::from(key).to_be_bytes::<32>()).as_bytes())
+    }
+
+    #[test]
+    fn it_verifies_proofs() {
+        // find two slots whose paths diverge at the first nibble, and a third
+        // that shares neither's first nibble
+        let (a, b) = (1, (2..).find(|k| path(*k)[0] != path(1)[0]).unwrap());
+        let absent = (2..)
+            .find(|k| path(*k)[0] != path(a)[0] && path(*k)[0] != path(b)[0])
+            .unwrap();
+
+        let value = |v: u64| {
+            let mut out = vec![];
+            U256::from(v).encode(&mut out);
+            out
+        };
+        let leaf_a = leaf(&path(a)[1..], &value(7));
+        let leaf_b = leaf(&path(b)[1..], &value(9));
+        let root_node = branch(&[
+            (path(a)[0], keccak256(&leaf_a)),
+            (path(b)[0], keccak256(&leaf_b)),
+        ]);
+        let storage_root = keccak256(&root_node);
+
+        slot(a, 7, &[&root_node, &leaf_a])
+            .verify(storage_root)
+            .unwrap();
+        slot(absent, 0, &[&root_node]).verify(storage_root).unwrap();
+        assert!(matches!(
+            slot(a, 8, &[&root_node, &leaf_a]).verify(storage_root),
+            Err(ProofError::ValueMismatch { .. })
+        ));
+        assert!(matches!(
+            slot(a, 7, &[&root_node, &leaf_b]).verify(storage_root),
+            Err(ProofError::HashMismatch { .. })
+        ));
+        assert_eq!(
+            slot(a, 7, &[&root_node]).verify(storage_root),
+            Err(ProofError::MissingNode)
+        );
+        slot(a, 0, &[]).verify(EMPTY_ROOT_HASH).unwrap();
+
+        let mut account = EIP1186ProofResponse {
+            address: Address::repeat_byte(0x11),
+            balance: U256::from(10),
+            code_hash: KECCAK_EMPTY,
+            nonce: U64::from(1),
+            storage_hash: storage_root,
+            account_proof: vec![],
+            storage_proof: vec![slot(a, 7, &[&root_node, &leaf_a])],
+        };
+        let account_path = nibbles(keccak256(account.address.as_bytes()).as_bytes());
+        let account_leaf = leaf(&account_path, &account.account_rlp());
+        account.account_proof = vec![account_leaf.clone().into()];
+        let state_root = keccak256(&account_leaf);
+        account.verify(state_root).unwrap();
+
+        account.balance = U256::from(11);
+        assert!(matches!(
+            account.verify(state_root),
+            Err(ProofError::ValueMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn it_deserializes_proofs() {
+        let json = r#"{
+            "address": "0x1111111111111111111111111111111111111111",
+            "balance": "0x0",
+            "codeHash": "0xc5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470",
+            "nonce": "0x0",
+            "storageHash": "0x56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421",
+            "accountProof": [],
+            "storageProof": [{ "key": "0x0", "value": "0x0", "proof": [] }]
+        }"#;
+        let proof: EIP1186ProofResponse = ethers_pub_use::serde_json::from_str(json).unwrap();
+        assert!(proof.is_empty());
+        assert_eq!(proof.storage_proof[0].key, U256::ZERO);
+        proof.verify(EMPTY_ROOT_HASH).unwrap();
+    }
+
+    /// An `eth_getProof` response for an account with code and one storage
+    /// slot, built with an independent trie implementation. The state trie
+    /// holds 40 accounts, and the storage proof passes through an extension
+    /// node
+    const PROOF_RESPONSE: &str = r#"{
+        "address": "0xd8da6bf26964af9d7eed9e03e53415d37aa96045",
+        "balance": "0xde0b6b3a7640000",
+        "codeHash": "0x1c3374235d773b2189aed115aa13143020fcdbbe86e38f358cf3e4771b2f0244",
+        "nonce": "0x5",
+        "storageHash": "0x740ffb9c8c0033fa7e1a7ba2f0f21854d8387252a10ce931d0ae69bb33e84834",
+        "accountProof": [
+            "0xf90211a0b7a7566b963b9cae3e4d0249b8e53056a213772383f48c1ea7210aa1f67fcbf2a05f1f1429f5c25833bd1c17b8033a432110a7b6f95b8222ee5884bba163b9f050a0551ca201a1b1c3dd9b851134ed89da9dd1ba4f43a3f2bffd42c843a0055c1573a0aae2a255bec94739d8f48f1ae75c29936677e2ea789ab109a401ab83a2c1485aa0597fe01507e8302f19a62b01a7bbd1b6385f863ded0b0700229522ceec2b86e9a05ff061d161ca8fe6cdfe3a26acdaacae3e49c469b4e420680ce798f43e9578eea05049e57bd236a6321ae6289dc754b25afbd799634b3392f8d203cf5513b807a7a0b83110bd94ee76922af2d6b9ca093c577017c89f5cfaee27f0bc1da08fa653e6a0f290b036f3c499062a8d392e251bbd48f5db8eeca379ec89f55aa7682153e396a05b761e833a0563bc55d80dee46c8aceed0e6098c2c98c1613271f0950341e01ba0e38fa2a9e0decb2368ac003231468a280bbe665502267c63d7edf5257e7a59b6a03fe8f7618248278bc374b27f286c09444030093afcc29700faf842435eb04b05a030eac030bc51f543d8fc88cf615f6232b62894823085fa14dd8524463a984070a0e685c9a93510c44eb5eb712297a04e041ce58a9a063780a31b1d4404849ac3e3a090677df524ccce1fac051155e4ed422c5315ddf2769988eafe7cd13a0f659992a04b518c04d4ebd8a783061bc60f0e0b205e0edf971bae9def0a5d6ced505d99f880",
+            "0xf8b1808080808080a0f4db6adb14081382357ca127465a7e999ecbbcef7355efbc629c73b066fcb7ffa0b3ee2e85eb47778ffdf0a52ec0b564702a988c678555b95c718c8e1e64e5ed2880a0efd0445874709c215562d927debe4f2b25573811b5813c424836bea8fe55358680a00b9abd5284a61c42ed1bb34b21ed86cc66ecb9faa9708b4674c53a40c620c9db8080a06512cc9f7571059387b24350ce743219a95bd92c57803c8db50f27a6a88969368080",
+            "0xf871a020e120c2c3547c60ee47f712d32e5acf38b35d1cc62e23b055a69bb88284c281b84ef84c05880de0b6b3a7640000a0740ffb9c8c0033fa7e1a7ba2f0f21854d8387252a10ce931d0ae69bb33e84834a01c3374235d773b2189aed115aa13143020fcdbbe86e38f358cf3e4771b2f0244"
+        ],
+        "storageProof": [{
+            "key": "0x0",
+            "value": "0x2a",
+            "proof": [
+                "0xf8518080a09ff88590bcad80a0c522d778b57c11c7676ffd68500cfc626521d92dd9405bac8080808080808080a0f4984a11f61a2921456141df88de6e1a710d28681b91af794c5a721e47839cd78080808080",
+                "0xe219a02936d0ad5fff269477cb713e6bf1110b59b787645b3b1d2b99eea6fe272cf1c0",
+                "0xf851a0b68980e64039c8477cbb8c4f13d9451576fe5cb0caa8e7a5b2a8704f770a8b15808080808080808080a055c99df464ead850fb671d06489c53a0d1a4933d50110b9194417bd82c20c360808080808080",
+                "0xe19f3decd9548b62a8d60345a988386fc84ba6bc95484008f6362f93160ef3e5632a"
+            ]
+        }]
+    }"#;
+
+    const STATE_ROOT: B256 =
+        b256!("9bb73dcb031ab8bd4b6a5393b9ce028452b2febfe535de7d6bf7b583bdffe90f");
+
+    #[test]
+    fn it_verifies_fixture_proofs() {
+        let mut proof: EIP1186ProofResponse =
+            ethers_pub_use::serde_json::from_str(PROOF_RESPONSE).unwrap();
+        proof.verify(STATE_ROOT).unwrap();
+
+        // the second storage node is the extension
+        let extension = list(&proof.storage_proof[0].proof[1]).unwrap();
+        assert_eq!(extension.len(), 2);
+        assert!(!compact_path(string(extension[0]).unwrap()).unwrap().1);
+
+        proof.storage_proof[0].value = U256::from(43);
+        assert!(matches!(
+            proof.verify_storage(),
+            Err(ProofError::ValueMismatch { .. })
+        ));
+        proof.storage_proof[0].key = U256::from(1);
+        proof.storage_proof[0].value = U256::ZERO;
+        assert!(proof.verify_storage().is_err());
+        proof.nonce = U64::from(6);
+        assert!(matches!(
+            proof.verify_account(STATE_ROOT),
+            Err(ProofError::ValueMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn it_follows_inline_nodes() {
+        // extension 0x12 -> branch -> leaf 0x4 at index 3, embedded in the
+        // branch as it is shorter than a hash
+        let inline_leaf = leaf(&[4], b"x");
+        assert!(inline_leaf.len() < 32);
+        let mut children = vec![encode_string(&[]); 17];
+        children[3] = inline_leaf;
+        let children: Vec<&[u8]> = children.iter().map(Vec::as_slice).collect();
+        let branch = encode_list(&children);
+        let extension = encode_list(&[
+            &encode_string(&[0x00, 0x12]),
+            &encode_string(keccak256(&branch).as_bytes()),
+        ]);
+        let root = keccak256(&extension);
+        let proof = [Bytes::from(extension), Bytes::from(branch)];
+
+        assert_eq!(
+            proven_value(root, &[1, 2, 3, 4], &proof).unwrap(),
+            Some(&b"x"[..])
+        );
+        assert_eq!(proven_value(root, &[1, 2, 3, 5], &proof).unwrap(), None);
+        assert_eq!(proven_value(root, &[1, 2, 4, 4], &proof).unwrap(), None);
+        assert_eq!(proven_value(root, &[1, 3, 3, 4], &proof).unwrap(), None);
+    }
+}
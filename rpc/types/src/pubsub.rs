@@ -0,0 +1,178 @@
+use ethers_pub_use::serde::{Deserialize, Serialize};
+
+use ethers_primitives::{B256, U256, U64};
+
+use crate::{block::Header, log::Log};
+
+/// The kind of an `eth_subscribe` subscription
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SubscriptionKind {
+    /// New block headers. Yields [`SubscriptionResult::Header`]
+    NewHeads,
+    /// Logs matching a filter. Yields [`SubscriptionResult::Log`]
+    Logs,
+    /// Hashes of transactions entering the pool. Yields
+    /// [`SubscriptionResult::TransactionHash`]
+    NewPendingTransactions,
+    /// Sync status changes. Yields [`SubscriptionResult::SyncState`]
+    Syncing,
+}
+
+/// The `params` of an `eth_subscription` notification
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SubscriptionNotification<T = SubscriptionResult> {
+    /// The server-assigned subscription id, as returned by `eth_subscribe`
+    pub subscription: U256,
+    /// The notification payload
+    pub result: T,
+}
+
+/// An `eth_subscription` notification payload.
+///
+/// The payload does not name its subscription kind, so variants are
+/// distinguished by shape. Callers that know the kind should deserialize the
+/// payload type directly.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum SubscriptionResult {
+    /// A `newHeads` notification
+    Header(Box<Header>),
+    /// A `logs` notification
+    Log(Box<Log>),
+    /// A `newPendingTransactions` notification
+    TransactionHash(B256),
+    /// A `syncing` notification
+    SyncState(PubSubSyncStatus),
+}
+
+/// A `syncing` notification payload
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum PubSubSyncStatus {
+    /// A bare flag, sent by some clients when syncing stops
+    Simple(bool),
+    /// Sync status with progress details
+    Detailed(SyncStatusMetadata),
+}
+
+impl PubSubSyncStatus {
+    /// `true` if the node reports that it is syncing
+    pub fn is_syncing(&self) -> bool {
+        match self {
+            PubSubSyncStatus::Simple(syncing) => *syncing,
+            PubSubSyncStatus::Detailed(meta) => meta.syncing,
+        }
+    }
+}
+
+/// Detailed sync status sent by `syncing` subscriptions
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SyncStatusMetadata {
+    /// Whether the node is syncing
+    pub syncing: bool,
+    /// Progress, present while syncing
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<SyncProgress>,
+}
+
+/// Sync progress, in blocks
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncProgress {
+    /// Block at which the sync started
+    pub starting_block: U64,
+    /// Block the node has synced up to
+    pub current_block: U64,
+    /// Highest known block
+    pub highest_block: U64,
+}
+
+#[cfg(test)]
+mod test {
+    use ethers_pub_use::serde_json;
+
+    use super::*;
+
+    #[test]
+    fn it_serializes_kinds() {
+        let cases = [
+            (SubscriptionKind::NewHeads, "\"newHeads\""),
+            (SubscriptionKind::Logs, "\"logs\""),
+            (
+                SubscriptionKind::NewPendingTransactions,
+                "\"newPendingTransactions\"",
+            ),
+            (SubscriptionKind::Syncing, "\"syncing\""),
+        ];
+        for (kind, json) in cases {
+            assert_eq!(serde_json::to_string(&kind).unwrap(), json);
+            assert_eq!(
+                serde_json::from_str::<SubscriptionKind>(json).unwrap(),
+                kind
+            );
+        }
+    }
+
+    #[test]
+    fn it_deserializes_notifications() {
+        let json = r#"{
+            "subscription": "0x9cef478923ff08bf67fde6c64013158d",
+            "result": "0xd6fdc5cc41a9959e922f30cb772a9aef46f4daea279307bc5f7024edc4ccd7fa"
+        }"#;
+        let notification: SubscriptionNotification = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            notification.subscription,
+            "0x9cef478923ff08bf67fde6c64013158d"
+                .parse::<U256>()
+                .unwrap()
+        );
+        assert!(matches!(
+            notification.result,
+            SubscriptionResult::TransactionHash(_)
+        ));
+
+        let json = r#"{
+            "subscription": "0x4a8a4c0517381924f9838102c5a4dcb7",
+            "result": {
+                "address": "0x8320fe7702b96808f7bbc0d4a888ed1468216cfd",
+                "blockHash": "0x61cdb2a09ab99abf791d474f20c2ea89bf8de2923a2d42bb49944c8c993cbf04",
+                "blockNumber": "0x29e87",
+                "data": "0x00000000000000000000000000000000000000000000000000000000000000030000000000000000000000000000000000000000000000000000000000000003",
+                "logIndex": "0x0",
+                "topics": ["0xd78a0cb8bb633d06981248b816e7bd33c2a35a6089241d099fa519e361cab902"],
+                "transactionHash": "0xe044554a0a55067caafd07f8020ab9f2af60bdfe337e395ecd84b4877a3d1ab4",
+                "transactionIndex": "0x0"
+            }
+        }"#;
+        let notification: SubscriptionNotification = serde_json::from_str(json).unwrap();
+        let SubscriptionResult::Log(log) = notification.result else {
+            panic!("expected a log, got {:?}", notification.result)
+        };
+        assert_eq!(log.data.len(), 64);
+
+        let json = r#"{
+            "subscription": "0xe2ffeb2703bcf602d42922385829ce96",
+            "result": {
+                "syncing": true,
+                "status": {
+                    "startingBlock": "0x28",
+                    "currentBlock": "0x2a",
+                    "highestBlock": "0x100"
+                }
+            }
+        }"#;
+        let notification: SubscriptionNotification = serde_json::from_str(json).unwrap();
+        let SubscriptionResult::SyncState(status) = notification.result else {
+            panic!("expected a sync status, got {:?}", notification.result)
+        };
+        assert!(status.is_syncing());
+
+        let notification: SubscriptionNotification =
+            serde_json::from_str(r#"{"subscription":"0x1","result":false}"#).unwrap();
+        assert_eq!(
+            notification.result,
+            SubscriptionResult::SyncState(PubSubSyncStatus::Simple(false))
+        );
+    }
+}
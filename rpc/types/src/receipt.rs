@@ -0,0 +1,268 @@
+use ethers_pub_use::serde::{Deserialize, Serialize};
+
+use ethers_primitives::{Address, Bloom, B256, U256, U64};
+
+use crate::{
+    other::{OtherFields, WithOtherFields},
+    Log,
+};
+
+/// A transaction receipt, as returned by `eth_getTransactionReceipt`.
+///
+/// `status` is `None` before Byzantium, when receipts carried a
+/// post-transaction state `root` instead.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionReceipt {
+    /// Hash of the transaction
+    pub transaction_hash: B256,
+    /// Index of the transaction in the block
+    pub transaction_index: U64,
+    /// Hash of the containing block
+    pub block_hash: Option<B256>,
+    /// Number of the containing block
+    pub block_number: Option<U64>,
+    /// Sender of the transaction
+    pub from: Address,
+    /// Recipient of the transaction. `None` for contract creations
+    pub to: Option<Address>,
+    /// Gas used by this and all preceding transactions in the block
+    pub cumulative_gas_used: U256,
+    /// Gas used by this transaction
+    pub gas_used: Option<U256>,
+    /// Address of the created contract, for contract creations
+    pub contract_address: Option<Address>,
+    /// Logs emitted by the transaction
+    pub logs: Vec<Log>,
+    /// Bloom filter over the logs of the transaction
+    pub logs_bloom: Bloom,
+    /// `1` on success, `0` on failure. Since Byzantium
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<U64>,
+    /// Post-transaction state root. Before Byzantium
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub root: Option<B256>,
+    /// Price paid per unit of gas
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub effective_gas_price: Option<U256>,
+    /// EIP-2718 transaction type
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    pub transaction_type: Option<U64>,
+    /// Optimism L1 fee fields
+    #[cfg(feature = "optimism")]
+    #[serde(flatten)]
+    pub optimism: crate::optimism::OptimismReceiptFields,
+    /// Arbitrum L1 gas fields
+    #[cfg(feature = "arbitrum")]
+    #[serde(flatten)]
+    pub arbitrum: crate::arbitrum::ArbitrumReceiptFields,
+    /// Fields outside the spec. Must follow the other flattened fields,
+    /// which take theirs first
+    #[serde(flatten)]
+    pub other: OtherFields,
+}
+
+impl WithOtherFields for TransactionReceipt {
+    fn other_fields(&self) -> &OtherFields {
+        &self.other
+    }
+}
+
+/// The outcome of a transaction, as reported by its receipt
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ReceiptOutcome {
+    /// The transaction succeeded. Since Byzantium
+    Success,
+    /// The transaction reverted or ran out of gas. Since Byzantium
+    Failure,
+    /// The post-transaction state root. Before Byzantium, receipts did not
+    /// report whether the transaction succeeded
+    StateRoot(B256),
+}
+
+impl TransactionReceipt {
+    /// The outcome of the transaction. `None` if the receipt has neither a
+    /// status nor a state root
+    pub fn outcome(&self) -> Option<ReceiptOutcome> {
+        match (self.status, self.root) {
+            (Some(status), _) if status.is_zero() => Some(ReceiptOutcome::Failure),
+            (Some(_), _) => Some(ReceiptOutcome::Success),
+            (None, Some(root)) => Some(ReceiptOutcome::StateRoot(root)),
+            (None, None) => None,
+        }
+    }
+
+    /// `Some(true)` if the transaction succeeded, `Some(false)` if it
+    /// failed. `None` for pre-Byzantium receipts, which have no status
+    pub fn success(&self) -> Option<bool> {
+        self.status.map(|status| !status.is_zero())
+    }
+
+    /// `true` if the transaction succeeded. Pre-Byzantium receipts, which
+    /// have no status, are treated as successful
+    pub fn is_success(&self) -> bool {
+        self.success().unwrap_or(true)
+    }
+
+    /// The address of the contract created by the transaction, if it
+    /// succeeded
+    pub fn created_contract(&self) -> Option<Address> {
+        self.contract_address.filter(|_| self.is_success())
+    }
+
+    /// Gas used by the preceding transactions in the block
+    pub fn preceding_gas_used(&self) -> Option<U256> {
+        self.gas_used
+            .map(|gas_used| self.cumulative_gas_used.saturating_sub(gas_used))
+    }
+
+    /// The fee paid for the execution gas, in wei. `None` if the receipt
+    /// has no gas used or effective gas price
+    pub fn execution_fee(&self) -> Option<U256> {
+        Some(self.gas_used?.saturating_mul(self.effective_gas_price?))
+    }
+
+    /// Decode the logs of the transaction, skipping those `decode` rejects,
+    /// such as logs of other events. For example, with the typed event
+    /// bindings of `ethers-contract`:
+    /// `receipt.decode_logs(Transfer::decode_log)`
+    pub fn decode_logs<'a, T, E>(
+        &'a self,
+        decode: impl Fn(&Log) -> Result<T, E> + 'a,
+    ) -> impl Iterator<Item = T> + 'a {
+        self.logs.iter().filter_map(move |log| decode(log).ok())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ethers_pub_use::serde_json;
+
+    use super::*;
+
+    #[test]
+    fn it_deserializes_receipts() {
+        let json = r#"{
+            "transactionHash": "0x5c504ed432cb51138bcf09aa5e8a410dd4a1e204ef84bfed1be16dfba1b22060",
+            "transactionIndex": "0x1",
+            "blockHash": "0x2b9d2d8f3d2e1a8a5e1c3b4f6d7e8f9a0b1c2d3e4f5a6b7c8d9e0f1a2b3c4d5e",
+            "blockNumber": "0x10d4f",
+            "from": "0xa7d9ddbe1f17865597fbd27ec712455208b6b76d",
+            "to": null,
+            "cumulativeGasUsed": "0x33bc",
+            "gasUsed": "0x4dc",
+            "contractAddress": "0xb60e8dd61c5d32be8058bb8eb970870f07233155",
+            "logs": [],
+            "logsBloom": "0x00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000",
+            "status": "0x1",
+            "effectiveGasPrice": "0x3b9aca00",
+            "type": "0x2"
+        }"#;
+        let receipt: TransactionReceipt = serde_json::from_str(json).unwrap();
+        assert_eq!(receipt.transaction_index, U64::from(1));
+        assert_eq!(receipt.to, None);
+        assert!(receipt.contract_address.is_some());
+        assert_eq!(receipt.transaction_type, Some(U64::from(2)));
+        assert!(receipt.is_success());
+        assert_eq!(receipt.outcome(), Some(ReceiptOutcome::Success));
+        assert_eq!(receipt.created_contract(), receipt.contract_address);
+        assert_eq!(
+            receipt.preceding_gas_used(),
+            Some(U256::from(0x33bc - 0x4dc))
+        );
+        assert_eq!(
+            receipt.execution_fee(),
+            Some(U256::from(0x4dc * 1_000_000_000u64))
+        );
+
+        let round_trip: TransactionReceipt =
+            serde_json::from_value(serde_json::to_value(&receipt).unwrap()).unwrap();
+        assert_eq!(round_trip, receipt);
+    }
+
+    #[cfg(feature = "optimism")]
+    #[test]
+    fn it_deserializes_optimism_receipts() {
+        let json = r#"{
+            "transactionHash": "0x5c504ed432cb51138bcf09aa5e8a410dd4a1e204ef84bfed1be16dfba1b22060",
+            "transactionIndex": "0x1",
+            "from": "0xa7d9ddbe1f17865597fbd27ec712455208b6b76d",
+            "cumulativeGasUsed": "0x33bc",
+            "logs": [],
+            "logsBloom": "0x00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000",
+            "l1GasPrice": "0x6fc23ac00",
+            "l1GasUsed": "0x640",
+            "l1Fee": "0x2bd9a3b8b800",
+            "l1FeeScalar": "0.684"
+        }"#;
+        let receipt: TransactionReceipt = serde_json::from_str(json).unwrap();
+        assert_eq!(receipt.optimism.l1_gas_used, Some(U256::from(1600)));
+        assert_eq!(receipt.optimism.l1_fee_scalar.as_deref(), Some("0.684"));
+        assert_eq!(receipt.optimism.deposit_nonce, None);
+
+        let round_trip: TransactionReceipt =
+            serde_json::from_value(serde_json::to_value(&receipt).unwrap()).unwrap();
+        assert_eq!(round_trip, receipt);
+    }
+
+    #[cfg(feature = "arbitrum")]
+    #[test]
+    fn it_deserializes_arbitrum_receipts() {
+        let json = r#"{
+            "transactionHash": "0x5c504ed432cb51138bcf09aa5e8a410dd4a1e204ef84bfed1be16dfba1b22060",
+            "transactionIndex": "0x1",
+            "from": "0xa7d9ddbe1f17865597fbd27ec712455208b6b76d",
+            "cumulativeGasUsed": "0x33bc",
+            "logs": [],
+            "logsBloom": "0x00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000",
+            "gasUsedForL1": "0x1a2b",
+            "l1BlockNumber": "0x112a880"
+        }"#;
+        let receipt: TransactionReceipt = serde_json::from_str(json).unwrap();
+        assert_eq!(receipt.arbitrum.gas_used_for_l1, Some(U256::from(0x1a2b)));
+        assert_eq!(receipt.arbitrum.l1_block_number, Some(U64::from(0x112a880)));
+    }
+
+    #[test]
+    fn it_reports_outcomes() {
+        let pre_byzantium = TransactionReceipt {
+            root: Some(B256::repeat_byte(1)),
+            contract_address: Some(Address::repeat_byte(2)),
+            ..Default::default()
+        };
+        assert_eq!(
+            pre_byzantium.outcome(),
+            Some(ReceiptOutcome::StateRoot(B256::repeat_byte(1)))
+        );
+        assert_eq!(pre_byzantium.success(), None);
+        assert!(pre_byzantium.is_success());
+
+        let failed = TransactionReceipt {
+            status: Some(U64::ZERO),
+            ..pre_byzantium
+        };
+        assert_eq!(failed.outcome(), Some(ReceiptOutcome::Failure));
+        assert_eq!(failed.success(), Some(false));
+        assert_eq!(failed.created_contract(), None);
+        assert_eq!(TransactionReceipt::default().outcome(), None);
+    }
+
+    #[test]
+    fn it_decodes_logs() {
+        let topic = B256::repeat_byte(0xdd);
+        let receipt = TransactionReceipt {
+            logs: vec![
+                Log {
+                    topics: vec![topic],
+                    ..Default::default()
+                },
+                Log::default(),
+            ],
+            ..Default::default()
+        };
+        let decoded: Vec<_> = receipt
+            .decode_logs(|log| log.topics.first().copied().ok_or(()))
+            .collect();
+        assert_eq!(decoded, [topic]);
+    }
+}
@@ -0,0 +1,121 @@
+use ethers_pub_use::serde::{Deserialize, Serialize};
+
+use ethers_primitives::{Address, U256, U64};
+
+use crate::{transaction::AccessList, Bytes, NameOrAddress};
+
+/// A transaction request, as accepted by `eth_call`, `eth_estimateGas`,
+/// `eth_sendTransaction` and the tracing namespaces.
+///
+/// Unset fields are omitted, leaving the node to fill them in.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionRequest {
+    /// Sender
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from: Option<Address>,
+    /// Recipient. `None` for contract creation. ENS names must be resolved
+    /// before the request is sent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to: Option<NameOrAddress>,
+    /// Gas limit
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gas: Option<U256>,
+    /// Legacy gas price
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gas_price: Option<U256>,
+    /// EIP-1559 max fee per gas
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_fee_per_gas: Option<U256>,
+    /// EIP-1559 max priority fee per gas
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_priority_fee_per_gas: Option<U256>,
+    /// Value transferred, in wei
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<U256>,
+    /// Calldata. Serialized as `data`, which all clients accept
+    #[serde(default, alias = "input", skip_serializing_if = "Option::is_none")]
+    pub data: Option<Bytes>,
+    /// Sender nonce
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<U256>,
+    /// Chain id
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chain_id: Option<U64>,
+    /// EIP-2930 access list
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub access_list: Option<AccessList>,
+}
+
+impl TransactionRequest {
+    /// Set the sender
+    #[must_use = "Builder method outputs must be used"]
+    pub fn from(mut self, from: Address) -> Self {
+        self.from = Some(from);
+        self
+    }
+
+    /// Set the recipient, by address or ENS name
+    #[must_use = "Builder method outputs must be used"]
+    pub fn to(mut self, to: impl Into<NameOrAddress>) -> Self {
+        self.to = Some(to.into());
+        self
+    }
+
+    /// Set the gas limit
+    #[must_use = "Builder method outputs must be used"]
+    pub fn gas(mut self, gas: U256) -> Self {
+        self.gas = Some(gas);
+        self
+    }
+
+    /// Set the value transferred
+    #[must_use = "Builder method outputs must be used"]
+    pub fn value(mut self, value: U256) -> Self {
+        self.value = Some(value);
+        self
+    }
+
+    /// Set the calldata
+    #[must_use = "Builder method outputs must be used"]
+    pub fn data(mut self, data: impl Into<Bytes>) -> Self {
+        self.data = Some(data.into());
+        self
+    }
+
+    /// Set the access list
+    #[must_use = "Builder method outputs must be used"]
+    pub fn access_list(mut self, access_list: AccessList) -> Self {
+        self.access_list = Some(access_list);
+        self
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ethers_pub_use::serde_json;
+
+    use super::*;
+
+    #[test]
+    fn it_serializes_requests() {
+        let request = TransactionRequest::default()
+            .to(Address::repeat_byte(0x11))
+            .data(vec![0xa9, 0x05, 0x9c, 0xbb]);
+        let json = serde_json::to_string(&request).unwrap();
+        assert_eq!(
+            json,
+            r#"{"to":"0x1111111111111111111111111111111111111111","data":"0xa9059cbb"}"#
+        );
+        assert_eq!(
+            serde_json::from_str::<TransactionRequest>(&json).unwrap(),
+            request
+        );
+
+        let input = r#"{"to":"0x1111111111111111111111111111111111111111","input":"0xa9059cbb"}"#;
+        assert_eq!(
+            serde_json::from_str::<TransactionRequest>(input).unwrap(),
+            request
+        );
+    }
+}
@@ -0,0 +1,45 @@
+//! Serde helpers for JSON-RPC encodings that the primitive types do not
+//! cover directly
+
+use std::fmt;
+
+use ethers_primitives::U256;
+use ethers_pub_use::serde::{de, Deserializer};
+
+/// Deserialize a [`U256`] from a hex or decimal string, or from a JSON
+/// number.
+///
+/// Numbers above `u64::MAX` are parsed as floats by `serde_json`, and lose
+/// precision beyond 53 bits.
+pub(crate) fn deserialize_json_u256<'de, D>(deserializer: D) -> Result<U256, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    struct JsonU256Visitor;
+
+    impl de::Visitor<'_> for JsonU256Visitor {
+        type Value = U256;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+            formatter.write_str("a hex or decimal string, or a non-negative number")
+        }
+
+        fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+            Ok(U256::from(v))
+        }
+
+        fn visit_f64<E: de::Error>(self, v: f64) -> Result<Self::Value, E> {
+            U256::try_from(v).map_err(E::custom)
+        }
+
+        fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+            match v.strip_prefix("0x") {
+                Some(hex) => U256::from_str_radix(hex, 16),
+                None => U256::from_str_radix(v, 10),
+            }
+            .map_err(E::custom)
+        }
+    }
+
+    deserializer.deserialize_any(JsonU256Visitor)
+}
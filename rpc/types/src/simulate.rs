@@ -0,0 +1,243 @@
+//! Types of `eth_simulateV1`, which executes sequences of calls across
+//! several simulated blocks, each with its own state and block overrides.
+//!
+//! See the [execution API spec](https://github.com/ethereum/execution-apis/pull/484)
+
+use ethers_pub_use::serde::{Deserialize, Serialize};
+
+use ethers_primitives::{Address, B256, U256, U64};
+
+use crate::{engine::Withdrawal, Bytes, Header, Log, StateOverride, TransactionRequest};
+
+/// The request of `eth_simulateV1`
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulatePayload {
+    /// The simulated blocks, executed in order on top of the base block
+    pub block_state_calls: Vec<SimulatedBlockCalls>,
+    /// Report ETH transfers as logs from the zero address
+    #[serde(default)]
+    pub trace_transfers: bool,
+    /// Validate nonces, balances and fees as a real block would. Otherwise
+    /// calls may have any sender and no fees
+    #[serde(default)]
+    pub validation: bool,
+    /// Return full transactions, rather than hashes, in the simulated
+    /// blocks
+    #[serde(default)]
+    pub return_full_transactions: bool,
+}
+
+impl SimulatePayload {
+    /// Append a simulated block
+    #[must_use = "Builder method outputs must be used"]
+    pub fn block(mut self, block: SimulatedBlockCalls) -> Self {
+        self.block_state_calls.push(block);
+        self
+    }
+
+    /// Report ETH transfers as logs
+    #[must_use = "Builder method outputs must be used"]
+    pub fn trace_transfers(mut self) -> Self {
+        self.trace_transfers = true;
+        self
+    }
+
+    /// Validate the calls as a real block would
+    #[must_use = "Builder method outputs must be used"]
+    pub fn validation(mut self) -> Self {
+        self.validation = true;
+        self
+    }
+}
+
+/// The calls of a simulated block, and the overrides applied before them
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulatedBlockCalls {
+    /// Overrides of the block environment
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_overrides: Option<BlockOverrides>,
+    /// Overrides of account state, applied before the first call
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state_overrides: Option<StateOverride>,
+    /// The calls, executed in order. Each sees the state changes of the
+    /// previous ones
+    pub calls: Vec<TransactionRequest>,
+}
+
+impl SimulatedBlockCalls {
+    /// Append a call
+    #[must_use = "Builder method outputs must be used"]
+    pub fn call(mut self, call: TransactionRequest) -> Self {
+        self.calls.push(call);
+        self
+    }
+
+    /// Set the block overrides
+    #[must_use = "Builder method outputs must be used"]
+    pub fn block_overrides(mut self, overrides: BlockOverrides) -> Self {
+        self.block_overrides = Some(overrides);
+        self
+    }
+
+    /// Set the state overrides
+    #[must_use = "Builder method outputs must be used"]
+    pub fn state_overrides(mut self, overrides: StateOverride) -> Self {
+        self.state_overrides = Some(overrides);
+        self
+    }
+}
+
+/// Overrides of a simulated block's environment. Unset fields default to
+/// following on from the previous block
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockOverrides {
+    /// Block number
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub number: Option<U64>,
+    /// Block timestamp, in seconds since the unix epoch
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time: Option<U64>,
+    /// Gas limit
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gas_limit: Option<U64>,
+    /// Beneficiary of the priority fees
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fee_recipient: Option<Address>,
+    /// Beacon chain randomness
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prev_randao: Option<B256>,
+    /// Base fee per gas
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_fee_per_gas: Option<U256>,
+    /// Base fee per blob gas
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blob_base_fee: Option<U256>,
+    /// Withdrawals processed at the end of the block
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub withdrawals: Option<Vec<Withdrawal>>,
+}
+
+impl BlockOverrides {
+    /// Set the block number
+    #[must_use = "Builder method outputs must be used"]
+    pub fn number(mut self, number: u64) -> Self {
+        self.number = Some(U64::from(number));
+        self
+    }
+
+    /// Set the timestamp
+    #[must_use = "Builder method outputs must be used"]
+    pub fn time(mut self, time: u64) -> Self {
+        self.time = Some(U64::from(time));
+        self
+    }
+
+    /// Set the base fee per gas
+    #[must_use = "Builder method outputs must be used"]
+    pub fn base_fee_per_gas(mut self, base_fee: U256) -> Self {
+        self.base_fee_per_gas = Some(base_fee);
+        self
+    }
+}
+
+/// A simulated block, as returned by `eth_simulateV1`
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulatedBlock {
+    /// The block header. Other block fields, such as the transactions, are
+    /// in its `other` fields
+    #[serde(flatten)]
+    pub header: Header,
+    /// The result of each call, in order
+    pub calls: Vec<SimulatedCall>,
+}
+
+/// The result of a simulated call
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulatedCall {
+    /// `1` on success, `0` on failure
+    pub status: U64,
+    /// Return data, or revert data on failure
+    pub return_data: Bytes,
+    /// Gas used
+    pub gas_used: U64,
+    /// Logs emitted. Empty on failure
+    #[serde(default)]
+    pub logs: Vec<Log>,
+    /// The reason for a failure
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<SimulatedCallError>,
+}
+
+impl SimulatedCall {
+    /// `true` if the call succeeded
+    pub fn is_success(&self) -> bool {
+        !self.status.is_zero()
+    }
+}
+
+/// The reason a simulated call failed
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SimulatedCallError {
+    /// Error code. `3` for reverts
+    pub code: i64,
+    /// Error message
+    pub message: String,
+    /// Revert data, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<String>,
+}
+
+#[cfg(test)]
+mod test {
+    use ethers_pub_use::serde_json::{self, json};
+
+    use super::*;
+
+    #[test]
+    fn it_serializes_payloads() {
+        let payload = SimulatePayload::default().validation().block(
+            SimulatedBlockCalls::default()
+                .block_overrides(BlockOverrides::default().number(18).time(1_700_000_000))
+                .call(TransactionRequest::default().value(U256::from(1))),
+        );
+        assert_eq!(
+            serde_json::to_value(&payload).unwrap(),
+            json!({
+                "blockStateCalls": [{
+                    "blockOverrides": { "number": "0x12", "time": "0x6553f100" },
+                    "calls": [{ "value": "0x1" }]
+                }],
+                "traceTransfers": false,
+                "validation": true,
+                "returnFullTransactions": false
+            })
+        );
+    }
+
+    #[test]
+    fn it_deserializes_results() {
+        let mut block = serde_json::to_value(Header::default()).unwrap();
+        block["transactions"] = json!([]);
+        block["calls"] = json!([
+            { "status": "0x1", "returnData": "0x", "gasUsed": "0x5208", "logs": [] },
+            {
+                "status": "0x0",
+                "returnData": "0x08c379a0",
+                "gasUsed": "0x6000",
+                "logs": [],
+                "error": { "code": 3, "message": "execution reverted", "data": "0x08c379a0" }
+            }
+        ]);
+        let block: SimulatedBlock = serde_json::from_value(block).unwrap();
+        assert!(block.header.other.contains_key("transactions"));
+        assert!(block.calls[0].is_success());
+        assert_eq!(block.calls[0].gas_used, U64::from(21_000));
+        assert!(!block.calls[1].is_success());
+        assert_eq!(block.calls[1].error.as_ref().unwrap().code, 3);
+    }
+}
@@ -0,0 +1,110 @@
+use std::collections::BTreeMap;
+
+use ethers_primitives::{Address, B256, U256, U64};
+use ethers_pub_use::serde::{Deserialize, Serialize};
+
+use crate::Bytes;
+
+/// Temporary changes to the state of accounts, applied before executing an
+/// `eth_call` or `eth_estimateGas`
+pub type StateOverride = BTreeMap<Address, AccountOverride>;
+
+/// Temporary changes to the state of an account. Unset fields are left
+/// unchanged.
+///
+/// `state` replaces the whole storage of the account, while `state_diff`
+/// only replaces the given slots. At most one of them may be set
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountOverride {
+    /// Balance, in wei
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub balance: Option<U256>,
+    /// Nonce
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<U64>,
+    /// Runtime bytecode
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<Bytes>,
+    /// Storage, replacing all existing slots
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state: Option<BTreeMap<B256, B256>>,
+    /// Storage slots to replace, keeping all others
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state_diff: Option<BTreeMap<B256, B256>>,
+}
+
+impl AccountOverride {
+    /// Set the balance
+    #[must_use = "Builder method outputs must be used"]
+    pub fn balance(mut self, balance: U256) -> Self {
+        self.balance = Some(balance);
+        self
+    }
+
+    /// Set the nonce
+    #[must_use = "Builder method outputs must be used"]
+    pub fn nonce(mut self, nonce: u64) -> Self {
+        self.nonce = Some(U64::from(nonce));
+        self
+    }
+
+    /// Set the runtime bytecode
+    #[must_use = "Builder method outputs must be used"]
+    pub fn code(mut self, code: impl Into<Bytes>) -> Self {
+        self.code = Some(code.into());
+        self
+    }
+
+    /// Replace the whole storage
+    #[must_use = "Builder method outputs must be used"]
+    pub fn state(mut self, state: BTreeMap<B256, B256>) -> Self {
+        self.state = Some(state);
+        self
+    }
+
+    /// Replace a single storage slot, keeping all others
+    #[must_use = "Builder method outputs must be used"]
+    pub fn slot(mut self, slot: B256, value: B256) -> Self {
+        self.state_diff
+            .get_or_insert_with(Default::default)
+            .insert(slot, value);
+        self
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ethers_pub_use::serde_json;
+
+    use super::*;
+
+    #[test]
+    fn it_serializes_overrides() {
+        let overrides = StateOverride::from([(
+            Address::repeat_byte(0x11),
+            AccountOverride::default()
+                .balance(U256::from(1))
+                .nonce(2)
+                .slot(B256::zero(), B256::repeat_byte(0xff)),
+        )]);
+        let json = serde_json::to_value(&overrides).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "0x1111111111111111111111111111111111111111": {
+                    "balance": "0x1",
+                    "nonce": "0x2",
+                    "stateDiff": {
+                        "0x0000000000000000000000000000000000000000000000000000000000000000":
+                            "0xffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff"
+                    }
+                }
+            })
+        );
+        assert_eq!(
+            serde_json::from_value::<StateOverride>(json).unwrap(),
+            overrides
+        );
+    }
+}
@@ -0,0 +1,88 @@
+use ethers_primitives::U64;
+use ethers_pub_use::serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+/// Sync status of a node, as returned by `eth_syncing`. Nodes respond
+/// `false` when they are not syncing
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum SyncStatus {
+    /// The node is at the head of the chain
+    #[default]
+    NotSyncing,
+    /// The node is catching up to the head of the chain
+    Syncing(SyncProgress),
+}
+
+impl SyncStatus {
+    /// True if the node is syncing
+    pub const fn is_syncing(&self) -> bool {
+        matches!(self, Self::Syncing(_))
+    }
+}
+
+/// Progress of a syncing node
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncProgress {
+    /// Block at which the sync started
+    pub starting_block: U64,
+    /// Block the node has synced to
+    pub current_block: U64,
+    /// Highest block known to the node
+    pub highest_block: U64,
+}
+
+impl Serialize for SyncStatus {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            Self::NotSyncing => serializer.serialize_bool(false),
+            Self::Syncing(progress) => progress.serialize(serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for SyncStatus {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Status {
+            Bool(bool),
+            Progress(SyncProgress),
+        }
+
+        match Status::deserialize(deserializer)? {
+            Status::Bool(false) => Ok(Self::NotSyncing),
+            Status::Bool(true) => Err(de::Error::custom("expected false or a sync progress")),
+            Status::Progress(progress) => Ok(Self::Syncing(progress)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ethers_pub_use::serde_json;
+
+    use super::*;
+
+    #[test]
+    fn it_deserializes_sync_status() {
+        let status: SyncStatus = serde_json::from_str("false").unwrap();
+        assert_eq!(status, SyncStatus::NotSyncing);
+        assert_eq!(serde_json::to_string(&status).unwrap(), "false");
+        assert!(serde_json::from_str::<SyncStatus>("true").is_err());
+
+        let json = r#"{"startingBlock":"0x0","currentBlock":"0x10","highestBlock":"0x20","pulledStates":"0x1"}"#;
+        let status: SyncStatus = serde_json::from_str(json).unwrap();
+        let SyncStatus::Syncing(progress) = status else {
+            panic!("expected progress");
+        };
+        assert_eq!(progress.current_block, U64::from(16));
+        assert_eq!(progress.highest_block, U64::from(32));
+        assert!(status.is_syncing());
+    }
+}
@@ -0,0 +1,349 @@
+//! Geth-style `debug_trace*` types
+
+use std::collections::BTreeMap;
+
+use ethers_pub_use::serde::{Deserialize, Serialize};
+use ethers_pub_use::serde_json::Value;
+
+use ethers_primitives::{Address, B256, U256};
+
+use crate::Bytes;
+
+/// Options for the default struct logger
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GethDefaultTracingOptions {
+    /// Omit storage from struct logs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub disable_storage: Option<bool>,
+    /// Omit the stack from struct logs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub disable_stack: Option<bool>,
+    /// Include memory in struct logs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enable_memory: Option<bool>,
+    /// Include returndata in struct logs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enable_return_data: Option<bool>,
+}
+
+/// Tracers built into geth
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum GethBuiltInTracer {
+    /// Call tree tracer. Responds with a [`CallFrame`]
+    #[serde(rename = "callTracer")]
+    CallTracer,
+    /// Touched state tracer. Responds with a [`PreStateFrame`]
+    #[serde(rename = "prestateTracer")]
+    PreStateTracer,
+    /// Selector frequency tracer. Responds with a map of selector and
+    /// calldata size to count
+    #[serde(rename = "4byteTracer")]
+    FourByteTracer,
+    /// Tracer that does nothing. Responds with an empty object
+    #[serde(rename = "noopTracer")]
+    NoopTracer,
+}
+
+/// The tracer to run
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum GethTracer {
+    /// A built-in tracer
+    BuiltIn(GethBuiltInTracer),
+    /// A javascript tracer, as source code
+    Js(String),
+}
+
+impl From<GethBuiltInTracer> for GethTracer {
+    fn from(tracer: GethBuiltInTracer) -> Self {
+        GethTracer::BuiltIn(tracer)
+    }
+}
+
+/// Options for `debug_traceTransaction` and `debug_traceCall`.
+///
+/// With no `tracer`, geth runs the struct logger and responds with a
+/// [`DefaultFrame`].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GethDebugTracingOptions {
+    /// Struct logger options
+    #[serde(flatten)]
+    pub config: GethDefaultTracingOptions,
+    /// The tracer to run instead of the struct logger
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tracer: Option<GethTracer>,
+    /// Tracer-specific configuration, e.g. `{"onlyTopCall": true}` for the
+    /// call tracer or `{"diffMode": true}` for the prestate tracer
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tracer_config: Option<Value>,
+    /// Tracing timeout, as a go duration string, e.g. `"10s"`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<String>,
+}
+
+impl GethDebugTracingOptions {
+    /// Set the tracer
+    #[must_use = "Builder method outputs must be used"]
+    pub fn with_tracer(mut self, tracer: impl Into<GethTracer>) -> Self {
+        self.tracer = Some(tracer.into());
+        self
+    }
+
+    /// Set the tracer configuration
+    #[must_use = "Builder method outputs must be used"]
+    pub fn with_tracer_config(mut self, config: Value) -> Self {
+        self.tracer_config = Some(config);
+        self
+    }
+
+    /// Set the tracing timeout
+    #[must_use = "Builder method outputs must be used"]
+    pub fn with_timeout(mut self, timeout: impl Into<String>) -> Self {
+        self.timeout = Some(timeout.into());
+        self
+    }
+}
+
+/// A single step of the struct logger
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StructLog {
+    /// Program counter
+    pub pc: u64,
+    /// Opcode name
+    pub op: String,
+    /// Remaining gas
+    pub gas: u64,
+    /// Cost of the opcode
+    pub gas_cost: u64,
+    /// Call depth
+    pub depth: u64,
+    /// Error raised by the opcode
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Stack, bottom first
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stack: Option<Vec<U256>>,
+    /// Memory, as 32-byte hex words
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory: Option<Vec<String>>,
+    /// Storage slots touched so far in the current contract
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub storage: Option<BTreeMap<B256, B256>>,
+    /// Refund counter
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub refund: Option<u64>,
+}
+
+/// Response of the default struct logger
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DefaultFrame {
+    /// Whether the transaction reverted
+    pub failed: bool,
+    /// Gas used
+    pub gas: u64,
+    /// Returndata
+    pub return_value: Bytes,
+    /// Steps of execution
+    pub struct_logs: Vec<StructLog>,
+}
+
+/// A call frame, as reported by the call tracer
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallFrame {
+    /// Call type, e.g. `CALL` or `CREATE2`
+    #[serde(rename = "type")]
+    pub typ: String,
+    /// Caller
+    pub from: Address,
+    /// Callee. `None` if a creation failed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to: Option<Address>,
+    /// Value transferred
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<U256>,
+    /// Gas available to the call
+    pub gas: U256,
+    /// Gas used by the call
+    pub gas_used: U256,
+    /// Calldata or init code
+    pub input: Bytes,
+    /// Returndata or deployed code
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<Bytes>,
+    /// Error, if the call failed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Decoded revert reason, if the call reverted with one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub revert_reason: Option<String>,
+    /// Child calls
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub calls: Vec<CallFrame>,
+}
+
+/// The state of an account, as reported by the prestate tracer
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccountState {
+    /// Balance
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub balance: Option<U256>,
+    /// Nonce
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<u64>,
+    /// Code
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<Bytes>,
+    /// Storage slots
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub storage: Option<BTreeMap<B256, B256>>,
+}
+
+/// Response of the prestate tracer
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum PreStateFrame {
+    /// State before and after the transaction, with `diffMode: true`
+    Diff {
+        /// Touched accounts before the transaction
+        pre: BTreeMap<Address, AccountState>,
+        /// Changed accounts after the transaction
+        post: BTreeMap<Address, AccountState>,
+    },
+    /// Touched accounts before the transaction
+    Default(BTreeMap<Address, AccountState>),
+}
+
+/// A `debug_trace*` response.
+///
+/// The response does not name its tracer, so variants are distinguished by
+/// shape. Anything unrecognized is returned as [`GethTrace::Unknown`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum GethTrace {
+    /// Default struct logger output
+    Default(DefaultFrame),
+    /// Call tracer output
+    CallTracer(Box<CallFrame>),
+    /// Prestate tracer output
+    PreStateTracer(PreStateFrame),
+    /// Output of any other tracer
+    Unknown(Value),
+}
+
+#[cfg(test)]
+mod test {
+    use ethers_pub_use::serde_json;
+
+    use super::*;
+
+    #[test]
+    fn it_serializes_options() {
+        let opts = GethDebugTracingOptions::default()
+            .with_tracer(GethBuiltInTracer::CallTracer)
+            .with_tracer_config(serde_json::json!({ "onlyTopCall": true }));
+        assert_eq!(
+            serde_json::to_string(&opts).unwrap(),
+            r#"{"tracer":"callTracer","tracerConfig":{"onlyTopCall":true}}"#
+        );
+
+        let opts = GethDebugTracingOptions {
+            config: GethDefaultTracingOptions {
+                disable_storage: Some(true),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert_eq!(
+            serde_json::to_string(&opts).unwrap(),
+            r#"{"disableStorage":true}"#
+        );
+
+        let js = GethDebugTracingOptions::default().with_tracer(GethTracer::Js(
+            "{data: [], fault: function() {}, result: function() {}}".into(),
+        ));
+        let round_trip: GethDebugTracingOptions =
+            serde_json::from_str(&serde_json::to_string(&js).unwrap()).unwrap();
+        assert_eq!(round_trip, js);
+    }
+
+    #[test]
+    fn it_deserializes_traces() {
+        let json = r#"{
+            "failed": false,
+            "gas": 21000,
+            "returnValue": "",
+            "structLogs": [{
+                "pc": 0,
+                "op": "PUSH1",
+                "gas": 79000,
+                "gasCost": 3,
+                "depth": 1,
+                "stack": [],
+                "storage": {
+                    "0000000000000000000000000000000000000000000000000000000000000000": "0000000000000000000000000000000000000000000000000000000000000001"
+                }
+            }]
+        }"#;
+        let GethTrace::Default(frame) = serde_json::from_str(json).unwrap() else {
+            panic!("expected a struct log trace")
+        };
+        assert_eq!(frame.struct_logs[0].op, "PUSH1");
+        assert_eq!(frame.struct_logs[0].storage.as_ref().unwrap().len(), 1);
+
+        let json = r#"{
+            "type": "CALL",
+            "from": "0x25e4a0a8d4d3e3e1c0f0c2bfc4e7d7b39f6cd9a5",
+            "to": "0x1c39ba39e4735cb65978d4db400ddd70a72dc750",
+            "value": "0x0",
+            "gas": "0x7148",
+            "gasUsed": "0x5208",
+            "input": "0xa9059cbb",
+            "output": "0x",
+            "calls": [{
+                "type": "STATICCALL",
+                "from": "0x1c39ba39e4735cb65978d4db400ddd70a72dc750",
+                "to": "0x0000000000000000000000000000000000000001",
+                "gas": "0x100",
+                "gasUsed": "0xbb8",
+                "input": "0x",
+                "error": "out of gas"
+            }]
+        }"#;
+        let GethTrace::CallTracer(frame) = serde_json::from_str(json).unwrap() else {
+            panic!("expected a call trace")
+        };
+        assert_eq!(frame.typ, "CALL");
+        assert_eq!(frame.calls[0].error.as_deref(), Some("out of gas"));
+
+        let json = r#"{
+            "0x1111111111111111111111111111111111111111": {
+                "balance": "0x10",
+                "nonce": 1,
+                "code": "0x6080"
+            }
+        }"#;
+        let GethTrace::PreStateTracer(PreStateFrame::Default(accounts)) =
+            serde_json::from_str(json).unwrap()
+        else {
+            panic!("expected a prestate trace")
+        };
+        let account = &accounts[&Address::repeat_byte(0x11)];
+        assert_eq!(account.code.as_deref(), Some(&[0x60, 0x80][..]));
+
+        let json = r#"{"pre": {}, "post": {}}"#;
+        assert!(matches!(
+            serde_json::from_str(json).unwrap(),
+            GethTrace::PreStateTracer(PreStateFrame::Diff { .. })
+        ));
+
+        assert!(matches!(
+            serde_json::from_str(r#"[1, 2, 3]"#).unwrap(),
+            GethTrace::Unknown(_)
+        ));
+    }
+}
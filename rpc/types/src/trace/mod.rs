@@ -0,0 +1,4 @@
+//! Types for the parity `trace_*` and geth `debug_trace*` namespaces
+
+pub mod geth;
+pub mod parity;
@@ -0,0 +1,381 @@
+//! Parity-style `trace_*` types
+
+use std::collections::BTreeMap;
+
+use ethers_pub_use::serde::{Deserialize, Serialize};
+use ethers_pub_use::serde_json::Value;
+
+use ethers_primitives::{Address, B256, U256, U64};
+
+use crate::{BlockNumber, Bytes};
+
+/// Kinds of trace requested from `trace_call` and `trace_replay*`
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TraceType {
+    /// Call traces
+    Trace,
+    /// Full VM execution traces
+    VmTrace,
+    /// State differences
+    StateDiff,
+}
+
+/// Filter for `trace_filter`
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TraceFilter {
+    /// First block to trace
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from_block: Option<BlockNumber>,
+    /// Last block to trace
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to_block: Option<BlockNumber>,
+    /// Only include traces sent from these addresses
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from_address: Option<Vec<Address>>,
+    /// Only include traces sent to these addresses
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to_address: Option<Vec<Address>>,
+    /// Skip this many traces
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub after: Option<u64>,
+    /// Return at most this many traces
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub count: Option<u64>,
+}
+
+/// The kind of a call
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CallType {
+    /// No call type. Seen on some clients for the outermost frame
+    None,
+    /// `CALL`
+    Call,
+    /// `CALLCODE`
+    CallCode,
+    /// `DELEGATECALL`
+    DelegateCall,
+    /// `STATICCALL`
+    StaticCall,
+}
+
+/// A message call
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallAction {
+    /// Caller
+    pub from: Address,
+    /// Callee
+    pub to: Address,
+    /// Value transferred
+    pub value: U256,
+    /// Gas available to the call
+    pub gas: U256,
+    /// Calldata
+    pub input: Bytes,
+    /// The kind of call
+    pub call_type: CallType,
+}
+
+/// A contract creation
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateAction {
+    /// Creator
+    pub from: Address,
+    /// Value endowed to the new contract
+    pub value: U256,
+    /// Gas available to the creation
+    pub gas: U256,
+    /// Init code
+    pub init: Bytes,
+}
+
+/// A `SELFDESTRUCT`
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SelfdestructAction {
+    /// The destroyed contract
+    pub address: Address,
+    /// Recipient of the remaining balance
+    pub refund_address: Address,
+    /// Balance transferred to the refund address
+    pub balance: U256,
+}
+
+/// A block or uncle reward
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RewardAction {
+    /// Rewarded account
+    pub author: Address,
+    /// Reward amount
+    pub value: U256,
+    /// `block` or `uncle`
+    pub reward_type: String,
+}
+
+/// The action of a trace, tagged by the trace's `type` field
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "action", rename_all = "lowercase")]
+pub enum Action {
+    /// A message call
+    Call(CallAction),
+    /// A contract creation
+    Create(CreateAction),
+    /// A `SELFDESTRUCT`
+    #[serde(rename = "suicide")]
+    Selfdestruct(SelfdestructAction),
+    /// A block or uncle reward
+    Reward(RewardAction),
+}
+
+/// The output of a successful trace
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum TraceOutput {
+    /// Output of a contract creation
+    Create {
+        /// Gas used
+        #[serde(rename = "gasUsed")]
+        gas_used: U256,
+        /// Deployed code
+        code: Bytes,
+        /// Address of the new contract
+        address: Address,
+    },
+    /// Output of a message call
+    Call {
+        /// Gas used
+        #[serde(rename = "gasUsed")]
+        gas_used: U256,
+        /// Returndata
+        output: Bytes,
+    },
+}
+
+/// A single trace of a transaction's execution
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionTrace {
+    /// The traced action
+    #[serde(flatten)]
+    pub action: Action,
+    /// Output, if the action succeeded
+    #[serde(default)]
+    pub result: Option<TraceOutput>,
+    /// Error, if the action failed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Number of child traces
+    pub subtraces: usize,
+    /// Path from the outermost call to this trace
+    pub trace_address: Vec<usize>,
+}
+
+/// A [`TransactionTrace`] with its position in the chain, as returned by
+/// `trace_transaction`, `trace_block` and `trace_filter`
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalizedTransactionTrace {
+    /// The trace
+    #[serde(flatten)]
+    pub trace: TransactionTrace,
+    /// Hash of the containing block
+    #[serde(default)]
+    pub block_hash: Option<B256>,
+    /// Number of the containing block
+    #[serde(default)]
+    pub block_number: Option<U64>,
+    /// Hash of the traced transaction. `None` for rewards
+    #[serde(default)]
+    pub transaction_hash: Option<B256>,
+    /// Index of the traced transaction in the block. `None` for rewards
+    #[serde(default)]
+    pub transaction_position: Option<u64>,
+}
+
+/// A change to a single value in a state diff
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Delta<T> {
+    /// Unchanged
+    #[serde(rename = "=")]
+    Unchanged,
+    /// Created with a value
+    #[serde(rename = "+")]
+    Added(T),
+    /// Removed, with its previous value
+    #[serde(rename = "-")]
+    Removed(T),
+    /// Changed between two values
+    #[serde(rename = "*")]
+    Changed(ChangedValue<T>),
+}
+
+/// The previous and new values of a [`Delta::Changed`]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChangedValue<T> {
+    /// Previous value
+    pub from: T,
+    /// New value
+    pub to: T,
+}
+
+/// Changes to a single account
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccountDiff {
+    /// Balance change
+    pub balance: Delta<U256>,
+    /// Nonce change
+    pub nonce: Delta<U256>,
+    /// Code change, as hex
+    pub code: Delta<String>,
+    /// Storage changes
+    pub storage: BTreeMap<B256, Delta<B256>>,
+}
+
+/// Results of `trace_call` and `trace_replay*`. Each trace kind is present
+/// only if requested
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TraceResults {
+    /// Returndata of the outermost call
+    pub output: Bytes,
+    /// Call traces
+    #[serde(default)]
+    pub trace: Option<Vec<TransactionTrace>>,
+    /// VM execution trace. Left untyped, as it is rarely consumed
+    /// programmatically
+    #[serde(default)]
+    pub vm_trace: Option<Value>,
+    /// State differences, by account
+    #[serde(default)]
+    pub state_diff: Option<BTreeMap<Address, AccountDiff>>,
+    /// Hash of the traced transaction, for `trace_replayBlockTransactions`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transaction_hash: Option<B256>,
+}
+
+#[cfg(test)]
+mod test {
+    use ethers_pub_use::serde_json;
+
+    use super::*;
+
+    #[test]
+    fn it_deserializes_localized_traces() {
+        let json = r#"[{
+            "action": {
+                "callType": "call",
+                "from": "0x83806d539d4ea1c140489a06660319c9a303f874",
+                "gas": "0x1a1f8",
+                "input": "0x",
+                "to": "0x1c39ba39e4735cb65978d4db400ddd70a72dc750",
+                "value": "0x7a16c911b4d00000"
+            },
+            "blockHash": "0x7eb25504e4c202cf3d62fd585d3e238f592c780cca82dacb2ed3cb5b38883add",
+            "blockNumber": "0x2d6da2",
+            "result": {"gasUsed": "0x2982", "output": "0x"},
+            "subtraces": 2,
+            "traceAddress": [],
+            "transactionHash": "0x17104ac9d3312d8c136b7f44d4b8b47852618065ebfa534bd2d3b5ef218ca1f3",
+            "transactionPosition": 2,
+            "type": "call"
+        }, {
+            "action": {
+                "from": "0x1c39ba39e4735cb65978d4db400ddd70a72dc750",
+                "gas": "0x13e99",
+                "init": "0x6080",
+                "value": "0x0"
+            },
+            "blockHash": "0x7eb25504e4c202cf3d62fd585d3e238f592c780cca82dacb2ed3cb5b38883add",
+            "blockNumber": "0x2d6da2",
+            "result": {
+                "address": "0x25e4a0a8d4d3e3e1c0f0c2bfc4e7d7b39f6cd9a5",
+                "code": "0x60",
+                "gasUsed": "0x1000"
+            },
+            "subtraces": 0,
+            "traceAddress": [0],
+            "transactionHash": "0x17104ac9d3312d8c136b7f44d4b8b47852618065ebfa534bd2d3b5ef218ca1f3",
+            "transactionPosition": 2,
+            "type": "create"
+        }, {
+            "action": {
+                "author": "0x5a0b54d5dc17e0aadc383d2db43b0a0d3e029c4c",
+                "rewardType": "block",
+                "value": "0x1bc16d674ec80000"
+            },
+            "blockHash": "0x7eb25504e4c202cf3d62fd585d3e238f592c780cca82dacb2ed3cb5b38883add",
+            "blockNumber": "0x2d6da2",
+            "result": null,
+            "subtraces": 0,
+            "traceAddress": [],
+            "transactionHash": null,
+            "transactionPosition": null,
+            "type": "reward"
+        }]"#;
+        let traces: Vec<LocalizedTransactionTrace> = serde_json::from_str(json).unwrap();
+        assert_eq!(traces.len(), 3);
+
+        let Action::Call(call) = &traces[0].trace.action else {
+            panic!("expected a call, got {:?}", traces[0].trace.action)
+        };
+        assert_eq!(call.call_type, CallType::Call);
+        assert!(matches!(
+            traces[0].trace.result,
+            Some(TraceOutput::Call { .. })
+        ));
+
+        assert!(matches!(traces[1].trace.action, Action::Create(_)));
+        assert!(matches!(
+            traces[1].trace.result,
+            Some(TraceOutput::Create { .. })
+        ));
+        assert_eq!(traces[1].trace.trace_address, vec![0]);
+
+        assert!(matches!(traces[2].trace.action, Action::Reward(_)));
+        assert_eq!(traces[2].transaction_hash, None);
+
+        let round_trip: Vec<LocalizedTransactionTrace> =
+            serde_json::from_value(serde_json::to_value(&traces).unwrap()).unwrap();
+        assert_eq!(round_trip, traces);
+    }
+
+    #[test]
+    fn it_deserializes_state_diffs() {
+        let json = r#"{
+            "output": "0x",
+            "trace": null,
+            "vmTrace": null,
+            "stateDiff": {
+                "0x1111111111111111111111111111111111111111": {
+                    "balance": {"*": {"from": "0x1", "to": "0x2"}},
+                    "nonce": "=",
+                    "code": "=",
+                    "storage": {
+                        "0x0000000000000000000000000000000000000000000000000000000000000000": {
+                            "+": "0x0000000000000000000000000000000000000000000000000000000000000001"
+                        }
+                    }
+                }
+            }
+        }"#;
+        let results: TraceResults = serde_json::from_str(json).unwrap();
+        let diff = &results.state_diff.unwrap()[&Address::repeat_byte(0x11)];
+        assert_eq!(
+            diff.balance,
+            Delta::Changed(ChangedValue {
+                from: U256::from(1),
+                to: U256::from(2)
+            })
+        );
+        assert_eq!(diff.nonce, Delta::Unchanged);
+        assert!(matches!(
+            diff.storage.values().next(),
+            Some(Delta::Added(_))
+        ));
+    }
+}
@@ -0,0 +1,111 @@
+use ethers_primitives::{Address, B256, U256};
+use ethers_pub_use::serde::{Deserialize, Serialize};
+use ethers_rlp::{BufMut, Decodable, DecodeError, Encodable};
+
+/// An address and the storage slots a transaction pre-declares it will touch
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccessListItem {
+    /// Accessed account
+    pub address: Address,
+    /// Accessed storage slots of the account
+    pub storage_keys: Vec<B256>,
+}
+
+impl AccessListItem {
+    fn payload_length(&self) -> usize {
+        self.address.length() + self.storage_keys.length()
+    }
+}
+
+impl Encodable for AccessListItem {
+    fn length(&self) -> usize {
+        super::list_length(self.payload_length())
+    }
+
+    fn encode(&self, out: &mut dyn BufMut) {
+        super::list_header(self.payload_length()).encode(out);
+        self.address.encode(out);
+        self.storage_keys.encode(out);
+    }
+}
+
+impl Decodable for AccessListItem {
+    fn decode(buf: &mut &[u8]) -> Result<Self, DecodeError> {
+        super::decode_list(buf, |buf| {
+            Ok(Self {
+                address: Decodable::decode(buf)?,
+                storage_keys: Decodable::decode(buf)?,
+            })
+        })
+    }
+}
+
+/// An EIP-2930 access list
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct AccessList(pub Vec<AccessListItem>);
+
+impl Encodable for AccessList {
+    fn length(&self) -> usize {
+        self.0.length()
+    }
+
+    fn encode(&self, out: &mut dyn BufMut) {
+        self.0.encode(out)
+    }
+}
+
+impl Decodable for AccessList {
+    fn decode(buf: &mut &[u8]) -> Result<Self, DecodeError> {
+        Vec::decode(buf).map(Self)
+    }
+}
+
+impl From<Vec<AccessListItem>> for AccessList {
+    fn from(items: Vec<AccessListItem>) -> Self {
+        Self(items)
+    }
+}
+
+/// An access list generated by `eth_createAccessList`, and the gas the
+/// transaction uses with it
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccessListWithGasUsed {
+    /// The generated access list
+    pub access_list: AccessList,
+    /// Gas used by the transaction with the access list
+    pub gas_used: U256,
+    /// Execution error of the transaction, if it reverted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[cfg(test)]
+mod test {
+    use ethers_pub_use::serde_json;
+
+    use super::*;
+
+    #[test]
+    fn it_deserializes_generated_access_lists() {
+        let json = r#"{
+            "accessList": [{
+                "address": "0x1111111111111111111111111111111111111111",
+                "storageKeys": ["0x0000000000000000000000000000000000000000000000000000000000000001"]
+            }],
+            "gasUsed": "0x5208"
+        }"#;
+        let generated: AccessListWithGasUsed = serde_json::from_str(json).unwrap();
+        assert_eq!(generated.gas_used, U256::from(21000));
+        assert_eq!(generated.error, None);
+        assert_eq!(
+            generated.access_list,
+            AccessList(vec![AccessListItem {
+                address: Address::repeat_byte(0x11),
+                storage_keys: vec![B256::from(U256::from(1))],
+            }])
+        );
+    }
+}
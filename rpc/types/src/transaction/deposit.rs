@@ -0,0 +1,148 @@
+use ethers_primitives::{keccak256, Address, B256, U256};
+use ethers_rlp::{BufMut, Decodable, DecodeError, Encodable};
+
+use crate::Bytes;
+
+/// An Optimism deposit transaction, derived from an L1 deposit.
+///
+/// It has no signature, as the sender is authenticated on L1, so it is not
+/// a [`TypedTransaction`] variant. Decode it from raw L2 transactions with
+/// type [`DepositTransaction::TYPE`]
+///
+/// [`TypedTransaction`]: super::TypedTransaction
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct DepositTransaction {
+    /// Uniquely identifies the deposit's origin on L1
+    pub source_hash: B256,
+    /// The sender
+    pub from: Address,
+    /// Recipient. `None` for contract creation
+    pub to: Option<Address>,
+    /// ETH minted on L2, in wei
+    pub mint: U256,
+    /// Value transferred, in wei
+    pub value: U256,
+    /// Gas limit
+    pub gas_limit: u64,
+    /// `true` for system transactions, which do not consume block gas
+    pub is_system_tx: bool,
+    /// Calldata, or initcode for contract creation
+    pub input: Bytes,
+}
+
+impl DepositTransaction {
+    /// The EIP-2718 transaction type
+    pub const TYPE: u8 = 0x7e;
+
+    /// The EIP-2718 encoding: the type byte followed by the RLP list of the
+    /// fields
+    pub fn rlp(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(1 + self.length());
+        out.push(Self::TYPE);
+        self.encode(&mut out);
+        out
+    }
+
+    /// Decode a deposit transaction from its EIP-2718 encoding
+    pub fn decode_raw(raw: &[u8]) -> Result<Self, DecodeError> {
+        match raw.split_first() {
+            Some((&Self::TYPE, mut buf)) => {
+                let tx = Self::decode(&mut buf)?;
+                if !buf.is_empty() {
+                    return Err(DecodeError::UnexpectedLength);
+                }
+                Ok(tx)
+            }
+            Some(_) => Err(DecodeError::Custom("not a deposit transaction")),
+            None => Err(DecodeError::InputTooShort),
+        }
+    }
+
+    /// The transaction hash, the hash of its EIP-2718 encoding
+    pub fn hash(&self) -> B256 {
+        keccak256(self.rlp())
+    }
+
+    fn fields_length(&self) -> usize {
+        self.source_hash.length()
+            + self.from.length()
+            + super::to_length(&self.to)
+            + self.mint.length()
+            + self.value.length()
+            + self.gas_limit.length()
+            + self.is_system_tx.length()
+            + self.input.length()
+    }
+
+    fn encode_fields(&self, out: &mut dyn BufMut) {
+        self.source_hash.encode(out);
+        self.from.encode(out);
+        super::encode_to(&self.to, out);
+        self.mint.encode(out);
+        self.value.encode(out);
+        self.gas_limit.encode(out);
+        self.is_system_tx.encode(out);
+        self.input.encode(out);
+    }
+
+    fn decode_fields(buf: &mut &[u8]) -> Result<Self, DecodeError> {
+        Ok(Self {
+            source_hash: Decodable::decode(buf)?,
+            from: Decodable::decode(buf)?,
+            to: super::decode_to(buf)?,
+            mint: Decodable::decode(buf)?,
+            value: Decodable::decode(buf)?,
+            gas_limit: Decodable::decode(buf)?,
+            is_system_tx: Decodable::decode(buf)?,
+            input: Decodable::decode(buf)?,
+        })
+    }
+}
+
+/// Encodes the transaction fields, without the type prefix
+impl Encodable for DepositTransaction {
+    fn length(&self) -> usize {
+        super::list_length(self.fields_length())
+    }
+
+    fn encode(&self, out: &mut dyn BufMut) {
+        super::list_header(self.fields_length()).encode(out);
+        self.encode_fields(out);
+    }
+}
+
+impl Decodable for DepositTransaction {
+    fn decode(buf: &mut &[u8]) -> Result<Self, DecodeError> {
+        super::decode_list(buf, Self::decode_fields)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_round_trips_deposits() {
+        let tx = DepositTransaction {
+            source_hash: B256::repeat_byte(0x11),
+            from: Address::repeat_byte(0x22),
+            to: Some(Address::repeat_byte(0x33)),
+            mint: U256::from(10u64.pow(18)),
+            value: U256::from(10u64.pow(18)),
+            gas_limit: 100_000,
+            is_system_tx: false,
+            input: Bytes::default(),
+        };
+        let raw = tx.rlp();
+        assert_eq!(raw[0], 0x7e);
+        assert_eq!(DepositTransaction::decode_raw(&raw).unwrap(), tx);
+        assert_eq!(tx.hash(), keccak256(&raw));
+
+        let creation = DepositTransaction { to: None, ..tx };
+        assert_eq!(
+            DepositTransaction::decode_raw(&creation.rlp()).unwrap(),
+            creation
+        );
+        assert!(DepositTransaction::decode_raw(&raw[1..]).is_err());
+    }
+}
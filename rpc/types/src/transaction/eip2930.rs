@@ -0,0 +1,85 @@
+use ethers_primitives::{Address, U256};
+use ethers_rlp::{BufMut, Decodable, DecodeError, Encodable};
+
+use crate::Bytes;
+
+use super::AccessList;
+
+/// An EIP-2930 transaction, carrying an access list
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Eip2930Transaction {
+    /// Chain id
+    pub chain_id: u64,
+    /// Sender nonce
+    pub nonce: u64,
+    /// Gas price, in wei
+    pub gas_price: U256,
+    /// Gas limit
+    pub gas_limit: u64,
+    /// Recipient. `None` for contract creation
+    pub to: Option<Address>,
+    /// Value transferred, in wei
+    pub value: U256,
+    /// Calldata, or initcode for contract creation
+    pub input: Bytes,
+    /// Accounts and storage slots the transaction pre-declares
+    pub access_list: AccessList,
+}
+
+impl Eip2930Transaction {
+    /// The EIP-2718 transaction type
+    pub const TYPE: u8 = 1;
+
+    pub(super) fn fields_length(&self) -> usize {
+        self.chain_id.length()
+            + self.nonce.length()
+            + self.gas_price.length()
+            + self.gas_limit.length()
+            + super::to_length(&self.to)
+            + self.value.length()
+            + self.input.length()
+            + self.access_list.length()
+    }
+
+    pub(super) fn encode_fields(&self, out: &mut dyn BufMut) {
+        self.chain_id.encode(out);
+        self.nonce.encode(out);
+        self.gas_price.encode(out);
+        self.gas_limit.encode(out);
+        super::encode_to(&self.to, out);
+        self.value.encode(out);
+        self.input.encode(out);
+        self.access_list.encode(out);
+    }
+
+    pub(super) fn decode_fields(buf: &mut &[u8]) -> Result<Self, DecodeError> {
+        Ok(Self {
+            chain_id: Decodable::decode(buf)?,
+            nonce: Decodable::decode(buf)?,
+            gas_price: Decodable::decode(buf)?,
+            gas_limit: Decodable::decode(buf)?,
+            to: super::decode_to(buf)?,
+            value: Decodable::decode(buf)?,
+            input: Decodable::decode(buf)?,
+            access_list: Decodable::decode(buf)?,
+        })
+    }
+}
+
+/// Encodes the fields signed by the sender, without the type prefix
+impl Encodable for Eip2930Transaction {
+    fn length(&self) -> usize {
+        super::list_length(self.fields_length())
+    }
+
+    fn encode(&self, out: &mut dyn BufMut) {
+        super::list_header(self.fields_length()).encode(out);
+        self.encode_fields(out);
+    }
+}
+
+impl Decodable for Eip2930Transaction {
+    fn decode(buf: &mut &[u8]) -> Result<Self, DecodeError> {
+        super::decode_list(buf, Self::decode_fields)
+    }
+}
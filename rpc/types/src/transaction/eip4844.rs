@@ -0,0 +1,103 @@
+use ethers_primitives::{Address, B256, U256};
+use ethers_rlp::{BufMut, Decodable, DecodeError, Encodable};
+
+use crate::Bytes;
+
+use super::AccessList;
+
+/// An EIP-4844 transaction, paying for blobs carried alongside it.
+///
+/// Only the canonical form is supported: the blobs, commitments and proofs
+/// sent to the mempool with the transaction are not part of this type
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Eip4844Transaction {
+    /// Chain id
+    pub chain_id: u64,
+    /// Sender nonce
+    pub nonce: u64,
+    /// Max priority fee per gas, in wei
+    pub max_priority_fee_per_gas: U256,
+    /// Max fee per gas, in wei
+    pub max_fee_per_gas: U256,
+    /// Gas limit
+    pub gas_limit: u64,
+    /// Recipient. Blob transactions cannot create contracts
+    pub to: Address,
+    /// Value transferred, in wei
+    pub value: U256,
+    /// Calldata
+    pub input: Bytes,
+    /// Accounts and storage slots the transaction pre-declares
+    pub access_list: AccessList,
+    /// Max fee per blob gas, in wei
+    pub max_fee_per_blob_gas: U256,
+    /// Versioned hashes of the blob commitments
+    pub blob_versioned_hashes: Vec<B256>,
+}
+
+impl Eip4844Transaction {
+    /// The EIP-2718 transaction type
+    pub const TYPE: u8 = 3;
+
+    pub(super) fn fields_length(&self) -> usize {
+        self.chain_id.length()
+            + self.nonce.length()
+            + self.max_priority_fee_per_gas.length()
+            + self.max_fee_per_gas.length()
+            + self.gas_limit.length()
+            + self.to.length()
+            + self.value.length()
+            + self.input.length()
+            + self.access_list.length()
+            + self.max_fee_per_blob_gas.length()
+            + self.blob_versioned_hashes.length()
+    }
+
+    pub(super) fn encode_fields(&self, out: &mut dyn BufMut) {
+        self.chain_id.encode(out);
+        self.nonce.encode(out);
+        self.max_priority_fee_per_gas.encode(out);
+        self.max_fee_per_gas.encode(out);
+        self.gas_limit.encode(out);
+        self.to.encode(out);
+        self.value.encode(out);
+        self.input.encode(out);
+        self.access_list.encode(out);
+        self.max_fee_per_blob_gas.encode(out);
+        self.blob_versioned_hashes.encode(out);
+    }
+
+    pub(super) fn decode_fields(buf: &mut &[u8]) -> Result<Self, DecodeError> {
+        Ok(Self {
+            chain_id: Decodable::decode(buf)?,
+            nonce: Decodable::decode(buf)?,
+            max_priority_fee_per_gas: Decodable::decode(buf)?,
+            max_fee_per_gas: Decodable::decode(buf)?,
+            gas_limit: Decodable::decode(buf)?,
+            to: Decodable::decode(buf)?,
+            value: Decodable::decode(buf)?,
+            input: Decodable::decode(buf)?,
+            access_list: Decodable::decode(buf)?,
+            max_fee_per_blob_gas: Decodable::decode(buf)?,
+            blob_versioned_hashes: Decodable::decode(buf)?,
+        })
+    }
+}
+
+/// Encodes the fields signed by the sender, without the type prefix
+impl Encodable for Eip4844Transaction {
+    fn length(&self) -> usize {
+        super::list_length(self.fields_length())
+    }
+
+    fn encode(&self, out: &mut dyn BufMut) {
+        super::list_header(self.fields_length()).encode(out);
+        self.encode_fields(out);
+    }
+}
+
+impl Decodable for Eip4844Transaction {
+    fn decode(buf: &mut &[u8]) -> Result<Self, DecodeError> {
+        super::decode_list(buf, Self::decode_fields)
+    }
+}
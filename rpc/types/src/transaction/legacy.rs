@@ -0,0 +1,95 @@
+use ethers_primitives::{Address, U256};
+use ethers_rlp::{BufMut, Decodable, DecodeError, Encodable};
+
+use crate::Bytes;
+
+/// A pre-EIP-2718 transaction
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct LegacyTransaction {
+    /// Chain id, for EIP-155 replay protection. `None` for unprotected
+    /// transactions
+    pub chain_id: Option<u64>,
+    /// Sender nonce
+    pub nonce: u64,
+    /// Gas price, in wei
+    pub gas_price: U256,
+    /// Gas limit
+    pub gas_limit: u64,
+    /// Recipient. `None` for contract creation
+    pub to: Option<Address>,
+    /// Value transferred, in wei
+    pub value: U256,
+    /// Calldata, or initcode for contract creation
+    pub input: Bytes,
+}
+
+impl LegacyTransaction {
+    /// The fields common to the signed and unsigned encodings. The chain id
+    /// is not one of them
+    pub(super) fn fields_length(&self) -> usize {
+        self.nonce.length()
+            + self.gas_price.length()
+            + self.gas_limit.length()
+            + super::to_length(&self.to)
+            + self.value.length()
+            + self.input.length()
+    }
+
+    pub(super) fn encode_fields(&self, out: &mut dyn BufMut) {
+        self.nonce.encode(out);
+        self.gas_price.encode(out);
+        self.gas_limit.encode(out);
+        super::encode_to(&self.to, out);
+        self.value.encode(out);
+        self.input.encode(out);
+    }
+
+    pub(super) fn decode_fields(buf: &mut &[u8]) -> Result<Self, DecodeError> {
+        Ok(Self {
+            chain_id: None,
+            nonce: Decodable::decode(buf)?,
+            gas_price: Decodable::decode(buf)?,
+            gas_limit: Decodable::decode(buf)?,
+            to: super::decode_to(buf)?,
+            value: Decodable::decode(buf)?,
+            input: Decodable::decode(buf)?,
+        })
+    }
+
+    fn eip155_length(&self) -> usize {
+        self.chain_id.map_or(0, |chain_id| chain_id.length() + 2)
+    }
+}
+
+/// Encodes the fields signed by the sender. Per EIP-155, replay protected
+/// transactions append the chain id and two empty fields
+impl Encodable for LegacyTransaction {
+    fn length(&self) -> usize {
+        super::list_length(self.fields_length() + self.eip155_length())
+    }
+
+    fn encode(&self, out: &mut dyn BufMut) {
+        super::list_header(self.fields_length() + self.eip155_length()).encode(out);
+        self.encode_fields(out);
+        if let Some(chain_id) = self.chain_id {
+            chain_id.encode(out);
+            0u8.encode(out);
+            0u8.encode(out);
+        }
+    }
+}
+
+impl Decodable for LegacyTransaction {
+    fn decode(buf: &mut &[u8]) -> Result<Self, DecodeError> {
+        super::decode_list(buf, |buf| {
+            let mut tx = Self::decode_fields(buf)?;
+            if !buf.is_empty() {
+                tx.chain_id = Some(Decodable::decode(buf)?);
+                if u8::decode(buf)? != 0 || u8::decode(buf)? != 0 {
+                    return Err(DecodeError::Custom("invalid EIP-155 fields"));
+                }
+            }
+            Ok(tx)
+        })
+    }
+}
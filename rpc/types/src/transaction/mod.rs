@@ -0,0 +1,565 @@
+//! Transactions and their EIP-2718 envelope
+
+use ethers_primitives::{keccak256, Address, Signature, B256, U256};
+use ethers_rlp::{
+    BufMut, Decodable, DecodeError, Encodable, Header, EMPTY_LIST_CODE, EMPTY_STRING_CODE,
+};
+
+mod access_list;
+pub use access_list::{AccessList, AccessListItem, AccessListWithGasUsed};
+
+#[cfg(feature = "optimism")]
+mod deposit;
+#[cfg(feature = "optimism")]
+pub use deposit::DepositTransaction;
+
+mod eip1559;
+pub use eip1559::Eip1559Transaction;
+
+mod eip2930;
+pub use eip2930::Eip2930Transaction;
+
+mod eip4844;
+pub use eip4844::Eip4844Transaction;
+
+mod legacy;
+pub use legacy::LegacyTransaction;
+
+/// A transaction of any supported type.
+///
+/// Encodes as the EIP-2718 envelope of the unsigned transaction: the RLP
+/// list of legacy transactions, and the type byte followed by the RLP list
+/// of typed transactions
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum TypedTransaction {
+    /// Legacy transaction
+    Legacy(LegacyTransaction),
+    /// EIP-2930 transaction
+    Eip2930(Eip2930Transaction),
+    /// EIP-1559 transaction
+    Eip1559(Eip1559Transaction),
+    /// EIP-4844 transaction
+    Eip4844(Eip4844Transaction),
+}
+
+impl TypedTransaction {
+    /// The EIP-2718 transaction type. `0` for legacy transactions
+    pub const fn tx_type(&self) -> u8 {
+        match self {
+            Self::Legacy(_) => 0,
+            Self::Eip2930(_) => Eip2930Transaction::TYPE,
+            Self::Eip1559(_) => Eip1559Transaction::TYPE,
+            Self::Eip4844(_) => Eip4844Transaction::TYPE,
+        }
+    }
+
+    /// The chain id. `None` for legacy transactions without EIP-155 replay
+    /// protection
+    pub const fn chain_id(&self) -> Option<u64> {
+        match self {
+            Self::Legacy(tx) => tx.chain_id,
+            Self::Eip2930(tx) => Some(tx.chain_id),
+            Self::Eip1559(tx) => Some(tx.chain_id),
+            Self::Eip4844(tx) => Some(tx.chain_id),
+        }
+    }
+
+    /// The sender's nonce
+    pub const fn nonce(&self) -> u64 {
+        match self {
+            Self::Legacy(tx) => tx.nonce,
+            Self::Eip2930(tx) => tx.nonce,
+            Self::Eip1559(tx) => tx.nonce,
+            Self::Eip4844(tx) => tx.nonce,
+        }
+    }
+
+    /// Set the sender's nonce
+    pub fn set_nonce(&mut self, nonce: u64) {
+        match self {
+            Self::Legacy(tx) => tx.nonce = nonce,
+            Self::Eip2930(tx) => tx.nonce = nonce,
+            Self::Eip1559(tx) => tx.nonce = nonce,
+            Self::Eip4844(tx) => tx.nonce = nonce,
+        }
+    }
+
+    /// The EIP-2718 encoding of the unsigned transaction
+    pub fn rlp_unsigned(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(self.length());
+        self.encode(&mut out);
+        out
+    }
+
+    /// The hash signed by the sender
+    pub fn signature_hash(&self) -> B256 {
+        keccak256(self.rlp_unsigned())
+    }
+
+    /// The EIP-2718 encoding of the signed transaction, as sent with
+    /// `eth_sendRawTransaction`. `signature.v` is the recovery id plus 27
+    pub fn rlp_signed(&self, signature: &Signature) -> Vec<u8> {
+        let y_parity = signature.v.saturating_sub(27);
+        let v = match self {
+            Self::Legacy(tx) => match tx.chain_id {
+                Some(chain_id) => 35 + 2 * chain_id + y_parity,
+                None => 27 + y_parity,
+            },
+            _ => y_parity,
+        };
+        let payload_length =
+            self.fields_length() + v.length() + signature.r.length() + signature.s.length();
+
+        let mut out = Vec::with_capacity(1 + list_length(payload_length));
+        if !matches!(self, Self::Legacy(_)) {
+            out.push(self.tx_type());
+        }
+        list_header(payload_length).encode(&mut out);
+        self.encode_fields(&mut out);
+        v.encode(&mut out);
+        signature.r.encode(&mut out);
+        signature.s.encode(&mut out);
+        out
+    }
+
+    /// Decode a signed transaction from its EIP-2718 encoding, and recover
+    /// its sender
+    pub fn decode_raw(raw: &[u8]) -> Result<SignedTransaction, DecodeError> {
+        let mut buf = raw;
+        let tx_type = *buf.first().ok_or(DecodeError::InputTooShort)?;
+        if tx_type < EMPTY_LIST_CODE {
+            buf = &buf[1..];
+        }
+
+        let (mut tx, v, r, s) = decode_list(&mut buf, |buf| {
+            let tx = match tx_type {
+                EMPTY_LIST_CODE.. => Self::Legacy(LegacyTransaction::decode_fields(buf)?),
+                Eip2930Transaction::TYPE => Self::Eip2930(Eip2930Transaction::decode_fields(buf)?),
+                Eip1559Transaction::TYPE => Self::Eip1559(Eip1559Transaction::decode_fields(buf)?),
+                Eip4844Transaction::TYPE => Self::Eip4844(Eip4844Transaction::decode_fields(buf)?),
+                _ => return Err(DecodeError::Custom("unsupported transaction type")),
+            };
+            Ok((
+                tx,
+                u64::decode(buf)?,
+                U256::decode(buf)?,
+                U256::decode(buf)?,
+            ))
+        })?;
+        if !buf.is_empty() {
+            return Err(DecodeError::UnexpectedLength);
+        }
+
+        let y_parity = match &mut tx {
+            Self::Legacy(tx) => match v {
+                27 | 28 => v - 27,
+                35.. => {
+                    tx.chain_id = Some((v - 35) / 2);
+                    (v - 35) % 2
+                }
+                _ => return Err(DecodeError::Custom("invalid signature")),
+            },
+            _ if v <= 1 => v,
+            _ => return Err(DecodeError::Custom("invalid signature")),
+        };
+        let signature = Signature {
+            r,
+            s,
+            v: y_parity + 27,
+        };
+        let from = signature
+            .recover(tx.signature_hash())
+            .ok_or(DecodeError::Custom("invalid signature"))?;
+
+        Ok(SignedTransaction {
+            tx,
+            signature,
+            from,
+            hash: keccak256(raw),
+        })
+    }
+
+    /// The length of the fields common to the signed and unsigned encodings
+    fn fields_length(&self) -> usize {
+        match self {
+            Self::Legacy(tx) => tx.fields_length(),
+            Self::Eip2930(tx) => tx.fields_length(),
+            Self::Eip1559(tx) => tx.fields_length(),
+            Self::Eip4844(tx) => tx.fields_length(),
+        }
+    }
+
+    fn encode_fields(&self, out: &mut dyn BufMut) {
+        match self {
+            Self::Legacy(tx) => tx.encode_fields(out),
+            Self::Eip2930(tx) => tx.encode_fields(out),
+            Self::Eip1559(tx) => tx.encode_fields(out),
+            Self::Eip4844(tx) => tx.encode_fields(out),
+        }
+    }
+}
+
+impl From<LegacyTransaction> for TypedTransaction {
+    fn from(tx: LegacyTransaction) -> Self {
+        Self::Legacy(tx)
+    }
+}
+
+impl From<Eip2930Transaction> for TypedTransaction {
+    fn from(tx: Eip2930Transaction) -> Self {
+        Self::Eip2930(tx)
+    }
+}
+
+impl From<Eip1559Transaction> for TypedTransaction {
+    fn from(tx: Eip1559Transaction) -> Self {
+        Self::Eip1559(tx)
+    }
+}
+
+impl From<Eip4844Transaction> for TypedTransaction {
+    fn from(tx: Eip4844Transaction) -> Self {
+        Self::Eip4844(tx)
+    }
+}
+
+impl Encodable for TypedTransaction {
+    fn length(&self) -> usize {
+        match self {
+            Self::Legacy(tx) => tx.length(),
+            Self::Eip2930(tx) => 1 + tx.length(),
+            Self::Eip1559(tx) => 1 + tx.length(),
+            Self::Eip4844(tx) => 1 + tx.length(),
+        }
+    }
+
+    fn encode(&self, out: &mut dyn BufMut) {
+        match self {
+            Self::Legacy(tx) => tx.encode(out),
+            Self::Eip2930(tx) => {
+                out.put_u8(Eip2930Transaction::TYPE);
+                tx.encode(out);
+            }
+            Self::Eip1559(tx) => {
+                out.put_u8(Eip1559Transaction::TYPE);
+                tx.encode(out);
+            }
+            Self::Eip4844(tx) => {
+                out.put_u8(Eip4844Transaction::TYPE);
+                tx.encode(out);
+            }
+        }
+    }
+}
+
+impl Decodable for TypedTransaction {
+    fn decode(buf: &mut &[u8]) -> Result<Self, DecodeError> {
+        let tx_type = *buf.first().ok_or(DecodeError::InputTooShort)?;
+        if tx_type >= EMPTY_LIST_CODE {
+            return LegacyTransaction::decode(buf).map(Self::Legacy);
+        }
+
+        *buf = &buf[1..];
+        match tx_type {
+            Eip2930Transaction::TYPE => Eip2930Transaction::decode(buf).map(Self::Eip2930),
+            Eip1559Transaction::TYPE => Eip1559Transaction::decode(buf).map(Self::Eip1559),
+            Eip4844Transaction::TYPE => Eip4844Transaction::decode(buf).map(Self::Eip4844),
+            _ => Err(DecodeError::Custom("unsupported transaction type")),
+        }
+    }
+}
+
+/// A signed transaction, with its recovered sender
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SignedTransaction {
+    /// The transaction
+    pub tx: TypedTransaction,
+    /// The sender's signature. `v` is the recovery id plus 27, whatever the
+    /// transaction type
+    pub signature: Signature,
+    /// The sender
+    pub from: Address,
+    /// The transaction hash, the hash of its raw encoding
+    pub hash: B256,
+}
+
+impl SignedTransaction {
+    /// The raw encoding, as sent with `eth_sendRawTransaction`
+    pub fn rlp(&self) -> Vec<u8> {
+        self.tx.rlp_signed(&self.signature)
+    }
+}
+
+fn list_header(payload_length: usize) -> Header {
+    Header {
+        list: true,
+        payload_length,
+    }
+}
+
+fn list_length(payload_length: usize) -> usize {
+    list_header(payload_length).length() + payload_length
+}
+
+/// Decode an RLP list with `f`, which must consume the whole list payload
+fn decode_list<T>(
+    buf: &mut &[u8],
+    f: impl FnOnce(&mut &[u8]) -> Result<T, DecodeError>,
+) -> Result<T, DecodeError> {
+    let header = Header::decode(buf)?;
+    if !header.list {
+        return Err(DecodeError::UnexpectedString);
+    }
+    let (mut payload, rest) = buf.split_at(header.payload_length);
+    let decoded = f(&mut payload)?;
+    if !payload.is_empty() {
+        return Err(DecodeError::UnexpectedLength);
+    }
+    *buf = rest;
+    Ok(decoded)
+}
+
+/// The recipient is encoded as the empty string for contract creations
+fn to_length(to: &Option<Address>) -> usize {
+    to.as_ref().map_or(1, Encodable::length)
+}
+
+fn encode_to(to: &Option<Address>, out: &mut dyn BufMut) {
+    match to {
+        Some(to) => to.encode(out),
+        None => out.put_u8(EMPTY_STRING_CODE),
+    }
+}
+
+fn decode_to(buf: &mut &[u8]) -> Result<Option<Address>, DecodeError> {
+    if buf.first() == Some(&EMPTY_STRING_CODE) {
+        *buf = &buf[1..];
+        return Ok(None);
+    }
+    Address::decode(buf).map(Some)
+}
+
+#[cfg(test)]
+mod test {
+    use ethers_pub_use::hex;
+    use k256::ecdsa::SigningKey;
+
+    use super::*;
+
+    fn encoded(tx: &TypedTransaction) -> Vec<u8> {
+        let mut out = vec![];
+        tx.encode(&mut out);
+        assert_eq!(out.len(), tx.length());
+        out
+    }
+
+    fn round_trip(tx: TypedTransaction) -> Vec<u8> {
+        let out = encoded(&tx);
+        let mut buf = &out[..];
+        assert_eq!(TypedTransaction::decode(&mut buf).unwrap(), tx);
+        assert!(buf.is_empty());
+        out
+    }
+
+    #[test]
+    fn it_encodes_eip155_signing_payloads() {
+        // the example from EIP-155
+        let tx = LegacyTransaction {
+            chain_id: Some(1),
+            nonce: 9,
+            gas_price: U256::from(20_000_000_000u64),
+            gas_limit: 21000,
+            to: Some(Address::repeat_byte(0x35)),
+            value: U256::from(10u64.pow(18)),
+            input: Default::default(),
+        };
+        let out = round_trip(tx.into());
+        assert_eq!(
+            hex::encode(&out),
+            "ec098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a764000080018080"
+        );
+        assert_eq!(
+            keccak256(&out),
+            "0xdaf5a779ae972f972197303d7b574746c7ef83eadac0f2791ad23db92e4c8e53"
+                .parse::<B256>()
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn it_round_trips_typed_transactions() {
+        round_trip(
+            LegacyTransaction {
+                input: vec![0xde, 0xad].into(),
+                ..Default::default()
+            }
+            .into(),
+        );
+
+        let access_list = AccessList(vec![AccessListItem {
+            address: Address::repeat_byte(0x11),
+            storage_keys: vec![B256::repeat_byte(0x22), B256::zero()],
+        }]);
+        let out = round_trip(
+            Eip2930Transaction {
+                chain_id: 1,
+                gas_price: U256::from(1),
+                to: Some(Address::repeat_byte(0x33)),
+                access_list: access_list.clone(),
+                ..Default::default()
+            }
+            .into(),
+        );
+        assert_eq!(out[0], 1);
+
+        let out = round_trip(
+            Eip1559Transaction {
+                chain_id: 5,
+                nonce: 1,
+                max_priority_fee_per_gas: U256::from(2_000_000_000u64),
+                max_fee_per_gas: U256::MAX,
+                gas_limit: 1_000_000,
+                input: vec![0x60; 100].into(),
+                access_list,
+                ..Default::default()
+            }
+            .into(),
+        );
+        assert_eq!(out[0], 2);
+
+        let out = round_trip(
+            Eip4844Transaction {
+                chain_id: 1,
+                max_fee_per_gas: U256::from(1),
+                to: Address::repeat_byte(0x44),
+                max_fee_per_blob_gas: U256::from(1),
+                blob_versioned_hashes: vec![B256::repeat_byte(0x01)],
+                ..Default::default()
+            }
+            .into(),
+        );
+        assert_eq!(out[0], 3);
+    }
+
+    #[test]
+    fn it_decodes_eip155_raw_transactions() {
+        // the example from EIP-155
+        let raw = hex::decode(
+            "f86c098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a7640000\
+             8025a028ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276a067cbe9d8997f\
+             761aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83",
+        )
+        .unwrap();
+        let signed = TypedTransaction::decode_raw(&raw).unwrap();
+        assert_eq!(signed.tx.chain_id(), Some(1));
+        assert_eq!(
+            signed.from,
+            Address::from_slice(&hex::decode("9d8a62f656a8d1615c1294fd71e9cfb3e4855a4f").unwrap())
+        );
+        assert_eq!(signed.hash, keccak256(&raw));
+        assert_eq!(signed.rlp(), raw);
+    }
+
+    fn sign(key: &SigningKey, tx: &TypedTransaction) -> Signature {
+        key.sign_prehash_recoverable(tx.signature_hash().as_bytes())
+            .unwrap()
+            .into()
+    }
+
+    #[test]
+    fn it_round_trips_signed_transactions() {
+        let key = SigningKey::from_slice(&[0x42; 32]).unwrap();
+        let from = ethers_primitives::public_key_address(key.verifying_key());
+        let access_list = AccessList(vec![AccessListItem {
+            address: Address::repeat_byte(0x11),
+            storage_keys: vec![B256::repeat_byte(0x22)],
+        }]);
+        let txs: Vec<TypedTransaction> = vec![
+            LegacyTransaction {
+                nonce: 1,
+                gas_price: U256::from(1),
+                gas_limit: 21000,
+                ..Default::default()
+            }
+            .into(),
+            LegacyTransaction {
+                chain_id: Some(1337),
+                to: Some(Address::repeat_byte(0x33)),
+                value: U256::from(1),
+                ..Default::default()
+            }
+            .into(),
+            Eip2930Transaction {
+                chain_id: 1,
+                access_list: access_list.clone(),
+                ..Default::default()
+            }
+            .into(),
+            Eip1559Transaction {
+                chain_id: 10,
+                max_fee_per_gas: U256::from(100),
+                input: vec![1, 2, 3].into(),
+                access_list,
+                ..Default::default()
+            }
+            .into(),
+            Eip4844Transaction {
+                chain_id: 1,
+                to: Address::repeat_byte(0x44),
+                blob_versioned_hashes: vec![B256::repeat_byte(0x01); 2],
+                ..Default::default()
+            }
+            .into(),
+        ];
+
+        for tx in txs {
+            let signature = sign(&key, &tx);
+            let raw = tx.rlp_signed(&signature);
+            let signed = TypedTransaction::decode_raw(&raw).unwrap();
+            assert_eq!(signed.tx, tx);
+            assert_eq!(signed.signature, signature);
+            assert_eq!(signed.from, from);
+            assert_eq!(signed.rlp(), raw);
+        }
+    }
+
+    #[test]
+    fn it_rejects_invalid_raw_transactions() {
+        assert_eq!(
+            TypedTransaction::decode_raw(&[0x04, 0xc0]),
+            Err(DecodeError::Custom("unsupported transaction type"))
+        );
+        assert_eq!(
+            TypedTransaction::decode_raw(&[0x02, 0xc1, 0x80]),
+            Err(DecodeError::InputTooShort)
+        );
+
+        let tx: TypedTransaction = Eip1559Transaction::default().into();
+        let mut signature = sign(&SigningKey::from_slice(&[0x42; 32]).unwrap(), &tx);
+        signature.v = 29;
+        assert_eq!(
+            TypedTransaction::decode_raw(&tx.rlp_signed(&signature)),
+            Err(DecodeError::Custom("invalid signature"))
+        );
+    }
+
+    #[test]
+    fn it_rejects_invalid_envelopes() {
+        assert_eq!(
+            TypedTransaction::decode(&mut &[][..]),
+            Err(DecodeError::InputTooShort)
+        );
+        assert_eq!(
+            TypedTransaction::decode(&mut &[0x04, 0xc0][..]),
+            Err(DecodeError::Custom("unsupported transaction type"))
+        );
+        // EIP-1559 transaction with a trailing field
+        let mut out = encoded(&Eip1559Transaction::default().into());
+        out[1] += 1;
+        out.push(0x80);
+        assert_eq!(
+            TypedTransaction::decode(&mut &out[..]),
+            Err(DecodeError::UnexpectedLength)
+        );
+    }
+}
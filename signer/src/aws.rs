@@ -0,0 +1,91 @@
+//! Signing with secp256k1 keys held in AWS KMS.
+//!
+//! The KMS key must have the `ECC_SECG_P256K1` key spec and the
+//! `SIGN_VERIFY` key usage.
+
+use aws_sdk_kms::{
+    primitives::Blob,
+    types::{MessageType, SigningAlgorithmSpec},
+    Client,
+};
+use ethers_primitives::B256;
+use ethers_pub_use::{async_trait::async_trait, thiserror};
+
+use crate::{DigestSigner, RemoteSigner, RemoteSignerError};
+
+/// AWS KMS errors
+#[derive(Debug, thiserror::Error)]
+pub enum AwsSignerError {
+    /// The KMS request failed
+    #[error(transparent)]
+    Kms(#[from] aws_sdk_kms::Error),
+    /// The KMS response did not include a field
+    #[error("KMS response is missing the {0}")]
+    MissingField(&'static str),
+}
+
+/// A KMS key, used as a [`DigestSigner`]
+#[derive(Debug, Clone)]
+pub struct AwsSigner {
+    client: Client,
+    key_id: String,
+}
+
+impl AwsSigner {
+    /// Instantiate a signer for a KMS key. The key's public key is fetched
+    /// from KMS
+    pub async fn connect(
+        client: Client,
+        key_id: impl Into<String>,
+    ) -> Result<RemoteSigner<Self>, RemoteSignerError> {
+        let signer = Self {
+            client,
+            key_id: key_id.into(),
+        };
+        let public_key = signer
+            .public_key()
+            .await
+            .map_err(|err| RemoteSignerError::Service(Box::new(err)))?;
+        RemoteSigner::new(signer, &public_key)
+    }
+
+    /// The KMS key id
+    pub fn key_id(&self) -> &str {
+        &self.key_id
+    }
+
+    /// Fetch the DER-encoded public key of the KMS key
+    pub async fn public_key(&self) -> Result<Vec<u8>, AwsSignerError> {
+        let resp = self
+            .client
+            .get_public_key()
+            .key_id(&self.key_id)
+            .send()
+            .await
+            .map_err(aws_sdk_kms::Error::from)?;
+        resp.public_key()
+            .map(|key| key.as_ref().to_vec())
+            .ok_or(AwsSignerError::MissingField("public key"))
+    }
+}
+
+#[async_trait]
+impl DigestSigner for AwsSigner {
+    type Error = AwsSignerError;
+
+    async fn sign_digest(&self, digest: B256) -> Result<Vec<u8>, AwsSignerError> {
+        let resp = self
+            .client
+            .sign()
+            .key_id(&self.key_id)
+            .message(Blob::new(digest.as_bytes()))
+            .message_type(MessageType::Digest)
+            .signing_algorithm(SigningAlgorithmSpec::EcdsaSha256)
+            .send()
+            .await
+            .map_err(aws_sdk_kms::Error::from)?;
+        resp.signature()
+            .map(|sig| sig.as_ref().to_vec())
+            .ok_or(AwsSignerError::MissingField("signature"))
+    }
+}
@@ -0,0 +1,550 @@
+//! Encrypted JSON keystores, as specified by [Web3 Secret Storage].
+//!
+//! [Web3 Secret Storage]: https://ethereum.org/en/developers/docs/data-structures-and-encoding/web3-secret-storage/
+
+use std::{fs, path::Path};
+
+use aes::cipher::{KeyIvInit, StreamCipher};
+use ethers_primitives::{keccak256, Address};
+use ethers_pub_use::{hex, serde_json, thiserror};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use subtle::ConstantTimeEq;
+use zeroize::Zeroizing;
+
+type Aes128Ctr = ctr::Ctr128BE<aes::Aes128>;
+
+/// The largest scrypt memory cost accepted when decrypting, in bytes.
+/// Larger parameters in an untrusted file could exhaust memory
+const MAX_SCRYPT_MEMORY: u64 = 1 << 30;
+
+/// Keystore errors
+#[derive(Debug, thiserror::Error)]
+pub enum KeystoreError {
+    /// The MAC does not match. Usually a wrong password
+    #[error("MAC mismatch. Wrong password?")]
+    MacMismatch,
+    /// Unsupported version, cipher or KDF
+    #[error("unsupported keystore: {0}")]
+    Unsupported(String),
+    /// Invalid KDF or cipher parameters
+    #[error("invalid keystore parameters: {0}")]
+    InvalidParams(&'static str),
+    /// The file is not a valid keystore
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    /// The file could not be read or written
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    /// The system random number generator failed
+    #[error("random number generator failed: {0}")]
+    Rng(getrandom::Error),
+}
+
+/// Hex serde without a `0x` prefix, as used by keystores
+mod hex_bytes {
+    use ethers_pub_use::hex;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub(super) fn serialize<S: Serializer>(bytes: &[u8], s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_str(&hex::encode(bytes))
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Vec<u8>, D::Error> {
+        let s = String::deserialize(d)?;
+        hex::decode(s.strip_prefix("0x").unwrap_or(&s)).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Key derivation parameters
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kdf", content = "kdfparams", rename_all = "lowercase")]
+pub enum KdfParams {
+    /// scrypt
+    Scrypt {
+        /// Derived key length
+        dklen: u32,
+        /// CPU/memory cost. A power of 2
+        n: u32,
+        /// Block size. `n` must be less than `2^(16 r)`, per RFC 7914
+        r: u32,
+        /// Parallelism
+        p: u32,
+        /// Salt
+        #[serde(with = "hex_bytes")]
+        salt: Vec<u8>,
+    },
+    /// PBKDF2 with HMAC-SHA256
+    Pbkdf2 {
+        /// Derived key length
+        dklen: u32,
+        /// Iterations
+        c: u32,
+        /// Pseudo-random function. Only `hmac-sha256` is supported
+        prf: String,
+        /// Salt
+        #[serde(with = "hex_bytes")]
+        salt: Vec<u8>,
+    },
+}
+
+impl KdfParams {
+    /// Derive the key. It is zeroized when dropped
+    fn derive(&self, password: &[u8]) -> Result<Zeroizing<Vec<u8>>, KeystoreError> {
+        match self {
+            KdfParams::Scrypt {
+                dklen,
+                n,
+                r,
+                p,
+                salt,
+            } => {
+                if *n < 2 || !n.is_power_of_two() || *r == 0 || *p == 0 {
+                    return Err(KeystoreError::InvalidParams("scrypt n, r or p"));
+                }
+                if 128 * *r as u64 * *n as u64 > MAX_SCRYPT_MEMORY {
+                    return Err(KeystoreError::InvalidParams("scrypt memory cost"));
+                }
+                let params = scrypt::Params::new(n.trailing_zeros() as u8, *r, *p, 32)
+                    .map_err(|_| KeystoreError::InvalidParams("scrypt n, r or p"))?;
+                let mut key = Zeroizing::new(vec![0; check_dklen(*dklen)?]);
+                scrypt::scrypt(password, salt, &params, &mut key)
+                    .map_err(|_| KeystoreError::InvalidParams("dklen"))?;
+                Ok(key)
+            }
+            KdfParams::Pbkdf2 {
+                dklen,
+                c,
+                prf,
+                salt,
+            } => {
+                if prf != "hmac-sha256" {
+                    return Err(KeystoreError::Unsupported(format!("prf {prf}")));
+                }
+                if *c == 0 {
+                    return Err(KeystoreError::InvalidParams("pbkdf2 c"));
+                }
+                let mut key = Zeroizing::new(vec![0; check_dklen(*dklen)?]);
+                pbkdf2::pbkdf2_hmac::<Sha256>(password, salt, *c, &mut key);
+                Ok(key)
+            }
+        }
+    }
+}
+
+/// The key must cover the AES key and the MAC key
+fn check_dklen(dklen: u32) -> Result<usize, KeystoreError> {
+    if !(32..=1024).contains(&dklen) {
+        return Err(KeystoreError::InvalidParams("dklen"));
+    }
+    Ok(dklen as usize)
+}
+
+/// The key derivation function used to encrypt a keystore
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Kdf {
+    /// scrypt with `n = 2^log_n`
+    Scrypt {
+        /// Log2 of the CPU/memory cost
+        log_n: u8,
+        /// Block size
+        r: u32,
+        /// Parallelism
+        p: u32,
+    },
+    /// PBKDF2 with HMAC-SHA256
+    Pbkdf2 {
+        /// Iterations
+        c: u32,
+    },
+}
+
+impl Default for Kdf {
+    /// scrypt with `n = 2^13`, `r = 8`, `p = 1`
+    fn default() -> Self {
+        Kdf::Scrypt {
+            log_n: 13,
+            r: 8,
+            p: 1,
+        }
+    }
+}
+
+/// Cipher parameters
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CipherParams {
+    /// The AES-128-CTR initial counter
+    #[serde(with = "hex_bytes")]
+    pub iv: Vec<u8>,
+}
+
+/// The encrypted key
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CryptoJson {
+    /// The cipher. Only `aes-128-ctr` is supported
+    pub cipher: String,
+    /// The cipher parameters
+    pub cipherparams: CipherParams,
+    /// The encrypted key
+    #[serde(with = "hex_bytes")]
+    pub ciphertext: Vec<u8>,
+    /// The key derivation function and its parameters
+    #[serde(flatten)]
+    pub kdf: KdfParams,
+    /// `keccak256(derived_key[16..32] ++ ciphertext)`
+    #[serde(with = "hex_bytes")]
+    pub mac: Vec<u8>,
+}
+
+/// A version 3 encrypted JSON keystore
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Keystore {
+    /// The encrypted key
+    #[serde(alias = "Crypto")]
+    pub crypto: CryptoJson,
+    /// The keystore UUID
+    pub id: String,
+    /// The keystore version. Always 3
+    pub version: u8,
+    /// The address of the key, if known. Not authenticated by the MAC
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub address: Option<Address>,
+}
+
+fn random<const N: usize>() -> Result<[u8; N], KeystoreError> {
+    let mut bytes = [0u8; N];
+    getrandom::getrandom(&mut bytes).map_err(KeystoreError::Rng)?;
+    Ok(bytes)
+}
+
+/// A random version 4 UUID
+fn uuid_v4() -> Result<String, KeystoreError> {
+    let mut bytes: [u8; 16] = random()?;
+    bytes[6] = (bytes[6] & 0x0f) | 0x40;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+    let hex = hex::encode(bytes);
+    Ok(format!(
+        "{}-{}-{}-{}-{}",
+        &hex[..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..]
+    ))
+}
+
+fn mac(derived_key: &[u8], ciphertext: &[u8]) -> [u8; 32] {
+    let mut preimage = Zeroizing::new(derived_key[16..32].to_vec());
+    preimage.extend_from_slice(ciphertext);
+    keccak256(&*preimage).0
+}
+
+/// Encrypt or decrypt with the first 16 bytes of the derived key
+fn aes_128_ctr(derived_key: &[u8], iv: &[u8; 16], data: &mut [u8]) {
+    Aes128Ctr::new(derived_key[..16].into(), iv.into()).apply_keystream(data);
+}
+
+impl Keystore {
+    /// Encrypt a secret key with a password, using a random salt, IV and
+    /// id
+    pub fn encrypt(
+        secret: &[u8],
+        password: impl AsRef<[u8]>,
+        kdf: Kdf,
+    ) -> Result<Self, KeystoreError> {
+        let salt: [u8; 32] = random()?;
+        let kdf = match kdf {
+            Kdf::Scrypt { log_n, r, p } => KdfParams::Scrypt {
+                dklen: 32,
+                n: 1u32
+                    .checked_shl(log_n.into())
+                    .ok_or(KeystoreError::InvalidParams("scrypt log_n"))?,
+                r,
+                p,
+                salt: salt.to_vec(),
+            },
+            Kdf::Pbkdf2 { c } => KdfParams::Pbkdf2 {
+                dklen: 32,
+                c,
+                prf: "hmac-sha256".to_owned(),
+                salt: salt.to_vec(),
+            },
+        };
+        let key = kdf.derive(password.as_ref())?;
+
+        let iv: [u8; 16] = random()?;
+        let mut ciphertext = secret.to_vec();
+        aes_128_ctr(&key, &iv, &mut ciphertext);
+
+        Ok(Self {
+            crypto: CryptoJson {
+                cipher: "aes-128-ctr".to_owned(),
+                cipherparams: CipherParams { iv: iv.to_vec() },
+                mac: mac(&key, &ciphertext).to_vec(),
+                ciphertext,
+                kdf,
+            },
+            id: uuid_v4()?,
+            version: 3,
+            address: None,
+        })
+    }
+
+    /// Set the address of the key
+    #[must_use = "Builder method outputs must be used"]
+    pub fn with_address(mut self, address: Address) -> Self {
+        self.address = Some(address);
+        self
+    }
+
+    /// Write the keystore to a file in `dir`, named `name` or the keystore id
+    pub fn write(&self, dir: impl AsRef<Path>, name: Option<&str>) -> Result<(), KeystoreError> {
+        let path = dir.as_ref().join(name.unwrap_or(&self.id));
+        fs::write(path, serde_json::to_vec(self)?)?;
+        Ok(())
+    }
+
+    /// Decrypt the secret key. Fails with [`KeystoreError::MacMismatch`] if
+    /// the password is wrong
+    pub fn decrypt(&self, password: impl AsRef<[u8]>) -> Result<Vec<u8>, KeystoreError> {
+        if self.version != 3 {
+            return Err(KeystoreError::Unsupported(format!(
+                "version {}",
+                self.version
+            )));
+        }
+        let crypto = &self.crypto;
+        if crypto.cipher != "aes-128-ctr" {
+            return Err(KeystoreError::Unsupported(format!(
+                "cipher {}",
+                crypto.cipher
+            )));
+        }
+        let iv: &[u8; 16] = crypto.cipherparams.iv[..]
+            .try_into()
+            .map_err(|_| KeystoreError::InvalidParams("iv"))?;
+
+        let key = crypto.kdf.derive(password.as_ref())?;
+        // constant time, so the comparison does not leak how much of a
+        // forged MAC is correct
+        if !bool::from(mac(&key, &crypto.ciphertext)[..].ct_eq(&crypto.mac)) {
+            return Err(KeystoreError::MacMismatch);
+        }
+
+        let mut secret = crypto.ciphertext.clone();
+        aes_128_ctr(&key, iv, &mut secret);
+        Ok(secret)
+    }
+}
+
+/// Decrypt the secret key in a keystore file
+pub fn decrypt_keystore(
+    path: impl AsRef<Path>,
+    password: impl AsRef<[u8]>,
+) -> Result<Vec<u8>, KeystoreError> {
+    let keystore: Keystore = serde_json::from_slice(&fs::read(path)?)?;
+    keystore.decrypt(password)
+}
+
+/// Encrypt a secret key with the default KDF, and write it to a file in
+/// `dir`. The file is named `name`, or the keystore id. Returns the keystore
+pub fn encrypt_keystore(
+    dir: impl AsRef<Path>,
+    secret: &[u8],
+    password: impl AsRef<[u8]>,
+    name: Option<&str>,
+) -> Result<Keystore, KeystoreError> {
+    let keystore = Keystore::encrypt(secret, password, Kdf::default())?;
+    keystore.write(dir, name)?;
+    Ok(keystore)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const SECRET: &str = "7a28b5ba57c53603b0b07b56bba752f7784bf506fa95edc395f5cf6c7514fe9d";
+
+    // test vectors from the Web3 Secret Storage definition
+    const PBKDF2_KEYSTORE: &str = r#"{
+        "crypto" : {
+            "cipher" : "aes-128-ctr",
+            "cipherparams" : { "iv" : "6087dab2f9fdbbfaddc31a909735c1e6" },
+            "ciphertext" : "5318b4d5bcd28de64ee5559e671353e16f075ecae9f99c7a79a38af5f869aa46",
+            "kdf" : "pbkdf2",
+            "kdfparams" : {
+                "c" : 262144,
+                "dklen" : 32,
+                "prf" : "hmac-sha256",
+                "salt" : "ae3cd4e7013836a3df6bd7241b12db061dbe2c6785853cce422d148a624ce0bd"
+            },
+            "mac" : "517ead924a9d0dc3124507e3393d175ce3ff7c1e96529c6c555ce9e51205e9b2"
+        },
+        "id" : "3198bc9c-6672-5ab3-d995-4942343ae5b6",
+        "version" : 3
+    }"#;
+
+    const SCRYPT_KEYSTORE: &str = r#"{
+        "crypto" : {
+            "cipher" : "aes-128-ctr",
+            "cipherparams" : { "iv" : "83dbcc02d8ccb40e466191a123791e0e" },
+            "ciphertext" : "d172bf743a674da9cdad04534d56926ef8358534d458fffccd4e6ad2fbde479c",
+            "kdf" : "scrypt",
+            "kdfparams" : {
+                "dklen" : 32,
+                "n" : 262144,
+                "p" : 8,
+                "r" : 1,
+                "salt" : "ab0c7876052600dd703518d6fc3fe8984592145b591fc8fb5c6d43190334ba19"
+            },
+            "mac" : "2103ac29920d71da29f15d75b4a16dbe95cfd7ff8faea1056c33131d846e3097"
+        },
+        "id" : "3198bc9c-6672-5ab3-d995-4942343ae5b6",
+        "version" : 3
+    }"#;
+
+    /// The spec vectors with cheaper KDF parameters, and the resulting
+    /// ciphertexts and MACs
+    fn cheap_test_vectors() -> [Keystore; 2] {
+        let mut pbkdf2: Keystore = serde_json::from_str(PBKDF2_KEYSTORE).unwrap();
+        let KdfParams::Pbkdf2 { c, .. } = &mut pbkdf2.crypto.kdf else {
+            unreachable!()
+        };
+        *c = 1024;
+        pbkdf2.crypto.ciphertext =
+            hex::decode("222e3de58e99185cba113c17eb5f7dda1cc32d677c070b033fb5b09731be6512")
+                .unwrap();
+        pbkdf2.crypto.mac =
+            hex::decode("8ee796d41a19c45aeeb40f06b3b691494397173041d116c0b01e1b547a73bb7d")
+                .unwrap();
+
+        let mut scrypt = pbkdf2.clone();
+        scrypt.crypto.kdf = KdfParams::Scrypt {
+            dklen: 32,
+            n: 1024,
+            r: 8,
+            p: 1,
+            salt: hex::decode("ae3cd4e7013836a3df6bd7241b12db061dbe2c6785853cce422d148a624ce0bd")
+                .unwrap(),
+        };
+        scrypt.crypto.ciphertext =
+            hex::decode("de3f5962b67bda23a1e56e19fbc7900e7eecb4ed4f77e5eb3ea853126ec00ec7")
+                .unwrap();
+        scrypt.crypto.mac =
+            hex::decode("dbb4c0f18597a22886f50d7c175fc7b92e3fa0f4a24eea91203c4c0b8540a461")
+                .unwrap();
+
+        [pbkdf2, scrypt]
+    }
+
+    #[test]
+    fn it_decrypts_test_vectors() {
+        for keystore in cheap_test_vectors() {
+            let secret = keystore.decrypt("testpassword").unwrap();
+            assert_eq!(hex::encode(secret), SECRET);
+            assert!(matches!(
+                keystore.decrypt("wrongpassword"),
+                Err(KeystoreError::MacMismatch)
+            ));
+        }
+    }
+
+    #[test]
+    fn it_decrypts_spec_test_vectors() {
+        let keystore: Keystore = serde_json::from_str(PBKDF2_KEYSTORE).unwrap();
+        let secret = keystore.decrypt("testpassword").unwrap();
+        assert_eq!(hex::encode(secret), SECRET);
+
+        // `n = 2^18` with `r = 1` breaks the RFC 7914 bound `n < 2^(16 r)`
+        let keystore: Keystore = serde_json::from_str(SCRYPT_KEYSTORE).unwrap();
+        assert!(matches!(
+            keystore.decrypt("testpassword"),
+            Err(KeystoreError::InvalidParams("scrypt n, r or p"))
+        ));
+    }
+
+    #[test]
+    fn it_rejects_truncated_macs() {
+        let [mut keystore, _] = cheap_test_vectors();
+        keystore.crypto.mac.truncate(16);
+        assert!(matches!(
+            keystore.decrypt("testpassword"),
+            Err(KeystoreError::MacMismatch)
+        ));
+    }
+
+    #[test]
+    fn it_parses_test_vectors() {
+        let keystore: Keystore = serde_json::from_str(SCRYPT_KEYSTORE).unwrap();
+        assert!(matches!(
+            keystore.crypto.kdf,
+            KdfParams::Scrypt {
+                n: 262144,
+                r: 1,
+                p: 8,
+                ..
+            }
+        ));
+
+        let keystore: Keystore = serde_json::from_str(PBKDF2_KEYSTORE).unwrap();
+        assert!(matches!(
+            keystore.crypto.kdf,
+            KdfParams::Pbkdf2 { c: 262144, .. }
+        ));
+        let round_trip: Keystore =
+            serde_json::from_value(serde_json::to_value(&keystore).unwrap()).unwrap();
+        assert_eq!(round_trip, keystore);
+    }
+
+    #[test]
+    fn it_round_trips_keystores() {
+        let secret = hex::decode(SECRET).unwrap();
+        for kdf in [
+            Kdf::Scrypt {
+                log_n: 4,
+                r: 8,
+                p: 1,
+            },
+            Kdf::Pbkdf2 { c: 16 },
+        ] {
+            let keystore = Keystore::encrypt(&secret, "password", kdf).unwrap();
+            assert_eq!(keystore.decrypt("password").unwrap(), secret);
+            assert!(matches!(
+                keystore.decrypt("wrong"),
+                Err(KeystoreError::MacMismatch)
+            ));
+        }
+    }
+
+    #[test]
+    fn it_writes_keystore_files() {
+        let dir = std::env::temp_dir().join(format!("ethers-keystore-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let secret = hex::decode(SECRET).unwrap();
+
+        let keystore = encrypt_keystore(&dir, &secret, "password", None).unwrap();
+        let path = dir.join(&keystore.id);
+        assert_eq!(decrypt_keystore(&path, "password").unwrap(), secret);
+
+        let id = keystore.id;
+        assert_eq!(id.len(), 36);
+        assert_eq!(&id[14..15], "4");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn it_rejects_expensive_params() {
+        let mut keystore: Keystore = serde_json::from_str(SCRYPT_KEYSTORE).unwrap();
+        keystore.crypto.kdf = KdfParams::Scrypt {
+            dklen: 32,
+            n: 1 << 30,
+            r: 8,
+            p: 1,
+            salt: vec![],
+        };
+        assert!(matches!(
+            keystore.decrypt("testpassword"),
+            Err(KeystoreError::InvalidParams(_))
+        ));
+    }
+}
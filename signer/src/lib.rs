@@ -0,0 +1,32 @@
+#![warn(
+    missing_debug_implementations,
+    missing_docs,
+    unreachable_pub,
+    unused_crate_dependencies
+)]
+#![deny(unused_must_use, rust_2018_idioms)]
+#![doc(test(
+    no_crate_inject,
+    attr(deny(warnings, rust_2018_idioms), allow(dead_code, unused_variables))
+))]
+
+//! Key management and signing
+
+#[cfg(feature = "aws")]
+pub mod aws;
+
+pub mod keystore;
+pub use keystore::{decrypt_keystore, encrypt_keystore, Kdf, Keystore, KeystoreError};
+
+pub mod remote;
+pub use remote::{DigestSigner, RemoteSigner, RemoteSignerError};
+
+mod signature;
+pub use ethers_primitives::{public_key_address, Signature};
+pub use signature::hash_message;
+
+mod signer;
+pub use signer::Signer;
+
+pub mod wallet;
+pub use wallet::{LocalWallet, WalletError};
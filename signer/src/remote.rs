@@ -0,0 +1,199 @@
+//! Signers whose keys are held by an external service, such as a KMS or an
+//! HSM.
+//!
+//! Such services sign a 32-byte digest and return a DER-encoded ECDSA
+//! signature, without a recovery id. [`RemoteSigner`] normalizes the
+//! signature and computes the recovery id from the signer's public key.
+
+use std::{error::Error, future::Future};
+
+use ethers_primitives::{Address, B256};
+use ethers_pub_use::{async_trait::async_trait, thiserror};
+use k256::{
+    ecdsa::{RecoveryId, VerifyingKey},
+    pkcs8::DecodePublicKey,
+};
+
+use crate::{public_key_address, Signature, Signer};
+
+/// Remote signer errors
+#[derive(Debug, thiserror::Error)]
+pub enum RemoteSignerError {
+    /// The signing service failed
+    #[error("signing service error: {0}")]
+    Service(Box<dyn Error + Send + Sync>),
+    /// The public key is not a secp256k1 key in SEC1 or SPKI DER form
+    #[error("invalid public key")]
+    InvalidPublicKey,
+    /// The service returned a malformed signature
+    #[error("invalid DER signature")]
+    InvalidSignature,
+    /// The signature was not made by the signer's key
+    #[error("signature does not match the public key")]
+    KeyMismatch,
+}
+
+/// A service that signs digests with a secp256k1 key it holds
+#[async_trait]
+pub trait DigestSigner: Send + Sync {
+    /// Service errors
+    type Error: Error + Send + Sync + 'static;
+
+    /// Sign a 32-byte digest, without hashing it. Returns a DER-encoded
+    /// ECDSA signature
+    async fn sign_digest(&self, digest: B256) -> Result<Vec<u8>, Self::Error>;
+}
+
+#[async_trait]
+impl<F, Fut, E> DigestSigner for F
+where
+    F: Fn(B256) -> Fut + Send + Sync,
+    Fut: Future<Output = Result<Vec<u8>, E>> + Send,
+    E: Error + Send + Sync + 'static,
+{
+    type Error = E;
+
+    async fn sign_digest(&self, digest: B256) -> Result<Vec<u8>, E> {
+        self(digest).await
+    }
+}
+
+/// A signer that delegates signing to a [`DigestSigner`]
+#[derive(Debug, Clone)]
+pub struct RemoteSigner<S> {
+    inner: S,
+    public_key: VerifyingKey,
+    address: Address,
+}
+
+impl<S> RemoteSigner<S>
+where
+    S: DigestSigner,
+{
+    /// Instantiate a signer from a service and its public key, either SEC1
+    /// encoded or a DER `SubjectPublicKeyInfo`, as returned by KMS
+    pub fn new(inner: S, public_key: &[u8]) -> Result<Self, RemoteSignerError> {
+        let public_key = VerifyingKey::from_sec1_bytes(public_key)
+            .or_else(|_| VerifyingKey::from_public_key_der(public_key))
+            .map_err(|_| RemoteSignerError::InvalidPublicKey)?;
+        Ok(Self {
+            inner,
+            address: public_key_address(&public_key),
+            public_key,
+        })
+    }
+
+    /// The signing service
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    /// The signer's public key
+    pub fn public_key(&self) -> &VerifyingKey {
+        &self.public_key
+    }
+
+    /// Normalize a DER signature to low-s, and find the recovery id that
+    /// yields the signer's key
+    fn to_recoverable(&self, digest: B256, der: &[u8]) -> Result<Signature, RemoteSignerError> {
+        let sig = k256::ecdsa::Signature::from_der(der)
+            .map_err(|_| RemoteSignerError::InvalidSignature)?;
+        let sig = sig.normalize_s().unwrap_or(sig);
+
+        let recid = (0..2)
+            .filter_map(RecoveryId::from_byte)
+            .find(|recid| {
+                VerifyingKey::recover_from_prehash(digest.as_bytes(), &sig, *recid)
+                    .is_ok_and(|key| key == self.public_key)
+            })
+            .ok_or(RemoteSignerError::KeyMismatch)?;
+
+        Ok((sig, recid).into())
+    }
+}
+
+#[async_trait]
+impl<S> Signer for RemoteSigner<S>
+where
+    S: DigestSigner,
+{
+    type Error = RemoteSignerError;
+
+    fn address(&self) -> Address {
+        self.address
+    }
+
+    async fn sign_hash(&self, digest: B256) -> Result<Signature, RemoteSignerError> {
+        let der = self
+            .inner
+            .sign_digest(digest)
+            .await
+            .map_err(|err| RemoteSignerError::Service(Box::new(err)))?;
+        self.to_recoverable(digest, &der)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::convert::Infallible;
+
+    use k256::ecdsa::{signature::hazmat::PrehashSigner, SigningKey};
+
+    use crate::hash_message;
+
+    use super::*;
+
+    fn key() -> SigningKey {
+        SigningKey::from_slice(&[0x42; 32]).unwrap()
+    }
+
+    #[tokio::test]
+    async fn it_signs_with_a_callback() {
+        let service = |digest: B256| async move {
+            let sig: k256::ecdsa::Signature = key().sign_prehash(digest.as_bytes()).unwrap();
+            Ok::<_, Infallible>(sig.to_der().as_bytes().to_vec())
+        };
+        let public_key = key().verifying_key().to_encoded_point(true);
+        let signer = RemoteSigner::new(service, public_key.as_bytes()).unwrap();
+
+        let sig = signer.sign_message("hello").await.unwrap();
+        assert_eq!(sig.recover(hash_message("hello")), Some(signer.address()));
+    }
+
+    #[tokio::test]
+    async fn it_normalizes_high_s() {
+        let service = |digest: B256| async move {
+            let sig: k256::ecdsa::Signature = key().sign_prehash(digest.as_bytes()).unwrap();
+            let (r, s) = sig.split_scalars();
+            let high = k256::ecdsa::Signature::from_scalars(r, -*s).unwrap();
+            Ok::<_, Infallible>(high.to_der().as_bytes().to_vec())
+        };
+        let public_key = key().verifying_key().to_encoded_point(false);
+        let signer = RemoteSigner::new(service, public_key.as_bytes()).unwrap();
+
+        let digest = B256::repeat_byte(7);
+        let sig = signer.sign_hash(digest).await.unwrap();
+        assert!(!sig.s.bit(255));
+        assert_eq!(sig.recover(digest), Some(signer.address()));
+    }
+
+    #[tokio::test]
+    async fn it_rejects_foreign_signatures() {
+        let service = |digest: B256| async move {
+            let other = SigningKey::from_slice(&[0x43; 32]).unwrap();
+            let sig: k256::ecdsa::Signature = other.sign_prehash(digest.as_bytes()).unwrap();
+            Ok::<_, Infallible>(sig.to_der().as_bytes().to_vec())
+        };
+        let public_key = key().verifying_key().to_encoded_point(true);
+        let signer = RemoteSigner::new(service, public_key.as_bytes()).unwrap();
+        assert!(matches!(
+            signer.sign_hash(B256::zero()).await,
+            Err(RemoteSignerError::KeyMismatch)
+        ));
+
+        assert!(matches!(
+            RemoteSigner::new(service, &[4; 3]),
+            Err(RemoteSignerError::InvalidPublicKey)
+        ));
+    }
+}
@@ -0,0 +1,24 @@
+use ethers_primitives::{keccak256, B256};
+
+/// The digest signed by `personal_sign` and `eth_sign`, per EIP-191
+pub fn hash_message(message: impl AsRef<[u8]>) -> B256 {
+    let message = message.as_ref();
+    let mut data = format!("\x19Ethereum Signed Message:\n{}", message.len()).into_bytes();
+    data.extend_from_slice(message);
+    keccak256(data)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_hashes_messages() {
+        assert_eq!(
+            hash_message("Hello World"),
+            "0xa1de988600a42c4b4ab089b619297c17d53cffae5d5120d82d8a92d0bb3b78f2"
+                .parse::<B256>()
+                .unwrap()
+        );
+    }
+}
@@ -0,0 +1,28 @@
+use std::error::Error;
+
+use ethers_primitives::{Address, B256};
+use ethers_pub_use::async_trait::async_trait;
+
+use crate::{hash_message, Signature};
+
+/// A key that signs digests and messages on behalf of an address
+#[async_trait]
+pub trait Signer: Send + Sync {
+    /// Signing errors
+    type Error: Error + Send + Sync + 'static;
+
+    /// The signer's address
+    fn address(&self) -> Address;
+
+    /// Sign a 32-byte digest, without hashing it
+    async fn sign_hash(&self, digest: B256) -> Result<Signature, Self::Error>;
+
+    /// Sign a message with the EIP-191 prefix, as `personal_sign` does. See
+    /// [`hash_message`]
+    async fn sign_message<M>(&self, message: M) -> Result<Signature, Self::Error>
+    where
+        M: AsRef<[u8]> + Send + Sync,
+    {
+        self.sign_hash(hash_message(message)).await
+    }
+}
@@ -0,0 +1,160 @@
+//! A signer holding a secp256k1 key in memory.
+
+use std::path::Path;
+
+use ethers_primitives::{Address, B256};
+use ethers_pub_use::{async_trait::async_trait, thiserror};
+use k256::ecdsa::SigningKey;
+use zeroize::Zeroizing;
+
+use crate::{
+    keystore::{self, Kdf, Keystore, KeystoreError},
+    public_key_address, Signature, Signer,
+};
+
+/// Local wallet errors
+#[derive(Debug, thiserror::Error)]
+pub enum WalletError {
+    /// The keystore could not be read, written or decrypted
+    #[error(transparent)]
+    Keystore(#[from] KeystoreError),
+    /// The secret is not a valid secp256k1 scalar
+    #[error("invalid secret key")]
+    InvalidKey,
+    /// Signing failed
+    #[error(transparent)]
+    Ecdsa(#[from] k256::ecdsa::Error),
+}
+
+/// A signer whose key is held in memory. The key is zeroized when dropped
+#[derive(Clone)]
+pub struct LocalWallet {
+    key: SigningKey,
+    address: Address,
+}
+
+impl std::fmt::Debug for LocalWallet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LocalWallet")
+            .field("address", &self.address)
+            .finish_non_exhaustive()
+    }
+}
+
+impl From<SigningKey> for LocalWallet {
+    fn from(key: SigningKey) -> Self {
+        Self {
+            address: public_key_address(key.verifying_key()),
+            key,
+        }
+    }
+}
+
+impl LocalWallet {
+    /// Instantiate a wallet from a 32-byte secret key
+    pub fn from_slice(secret: &[u8]) -> Result<Self, WalletError> {
+        SigningKey::from_slice(secret)
+            .map(Self::from)
+            .map_err(|_| WalletError::InvalidKey)
+    }
+
+    /// The wallet's signing key
+    pub fn signing_key(&self) -> &SigningKey {
+        &self.key
+    }
+
+    /// Decrypt a keystore file. See [`keystore::decrypt_keystore`]
+    pub fn decrypt_keystore(
+        path: impl AsRef<Path>,
+        password: impl AsRef<[u8]>,
+    ) -> Result<Self, WalletError> {
+        let secret = Zeroizing::new(keystore::decrypt_keystore(path, password)?);
+        Self::from_slice(&secret)
+    }
+
+    /// Encrypt the key with `kdf`, and write it to a file in `dir`. The file
+    /// is named `name`, or the keystore id. Returns the keystore, with the
+    /// wallet's address set
+    pub fn encrypt_keystore(
+        &self,
+        dir: impl AsRef<Path>,
+        password: impl AsRef<[u8]>,
+        kdf: Kdf,
+        name: Option<&str>,
+    ) -> Result<Keystore, WalletError> {
+        let secret = Zeroizing::new(self.key.to_bytes());
+        let keystore = Keystore::encrypt(&secret, password, kdf)?.with_address(self.address);
+        keystore.write(dir, name)?;
+        Ok(keystore)
+    }
+}
+
+#[async_trait]
+impl Signer for LocalWallet {
+    type Error = WalletError;
+
+    fn address(&self) -> Address {
+        self.address
+    }
+
+    async fn sign_hash(&self, digest: B256) -> Result<Signature, WalletError> {
+        Ok(self.key.sign_prehash_recoverable(digest.as_bytes())?.into())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+
+    use ethers_pub_use::hex;
+
+    use super::*;
+    use crate::hash_message;
+
+    #[tokio::test]
+    async fn it_round_trips_keystore_files() {
+        let dir = std::env::temp_dir().join(format!("ethers-wallet-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        let wallet = LocalWallet::from_slice(&[0x42; 32]).unwrap();
+        let kdf = Kdf::Scrypt {
+            log_n: 4,
+            r: 8,
+            p: 1,
+        };
+        let keystore = wallet
+            .encrypt_keystore(&dir, "password", kdf, Some("key.json"))
+            .unwrap();
+        assert_eq!(keystore.address, Some(wallet.address()));
+
+        let path = dir.join("key.json");
+        let decrypted = LocalWallet::decrypt_keystore(&path, "password").unwrap();
+        assert_eq!(decrypted.address(), wallet.address());
+        assert!(matches!(
+            LocalWallet::decrypt_keystore(&path, "wrong"),
+            Err(WalletError::Keystore(KeystoreError::MacMismatch))
+        ));
+        fs::remove_dir_all(&dir).unwrap();
+
+        let sig = decrypted.sign_message("hello").await.unwrap();
+        assert_eq!(sig.recover(hash_message("hello")), Some(wallet.address()));
+    }
+
+    #[test]
+    fn it_derives_addresses() {
+        // the first anvil/hardhat dev account
+        let secret =
+            hex::decode("ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80")
+                .unwrap();
+        let wallet = LocalWallet::from_slice(&secret).unwrap();
+        assert_eq!(
+            wallet.address().to_string(),
+            "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266"
+        );
+        assert!(format!("{wallet:?}").contains("0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266"));
+        assert!(matches!(
+            LocalWallet::from_slice(&[0; 32]),
+            Err(WalletError::InvalidKey)
+        ));
+    }
+}
@@ -0,0 +1,338 @@
+use std::{
+    path::PathBuf,
+    process::{Child, Command, Stdio},
+    time::Duration,
+};
+
+use ethers_primitives::{Address, B256};
+
+use crate::{unused_port, wait_for, NodeError, DEFAULT_STARTUP_TIMEOUT};
+
+/// Builder for an anvil node.
+///
+/// ```no_run
+/// use ethers_test_node::Anvil;
+///
+/// let anvil = Anvil::new().chain_id(1337).accounts(3).spawn();
+/// assert_eq!(anvil.addresses().len(), 3);
+/// println!("anvil listening on {}", anvil.endpoint());
+/// // anvil is killed here
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Anvil {
+    program: Option<PathBuf>,
+    port: Option<u16>,
+    chain_id: Option<u64>,
+    accounts: Option<usize>,
+    mnemonic: Option<String>,
+    block_time: Option<u64>,
+    fork: Option<String>,
+    args: Vec<String>,
+    timeout: Option<Duration>,
+}
+
+impl Anvil {
+    /// Instantiate a builder for the `anvil` on the `PATH`
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Use the `anvil` binary at a path
+    pub fn at(path: impl Into<PathBuf>) -> Self {
+        Self {
+            program: Some(path.into()),
+            ..Default::default()
+        }
+    }
+
+    /// Set the port. Defaults to an unused port
+    #[must_use = "Builder method outputs must be used"]
+    pub fn port(mut self, port: u16) -> Self {
+        self.port = Some(port);
+        self
+    }
+
+    /// Set the chain id. Defaults to anvil's default, 31337
+    #[must_use = "Builder method outputs must be used"]
+    pub fn chain_id(mut self, chain_id: u64) -> Self {
+        self.chain_id = Some(chain_id);
+        self
+    }
+
+    /// Set the number of dev accounts. Defaults to anvil's default, 10
+    #[must_use = "Builder method outputs must be used"]
+    pub fn accounts(mut self, accounts: usize) -> Self {
+        self.accounts = Some(accounts);
+        self
+    }
+
+    /// Set the mnemonic the dev accounts are derived from
+    #[must_use = "Builder method outputs must be used"]
+    pub fn mnemonic(mut self, mnemonic: impl Into<String>) -> Self {
+        self.mnemonic = Some(mnemonic.into());
+        self
+    }
+
+    /// Mine a block every `seconds`, instead of on every transaction
+    #[must_use = "Builder method outputs must be used"]
+    pub fn block_time(mut self, seconds: u64) -> Self {
+        self.block_time = Some(seconds);
+        self
+    }
+
+    /// Fork the chain served by a url
+    #[must_use = "Builder method outputs must be used"]
+    pub fn fork(mut self, url: impl Into<String>) -> Self {
+        self.fork = Some(url.into());
+        self
+    }
+
+    /// Pass an extra argument
+    #[must_use = "Builder method outputs must be used"]
+    pub fn arg(mut self, arg: impl Into<String>) -> Self {
+        self.args.push(arg.into());
+        self
+    }
+
+    /// Pass extra arguments
+    #[must_use = "Builder method outputs must be used"]
+    pub fn args<I, S>(mut self, args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.args.extend(args.into_iter().map(Into::into));
+        self
+    }
+
+    /// Set how long to wait for anvil to start. Forking may need longer
+    /// than the default
+    #[must_use = "Builder method outputs must be used"]
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Spawn anvil. Panics if it fails to start
+    pub fn spawn(self) -> AnvilInstance {
+        self.try_spawn().expect("could not start anvil")
+    }
+
+    /// Spawn anvil and wait until it is listening
+    pub fn try_spawn(self) -> Result<AnvilInstance, NodeError> {
+        let program = self.program.unwrap_or_else(|| "anvil".into());
+        let port = self.port.unwrap_or_else(unused_port);
+
+        let mut cmd = Command::new(&program);
+        cmd.stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .arg("--port")
+            .arg(port.to_string());
+        if let Some(chain_id) = self.chain_id {
+            cmd.arg("--chain-id").arg(chain_id.to_string());
+        }
+        if let Some(accounts) = self.accounts {
+            cmd.arg("--accounts").arg(accounts.to_string());
+        }
+        if let Some(mnemonic) = &self.mnemonic {
+            cmd.arg("--mnemonic").arg(mnemonic);
+        }
+        if let Some(block_time) = self.block_time {
+            cmd.arg("--block-time").arg(block_time.to_string());
+        }
+        if let Some(fork) = &self.fork {
+            cmd.arg("--fork-url").arg(fork);
+        }
+        cmd.args(&self.args);
+
+        let mut child = cmd.spawn().map_err(|source| NodeError::Spawn {
+            program: program.display().to_string(),
+            source,
+        })?;
+        let stdout = child.stdout.take().expect("stdout is piped");
+
+        let mut startup = Startup::default();
+        wait_for(
+            &mut child,
+            stdout,
+            self.timeout.unwrap_or(DEFAULT_STARTUP_TIMEOUT),
+            |line| startup.on_line(line),
+        )?;
+
+        Ok(AnvilInstance {
+            child,
+            port,
+            chain_id: self.chain_id.unwrap_or(DEFAULT_CHAIN_ID),
+            addresses: startup.addresses,
+            keys: startup.keys,
+        })
+    }
+}
+
+/// Anvil's default chain id
+const DEFAULT_CHAIN_ID: u64 = 31337;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum Section {
+    #[default]
+    Other,
+    Accounts,
+    Keys,
+}
+
+/// Anvil startup output parser
+#[derive(Debug, Default)]
+struct Startup {
+    section: Section,
+    addresses: Vec<Address>,
+    keys: Vec<B256>,
+}
+
+impl Startup {
+    /// Process a line of output. Returns `true` once anvil is listening
+    fn on_line(&mut self, line: &str) -> bool {
+        if line.starts_with("Listening on") {
+            return true;
+        }
+        match line {
+            "Available Accounts" => self.section = Section::Accounts,
+            "Private Keys" => self.section = Section::Keys,
+            _ if line.starts_with('(') => {
+                // `(0) "0xf39f…2266" (10000.000000000000000000 ETH)`, with
+                // or without quotes
+                let value = line
+                    .split_whitespace()
+                    .nth(1)
+                    .map(|value| value.trim_matches('"'));
+                match (self.section, value) {
+                    (Section::Accounts, Some(value)) => {
+                        self.addresses.extend(value.parse::<Address>().ok())
+                    }
+                    (Section::Keys, Some(value)) => self.keys.extend(value.parse::<B256>().ok()),
+                    _ => {}
+                }
+            }
+            _ if line.is_empty() || line.starts_with('=') => {}
+            _ => self.section = Section::Other,
+        }
+        false
+    }
+}
+
+/// A running anvil node. Killed on drop
+#[derive(Debug)]
+pub struct AnvilInstance {
+    child: Child,
+    port: u16,
+    chain_id: u64,
+    addresses: Vec<Address>,
+    keys: Vec<B256>,
+}
+
+impl AnvilInstance {
+    /// The port anvil is listening on
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    /// The http endpoint
+    pub fn endpoint(&self) -> String {
+        format!("http://127.0.0.1:{}", self.port)
+    }
+
+    /// The websocket endpoint
+    pub fn ws_endpoint(&self) -> String {
+        format!("ws://127.0.0.1:{}", self.port)
+    }
+
+    /// The chain id
+    pub fn chain_id(&self) -> u64 {
+        self.chain_id
+    }
+
+    /// The funded dev accounts
+    pub fn addresses(&self) -> &[Address] {
+        &self.addresses
+    }
+
+    /// The private keys of the dev accounts, in the same order
+    pub fn keys(&self) -> &[B256] {
+        &self.keys
+    }
+}
+
+impl Drop for AnvilInstance {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_parses_startup_output() {
+        let output = r#"
+                             _   _
+                            (_) | |
+      __ _   _ __   __   __  _  | |
+     / _` | | '_ \  \ \ / / | | | |
+    | (_| | | | | |  \ V /  | | | |
+     \__,_| |_| |_|   \_/   |_| |_|
+
+    0.1.0 (e15e33a 2023-04-06T00:15:52.428297Z)
+    https://github.com/foundry-rs/foundry
+
+Available Accounts
+==================
+
+(0) "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266" (10000.000000000000000000 ETH)
+(1) 0x70997970C51812dc3A010C7d01b50e0d17dc79C8 (10000.000000000000000000 ETH)
+
+Private Keys
+==================
+
+(0) 0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80
+(1) 0x59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d
+
+Wallet
+==================
+Mnemonic:          test test test test test test test test test test test junk
+
+Listening on 127.0.0.1:8545
+"#;
+        let mut startup = Startup::default();
+        let listening = output
+            .lines()
+            .map(str::trim)
+            .any(|line| startup.on_line(line));
+
+        assert!(listening);
+        assert_eq!(
+            startup.addresses,
+            vec![
+                "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266"
+                    .parse::<Address>()
+                    .unwrap(),
+                "0x70997970C51812dc3A010C7d01b50e0d17dc79C8"
+                    .parse::<Address>()
+                    .unwrap(),
+            ]
+        );
+        assert_eq!(startup.keys.len(), 2);
+        assert_eq!(
+            startup.keys[0],
+            "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80"
+                .parse::<B256>()
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn it_reports_missing_binaries() {
+        let err = Anvil::at("/nonexistent/anvil").try_spawn().unwrap_err();
+        assert!(matches!(err, NodeError::Spawn { .. }));
+    }
+}
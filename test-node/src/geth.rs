@@ -0,0 +1,212 @@
+use std::{
+    path::PathBuf,
+    process::{Child, Command, Stdio},
+    time::Duration,
+};
+
+use ethers_primitives::Address;
+
+use crate::{unused_port, wait_for, NodeError, DEFAULT_STARTUP_TIMEOUT};
+
+/// The chain id of geth's dev mode
+const DEV_CHAIN_ID: u64 = 1337;
+
+/// Builder for a geth node in [dev mode], with an in-memory database and a
+/// single funded developer account.
+///
+/// ```no_run
+/// use ethers_test_node::Geth;
+///
+/// let geth = Geth::new().dev_period(1).spawn();
+/// println!("geth listening on {}", geth.endpoint());
+/// // geth is killed here
+/// ```
+///
+/// [dev mode]: https://geth.ethereum.org/docs/developers/dapp-developer/dev-mode
+#[derive(Debug, Clone, Default)]
+pub struct Geth {
+    program: Option<PathBuf>,
+    port: Option<u16>,
+    dev_period: Option<u64>,
+    args: Vec<String>,
+    timeout: Option<Duration>,
+}
+
+impl Geth {
+    /// Instantiate a builder for the `geth` on the `PATH`
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Use the `geth` binary at a path
+    pub fn at(path: impl Into<PathBuf>) -> Self {
+        Self {
+            program: Some(path.into()),
+            ..Default::default()
+        }
+    }
+
+    /// Set the http port. Defaults to an unused port
+    #[must_use = "Builder method outputs must be used"]
+    pub fn port(mut self, port: u16) -> Self {
+        self.port = Some(port);
+        self
+    }
+
+    /// Mine a block every `seconds`, instead of on every transaction
+    #[must_use = "Builder method outputs must be used"]
+    pub fn dev_period(mut self, seconds: u64) -> Self {
+        self.dev_period = Some(seconds);
+        self
+    }
+
+    /// Pass an extra argument
+    #[must_use = "Builder method outputs must be used"]
+    pub fn arg(mut self, arg: impl Into<String>) -> Self {
+        self.args.push(arg.into());
+        self
+    }
+
+    /// Pass extra arguments
+    #[must_use = "Builder method outputs must be used"]
+    pub fn args<I, S>(mut self, args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.args.extend(args.into_iter().map(Into::into));
+        self
+    }
+
+    /// Set how long to wait for geth to start
+    #[must_use = "Builder method outputs must be used"]
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Spawn geth. Panics if it fails to start
+    pub fn spawn(self) -> GethInstance {
+        self.try_spawn().expect("could not start geth")
+    }
+
+    /// Spawn geth and wait until its http server is listening
+    pub fn try_spawn(self) -> Result<GethInstance, NodeError> {
+        let program = self.program.unwrap_or_else(|| "geth".into());
+        let port = self.port.unwrap_or_else(unused_port);
+
+        let mut cmd = Command::new(&program);
+        // geth logs to stderr
+        cmd.stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .args([
+                "--dev",
+                "--ipcdisable",
+                "--http",
+                "--http.addr",
+                "127.0.0.1",
+            ])
+            .arg("--http.port")
+            .arg(port.to_string())
+            .args(["--http.api", "eth,net,web3,debug,admin,txpool"]);
+        if let Some(period) = self.dev_period {
+            cmd.arg("--dev.period").arg(period.to_string());
+        }
+        cmd.args(&self.args);
+
+        let mut child = cmd.spawn().map_err(|source| NodeError::Spawn {
+            program: program.display().to_string(),
+            source,
+        })?;
+        let stderr = child.stderr.take().expect("stderr is piped");
+
+        let mut dev_account = None;
+        wait_for(
+            &mut child,
+            stderr,
+            self.timeout.unwrap_or(DEFAULT_STARTUP_TIMEOUT),
+            |line| {
+                if dev_account.is_none() {
+                    dev_account = parse_dev_account(line);
+                }
+                line.contains("HTTP server started")
+            },
+        )?;
+
+        Ok(GethInstance {
+            child,
+            port,
+            dev_account,
+        })
+    }
+}
+
+/// Parse `Using developer account address=0x…`
+fn parse_dev_account(line: &str) -> Option<Address> {
+    if !line.contains("developer account") {
+        return None;
+    }
+    line.split_whitespace()
+        .find_map(|field| field.strip_prefix("address="))
+        .and_then(|address| address.parse().ok())
+}
+
+/// A running geth node. Killed on drop
+#[derive(Debug)]
+pub struct GethInstance {
+    child: Child,
+    port: u16,
+    dev_account: Option<Address>,
+}
+
+impl GethInstance {
+    /// The http port
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    /// The http endpoint
+    pub fn endpoint(&self) -> String {
+        format!("http://127.0.0.1:{}", self.port)
+    }
+
+    /// The chain id
+    pub fn chain_id(&self) -> u64 {
+        DEV_CHAIN_ID
+    }
+
+    /// The funded developer account, if geth reported it. Its key is held
+    /// by geth, and transactions from it can be sent unsigned
+    pub fn dev_account(&self) -> Option<Address> {
+        self.dev_account
+    }
+}
+
+impl Drop for GethInstance {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_parses_dev_accounts() {
+        let line = "INFO [04-20|12:00:00.000] Using developer account                  address=0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed";
+        assert_eq!(
+            parse_dev_account(line),
+            Some(
+                "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed"
+                    .parse()
+                    .unwrap()
+            )
+        );
+        assert_eq!(
+            parse_dev_account("INFO [04-20|12:00:00.000] HTTP server started"),
+            None
+        );
+    }
+}
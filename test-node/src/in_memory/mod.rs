@@ -0,0 +1,714 @@
+//! An in-memory node backed by [revm].
+//!
+//! [revm]: https://github.com/bluealloy/revm
+
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use ethers_primitives::{keccak256, Address, Bloom, B256, U256, U64};
+use ethers_pub_use::{
+    hex,
+    serde::{de::DeserializeOwned, Serialize},
+    serde_json::{self, Value},
+};
+use ethers_rpc_types::{
+    BlockNumber, Bytes, Log, NameOrAddress, TransactionReceipt, TransactionRequest,
+    TypedTransaction,
+};
+use ethers_transports::{
+    common::{self, ErrorObject, JsonRpcResultOwned},
+    Connection, TransportError,
+};
+use revm::{
+    db::InMemoryDB,
+    primitives::{
+        self as rp, AccountInfo, Bytecode, ExecutionResult, Output, SpecId, TxEnv, TxKind,
+    },
+    DatabaseRef, Evm,
+};
+
+mod tx;
+
+/// The default chain id, as used by anvil
+pub const DEFAULT_CHAIN_ID: u64 = 31337;
+
+/// The default block gas limit
+pub const DEFAULT_GAS_LIMIT: u64 = 30_000_000;
+
+/// The default base fee per gas, 1 gwei
+pub const DEFAULT_BASE_FEE: u64 = 1_000_000_000;
+
+/// JSON-RPC error code for a missing method
+const METHOD_NOT_FOUND: i32 = -32601;
+/// JSON-RPC error code for invalid params
+const INVALID_PARAMS: i32 = -32602;
+/// Error code used by nodes for failed transactions and calls
+const SERVER_ERROR: i32 = -32000;
+/// Error code used by nodes for reverted calls. The revert data is the
+/// error data
+const EXECUTION_REVERTED: i32 = 3;
+
+type RpcError = ErrorObject<'static>;
+
+fn rpc_error(code: i32, message: impl Into<String>) -> RpcError {
+    ErrorObject::owned(code, message.into(), None::<()>)
+}
+
+fn to_revm_address(address: Address) -> rp::Address {
+    rp::Address::from(address.to_fixed_bytes())
+}
+
+fn from_revm_address(address: rp::Address) -> Address {
+    Address::from_slice(address.as_slice())
+}
+
+/// Chain state and configuration
+#[derive(Debug)]
+struct Node {
+    db: InMemoryDB,
+    chain_id: u64,
+    block_number: u64,
+    timestamp: u64,
+    gas_limit: u64,
+    base_fee: U256,
+    receipts: HashMap<B256, TransactionReceipt>,
+}
+
+/// A transaction to execute
+struct Execution {
+    hash: B256,
+    tx_type: u8,
+    env: TxEnv,
+}
+
+/// An in-process dev node that executes transactions and calls against an
+/// embedded EVM and in-memory state. Contract tests can use it as a
+/// [`Connection`] without launching a node.
+///
+/// Every transaction is mined in a block of its own. Only the latest state
+/// is kept, so requests for the state at other blocks fail.
+///
+/// Supported methods are `eth_chainId`, `net_version`, `eth_blockNumber`,
+/// `eth_gasPrice`, `eth_getBalance`, `eth_getTransactionCount`,
+/// `eth_getCode`, `eth_getStorageAt`, `eth_call`, `eth_estimateGas`,
+/// `eth_sendTransaction`, `eth_sendRawTransaction` and
+/// `eth_getTransactionReceipt`. `eth_sendTransaction` executes as `from`
+/// without a signature, as anvil does for impersonated accounts. Raw
+/// transactions may be legacy, EIP-2930 or EIP-1559 transactions.
+///
+/// Clones share their state.
+///
+/// ```
+/// # async fn example() {
+/// use ethers_primitives::{Address, U256};
+/// use ethers_test_node::InMemory;
+/// use ethers_transports::Connection;
+///
+/// let node = InMemory::new();
+/// node.set_balance(Address::repeat_byte(0x11), U256::from(100));
+///
+/// let balance: U256 = node
+///     .request("eth_getBalance", (Address::repeat_byte(0x11), "latest"))
+///     .await
+///     .unwrap()
+///     .unwrap();
+/// assert_eq!(balance, U256::from(100));
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct InMemory {
+    node: Arc<Mutex<Node>>,
+    id: Arc<AtomicU64>,
+}
+
+impl fmt::Debug for InMemory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let node = self.node.lock().unwrap();
+        f.debug_struct("InMemory")
+            .field("chain_id", &node.chain_id)
+            .field("block_number", &node.block_number)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Default for InMemory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl InMemory {
+    /// Instantiate a node with empty state
+    pub fn new() -> Self {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        let node = Node {
+            db: InMemoryDB::default(),
+            chain_id: DEFAULT_CHAIN_ID,
+            block_number: 0,
+            timestamp,
+            gas_limit: DEFAULT_GAS_LIMIT,
+            base_fee: U256::from(DEFAULT_BASE_FEE),
+            receipts: HashMap::new(),
+        };
+        Self {
+            node: Arc::new(Mutex::new(node)),
+            id: Default::default(),
+        }
+    }
+
+    /// Set the chain id
+    #[must_use = "Builder method outputs must be used"]
+    pub fn with_chain_id(self, chain_id: u64) -> Self {
+        self.node.lock().unwrap().chain_id = chain_id;
+        self
+    }
+
+    /// Set the block gas limit
+    #[must_use = "Builder method outputs must be used"]
+    pub fn with_gas_limit(self, gas_limit: u64) -> Self {
+        self.node.lock().unwrap().gas_limit = gas_limit;
+        self
+    }
+
+    /// Set the base fee per gas
+    #[must_use = "Builder method outputs must be used"]
+    pub fn with_base_fee(self, base_fee: U256) -> Self {
+        self.node.lock().unwrap().base_fee = base_fee;
+        self
+    }
+
+    /// Modify an account, creating it if it does not exist
+    fn modify_account(&self, address: Address, f: impl FnOnce(&mut AccountInfo)) {
+        let mut node = self.node.lock().unwrap();
+        let address = to_revm_address(address);
+        let mut info = node
+            .db
+            .basic_ref(address)
+            .ok()
+            .flatten()
+            .unwrap_or_default();
+        f(&mut info);
+        node.db.insert_account_info(address, info);
+    }
+
+    /// Set the balance of an account
+    pub fn set_balance(&self, address: Address, balance: U256) {
+        self.modify_account(address, |info| info.balance = balance);
+    }
+
+    /// Set the nonce of an account
+    pub fn set_nonce(&self, address: Address, nonce: u64) {
+        self.modify_account(address, |info| info.nonce = nonce);
+    }
+
+    /// Set the runtime code of an account
+    pub fn set_code(&self, address: Address, code: impl Into<Vec<u8>>) {
+        let code = Bytecode::new_raw(code.into().into());
+        self.modify_account(address, |info| {
+            info.code_hash = code.hash_slow();
+            info.code = Some(code);
+        });
+    }
+
+    /// Set a storage slot of an account
+    pub fn set_storage(&self, address: Address, slot: U256, value: U256) {
+        self.node
+            .lock()
+            .unwrap()
+            .db
+            .insert_account_storage(to_revm_address(address), slot, value)
+            .expect("in-memory database is infallible");
+    }
+
+    /// The number of the latest block
+    pub fn block_number(&self) -> u64 {
+        self.node.lock().unwrap().block_number
+    }
+
+    /// Serve a request
+    fn handle(&self, method: &str, params: &[Value]) -> Result<Value, RpcError> {
+        let mut node = self.node.lock().unwrap();
+        match method {
+            "web3_clientVersion" => {
+                to_value(concat!("ethers-in-memory/", env!("CARGO_PKG_VERSION")))
+            }
+            "eth_chainId" => to_value(U64::from(node.chain_id)),
+            "net_version" => to_value(node.chain_id.to_string()),
+            "eth_blockNumber" => to_value(U64::from(node.block_number)),
+            "eth_gasPrice" => to_value(node.base_fee),
+            "eth_getBalance" | "eth_getTransactionCount" | "eth_getCode" => {
+                let address = to_revm_address(param(params, 0)?);
+                node.check_block(param(params, 1)?)?;
+                let info = node
+                    .db
+                    .basic_ref(address)
+                    .ok()
+                    .flatten()
+                    .unwrap_or_default();
+                match method {
+                    "eth_getBalance" => to_value(info.balance),
+                    "eth_getTransactionCount" => to_value(U64::from(info.nonce)),
+                    _ => {
+                        let code = match info.code {
+                            Some(code) => code.original_bytes(),
+                            None => node
+                                .db
+                                .code_by_hash_ref(info.code_hash)
+                                .map(|code| code.original_bytes())
+                                .unwrap_or_default(),
+                        };
+                        to_value(Bytes::from(code.to_vec()))
+                    }
+                }
+            }
+            "eth_getStorageAt" => {
+                let address = to_revm_address(param(params, 0)?);
+                let slot: U256 = param(params, 1)?;
+                node.check_block(param(params, 2)?)?;
+                let value = node.db.storage_ref(address, slot).unwrap_or_default();
+                to_value(B256::from(value.to_be_bytes::<32>()))
+            }
+            "eth_call" | "eth_estimateGas" => {
+                let request: TransactionRequest = param(params, 0)?;
+                node.check_block(param(params, 1)?)?;
+                let env = node.call_env(request, false)?;
+                let (result, _) = node.execute(env, false)?;
+                match (method, result) {
+                    ("eth_call", ExecutionResult::Success { output, .. }) => {
+                        to_value(Bytes::from(output.into_data().to_vec()))
+                    }
+                    (_, ExecutionResult::Success { gas_used, .. }) => to_value(U64::from(gas_used)),
+                    (_, result) => Err(execution_error(result)),
+                }
+            }
+            "eth_sendTransaction" => {
+                let request: TransactionRequest = param(params, 0)?;
+                let from = request
+                    .from
+                    .ok_or_else(|| rpc_error(INVALID_PARAMS, "missing from"))?;
+                let nonce = match request.nonce {
+                    Some(nonce) => u64::try_from(nonce)
+                        .map_err(|_| rpc_error(INVALID_PARAMS, "nonce too large"))?,
+                    None => {
+                        let address = to_revm_address(from);
+                        let info = node.db.basic_ref(address).ok().flatten();
+                        info.map(|info| info.nonce).unwrap_or_default()
+                    }
+                };
+                let tx_type = if request.max_fee_per_gas.is_some() {
+                    2
+                } else {
+                    0
+                };
+                let mut env = node.call_env(request, true)?;
+                env.nonce = Some(nonce);
+                let mut preimage = from.0.to_vec();
+                preimage.extend(nonce.to_be_bytes());
+                let execution = Execution {
+                    hash: keccak256(preimage),
+                    tx_type,
+                    env,
+                };
+                node.mine(execution).and_then(to_value)
+            }
+            "eth_sendRawTransaction" => {
+                let raw: Bytes = param(params, 0)?;
+                let signed = TypedTransaction::decode_raw(&raw.0)
+                    .map_err(|e| rpc_error(INVALID_PARAMS, e.to_string()))?;
+                if signed.tx.chain_id().is_some_and(|id| id != node.chain_id) {
+                    return Err(rpc_error(SERVER_ERROR, "invalid chain id"));
+                }
+                let execution = Execution {
+                    hash: signed.hash,
+                    tx_type: signed.tx.tx_type(),
+                    env: tx::tx_env(signed),
+                };
+                node.mine(execution).and_then(to_value)
+            }
+            "eth_getTransactionReceipt" => {
+                let hash: B256 = param(params, 0)?;
+                to_value(node.receipts.get(&hash))
+            }
+            _ => Err(rpc_error(
+                METHOD_NOT_FOUND,
+                format!("the method {method} does not exist/is not available"),
+            )),
+        }
+    }
+
+    fn serve(&self, req: &common::Request<'_>) -> Result<JsonRpcResultOwned, TransportError> {
+        let params = match &req.params {
+            Some(params) => serde_json::from_str(params.get()).map_err(TransportError::ser_err)?,
+            None => Value::Null,
+        };
+        let params = match params {
+            Value::Array(params) => params,
+            Value::Null => vec![],
+            params => vec![params],
+        };
+        Ok(match self.handle(&req.method, &params) {
+            Ok(value) => Ok(std::borrow::Cow::Owned(
+                serde_json::value::to_raw_value(&value).map_err(TransportError::ser_err)?,
+            )),
+            Err(err) => Err(err),
+        })
+    }
+}
+
+fn to_value(value: impl Serialize) -> Result<Value, RpcError> {
+    serde_json::to_value(value).map_err(|e| rpc_error(SERVER_ERROR, e.to_string()))
+}
+
+/// A positional param. Missing params deserialize from `null`
+fn param<T: DeserializeOwned>(params: &[Value], index: usize) -> Result<T, RpcError> {
+    let value = params.get(index).cloned().unwrap_or(Value::Null);
+    serde_json::from_value(value)
+        .map_err(|e| rpc_error(INVALID_PARAMS, format!("invalid param {index}: {e}")))
+}
+
+/// The error for a call or transaction that did not succeed
+fn execution_error(result: ExecutionResult) -> RpcError {
+    match result {
+        ExecutionResult::Revert { output, .. } => ErrorObject::owned(
+            EXECUTION_REVERTED,
+            "execution reverted",
+            Some(format!("0x{}", hex::encode(output))),
+        ),
+        ExecutionResult::Halt { reason, .. } => {
+            rpc_error(SERVER_ERROR, format!("execution halted: {reason:?}"))
+        }
+        ExecutionResult::Success { .. } => unreachable!("not an error"),
+    }
+}
+
+/// Set bloom bits for an address or topic
+fn accrue(bloom: &mut Bloom, input: &[u8]) {
+    let hash = keccak256(input);
+    for i in [0, 2, 4] {
+        let bit = (usize::from(hash[i]) << 8 | usize::from(hash[i + 1])) & 2047;
+        bloom.0[255 - bit / 8] |= 1 << (bit % 8);
+    }
+}
+
+impl Node {
+    /// Only the latest state is kept
+    fn check_block(&self, block: Option<BlockNumber>) -> Result<(), RpcError> {
+        match block {
+            None
+            | Some(BlockNumber::Latest)
+            | Some(BlockNumber::Pending)
+            | Some(BlockNumber::Safe)
+            | Some(BlockNumber::Finalized) => Ok(()),
+            Some(BlockNumber::Earliest) if self.block_number == 0 => Ok(()),
+            Some(BlockNumber::Number(n)) if n == self.block_number => Ok(()),
+            Some(block) => Err(rpc_error(
+                SERVER_ERROR,
+                format!("state at block {block:?} is not available, only the latest state is"),
+            )),
+        }
+    }
+
+    /// The environment for a call or unsigned transaction. Calls without
+    /// a gas price pay no base fee
+    fn call_env(&self, request: TransactionRequest, is_tx: bool) -> Result<TxEnv, RpcError> {
+        let transact_to = match request.to {
+            Some(NameOrAddress::Address(to)) => TxKind::Call(to_revm_address(to)),
+            Some(NameOrAddress::Name(name)) => {
+                return Err(rpc_error(
+                    INVALID_PARAMS,
+                    format!("cannot resolve ENS name {name}"),
+                ))
+            }
+            None => TxKind::Create,
+        };
+        let gas_limit = match request.gas {
+            Some(gas) => {
+                u64::try_from(gas).map_err(|_| rpc_error(INVALID_PARAMS, "gas too large"))?
+            }
+            None => self.gas_limit,
+        };
+        let default_price = if is_tx { self.base_fee } else { U256::ZERO };
+        Ok(TxEnv {
+            caller: request.from.map(to_revm_address).unwrap_or_default(),
+            gas_limit,
+            gas_price: request
+                .max_fee_per_gas
+                .or(request.gas_price)
+                .unwrap_or(default_price),
+            gas_priority_fee: request.max_priority_fee_per_gas,
+            transact_to,
+            value: request.value.unwrap_or_default(),
+            data: request.data.unwrap_or_default().0.into(),
+            chain_id: Some(self.chain_id),
+            ..Default::default()
+        })
+    }
+
+    /// Execute a transaction in the next block. The base fee is waived for
+    /// calls without a gas price
+    fn execute(&mut self, env: TxEnv, commit: bool) -> Result<(ExecutionResult, U256), RpcError> {
+        let base_fee = if commit || env.gas_price > U256::ZERO {
+            self.base_fee
+        } else {
+            U256::ZERO
+        };
+        let effective_gas_price = match env.gas_priority_fee {
+            Some(priority_fee) => env.gas_price.min(base_fee.saturating_add(priority_fee)),
+            None => env.gas_price,
+        };
+        let (chain_id, number, timestamp, gas_limit) = (
+            self.chain_id,
+            self.block_number + 1,
+            self.timestamp + 1,
+            self.gas_limit,
+        );
+
+        let mut evm = Evm::builder()
+            .with_db(&mut self.db)
+            .with_spec_id(SpecId::CANCUN)
+            .modify_cfg_env(|cfg| cfg.chain_id = chain_id)
+            .modify_block_env(|block| {
+                block.number = U256::from(number);
+                block.timestamp = U256::from(timestamp);
+                block.gas_limit = U256::from(gas_limit);
+                block.basefee = base_fee;
+            })
+            .with_tx_env(env)
+            .build();
+        let result = if commit {
+            evm.transact_commit()
+        } else {
+            evm.transact().map(|result| result.result)
+        };
+        result
+            .map(|result| (result, effective_gas_price))
+            .map_err(|e| rpc_error(SERVER_ERROR, e.to_string()))
+    }
+
+    /// Execute and commit a transaction in a new block, and store its
+    /// receipt. Reverted transactions are mined. Invalid transactions are
+    /// rejected
+    fn mine(&mut self, execution: Execution) -> Result<B256, RpcError> {
+        let Execution { hash, tx_type, env } = execution;
+        let from = from_revm_address(env.caller);
+        let to = match env.transact_to {
+            TxKind::Call(to) => Some(from_revm_address(to)),
+            TxKind::Create => None,
+        };
+        let (result, effective_gas_price) = self.execute(env, true)?;
+
+        self.block_number += 1;
+        self.timestamp += 1;
+        let block_number = U64::from(self.block_number);
+        let block_hash = keccak256(self.block_number.to_be_bytes());
+
+        let gas_used = result.gas_used();
+        let status = result.is_success();
+        let (logs, contract_address) = match result {
+            ExecutionResult::Success { logs, output, .. } => {
+                let contract_address = match output {
+                    Output::Create(_, address) => address.map(from_revm_address),
+                    Output::Call(_) => None,
+                };
+                (logs, contract_address)
+            }
+            _ => (vec![], None),
+        };
+
+        let mut logs_bloom = Bloom::default();
+        let logs = logs
+            .into_iter()
+            .enumerate()
+            .map(|(i, log)| {
+                accrue(&mut logs_bloom, log.address.as_slice());
+                let topics = log
+                    .data
+                    .topics()
+                    .iter()
+                    .map(|topic| {
+                        accrue(&mut logs_bloom, topic.as_slice());
+                        B256::from_slice(topic.as_slice())
+                    })
+                    .collect();
+                Log {
+                    address: from_revm_address(log.address),
+                    topics,
+                    data: log.data.data.0.clone().into(),
+                    block_hash: Some(block_hash),
+                    block_number: Some(block_number),
+                    transaction_hash: Some(hash),
+                    transaction_index: Some(U64::ZERO),
+                    log_index: Some(U256::from(i)),
+                    removed: false,
+                }
+            })
+            .collect();
+
+        let receipt = TransactionReceipt {
+            transaction_hash: hash,
+            transaction_index: U64::ZERO,
+            block_hash: Some(block_hash),
+            block_number: Some(block_number),
+            from,
+            to,
+            cumulative_gas_used: U256::from(gas_used),
+            gas_used: Some(U256::from(gas_used)),
+            contract_address,
+            logs,
+            logs_bloom,
+            status: Some(U64::from(status as u8)),
+            root: None,
+            effective_gas_price: Some(effective_gas_price),
+            transaction_type: Some(U64::from(tx_type)),
+            ..Default::default()
+        };
+        self.receipts.insert(hash, receipt);
+        Ok(hash)
+    }
+}
+
+impl Connection for InMemory {
+    fn is_local(&self) -> bool {
+        true
+    }
+
+    fn increment_id(&self) -> u64 {
+        self.id.fetch_add(1, Ordering::Relaxed)
+    }
+
+    fn json_rpc_request(&self, req: &common::Request<'_>) -> common::RpcFuture {
+        let resp = self.serve(req);
+        Box::pin(async move { resp })
+    }
+
+    fn batch_request(&self, reqs: &[common::Request<'_>]) -> common::BatchRpcFuture {
+        let resps = reqs.iter().map(|req| self.serve(req)).collect();
+        Box::pin(async move { resps })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ethers_pub_use::serde_json::json;
+
+    use super::*;
+
+    /// Init code deploying a contract that returns 42
+    const INIT_CODE: &str = "600a600c600039600a6000f3602a60005260206000f3";
+
+    fn get_receipt(node: &InMemory, hash: Value) -> TransactionReceipt {
+        serde_json::from_value(node.handle("eth_getTransactionReceipt", &[hash]).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn it_deploys_and_calls_contracts() {
+        let node = InMemory::new();
+        let from = Address::repeat_byte(0x11);
+        node.set_balance(from, U256::from(10u64.pow(18)));
+
+        let hash = node
+            .handle(
+                "eth_sendTransaction",
+                &[json!({ "from": from, "data": format!("0x{INIT_CODE}") })],
+            )
+            .unwrap();
+        let receipt = get_receipt(&node, hash);
+        assert!(receipt.is_success());
+        assert_eq!(receipt.block_number, Some(U64::from(1)));
+        let contract = receipt.contract_address.unwrap();
+
+        let output = node
+            .handle("eth_call", &[json!({ "to": contract }), json!("latest")])
+            .unwrap();
+        assert_eq!(output, json!(format!("0x{:064x}", 42)));
+        let nonce = node
+            .handle("eth_getTransactionCount", &[json!(from)])
+            .unwrap();
+        assert_eq!(nonce, json!("0x1"));
+        assert!(node
+            .handle("eth_getBalance", &[json!(from), json!("0x0")])
+            .is_err());
+    }
+
+    #[test]
+    fn it_mines_logs_and_reverts() {
+        let node = InMemory::new();
+        let from = Address::repeat_byte(0x11);
+        let emitter = Address::repeat_byte(0x22);
+        let reverter = Address::repeat_byte(0x33);
+        node.set_balance(from, U256::from(10u64.pow(18)));
+        // LOG0 of 32 bytes of memory
+        node.set_code(emitter, hex::decode("602060006000a000").unwrap());
+        // reverts with 42
+        node.set_code(reverter, hex::decode("602a60005260206000fd").unwrap());
+
+        let hash = node
+            .handle(
+                "eth_sendTransaction",
+                &[json!({ "from": from, "to": emitter })],
+            )
+            .unwrap();
+        let receipt = get_receipt(&node, hash);
+        assert_eq!(receipt.logs.len(), 1);
+        assert_eq!(receipt.logs[0].address, emitter);
+        assert_ne!(receipt.logs_bloom, Bloom::default());
+
+        let err = node
+            .handle("eth_call", &[json!({ "to": reverter })])
+            .unwrap_err();
+        assert_eq!(err.code(), EXECUTION_REVERTED);
+        let hash = node
+            .handle(
+                "eth_sendTransaction",
+                &[json!({ "from": from, "to": reverter })],
+            )
+            .unwrap();
+        assert!(!get_receipt(&node, hash).is_success());
+        assert_eq!(node.block_number(), 2);
+
+        let err = node.handle("eth_getBlockByNumber", &[]).unwrap_err();
+        assert_eq!(err.code(), METHOD_NOT_FOUND);
+    }
+
+    #[test]
+    fn it_executes_raw_transactions() {
+        // the example from EIP-155
+        let raw = "0xf86c098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a7640000\
+                   8025a028ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276a067cbe9d8997f\
+                   761aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83";
+        let sender =
+            Address::from_slice(&hex::decode("9d8a62f656a8d1615c1294fd71e9cfb3e4855a4f").unwrap());
+        let node = InMemory::new();
+        node.set_balance(sender, U256::from(2 * 10u64.pow(18)));
+        node.set_nonce(sender, 9);
+
+        let err = node
+            .handle("eth_sendRawTransaction", &[json!(raw)])
+            .unwrap_err();
+        assert_eq!(err.message(), "invalid chain id");
+
+        let node = node.with_chain_id(1);
+        let hash = node
+            .handle("eth_sendRawTransaction", &[json!(raw)])
+            .unwrap();
+        let receipt = get_receipt(&node, hash);
+        assert!(receipt.is_success());
+        assert_eq!(receipt.from, sender);
+        assert_eq!(receipt.gas_used, Some(U256::from(21000)));
+
+        let balance = node
+            .handle("eth_getBalance", &[json!(Address::repeat_byte(0x35))])
+            .unwrap();
+        assert_eq!(balance, json!("0xde0b6b3a7640000"));
+    }
+}
@@ -0,0 +1,94 @@
+//! Execution environments for signed raw transactions, as sent with
+//! `eth_sendRawTransaction`.
+
+use ethers_primitives::{Address, U256};
+use ethers_rpc_types::{
+    transaction::{AccessList, TypedTransaction},
+    SignedTransaction,
+};
+use revm::primitives::{self as rp, TxEnv, TxKind};
+
+use super::to_revm_address;
+
+fn transact_to(to: Option<Address>) -> TxKind {
+    match to {
+        Some(to) => TxKind::Call(to_revm_address(to)),
+        None => TxKind::Create,
+    }
+}
+
+fn access_list(access_list: AccessList) -> Vec<(rp::Address, Vec<U256>)> {
+    access_list
+        .0
+        .into_iter()
+        .map(|item| {
+            let keys = item
+                .storage_keys
+                .into_iter()
+                .map(|key| U256::from_be_bytes(key.0))
+                .collect();
+            (to_revm_address(item.address), keys)
+        })
+        .collect()
+}
+
+/// The environment executing a signed transaction
+pub(super) fn tx_env(signed: SignedTransaction) -> TxEnv {
+    let caller = to_revm_address(signed.from);
+    match signed.tx {
+        TypedTransaction::Legacy(tx) => TxEnv {
+            caller,
+            gas_limit: tx.gas_limit,
+            gas_price: tx.gas_price,
+            transact_to: transact_to(tx.to),
+            value: tx.value,
+            data: tx.input.0.into(),
+            nonce: Some(tx.nonce),
+            chain_id: tx.chain_id,
+            ..Default::default()
+        },
+        TypedTransaction::Eip2930(tx) => TxEnv {
+            caller,
+            gas_limit: tx.gas_limit,
+            gas_price: tx.gas_price,
+            transact_to: transact_to(tx.to),
+            value: tx.value,
+            data: tx.input.0.into(),
+            nonce: Some(tx.nonce),
+            chain_id: Some(tx.chain_id),
+            access_list: access_list(tx.access_list),
+            ..Default::default()
+        },
+        TypedTransaction::Eip1559(tx) => TxEnv {
+            caller,
+            gas_limit: tx.gas_limit,
+            gas_price: tx.max_fee_per_gas,
+            gas_priority_fee: Some(tx.max_priority_fee_per_gas),
+            transact_to: transact_to(tx.to),
+            value: tx.value,
+            data: tx.input.0.into(),
+            nonce: Some(tx.nonce),
+            chain_id: Some(tx.chain_id),
+            access_list: access_list(tx.access_list),
+            ..Default::default()
+        },
+        TypedTransaction::Eip4844(tx) => TxEnv {
+            caller,
+            gas_limit: tx.gas_limit,
+            gas_price: tx.max_fee_per_gas,
+            gas_priority_fee: Some(tx.max_priority_fee_per_gas),
+            transact_to: transact_to(Some(tx.to)),
+            value: tx.value,
+            data: tx.input.0.into(),
+            nonce: Some(tx.nonce),
+            chain_id: Some(tx.chain_id),
+            access_list: access_list(tx.access_list),
+            blob_hashes: tx
+                .blob_versioned_hashes
+                .into_iter()
+                .map(|hash| rp::B256::from(hash.0))
+                .collect(),
+            max_fee_per_blob_gas: Some(tx.max_fee_per_blob_gas),
+        },
+    }
+}
@@ -0,0 +1,111 @@
+#![warn(
+    missing_debug_implementations,
+    missing_docs,
+    unreachable_pub,
+    unused_crate_dependencies
+)]
+#![deny(unused_must_use, rust_2018_idioms)]
+#![doc(test(
+    no_crate_inject,
+    attr(deny(warnings, rust_2018_idioms), allow(dead_code, unused_variables))
+))]
+
+//! Launch local [anvil] or [geth] dev nodes for tests.
+//!
+//! Nodes are spawned as child processes on unused ports, and are killed
+//! when their handle is dropped. The binaries must be installed, or their
+//! path must be configured.
+//!
+//! With the `revm` feature, `InMemory` runs a node in-process instead.
+//!
+//! [anvil]: https://github.com/foundry-rs/foundry/tree/master/anvil
+//! [geth]: https://geth.ethereum.org/docs/developers/dapp-developer/dev-mode
+
+use std::{
+    io::{BufRead, BufReader, Read},
+    net::TcpListener,
+    process::Child,
+    time::{Duration, Instant},
+};
+
+use ethers_pub_use::thiserror;
+
+mod anvil;
+pub use anvil::{Anvil, AnvilInstance};
+
+mod geth;
+pub use geth::{Geth, GethInstance};
+
+#[cfg(feature = "revm")]
+mod in_memory;
+#[cfg(feature = "revm")]
+pub use in_memory::{InMemory, DEFAULT_BASE_FEE, DEFAULT_CHAIN_ID, DEFAULT_GAS_LIMIT};
+
+/// The default time to wait for a node to start
+pub const DEFAULT_STARTUP_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Errors launching a node
+#[derive(Debug, thiserror::Error)]
+pub enum NodeError {
+    /// The node process could not be spawned
+    #[error("could not spawn {program}: {source}")]
+    Spawn {
+        /// The program
+        program: String,
+        /// The spawn error
+        source: std::io::Error,
+    },
+    /// The node did not report that it was listening in time
+    #[error("node did not start within {0:?}")]
+    Timeout(Duration),
+    /// The node exited before it started listening
+    #[error("node exited before it started listening")]
+    Exited,
+    /// The node output could not be read
+    #[error("could not read node output: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Find a port that is not in use. The port may be taken by another process
+/// before the node binds it
+pub fn unused_port() -> u16 {
+    TcpListener::bind("127.0.0.1:0")
+        .and_then(|listener| listener.local_addr())
+        .expect("could not bind an unused port")
+        .port()
+}
+
+/// Read lines of a node's output, passing each to `on_line`, until it
+/// returns `true`. The rest of the output is then drained in the
+/// background, so the node does not block on a full pipe
+fn wait_for<R>(
+    child: &mut Child,
+    output: R,
+    timeout: Duration,
+    mut on_line: impl FnMut(&str) -> bool,
+) -> Result<(), NodeError>
+where
+    R: Read + Send + 'static,
+{
+    let start = Instant::now();
+    let mut reader = BufReader::new(output);
+    let mut line = String::new();
+    loop {
+        if start.elapsed() > timeout {
+            let _ = child.kill();
+            return Err(NodeError::Timeout(timeout));
+        }
+
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            let _ = child.kill();
+            return Err(NodeError::Exited);
+        }
+        if on_line(line.trim()) {
+            break;
+        }
+    }
+
+    std::thread::spawn(move || std::io::copy(&mut reader, &mut std::io::sink()));
+    Ok(())
+}